@@ -8,6 +8,7 @@ use sc_finality_grandpa::SharedVoterState;
 use sc_keystore::LocalKeystore;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
+use sp_consensus::equivocation::EquivocationSink;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 use std::{sync::Arc, time::Duration};
 
@@ -53,6 +54,7 @@ pub fn new_partial(
 				FullSelectChain,
 			>,
 			sc_finality_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
+			EquivocationSink,
 			Option<Telemetry>,
 		),
 	>,
@@ -110,6 +112,23 @@ pub fn new_partial(
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
 
+	// Aura and GRANDPA equivocations are submitted to the runtime by a single task, which drops
+	// the duplicate reports.
+	let (equivocation_sink, equivocation_reporting) =
+		sp_consensus::equivocation::equivocation_reporting();
+	let equivocation_reporting = equivocation_reporting
+		.with_submitter(
+			sp_consensus_aura::AURA_ENGINE_ID,
+			sc_consensus_aura::AuraEquivocationSubmitter::<Block, _, AuraPair>::new(client.clone()),
+		)
+		.with_submitter(
+			sp_finality_grandpa::GRANDPA_ENGINE_ID,
+			sc_finality_grandpa::GrandpaEquivocationSubmitter::<Block, _>::new(client.clone()),
+		);
+	task_manager
+		.spawn_handle()
+		.spawn("equivocation-reporting", None, equivocation_reporting.run());
+
 	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
 
 	let import_queue =
@@ -131,8 +150,10 @@ pub fn new_partial(
 			spawner: &task_manager.spawn_essential_handle(),
 			registry: config.prometheus_registry(),
 			check_for_equivocation: Default::default(),
+			equivocation_reporting: Some(equivocation_sink.clone()),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 			compatibility_mode: Default::default(),
+			verification_workers: 2,
 		})?;
 
 	Ok(sc_service::PartialComponents {
@@ -143,7 +164,7 @@ pub fn new_partial(
 		keystore_container,
 		select_chain,
 		transaction_pool,
-		other: (grandpa_block_import, grandpa_link, telemetry),
+		other: (grandpa_block_import, grandpa_link, equivocation_sink, telemetry),
 	})
 }
 
@@ -164,7 +185,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		mut keystore_container,
 		select_chain,
 		transaction_pool,
-		other: (block_import, grandpa_link, mut telemetry),
+		other: (block_import, grandpa_link, equivocation_sink, mut telemetry),
 	} = new_partial(&config)?;
 
 	if let Some(url) = &config.keystore_remote {
@@ -323,6 +344,8 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 			voting_rule: sc_finality_grandpa::VotingRulesBuilder::default().build(),
 			prometheus_registry,
 			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_participation: sc_finality_grandpa::SharedVoterParticipation::empty(),
+			equivocation_reporting: Some(equivocation_sink),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 		};
 
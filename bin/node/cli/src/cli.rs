@@ -97,4 +97,7 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Cross-check the keystore against the session keys registered on chain.
+	AuditSessionKeys(sc_cli::AuditSessionKeysCmd),
 }
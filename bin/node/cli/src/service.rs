@@ -34,6 +34,7 @@ use sc_network_common::{protocol::event::Event, service::NetworkEventStream};
 use sc_service::{config::Configuration, error::Error as ServiceError, RpcHandlers, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sp_api::ProvideRuntimeApi;
+use sp_consensus::equivocation::EquivocationSink;
 use sp_core::crypto::Pair;
 use sp_runtime::{generic, traits::Block as BlockT, SaturatedConversion};
 use std::sync::Arc;
@@ -142,8 +143,9 @@ pub fn new_partial(
 				sc_consensus_babe::BabeBlockImport<Block, FullClient, FullGrandpaBlockImport>,
 				grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
 				sc_consensus_babe::BabeLink<Block>,
+				EquivocationSink,
 			),
-			grandpa::SharedVoterState,
+			(grandpa::SharedVoterState, grandpa::SharedVoterParticipation),
 			Option<Telemetry>,
 		),
 	>,
@@ -198,6 +200,23 @@ pub fn new_partial(
 	)?;
 	let justification_import = grandpa_block_import.clone();
 
+	// BABE and GRANDPA equivocations are submitted to the runtime by a single task, which drops
+	// the duplicate reports.
+	let (equivocation_sink, equivocation_reporting) =
+		sp_consensus::equivocation::equivocation_reporting();
+	let equivocation_reporting = equivocation_reporting
+		.with_submitter(
+			sp_consensus_babe::BABE_ENGINE_ID,
+			sc_consensus_babe::BabeEquivocationSubmitter::<Block, _>::new(client.clone()),
+		)
+		.with_submitter(
+			grandpa_primitives::GRANDPA_ENGINE_ID,
+			grandpa::GrandpaEquivocationSubmitter::<Block, _>::new(client.clone()),
+		);
+	task_manager
+		.spawn_handle()
+		.spawn("equivocation-reporting", None, equivocation_reporting.run());
+
 	let (block_import, babe_link) = sc_consensus_babe::block_import(
 		sc_consensus_babe::configuration(&*client)?,
 		grandpa_block_import,
@@ -228,17 +247,20 @@ pub fn new_partial(
 		&task_manager.spawn_essential_handle(),
 		config.prometheus_registry(),
 		telemetry.as_ref().map(|x| x.handle()),
+		Some(equivocation_sink.clone()),
 	)?;
 
-	let import_setup = (block_import, grandpa_link, babe_link);
+	let import_setup = (block_import, grandpa_link, babe_link, equivocation_sink);
 
 	let (rpc_extensions_builder, rpc_setup) = {
-		let (_, grandpa_link, babe_link) = &import_setup;
+		let (_, grandpa_link, babe_link, _) = &import_setup;
 
 		let justification_stream = grandpa_link.justification_stream();
 		let shared_authority_set = grandpa_link.shared_authority_set().clone();
 		let shared_voter_state = grandpa::SharedVoterState::empty();
 		let shared_voter_state2 = shared_voter_state.clone();
+		let shared_voter_participation = grandpa::SharedVoterParticipation::empty();
+		let shared_voter_participation2 = shared_voter_participation.clone();
 
 		let finality_proof_provider = grandpa::FinalityProofProvider::new_for_service(
 			backend.clone(),
@@ -269,6 +291,7 @@ pub fn new_partial(
 				},
 				grandpa: node_rpc::GrandpaDeps {
 					shared_voter_state: shared_voter_state.clone(),
+					shared_voter_participation: shared_voter_participation.clone(),
 					shared_authority_set: shared_authority_set.clone(),
 					justification_stream: justification_stream.clone(),
 					subscription_executor,
@@ -279,7 +302,7 @@ pub fn new_partial(
 			node_rpc::create_full(deps, rpc_backend.clone()).map_err(Into::into)
 		};
 
-		(rpc_extensions_builder, shared_voter_state2)
+		(rpc_extensions_builder, (shared_voter_state2, shared_voter_participation2))
 	};
 
 	Ok(sc_service::PartialComponents {
@@ -337,7 +360,7 @@ pub fn new_full_base(
 		other: (rpc_builder, import_setup, rpc_setup, mut telemetry),
 	} = new_partial(&config)?;
 
-	let shared_voter_state = rpc_setup;
+	let (shared_voter_state, shared_voter_participation) = rpc_setup;
 	let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
 	let grandpa_protocol_name = grandpa::protocol_standard_name(
 		&client.block_hash(0).ok().flatten().expect("Genesis block exists; qed"),
@@ -409,7 +432,7 @@ pub fn new_full_base(
 		}
 	}
 
-	let (block_import, grandpa_link, babe_link) = import_setup;
+	let (block_import, grandpa_link, babe_link, equivocation_sink) = import_setup;
 
 	(with_startup_data)(&block_import, &babe_link);
 
@@ -522,6 +545,17 @@ pub fn new_full_base(
 	};
 
 	if enable_grandpa {
+		// keep track of which voters are missing prevotes and precommits.
+		task_manager.spawn_handle().spawn(
+			"grandpa-voter-participation",
+			None,
+			grandpa::run_voter_participation_monitor(
+				client.clone(),
+				shared_voter_participation.clone(),
+				prometheus_registry.as_ref(),
+			)?,
+		);
+
 		// start the full GRANDPA voter
 		// NOTE: non-authorities could run the GRANDPA observer protocol, but at
 		// this point the full voter should provide better guarantees of block
@@ -536,6 +570,8 @@ pub fn new_full_base(
 			voting_rule: grandpa::VotingRulesBuilder::default().build(),
 			prometheus_registry,
 			shared_voter_state,
+			shared_voter_participation,
+			equivocation_reporting: Some(equivocation_sink),
 		};
 
 		// the GRANDPA voter task is considered infallible, i.e.
@@ -39,7 +39,8 @@ use sc_client_api::AuxStore;
 use sc_consensus_babe::{BabeConfiguration, Epoch};
 use sc_consensus_epochs::SharedEpochChanges;
 use sc_finality_grandpa::{
-	FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState,
+	FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet,
+	SharedVoterParticipation, SharedVoterState,
 };
 use sc_rpc::SubscriptionTaskExecutor;
 pub use sc_rpc_api::DenyUnsafe;
@@ -65,6 +66,8 @@ pub struct BabeDeps {
 pub struct GrandpaDeps<B> {
 	/// Voting round info.
 	pub shared_voter_state: SharedVoterState,
+	/// Participation of the voters.
+	pub shared_voter_participation: SharedVoterParticipation,
 	/// Authority set info.
 	pub shared_authority_set: SharedAuthoritySet<Hash, BlockNumber>,
 	/// Receives notifications about justification events from Grandpa.
@@ -114,6 +117,7 @@ where
 		BlockNumber,
 	>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: pallet_asset_tx_payment_rpc::AssetTxPaymentRuntimeApi<Block, u32, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
@@ -121,6 +125,7 @@ where
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
+	use pallet_asset_tx_payment_rpc::{AssetTxPayment, AssetTxPaymentApiServer};
 	use pallet_mmr_rpc::{Mmr, MmrApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
@@ -137,6 +142,7 @@ where
 	let BabeDeps { keystore, babe_config, shared_epoch_changes } = babe;
 	let GrandpaDeps {
 		shared_voter_state,
+		shared_voter_participation,
 		shared_authority_set,
 		justification_stream,
 		subscription_executor,
@@ -154,6 +160,7 @@ where
 	// These RPCs should use an asynchronous caller instead.
 	io.merge(Mmr::new(client.clone()).into_rpc())?;
 	io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	io.merge(AssetTxPayment::new(client.clone()).into_rpc())?;
 	io.merge(
 		Babe::new(
 			client.clone(),
@@ -170,6 +177,7 @@ where
 			subscription_executor,
 			shared_authority_set.clone(),
 			shared_voter_state,
+			shared_voter_participation,
 			justification_stream,
 			finality_provider,
 		)
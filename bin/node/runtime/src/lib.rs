@@ -34,7 +34,8 @@ use frame_support::{
 	traits::{
 		AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32, Currency, EitherOfDiverse,
 		EqualPrivilegeOnly, Everything, Imbalance, InstanceFilter, KeyOwnerProofSystem,
-		LockIdentifier, Nothing, OnUnbalanced, U128CurrencyToVote, WithdrawReasons,
+		LockIdentifier, Nothing, OnUnbalanced, U128CurrencyToVote, VariantCountOf,
+		WithdrawReasons,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -44,10 +45,11 @@ use frame_support::{
 };
 use frame_system::{
 	limits::{BlockLength, BlockWeights},
-	EnsureRoot, EnsureRootWithSuccess, EnsureSigned, EnsureWithSuccess,
+	EnsureNever, EnsureRoot, EnsureRootWithSuccess, EnsureSigned, EnsureWithSuccess,
 };
 pub use node_primitives::{AccountId, Signature};
 use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment};
+use pallet_asset_conversion::NativeOrAssetId;
 use pallet_election_provider_multi_phase::SolutionAccuracyOf;
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
@@ -126,7 +128,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// and set impl_version to 0. If only runtime
 	// implementation changes and behavior does not, then leave spec_version as
 	// is and increment impl_version.
-	spec_version: 268,
+	spec_version: 270,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 2,
@@ -207,6 +209,7 @@ impl frame_system::Config for Runtime {
 	type DbWeight = RocksDbWeight;
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
 	type Index = Index;
 	type BlockNumber = BlockNumber;
 	type Hash = Hash;
@@ -365,6 +368,7 @@ parameter_types! {
 impl pallet_preimage::Config for Runtime {
 	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type Currency = Balances;
 	type ManagerOrigin = EnsureRoot<AccountId>;
 	type BaseDeposit = PreimageBaseDeposit;
@@ -429,6 +433,11 @@ impl pallet_balances::Config for Runtime {
 	type MaxLocks = MaxLocks;
 	type MaxReserves = MaxReserves;
 	type ReserveIdentifier = [u8; 8];
+	type HoldIdentifier = RuntimeHoldReason;
+	type FreezeIdentifier = RuntimeFreezeReason;
+	// An account holds at most once per reason of the runtime.
+	type MaxHolds = VariantCountOf<RuntimeHoldReason>;
+	type MaxFreezes = VariantCountOf<RuntimeFreezeReason>;
 	type Balance = Balance;
 	type DustRemoval = ();
 	type RuntimeEvent = RuntimeEvent;
@@ -464,8 +473,13 @@ impl pallet_transaction_payment::Config for Runtime {
 impl pallet_asset_tx_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Fungibles = Assets;
+	// Assets with a pool of native currency are priced by the pool, the sufficient ones without a
+	// pool by their `min_balance`.
 	type OnChargeAssetTransaction = pallet_asset_tx_payment::FungiblesAdapter<
-		pallet_assets::BalanceToAssetBalance<Balances, Runtime, ConvertInto>,
+		pallet_asset_tx_payment::ConversionWithFallback<
+			AssetConversion,
+			pallet_assets::BalanceToAssetBalance<Balances, Runtime, ConvertInto>,
+		>,
 		CreditToBlockAuthor,
 	>;
 }
@@ -1455,6 +1469,63 @@ impl pallet_assets::Config for Runtime {
 	type RemoveItemsLimit = ConstU32<1000>;
 }
 
+impl pallet_assets::Config<pallet_assets::Instance1> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = u128;
+	type AssetId = u32;
+	type Currency = Balances;
+	// LP tokens can only be created by the asset conversion pallet.
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureNever<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU128<0>;
+	type AssetAccountDeposit = ConstU128<0>;
+	type MetadataDepositBase = ConstU128<0>;
+	type MetadataDepositPerByte = ConstU128<0>;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+	type RemoveItemsLimit = ConstU32<1000>;
+}
+
+parameter_types! {
+	pub const AssetConversionPalletId: PalletId = PalletId(*b"py/ascon");
+	pub const LiquidityFee: Permill = Permill::from_perthousand(3);
+	pub const MintMinLiquidity: Balance = 100;
+}
+
+impl pallet_asset_conversion::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type Balance = Balance;
+	type AssetId = u32;
+	type Assets = Assets;
+	type PoolAssetId = u32;
+	type PoolAssets = PoolAssets;
+	type LPFee = LiquidityFee;
+	type PalletId = AssetConversionPalletId;
+	type MintMinLiquidity = MintMinLiquidity;
+	type MaxSwapPathLength = ConstU32<4>;
+	type WeightInfo = pallet_asset_conversion::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MinReversibleTransferDelay: BlockNumber = 10 * MINUTES;
+	pub const MaxReversibleTransferDelay: BlockNumber = 30 * DAYS;
+}
+
+impl pallet_reversible_transfers::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type Currency = Balances;
+	type Scheduler = Scheduler;
+	type MinDelay = MinReversibleTransferDelay;
+	type MaxDelay = MaxReversibleTransferDelay;
+	type WeightInfo = pallet_reversible_transfers::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
 	pub IgnoredIssuance: Balance = Treasury::pot();
 	pub const QueueCount: u32 = 300;
@@ -1681,6 +1752,9 @@ construct_runtime!(
 		RankedPolls: pallet_referenda::<Instance2>,
 		RankedCollective: pallet_ranked_collective,
 		FastUnstake: pallet_fast_unstake,
+		PoolAssets: pallet_assets::<Instance1>,
+		AssetConversion: pallet_asset_conversion,
+		ReversibleTransfers: pallet_reversible_transfers,
 	}
 );
 
@@ -1733,6 +1807,7 @@ type Migrations = (
 	pallet_nomination_pools::migration::v2::MigrateToV2<Runtime>,
 	pallet_alliance::migration::Migration<Runtime>,
 	pallet_contracts::Migration<Runtime>,
+	pallet_preimage::migration::v2::MigrateToHolds<Runtime, Balances>,
 );
 
 /// MMR helper types.
@@ -1755,6 +1830,7 @@ mod benches {
 		[frame_benchmarking, BaselineBench::<Runtime>]
 		[pallet_alliance, Alliance]
 		[pallet_assets, Assets]
+		[pallet_asset_conversion, AssetConversion]
 		[pallet_babe, Babe]
 		[pallet_bags_list, VoterList]
 		[pallet_balances, Balances]
@@ -1783,6 +1859,7 @@ mod benches {
 		[pallet_proxy, Proxy]
 		[pallet_ranked_collective, RankedCollective]
 		[pallet_referenda, Referenda]
+		[pallet_reversible_transfers, ReversibleTransfers]
 		[pallet_recovery, Recovery]
 		[pallet_remark, Remark]
 		[pallet_scheduler, Scheduler]
@@ -1959,6 +2036,26 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl sp_storage_size::StorageSizeApi<Block> for Runtime {
+		fn storage_size() -> Vec<sp_storage_size::StorageSize> {
+			use frame_support::traits::StorageSizeTrait;
+			AllPalletsWithSystem::storage_size()
+		}
+	}
+
+	impl frame_support::view_functions::RuntimeViewFunction<Block> for Runtime {
+		fn execute_view_function(
+			id: frame_support::view_functions::ViewFunctionId,
+			input: Vec<u8>,
+		) -> Result<Vec<u8>, frame_support::view_functions::ViewFunctionDispatchError> {
+			frame_support::view_functions::execute_view_function::<AllPalletsWithSystem>(id, input)
+		}
+
+		fn view_functions_metadata() -> OpaqueMetadata {
+			frame_support::view_functions::view_functions_metadata::<AllPalletsWithSystem>()
+		}
+	}
+
 	impl pallet_contracts::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime
 	{
 		fn call(
@@ -2054,6 +2151,47 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_asset_tx_payment_rpc_runtime_api::AssetTxPaymentApi<Block, u32, Balance> for Runtime {
+		fn query_info_in_asset(
+			uxt: <Block as BlockT>::Extrinsic,
+			len: u32,
+			asset_id: u32,
+		) -> Option<RuntimeDispatchInfo<Balance>> {
+			AssetTxPayment::query_info_in_asset(uxt, len, asset_id)
+		}
+	}
+
+	impl pallet_asset_conversion_runtime_api::AssetConversionApi<
+		Block,
+		Balance,
+		NativeOrAssetId<u32>,
+	> for Runtime {
+		fn quote_price_tokens_for_exact_tokens(
+			asset1: NativeOrAssetId<u32>,
+			asset2: NativeOrAssetId<u32>,
+			amount: Balance,
+			include_fee: bool,
+		) -> Option<Balance> {
+			AssetConversion::quote_price_tokens_for_exact_tokens(asset1, asset2, amount, include_fee)
+		}
+
+		fn quote_price_exact_tokens_for_tokens(
+			asset1: NativeOrAssetId<u32>,
+			asset2: NativeOrAssetId<u32>,
+			amount: Balance,
+			include_fee: bool,
+		) -> Option<Balance> {
+			AssetConversion::quote_price_exact_tokens_for_tokens(asset1, asset2, amount, include_fee)
+		}
+
+		fn get_reserves(
+			asset1: NativeOrAssetId<u32>,
+			asset2: NativeOrAssetId<u32>,
+		) -> Option<(Balance, Balance)> {
+			AssetConversion::get_reserves(asset1, asset2).ok()
+		}
+	}
+
 	impl pallet_mmr::primitives::MmrApi<
 		Block,
 		mmr::Hash,
@@ -60,6 +60,20 @@ pub trait CallExecutor<B: BlockT>: RuntimeVersionOf {
 		strategy: ExecutionStrategy,
 	) -> Result<Vec<u8>, sp_blockchain::Error>;
 
+	/// Execute a call like [`Self::call`], aborting it once it consumed `fuel_limit` units of
+	/// fuel.
+	///
+	/// The call always runs in Wasm, compiled to consume fuel. Exhausting the budget fails the
+	/// call with an out of fuel error.
+	fn call_with_fuel_limit(
+		&self,
+		id: &BlockId<B>,
+		method: &str,
+		call_data: &[u8],
+		strategy: ExecutionStrategy,
+		fuel_limit: u64,
+	) -> Result<Vec<u8>, sp_blockchain::Error>;
+
 	/// Execute a contextual call on top of state in a block of a given hash.
 	///
 	/// No changes are made.
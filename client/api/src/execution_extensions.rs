@@ -38,10 +38,40 @@ use sp_runtime::{
 pub use sp_state_machine::ExecutionStrategy;
 use sp_state_machine::{DefaultHandler, ExecutionManager};
 use std::{
+	cell::Cell,
 	marker::PhantomData,
 	sync::{Arc, Weak},
 };
 
+thread_local! {
+	static OFFCHAIN_CALL_STATS: Cell<OffchainCallStats> = Cell::new(Default::default());
+}
+
+/// Side effects of the offchain calls executed on the current thread.
+///
+/// Offchain calls run on the thread that calls into the runtime, which allows the caller to
+/// attribute them to a single call using [`take_offchain_call_stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OffchainCallStats {
+	/// Number of bytes written to the offchain database.
+	pub local_storage_bytes_written: u64,
+	/// Number of transactions successfully submitted to the transaction pool.
+	pub transactions_submitted: u64,
+}
+
+/// Returns the [`OffchainCallStats`] collected on the current thread and resets them.
+pub fn take_offchain_call_stats() -> OffchainCallStats {
+	OFFCHAIN_CALL_STATS.with(|stats| stats.take())
+}
+
+fn note_offchain_call(f: impl FnOnce(&mut OffchainCallStats)) {
+	OFFCHAIN_CALL_STATS.with(|stats| {
+		let mut current = stats.get();
+		f(&mut current);
+		stats.set(current);
+	});
+}
+
 /// Execution strategies settings.
 #[derive(Debug, Clone)]
 pub struct ExecutionStrategies {
@@ -259,7 +289,7 @@ impl<Block: BlockT> ExecutionExtensions<Block> {
 			if let Some(offchain_db) = self.offchain_db.as_ref() {
 				extensions.register(OffchainDbExt::new(offchain::LimitedExternalities::new(
 					capabilities,
-					offchain_db.create(),
+					OffchainDbAdapter(offchain_db.create()),
 				)));
 			}
 		}
@@ -313,6 +343,42 @@ impl<Block: BlockT> offchain::TransactionPool for TransactionPoolAdapter<Block>
 			},
 		};
 
-		self.pool.submit_at(&self.at, xt)
+		let result = self.pool.submit_at(&self.at, xt);
+		if result.is_ok() {
+			note_offchain_call(|stats| stats.transactions_submitted += 1);
+		}
+		result
+	}
+}
+
+/// A wrapper type accounting the writes to the offchain database.
+struct OffchainDbAdapter(Box<dyn offchain::DbExternalities>);
+
+impl offchain::DbExternalities for OffchainDbAdapter {
+	fn local_storage_set(&mut self, kind: offchain::StorageKind, key: &[u8], value: &[u8]) {
+		note_offchain_call(|stats| stats.local_storage_bytes_written += value.len() as u64);
+		self.0.local_storage_set(kind, key, value)
+	}
+
+	fn local_storage_clear(&mut self, kind: offchain::StorageKind, key: &[u8]) {
+		self.0.local_storage_clear(kind, key)
+	}
+
+	fn local_storage_compare_and_set(
+		&mut self,
+		kind: offchain::StorageKind,
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) -> bool {
+		let set = self.0.local_storage_compare_and_set(kind, key, old_value, new_value);
+		if set {
+			note_offchain_call(|stats| stats.local_storage_bytes_written += new_value.len() as u64);
+		}
+		set
+	}
+
+	fn local_storage_get(&mut self, kind: offchain::StorageKind, key: &[u8]) -> Option<Vec<u8>> {
+		self.0.local_storage_get(kind, key)
 	}
 }
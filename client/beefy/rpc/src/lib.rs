@@ -23,8 +23,9 @@
 use parking_lot::RwLock;
 use std::sync::Arc;
 
+use sc_client_api::AuxStore;
 use sc_rpc::SubscriptionTaskExecutor;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
 
 use futures::{task::SpawnError, FutureExt, StreamExt};
 use jsonrpsee::{
@@ -41,6 +42,9 @@ use beefy_gadget::communication::notification::{
 
 mod notification;
 
+/// Maximum number of blocks in a `beefy_getJustifications` range.
+const MAX_JUSTIFICATIONS_RANGE: u32 = 4096;
+
 #[derive(Debug, thiserror::Error)]
 /// Top-level error type for the RPC handler
 pub enum Error {
@@ -50,6 +54,12 @@ pub enum Error {
 	/// The BEEFY RPC background task failed to spawn.
 	#[error("BEEFY RPC background task failed to spawn")]
 	RpcTaskFailure(#[from] SpawnError),
+	/// The requested range of blocks is invalid.
+	#[error("Invalid block range: {0}")]
+	InvalidRange(String),
+	/// Reading archived BEEFY justifications failed.
+	#[error("Failed to load BEEFY justifications: {0}")]
+	LoadJustifications(#[from] sp_blockchain::Error),
 }
 
 /// The error codes returned by jsonrpc.
@@ -58,6 +68,10 @@ pub enum ErrorCode {
 	NotReady = 1,
 	/// Returned on BEEFY RPC background task failure.
 	TaskFailure = 2,
+	/// Returned when the requested block range is invalid.
+	InvalidRange = 3,
+	/// Returned when archived justifications could not be loaded.
+	LoadJustifications = 4,
 }
 
 impl From<Error> for ErrorCode {
@@ -65,6 +79,8 @@ impl From<Error> for ErrorCode {
 		match error {
 			Error::EndpointNotReady => ErrorCode::NotReady,
 			Error::RpcTaskFailure(_) => ErrorCode::TaskFailure,
+			Error::InvalidRange(_) => ErrorCode::InvalidRange,
+			Error::LoadJustifications(_) => ErrorCode::LoadJustifications,
 		}
 	}
 }
@@ -83,7 +99,7 @@ impl From<Error> for JsonRpseeError {
 
 // Provides RPC methods for interacting with BEEFY.
 #[rpc(client, server)]
-pub trait BeefyApi<Notification, Hash> {
+pub trait BeefyApi<Notification, Hash, Number> {
 	/// Returns the block most recently finalized by BEEFY, alongside its justification.
 	#[subscription(
 		name = "beefy_subscribeJustifications" => "beefy_justifications",
@@ -99,16 +115,25 @@ pub trait BeefyApi<Notification, Hash> {
 	/// In such case an error would be returned.
 	#[method(name = "beefy_getFinalizedHead")]
 	async fn latest_finalized(&self) -> RpcResult<Hash>;
+
+	/// Returns the archived BEEFY justifications for blocks in the inclusive range `[from, to]`,
+	/// ordered by block number.
+	///
+	/// Only justifications this node has seen are returned, which always include the ones for
+	/// mandatory blocks once the node is in sync.
+	#[method(name = "beefy_getJustifications")]
+	async fn justifications(&self, from: Number, to: Number) -> RpcResult<Vec<Notification>>;
 }
 
 /// Implements the BeefyApi RPC trait for interacting with BEEFY.
-pub struct Beefy<Block: BlockT> {
+pub struct Beefy<Block: BlockT, Backend> {
 	finality_proof_stream: BeefyVersionedFinalityProofStream<Block>,
 	beefy_best_block: Arc<RwLock<Option<Block::Hash>>>,
+	backend: Arc<Backend>,
 	executor: SubscriptionTaskExecutor,
 }
 
-impl<Block> Beefy<Block>
+impl<Block, Backend> Beefy<Block, Backend>
 where
 	Block: BlockT,
 {
@@ -116,6 +141,7 @@ where
 	pub fn new(
 		finality_proof_stream: BeefyVersionedFinalityProofStream<Block>,
 		best_block_stream: BeefyBestBlockStream<Block>,
+		backend: Arc<Backend>,
 		executor: SubscriptionTaskExecutor,
 	) -> Result<Self, Error> {
 		let beefy_best_block = Arc::new(RwLock::new(None));
@@ -128,15 +154,17 @@ where
 		});
 
 		executor.spawn("substrate-rpc-subscription", Some("rpc"), future.map(drop).boxed());
-		Ok(Self { finality_proof_stream, beefy_best_block, executor })
+		Ok(Self { finality_proof_stream, beefy_best_block, backend, executor })
 	}
 }
 
 #[async_trait]
-impl<Block> BeefyApiServer<notification::EncodedVersionedFinalityProof, Block::Hash>
-	for Beefy<Block>
+impl<Block, Backend>
+	BeefyApiServer<notification::EncodedVersionedFinalityProof, Block::Hash, NumberFor<Block>>
+	for Beefy<Block, Backend>
 where
 	Block: BlockT,
+	Backend: AuxStore + Send + Sync + 'static,
 {
 	fn subscribe_justifications(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
 		let stream = self
@@ -160,6 +188,32 @@ where
 			.ok_or(Error::EndpointNotReady)
 			.map_err(Into::into)
 	}
+
+	async fn justifications(
+		&self,
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+	) -> RpcResult<Vec<notification::EncodedVersionedFinalityProof>> {
+		if from > to || to - from >= MAX_JUSTIFICATIONS_RANGE.into() {
+			return Err(Error::InvalidRange(format!(
+				"expected `from <= to` spanning at most {} blocks",
+				MAX_JUSTIFICATIONS_RANGE
+			))
+			.into())
+		}
+
+		beefy_gadget::load_justifications::<Block, _>(&*self.backend, from, to)
+			.map(|justifs| {
+				justifs
+					.into_iter()
+					.map(notification::EncodedVersionedFinalityProof::new::<Block>)
+					.collect()
+			})
+			.map_err(|e| {
+				warn!("Error loading BEEFY justifications: {}", e);
+				Error::LoadJustifications(e).into()
+			})
+	}
 }
 
 #[cfg(test)]
@@ -174,22 +228,28 @@ mod tests {
 	use codec::{Decode, Encode};
 	use jsonrpsee::{types::EmptyParams, RpcModule};
 	use sp_runtime::traits::{BlakeTwo256, Hash};
-	use substrate_test_runtime_client::runtime::Block;
+	use substrate_test_runtime_client::{runtime::Block, TestClient};
+
+	type TestRpcModule = RpcModule<Beefy<Block, TestClient>>;
 
-	fn setup_io_handler() -> (RpcModule<Beefy<Block>>, BeefyVersionedFinalityProofSender<Block>) {
+	fn setup_io_handler() -> (TestRpcModule, BeefyVersionedFinalityProofSender<Block>) {
 		let (_, stream) = BeefyBestBlockStream::<Block>::channel();
 		setup_io_handler_with_best_block_stream(stream)
 	}
 
 	fn setup_io_handler_with_best_block_stream(
 		best_block_stream: BeefyBestBlockStream<Block>,
-	) -> (RpcModule<Beefy<Block>>, BeefyVersionedFinalityProofSender<Block>) {
+	) -> (TestRpcModule, BeefyVersionedFinalityProofSender<Block>) {
 		let (finality_proof_sender, finality_proof_stream) =
 			BeefyVersionedFinalityProofStream::<Block>::channel();
 
-		let handler =
-			Beefy::new(finality_proof_stream, best_block_stream, sc_rpc::testing::test_executor())
-				.expect("Setting up the BEEFY RPC handler works");
+		let handler = Beefy::new(
+			finality_proof_stream,
+			best_block_stream,
+			Arc::new(substrate_test_runtime_client::new()),
+			sc_rpc::testing::test_executor(),
+		)
+		.expect("Setting up the BEEFY RPC handler works");
 
 		(handler.into_rpc(), finality_proof_sender)
 	}
@@ -244,6 +304,30 @@ mod tests {
 		);
 	}
 
+	#[tokio::test]
+	async fn justifications_rpc() {
+		let (rpc, _) = setup_io_handler();
+
+		// nothing archived
+		let request =
+			r#"{"jsonrpc":"2.0","method":"beefy_getJustifications","params":[1,10],"id":1}"#;
+		let (response, _) = rpc.raw_json_request(&request).await.unwrap();
+		assert_eq!(response.result, r#"{"jsonrpc":"2.0","result":[],"id":1}"#);
+
+		// reversed range
+		let request =
+			r#"{"jsonrpc":"2.0","method":"beefy_getJustifications","params":[10,1],"id":1}"#;
+		let (response, _) = rpc.raw_json_request(&request).await.unwrap();
+		let expected = r#"{"jsonrpc":"2.0","error":{"code":3,"message":"Invalid block range: expected `from <= to` spanning at most 4096 blocks"},"id":1}"#;
+		assert_eq!(response.result, expected);
+
+		// range too long
+		let request =
+			r#"{"jsonrpc":"2.0","method":"beefy_getJustifications","params":[1,4097],"id":1}"#;
+		let (response, _) = rpc.raw_json_request(&request).await.unwrap();
+		assert_eq!(response.result, expected);
+	}
+
 	#[tokio::test]
 	async fn subscribe_and_unsubscribe_with_wrong_id() {
 		let (rpc, _) = setup_io_handler();
@@ -18,15 +18,17 @@
 
 //! Schema for BEEFY state persisted in the aux-db.
 
-use crate::worker::PersistedState;
+use crate::{justification::BeefyVersionedFinalityProof, worker::PersistedState};
+use beefy_primitives::VersionedFinalityProof;
 use codec::{Decode, Encode};
 use log::{info, trace};
 use sc_client_api::{backend::AuxStore, Backend};
 use sp_blockchain::{Error as ClientError, Result as ClientResult};
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, NumberFor, One};
 
 const VERSION_KEY: &[u8] = b"beefy_auxschema_version";
 const WORKER_STATE: &[u8] = b"beefy_voter_state";
+const JUSTIFICATION_PREFIX: &[u8] = b"beefy_justification";
 
 const CURRENT_VERSION: u32 = 1;
 
@@ -44,6 +46,52 @@ pub(crate) fn write_voter_state<Block: BlockT, B: AuxStore>(
 	backend.insert_aux(&[(WORKER_STATE, state.encode().as_slice())], &[])
 }
 
+fn justification_key<N: Encode>(block: N) -> Vec<u8> {
+	(JUSTIFICATION_PREFIX, block).encode()
+}
+
+/// Archive a BEEFY justification by the number of the block it finalizes.
+pub(crate) fn write_justification<Block: BlockT, B: AuxStore>(
+	backend: &B,
+	justification: &BeefyVersionedFinalityProof<Block>,
+) -> ClientResult<()> {
+	let block = match justification {
+		VersionedFinalityProof::V1(sc) => sc.commitment.block_number,
+	};
+	trace!(target: "beefy", "🥩 archiving justification for block #{:?}", block);
+	let key = justification_key(block);
+	backend.insert_aux(&[(key.as_slice(), justification.encode().as_slice())], &[])
+}
+
+/// Load the archived BEEFY justification for `block`, if any.
+pub fn load_justification<Block: BlockT, B: AuxStore>(
+	backend: &B,
+	block: NumberFor<Block>,
+) -> ClientResult<Option<BeefyVersionedFinalityProof<Block>>> {
+	load_decode(backend, &justification_key(block))
+}
+
+/// Load all archived BEEFY justifications for blocks in the inclusive range `[from, to]`,
+/// ordered by block number.
+pub fn load_justifications<Block: BlockT, B: AuxStore>(
+	backend: &B,
+	from: NumberFor<Block>,
+	to: NumberFor<Block>,
+) -> ClientResult<Vec<BeefyVersionedFinalityProof<Block>>> {
+	let mut justifications = Vec::new();
+	let mut block = from;
+	while block <= to {
+		if let Some(justification) = load_justification::<Block, _>(backend, block)? {
+			justifications.push(justification);
+		}
+		if block == to {
+			break
+		}
+		block += One::one();
+	}
+	Ok(justifications)
+}
+
 fn load_decode<B: AuxStore, T: Decode>(backend: &B, key: &[u8]) -> ClientResult<Option<T>> {
 	match backend.get_aux(key)? {
 		None => Ok(None),
@@ -75,8 +123,13 @@ where
 #[cfg(test)]
 pub(crate) mod tests {
 	use super::*;
-	use crate::tests::BeefyTestNet;
-	use sc_network_test::TestNetFactory;
+	use crate::{
+		justification::tests::new_finality_proof,
+		keystore::tests::Keyring,
+		tests::{make_beefy_ids, BeefyTestNet},
+	};
+	use beefy_primitives::ValidatorSet;
+	use sc_network_test::{Block, TestNetFactory};
 
 	// also used in tests.rs
 	pub fn verify_persisted_version<B: BlockT, BE: Backend<B>>(backend: &BE) -> bool {
@@ -102,4 +155,34 @@ pub(crate) mod tests {
 
 		// full `PersistedState` load is tested in `tests.rs`.
 	}
+
+	#[test]
+	fn should_archive_justifications_by_block_number() {
+		let mut net = BeefyTestNet::new(1);
+		let backend = net.peer(0).client().as_backend();
+
+		let keys = &[Keyring::Alice, Keyring::Bob];
+		let validator_set = ValidatorSet::new(make_beefy_ids(keys), 0).unwrap();
+		let justification = |block| new_finality_proof(block, &validator_set, keys);
+
+		// nothing archived yet.
+		assert_eq!(load_justification::<Block, _>(&*backend, 1).unwrap(), None);
+		assert_eq!(load_justifications::<Block, _>(&*backend, 1, 10).unwrap(), vec![]);
+
+		for block in [2, 5, 8] {
+			write_justification(&*backend, &justification(block)).unwrap();
+		}
+
+		assert_eq!(load_justification::<Block, _>(&*backend, 5).unwrap(), Some(justification(5)));
+		assert_eq!(
+			load_justifications::<Block, _>(&*backend, 1, 5).unwrap(),
+			vec![justification(2), justification(5)],
+		);
+		assert_eq!(
+			load_justifications::<Block, _>(&*backend, 6, 8).unwrap(),
+			vec![justification(8)],
+		);
+		// empty range.
+		assert_eq!(load_justifications::<Block, _>(&*backend, 8, 2).unwrap(), vec![]);
+	}
 }
@@ -17,20 +17,23 @@
 //! Helper for handling (i.e. answering) BEEFY justifications requests from a remote peer.
 
 use beefy_primitives::BEEFY_ENGINE_ID;
-use codec::Decode;
+use codec::{Decode, Encode};
 use futures::{
 	channel::{mpsc, oneshot},
 	StreamExt,
 };
 use log::{debug, trace};
-use sc_client_api::BlockBackend;
+use sc_client_api::{AuxStore, BlockBackend};
 use sc_network::{config as netconfig, config::RequestResponseConfig, PeerId, ReputationChange};
 use sc_network_common::protocol::ProtocolName;
 use sp_runtime::traits::Block;
 use std::{marker::PhantomData, sync::Arc};
 
-use crate::communication::request_response::{
-	on_demand_justifications_protocol_config, Error, JustificationRequest,
+use crate::{
+	aux_schema::load_justification,
+	communication::request_response::{
+		on_demand_justifications_protocol_config, Error, JustificationRequest,
+	},
 };
 
 /// A request coming in, including a sender for sending responses.
@@ -125,7 +128,7 @@ pub struct BeefyJustifsRequestHandler<B, Client> {
 impl<B, Client> BeefyJustifsRequestHandler<B, Client>
 where
 	B: Block,
-	Client: BlockBackend<B> + Send + Sync,
+	Client: BlockBackend<B> + AuxStore + Send + Sync,
 {
 	/// Create a new [`BeefyJustifsRequestHandler`].
 	pub fn new<Hash: AsRef<[u8]>>(
@@ -145,21 +148,28 @@ where
 		self.justif_protocol_name.clone()
 	}
 
-	// Sends back justification response if justification found in client backend.
+	// Sends back justification response if justification found in client backend or in the
+	// justifications archive.
 	fn handle_request(&self, request: IncomingRequest<B>) -> Result<(), Error> {
 		// TODO (issue #12293): validate `request` and change peer reputation for invalid requests.
 
-		let maybe_encoded_proof = if let Some(hash) =
-			self.client.block_hash(request.payload.begin).map_err(Error::Client)?
-		{
-			self.client
-				.justifications(hash)
+		let block = request.payload.begin;
+		let maybe_encoded_proof =
+			if let Some(hash) = self.client.block_hash(block).map_err(Error::Client)? {
+				self.client
+					.justifications(hash)
+					.map_err(Error::Client)?
+					.and_then(|justifs| justifs.get(BEEFY_ENGINE_ID).cloned())
+			} else {
+				None
+			};
+		let maybe_encoded_proof = match maybe_encoded_proof {
+			Some(encoded) => Ok(encoded),
+			None => load_justification::<B, _>(&*self.client, block)
 				.map_err(Error::Client)?
-				.and_then(|justifs| justifs.get(BEEFY_ENGINE_ID).cloned())
+				.map(|justif| justif.encode())
 				// No BEEFY justification present.
-				.ok_or(())
-		} else {
-			Err(())
+				.ok_or(()),
 		};
 
 		request
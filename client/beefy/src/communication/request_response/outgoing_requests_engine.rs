@@ -117,12 +117,17 @@ impl<B: Block> OnDemandJustificationsEngine<B> {
 		self.state = State::AwaitingResponse(peer, req_info, rx);
 	}
 
+	/// Whether a justification request is in progress.
+	pub fn is_busy(&self) -> bool {
+		matches!(self.state, State::AwaitingResponse(_, _, _))
+	}
+
 	/// Start new justification request for `block`, if no other request is in progress.
 	///
 	/// `active_set` will be used to verify validity of potential responses.
 	pub fn request(&mut self, block: NumberFor<B>, active_set: ValidatorSet<AuthorityId>) {
 		// ignore new requests while there's already one pending
-		if matches!(self.state, State::AwaitingResponse(_, _, _)) {
+		if self.is_busy() {
 			return
 		}
 		self.reset_peers_cache_for_block(block);
@@ -39,7 +39,9 @@ use futures::{stream::Fuse, StreamExt};
 use log::{debug, error, info};
 use parking_lot::Mutex;
 use prometheus::Registry;
-use sc_client_api::{Backend, BlockBackend, BlockchainEvents, FinalityNotifications, Finalizer};
+use sc_client_api::{
+	AuxStore, Backend, BlockBackend, BlockchainEvents, FinalityNotifications, Finalizer,
+};
 use sc_consensus::BlockImport;
 use sc_network::ProtocolName;
 use sc_network_common::service::NetworkRequest;
@@ -68,6 +70,7 @@ pub mod communication;
 pub mod import;
 pub mod justification;
 
+pub use aux_schema::{load_justification, load_justifications};
 pub use communication::beefy_protocol_name::{
 	gossip_protocol_name, justifications_protocol_name as justifs_protocol_name,
 };
@@ -209,7 +212,7 @@ pub async fn start_beefy_gadget<B, BE, C, N, P, R>(beefy_params: BeefyParams<B,
 where
 	B: Block,
 	BE: Backend<B>,
-	C: Client<B, BE> + BlockBackend<B>,
+	C: Client<B, BE> + BlockBackend<B> + AuxStore,
 	P: PayloadProvider<B>,
 	R: ProvideRuntimeApi<B>,
 	R::Api: BeefyApi<B> + MmrApi<B, MmrRootHash, NumberFor<B>>,
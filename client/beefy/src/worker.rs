@@ -22,7 +22,7 @@ use crate::{
 		request_response::outgoing_requests_engine::OnDemandJustificationsEngine,
 	},
 	error::Error,
-	justification::BeefyVersionedFinalityProof,
+	justification::{verify_with_validator_set, BeefyVersionedFinalityProof},
 	keystore::BeefyKeystore,
 	metric_inc, metric_set,
 	metrics::Metrics,
@@ -46,7 +46,7 @@ use sp_arithmetic::traits::{AtLeast32Bit, Saturating};
 use sp_consensus::SyncOracle;
 use sp_runtime::{
 	generic::OpaqueDigestItemId,
-	traits::{Block, Header, NumberFor, Zero},
+	traits::{Block, Header, NumberFor, One, Zero},
 	SaturatedConversion,
 };
 use std::{
@@ -311,6 +311,9 @@ pub(crate) struct BeefyWorker<B: Block, BE, P, N> {
 	pending_justifications: BTreeMap<NumberFor<B>, BeefyVersionedFinalityProof<B>>,
 	/// Persisted voter state.
 	persisted_state: PersistedState<B>,
+	/// Start of the last session whose mandatory justification was looked up for backfilling,
+	/// walking back from the active session. Zero once there are no sessions left to walk.
+	backfill_cursor: Option<NumberFor<B>>,
 }
 
 impl<B, BE, P, N> BeefyWorker<B, BE, P, N>
@@ -353,6 +356,7 @@ where
 			pending_votes: BTreeMap::new(),
 			pending_justifications: BTreeMap::new(),
 			persisted_state,
+			backfill_cursor: None,
 		}
 	}
 
@@ -507,7 +511,8 @@ where
 				debug!(target: "beefy", "🥩 Buffer justification for round: {:?}.", block_num);
 				self.pending_justifications.entry(block_num).or_insert(justification);
 			},
-			RoundAction::Drop => (),
+			// Round is already concluded, but we might still be missing its justification.
+			RoundAction::Drop => self.verify_and_archive_justification(&justification),
 		};
 		Ok(())
 	}
@@ -567,9 +572,10 @@ where
 
 	/// Provide BEEFY finality for block based on `finality_proof`:
 	/// 1. Prune now-irrelevant past sessions from the oracle,
-	/// 2. Set BEEFY best block,
-	/// 3. Persist voter state,
-	/// 4. Send best block hash and `finality_proof` to RPC worker.
+	/// 2. Archive `finality_proof`,
+	/// 3. Set BEEFY best block,
+	/// 4. Persist voter state,
+	/// 5. Send best block hash and `finality_proof` to RPC worker.
 	///
 	/// Expects `finality proof` to be valid.
 	fn finalize(&mut self, finality_proof: BeefyVersionedFinalityProof<B>) -> Result<(), Error> {
@@ -580,6 +586,8 @@ where
 		// Finalize inner round and update voting_oracle state.
 		self.persisted_state.voting_oracle.finalize(block_num)?;
 
+		self.archive_justification(&finality_proof);
+
 		if block_num > self.best_beefy_block() {
 			// Set new best BEEFY block number.
 			self.persisted_state.best_beefy_block = block_num;
@@ -616,6 +624,118 @@ where
 		Ok(())
 	}
 
+	/// Archive `justification` in the aux db, unless there already is one for its block.
+	///
+	/// Expects `justification` to be valid.
+	fn archive_justification(&self, justification: &BeefyVersionedFinalityProof<B>) {
+		let block_num = match justification {
+			VersionedFinalityProof::V1(ref sc) => sc.commitment.block_number,
+		};
+		let result = crate::aux_schema::load_justification::<B, _>(&*self.backend, block_num)
+			.and_then(|archived| match archived {
+				Some(_) => Ok(()),
+				None => crate::aux_schema::write_justification(&*self.backend, justification),
+			});
+		if let Err(e) = result {
+			error!(target: "beefy", "🥩 Error {:?} on archiving justification: {:?}", e, justification);
+		}
+	}
+
+	/// Archive the `justification` of an already concluded round, after verifying it against the
+	/// validator set of the session it belongs to.
+	fn verify_and_archive_justification(&self, justification: &BeefyVersionedFinalityProof<B>) {
+		let block_num = match justification {
+			VersionedFinalityProof::V1(ref sc) => sc.commitment.block_number,
+		};
+		let validator_set = match self.session_at(block_num) {
+			Some((_, validator_set)) => validator_set,
+			None => {
+				debug!(target: "beefy", "🥩 Unknown session for justification #{:?}.", block_num);
+				return
+			},
+		};
+		match verify_with_validator_set::<B>(block_num, &validator_set, justification) {
+			Ok(()) => self.archive_justification(justification),
+			Err(e) => debug!(
+				target: "beefy",
+				"🥩 Not archiving invalid justification #{:?}: {:?}.", block_num, e
+			),
+		}
+	}
+
+	/// Find the session `block` belongs to, returning its start and validator set.
+	///
+	/// Past sessions are pruned from the voting oracle, for those walk back the headers until the
+	/// BEEFY authorities change starting the session. Returns `None` if there is none, e.g. for
+	/// the genesis session.
+	fn session_at(&self, block: NumberFor<B>) -> Option<(NumberFor<B>, ValidatorSet<AuthorityId>)> {
+		if let Some(rounds) =
+			self.voting_oracle().sessions.iter().rev().find(|r| r.session_start() <= block)
+		{
+			return Some((rounds.session_start(), rounds.validator_set().clone()))
+		}
+
+		let blockchain = self.backend.blockchain();
+		let mut number = block;
+		loop {
+			let header = blockchain.header(BlockId::number(number)).ok().flatten()?;
+			if let Some(validator_set) = find_authorities_change::<B>(&header) {
+				return Some((number, validator_set))
+			}
+			if number.is_zero() {
+				return None
+			}
+			number -= One::one();
+		}
+	}
+
+	/// Request the justifications of mandatory blocks missing from the archive (e.g. finalized
+	/// before the archive existed) from peers.
+	///
+	/// Walks back one session at a time, starting from the active one, and only while no other
+	/// request is in flight. Responses are archived by [`Self::triage_incoming_justif`]. Sessions
+	/// whose request fails are only retried after a restart.
+	fn backfill_justifs(&mut self) {
+		if self.on_demand_justifications.is_busy() {
+			return
+		}
+
+		loop {
+			let session = match self.backfill_cursor {
+				None => match self.voting_oracle().active_rounds() {
+					Some(rounds) if rounds.mandatory_done() =>
+						Some((rounds.session_start(), rounds.validator_set().clone())),
+					_ => return,
+				},
+				Some(walked) if walked.is_zero() => return,
+				Some(walked) => self.session_at(walked - One::one()),
+			};
+			let (block, validator_set) = match session {
+				Some(session) => session,
+				None => {
+					debug!(target: "beefy", "🥩 Done backfilling justifications.");
+					self.backfill_cursor = Some(Zero::zero());
+					return
+				},
+			};
+			self.backfill_cursor = Some(block);
+
+			match crate::aux_schema::load_justification::<B, _>(&*self.backend, block) {
+				// Already archived, move on to the previous session.
+				Ok(Some(_)) => (),
+				Ok(None) => {
+					debug!(target: "beefy", "🥩 Backfilling missing justification #{:?}.", block);
+					self.on_demand_justifications.request(block, validator_set);
+					return
+				},
+				Err(e) => {
+					debug!(target: "beefy", "🥩 Error {:?} on loading justification.", e);
+					return
+				},
+			}
+		}
+	}
+
 	/// Handle previously buffered justifications and votes that now land in the voting interval.
 	fn try_pending_justif_and_votes(&mut self) -> Result<(), Error> {
 		let best_grandpa = self.best_grandpa_block();
@@ -789,6 +909,9 @@ where
 			if let Some((block, active)) = self.voting_oracle().mandatory_pending() {
 				// This only starts new request if there isn't already an active one.
 				self.on_demand_justifications.request(block, active);
+			} else {
+				// Otherwise, make sure the archive has the mandatory blocks of past sessions.
+				self.backfill_justifs();
 			}
 		}
 	}
@@ -939,6 +1062,7 @@ pub(crate) mod tests {
 	use super::*;
 	use crate::{
 		communication::notification::{BeefyBestBlockStream, BeefyVersionedFinalityProofStream},
+		justification::tests::new_finality_proof,
 		keystore::tests::Keyring,
 		tests::{
 			create_beefy_keystore, get_beefy_streams, make_beefy_ids, two_validators::TestApi,
@@ -1387,7 +1511,91 @@ pub(crate) mod tests {
 
 		// check BEEFY justifications are also appended to backend
 		let justifs = backend.blockchain().justifications(hashof2).unwrap().unwrap();
-		assert!(justifs.get(BEEFY_ENGINE_ID).is_some())
+		assert!(justifs.get(BEEFY_ENGINE_ID).is_some());
+
+		// check BEEFY justifications are archived, also for #1 which had an unknown hash
+		let archived = crate::aux_schema::load_justifications::<Block, _>(&*backend, 1, 2);
+		assert_eq!(archived.unwrap().len(), 2);
+	}
+
+	#[test]
+	fn should_verify_and_archive_justifications_of_concluded_rounds() {
+		let keys = [Keyring::Alice];
+		let validator_set = ValidatorSet::new(make_beefy_ids(&keys), 0).unwrap();
+		let mut net = BeefyTestNet::new(1);
+		let backend = net.peer(0).client().as_backend();
+		let mut worker = create_beefy_worker(&net.peer(0), &keys[0], 1, validator_set.clone());
+		// sessions start at #3 and #6.
+		net.generate_blocks_and_sync(7, 3, &validator_set, false);
+		// replace default session with the one starting at #6.
+		worker.persisted_state.voting_oracle.sessions.clear();
+		worker
+			.persisted_state
+			.voting_oracle
+			.add_session(Rounds::new(6, validator_set.clone()));
+
+		// justification for #4 is dropped by the voter, but archived once verified against the
+		// validator set of the session starting at #3.
+		let justif = new_finality_proof(4, &validator_set, &keys);
+		worker.triage_incoming_justif(justif.clone()).unwrap();
+		assert_eq!(worker.best_beefy_block(), 0);
+		assert_eq!(
+			crate::aux_schema::load_justification::<Block, _>(&*backend, 4).unwrap(),
+			Some(justif),
+		);
+
+		// justification for #5 is not signed by the validator set, it's not archived.
+		let justif = new_finality_proof(5, &validator_set, &[Keyring::Bob]);
+		worker.triage_incoming_justif(justif).unwrap();
+		assert_eq!(crate::aux_schema::load_justification::<Block, _>(&*backend, 5).unwrap(), None);
+
+		// the session of #2 is unknown, so its justification can't be verified nor archived.
+		let justif = new_finality_proof(2, &validator_set, &keys);
+		worker.triage_incoming_justif(justif).unwrap();
+		assert_eq!(crate::aux_schema::load_justification::<Block, _>(&*backend, 2).unwrap(), None);
+	}
+
+	#[test]
+	fn should_backfill_justifications_of_past_sessions() {
+		let keys = [Keyring::Alice];
+		let validator_set = ValidatorSet::new(make_beefy_ids(&keys), 0).unwrap();
+		let mut net = BeefyTestNet::new(1);
+		let backend = net.peer(0).client().as_backend();
+		let mut worker = create_beefy_worker(&net.peer(0), &keys[0], 1, validator_set.clone());
+		// sessions start at #3, #6 and #9.
+		net.generate_blocks_and_sync(10, 3, &validator_set, false);
+		worker.persisted_state.voting_oracle.sessions.clear();
+		worker
+			.persisted_state
+			.voting_oracle
+			.add_session(Rounds::new(9, validator_set.clone()));
+
+		// nothing to backfill while the mandatory block of the active session is pending.
+		worker.backfill_justifs();
+		assert_eq!(worker.backfill_cursor, None);
+
+		// the mandatory block #6 is already archived.
+		let justif = new_finality_proof(6, &validator_set, &keys);
+		crate::aux_schema::write_justification(&*backend, &justif).unwrap();
+		worker
+			.persisted_state
+			.voting_oracle
+			.sessions
+			.front_mut()
+			.unwrap()
+			.test_set_mandatory_done(true);
+
+		// #9 is requested first, then the walk skips #6 and requests #3.
+		worker.backfill_justifs();
+		assert_eq!(worker.backfill_cursor, Some(9));
+		worker.backfill_justifs();
+		assert_eq!(worker.backfill_cursor, Some(3));
+
+		// there is no session change before #3, the walk is done.
+		worker.backfill_justifs();
+		assert_eq!(worker.backfill_cursor, Some(0));
+		worker.backfill_justifs();
+		assert_eq!(worker.backfill_cursor, Some(0));
 	}
 
 	#[test]
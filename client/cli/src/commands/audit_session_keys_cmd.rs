@@ -0,0 +1,187 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	error,
+	params::{BlockNumberOrHash, DatabaseParams, KeystoreParams, PruningParams, SharedParams},
+	CliConfiguration,
+};
+use clap::Parser;
+use parity_scale_codec::Decode;
+use sc_client_api::{HeaderBackend, StorageProvider, UsageProvider};
+use sp_api::ProvideRuntimeApi;
+use sp_core::{
+	crypto::{AccountId32, KeyTypeId, Ss58Codec},
+	storage::StorageKey,
+};
+use sp_keystore::SyncCryptoStorePtr;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+};
+use sp_session::{
+	audit::{
+		audit_session_keys, current_index_storage_key, find_current_keys_block,
+		next_keys_storage_key, DEFAULT_AUDITED_KEY_TYPES,
+	},
+	SessionKeys,
+};
+use std::{fmt::Debug, str::FromStr, sync::Arc};
+
+/// The `audit-session-keys` command used to cross-check the keystore against the session keys
+/// registered on chain for a validator, and the keys it uses in the current session.
+#[derive(Debug, Clone, Parser)]
+pub struct AuditSessionKeysCmd {
+	/// The validator to audit, either as SS58 address or as hex encoded SCALE validator id.
+	#[arg(value_name = "VALIDATOR")]
+	pub validator: String,
+
+	/// Key types to search for orphan keys, in addition to those registered on chain.
+	///
+	/// Defaults to the key types commonly used as session keys.
+	#[arg(long, value_name = "KEY_TYPE", num_args = 1..)]
+	pub key_types: Option<Vec<String>>,
+
+	/// Block hash or number to read the session keys at. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Name of the session pallet in the runtime.
+	#[arg(long, value_name = "NAME", default_value = "Session")]
+	pub pallet: String,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub keystore_params: KeystoreParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl AuditSessionKeysCmd {
+	/// Run the `audit-session-keys` command
+	pub async fn run<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		keystore: SyncCryptoStorePtr,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B> + StorageProvider<B, BA> + HeaderBackend<B> + ProvideRuntimeApi<B>,
+		C::Api: SessionKeys<B>,
+		BA: sc_client_api::backend::Backend<B>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let validator = match AccountId32::from_ss58check(&self.validator) {
+			Ok(account) => AsRef::<[u8]>::as_ref(&account).to_vec(),
+			Err(_) => array_bytes::hex2bytes(&self.validator)
+				.map_err(|_| "Validator must be an SS58 address or hex encoded")?,
+		};
+
+		let key_types = match &self.key_types {
+			Some(key_types) => key_types
+				.iter()
+				.map(|key_type| {
+					KeyTypeId::try_from(key_type.as_str()).map_err(|_| error::Error::KeyTypeInvalid)
+				})
+				.collect::<Result<Vec<_>, _>>()?,
+			None => DEFAULT_AUDITED_KEY_TYPES.to_vec(),
+		};
+
+		let block_id = self.at.as_ref().map(|b| b.parse()).transpose()?;
+		let hash = match block_id {
+			Some(id) => client.expect_block_hash_from_id(&id)?,
+			None => client.usage_info().chain.best_hash,
+		};
+
+		let pallet = self.pallet.as_bytes();
+		let key = StorageKey(next_keys_storage_key(pallet, &validator));
+		let session_keys = |hash: B::Hash| -> error::Result<_> {
+			match client.storage(hash, &key)? {
+				Some(encoded) => Ok(Some(
+					client
+						.runtime_api()
+						.decode_session_keys(&BlockId::Hash(hash), encoded.0)
+						.map_err(|e| error::Error::Application(Box::new(e)))?
+						.ok_or("Session keys stored on chain could not be decoded")?,
+				)),
+				None => Ok(None),
+			}
+		};
+
+		let index_key = StorageKey(current_index_storage_key(pallet));
+		let session_index = |hash: B::Hash| -> error::Result<u32> {
+			match client.storage(hash, &index_key)? {
+				Some(encoded) => Ok(u32::decode(&mut &encoded.0[..])?),
+				None => Ok(0),
+			}
+		};
+
+		// the keys of the current session are searched for on the canonical chain.
+		let number = client.expect_block_number_from_id(&BlockId::Hash(hash))?;
+		let current_keys_block = find_current_keys_block(session_index(hash)?, number, |number| {
+			session_index(client.expect_block_hash_from_id(&BlockId::Number(number))?)
+		})?;
+		let current_keys_hash =
+			client.expect_block_hash_from_id(&BlockId::Number(current_keys_block))?;
+
+		let on_chain = session_keys(hash)?;
+		let current = session_keys(current_keys_hash)?;
+
+		let audit =
+			audit_session_keys(&*keystore, on_chain.as_deref(), current.as_deref(), &key_types);
+		let json = serde_json::to_string_pretty(&audit)
+			.map_err(|e| error::Error::Application(Box::new(e)))?;
+		println!("{}", json);
+
+		if !audit.is_healthy() {
+			return Err("Session keys are not healthy".into())
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for AuditSessionKeysCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn keystore_params(&self) -> Option<&KeystoreParams> {
+		Some(&self.keystore_params)
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -116,6 +116,12 @@ pub struct RunCmd {
 	#[arg(long)]
 	pub rpc_max_subscriptions_per_connection: Option<usize>,
 
+	/// Set the maximum fuel a single `state_call` RPC may consume before it is aborted.
+	/// Limited calls always run in Wasm and require `--wasm-execution compiled`.
+	/// Default is unlimited.
+	#[arg(long)]
+	pub rpc_call_fuel_limit: Option<u64>,
+
 	/// Expose Prometheus exporter on all interfaces.
 	///
 	/// Default is local.
@@ -465,6 +471,10 @@ impl CliConfiguration for RunCmd {
 		Ok(self.rpc_max_subscriptions_per_connection)
 	}
 
+	fn rpc_call_fuel_limit(&self) -> Result<Option<u64>> {
+		Ok(self.rpc_call_fuel_limit)
+	}
+
 	fn ws_max_out_buffer_capacity(&self) -> Result<Option<usize>> {
 		Ok(self.ws_max_out_buffer_capacity)
 	}
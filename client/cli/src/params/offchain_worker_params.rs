@@ -23,9 +23,11 @@
 //! targeted at handling input parameter parsing providing
 //! a reasonable abstraction.
 
+use std::path::PathBuf;
+
 use clap::{ArgAction, Args};
 use sc_network::config::Role;
-use sc_service::config::OffchainWorkerConfig;
+use sc_service::config::{OffchainHttpAllowedHost, OffchainHttpMode, OffchainWorkerConfig};
 
 use crate::{error, OffchainWorkerEnabled};
 
@@ -50,6 +52,23 @@ pub struct OffchainWorkerParams {
 	/// DB during block import.
 	#[arg(long = "enable-offchain-indexing", value_name = "ENABLE_OFFCHAIN_INDEXING", default_value_t = false, action = ArgAction::Set)]
 	pub indexing_enabled: bool,
+
+	/// Only allow offchain workers to send HTTP requests to the given host.
+	///
+	/// Can be passed multiple times. A leading `*.` also allows all subdomains and an optional
+	/// `=REQUESTS_PER_MINUTE` limits the rate of requests to the host. Requests to hosts not on
+	/// the list fail. By default all hosts are allowed.
+	#[arg(long = "offchain-http-allow", value_name = "HOST[=REQUESTS_PER_MINUTE]")]
+	pub http_allow: Vec<OffchainHttpAllowedHost>,
+
+	/// Append all HTTP requests of offchain workers and their responses to the given file.
+	#[arg(long = "offchain-http-record", value_name = "PATH", conflicts_with = "http_replay")]
+	pub http_record: Option<PathBuf>,
+
+	/// Answer HTTP requests of offchain workers from a file written with
+	/// `--offchain-http-record`, without any network access.
+	#[arg(long = "offchain-http-replay", value_name = "PATH")]
+	pub http_replay: Option<PathBuf>,
 }
 
 impl OffchainWorkerParams {
@@ -63,6 +82,13 @@ impl OffchainWorkerParams {
 		};
 
 		let indexing_enabled = self.indexing_enabled;
-		Ok(OffchainWorkerConfig { enabled, indexing_enabled })
+		let http_allow_list = (!self.http_allow.is_empty()).then(|| self.http_allow.clone());
+		let http_mode = match (&self.http_record, &self.http_replay) {
+			(Some(path), _) => OffchainHttpMode::Record(path.clone()),
+			(None, Some(path)) => OffchainHttpMode::Replay(path.clone()),
+			(None, None) => OffchainHttpMode::Live,
+		};
+
+		Ok(OffchainWorkerConfig { enabled, indexing_enabled, http_allow_list, http_mode })
 	}
 }
@@ -0,0 +1,97 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use clap::{Args, ValueEnum};
+use sc_executor::profiler;
+use std::path::PathBuf;
+
+/// The format a profile of the runtime calls is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum WasmProfileFormat {
+	/// Every call with its host functions and peak heap usage as JSON.
+	Json,
+	/// Folded stacks of runtime calls and host functions, as consumed by flamegraph tools.
+	Folded,
+}
+
+/// Parameters for profiling the calls into the runtime.
+#[derive(Debug, Clone, PartialEq, Args)]
+pub struct WasmProfilingParams {
+	/// Write a profile of all calls into the runtime to the given file.
+	///
+	/// The profile contains the number of calls and the time spent per host function as well as
+	/// the peak heap usage of every call. Requires a node compiled with the
+	/// `host-function-profiling` feature.
+	#[arg(long, value_name = "PATH")]
+	pub wasm_profile: Option<PathBuf>,
+
+	/// The format of the file written with `--wasm-profile`.
+	#[arg(long, value_name = "FORMAT", value_enum, default_value_t = WasmProfileFormat::Json)]
+	pub wasm_profile_format: WasmProfileFormat,
+}
+
+impl WasmProfilingParams {
+	/// Start profiling if a profile was requested.
+	///
+	/// Profiling stops when the returned [`WasmProfiling`] is dropped, the profile is only
+	/// written by [`WasmProfiling::finish`].
+	pub fn start(&self) -> error::Result<WasmProfiling<'_>> {
+		if self.wasm_profile.is_some() {
+			profiler::start().map_err(|e| error::Error::Input(format!("--wasm-profile: {}", e)))?;
+		}
+		Ok(WasmProfiling { params: self, recording: self.wasm_profile.is_some() })
+	}
+}
+
+/// A profile of the runtime calls being recorded, see [`WasmProfilingParams::start`].
+#[must_use = "profiling stops when this is dropped"]
+pub struct WasmProfiling<'a> {
+	params: &'a WasmProfilingParams,
+	recording: bool,
+}
+
+impl WasmProfiling<'_> {
+	/// Stop profiling and write the profile to the requested file, if any.
+	pub fn finish(mut self) -> error::Result<()> {
+		let path = match &self.params.wasm_profile {
+			Some(path) if self.recording => path,
+			_ => return Ok(()),
+		};
+
+		self.recording = false;
+		let profile = profiler::finish();
+		let output = match self.params.wasm_profile_format {
+			WasmProfileFormat::Json => profile.to_json(),
+			WasmProfileFormat::Folded => profile.to_folded_stacks(),
+		};
+		std::fs::write(path, output)?;
+		log::info!("Wrote profile of {} runtime calls to {}", profile.calls.len(), path.display());
+
+		Ok(())
+	}
+}
+
+impl Drop for WasmProfiling<'_> {
+	fn drop(&mut self) {
+		if self.recording {
+			profiler::finish();
+		}
+	}
+}
@@ -34,8 +34,13 @@ use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_TRACE}
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::{well_known_cache_keys::Id as CacheKeyId, HeaderBackend};
-use sp_consensus::Error as ConsensusError;
-use sp_consensus_aura::{digests::CompatibleDigestItem, inherents::AuraInherentData, AuraApi};
+use sp_consensus::{
+	equivocation::{EquivocationReport, EquivocationSink},
+	BlockOrigin, Error as ConsensusError,
+};
+use sp_consensus_aura::{
+	digests::CompatibleDigestItem, inherents::AuraInherentData, AuraApi, AURA_ENGINE_ID,
+};
 use sp_consensus_slots::Slot;
 use sp_core::{crypto::Pair, ExecutionContext};
 use sp_inherents::{CreateInherentDataProviders, InherentDataProvider as _};
@@ -51,6 +56,8 @@ use std::{fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc};
 /// containing the seal.
 ///
 /// This digest item will always return `Some` when used with `as_aura_seal`.
+///
+/// Equivocations found while checking are sent to `equivocation_sink`, if any.
 fn check_header<C, B: BlockT, P: Pair>(
 	client: &C,
 	slot_now: Slot,
@@ -58,6 +65,7 @@ fn check_header<C, B: BlockT, P: Pair>(
 	hash: B::Hash,
 	authorities: &[AuthorityId<P>],
 	check_for_equivocation: CheckForEquivocation,
+	equivocation_sink: Option<&EquivocationSink>,
 ) -> Result<CheckedHeader<B::Header, (Slot, DigestItem)>, Error<B>>
 where
 	P::Signature: Codec,
@@ -94,6 +102,15 @@ where
 						equivocation_proof.first_header.hash(),
 						equivocation_proof.second_header.hash(),
 					);
+
+					if let Some(sink) = equivocation_sink {
+						let offence = (&equivocation_proof.offender, equivocation_proof.slot);
+						sink.report(EquivocationReport::new(
+							AURA_ENGINE_ID,
+							offence,
+							&equivocation_proof,
+						));
+					}
 				}
 			}
 
@@ -108,8 +125,9 @@ where
 pub struct AuraVerifier<C, P, CIDP, N> {
 	client: Arc<C>,
 	phantom: PhantomData<P>,
-	create_inherent_data_providers: CIDP,
+	create_inherent_data_providers: Arc<CIDP>,
 	check_for_equivocation: CheckForEquivocation,
+	equivocation_reporting: Option<EquivocationSink>,
 	telemetry: Option<TelemetryHandle>,
 	compatibility_mode: CompatibilityMode<N>,
 }
@@ -119,13 +137,15 @@ impl<C, P, CIDP, N> AuraVerifier<C, P, CIDP, N> {
 		client: Arc<C>,
 		create_inherent_data_providers: CIDP,
 		check_for_equivocation: CheckForEquivocation,
+		equivocation_reporting: Option<EquivocationSink>,
 		telemetry: Option<TelemetryHandle>,
 		compatibility_mode: CompatibilityMode<N>,
 	) -> Self {
 		Self {
 			client,
-			create_inherent_data_providers,
+			create_inherent_data_providers: Arc::new(create_inherent_data_providers),
 			check_for_equivocation,
+			equivocation_reporting,
 			telemetry,
 			compatibility_mode,
 			phantom: PhantomData,
@@ -133,6 +153,20 @@ impl<C, P, CIDP, N> AuraVerifier<C, P, CIDP, N> {
 	}
 }
 
+impl<C, P, CIDP, N: Clone> Clone for AuraVerifier<C, P, CIDP, N> {
+	fn clone(&self) -> Self {
+		Self {
+			client: self.client.clone(),
+			phantom: PhantomData,
+			create_inherent_data_providers: self.create_inherent_data_providers.clone(),
+			check_for_equivocation: self.check_for_equivocation,
+			equivocation_reporting: self.equivocation_reporting.clone(),
+			telemetry: self.telemetry.clone(),
+			compatibility_mode: self.compatibility_mode.clone(),
+		}
+	}
+}
+
 impl<C, P, CIDP, N> AuraVerifier<C, P, CIDP, N>
 where
 	P: Send + Sync + 'static,
@@ -207,6 +241,13 @@ where
 
 		let slot_now = create_inherent_data_providers.slot();
 
+		// don't report any equivocations during initial sync
+		// as they are most likely stale.
+		let equivocation_sink = self
+			.equivocation_reporting
+			.as_ref()
+			.filter(|_| block.origin != BlockOrigin::NetworkInitialSync);
+
 		// we add one to allow for some small drift.
 		// FIXME #1019 in the future, alter this queue to allow deferring of
 		// headers
@@ -217,6 +258,7 @@ where
 			hash,
 			&authorities[..],
 			self.check_for_equivocation,
+			equivocation_sink,
 		)
 		.map_err(|e| e.to_string())?;
 		match checked_header {
@@ -326,12 +368,20 @@ pub struct ImportQueueParams<'a, Block: BlockT, I, C, S, CIDP> {
 	pub registry: Option<&'a Registry>,
 	/// Should we check for equivocation?
 	pub check_for_equivocation: CheckForEquivocation,
+	/// Where to report the equivocations found when checking for equivocation.
+	///
+	/// If `None`, they are only logged.
+	pub equivocation_reporting: Option<EquivocationSink>,
 	/// Telemetry instance used to report telemetry metrics.
 	pub telemetry: Option<TelemetryHandle>,
 	/// Compatibility mode that should be used.
 	///
 	/// If in doubt, use `Default::default()`.
 	pub compatibility_mode: CompatibilityMode<NumberFor<Block>>,
+	/// Number of threads verifying blocks ahead of their import.
+	///
+	/// With `0`, blocks are verified one by one when they are imported.
+	pub verification_workers: usize,
 }
 
 /// Start an import queue for the Aura consensus algorithm.
@@ -344,8 +394,10 @@ pub fn import_queue<P, Block, I, C, S, CIDP>(
 		spawner,
 		registry,
 		check_for_equivocation,
+		equivocation_reporting,
 		telemetry,
 		compatibility_mode,
+		verification_workers,
 	}: ImportQueueParams<Block, I, C, S, CIDP>,
 ) -> Result<DefaultImportQueue<Block, C>, sp_consensus::Error>
 where
@@ -374,11 +426,29 @@ where
 		client,
 		create_inherent_data_providers,
 		check_for_equivocation,
+		equivocation_reporting,
 		telemetry,
 		compatibility_mode,
 	});
 
-	Ok(BasicQueue::new(verifier, Box::new(block_import), justification_import, spawner, registry))
+	if verification_workers == 0 {
+		return Ok(BasicQueue::new(
+			verifier,
+			Box::new(block_import),
+			justification_import,
+			spawner,
+			registry,
+		))
+	}
+
+	Ok(BasicQueue::new_with_parallel_verification(
+		verifier,
+		verification_workers,
+		Box::new(block_import),
+		justification_import,
+		spawner,
+		registry,
+	))
 }
 
 /// Parameters of [`build_verifier`].
@@ -389,6 +459,10 @@ pub struct BuildVerifierParams<C, CIDP, N> {
 	pub create_inherent_data_providers: CIDP,
 	/// Should we check for equivocation?
 	pub check_for_equivocation: CheckForEquivocation,
+	/// Where to report the equivocations found when checking for equivocation.
+	///
+	/// If `None`, they are only logged.
+	pub equivocation_reporting: Option<EquivocationSink>,
 	/// Telemetry instance used to report telemetry metrics.
 	pub telemetry: Option<TelemetryHandle>,
 	/// Compatibility mode that should be used.
@@ -403,6 +477,7 @@ pub fn build_verifier<P, C, CIDP, N>(
 		client,
 		create_inherent_data_providers,
 		check_for_equivocation,
+		equivocation_reporting,
 		telemetry,
 		compatibility_mode,
 	}: BuildVerifierParams<C, CIDP, N>,
@@ -411,6 +486,7 @@ pub fn build_verifier<P, C, CIDP, N>(
 		client,
 		create_inherent_data_providers,
 		check_for_equivocation,
+		equivocation_reporting,
 		telemetry,
 		compatibility_mode,
 	)
@@ -58,8 +58,10 @@ use sp_runtime::{
 	DigestItem,
 };
 
+mod equivocation;
 mod import_queue;
 
+pub use equivocation::AuraEquivocationSubmitter;
 pub use import_queue::{
 	build_verifier, import_queue, AuraVerifier, BuildVerifierParams, CheckForEquivocation,
 	ImportQueueParams,
@@ -69,7 +71,7 @@ pub use sp_consensus::SyncOracle;
 pub use sp_consensus_aura::{
 	digests::CompatibleDigestItem,
 	inherents::{InherentDataProvider, InherentType as AuraInherent, INHERENT_IDENTIFIER},
-	AuraApi, ConsensusLog, SlotDuration, AURA_ENGINE_ID,
+	AuraApi, AuraEquivocationApi, ConsensusLog, SlotDuration, AURA_ENGINE_ID,
 };
 
 type AuthorityId<P> = <P as Pair>::Public;
@@ -638,7 +640,10 @@ mod tests {
 	use sc_block_builder::BlockBuilderProvider;
 	use sc_client_api::BlockchainEvents;
 	use sc_consensus::BoxJustificationImport;
-	use sc_consensus_slots::{BackoffAuthoringOnFinalizedHeadLagging, SimpleSlotWorker};
+	use sc_consensus_slots::{
+		simulation::{SimulatedAuthority, Simulation},
+		BackoffAuthoringOnFinalizedHeadLagging, SimpleSlotWorker,
+	};
 	use sc_keystore::LocalKeystore;
 	use sc_network_test::{Block as TestBlock, *};
 	use sp_application_crypto::key_types::AURA;
@@ -742,6 +747,7 @@ mod tests {
 				}),
 				CheckForEquivocation::Yes,
 				None,
+				None,
 				CompatibilityMode::None,
 			)
 		}
@@ -970,4 +976,160 @@ mod tests {
 		// The returned block should be imported and we should be able to get its header by now.
 		assert!(client.header(&BlockId::Hash(res.block.hash())).unwrap().is_some());
 	}
+
+	/// Proposer factory recording the time given to each proposal.
+	struct RecordingFactory(Arc<TestClient>, Arc<Mutex<Vec<Duration>>>);
+	struct RecordingProposer(DummyProposer, Arc<Mutex<Vec<Duration>>>);
+
+	impl Environment<TestBlock> for RecordingFactory {
+		type Proposer = RecordingProposer;
+		type CreateProposer = futures::future::Ready<Result<RecordingProposer, Error>>;
+		type Error = Error;
+
+		fn init(&mut self, parent_header: &<TestBlock as BlockT>::Header) -> Self::CreateProposer {
+			let proposer = DummyProposer(parent_header.number + 1, self.0.clone());
+			futures::future::ready(Ok(RecordingProposer(proposer, self.1.clone())))
+		}
+	}
+
+	impl Proposer<TestBlock> for RecordingProposer {
+		type Error = Error;
+		type Transaction = <DummyProposer as Proposer<TestBlock>>::Transaction;
+		type Proposal = <DummyProposer as Proposer<TestBlock>>::Proposal;
+		type ProofRecording = DisableProofRecording;
+		type Proof = ();
+
+		fn propose(
+			self,
+			inherent_data: InherentData,
+			digests: Digest,
+			max_duration: Duration,
+			block_size_limit: Option<usize>,
+		) -> Self::Proposal {
+			self.1.lock().push(max_duration);
+			self.0.propose(inherent_data, digests, max_duration, block_size_limit)
+		}
+	}
+
+	type SimulatedAuraWorker = SimpleSlotWorkerToSlotWorker<
+		AuraWorker<
+			TestClient,
+			RecordingFactory,
+			Arc<TestClient>,
+			AuthorityPair,
+			DummyOracle,
+			(),
+			(),
+			u64,
+		>,
+	>;
+
+	/// An Aura authority with its own client, importing the blocks of the others.
+	struct SimulatedAura {
+		worker: SimulatedAuraWorker,
+		client: Arc<TestClient>,
+	}
+
+	impl SimulatedAura {
+		fn new(key: Keyring, proposals: Arc<Mutex<Vec<Duration>>>) -> Self {
+			let client = Arc::new(substrate_test_runtime_client::new());
+			let keystore = LocalKeystore::in_memory();
+			SyncCryptoStore::sr25519_generate_new(
+				&keystore,
+				AuthorityPair::ID,
+				Some(&key.to_seed()),
+			)
+			.expect("Key should be created");
+
+			let worker = AuraWorker {
+				client: client.clone(),
+				block_import: client.clone(),
+				env: RecordingFactory(client.clone(), proposals),
+				keystore: keystore.into(),
+				sync_oracle: DummyOracle,
+				justification_sync_link: (),
+				force_authoring: false,
+				backoff_authoring_blocks: Option::<()>::None,
+				telemetry: None,
+				_key_type: PhantomData::<AuthorityPair>,
+				block_proposal_slot_portion: SlotProportion::new(0.5),
+				max_block_proposal_slot_portion: None,
+				compatibility_mode: Default::default(),
+			};
+
+			SimulatedAura { worker: SimpleSlotWorkerToSlotWorker(worker), client }
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl SimulatedAuthority<TestBlock> for SimulatedAura {
+		type Proof = ();
+		type Worker = SimulatedAuraWorker;
+
+		fn worker(&mut self) -> &mut SimulatedAuraWorker {
+			&mut self.worker
+		}
+
+		async fn best_header(&mut self) -> Header {
+			let best_hash = self.client.chain_info().best_hash;
+			self.client.header(&BlockId::Hash(best_hash)).unwrap().unwrap()
+		}
+
+		fn inherent_data(&mut self, _slot: Slot, _timestamp: u64) -> InherentData {
+			InherentData::new()
+		}
+
+		async fn import_block(&mut self, block: TestBlock) {
+			let (mut header, extrinsics) = block.deconstruct();
+			let seal = header.digest_mut().pop().expect("Authored blocks are sealed");
+
+			let mut import = BlockImportParams::new(BlockOrigin::NetworkBroadcast, header);
+			import.post_digests.push(seal);
+			import.body = Some(extrinsics);
+			import.fork_choice = Some(ForkChoiceStrategy::LongestChain);
+
+			self.client
+				.import_block(import, Default::default())
+				.await
+				.expect("Imports block");
+		}
+
+		fn finalized_number(&self) -> u64 {
+			self.client.chain_info().finalized_number
+		}
+	}
+
+	#[test]
+	fn simulated_authorities_author_in_turn_with_slot_lenience() {
+		let proposals = Arc::new(Mutex::new(Vec::new()));
+		let slot_duration = SlotDuration::from_millis(SLOT_DURATION_MS);
+		let slots = |n: u64| Duration::from_millis(SLOT_DURATION_MS * n);
+
+		// start on a slot boundary, so the first slot is `1_000`.
+		let mut simulation = Simulation::new(slot_duration, slots(1_000));
+		for key in [Keyring::Alice, Keyring::Bob, Keyring::Charlie] {
+			simulation.add_authority(SimulatedAura::new(key, proposals.clone()), 0);
+		}
+
+		// every slot is claimed by the authority in turn, on top of the previous block.
+		executor::block_on(simulation.run_for(slots(6)));
+		let authored = simulation.authored_blocks();
+		assert_eq!(authored.len(), 7);
+		assert!(authored.iter().enumerate().all(|(i, block)| block.number == i as u64 + 1));
+		assert!(authored.iter().all(|block| *block.slot % 3 == block.authority as u64));
+		assert_eq!(simulation.leaves().len(), 1);
+
+		// with no slot skipped, proposing gets its portion of the slot.
+		let portion = Duration::from_millis(SLOT_DURATION_MS / 2);
+		assert_eq!(*proposals.lock(), vec![portion.mul_f32(0.98); 7]);
+		proposals.lock().clear();
+
+		// Charlie misses slot `1_007`, so Alice gets an exponential lenience of one slot,
+		// weighted by the same portion, to propose in slot `1_008`.
+		simulation.set_online(2, false);
+		executor::block_on(simulation.run_for(slots(2)));
+		assert_eq!(simulation.authored_blocks().len(), 8);
+		assert_eq!(simulation.leaves().len(), 1);
+		assert_eq!(*proposals.lock(), vec![(portion * 2).mul_f32(0.98)]);
+	}
 }
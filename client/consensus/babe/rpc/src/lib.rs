@@ -25,8 +25,11 @@ use jsonrpsee::{
 	types::{error::CallError, ErrorObject},
 };
 
-use sc_consensus_babe::{authorship, Epoch};
-use sc_consensus_epochs::{descendent_query, Epoch as EpochT, SharedEpochChanges};
+use sc_consensus_babe::{authorship, find_pre_digest, Epoch};
+use sc_consensus_epochs::{
+	descendent_query, Epoch as EpochT, EpochIdentifier, EpochIdentifierPosition,
+	IsDescendentOfBuilder, PersistedEpochHeader, SharedEpochChanges,
+};
 use sc_rpc_api::DenyUnsafe;
 use serde::{Deserialize, Serialize};
 use sp_api::{BlockId, ProvideRuntimeApi};
@@ -34,20 +37,42 @@ use sp_application_crypto::AppKey;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_consensus::{Error as ConsensusError, SelectChain};
 use sp_consensus_babe::{
-	digests::PreDigest, AuthorityId, BabeApi as BabeRuntimeApi, BabeConfiguration,
+	digests::PreDigest, AuthorityId, BabeApi as BabeRuntimeApi, BabeAuthorityWeight,
+	BabeConfiguration, BabeEpochConfiguration,
 };
-use sp_core::crypto::ByteArray;
+use sp_core::{crypto::ByteArray, Bytes};
 use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
-use sp_runtime::traits::{Block as BlockT, Header as _};
+use sp_runtime::traits::{Block as BlockT, Header as _, NumberFor, One};
 use std::{collections::HashMap, sync::Arc};
 
+/// Maximum number of entries returned by `babe_slotClaims`.
+const MAX_SLOT_CLAIMS: usize = 4096;
+
 /// Provides rpc methods for interacting with Babe.
+///
+/// The api is generic over the block hash and number types used by `babe_epoch`,
+/// `babe_epochChanges` and `babe_slotClaims`. Users of the generated `BabeApiClient` must now
+/// specify them, e.g. `BabeApiClient::<Hash, BlockNumber>::epoch_authorship(&client)`, while
+/// `Babe::into_rpc` infers them from the block type.
 #[rpc(client, server)]
-pub trait BabeApi {
+pub trait BabeApi<Hash, Number> {
 	/// Returns data about which slots (primary or secondary) can be claimed in the current epoch
 	/// with the keys in the keystore.
 	#[method(name = "babe_epochAuthorship")]
 	async fn epoch_authorship(&self) -> RpcResult<HashMap<AuthorityId, EpochAuthorship>>;
+
+	/// Returns the epoch the given block (or the best block) belongs to, as tracked by the node.
+	#[method(name = "babe_epoch")]
+	async fn epoch(&self, at: Option<Hash>) -> RpcResult<EpochInfo>;
+
+	/// Returns the nodes of the epoch changes fork tree, in pre-order.
+	#[method(name = "babe_epochChanges")]
+	async fn epoch_changes(&self) -> RpcResult<Vec<EpochChangesNode<Hash, Number>>>;
+
+	/// Returns the outcome of every slot spanned by the best chain blocks in the inclusive range
+	/// `[from, to]`, including the empty slots in between.
+	#[method(name = "babe_slotClaims")]
+	async fn slot_claims(&self, from: Number, to: Number) -> RpcResult<Vec<SlotClaim<Hash>>>;
 }
 
 /// Provides RPC methods for interacting with Babe.
@@ -81,7 +106,7 @@ impl<B: BlockT, C, SC> Babe<B, C, SC> {
 }
 
 #[async_trait]
-impl<B: BlockT, C, SC> BabeApiServer for Babe<B, C, SC>
+impl<B: BlockT, C, SC> BabeApiServer<B::Hash, NumberFor<B>> for Babe<B, C, SC>
 where
 	B: BlockT,
 	C: ProvideRuntimeApi<B>
@@ -105,9 +130,8 @@ where
 			&self.client,
 			&self.babe_config,
 			*epoch_start,
-			&self.select_chain,
-		)
-		.await?;
+			&header,
+		)?;
 		let (epoch_start, epoch_end) = (epoch.start_slot(), epoch.end_slot());
 		let mut claims: HashMap<AuthorityId, EpochAuthorship> = HashMap::new();
 
@@ -149,6 +173,137 @@ where
 
 		Ok(claims)
 	}
+
+	async fn epoch(&self, at: Option<B::Hash>) -> RpcResult<EpochInfo> {
+		self.deny_unsafe.check_if_safe()?;
+		let header = match at {
+			Some(hash) => self
+				.client
+				.header(BlockId::Hash(hash))
+				.map_err(|e| Error::StringError(e.to_string()))?
+				.ok_or_else(|| Error::StringError(format!("Unknown block: {:?}", hash)))?,
+			None => self.select_chain.best_chain().map_err(Error::Consensus).await?,
+		};
+		// The start slot of the epoch as seen by the runtime, a child of `header` in that slot
+		// belongs to the same epoch as `header`.
+		let epoch_start = self
+			.client
+			.runtime_api()
+			.current_epoch_start(&BlockId::Hash(header.hash()))
+			.map_err(|err| Error::StringError(format!("{:?}", err)))?;
+
+		epoch_data(
+			&self.shared_epoch_changes,
+			&self.client,
+			&self.babe_config,
+			*epoch_start,
+			&header,
+		)
+		.map(|epoch| EpochInfo::from(&epoch))
+		.map_err(Into::into)
+	}
+
+	async fn epoch_changes(&self) -> RpcResult<Vec<EpochChangesNode<B::Hash, NumberFor<B>>>> {
+		self.deny_unsafe.check_if_safe()?;
+		let epoch_changes = self.shared_epoch_changes.shared_data();
+		let is_descendent_of = descendent_query(&*self.client).build_is_descendent_of(None);
+
+		let mut nodes = Vec::new();
+		// Ancestors of the current node, pre-order visits them before their descendents.
+		let mut ancestors: Vec<B::Hash> = Vec::new();
+		for (hash, number, header) in epoch_changes.tree().iter() {
+			while let Some(ancestor) = ancestors.last() {
+				if is_descendent_of(ancestor, hash)
+					.map_err(|e| Error::StringError(e.to_string()))?
+				{
+					break
+				}
+				ancestors.pop();
+			}
+
+			let positions: &[_] = match header {
+				PersistedEpochHeader::Genesis(_, _) =>
+					&[EpochIdentifierPosition::Genesis0, EpochIdentifierPosition::Genesis1],
+				PersistedEpochHeader::Regular(_) => &[EpochIdentifierPosition::Regular],
+			};
+			let epochs = positions
+				.iter()
+				.filter_map(|position| {
+					epoch_changes.epoch(&EpochIdentifier {
+						position: *position,
+						hash: *hash,
+						number: *number,
+					})
+				})
+				.map(EpochInfo::from)
+				.collect();
+
+			nodes.push(EpochChangesNode {
+				hash: *hash,
+				number: *number,
+				parent: ancestors.last().cloned(),
+				epochs,
+			});
+			ancestors.push(*hash);
+		}
+
+		Ok(nodes)
+	}
+
+	async fn slot_claims(
+		&self,
+		from: NumberFor<B>,
+		to: NumberFor<B>,
+	) -> RpcResult<Vec<SlotClaim<B::Hash>>> {
+		self.deny_unsafe.check_if_safe()?;
+		if from > to {
+			return Err(Error::StringError("Invalid block range".into()).into())
+		}
+
+		// the genesis block doesn't claim any slot.
+		let mut number = from.max(One::one());
+		let mut claims: Vec<SlotClaim<B::Hash>> = Vec::new();
+		while number <= to {
+			let hash =
+				match self.client.hash(number).map_err(|e| Error::StringError(e.to_string()))? {
+					Some(hash) => hash,
+					// reached the tip of the best chain.
+					None => break,
+				};
+			let header = self
+				.client
+				.header(BlockId::Hash(hash))
+				.map_err(|e| Error::StringError(e.to_string()))?
+				.ok_or_else(|| Error::StringError(format!("Missing header: {:?}", hash)))?;
+			let pre_digest =
+				find_pre_digest::<B>(&header).map_err(|e| Error::StringError(e.to_string()))?;
+			let slot = *pre_digest.slot();
+
+			let empty_from = claims.last().map_or(slot, |last| last.slot + 1);
+			if claims.len() as u64 + slot.saturating_sub(empty_from) >= MAX_SLOT_CLAIMS as u64 {
+				return Err(Error::StringError(format!(
+					"Block range spans more than {} slots",
+					MAX_SLOT_CLAIMS
+				))
+				.into())
+			}
+			claims.extend((empty_from..slot).map(|slot| SlotClaim {
+				slot,
+				outcome: SlotOutcome::Empty,
+				authority_index: None,
+				block: None,
+			}));
+			claims.push(SlotClaim {
+				slot,
+				outcome: SlotOutcome::from(&pre_digest),
+				authority_index: Some(pre_digest.authority_index()),
+				block: Some(header.hash()),
+			});
+			number += One::one();
+		}
+
+		Ok(claims)
+	}
 }
 
 /// Holds information about the `slot`'s that can be claimed by a given key.
@@ -162,6 +317,86 @@ pub struct EpochAuthorship {
 	secondary_vrf: Vec<u64>,
 }
 
+/// The full data of a BABE epoch.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EpochInfo {
+	/// The epoch index.
+	pub epoch_index: u64,
+	/// The starting slot of the epoch.
+	pub start_slot: u64,
+	/// The duration of the epoch in slots.
+	pub duration: u64,
+	/// The authorities and their weights.
+	pub authorities: Vec<(AuthorityId, BabeAuthorityWeight)>,
+	/// The randomness of the epoch.
+	pub randomness: Bytes,
+	/// The configuration of the epoch.
+	pub config: BabeEpochConfiguration,
+}
+
+impl From<&Epoch> for EpochInfo {
+	fn from(epoch: &Epoch) -> Self {
+		EpochInfo {
+			epoch_index: epoch.epoch_index,
+			start_slot: *epoch.start_slot,
+			duration: epoch.duration,
+			authorities: epoch.authorities.clone(),
+			randomness: epoch.randomness.to_vec().into(),
+			config: epoch.config.clone(),
+		}
+	}
+}
+
+/// A node of the epoch changes fork tree.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EpochChangesNode<Hash, Number> {
+	/// Hash of the block signaling the epoch(s).
+	pub hash: Hash,
+	/// Number of the block signaling the epoch(s).
+	pub number: Number,
+	/// Hash of the parent node in the tree, if any.
+	pub parent: Option<Hash>,
+	/// The signaled epochs, the genesis node holds the first two epochs.
+	pub epochs: Vec<EpochInfo>,
+}
+
+/// The outcome of a slot.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotOutcome {
+	/// The slot was claimed by a primary VRF-based slot assignment.
+	Primary,
+	/// The slot was claimed by a secondary deterministic slot assignment.
+	Secondary,
+	/// The slot was claimed by a secondary deterministic slot assignment with a VRF output.
+	SecondaryVrf,
+	/// No block was authored in the slot.
+	Empty,
+}
+
+impl From<&PreDigest> for SlotOutcome {
+	fn from(pre_digest: &PreDigest) -> Self {
+		match pre_digest {
+			PreDigest::Primary(_) => SlotOutcome::Primary,
+			PreDigest::SecondaryPlain(_) => SlotOutcome::Secondary,
+			PreDigest::SecondaryVRF(_) => SlotOutcome::SecondaryVrf,
+		}
+	}
+}
+
+/// The claim of a slot by a block on the best chain.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SlotClaim<Hash> {
+	/// The slot number.
+	pub slot: u64,
+	/// How the slot was claimed, if it was.
+	pub outcome: SlotOutcome,
+	/// The index of the authority that claimed the slot.
+	pub authority_index: Option<u32>,
+	/// The block authored in the slot.
+	pub block: Option<Hash>,
+}
+
 /// Errors encountered by the RPC
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -183,20 +418,18 @@ impl From<Error> for JsonRpseeError {
 	}
 }
 
-/// Fetches the epoch data for a given slot.
-async fn epoch_data<B, C, SC>(
+/// Fetches the epoch data for a given slot, for a child of `parent`.
+fn epoch_data<B, C>(
 	epoch_changes: &SharedEpochChanges<B, Epoch>,
 	client: &Arc<C>,
 	babe_config: &BabeConfiguration,
 	slot: u64,
-	select_chain: &SC,
+	parent: &B::Header,
 ) -> Result<Epoch, Error>
 where
 	B: BlockT,
 	C: HeaderBackend<B> + HeaderMetadata<B, Error = BlockChainError> + 'static,
-	SC: SelectChain<B>,
 {
-	let parent = select_chain.best_chain().await?;
 	epoch_changes
 		.shared_data()
 		.epoch_data_for_child_of(
@@ -223,8 +456,13 @@ mod tests {
 		TestClientBuilderExt,
 	};
 
+	use sc_block_builder::BlockBuilderProvider;
 	use sc_consensus_babe::{block_import, AuthorityPair};
+	use sp_consensus::BlockOrigin;
+	use sp_consensus_babe::digests::CompatibleDigestItem;
+	use sp_runtime::{Digest, DigestItem};
 	use std::sync::Arc;
+	use substrate_test_runtime_client::ClientBlockImportExt;
 
 	/// creates keystore backed by a temp file
 	fn create_temp_keystore<P: AppPair>(
@@ -267,6 +505,108 @@ mod tests {
 		assert_eq!(&response.result, expected);
 	}
 
+	#[tokio::test]
+	async fn epoch_works() {
+		let babe_rpc = test_babe_rpc_module(DenyUnsafe::No);
+		let authorities = babe_rpc.babe_config.authorities.clone();
+		let api = babe_rpc.into_rpc();
+
+		let request = r#"{"jsonrpc":"2.0","method":"babe_epoch","params":[],"id":1}"#;
+		let (response, _) = api.raw_json_request(request).await.unwrap();
+		let response: serde_json::Value = serde_json::from_str(&response.result).unwrap();
+		let epoch: EpochInfo = serde_json::from_value(response["result"].clone()).unwrap();
+
+		assert_eq!(epoch.epoch_index, 0);
+		assert_eq!(epoch.start_slot, 0);
+		assert_eq!(epoch.authorities, authorities);
+	}
+
+	#[tokio::test]
+	async fn epoch_changes_and_slot_claims_on_genesis() {
+		let babe_rpc = test_babe_rpc_module(DenyUnsafe::No);
+		let api = babe_rpc.into_rpc();
+
+		// no epoch has been signaled yet.
+		let request = r#"{"jsonrpc":"2.0","method":"babe_epochChanges","params":[],"id":1}"#;
+		let (response, _) = api.raw_json_request(request).await.unwrap();
+		assert_eq!(&response.result, r#"{"jsonrpc":"2.0","result":[],"id":1}"#);
+
+		// the genesis block doesn't claim a slot.
+		let request = r#"{"jsonrpc":"2.0","method":"babe_slotClaims","params":[0,10],"id":1}"#;
+		let (response, _) = api.raw_json_request(request).await.unwrap();
+		assert_eq!(&response.result, r#"{"jsonrpc":"2.0","result":[],"id":1}"#);
+
+		let request = r#"{"jsonrpc":"2.0","method":"babe_slotClaims","params":[10,0],"id":1}"#;
+		let (response, _) = api.raw_json_request(request).await.unwrap();
+		let expected = r#"{"jsonrpc":"2.0","error":{"code":1234,"message":"Invalid block range"},"id":1}"#;
+		assert_eq!(&response.result, expected);
+	}
+
+	#[tokio::test]
+	async fn slot_claims_on_authored_blocks() {
+		let babe_rpc = test_babe_rpc_module(DenyUnsafe::No);
+		let mut client = babe_rpc.client.clone();
+		let keystore = babe_rpc.keystore.clone();
+		let epoch = Epoch::genesis(&babe_rpc.babe_config, 0.into());
+
+		// Alice claims slot 0 as primary and slots 1 and 4 as secondary, see
+		// `epoch_authorship_works`. Slots 2 and 3 are left empty.
+		let mut parent = client.chain_info().genesis_hash;
+		let mut hashes = Vec::new();
+		for slot in [0u64, 1, 4] {
+			let (pre_digest, _) = authorship::claim_slot(slot.into(), &epoch, &keystore)
+				.expect("Alice can claim the slot");
+			let digest = Digest { logs: vec![DigestItem::babe_pre_digest(pre_digest)] };
+			let block = client
+				.new_block_at(&BlockId::Hash(parent), digest, false)
+				.unwrap()
+				.build()
+				.unwrap()
+				.block;
+			parent = block.hash();
+			hashes.push(parent);
+			client.import(BlockOrigin::Own, block).await.unwrap();
+		}
+
+		let api = babe_rpc.into_rpc();
+		let request = r#"{"jsonrpc":"2.0","method":"babe_slotClaims","params":[0,10],"id":1}"#;
+		let (response, _) = api.raw_json_request(request).await.unwrap();
+		let response: serde_json::Value = serde_json::from_str(&response.result).unwrap();
+		let claims: Vec<SlotClaim<<Block as BlockT>::Hash>> =
+			serde_json::from_value(response["result"].clone()).unwrap();
+
+		let claimed = |slot, outcome, block| SlotClaim {
+			slot,
+			outcome,
+			authority_index: Some(0),
+			block: Some(block),
+		};
+		let empty = |slot| SlotClaim {
+			slot,
+			outcome: SlotOutcome::Empty,
+			authority_index: None,
+			block: None,
+		};
+		assert_eq!(
+			claims,
+			vec![
+				claimed(0, SlotOutcome::Primary, hashes[0]),
+				claimed(1, SlotOutcome::Secondary, hashes[1]),
+				empty(2),
+				empty(3),
+				claimed(4, SlotOutcome::Secondary, hashes[2]),
+			],
+		);
+
+		// only the range of blocks that is asked for is reported.
+		let request = r#"{"jsonrpc":"2.0","method":"babe_slotClaims","params":[3,3],"id":1}"#;
+		let (response, _) = api.raw_json_request(request).await.unwrap();
+		let response: serde_json::Value = serde_json::from_str(&response.result).unwrap();
+		let claims: Vec<SlotClaim<<Block as BlockT>::Hash>> =
+			serde_json::from_value(response["result"].clone()).unwrap();
+		assert_eq!(claims, vec![claimed(4, SlotOutcome::Secondary, hashes[2])]);
+	}
+
 	#[tokio::test]
 	async fn epoch_authorship_is_unsafe() {
 		let babe_rpc = test_babe_rpc_module(DenyUnsafe::Yes);
@@ -278,4 +618,20 @@ mod tests {
 
 		assert_eq!(&response.result, expected);
 	}
+
+	#[tokio::test]
+	async fn epoch_and_slot_claims_are_unsafe() {
+		let babe_rpc = test_babe_rpc_module(DenyUnsafe::Yes);
+		let api = babe_rpc.into_rpc();
+
+		let expected = r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"RPC call is unsafe to be called externally"},"id":1}"#;
+		for request in [
+			r#"{"jsonrpc":"2.0","method":"babe_epoch","params":[],"id":1}"#,
+			r#"{"jsonrpc":"2.0","method":"babe_epochChanges","params":[],"id":1}"#,
+			r#"{"jsonrpc":"2.0","method":"babe_slotClaims","params":[0,10],"id":1}"#,
+		] {
+			let (response, _) = api.raw_json_request(request).await.unwrap();
+			assert_eq!(&response.result, expected);
+		}
+	}
 }
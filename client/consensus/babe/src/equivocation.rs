@@ -0,0 +1,109 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Submission of BABE equivocation reports to the runtime.
+
+use codec::Decode;
+use log::{debug, info};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::{equivocation::SubmitEquivocation, Error as ConsensusError};
+use sp_consensus_babe::{BabeApi, EquivocationProof};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header},
+};
+use std::{marker::PhantomData, sync::Arc};
+
+/// Submits the BABE equivocation proofs sent by the [`BabeVerifier`](crate::BabeVerifier) as
+/// unsigned `report_equivocation_unsigned` extrinsics, through the [`BabeApi`].
+///
+/// Register it for [`BABE_ENGINE_ID`](crate::BABE_ENGINE_ID) with
+/// [`EquivocationReporting::with_submitter`](sp_consensus::equivocation::EquivocationReporting::with_submitter).
+pub struct BabeEquivocationSubmitter<B, C> {
+	client: Arc<C>,
+	_phantom: PhantomData<B>,
+}
+
+impl<B, C> BabeEquivocationSubmitter<B, C> {
+	/// Create a new submitter using the runtime of `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _phantom: PhantomData }
+	}
+}
+
+impl<B, C> SubmitEquivocation for BabeEquivocationSubmitter<B, C>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync,
+	C::Api: BabeApi<B>,
+{
+	fn submit(&mut self, proof: &[u8]) -> Result<(), ConsensusError> {
+		let equivocation_proof = EquivocationProof::<B::Header>::decode(&mut &proof[..])
+			.map_err(|e| ConsensusError::Other(Box::new(e)))?;
+		let offender = equivocation_proof.offender.clone();
+		let slot = equivocation_proof.slot;
+
+		// generate a key ownership proof. we start by trying to generate the
+		// key ownership proof at the parent of the equivocating header, this
+		// will make sure that proof generation is successful since it happens
+		// during the on-going session (i.e. session keys are available in the
+		// state to be able to generate the proof). this might fail if the
+		// equivocation happens on the first block of the session, in which case
+		// its parent would be on the previous session. if generation on the
+		// parent header fails we try with best block as well.
+		let best_id = BlockId::Hash(self.client.info().best_hash);
+		let generate_key_owner_proof = |block_id: &BlockId<B>| {
+			self.client
+				.runtime_api()
+				.generate_key_ownership_proof(block_id, slot, offender.clone())
+				.map_err(|e| ConsensusError::Other(Box::new(e)))
+		};
+
+		let parent_id = BlockId::Hash(*equivocation_proof.first_header.parent_hash());
+		let key_owner_proof = match generate_key_owner_proof(&parent_id)? {
+			Some(proof) => proof,
+			None => match generate_key_owner_proof(&best_id)? {
+				Some(proof) => proof,
+				None => {
+					debug!(target: "babe", "Equivocation offender is not part of the authority set.");
+					return Ok(())
+				},
+			},
+		};
+
+		// submit equivocation report at best block.
+		self.client
+			.runtime_api()
+			.submit_report_equivocation_unsigned_extrinsic(
+				&best_id,
+				equivocation_proof,
+				key_owner_proof,
+			)
+			.map_err(|e| ConsensusError::Other(Box::new(e)))?
+			.ok_or_else(|| {
+				ConsensusError::Other(
+					"Runtime failed to submit the equivocation report transaction".into(),
+				)
+			})?;
+
+		info!(target: "babe", "Submitted equivocation report for author {:?}", offender);
+
+		Ok(())
+	}
+}
@@ -114,6 +114,7 @@ use sp_blockchain::{
 	Backend as _, Error as ClientError, HeaderBackend, HeaderMetadata, Result as ClientResult,
 };
 use sp_consensus::{
+	equivocation::{EquivocationReport, EquivocationSink},
 	BlockOrigin, CacheKeyId, Environment, Error as ConsensusError, Proposer, SelectChain,
 };
 use sp_consensus_babe::inherents::BabeInherentData;
@@ -139,7 +140,9 @@ pub use sp_consensus_babe::{
 };
 
 pub use aux_schema::load_block_weight as block_weight;
+pub use equivocation::BabeEquivocationSubmitter;
 
+mod equivocation;
 mod migration;
 mod verification;
 
@@ -1001,6 +1004,7 @@ pub struct BabeVerifier<Block: BlockT, Client, SelectChain, CIDP> {
 	create_inherent_data_providers: CIDP,
 	config: BabeConfiguration,
 	epoch_changes: SharedEpochChanges<Block, Epoch>,
+	equivocation_reporting: Option<EquivocationSink>,
 	telemetry: Option<TelemetryHandle>,
 }
 
@@ -1069,6 +1073,13 @@ where
 			equivocation_proof.second_header.hash(),
 		);
 
+		// leave the submission to the equivocation reporting, if any.
+		if let Some(sink) = &self.equivocation_reporting {
+			let offence = (&equivocation_proof.offender, equivocation_proof.slot);
+			sink.report(EquivocationReport::new(BABE_ENGINE_ID, offence, &equivocation_proof));
+			return Ok(())
+		}
+
 		// get the best block on which we will build and send the equivocation report.
 		let best_id = self
 			.select_chain
@@ -1748,6 +1759,9 @@ where
 ///
 /// The block import object provided must be the `BabeBlockImport` or a wrapper
 /// of it, otherwise crucial import logic will be omitted.
+///
+/// The equivocations found while verifying blocks are sent to `equivocation_reporting` if
+/// given, and otherwise submitted to the runtime directly.
 pub fn import_queue<Block: BlockT, Client, SelectChain, Inner, CIDP>(
 	babe_link: BabeLink<Block>,
 	block_import: Inner,
@@ -1758,6 +1772,7 @@ pub fn import_queue<Block: BlockT, Client, SelectChain, Inner, CIDP>(
 	spawner: &impl sp_core::traits::SpawnEssentialNamed,
 	registry: Option<&Registry>,
 	telemetry: Option<TelemetryHandle>,
+	equivocation_reporting: Option<EquivocationSink>,
 ) -> ClientResult<DefaultImportQueue<Block, Client>>
 where
 	Inner: BlockImport<
@@ -1784,6 +1799,7 @@ where
 		create_inherent_data_providers,
 		config: babe_link.config,
 		epoch_changes: babe_link.epoch_changes,
+		equivocation_reporting,
 		telemetry,
 		client,
 	};
@@ -29,7 +29,10 @@ use rand_chacha::{
 use sc_block_builder::{BlockBuilder, BlockBuilderProvider};
 use sc_client_api::{backend::TransactionFor, BlockchainEvents, Finalizer};
 use sc_consensus::{BoxBlockImport, BoxJustificationImport};
-use sc_consensus_slots::BackoffAuthoringOnFinalizedHeadLagging;
+use sc_consensus_slots::{
+	simulation::{SimulatedAuthority, Simulation},
+	BackoffAuthoringOnFinalizedHeadLagging, SimpleSlotWorkerToSlotWorker,
+};
 use sc_network_test::{Block as TestBlock, *};
 use sp_application_crypto::key_types::BABE;
 use sp_consensus::{DisableProofRecording, NoNetwork as DummyOracle, Proposal};
@@ -49,7 +52,7 @@ use sp_runtime::{
 	traits::Block as BlockT,
 };
 use sp_timestamp::Timestamp;
-use std::{cell::RefCell, task::Poll, time::Duration};
+use std::{cell::RefCell, collections::BTreeSet, task::Poll, time::Duration};
 
 type Item = DigestItem;
 
@@ -336,6 +339,7 @@ impl TestNetFactory for BabeTestNet {
 				}),
 				config: data.link.config.clone(),
 				epoch_changes: data.link.epoch_changes.clone(),
+				equivocation_reporting: None,
 				telemetry: None,
 			},
 			mutator: MUTATOR.with(|m| m.borrow().clone()),
@@ -1051,3 +1055,168 @@ fn obsolete_blocks_aux_data_cleanup() {
 	// Present C4, C5
 	assert!(aux_data_check(&fork3_hashes, true));
 }
+
+type SimulatedBabeWorker = SimpleSlotWorkerToSlotWorker<
+	BabeSlotWorker<
+		TestBlock,
+		TestClient,
+		DummyFactory,
+		crate::BabeBlockImport<TestBlock, TestClient, Arc<TestClient>>,
+		DummyOracle,
+		(),
+		(),
+	>,
+>;
+
+/// A BABE authority with its own client, verifying and importing the blocks of the others.
+struct SimulatedBabe {
+	worker: SimulatedBabeWorker,
+	client: Arc<TestClient>,
+	verifier: BabeVerifier<
+		TestBlock,
+		TestClient,
+		TestSelectChain,
+		Box<
+			dyn CreateInherentDataProviders<
+				TestBlock,
+				(),
+				InherentDataProviders = (InherentDataProvider,),
+			>,
+		>,
+	>,
+	block_import: crate::BabeBlockImport<TestBlock, TestClient, Arc<TestClient>>,
+}
+
+impl SimulatedBabe {
+	fn new(authority: Sr25519Keyring) -> Self {
+		use substrate_test_runtime_client::DefaultTestClientBuilderExt;
+
+		let (client, select_chain) = TestClientBuilder::new().build_with_longest_chain();
+		let client = Arc::new(client);
+
+		let config = crate::configuration(&*client).expect("config available");
+		let (block_import, link) = crate::block_import(config, client.clone(), client.clone())
+			.expect("can initialize block-import");
+
+		let env = DummyFactory {
+			client: client.clone(),
+			config: link.config.clone(),
+			epoch_changes: link.epoch_changes.clone(),
+			mutator: Arc::new(|_, _| ()),
+		};
+
+		let worker = BabeSlotWorker {
+			client: client.clone(),
+			block_import: block_import.clone(),
+			env,
+			sync_oracle: DummyOracle,
+			justification_sync_link: (),
+			force_authoring: false,
+			backoff_authoring_blocks: Option::<()>::None,
+			keystore: create_keystore(authority),
+			epoch_changes: link.epoch_changes.clone(),
+			slot_notification_sinks: Default::default(),
+			config: link.config.clone(),
+			block_proposal_slot_portion: SlotProportion::new(0.5),
+			max_block_proposal_slot_portion: None,
+			telemetry: None,
+		};
+
+		let verifier = BabeVerifier {
+			client: client.clone(),
+			select_chain,
+			create_inherent_data_providers: Box::new(|_, _| async {
+				let slot = InherentDataProvider::from_timestamp_and_slot_duration(
+					Timestamp::current(),
+					SlotDuration::from_millis(SLOT_DURATION_MS),
+				);
+				Ok((slot,))
+			}),
+			config: link.config.clone(),
+			epoch_changes: link.epoch_changes.clone(),
+			equivocation_reporting: None,
+			telemetry: None,
+		};
+
+		SimulatedBabe {
+			worker: SimpleSlotWorkerToSlotWorker(worker),
+			client,
+			verifier,
+			block_import,
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl SimulatedAuthority<TestBlock> for SimulatedBabe {
+	type Proof = ();
+	type Worker = SimulatedBabeWorker;
+
+	fn worker(&mut self) -> &mut SimulatedBabeWorker {
+		&mut self.worker
+	}
+
+	async fn best_header(&mut self) -> TestHeader {
+		let best_hash = self.client.chain_info().best_hash;
+		self.client.header(&BlockId::Hash(best_hash)).unwrap().unwrap()
+	}
+
+	fn inherent_data(&mut self, _slot: Slot, _timestamp: u64) -> InherentData {
+		InherentData::new()
+	}
+
+	async fn import_block(&mut self, block: TestBlock) {
+		let (header, extrinsics) = block.deconstruct();
+		let mut import = BlockImportParams::new(BlockOrigin::NetworkBroadcast, header);
+		import.body = Some(extrinsics);
+
+		// the verifier moves the seal to the post digests and computes the epoch of the block,
+		// the block import then tracks the epoch changes and picks the heaviest chain.
+		let (import, _) = self.verifier.verify(import).await.expect("Verifies block");
+		self.block_import
+			.import_block(import.clear_storage_changes_and_mutate(), Default::default())
+			.await
+			.expect("Imports block");
+	}
+
+	fn finalized_number(&self) -> u64 {
+		self.client.chain_info().finalized_number
+	}
+}
+
+#[test]
+fn simulated_authorities_claim_every_slot_across_epochs() {
+	sp_tracing::try_init_simple();
+	let slot_duration = SlotDuration::from_millis(SLOT_DURATION_MS);
+	let slots = |n: u64| Duration::from_millis(SLOT_DURATION_MS * n);
+
+	// start on a slot boundary, so the first slot is `1_000`, and run for two epochs of the
+	// test runtime, which are 6 slots long.
+	let mut simulation = Simulation::new(slot_duration, slots(1_000));
+	for authority in [Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie] {
+		simulation.add_authority(SimulatedBabe::new(authority), 0);
+	}
+	block_on(simulation.run_for(slots(12)));
+
+	// secondary slots make sure every slot is claimed, and primary claims may add competing
+	// blocks of other authorities in the same slot, all on top of the heaviest chain.
+	let authored = simulation.authored_blocks();
+	let claimed = authored.iter().map(|block| *block.slot).collect::<BTreeSet<_>>();
+	assert_eq!(claimed, (1_000..=1_012).collect());
+	assert!(authored.iter().all(|block| block.number == *block.slot - 999));
+
+	// no authority claimed a slot twice.
+	let claims = authored
+		.iter()
+		.map(|block| (block.slot, block.authority))
+		.collect::<HashSet<_>>();
+	assert_eq!(claims.len(), authored.len());
+
+	// the others keep authoring while an authority is offline.
+	let before = authored.len();
+	simulation.set_online(2, false);
+	block_on(simulation.run_for(slots(6)));
+	let authored = &simulation.authored_blocks()[before..];
+	assert!(!authored.is_empty());
+	assert!(authored.iter().all(|block| block.authority != 2));
+}
@@ -0,0 +1,814 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Pluggable fork choice rules.
+//!
+//! [`ScoredChain`] is a [`SelectChain`] implementation picking the best block among the
+//! unfinalized leaves according to a [`ChainScore`], e.g. [`LongestChainScore`] or
+//! [`HeaviestSubtree`]. [`ReorgDepthLimit`] is a [`BlockImport`] wrapper refusing to make a block
+//! the new best block if that would revert more than a maximum number of blocks.
+
+use crate::{BlockCheckParams, BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult};
+use log::{debug, warn};
+use parking_lot::Mutex;
+use sc_client_api::backend;
+use sp_blockchain::{
+	lowest_common_ancestor, well_known_cache_keys::Id as CacheKeyId, Backend, HashAndNumber,
+	HeaderBackend, HeaderMetadata,
+};
+use sp_consensus::{Error as ConsensusError, SelectChain};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, Saturating, Zero},
+};
+use std::{collections::HashMap, sync::Arc};
+
+struct TreeNode<Block: BlockT> {
+	number: NumberFor<Block>,
+	parent: Block::Hash,
+	children: Vec<Block::Hash>,
+	weight: u64,
+}
+
+/// The tree of blocks descending from the last finalized block, which is its root.
+pub struct BlockTree<Block: BlockT> {
+	root: Block::Hash,
+	best: Block::Hash,
+	leaves: Vec<Block::Hash>,
+	nodes: HashMap<Block::Hash, TreeNode<Block>>,
+}
+
+impl<Block: BlockT> BlockTree<Block> {
+	/// Build the tree of the blocks between `finalized` and the given `leaves`. Leaves which don't
+	/// descend from `finalized` are ignored. `best` is the current best block.
+	pub fn new<T>(
+		blockchain: &T,
+		finalized: HashAndNumber<Block>,
+		best: Block::Hash,
+		leaves: Vec<Block::Hash>,
+	) -> Result<Self, T::Error>
+	where
+		T: HeaderMetadata<Block> + ?Sized,
+	{
+		let mut nodes = HashMap::new();
+		nodes.insert(
+			finalized.hash,
+			TreeNode {
+				number: finalized.number,
+				parent: Default::default(),
+				children: Vec::new(),
+				weight: 1,
+			},
+		);
+		let mut tree = BlockTree { root: finalized.hash, best, leaves: Vec::new(), nodes };
+
+		for leaf in leaves {
+			if tree.insert_leaf(blockchain, leaf)? {
+				tree.leaves.push(leaf);
+			}
+		}
+
+		Ok(tree)
+	}
+
+	/// Update the tree, keeping its root, to the current `best` block and `leaves`.
+	///
+	/// Only the blocks imported since the tree was built are fetched. Returns `false` if blocks of
+	/// the tree were removed since, in which case the tree must be built again.
+	pub fn update<T>(
+		&mut self,
+		blockchain: &T,
+		best: Block::Hash,
+		leaves: &[Block::Hash],
+	) -> Result<bool, T::Error>
+	where
+		T: HeaderMetadata<Block> + ?Sized,
+	{
+		let mut tree_leaves = Vec::new();
+		for leaf in leaves {
+			if self.insert_leaf(blockchain, *leaf)? {
+				tree_leaves.push(*leaf);
+			}
+		}
+
+		// a leaf of the tree which isn't a leaf anymore must have been built upon, otherwise it
+		// was removed.
+		let removed = self
+			.leaves
+			.iter()
+			.any(|leaf| !tree_leaves.contains(leaf) && self.children(leaf).is_empty());
+		self.best = best;
+		self.leaves = tree_leaves;
+
+		Ok(!removed)
+	}
+
+	/// Insert the blocks between the tree and `leaf`, returning whether `leaf` descends from the
+	/// root.
+	fn insert_leaf<T>(&mut self, blockchain: &T, leaf: Block::Hash) -> Result<bool, T::Error>
+	where
+		T: HeaderMetadata<Block> + ?Sized,
+	{
+		let root_number = self.nodes[&self.root].number;
+		let mut route = Vec::new();
+		let mut hash = leaf;
+		while !self.nodes.contains_key(&hash) {
+			let metadata = blockchain.header_metadata(hash)?;
+			if metadata.number <= root_number {
+				return Ok(false)
+			}
+			route.push((hash, metadata.number, metadata.parent));
+			hash = metadata.parent;
+		}
+
+		// the weight of a block is the number of blocks in its subtree: the new blocks are added
+		// to the subtrees of the block they connect to and of its ancestors.
+		let added = route.len() as u64;
+		let mut ancestor = Some(hash);
+		while let Some(hash) = ancestor {
+			if let Some(node) = self.nodes.get_mut(&hash) {
+				node.weight += added;
+			}
+			ancestor = self.parent(&hash);
+		}
+
+		for (index, (hash, number, parent)) in route.into_iter().rev().enumerate() {
+			self.nodes
+				.get_mut(&parent)
+				.expect("parent was inserted before its children; qed")
+				.children
+				.push(hash);
+			let weight = added - index as u64;
+			self.nodes
+				.insert(hash, TreeNode { number, parent, children: Vec::new(), weight });
+		}
+
+		Ok(true)
+	}
+
+	/// The last finalized block.
+	pub fn root(&self) -> Block::Hash {
+		self.root
+	}
+
+	/// The current best block.
+	pub fn best(&self) -> Block::Hash {
+		self.best
+	}
+
+	/// The leaves of the tree.
+	pub fn leaves(&self) -> &[Block::Hash] {
+		&self.leaves
+	}
+
+	/// Whether the given block is in the tree.
+	pub fn contains(&self, hash: &Block::Hash) -> bool {
+		self.nodes.contains_key(hash)
+	}
+
+	/// The number of the given block, if it is in the tree.
+	pub fn number(&self, hash: &Block::Hash) -> Option<NumberFor<Block>> {
+		self.nodes.get(hash).map(|node| node.number)
+	}
+
+	/// The parent of the given block, if it is in the tree and isn't the root.
+	pub fn parent(&self, hash: &Block::Hash) -> Option<Block::Hash> {
+		if *hash == self.root {
+			return None
+		}
+		self.nodes.get(hash).map(|node| node.parent)
+	}
+
+	/// The children of the given block.
+	pub fn children(&self, hash: &Block::Hash) -> &[Block::Hash] {
+		self.nodes.get(hash).map_or(&[], |node| &node.children[..])
+	}
+
+	/// The number of blocks in the subtree of the given block, including itself.
+	pub fn weight(&self, hash: &Block::Hash) -> u64 {
+		self.nodes.get(hash).map_or(0, |node| node.weight)
+	}
+
+	/// The blocks from the root (excluded) to the given block (included).
+	pub fn route(&self, hash: &Block::Hash) -> Vec<Block::Hash> {
+		let mut route = Vec::new();
+		let mut current = *hash;
+		while let Some(parent) = self.parent(&current) {
+			route.push(current);
+			current = parent;
+		}
+		route.reverse();
+		route
+	}
+
+	/// Whether `descendent` is `ancestor` or one of its descendents.
+	pub fn is_descendent_of(&self, ancestor: &Block::Hash, descendent: &Block::Hash) -> bool {
+		let mut current = *descendent;
+		loop {
+			if current == *ancestor {
+				return true
+			}
+			match self.parent(&current) {
+				Some(parent) => current = parent,
+				None => return false,
+			}
+		}
+	}
+
+	/// The lowest common ancestor of the given blocks, if both are in the tree.
+	pub fn common_ancestor(&self, one: &Block::Hash, two: &Block::Hash) -> Option<Block::Hash> {
+		let (mut one, mut two) = (*one, *two);
+		loop {
+			if one == two {
+				return Some(one)
+			}
+			if self.number(&one)? >= self.number(&two)? {
+				one = self.parent(&one)?;
+			} else {
+				two = self.parent(&two)?;
+			}
+		}
+	}
+}
+
+/// Scoring of the chains of a [`BlockTree`], the best chain having the highest score.
+pub trait ChainScore<Block: BlockT>: Send + Sync {
+	/// The score of a chain.
+	type Score: Ord;
+
+	/// Score the chain ending at the given leaf of the tree.
+	fn score(&self, tree: &BlockTree<Block>, leaf: &Block::Hash) -> Self::Score;
+}
+
+/// Select the leaf among `candidates` with the highest score, the first one in case of a tie.
+pub fn select_best<Block, S>(
+	tree: &BlockTree<Block>,
+	score: &S,
+	candidates: impl IntoIterator<Item = Block::Hash>,
+) -> Option<Block::Hash>
+where
+	Block: BlockT,
+	S: ChainScore<Block>,
+{
+	let mut best: Option<(Block::Hash, S::Score)> = None;
+	for candidate in candidates {
+		let candidate_score = score.score(tree, &candidate);
+		if best.as_ref().map_or(true, |(_, best_score)| candidate_score > *best_score) {
+			best = Some((candidate, candidate_score));
+		}
+	}
+	best.map(|(hash, _)| hash)
+}
+
+/// Prefer the longest chain, ignoring chains that would revert more than `max_reorg_depth` blocks
+/// of the current best chain, if set.
+#[derive(Clone, Debug)]
+pub struct LongestChainScore<N> {
+	/// Maximum number of blocks of the current best chain that can be reverted.
+	pub max_reorg_depth: Option<N>,
+}
+
+impl<Block: BlockT> ChainScore<Block> for LongestChainScore<NumberFor<Block>> {
+	/// Whether the reorg depth is within the limit, and the length of the chain.
+	type Score = (bool, NumberFor<Block>);
+
+	fn score(&self, tree: &BlockTree<Block>, leaf: &Block::Hash) -> Self::Score {
+		let number = tree.number(leaf).unwrap_or_else(Zero::zero);
+		let within_limit = match (self.max_reorg_depth, tree.number(&tree.best())) {
+			(Some(max_reorg_depth), Some(best_number)) => tree
+				.common_ancestor(leaf, &tree.best())
+				.and_then(|ancestor| tree.number(&ancestor))
+				.map_or(true, |ancestor| best_number.saturating_sub(ancestor) <= max_reorg_depth),
+			_ => true,
+		};
+
+		(within_limit, number)
+	}
+}
+
+/// GHOST-style rule, following the child with the heaviest subtree from the last finalized
+/// block.
+#[derive(Clone, Debug, Default)]
+pub struct HeaviestSubtree;
+
+impl<Block: BlockT> ChainScore<Block> for HeaviestSubtree {
+	/// The weights of the blocks of the chain, from the root. Chains are compared at the block
+	/// where they diverge.
+	type Score = Vec<u64>;
+
+	fn score(&self, tree: &BlockTree<Block>, leaf: &Block::Hash) -> Self::Score {
+		tree.route(leaf).iter().map(|hash| tree.weight(hash)).collect()
+	}
+}
+
+/// A [`SelectChain`] implementation choosing the best chain according to a [`ChainScore`].
+///
+/// Only chains descending from the last finalized block are considered. The tree of these chains
+/// is kept between calls and only extended with the blocks imported since, until the last
+/// finalized block changes.
+pub struct ScoredChain<B, Block: BlockT, S> {
+	backend: Arc<B>,
+	score: Arc<S>,
+	tree: Arc<Mutex<Option<BlockTree<Block>>>>,
+}
+
+impl<B, Block: BlockT, S> Clone for ScoredChain<B, Block, S> {
+	fn clone(&self) -> Self {
+		ScoredChain {
+			backend: self.backend.clone(),
+			score: self.score.clone(),
+			tree: self.tree.clone(),
+		}
+	}
+}
+
+impl<B, Block, S> ScoredChain<B, Block, S>
+where
+	B: backend::Backend<Block>,
+	Block: BlockT,
+	S: ChainScore<Block>,
+{
+	/// Instantiate a new ScoredChain for Backend B, using the given score.
+	pub fn new(backend: Arc<B>, score: S) -> Self {
+		ScoredChain { backend, score: Arc::new(score), tree: Default::default() }
+	}
+
+	/// Call `f` with the up to date tree of the unfinalized blocks.
+	fn with_tree<R>(&self, f: impl FnOnce(&BlockTree<Block>) -> R) -> sp_blockchain::Result<R> {
+		let _import_lock = self.backend.get_import_lock().read();
+		let blockchain = self.backend.blockchain();
+		let info = blockchain.info();
+		let leaves = blockchain.leaves()?;
+
+		let mut tree = self.tree.lock();
+		let updated = match tree.as_mut() {
+			Some(tree) if tree.root() == info.finalized_hash =>
+				tree.update(blockchain, info.best_hash, &leaves)?,
+			_ => false,
+		};
+		let tree = match &mut *tree {
+			Some(tree) if updated => tree,
+			tree => {
+				let finalized =
+					HashAndNumber { hash: info.finalized_hash, number: info.finalized_number };
+				tree.insert(BlockTree::new(blockchain, finalized, info.best_hash, leaves)?)
+			},
+		};
+
+		Ok(f(tree))
+	}
+
+	fn header(&self, hash: Block::Hash) -> sp_blockchain::Result<Block::Header> {
+		self.backend
+			.blockchain()
+			.header(BlockId::Hash(hash))?
+			.ok_or_else(|| sp_blockchain::Error::UnknownBlock(hash.to_string()))
+	}
+
+	fn best_block_header(&self) -> sp_blockchain::Result<Block::Header> {
+		let best = self.with_tree(|tree| {
+			select_best(tree, &*self.score, tree.leaves().iter().copied()).unwrap_or(tree.root())
+		})?;
+
+		self.header(best)
+	}
+
+	fn finality_target(
+		&self,
+		target_hash: Block::Hash,
+		maybe_max_number: Option<NumberFor<Block>>,
+	) -> sp_blockchain::Result<Block::Hash> {
+		self.with_tree(|tree| {
+			if !tree.contains(&target_hash) {
+				return target_hash
+			}
+
+			let candidates =
+				tree.leaves().iter().filter(|leaf| tree.is_descendent_of(&target_hash, leaf));
+			let mut best =
+				select_best(tree, &*self.score, candidates.copied()).unwrap_or(target_hash);
+
+			if let Some(max_number) = maybe_max_number {
+				while tree.number(&best).map_or(false, |number| number > max_number) {
+					match tree.parent(&best) {
+						Some(parent) => best = parent,
+						None => break,
+					}
+				}
+			}
+
+			best
+		})
+	}
+}
+
+#[async_trait::async_trait]
+impl<B, Block, S> SelectChain<Block> for ScoredChain<B, Block, S>
+where
+	B: backend::Backend<Block>,
+	Block: BlockT,
+	S: ChainScore<Block>,
+{
+	async fn leaves(&self) -> Result<Vec<<Block as BlockT>::Hash>, ConsensusError> {
+		self.backend
+			.blockchain()
+			.leaves()
+			.map_err(|e| ConsensusError::ChainLookup(e.to_string()))
+	}
+
+	async fn best_chain(&self) -> Result<<Block as BlockT>::Header, ConsensusError> {
+		ScoredChain::best_block_header(self).map_err(|e| ConsensusError::ChainLookup(e.to_string()))
+	}
+
+	async fn finality_target(
+		&self,
+		target_hash: Block::Hash,
+		maybe_max_number: Option<NumberFor<Block>>,
+	) -> Result<Block::Hash, ConsensusError> {
+		ScoredChain::finality_target(self, target_hash, maybe_max_number)
+			.map_err(|e| ConsensusError::ChainLookup(e.to_string()))
+	}
+}
+
+/// A [`BlockImport`] wrapper which imports blocks that would revert more than `max_reorg_depth`
+/// blocks of the current best chain without making them the new best block.
+///
+/// The limit applies to the fork choice of the blocks as they reach this block import. Consensus
+/// engines deciding the fork choice in their own block import, like BABE, override any earlier
+/// decision: wrapping BABE's block import has no effect, this must be the inner block import of
+/// BABE's instead.
+pub struct ReorgDepthLimit<Block: BlockT, I, C> {
+	inner: I,
+	client: Arc<C>,
+	max_reorg_depth: NumberFor<Block>,
+}
+
+impl<Block: BlockT, I: Clone, C> Clone for ReorgDepthLimit<Block, I, C> {
+	fn clone(&self) -> Self {
+		ReorgDepthLimit {
+			inner: self.inner.clone(),
+			client: self.client.clone(),
+			max_reorg_depth: self.max_reorg_depth,
+		}
+	}
+}
+
+impl<Block, I, C> ReorgDepthLimit<Block, I, C>
+where
+	Block: BlockT,
+	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = sp_blockchain::Error>,
+{
+	/// Wrap `inner`, limiting reorgs to `max_reorg_depth` blocks.
+	pub fn new(inner: I, client: Arc<C>, max_reorg_depth: NumberFor<Block>) -> Self {
+		ReorgDepthLimit { inner, client, max_reorg_depth }
+	}
+
+	/// The number of blocks of the best chain that importing `block` would revert, if it would
+	/// become the new best block.
+	fn reorg_depth<Transaction>(
+		&self,
+		block: &BlockImportParams<Block, Transaction>,
+	) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+		let info = self.client.info();
+		let becomes_best = match block.fork_choice {
+			Some(ForkChoiceStrategy::LongestChain) => *block.header.number() > info.best_number,
+			Some(ForkChoiceStrategy::Custom(becomes_best)) => becomes_best,
+			None => false,
+		};
+		if !becomes_best {
+			return Ok(None)
+		}
+
+		let ancestor =
+			lowest_common_ancestor(&*self.client, info.best_hash, *block.header.parent_hash())?;
+		Ok(Some(info.best_number.saturating_sub(ancestor.number)))
+	}
+}
+
+#[async_trait::async_trait]
+impl<Block, I, C> BlockImport<Block> for ReorgDepthLimit<Block, I, C>
+where
+	Block: BlockT,
+	I: BlockImport<Block> + Send,
+	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = sp_blockchain::Error> + Send + Sync,
+{
+	type Error = I::Error;
+	type Transaction = I::Transaction;
+
+	async fn check_block(
+		&mut self,
+		block: BlockCheckParams<Block>,
+	) -> Result<ImportResult, Self::Error> {
+		self.inner.check_block(block).await
+	}
+
+	async fn import_block(
+		&mut self,
+		mut block: BlockImportParams<Block, Self::Transaction>,
+		cache: HashMap<CacheKeyId, Vec<u8>>,
+	) -> Result<ImportResult, Self::Error> {
+		match self.reorg_depth(&block) {
+			Ok(Some(depth)) if depth > self.max_reorg_depth => {
+				debug!(
+					target: "sync",
+					"Not making block {:?} the best block: reorg of {} blocks exceeds the maximum of {}",
+					block.header.hash(),
+					depth,
+					self.max_reorg_depth,
+				);
+				block.fork_choice = Some(ForkChoiceStrategy::Custom(false));
+			},
+			Ok(_) => {},
+			Err(e) => warn!(
+				target: "sync",
+				"Unable to compute reorg depth of block {:?}: {}",
+				block.header.hash(),
+				e,
+			),
+		}
+
+		self.inner.import_block(block, cache).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::executor::block_on;
+	use sc_client_api::{in_mem, Backend as _, NewBlockState};
+	use sp_blockchain::CachedHeaderMetadata;
+	use sp_consensus::BlockOrigin;
+	use sp_core::H256;
+	use sp_test_primitives::{Block, Header};
+
+	/// In memory header metadata, the state root of the headers identifying the blocks.
+	#[derive(Default)]
+	struct Headers(HashMap<H256, Header>);
+
+	impl Headers {
+		fn push(&mut self, id: u64, parent: Option<H256>) -> H256 {
+			let number = parent.map_or(0, |parent| self.0[&parent].number + 1);
+			let header = Header::new(
+				number,
+				Default::default(),
+				H256::from_low_u64_be(id),
+				parent.unwrap_or_default(),
+				Default::default(),
+			);
+			let hash = header.hash();
+			self.0.insert(hash, header);
+			hash
+		}
+	}
+
+	impl HeaderMetadata<Block> for Headers {
+		type Error = sp_blockchain::Error;
+
+		fn header_metadata(
+			&self,
+			hash: H256,
+		) -> sp_blockchain::Result<CachedHeaderMetadata<Block>> {
+			self.0
+				.get(&hash)
+				.map(CachedHeaderMetadata::from)
+				.ok_or_else(|| sp_blockchain::Error::UnknownBlock(hash.to_string()))
+		}
+
+		fn insert_header_metadata(&self, _: H256, _: CachedHeaderMetadata<Block>) {}
+
+		fn remove_header_metadata(&self, _: H256) {}
+	}
+
+	// genesis - a1 - a2 - a3 - a4
+	//         \ b1 - b2
+	//              \ c2 - c3
+	//              \ d2
+	struct Fork {
+		headers: Headers,
+		genesis: H256,
+		a4: H256,
+		c3: H256,
+		leaves: Vec<H256>,
+	}
+
+	fn fork() -> Fork {
+		let mut headers = Headers::default();
+		let genesis = headers.push(0, None);
+		let a1 = headers.push(1, Some(genesis));
+		let a2 = headers.push(2, Some(a1));
+		let a3 = headers.push(3, Some(a2));
+		let a4 = headers.push(4, Some(a3));
+		let b1 = headers.push(5, Some(genesis));
+		let b2 = headers.push(6, Some(b1));
+		let c2 = headers.push(7, Some(b1));
+		let c3 = headers.push(8, Some(c2));
+		let d2 = headers.push(9, Some(b1));
+
+		Fork { headers, genesis, a4, c3, leaves: vec![a4, c3, b2, d2] }
+	}
+
+	fn tree(fork: &Fork, best: H256) -> BlockTree<Block> {
+		let finalized = HashAndNumber { hash: fork.genesis, number: 0 };
+		BlockTree::new(&fork.headers, finalized, best, fork.leaves.clone()).unwrap()
+	}
+
+	#[test]
+	fn heaviest_subtree_differs_from_longest_chain() {
+		let fork = fork();
+		let tree = tree(&fork, fork.a4);
+
+		assert_eq!(tree.weight(&fork.genesis), 10);
+		assert_eq!(
+			tree.route(&fork.c3).iter().map(|hash| tree.weight(hash)).collect::<Vec<_>>(),
+			[5, 2, 1]
+		);
+
+		let longest = LongestChainScore::<u64> { max_reorg_depth: None };
+		assert_eq!(select_best(&tree, &longest, tree.leaves().to_vec()), Some(fork.a4));
+		assert_eq!(select_best(&tree, &HeaviestSubtree, tree.leaves().to_vec()), Some(fork.c3));
+	}
+
+	#[test]
+	fn longest_chain_score_caps_reorg_depth() {
+		let mut fork = fork();
+		let c4 = fork.headers.push(10, Some(fork.c3));
+		let c5 = fork.headers.push(11, Some(c4));
+		fork.leaves[1] = c5;
+
+		// reverting a4 takes 4 blocks.
+		let tree = tree(&fork, fork.a4);
+		let longest = LongestChainScore::<u64> { max_reorg_depth: Some(4) };
+		assert_eq!(select_best(&tree, &longest, tree.leaves().to_vec()), Some(c5));
+		let capped = LongestChainScore::<u64> { max_reorg_depth: Some(3) };
+		assert_eq!(select_best(&tree, &capped, tree.leaves().to_vec()), Some(fork.a4));
+
+		// leaves not descending from the finalized block are ignored.
+		let finalized = HashAndNumber { hash: fork.headers.0[&fork.a4].parent_hash, number: 3 };
+		let tree = BlockTree::new(&fork.headers, finalized, fork.a4, fork.leaves.clone()).unwrap();
+		assert_eq!(tree.leaves(), [fork.a4]);
+		assert_eq!(select_best(&tree, &capped, tree.leaves().to_vec()), Some(fork.a4));
+	}
+
+	#[test]
+	fn updated_tree_matches_rebuilt_tree() {
+		let mut fork = fork();
+		let finalized = HashAndNumber { hash: fork.genesis, number: 0 };
+		let mut tree =
+			BlockTree::new(&fork.headers, finalized.clone(), fork.a4, vec![fork.a4]).unwrap();
+
+		let c4 = fork.headers.push(10, Some(fork.c3));
+		fork.leaves[1] = c4;
+		assert!(tree.update(&fork.headers, c4, &fork.leaves).unwrap());
+
+		let rebuilt = BlockTree::new(&fork.headers, finalized, c4, fork.leaves.clone()).unwrap();
+		assert_eq!(tree.leaves(), rebuilt.leaves());
+		assert_eq!(tree.best(), c4);
+		for hash in fork.headers.0.keys() {
+			assert_eq!(tree.weight(hash), rebuilt.weight(hash));
+			assert_eq!(tree.parent(hash), rebuilt.parent(hash));
+		}
+		assert_eq!(tree.weight(&fork.genesis), 11);
+
+		// a removed leaf requires building the tree again.
+		let leaves = fork.leaves.iter().copied().filter(|leaf| *leaf != c4).collect::<Vec<_>>();
+		assert!(!tree.update(&fork.headers, fork.a4, &leaves).unwrap());
+	}
+
+	/// A block import recording the fork choice of the imported blocks.
+	#[derive(Default)]
+	struct RecordForkChoice(Vec<Option<ForkChoiceStrategy>>);
+
+	#[async_trait::async_trait]
+	impl BlockImport<Block> for RecordForkChoice {
+		type Error = ConsensusError;
+		type Transaction = ();
+
+		async fn check_block(
+			&mut self,
+			_block: BlockCheckParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			Ok(ImportResult::imported(false))
+		}
+
+		async fn import_block(
+			&mut self,
+			block: BlockImportParams<Block, Self::Transaction>,
+			_cache: HashMap<CacheKeyId, Vec<u8>>,
+		) -> Result<ImportResult, Self::Error> {
+			self.0.push(block.fork_choice);
+			Ok(ImportResult::imported(false))
+		}
+	}
+
+	fn header(id: u64, number: u64, parent: H256) -> Header {
+		Header::new(
+			number,
+			Default::default(),
+			H256::from_low_u64_be(id),
+			parent,
+			Default::default(),
+		)
+	}
+
+	fn insert(
+		blockchain: &in_mem::Blockchain<Block>,
+		header: Header,
+		state: NewBlockState,
+	) -> H256 {
+		let hash = header.hash();
+		blockchain.insert(hash, header, None, None, state).unwrap();
+		hash
+	}
+
+	#[test]
+	fn reorg_depth_limit_refuses_deeper_reorgs() {
+		// genesis - a1 - a2 - a3 (best)
+		//         \ b1 - b2 - b3
+		let blockchain = Arc::new(in_mem::Blockchain::<Block>::new());
+		let genesis = insert(&blockchain, header(0, 0, Default::default()), NewBlockState::Final);
+		let a1 = insert(&blockchain, header(1, 1, genesis), NewBlockState::Best);
+		let a2 = insert(&blockchain, header(2, 2, a1), NewBlockState::Best);
+		insert(&blockchain, header(3, 3, a2), NewBlockState::Best);
+		let b1 = insert(&blockchain, header(4, 1, genesis), NewBlockState::Normal);
+		let b2 = insert(&blockchain, header(5, 2, b1), NewBlockState::Normal);
+		let b3 = insert(&blockchain, header(6, 3, b2), NewBlockState::Normal);
+
+		let import = |max_reorg_depth: u64, header: Header| {
+			let mut limit = ReorgDepthLimit::new(
+				RecordForkChoice::default(),
+				blockchain.clone(),
+				max_reorg_depth,
+			);
+			let mut block = BlockImportParams::new(BlockOrigin::NetworkBroadcast, header);
+			block.fork_choice = Some(ForkChoiceStrategy::LongestChain);
+			block_on(limit.import_block(block, HashMap::new())).unwrap();
+			limit.inner.0
+		};
+
+		// b4 reverts the 3 blocks of the best chain.
+		let b4 = header(7, 4, b3);
+		assert_eq!(import(3, b4.clone()), [Some(ForkChoiceStrategy::LongestChain)]);
+		assert_eq!(import(2, b4), [Some(ForkChoiceStrategy::Custom(false))]);
+
+		// blocks which don't become the best block aren't affected.
+		assert_eq!(import(0, header(8, 3, b2)), [Some(ForkChoiceStrategy::LongestChain)]);
+	}
+
+	#[test]
+	fn scored_chain_follows_imported_and_finalized_blocks() {
+		let backend = Arc::new(in_mem::Backend::<Block>::new());
+		let blockchain = backend.blockchain();
+		let fork = fork();
+		let best_chain = tree(&fork, fork.a4).route(&fork.a4);
+		let mut by_number = fork.headers.0.values().cloned().collect::<Vec<_>>();
+		by_number.sort_by_key(|header| header.number);
+		for header in by_number {
+			let state = match header.hash() {
+				hash if hash == fork.genesis => NewBlockState::Final,
+				hash if best_chain.contains(&hash) => NewBlockState::Best,
+				_ => NewBlockState::Normal,
+			};
+			insert(blockchain, header, state);
+		}
+		assert_eq!(blockchain.info().best_hash, fork.a4);
+
+		let chain = ScoredChain::new(backend.clone(), HeaviestSubtree);
+		assert_eq!(block_on(SelectChain::best_chain(&chain)).unwrap().hash(), fork.c3);
+		let c2 = fork.headers.0[&fork.c3].parent_hash;
+		let b1 = fork.headers.0[&c2].parent_hash;
+		assert_eq!(block_on(SelectChain::finality_target(&chain, b1, None)).unwrap(), fork.c3);
+		assert_eq!(block_on(SelectChain::finality_target(&chain, b1, Some(2))).unwrap(), c2);
+		assert_eq!(block_on(SelectChain::finality_target(&chain, fork.a4, None)).unwrap(), fork.a4);
+
+		// the subtree of a4 grows heavier.
+		let a5 = insert(blockchain, header(10, 5, fork.a4), NewBlockState::Best);
+		let a6 = insert(blockchain, header(11, 6, a5), NewBlockState::Best);
+		let a7 = insert(blockchain, header(12, 7, a6), NewBlockState::Best);
+		assert_eq!(block_on(SelectChain::best_chain(&chain)).unwrap().hash(), a7);
+
+		let c4 = insert(blockchain, header(13, 4, fork.c3), NewBlockState::Normal);
+		let c5 = insert(blockchain, header(14, 5, c4), NewBlockState::Normal);
+		let c6 = insert(blockchain, header(15, 6, c5), NewBlockState::Normal);
+		let c7 = insert(blockchain, header(16, 7, c6), NewBlockState::Normal);
+		assert_eq!(block_on(SelectChain::best_chain(&chain)).unwrap().hash(), c7);
+
+		// chains not descending from the last finalized block are ignored.
+		let a8 = insert(blockchain, header(17, 8, a7), NewBlockState::Final);
+		assert_eq!(block_on(SelectChain::best_chain(&chain)).unwrap().hash(), a8);
+	}
+}
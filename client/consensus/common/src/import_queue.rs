@@ -100,6 +100,21 @@ pub trait Verifier<B: BlockT>: Send + Sync {
 		&mut self,
 		block: BlockImportParams<B, ()>,
 	) -> Result<(BlockImportParams<B, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String>;
+
+	/// Run the checks of [`Self::verify`] that don't need the state of the parent block.
+	///
+	/// With parallel verification, the [`BasicQueue`] calls this ahead of time for blocks
+	/// whose parent is still being imported, and passes the returned block to [`Self::verify`]
+	/// once the parent is imported. The outcome of the checks can be stored in the
+	/// `intermediates` of the block so that `verify` doesn't repeat them.
+	///
+	/// Does nothing by default.
+	async fn pre_verify(
+		&mut self,
+		block: BlockImportParams<B, ()>,
+	) -> Result<BlockImportParams<B, ()>, String> {
+		Ok(block)
+	}
 }
 
 /// Blocks import queue API.
@@ -226,6 +241,47 @@ pub(crate) async fn import_single_block_metered<
 	verifier: &mut V,
 	metrics: Option<Metrics>,
 ) -> BlockImportResult<B> {
+	let PreparedBlock { peer, check, import_block } = prepare_block(block_origin, block)?;
+
+	if let Some(status) = check_block(import_handle, &check, peer).await? {
+		return Ok(status)
+	}
+
+	let started = std::time::Instant::now();
+
+	let verified = verifier.verify(import_block).await;
+	if let Some(metrics) = metrics.as_ref() {
+		metrics.report_verification(verified.is_ok(), started.elapsed());
+	}
+	let verified = verified.map_err(|msg| verification_failed(&check, peer, msg))?;
+
+	let imported = import_verified_block(import_handle, &check, peer, verified, &metrics).await;
+	if let Some(metrics) = metrics.as_ref() {
+		metrics.report_verification_and_import(started.elapsed());
+	}
+	imported
+}
+
+/// The result of [`Verifier::verify`].
+pub(crate) type VerifiedBlock<B> = (BlockImportParams<B, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>);
+
+/// A block with a header, split into the parts needed to check, verify and import it.
+pub(crate) struct PreparedBlock<B: BlockT> {
+	/// The peer we received the block from.
+	pub peer: Option<RuntimeOrigin>,
+	/// Parameters for [`BlockImport::check_block`].
+	pub check: BlockCheckParams<B>,
+	/// The block to pass to the verifier.
+	pub import_block: BlockImportParams<B, ()>,
+}
+
+/// Turn an incoming block into the parameters for checking, verifying and importing it.
+///
+/// Fails if the block has no header.
+pub(crate) fn prepare_block<B: BlockT>(
+	block_origin: BlockOrigin,
+	block: IncomingBlock<B>,
+) -> Result<PreparedBlock<B>, BlockImportError> {
 	let peer = block.origin;
 
 	let (header, justifications) = match (block.header, block.justifications) {
@@ -242,11 +298,92 @@ pub(crate) async fn import_single_block_metered<
 
 	trace!(target: "sync", "Header {} has {:?} logs", block.hash, header.digest().logs().len());
 
-	let number = *header.number();
-	let hash = block.hash;
-	let parent_hash = *header.parent_hash();
+	let check = BlockCheckParams {
+		hash: block.hash,
+		number: *header.number(),
+		parent_hash: *header.parent_hash(),
+		allow_missing_state: block.allow_missing_state,
+		import_existing: block.import_existing,
+		allow_missing_parent: block.state.is_some(),
+	};
+
+	let mut import_block = BlockImportParams::new(block_origin, header);
+	import_block.body = block.body;
+	import_block.justifications = justifications;
+	import_block.post_hash = Some(block.hash);
+	import_block.import_existing = block.import_existing;
+	import_block.indexed_body = block.indexed_body;
+
+	if let Some(state) = block.state {
+		let changes = crate::block_import::StorageChanges::Import(state);
+		import_block.state_action = StateAction::ApplyChanges(changes);
+	} else if block.skip_execution {
+		import_block.state_action = StateAction::Skip;
+	} else if block.allow_missing_state {
+		import_block.state_action = StateAction::ExecuteIfPossible;
+	}
+
+	Ok(PreparedBlock { peer, check, import_block })
+}
+
+/// Run [`BlockImport::check_block`] for a prepared block.
+///
+/// Returns `Some` if the block doesn't need to be verified and imported because it is already
+/// imported.
+pub(crate) async fn check_block<B: BlockT, Transaction: Send + 'static>(
+	import_handle: &mut impl BlockImport<B, Transaction = Transaction, Error = ConsensusError>,
+	check: &BlockCheckParams<B>,
+	peer: Option<RuntimeOrigin>,
+) -> Result<Option<BlockImportStatus<NumberFor<B>>>, BlockImportError> {
+	let checked = import_handle.check_block(check.clone()).await;
+	match import_result(checked, check, peer)? {
+		BlockImportStatus::ImportedUnknown { .. } => Ok(None),
+		// Any other successful result means that the block is already imported.
+		r => Ok(Some(r)),
+	}
+}
+
+/// Log a failed verification and turn it into the matching import error.
+pub(crate) fn verification_failed<B: BlockT>(
+	check: &BlockCheckParams<B>,
+	peer: Option<RuntimeOrigin>,
+	msg: String,
+) -> BlockImportError {
+	let (number, hash) = (check.number, check.hash);
+	if let Some(ref peer) = peer {
+		trace!(target: "sync", "Verifying {}({}) from {} failed: {}", number, hash, peer, msg);
+	} else {
+		trace!(target: "sync", "Verifying {}({}) failed: {}", number, hash, msg);
+	}
+	BlockImportError::VerificationFailed(peer, msg)
+}
+
+/// Import a block that passed verification.
+pub(crate) async fn import_verified_block<B: BlockT, Transaction: Send + 'static>(
+	import_handle: &mut impl BlockImport<B, Transaction = Transaction, Error = ConsensusError>,
+	check: &BlockCheckParams<B>,
+	peer: Option<RuntimeOrigin>,
+	(import_block, maybe_keys): VerifiedBlock<B>,
+	metrics: &Option<Metrics>,
+) -> BlockImportResult<B> {
+	let started = std::time::Instant::now();
+
+	let cache = HashMap::from_iter(maybe_keys.unwrap_or_default());
+	let import_block = import_block.clear_storage_changes_and_mutate();
+	let imported = import_handle.import_block(import_block, cache).await;
+	if let Some(metrics) = metrics.as_ref() {
+		metrics.report_block_import(started.elapsed());
+	}
+	import_result(imported, check, peer)
+}
 
-	let import_handler = |import| match import {
+fn import_result<B: BlockT>(
+	import: Result<ImportResult, ConsensusError>,
+	check: &BlockCheckParams<B>,
+	peer: Option<RuntimeOrigin>,
+) -> BlockImportResult<B> {
+	let (number, hash, parent_hash) = (check.number, check.hash, check.parent_hash);
+	match import {
 		Ok(ImportResult::AlreadyInChain) => {
 			trace!(target: "sync", "Block already in chain {}: {:?}", number, hash);
 			Ok(BlockImportStatus::ImportedKnown(number, peer))
@@ -271,63 +408,5 @@ pub(crate) async fn import_single_block_metered<
 			debug!(target: "sync", "Error importing block {}: {:?}: {}", number, hash, e);
 			Err(BlockImportError::Other(e))
 		},
-	};
-
-	match import_handler(
-		import_handle
-			.check_block(BlockCheckParams {
-				hash,
-				number,
-				parent_hash,
-				allow_missing_state: block.allow_missing_state,
-				import_existing: block.import_existing,
-				allow_missing_parent: block.state.is_some(),
-			})
-			.await,
-	)? {
-		BlockImportStatus::ImportedUnknown { .. } => (),
-		r => return Ok(r), // Any other successful result means that the block is already imported.
-	}
-
-	let started = std::time::Instant::now();
-
-	let mut import_block = BlockImportParams::new(block_origin, header);
-	import_block.body = block.body;
-	import_block.justifications = justifications;
-	import_block.post_hash = Some(hash);
-	import_block.import_existing = block.import_existing;
-	import_block.indexed_body = block.indexed_body;
-
-	if let Some(state) = block.state {
-		let changes = crate::block_import::StorageChanges::Import(state);
-		import_block.state_action = StateAction::ApplyChanges(changes);
-	} else if block.skip_execution {
-		import_block.state_action = StateAction::Skip;
-	} else if block.allow_missing_state {
-		import_block.state_action = StateAction::ExecuteIfPossible;
-	}
-
-	let (import_block, maybe_keys) = verifier.verify(import_block).await.map_err(|msg| {
-		if let Some(ref peer) = peer {
-			trace!(target: "sync", "Verifying {}({}) from {} failed: {}", number, hash, peer, msg);
-		} else {
-			trace!(target: "sync", "Verifying {}({}) failed: {}", number, hash, msg);
-		}
-		if let Some(metrics) = metrics.as_ref() {
-			metrics.report_verification(false, started.elapsed());
-		}
-		BlockImportError::VerificationFailed(peer, msg)
-	})?;
-
-	if let Some(metrics) = metrics.as_ref() {
-		metrics.report_verification(true, started.elapsed());
-	}
-
-	let cache = HashMap::from_iter(maybe_keys.unwrap_or_default());
-	let import_block = import_block.clear_storage_changes_and_mutate();
-	let imported = import_handle.import_block(import_block, cache).await;
-	if let Some(metrics) = metrics.as_ref() {
-		metrics.report_verification_and_import(started.elapsed());
 	}
-	import_handler(imported)
 }
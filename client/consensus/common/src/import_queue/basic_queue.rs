@@ -16,8 +16,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 use futures::{
+	executor::ThreadPool,
+	future::RemoteHandle,
 	prelude::*,
-	task::{Context, Poll},
+	task::{Context, Poll, SpawnExt},
 };
 use futures_timer::Delay;
 use log::{debug, trace};
@@ -28,13 +30,21 @@ use sp_runtime::{
 	traits::{Block as BlockT, Header as HeaderT, NumberFor},
 	Justification, Justifications,
 };
-use std::{marker::PhantomData, pin::Pin, time::Duration};
+use std::{
+	collections::{HashSet, VecDeque},
+	marker::PhantomData,
+	pin::Pin,
+	time::{Duration, Instant},
+};
 
 use crate::{
+	block_import::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult},
 	import_queue::{
 		buffered_link::{self, BufferedLinkReceiver, BufferedLinkSender},
-		import_single_block_metered, BlockImportError, BlockImportStatus, BoxBlockImport,
-		BoxJustificationImport, ImportQueue, IncomingBlock, Link, RuntimeOrigin, Verifier,
+		check_block, import_result, import_single_block_metered, import_verified_block,
+		prepare_block, verification_failed, BlockImportError, BlockImportResult, BlockImportStatus,
+		BoxBlockImport, BoxJustificationImport, ImportQueue, IncomingBlock, Link, PreparedBlock,
+		RuntimeOrigin, VerifiedBlock, Verifier,
 	},
 	metrics::Metrics,
 };
@@ -70,6 +80,60 @@ impl<B: BlockT, Transaction: Send + 'static> BasicQueue<B, Transaction> {
 		justification_import: Option<BoxJustificationImport<B>>,
 		spawner: &impl sp_core::traits::SpawnEssentialNamed,
 		prometheus_registry: Option<&Registry>,
+	) -> Self {
+		Self::with_verification_pool(
+			verifier,
+			None,
+			block_import,
+			justification_import,
+			spawner,
+			prometheus_registry,
+		)
+	}
+
+	/// Instantiate a new basic queue which verifies blocks in parallel.
+	///
+	/// Up to `verification_workers` blocks following the one being imported are verified ahead
+	/// of time on a dedicated thread pool, each by its own clone of `verifier`. Blocks are still
+	/// imported one by one, in the order they were received, and blocks which are already
+	/// imported are not verified. As verifiers usually need the state of the parent, a block
+	/// whose parent is imported in the same batch only goes through [`Verifier::pre_verify`]
+	/// ahead of time, and is verified once its parent is imported.
+	pub fn new_with_parallel_verification<V: 'static + Verifier<B> + Clone>(
+		verifier: V,
+		verification_workers: usize,
+		block_import: BoxBlockImport<B, Transaction>,
+		justification_import: Option<BoxJustificationImport<B>>,
+		spawner: &impl sp_core::traits::SpawnEssentialNamed,
+		prometheus_registry: Option<&Registry>,
+	) -> Self {
+		let verification_pool = VerificationPool::new(verifier.clone(), verification_workers)
+			.map_err(|err| {
+				log::warn!(
+					target: "block-import",
+					"Failed to start block verification pool, verifying sequentially: {}",
+					err,
+				);
+			})
+			.ok();
+
+		Self::with_verification_pool(
+			verifier,
+			verification_pool,
+			block_import,
+			justification_import,
+			spawner,
+			prometheus_registry,
+		)
+	}
+
+	fn with_verification_pool<V: 'static + Verifier<B>>(
+		verifier: V,
+		verification_pool: Option<VerificationPool<B>>,
+		block_import: BoxBlockImport<B, Transaction>,
+		justification_import: Option<BoxJustificationImport<B>>,
+		spawner: &impl sp_core::traits::SpawnEssentialNamed,
+		prometheus_registry: Option<&Registry>,
 	) -> Self {
 		let (result_sender, result_port) = buffered_link::buffered_link();
 
@@ -84,6 +148,7 @@ impl<B: BlockT, Transaction: Send + 'static> BasicQueue<B, Transaction> {
 		let (future, justification_sender, block_import_sender) = BlockImportWorker::new(
 			result_sender,
 			verifier,
+			verification_pool,
 			block_import,
 			justification_import,
 			metrics,
@@ -169,6 +234,7 @@ mod worker_messages {
 async fn block_import_process<B: BlockT, Transaction: Send + 'static>(
 	mut block_import: BoxBlockImport<B, Transaction>,
 	mut verifier: impl Verifier<B>,
+	mut verification_pool: Option<VerificationPool<B>>,
 	mut result_sender: BufferedLinkSender<B>,
 	mut block_import_receiver: TracingUnboundedReceiver<worker_messages::ImportBlocks<B>>,
 	metrics: Option<Metrics>,
@@ -192,6 +258,7 @@ async fn block_import_process<B: BlockT, Transaction: Send + 'static>(
 			origin,
 			blocks,
 			&mut verifier,
+			verification_pool.as_mut(),
 			delay_between_blocks,
 			metrics.clone(),
 		)
@@ -211,6 +278,7 @@ impl<B: BlockT> BlockImportWorker<B> {
 	fn new<V: 'static + Verifier<B>, Transaction: Send + 'static>(
 		result_sender: BufferedLinkSender<B>,
 		verifier: V,
+		verification_pool: Option<VerificationPool<B>>,
 		block_import: BoxBlockImport<B, Transaction>,
 		justification_import: Option<BoxJustificationImport<B>>,
 		metrics: Option<Metrics>,
@@ -242,6 +310,7 @@ impl<B: BlockT> BlockImportWorker<B> {
 			let block_import_process = block_import_process(
 				block_import,
 				verifier,
+				verification_pool,
 				worker.result_sender.clone(),
 				block_import_port,
 				worker.metrics.clone(),
@@ -336,12 +405,14 @@ struct ImportManyBlocksResult<B: BlockT> {
 /// Import several blocks at once, returning import result for each block.
 ///
 /// This will yield after each imported block once, to ensure that other futures can
-/// be called as well.
+/// be called as well. With a `verification_pool`, the blocks following the one being imported
+/// are verified on the pool in the meantime, or only pre-verified if their parent is in `blocks`.
 async fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction: Send + 'static>(
 	import_handle: &mut BoxBlockImport<B, Transaction>,
 	blocks_origin: BlockOrigin,
 	blocks: Vec<IncomingBlock<B>>,
 	verifier: &mut V,
+	mut verification_pool: Option<&mut VerificationPool<B>>,
 	delay_between_blocks: Duration,
 	metrics: Option<Metrics>,
 ) -> ImportManyBlocksResult<B> {
@@ -361,12 +432,18 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction: Send + 'stat
 	let mut imported = 0;
 	let mut results = vec![];
 	let mut has_error = false;
-	let mut blocks = blocks.into_iter();
+	let mut blocks: VecDeque<_> = blocks.into_iter().map(QueuedBlock::Incoming).collect();
 
 	// Blocks in the response/drain should be in ascending order.
 	loop {
+		if let Some(pool) = verification_pool.as_mut() {
+			if !has_error {
+				pool.verify_ahead(import_handle, blocks_origin, &mut blocks).await;
+			}
+		}
+
 		// Is there any block left to import?
-		let block = match blocks.next() {
+		let block = match blocks.pop_front() {
 			Some(b) => b,
 			None => {
 				// No block left to import, success!
@@ -374,20 +451,27 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction: Send + 'stat
 			},
 		};
 
-		let block_number = block.header.as_ref().map(|h| *h.number());
-		let block_hash = block.hash;
+		let block_number = block.number();
+		let block_hash = block.hash();
 		let import_result = if has_error {
+			// Dropping a block that is being verified cancels its verification.
 			Err(BlockImportError::Cancelled)
 		} else {
 			// The actual import.
-			import_single_block_metered(
-				import_handle,
-				blocks_origin,
-				block,
-				verifier,
-				metrics.clone(),
-			)
-			.await
+			match block {
+				QueuedBlock::Incoming(block) =>
+					import_single_block_metered(
+						import_handle,
+						blocks_origin,
+						block,
+						verifier,
+						metrics.clone(),
+					)
+					.await,
+				QueuedBlock::Verifying(block) =>
+					import_verifying_block(import_handle, block, verifier, metrics.clone()).await,
+				QueuedBlock::Checked(_, result) => result,
+			}
 		};
 
 		if let Some(metrics) = metrics.as_ref() {
@@ -416,6 +500,205 @@ async fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction: Send + 'stat
 	}
 }
 
+/// Checks of a block run on the [`VerificationPool`].
+enum Verification<B: BlockT> {
+	/// The block passed [`Verifier::verify`].
+	Verified(VerifiedBlock<B>),
+	/// The block passed [`Verifier::pre_verify`], and is verified once its parent is imported.
+	PreVerified(BlockImportParams<B, ()>),
+}
+
+/// Outcome of the checks of a block run on the [`VerificationPool`], with the time they took.
+type VerificationOutcome<B> = (Result<Verification<B>, String>, Duration);
+
+/// Starts the checks of a block on the [`VerificationPool`], which are limited to
+/// [`Verifier::pre_verify`] when the parent of the block is not imported yet.
+type SpawnVerification<B> =
+	Box<dyn FnMut(BlockImportParams<B, ()>, bool) -> RemoteHandle<VerificationOutcome<B>> + Send>;
+
+/// Verifies blocks on a thread pool ahead of their import.
+struct VerificationPool<B: BlockT> {
+	/// Maximum number of blocks verified ahead of their import.
+	workers: usize,
+	/// Starts the checks of a block on the pool.
+	spawn: SpawnVerification<B>,
+}
+
+impl<B: BlockT> VerificationPool<B> {
+	fn new<V: 'static + Verifier<B> + Clone>(verifier: V, workers: usize) -> std::io::Result<Self> {
+		let workers = workers.max(1);
+		let pool = ThreadPool::builder()
+			.pool_size(workers)
+			.name_prefix("block-verifier-")
+			.create()?;
+
+		let spawn = move |block, parent_in_flight| {
+			let mut verifier = verifier.clone();
+			pool.spawn_with_handle(async move {
+				let started = Instant::now();
+				let verification = if parent_in_flight {
+					verifier.pre_verify(block).await.map(Verification::PreVerified)
+				} else {
+					verifier.verify(block).await.map(Verification::Verified)
+				};
+				(verification, started.elapsed())
+			})
+			.expect("Spawning on a `ThreadPool` never fails; qed")
+		};
+
+		Ok(Self { workers, spawn: Box::new(spawn) })
+	}
+
+	/// Start verifying the queued blocks, in order, until `workers` blocks are being verified.
+	///
+	/// Blocks are checked with [`BlockImport::check_block`] first, and the ones that don't need
+	/// to be imported are not verified. Blocks whose parent is queued before them are only
+	/// pre-verified, and fully verified once their parent is imported.
+	///
+	/// [`BlockImport::check_block`]: crate::block_import::BlockImport::check_block
+	async fn verify_ahead<Transaction: Send + 'static>(
+		&mut self,
+		import_handle: &mut BoxBlockImport<B, Transaction>,
+		origin: BlockOrigin,
+		blocks: &mut VecDeque<QueuedBlock<B>>,
+	) {
+		let mut verifying = blocks
+			.iter()
+			.filter(|queued| matches!(queued, QueuedBlock::Verifying(_)))
+			.count();
+		let mut in_flight = HashSet::new();
+
+		for index in 0..blocks.len() {
+			if verifying >= self.workers {
+				break
+			}
+
+			let parent_in_flight = match &blocks[index] {
+				QueuedBlock::Incoming(block) => block
+					.header
+					.as_ref()
+					.map_or(false, |header| in_flight.contains(header.parent_hash())),
+				QueuedBlock::Verifying(block) => {
+					in_flight.insert(block.check.hash);
+					continue
+				},
+				QueuedBlock::Checked(..) => continue,
+			};
+
+			if let Some(QueuedBlock::Incoming(block)) = blocks.remove(index) {
+				let queued = self.verify(import_handle, origin, block, parent_in_flight).await;
+				if let QueuedBlock::Verifying(block) = &queued {
+					in_flight.insert(block.check.hash);
+					verifying += 1;
+				}
+				blocks.insert(index, queued);
+			}
+		}
+	}
+
+	/// Check a block, and start verifying it if it needs to be imported.
+	async fn verify<Transaction: Send + 'static>(
+		&mut self,
+		import_handle: &mut BoxBlockImport<B, Transaction>,
+		origin: BlockOrigin,
+		block: IncomingBlock<B>,
+		parent_in_flight: bool,
+	) -> QueuedBlock<B> {
+		let hash = block.hash;
+		let PreparedBlock { peer, check, import_block } = match prepare_block(origin, block) {
+			Ok(prepared) => prepared,
+			Err(error) => return QueuedBlock::Checked(hash, Err(error)),
+		};
+
+		match import_handle.check_block(check.clone()).await {
+			Ok(ImportResult::Imported(_)) => {},
+			// The parent is imported before the block.
+			Ok(ImportResult::UnknownParent) if parent_in_flight => {},
+			checked => return QueuedBlock::Checked(hash, import_result(checked, &check, peer)),
+		}
+
+		QueuedBlock::Verifying(VerifyingBlock {
+			peer,
+			check,
+			verification: (self.spawn)(import_block, parent_in_flight),
+		})
+	}
+}
+
+/// A block waiting to be imported by [`import_many_blocks`].
+enum QueuedBlock<B: BlockT> {
+	/// A block that is verified when it is imported.
+	Incoming(IncomingBlock<B>),
+	/// A block being verified on the [`VerificationPool`].
+	Verifying(VerifyingBlock<B>),
+	/// A block that is not verified, as it is already imported or can't be imported.
+	Checked(B::Hash, BlockImportResult<B>),
+}
+
+impl<B: BlockT> QueuedBlock<B> {
+	fn hash(&self) -> B::Hash {
+		match self {
+			QueuedBlock::Incoming(block) => block.hash,
+			QueuedBlock::Verifying(block) => block.check.hash,
+			QueuedBlock::Checked(hash, _) => *hash,
+		}
+	}
+
+	fn number(&self) -> Option<NumberFor<B>> {
+		match self {
+			QueuedBlock::Incoming(block) => block.header.as_ref().map(|h| *h.number()),
+			QueuedBlock::Verifying(block) => Some(block.check.number),
+			QueuedBlock::Checked(..) => None,
+		}
+	}
+}
+
+/// A block being verified on the [`VerificationPool`].
+struct VerifyingBlock<B: BlockT> {
+	peer: Option<RuntimeOrigin>,
+	check: BlockCheckParams<B>,
+	verification: RemoteHandle<VerificationOutcome<B>>,
+}
+
+/// Import a block whose verification was started on the [`VerificationPool`].
+///
+/// Pre-verified blocks are verified by `verifier` before being imported.
+async fn import_verifying_block<B: BlockT, V: Verifier<B>, Transaction: Send + 'static>(
+	import_handle: &mut BoxBlockImport<B, Transaction>,
+	block: VerifyingBlock<B>,
+	verifier: &mut V,
+	metrics: Option<Metrics>,
+) -> Result<BlockImportStatus<NumberFor<B>>, BlockImportError> {
+	let VerifyingBlock { peer, check, verification } = block;
+
+	if let Some(status) = check_block(import_handle, &check, peer).await? {
+		return Ok(status)
+	}
+
+	let (verification, mut verification_time) = verification.await;
+	let verified = match verification {
+		Ok(Verification::Verified(verified)) => Ok(verified),
+		Ok(Verification::PreVerified(block)) => {
+			let started = Instant::now();
+			let verified = verifier.verify(block).await;
+			verification_time += started.elapsed();
+			verified
+		},
+		Err(msg) => Err(msg),
+	};
+	if let Some(metrics) = metrics.as_ref() {
+		metrics.report_verification(verified.is_ok(), verification_time);
+	}
+	let verified = verified.map_err(|msg| verification_failed(&check, peer, msg))?;
+
+	let started = Instant::now();
+	let imported = import_verified_block(import_handle, &check, peer, verified, &metrics).await;
+	if let Some(metrics) = metrics.as_ref() {
+		metrics.report_verification_and_import(verification_time + started.elapsed());
+	}
+	imported
+}
+
 /// A future that will always `yield` on the first call of `poll` but schedules the
 /// current task for re-execution.
 ///
@@ -454,7 +737,10 @@ mod tests {
 	};
 	use futures::{executor::block_on, Future};
 	use sp_test_primitives::{Block, BlockNumber, Extrinsic, Hash, Header};
-	use std::collections::HashMap;
+	use std::{
+		collections::HashMap,
+		sync::{Arc, Mutex},
+	};
 
 	#[async_trait::async_trait]
 	impl Verifier<Block> for () {
@@ -544,7 +830,7 @@ mod tests {
 		let (result_sender, mut result_port) = buffered_link::buffered_link();
 
 		let (worker, mut finality_sender, mut block_import_sender) =
-			BlockImportWorker::new(result_sender, (), Box::new(()), Some(Box::new(())), None);
+			BlockImportWorker::new(result_sender, (), None, Box::new(()), Some(Box::new(())), None);
 		futures::pin_mut!(worker);
 
 		let mut import_block = |n| {
@@ -634,4 +920,287 @@ mod tests {
 			]
 		);
 	}
+
+	/// Verifier that only accepts blocks whose parent was imported by [`RecordingImport`], and
+	/// records the blocks it verified.
+	#[derive(Clone)]
+	struct ParentImported(Arc<Mutex<Vec<Hash>>>, Arc<Mutex<Vec<Hash>>>);
+
+	#[async_trait::async_trait]
+	impl Verifier<Block> for ParentImported {
+		async fn verify(
+			&mut self,
+			block: BlockImportParams<Block, ()>,
+		) -> Result<(BlockImportParams<Block, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String> {
+			self.1.lock().unwrap().push(block.header.hash());
+			if self.0.lock().unwrap().contains(block.header.parent_hash()) {
+				Ok((BlockImportParams::new(block.origin, block.header), None))
+			} else {
+				Err("parent not imported".into())
+			}
+		}
+	}
+
+	struct RecordingImport(Arc<Mutex<Vec<Hash>>>);
+
+	#[async_trait::async_trait]
+	impl BlockImport<Block> for RecordingImport {
+		type Error = sp_consensus::Error;
+		type Transaction = Extrinsic;
+
+		async fn check_block(
+			&mut self,
+			_block: BlockCheckParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			Ok(ImportResult::imported(false))
+		}
+
+		async fn import_block(
+			&mut self,
+			block: BlockImportParams<Block, Self::Transaction>,
+			_cache: HashMap<CacheKeyId, Vec<u8>>,
+		) -> Result<ImportResult, Self::Error> {
+			self.0.lock().unwrap().push(block.header.hash());
+			Ok(ImportResult::imported(true))
+		}
+	}
+
+	fn incoming(number: BlockNumber, parent_hash: Hash) -> IncomingBlock<Block> {
+		let header = Header {
+			parent_hash,
+			number,
+			extrinsics_root: Hash::random(),
+			state_root: Default::default(),
+			digest: Default::default(),
+		};
+		IncomingBlock {
+			hash: header.hash(),
+			header: Some(header),
+			body: None,
+			indexed_body: None,
+			justifications: None,
+			origin: None,
+			allow_missing_state: false,
+			import_existing: false,
+			state: None,
+			skip_execution: false,
+		}
+	}
+
+	#[test]
+	fn parallel_verification_keeps_import_order() {
+		let genesis = Hash::random();
+		let imported = Arc::new(Mutex::new(vec![genesis]));
+		let verified = Arc::new(Mutex::new(Vec::new()));
+		let verifier = ParentImported(imported.clone(), verified.clone());
+		let mut pool = VerificationPool::new(verifier.clone(), 4).unwrap();
+		let mut block_import: BoxBlockImport<Block, Extrinsic> =
+			Box::new(RecordingImport(imported.clone()));
+
+		// A chain of blocks, each depending on the import of the previous one, followed by a
+		// block on an unknown fork.
+		let mut blocks = Vec::new();
+		let mut parent_hash = genesis;
+		for number in 1..=6 {
+			let block = incoming(number, parent_hash);
+			parent_hash = block.hash;
+			blocks.push(block);
+		}
+		blocks.push(incoming(7, Hash::random()));
+		let hashes: Vec<_> = blocks.iter().map(|block| block.hash).collect();
+
+		let result = block_on(import_many_blocks(
+			&mut block_import,
+			BlockOrigin::NetworkInitialSync,
+			blocks,
+			&mut verifier.clone(),
+			Some(&mut pool),
+			Duration::default(),
+			None,
+		));
+
+		assert_eq!(result.block_count, 7);
+		assert_eq!(result.imported, 6);
+		assert!(result.results[..6].iter().all(|(result, _)| result.is_ok()));
+		assert!(matches!(result.results[6].0, Err(BlockImportError::VerificationFailed(None, _))));
+		assert_eq!(result.results.iter().map(|(_, hash)| *hash).collect::<Vec<_>>(), hashes);
+		assert_eq!(imported.lock().unwrap()[1..], hashes[..6]);
+
+		// every block was verified once
+		let mut verified = verified.lock().unwrap().clone();
+		verified.sort();
+		let mut expected = hashes.clone();
+		expected.sort();
+		assert_eq!(verified, expected);
+	}
+
+	#[test]
+	fn blocks_with_a_parent_in_flight_are_pre_verified() {
+		let verified = Arc::new(Mutex::new(Vec::new()));
+		let verifier = ParentImported(Default::default(), verified.clone());
+		let mut pool = VerificationPool::new(verifier, 3).unwrap();
+		let mut block_import: BoxBlockImport<Block, Extrinsic> =
+			Box::new(RecordingImport(Default::default()));
+
+		// a chain of three blocks, and three blocks with parents which are not queued
+		let first = incoming(1, Hash::random());
+		let second = incoming(2, first.hash);
+		let third = incoming(3, second.hash);
+		let mut blocks: VecDeque<_> = vec![
+			first,
+			second,
+			third,
+			incoming(1, Hash::random()),
+			incoming(1, Hash::random()),
+			incoming(1, Hash::random()),
+		]
+		.into_iter()
+		.map(QueuedBlock::Incoming)
+		.collect();
+		let hashes: Vec<_> = blocks.iter().map(|queued| queued.hash()).collect();
+
+		let is_verifying = |blocks: &VecDeque<QueuedBlock<Block>>| {
+			blocks
+				.iter()
+				.map(|queued| matches!(queued, QueuedBlock::Verifying(_)))
+				.collect::<Vec<_>>()
+		};
+
+		block_on(pool.verify_ahead(
+			&mut block_import,
+			BlockOrigin::NetworkInitialSync,
+			&mut blocks,
+		));
+		assert_eq!(is_verifying(&blocks), vec![true, true, true, false, false, false]);
+
+		// only the first block is fully verified, its children are pre-verified
+		let outcomes: Vec<_> = blocks
+			.drain(..3)
+			.map(|queued| match queued {
+				QueuedBlock::Verifying(block) => block_on(block.verification).0,
+				_ => unreachable!(),
+			})
+			.collect();
+		assert!(outcomes[0].is_err());
+		assert!(matches!(outcomes[1], Ok(Verification::PreVerified(_))));
+		assert!(matches!(outcomes[2], Ok(Verification::PreVerified(_))));
+		assert_eq!(*verified.lock().unwrap(), vec![hashes[0]]);
+
+		block_on(pool.verify_ahead(
+			&mut block_import,
+			BlockOrigin::NetworkInitialSync,
+			&mut blocks,
+		));
+		assert_eq!(is_verifying(&blocks), vec![true, true, true]);
+	}
+
+	/// Records when blocks are pre-verified and verified.
+	#[derive(Clone, Default)]
+	struct RecordingVerifier(Arc<Mutex<Vec<(&'static str, BlockNumber)>>>);
+
+	#[async_trait::async_trait]
+	impl Verifier<Block> for RecordingVerifier {
+		async fn verify(
+			&mut self,
+			block: BlockImportParams<Block, ()>,
+		) -> Result<(BlockImportParams<Block, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String> {
+			self.0.lock().unwrap().push(("verified", block.header.number));
+			Ok((block, None))
+		}
+
+		async fn pre_verify(
+			&mut self,
+			block: BlockImportParams<Block, ()>,
+		) -> Result<BlockImportParams<Block, ()>, String> {
+			self.0.lock().unwrap().push(("pre-verified", block.header.number));
+			Ok(block)
+		}
+	}
+
+	/// Only imports a block below `last` once the block following it is pre-verified.
+	struct WaitForPreVerification {
+		events: Arc<Mutex<Vec<(&'static str, BlockNumber)>>>,
+		known: Vec<Hash>,
+		last: BlockNumber,
+	}
+
+	#[async_trait::async_trait]
+	impl BlockImport<Block> for WaitForPreVerification {
+		type Error = sp_consensus::Error;
+		type Transaction = Extrinsic;
+
+		async fn check_block(
+			&mut self,
+			block: BlockCheckParams<Block>,
+		) -> Result<ImportResult, Self::Error> {
+			if self.known.contains(&block.hash) {
+				Ok(ImportResult::AlreadyInChain)
+			} else {
+				Ok(ImportResult::imported(false))
+			}
+		}
+
+		async fn import_block(
+			&mut self,
+			block: BlockImportParams<Block, Self::Transaction>,
+			_cache: HashMap<CacheKeyId, Vec<u8>>,
+		) -> Result<ImportResult, Self::Error> {
+			let number = block.header.number;
+			let child_pre_verified =
+				|| self.events.lock().unwrap().contains(&("pre-verified", number + 1));
+			let started = Instant::now();
+			while number < self.last &&
+				!child_pre_verified() &&
+				started.elapsed() < Duration::from_secs(5)
+			{
+				std::thread::sleep(Duration::from_millis(1));
+			}
+
+			self.events.lock().unwrap().push(("imported", number));
+			Ok(ImportResult::imported(true))
+		}
+	}
+
+	#[test]
+	fn verification_overlaps_import_of_the_parent() {
+		let events = Arc::new(Mutex::new(Vec::new()));
+		let verifier = RecordingVerifier(events.clone());
+		let mut pool = VerificationPool::new(verifier.clone(), 2).unwrap();
+
+		// a chain of four blocks, the first of which is already imported
+		let mut blocks = vec![incoming(1, Hash::random())];
+		for number in 2..=4 {
+			blocks.push(incoming(number, blocks.last().unwrap().hash));
+		}
+		let mut block_import: BoxBlockImport<Block, Extrinsic> = Box::new(WaitForPreVerification {
+			events: events.clone(),
+			known: vec![blocks[0].hash],
+			last: 4,
+		});
+
+		let result = block_on(import_many_blocks(
+			&mut block_import,
+			BlockOrigin::NetworkInitialSync,
+			blocks,
+			&mut verifier.clone(),
+			Some(&mut pool),
+			Duration::default(),
+			None,
+		));
+		assert_eq!(result.imported, 4);
+		assert!(matches!(result.results[0].0, Ok(BlockImportStatus::ImportedKnown(1, None))));
+
+		let events = events.lock().unwrap();
+		let position = |event| events.iter().position(|e| *e == event).unwrap();
+
+		// the known block is not verified
+		assert_eq!(events.iter().filter(|(_, number)| *number == 1).count(), 0);
+		// the second block is verified ahead of time, as its parent is known
+		assert!(position(("verified", 2)) < position(("imported", 2)));
+		for number in 3..=4 {
+			// each block is pre-verified while its parent is imported, and verified after it
+			assert!(position(("pre-verified", number)) < position(("imported", number - 1)));
+			assert!(position(("imported", number - 1)) < position(("verified", number)));
+		}
+	}
 }
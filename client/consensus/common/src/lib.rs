@@ -32,8 +32,13 @@ pub use import_queue::{
 	BoxJustificationImport, DefaultImportQueue, ImportQueue, IncomingBlock, Link, Verifier,
 };
 
+mod fork_choice;
 mod longest_chain;
 
 pub mod shared_data;
 
+pub use fork_choice::{
+	select_best, BlockTree, ChainScore, HeaviestSubtree, LongestChainScore, ReorgDepthLimit,
+	ScoredChain,
+};
 pub use longest_chain::LongestChain;
@@ -33,6 +33,7 @@ pub(crate) struct Metrics {
 	pub import_queue_processed: CounterVec<U64>,
 	pub block_verification_time: HistogramVec,
 	pub block_verification_and_import_time: Histogram,
+	pub block_import_time: Histogram,
 	pub justification_import_time: Histogram,
 }
 
@@ -66,6 +67,13 @@ impl Metrics {
 				))?,
 				registry,
 			)?,
+			block_import_time: register(
+				Histogram::with_opts(HistogramOpts::new(
+					"substrate_block_import_time",
+					"Time taken to import verified blocks",
+				))?,
+				registry,
+			)?,
 			justification_import_time: register(
 				Histogram::with_opts(HistogramOpts::new(
 					"substrate_justification_import_time",
@@ -103,4 +111,8 @@ impl Metrics {
 	pub fn report_verification_and_import(&self, time: std::time::Duration) {
 		self.block_verification_and_import_time.observe(time.as_secs_f64());
 	}
+
+	pub fn report_block_import(&self, time: std::time::Duration) {
+		self.block_import_time.observe(time.as_secs_f64());
+	}
 }
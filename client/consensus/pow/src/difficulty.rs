@@ -0,0 +1,215 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reference difficulty adjustment algorithms.
+//!
+//! A [`PowAlgorithm::difficulty`](crate::PowAlgorithm::difficulty) implementation can collect
+//! the timestamps and difficulties of the most recent ancestors of the parent block and pass
+//! them to a [`DifficultyAdjustment`] instead of implementing its own adjustment. The difficulty
+//! of each block is available through [`PowAux`](crate::PowAux), while the timestamps are chain
+//! specific.
+
+use sp_core::U256;
+
+/// Solve times are clamped to this many target block times, to limit the effect of timestamp
+/// manipulation.
+const MAX_SOLVE_TIME_FACTOR: u64 = 6;
+
+/// Timestamp and difficulty of a past block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DifficultySample {
+	/// Timestamp of the block, in milliseconds.
+	pub timestamp: u64,
+	/// Difficulty the block was mined at.
+	pub difficulty: U256,
+}
+
+/// Algorithm computing the difficulty of the next block from its ancestors.
+pub trait DifficultyAdjustment {
+	/// Number of most recent blocks the algorithm looks at.
+	fn samples(&self) -> usize;
+
+	/// Compute the difficulty of the next block from `samples`, ordered from oldest to newest and
+	/// ending with the parent block.
+	///
+	/// Fewer than [`Self::samples`] samples may be passed close to genesis.
+	fn next_difficulty(&self, samples: &[DifficultySample]) -> U256;
+}
+
+fn solve_time(previous: &DifficultySample, current: &DifficultySample, target: u64) -> u64 {
+	current
+		.timestamp
+		.saturating_sub(previous.timestamp)
+		.clamp(1, target.saturating_mul(MAX_SOLVE_TIME_FACTOR))
+}
+
+/// Linearly weighted moving average of the solve times of the last `window` blocks, with more
+/// recent blocks weighing more.
+#[derive(Clone, Debug)]
+pub struct Lwma {
+	/// Target block time, in milliseconds.
+	pub target_block_time: u64,
+	/// Number of solve times to average over.
+	pub window: usize,
+	/// Lower bound of the returned difficulty, also used when there are not enough samples.
+	pub min_difficulty: U256,
+}
+
+impl DifficultyAdjustment for Lwma {
+	fn samples(&self) -> usize {
+		self.window + 1
+	}
+
+	fn next_difficulty(&self, samples: &[DifficultySample]) -> U256 {
+		let samples = &samples[samples.len().saturating_sub(self.samples())..];
+		if samples.len() < 2 {
+			let last = samples.last().map_or(U256::zero(), |sample| sample.difficulty);
+			return last.max(self.min_difficulty)
+		}
+
+		let target = self.target_block_time.max(1);
+		let n = (samples.len() - 1) as u64;
+		let mut weighted_solve_times = U256::zero();
+		let mut sum_difficulties = U256::zero();
+		for (weight, pair) in (1u64..).zip(samples.windows(2)) {
+			let solve_time = solve_time(&pair[0], &pair[1], target);
+			weighted_solve_times =
+				weighted_solve_times.saturating_add(U256::from(weight) * U256::from(solve_time));
+			sum_difficulties = sum_difficulties.saturating_add(pair[1].difficulty);
+		}
+
+		// Sum of the weights, the weighted solve times if all blocks were on target.
+		let k = U256::from(n * (n + 1) / 2);
+		let expected = k * U256::from(target);
+		// Don't let the weighted solve times go below a tenth of the target, which would make
+		// the difficulty jump more than ten times its average.
+		let weighted_solve_times = weighted_solve_times.max(expected / 10);
+
+		let next = sum_difficulties.saturating_mul(expected) /
+			weighted_solve_times.saturating_mul(U256::from(n));
+		next.max(self.min_difficulty)
+	}
+}
+
+/// Exponential moving average of the solve times, only looking at the parent block.
+///
+/// This responds to hash rate changes about as fast as an [`Lwma`] with twice the `window`.
+#[derive(Clone, Debug)]
+pub struct Ema {
+	/// Target block time, in milliseconds.
+	pub target_block_time: u64,
+	/// Smoothing window, in blocks. Values below 2 are treated as 2.
+	pub window: u64,
+	/// Lower bound of the returned difficulty, also used when there are not enough samples.
+	pub min_difficulty: U256,
+}
+
+impl DifficultyAdjustment for Ema {
+	fn samples(&self) -> usize {
+		2
+	}
+
+	fn next_difficulty(&self, samples: &[DifficultySample]) -> U256 {
+		let (previous, parent) = match samples {
+			[.., previous, parent] => (previous, parent),
+			[parent] => return parent.difficulty.max(self.min_difficulty),
+			[] => return self.min_difficulty,
+		};
+
+		let target = self.target_block_time.max(1);
+		let window = self.window.max(2);
+		let solve_time = solve_time(previous, parent, target);
+
+		// next = difficulty * window * target / (window * target + solve_time - target)
+		let expected = U256::from(window) * U256::from(target);
+		let actual = expected + U256::from(solve_time) - U256::from(target);
+		let next = parent.difficulty.saturating_mul(expected) / actual;
+		next.max(self.min_difficulty)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TARGET: u64 = 6_000;
+
+	fn samples(solve_times: &[u64], difficulty: u64) -> Vec<DifficultySample> {
+		let mut timestamp = 1_000_000;
+		let mut samples = vec![DifficultySample { timestamp, difficulty: difficulty.into() }];
+		for solve_time in solve_times {
+			timestamp += solve_time;
+			samples.push(DifficultySample { timestamp, difficulty: difficulty.into() });
+		}
+		samples
+	}
+
+	#[test]
+	fn lwma_adjusts_difficulty() {
+		let lwma = Lwma { target_block_time: TARGET, window: 10, min_difficulty: 100.into() };
+
+		// on target, the difficulty stays the same.
+		let on_target = samples(&[TARGET; 20], 1_000_000);
+		assert_eq!(lwma.next_difficulty(&on_target), 1_000_000.into());
+
+		// blocks twice as fast double the difficulty, and the other way around.
+		let fast = samples(&[TARGET / 2; 10], 1_000_000);
+		assert_eq!(lwma.next_difficulty(&fast), 2_000_000.into());
+		let slow = samples(&[TARGET * 2; 10], 1_000_000);
+		assert_eq!(lwma.next_difficulty(&slow), 500_000.into());
+
+		// recent solve times weigh more.
+		let recently_fast = samples(&[&[TARGET; 5][..], &[TARGET / 2; 5][..]].concat(), 1_000_000);
+		let recently_slow = samples(&[&[TARGET / 2; 5][..], &[TARGET; 5][..]].concat(), 1_000_000);
+		assert!(lwma.next_difficulty(&recently_fast) > lwma.next_difficulty(&recently_slow));
+	}
+
+	#[test]
+	fn lwma_bounds_difficulty() {
+		let lwma = Lwma { target_block_time: TARGET, window: 10, min_difficulty: 100.into() };
+
+		// not enough samples.
+		assert_eq!(lwma.next_difficulty(&[]), 100.into());
+		assert_eq!(lwma.next_difficulty(&samples(&[], 1_000)), 1_000.into());
+
+		// identical timestamps are limited to a tenfold increase.
+		let instant = samples(&[0; 10], 1_000);
+		assert_eq!(lwma.next_difficulty(&instant), 10_000.into());
+
+		// very slow blocks don't go below the minimum.
+		let slow = samples(&[TARGET * 100; 10], 200);
+		assert_eq!(lwma.next_difficulty(&slow), 100.into());
+	}
+
+	#[test]
+	fn ema_adjusts_difficulty() {
+		let ema = Ema { target_block_time: TARGET, window: 10, min_difficulty: 100.into() };
+
+		assert_eq!(ema.next_difficulty(&samples(&[TARGET], 1_000_000)), 1_000_000.into());
+		// a fast block increases the difficulty by about a tenth of the deviation.
+		let fast = ema.next_difficulty(&samples(&[0], 1_000_000));
+		assert_eq!(fast, 1_111_090.into());
+		// a slow block decreases it, limited by the maximum solve time.
+		let slow = ema.next_difficulty(&samples(&[TARGET * 100], 1_000_000));
+		assert_eq!(slow, 666_666.into());
+
+		// not enough samples.
+		assert_eq!(ema.next_difficulty(&[]), 100.into());
+		assert_eq!(ema.next_difficulty(&samples(&[], 1_000)), 1_000.into());
+	}
+}
@@ -29,7 +29,12 @@
 //! the worker handle, you can pull the metadata needed to start the
 //! mining process via [`MiningHandle::metadata`], and then do the actual
 //! mining on a standalone thread. Finally, when a seal is found, call
-//! [`MiningHandle::submit`] to build the block.
+//! [`MiningHandle::submit`] to build the block. To mine in external
+//! processes instead, serve the handle with [`stratum::start_stratum_server`],
+//! which pushes jobs to every connected miner.
+//!
+//! The [`difficulty`] module provides reference difficulty adjustment
+//! algorithms which [`PowAlgorithm::difficulty`] implementations can use.
 //!
 //! The auxiliary storage for PoW engine only stores the total difficulty.
 //! For other storage requirements for particular PoW algorithm (such as
@@ -39,6 +44,8 @@
 //! as the storage, but it is not recommended as it won't work well with light
 //! clients.
 
+pub mod difficulty;
+pub mod stratum;
 mod worker;
 
 pub use crate::worker::{MiningBuild, MiningHandle, MiningMetadata};
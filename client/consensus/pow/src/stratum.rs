@@ -0,0 +1,689 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Stratum-like TCP protocol serving mining jobs to external miner processes.
+//!
+//! Messages are JSON objects, one per line. A miner first sends
+//!
+//! `{"id": 1, "method": "mining.subscribe"}`
+//!
+//! after which it receives a `{"method": "mining.notify", "params": job}` notification every
+//! time the job changes, where `job` is a [`MiningJob`] or `null` if there is nothing to mine.
+//! Each subscribed miner gets a distinct `workerIndex` out of `workerCount`, which it should use
+//! to split the search space (e.g. only trying nonces equal to its index modulo the count) so
+//! miners don't duplicate work. Seals are submitted with
+//!
+//! `{"id": 2, "method": "mining.submit", "params": {"jobId": 3, "seal": "0x.."}}`
+//!
+//! and answered with `{"id": 2, "result": true}` if the block was imported. Errors are answered
+//! with `{"id": .., "error": "<message>"}`.
+//!
+//! When the server is started with a secret, a miner must first authorize with
+//!
+//! `{"id": 0, "method": "mining.authorize", "params": {"secret": ".."}}`
+//!
+//! and any other request is answered with an `"Unauthorized"` error until it does.
+//!
+//! The server accepts at most [`MAX_CONNECTIONS`] miners and [`MAX_REQUEST_LEN`] bytes per
+//! request. Miners which don't read their messages are disconnected after [`WRITE_TIMEOUT`].
+
+use codec::Encode;
+use futures::{
+	channel::{mpsc, oneshot},
+	future::{self, BoxFuture, Shared},
+	FutureExt, SinkExt, Stream, StreamExt,
+};
+use log::*;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sp_core::{traits::SpawnNamed, Bytes};
+use sp_runtime::traits::Block as BlockT;
+use std::{
+	collections::BTreeMap,
+	io,
+	net::{SocketAddr, ToSocketAddrs},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+use tokio::{
+	io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+	net::{
+		tcp::{OwnedReadHalf, OwnedWriteHalf},
+		TcpListener, TcpStream,
+	},
+};
+
+use crate::{MiningHandle, PowAlgorithm};
+
+/// Maximum number of miners connected at the same time.
+pub const MAX_CONNECTIONS: usize = 128;
+
+/// Maximum length of a request, in bytes.
+pub const MAX_REQUEST_LEN: usize = 16 * 1024;
+
+/// Time after which a write to a miner which doesn't read is given up, closing its connection.
+pub const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of messages waiting to be written to a miner.
+const MAX_QUEUED_MESSAGES: usize = 16;
+
+/// A mining job, as sent to a subscribed miner.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MiningJob {
+	/// Identifier of the job, to be passed back when submitting a seal.
+	pub job_id: u64,
+	/// SCALE-encoded hash of the block the job builds on.
+	pub best_hash: Bytes,
+	/// SCALE-encoded mining pre-hash.
+	pub pre_hash: Bytes,
+	/// Pre-runtime digest item.
+	pub pre_runtime: Option<Bytes>,
+	/// SCALE-encoded target difficulty.
+	pub difficulty: Bytes,
+	/// Index of the miner among the subscribed miners.
+	pub worker_index: u32,
+	/// Number of subscribed miners.
+	pub worker_count: u32,
+}
+
+#[derive(Deserialize)]
+struct Request {
+	#[serde(default)]
+	id: Value,
+	method: String,
+	#[serde(default)]
+	params: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmitParams {
+	job_id: u64,
+	seal: Bytes,
+}
+
+#[derive(Deserialize)]
+struct AuthorizeParams {
+	secret: String,
+}
+
+/// The mining operations the server relies on.
+trait JobSource: Send + Sync + 'static {
+	/// Identifier of the current job.
+	fn job_id(&self) -> u64;
+
+	/// The current job, if any, with zero worker index and count.
+	fn job(&self) -> Option<MiningJob>;
+
+	/// Submit a seal for the current job, returning whether the block was imported.
+	///
+	/// This blocks until the block is imported.
+	fn submit(&self, seal: Vec<u8>) -> bool;
+}
+
+impl<Block, Algorithm, C, L, Proof> JobSource for MiningHandle<Block, Algorithm, C, L, Proof>
+where
+	Block: BlockT,
+	C: sp_api::ProvideRuntimeApi<Block>,
+	Algorithm: PowAlgorithm<Block>,
+	Algorithm::Difficulty: 'static + Send,
+	L: sc_consensus::JustificationSyncLink<Block>,
+	sp_api::TransactionFor<C, Block>: Send + 'static,
+	Self: Send + Sync + 'static,
+{
+	fn job_id(&self) -> u64 {
+		self.version().as_u64()
+	}
+
+	fn job(&self) -> Option<MiningJob> {
+		let job_id = self.job_id();
+		self.metadata().map(|metadata| MiningJob {
+			job_id,
+			best_hash: metadata.best_hash.encode().into(),
+			pre_hash: metadata.pre_hash.encode().into(),
+			pre_runtime: metadata.pre_runtime.map(Into::into),
+			difficulty: metadata.difficulty.encode().into(),
+			worker_index: 0,
+			worker_count: 0,
+		})
+	}
+
+	fn submit(&self, seal: Vec<u8>) -> bool {
+		futures::executor::block_on(MiningHandle::submit(self, seal))
+	}
+}
+
+/// Queue of the messages to write to a miner.
+type Outbox = mpsc::Sender<Value>;
+
+/// Resolves once the server is stopped.
+type StopSignal = Shared<oneshot::Receiver<()>>;
+
+/// Handle to a running stratum server.
+///
+/// The server, including the connections of all its miners, stops when this is dropped.
+pub struct StratumServer {
+	local_addr: SocketAddr,
+	_stop: oneshot::Sender<()>,
+}
+
+impl StratumServer {
+	/// The address the server is listening on.
+	pub fn local_addr(&self) -> SocketAddr {
+		self.local_addr
+	}
+}
+
+struct Server<S> {
+	source: S,
+	/// Secret miners must authorize with, if any.
+	secret: Option<String>,
+	spawner: Box<dyn SpawnNamed>,
+	/// Subscribed miners, by connection id.
+	miners: Mutex<BTreeMap<u64, Outbox>>,
+	/// Number of open connections.
+	connections: AtomicUsize,
+}
+
+impl<S: JobSource> Server<S> {
+	/// Send the current job to all subscribed miners, each with its own worker index.
+	fn broadcast(&self) {
+		let job = self.source.job();
+		let miners = self.miners.lock().values().cloned().collect::<Vec<_>>();
+		let worker_count = miners.len() as u32;
+
+		for (worker_index, mut outbox) in miners.into_iter().enumerate() {
+			let job = job.clone().map(|job| MiningJob {
+				worker_index: worker_index as u32,
+				worker_count,
+				..job
+			});
+			// The queue is only full if the miner stopped reading, in which case its writes time
+			// out and its connection is closed.
+			let _ = outbox.try_send(json!({ "method": "mining.notify", "params": job }));
+		}
+	}
+
+	/// Submit a seal on a blocking task, so that the import doesn't hold up other tasks.
+	async fn submit(self: &Arc<Self>, seal: Vec<u8>) -> bool {
+		let (result, imported) = oneshot::channel();
+		let server = self.clone();
+		self.spawner.spawn_blocking(
+			"pow-stratum-submit",
+			Some("pow"),
+			async move {
+				let _ = result.send(server.source.submit(seal));
+			}
+			.boxed(),
+		);
+		imported.await.unwrap_or(false)
+	}
+
+	async fn handle_request(
+		self: &Arc<Self>,
+		connection: u64,
+		outbox: &Outbox,
+		authorized: &mut bool,
+		request: Request,
+	) -> Value {
+		if request.method == "mining.authorize" {
+			let secret = match serde_json::from_value::<AuthorizeParams>(request.params) {
+				Ok(params) => params.secret,
+				Err(err) => {
+					let error = format!("Invalid params: {}", err);
+					return json!({ "id": request.id, "error": error })
+				},
+			};
+			*authorized = self.secret.as_ref().map_or(true, |expected| *expected == secret);
+			return if *authorized {
+				json!({ "id": request.id, "result": true })
+			} else {
+				json!({ "id": request.id, "error": "Invalid secret" })
+			}
+		}
+		if !*authorized {
+			return json!({ "id": request.id, "error": "Unauthorized" })
+		}
+
+		match &request.method[..] {
+			"mining.subscribe" => {
+				self.miners.lock().insert(connection, outbox.clone());
+				json!({ "id": request.id, "result": true })
+			},
+			"mining.submit" => {
+				let params = match serde_json::from_value::<SubmitParams>(request.params) {
+					Ok(params) => params,
+					Err(err) => {
+						let error = format!("Invalid params: {}", err);
+						return json!({ "id": request.id, "error": error })
+					},
+				};
+				if params.job_id != self.source.job_id() {
+					return json!({ "id": request.id, "error": "Stale job" })
+				}
+				let imported = self.submit(params.seal.0).await;
+				json!({ "id": request.id, "result": imported })
+			},
+			method => json!({ "id": request.id, "error": format!("Unknown method: {}", method) }),
+		}
+	}
+
+	/// Read and answer the requests of a miner, until it disconnects.
+	async fn read_requests(
+		self: &Arc<Self>,
+		connection: u64,
+		mut outbox: Outbox,
+		reader: OwnedReadHalf,
+	) -> io::Result<()> {
+		let mut reader = BufReader::new(reader);
+		let mut authorized = self.secret.is_none();
+		let mut line = String::new();
+		loop {
+			line.clear();
+			let read = (&mut reader).take(MAX_REQUEST_LEN as u64).read_line(&mut line).await?;
+			if read == 0 {
+				return Ok(())
+			}
+			if read == MAX_REQUEST_LEN && !line.ends_with('\n') {
+				return Err(io::Error::new(io::ErrorKind::InvalidData, "request too long"))
+			}
+			if line.trim().is_empty() {
+				continue
+			}
+
+			let (response, subscribed) = match serde_json::from_str::<Request>(&line) {
+				Ok(request) => {
+					let subscribe = request.method == "mining.subscribe";
+					let response =
+						self.handle_request(connection, &outbox, &mut authorized, request).await;
+					let subscribed = subscribe && response.get("result").is_some();
+					(response, subscribed)
+				},
+				Err(err) => {
+					let error = format!("Invalid request: {}", err);
+					(json!({ "id": null, "error": error }), false)
+				},
+			};
+			outbox
+				.send(response)
+				.await
+				.map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "writer stopped"))?;
+			if subscribed {
+				// Everyone's worker index may have changed.
+				self.broadcast();
+			}
+		}
+	}
+
+	/// Serve a miner until it disconnects, fails to read its messages in time, or the server
+	/// stops.
+	async fn handle_connection(self: Arc<Self>, connection: u64, stream: TcpStream) {
+		let (reader, writer) = stream.into_split();
+		// The queue is bounded, so that a miner which doesn't read can't make it grow, and
+		// written concurrently with the reading, so that neither the broadcast nor the requests
+		// of other miners wait for a slow miner.
+		let (outbox, messages) = mpsc::channel::<Value>(MAX_QUEUED_MESSAGES);
+
+		let reading = self.read_requests(connection, outbox, reader);
+		let writing = write_messages(messages, writer);
+		let result = match future::select(reading.boxed(), writing.boxed()).await {
+			future::Either::Left((result, _)) | future::Either::Right((result, _)) => result,
+		};
+		if let Err(err) = result {
+			debug!(target: "pow", "Stratum connection {} closed: {}", connection, err);
+		}
+
+		if self.miners.lock().remove(&connection).is_some() {
+			self.broadcast();
+		}
+	}
+
+	/// Accept miners until the server stops.
+	async fn accept_connections(self: Arc<Self>, listener: TcpListener, stop: StopSignal) {
+		let mut next_connection = 0u64;
+		loop {
+			let stream = match listener.accept().await {
+				Ok((stream, _)) => stream,
+				Err(err) => {
+					warn!(target: "pow", "Failed to accept stratum connection: {}", err);
+					continue
+				},
+			};
+			if self.connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+				self.connections.fetch_sub(1, Ordering::SeqCst);
+				warn!(
+					target: "pow",
+					"Rejected stratum connection from {:?}: too many connections",
+					stream.peer_addr(),
+				);
+				continue
+			}
+			let connection = next_connection;
+			next_connection += 1;
+
+			let server = self.clone();
+			let stop = stop.clone();
+			self.spawner.spawn(
+				"pow-stratum-miner",
+				Some("pow"),
+				async move {
+					future::select(
+						server.clone().handle_connection(connection, stream).boxed(),
+						stop,
+					)
+					.await;
+					server.connections.fetch_sub(1, Ordering::SeqCst);
+				}
+				.boxed(),
+			);
+		}
+	}
+}
+
+/// Write `messages` to a miner, until one can't be written within [`WRITE_TIMEOUT`].
+async fn write_messages(
+	mut messages: mpsc::Receiver<Value>,
+	mut writer: OwnedWriteHalf,
+) -> io::Result<()> {
+	while let Some(message) = messages.next().await {
+		let mut line = message.to_string();
+		line.push('\n');
+		tokio::time::timeout(WRITE_TIMEOUT, writer.write_all(line.as_bytes()))
+			.await
+			.map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "write timed out"))??;
+	}
+	Ok(())
+}
+
+/// Start serving mining jobs of `handle` to external miners connecting to `addr`.
+///
+/// If a `secret` is given, miners must authorize with it before subscribing or submitting seals.
+/// The server runs as tasks of `spawner`, which must be driven by a tokio runtime, until the
+/// returned [`StratumServer`] is dropped.
+pub fn start_stratum_server<Block, Algorithm, C, L, Proof>(
+	handle: MiningHandle<Block, Algorithm, C, L, Proof>,
+	addr: impl ToSocketAddrs,
+	secret: Option<String>,
+	spawner: impl SpawnNamed + 'static,
+) -> io::Result<StratumServer>
+where
+	Block: BlockT,
+	C: sp_api::ProvideRuntimeApi<Block>,
+	Algorithm: PowAlgorithm<Block>,
+	Algorithm::Difficulty: 'static + Send,
+	L: sc_consensus::JustificationSyncLink<Block>,
+	sp_api::TransactionFor<C, Block>: Send + 'static,
+	MiningHandle<Block, Algorithm, C, L, Proof>: Send + Sync + 'static,
+{
+	let jobs = handle.job_notification_stream();
+	start_server(handle, jobs, addr, secret, Box::new(spawner))
+}
+
+fn start_server<S: JobSource>(
+	source: S,
+	jobs: impl Stream<Item = ()> + Send + 'static,
+	addr: impl ToSocketAddrs,
+	secret: Option<String>,
+	spawner: Box<dyn SpawnNamed>,
+) -> io::Result<StratumServer> {
+	// Bound here rather than in the accepting task, so that binding errors are returned.
+	let listener = std::net::TcpListener::bind(addr)?;
+	listener.set_nonblocking(true)?;
+	let local_addr = listener.local_addr()?;
+
+	let (stop_sender, stop) = oneshot::channel();
+	let stop = stop.shared();
+	let server = Arc::new(Server {
+		source,
+		secret,
+		spawner,
+		miners: Mutex::new(BTreeMap::new()),
+		connections: AtomicUsize::new(0),
+	});
+
+	let notifier = server.clone();
+	let notify = jobs.for_each(move |()| {
+		notifier.broadcast();
+		future::ready(())
+	});
+	server.spawner.spawn(
+		"pow-stratum-notify",
+		Some("pow"),
+		future::select(notify.boxed(), stop.clone()).map(drop).boxed(),
+	);
+
+	let accept: BoxFuture<'static, ()> = {
+		let server = server.clone();
+		let stop = stop.clone();
+		async move {
+			let listener = match TcpListener::from_std(listener) {
+				Ok(listener) => listener,
+				Err(err) => {
+					warn!(target: "pow", "Failed to listen for stratum connections: {}", err);
+					return
+				},
+			};
+			server.accept_connections(listener, stop).await
+		}
+		.boxed()
+	};
+	server.spawner.spawn(
+		"pow-stratum-accept",
+		Some("pow"),
+		future::select(accept, stop).map(drop).boxed(),
+	);
+
+	info!(target: "pow", "⛏  Stratum server listening on {}", local_addr);
+	Ok(StratumServer { local_addr, _stop: stop_sender })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{
+		io::{BufRead, BufReader, Write},
+		net::TcpStream,
+		sync::atomic::AtomicU64,
+	};
+
+	#[derive(Default)]
+	struct TestSource {
+		job_id: AtomicU64,
+	}
+
+	impl JobSource for Arc<TestSource> {
+		fn job_id(&self) -> u64 {
+			self.job_id.load(Ordering::SeqCst)
+		}
+
+		fn job(&self) -> Option<MiningJob> {
+			Some(MiningJob {
+				job_id: self.job_id(),
+				best_hash: vec![1].into(),
+				pre_hash: vec![2].into(),
+				pre_runtime: None,
+				difficulty: vec![3].into(),
+				worker_index: 0,
+				worker_count: 0,
+			})
+		}
+
+		fn submit(&self, seal: Vec<u8>) -> bool {
+			seal == b"good"
+		}
+	}
+
+	#[derive(Clone)]
+	struct TestSpawner(tokio::runtime::Handle);
+
+	impl SpawnNamed for TestSpawner {
+		fn spawn_blocking(
+			&self,
+			_: &'static str,
+			_: Option<&'static str>,
+			future: BoxFuture<'static, ()>,
+		) {
+			self.0.spawn_blocking(move || futures::executor::block_on(future));
+		}
+
+		fn spawn(&self, _: &'static str, _: Option<&'static str>, future: BoxFuture<'static, ()>) {
+			self.0.spawn(future);
+		}
+	}
+
+	fn start(
+		runtime: &tokio::runtime::Runtime,
+		source: Arc<TestSource>,
+		secret: Option<&str>,
+	) -> (StratumServer, mpsc::Sender<()>) {
+		let (notify, jobs) = mpsc::channel(0);
+		let spawner = Box::new(TestSpawner(runtime.handle().clone()));
+		let server =
+			start_server(source, jobs, "127.0.0.1:0", secret.map(Into::into), spawner).unwrap();
+		(server, notify)
+	}
+
+	struct Miner {
+		stream: TcpStream,
+		reader: BufReader<TcpStream>,
+	}
+
+	impl Miner {
+		fn connect(addr: SocketAddr) -> Self {
+			let stream = TcpStream::connect(addr).unwrap();
+			stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+			let reader = BufReader::new(stream.try_clone().unwrap());
+			Self { stream, reader }
+		}
+
+		fn send(&mut self, request: Value) {
+			writeln!(self.stream, "{}", request).unwrap();
+		}
+
+		fn receive(&mut self) -> Value {
+			let mut line = String::new();
+			self.reader.read_line(&mut line).unwrap();
+			serde_json::from_str(&line).unwrap()
+		}
+
+		fn receive_job(&mut self) -> (u64, u32, u32) {
+			let notification = self.receive();
+			assert_eq!(notification["method"], "mining.notify");
+			let job: MiningJob = serde_json::from_value(notification["params"].clone()).unwrap();
+			(job.job_id, job.worker_index, job.worker_count)
+		}
+
+		fn is_disconnected(&mut self) -> bool {
+			let mut line = String::new();
+			matches!(self.reader.read_line(&mut line), Ok(0) | Err(_))
+		}
+	}
+
+	#[test]
+	fn miners_subscribe_get_notified_and_submit() {
+		let runtime = tokio::runtime::Runtime::new().unwrap();
+		let source = Arc::new(TestSource::default());
+		let (server, mut notify) = start(&runtime, source.clone(), None);
+
+		let mut first = Miner::connect(server.local_addr());
+		first.send(json!({ "id": 1, "method": "mining.subscribe" }));
+		assert_eq!(first.receive(), json!({ "id": 1, "result": true }));
+		assert_eq!(first.receive_job(), (0, 0, 1));
+
+		// Subscribing splits the work among the miners.
+		let mut second = Miner::connect(server.local_addr());
+		second.send(json!({ "id": 1, "method": "mining.subscribe" }));
+		assert_eq!(second.receive(), json!({ "id": 1, "result": true }));
+		assert_eq!(first.receive_job(), (0, 0, 2));
+		assert_eq!(second.receive_job(), (0, 1, 2));
+
+		// New jobs are pushed to every miner.
+		source.job_id.store(1, Ordering::SeqCst);
+		futures::executor::block_on(notify.send(())).unwrap();
+		assert_eq!(first.receive_job(), (1, 0, 2));
+		assert_eq!(second.receive_job(), (1, 1, 2));
+
+		let submit = |id: u64, job_id: u64, seal: &[u8]| {
+			json!({
+				"id": id,
+				"method": "mining.submit",
+				"params": { "jobId": job_id, "seal": Bytes(seal.to_vec()) },
+			})
+		};
+		first.send(submit(2, 0, b"good"));
+		assert_eq!(first.receive(), json!({ "id": 2, "error": "Stale job" }));
+		first.send(submit(3, 1, b"bad"));
+		assert_eq!(first.receive(), json!({ "id": 3, "result": false }));
+		first.send(submit(4, 1, b"good"));
+		assert_eq!(first.receive(), json!({ "id": 4, "result": true }));
+
+		// Oversized requests close the connection, and the work is split again.
+		second.stream.write_all(&[b'x'; MAX_REQUEST_LEN + 1]).unwrap();
+		assert!(second.is_disconnected());
+		assert_eq!(first.receive_job(), (1, 0, 1));
+	}
+
+	#[test]
+	fn miners_must_authorize_with_the_secret() {
+		let runtime = tokio::runtime::Runtime::new().unwrap();
+		let (server, _notify) = start(&runtime, Default::default(), Some("secret"));
+
+		let mut miner = Miner::connect(server.local_addr());
+		miner.send(json!({ "id": 1, "method": "mining.subscribe" }));
+		assert_eq!(miner.receive(), json!({ "id": 1, "error": "Unauthorized" }));
+
+		let authorize = |id: u64, secret: &str| {
+			json!({ "id": id, "method": "mining.authorize", "params": { "secret": secret } })
+		};
+		miner.send(authorize(2, "wrong"));
+		assert_eq!(miner.receive(), json!({ "id": 2, "error": "Invalid secret" }));
+		miner.send(json!({ "id": 3, "method": "mining.subscribe" }));
+		assert_eq!(miner.receive(), json!({ "id": 3, "error": "Unauthorized" }));
+
+		miner.send(authorize(4, "secret"));
+		assert_eq!(miner.receive(), json!({ "id": 4, "result": true }));
+		miner.send(json!({ "id": 5, "method": "mining.subscribe" }));
+		assert_eq!(miner.receive(), json!({ "id": 5, "result": true }));
+		assert_eq!(miner.receive_job(), (0, 0, 1));
+	}
+
+	#[test]
+	fn dropping_the_server_disconnects_miners() {
+		let runtime = tokio::runtime::Runtime::new().unwrap();
+		let (server, _notify) = start(&runtime, Default::default(), None);
+		let addr = server.local_addr();
+
+		let mut miner = Miner::connect(addr);
+		miner.send(json!({ "id": 1, "method": "mining.subscribe" }));
+		assert_eq!(miner.receive(), json!({ "id": 1, "result": true }));
+		assert_eq!(miner.receive_job(), (0, 0, 1));
+
+		drop(server);
+		assert!(miner.is_disconnected());
+		// The listener is closed too, once its task notices the server stopped.
+		assert!((0..100).any(|_| {
+			std::thread::sleep(Duration::from_millis(10));
+			TcpStream::connect(addr).is_err()
+		}));
+	}
+}
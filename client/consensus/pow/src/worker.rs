@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use futures::{
+	channel::mpsc,
 	prelude::*,
 	task::{Context, Poll},
 };
@@ -73,6 +74,13 @@ pub struct MiningBuild<
 #[derive(Eq, PartialEq, Clone, Copy)]
 pub struct Version(usize);
 
+impl Version {
+	/// Numeric value of the version, used to identify mining jobs handed out to external miners.
+	pub(crate) fn as_u64(&self) -> u64 {
+		self.0 as u64
+	}
+}
+
 /// Mining worker that exposes structs to query the current mining build and submit mined blocks.
 pub struct MiningHandle<
 	Block: BlockT,
@@ -86,6 +94,7 @@ pub struct MiningHandle<
 	justification_sync_link: Arc<L>,
 	build: Arc<Mutex<Option<MiningBuild<Block, Algorithm, C, Proof>>>>,
 	block_import: Arc<Mutex<BoxBlockImport<Block, sp_api::TransactionFor<C, Block>>>>,
+	job_subscribers: Arc<Mutex<Vec<mpsc::Sender<()>>>>,
 }
 
 impl<Block, Algorithm, C, L, Proof> MiningHandle<Block, Algorithm, C, L, Proof>
@@ -99,6 +108,11 @@ where
 {
 	fn increment_version(&self) {
 		self.version.fetch_add(1, Ordering::SeqCst);
+		// A full channel already holds a notification the subscriber hasn't seen yet, so this one
+		// can be dropped.
+		self.job_subscribers.lock().retain_mut(|sink| {
+			sink.try_send(()).map_or_else(|err| !err.is_disconnected(), |()| true)
+		});
 	}
 
 	pub(crate) fn new(
@@ -112,6 +126,7 @@ where
 			justification_sync_link: Arc::new(justification_sync_link),
 			build: Arc::new(Mutex::new(None)),
 			block_import: Arc::new(Mutex::new(block_import)),
+			job_subscribers: Arc::new(Mutex::new(Vec::new())),
 		}
 	}
 
@@ -135,6 +150,17 @@ where
 		Version(self.version.load(Ordering::SeqCst))
 	}
 
+	/// Get a stream that yields every time the version of the mining worker changes, that is when
+	/// a new build is available or the current one was dropped.
+	///
+	/// This allows to push new work to miners instead of polling [`Self::version`]. Changes
+	/// happening before the stream is polled again are reported once.
+	pub fn job_notification_stream(&self) -> mpsc::Receiver<()> {
+		let (sink, stream) = mpsc::channel(0);
+		self.job_subscribers.lock().push(sink);
+		stream
+	}
+
 	/// Get the current best hash. `None` if the worker has just started or the client is doing
 	/// major syncing.
 	pub fn best_hash(&self) -> Option<Block::Hash> {
@@ -257,6 +283,7 @@ where
 			justification_sync_link: self.justification_sync_link.clone(),
 			build: self.build.clone(),
 			block_import: self.block_import.clone(),
+			job_subscribers: self.job_subscribers.clone(),
 		}
 	}
 }
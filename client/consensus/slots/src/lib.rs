@@ -26,6 +26,8 @@
 #![warn(missing_docs)]
 
 mod aux_schema;
+#[cfg(any(test, feature = "test-helpers"))]
+pub mod simulation;
 mod slots;
 
 pub use aux_schema::{check_equivocation, MAX_SLOT_CAPACITY, PRUNING_BOUND};
@@ -0,0 +1,472 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Deterministic simulation of slot-based authoring.
+//!
+//! A [`Simulation`] drives the [`SlotWorker`]s of multiple in-process authorities from a
+//! simulated clock instead of the system time. Each authority can have its clock skewed, and
+//! blocks are delivered to the other authorities with a configurable latency, or not at all.
+//! This allows testing authoring edge cases such as skipped slots, clock drift or authoring
+//! backoff, and asserting on the resulting forks and finality, without a network.
+
+use crate::{SlotInfo, SlotWorker};
+use sp_consensus_slots::{Slot, SlotDuration};
+use sp_inherents::InherentData;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use std::{
+	collections::{BTreeMap, HashSet},
+	time::{Duration, Instant},
+};
+
+/// An authority taking part in a [`Simulation`], wrapping the slot worker under test together
+/// with the client it authors on.
+#[async_trait::async_trait]
+pub trait SimulatedAuthority<B: BlockT> {
+	/// The storage proof type of the slot worker.
+	type Proof;
+	/// The slot worker under test, e.g. an Aura or BABE worker.
+	type Worker: SlotWorker<B, Self::Proof>;
+
+	/// Get the slot worker of the authority.
+	fn worker(&mut self) -> &mut Self::Worker;
+
+	/// Get the header of the best block, which is used as the chain head of new slots.
+	async fn best_header(&mut self) -> B::Header;
+
+	/// Create the inherent data for authoring in `slot`, at the given local `timestamp` in
+	/// milliseconds.
+	fn inherent_data(&mut self, slot: Slot, timestamp: u64) -> InherentData;
+
+	/// Import a block authored by another authority.
+	async fn import_block(&mut self, block: B);
+
+	/// Get the number of the last block finalized by the authority.
+	fn finalized_number(&self) -> NumberFor<B>;
+}
+
+/// A block authored during a [`Simulation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthoredBlock<B: BlockT> {
+	/// Index of the authority that authored the block.
+	pub authority: usize,
+	/// Slot the block was authored in.
+	pub slot: Slot,
+	/// Hash of the block.
+	pub hash: B::Hash,
+	/// Hash of the parent of the block.
+	pub parent_hash: B::Hash,
+	/// Number of the block.
+	pub number: NumberFor<B>,
+}
+
+enum Event<B> {
+	/// A new slot starts on the clock of the given authority.
+	Slot(usize),
+	/// A block is delivered to the given authority.
+	Deliver(usize, B),
+}
+
+struct Node<A> {
+	authority: A,
+	clock_skew: i64,
+	online: bool,
+}
+
+/// Latency of delivering blocks from one authority to another, `None` if they are not delivered.
+type Latency = Box<dyn Fn(usize, usize) -> Option<Duration>>;
+
+/// A deterministic simulation of slot-based authoring by multiple authorities.
+///
+/// Time is only advanced by [`Simulation::run_for`], which processes the slots and block
+/// deliveries falling in the given period in order.
+pub struct Simulation<B: BlockT, A> {
+	slot_duration: SlotDuration,
+	/// Simulated time, in milliseconds since the unix epoch.
+	now: u64,
+	nodes: Vec<Node<A>>,
+	latency: Latency,
+	/// Pending events, by time and insertion order.
+	events: BTreeMap<(u64, u64), Event<B>>,
+	next_event: u64,
+	authored: Vec<AuthoredBlock<B>>,
+}
+
+impl<B: BlockT, A: SimulatedAuthority<B>> Simulation<B, A> {
+	/// Create a new simulation starting at `start`, a duration since the unix epoch. Blocks are
+	/// delivered instantly, unless configured otherwise with [`Simulation::with_latency`].
+	pub fn new(slot_duration: SlotDuration, start: Duration) -> Self {
+		Simulation {
+			slot_duration,
+			now: start.as_millis() as u64,
+			nodes: Vec::new(),
+			latency: Box::new(|_, _| Some(Duration::ZERO)),
+			events: BTreeMap::new(),
+			next_event: 0,
+			authored: Vec::new(),
+		}
+	}
+
+	/// Set the latency of delivering blocks from an authority to another, given their indices.
+	/// Returning `None` drops the block, e.g. to simulate a network partition.
+	pub fn with_latency(
+		mut self,
+		latency: impl Fn(usize, usize) -> Option<Duration> + 'static,
+	) -> Self {
+		self.latency = Box::new(latency);
+		self
+	}
+
+	/// Add an authority whose clock is `clock_skew` milliseconds ahead (or behind, if negative)
+	/// of the simulated time. Returns the index of the authority.
+	pub fn add_authority(&mut self, authority: A, clock_skew: i64) -> usize {
+		let index = self.nodes.len();
+		self.nodes.push(Node { authority, clock_skew, online: true });
+
+		// schedule the first slot starting on the authority's clock.
+		let slot_duration = self.slot_duration.as_millis();
+		let local_now = self.local_time(index, self.now);
+		let slot_start = (local_now + slot_duration - 1) / slot_duration * slot_duration;
+		let at = self.now + (slot_start - local_now);
+		self.schedule(at, Event::Slot(index));
+
+		index
+	}
+
+	/// Set whether the authority with the given index is online. Offline authorities don't
+	/// author blocks, and blocks delivered to them are lost.
+	pub fn set_online(&mut self, index: usize, online: bool) {
+		self.nodes[index].online = online;
+	}
+
+	/// Get the authority with the given index.
+	pub fn authority(&self, index: usize) -> &A {
+		&self.nodes[index].authority
+	}
+
+	/// Get the authority with the given index, mutably.
+	pub fn authority_mut(&mut self, index: usize) -> &mut A {
+		&mut self.nodes[index].authority
+	}
+
+	/// Get the simulated time, as a duration since the unix epoch.
+	pub fn now(&self) -> Duration {
+		Duration::from_millis(self.now)
+	}
+
+	/// Get all blocks authored so far, in authoring order.
+	pub fn authored_blocks(&self) -> &[AuthoredBlock<B>] {
+		&self.authored
+	}
+
+	/// Get the authored blocks that no other authored block builds on. More than one leaf means
+	/// that the authorities forked.
+	pub fn leaves(&self) -> Vec<&AuthoredBlock<B>> {
+		let parents = self.authored.iter().map(|block| block.parent_hash).collect::<HashSet<_>>();
+		self.authored.iter().filter(|block| !parents.contains(&block.hash)).collect()
+	}
+
+	/// Get the number of the last finalized block of each authority.
+	pub fn finalized_numbers(&self) -> Vec<NumberFor<B>> {
+		self.nodes.iter().map(|node| node.authority.finalized_number()).collect()
+	}
+
+	/// Advance the simulated time by `duration`, running all slots and delivering all blocks in
+	/// that period.
+	pub async fn run_for(&mut self, duration: Duration) {
+		let end = self.now + duration.as_millis() as u64;
+
+		while let Some(&(at, order)) = self.events.keys().next() {
+			if at > end {
+				break
+			}
+			let event = self.events.remove(&(at, order)).expect("key was just read; qed");
+			self.now = at;
+
+			match event {
+				Event::Slot(index) => {
+					self.schedule(at + self.slot_duration.as_millis(), Event::Slot(index));
+					if self.nodes[index].online {
+						self.on_slot(index).await;
+					}
+				},
+				Event::Deliver(index, block) =>
+					if self.nodes[index].online {
+						self.nodes[index].authority.import_block(block).await;
+					},
+			}
+		}
+
+		self.now = end;
+	}
+
+	async fn on_slot(&mut self, index: usize) {
+		let slot_duration = self.slot_duration.as_millis();
+		let local_now = self.local_time(index, self.now);
+		let slot = Slot::from(local_now / slot_duration);
+		let authority = &mut self.nodes[index].authority;
+
+		// the worker measures the time left in the slot against the system clock, so give it
+		// what is left of the slot on the simulated clock of the authority.
+		let slot_end = (*slot + 1) * slot_duration;
+		let ends_at = Instant::now() + Duration::from_millis(slot_end - local_now);
+
+		let chain_head = authority.best_header().await;
+		let slot_info = SlotInfo {
+			slot,
+			ends_at,
+			inherent_data: authority.inherent_data(slot, local_now),
+			duration: self.slot_duration.as_duration(),
+			chain_head,
+			block_size_limit: None,
+		};
+
+		let block = match authority.worker().on_slot(slot_info).await {
+			Some(result) => result.block,
+			None => return,
+		};
+
+		let header = block.header();
+		self.authored.push(AuthoredBlock {
+			authority: index,
+			slot,
+			hash: header.hash(),
+			parent_hash: *header.parent_hash(),
+			number: *header.number(),
+		});
+
+		for other in (0..self.nodes.len()).filter(|other| *other != index) {
+			if let Some(latency) = (self.latency)(index, other) {
+				let at = self.now + latency.as_millis() as u64;
+				self.schedule(at, Event::Deliver(other, block.clone()));
+			}
+		}
+	}
+
+	fn local_time(&self, index: usize, time: u64) -> u64 {
+		(time as i64 + self.nodes[index].clock_skew).max(0) as u64
+	}
+
+	fn schedule(&mut self, at: u64, event: Event<B>) {
+		self.events.insert((at, self.next_event), event);
+		self.next_event += 1;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		BackoffAuthoringBlocksStrategy, BackoffAuthoringOnFinalizedHeadLagging, SlotResult,
+	};
+	use sp_core::H256;
+	use std::sync::{Arc, Mutex};
+	use substrate_test_runtime_client::runtime::{Block, Header};
+
+	const SLOT_DURATION: SlotDuration = SlotDuration::from_millis(6000);
+	const START: Duration = Duration::from_secs(1_600_000_000);
+
+	/// A chain of headers whose state root encodes the slot they were authored in.
+	struct Chain {
+		best: Header,
+		/// Blocks are finalized at this depth, never if `None`.
+		finality_depth: Option<u64>,
+	}
+
+	impl Chain {
+		fn import(&mut self, header: Header) {
+			if header.number > self.best.number {
+				self.best = header;
+			}
+		}
+	}
+
+	fn slot_of(header: &Header) -> Slot {
+		header.state_root.to_low_u64_be().into()
+	}
+
+	/// Round robin slot worker, similar to Aura.
+	struct RoundRobin {
+		index: u64,
+		authorities: u64,
+		backoff: Option<BackoffAuthoringOnFinalizedHeadLagging<u64>>,
+		chain: Arc<Mutex<Chain>>,
+	}
+
+	#[async_trait::async_trait]
+	impl SlotWorker<Block, ()> for RoundRobin {
+		async fn on_slot(&mut self, slot_info: SlotInfo<Block>) -> Option<SlotResult<Block, ()>> {
+			if *slot_info.slot % self.authorities != self.index {
+				return None
+			}
+
+			let mut chain = self.chain.lock().unwrap();
+			let head = &slot_info.chain_head;
+			let finalized =
+				chain.finality_depth.map_or(0, |depth| head.number.saturating_sub(depth));
+			if let Some(backoff) = &self.backoff {
+				if backoff.should_backoff(
+					head.number,
+					slot_of(head),
+					finalized,
+					slot_info.slot,
+					"test",
+				) {
+					return None
+				}
+			}
+
+			let header = Header::new(
+				head.number + 1,
+				Default::default(),
+				H256::from_low_u64_be(*slot_info.slot),
+				head.hash(),
+				Default::default(),
+			);
+			chain.import(header.clone());
+
+			Some(SlotResult { block: Block { header, extrinsics: Vec::new() }, storage_proof: () })
+		}
+	}
+
+	struct TestAuthority {
+		worker: RoundRobin,
+		chain: Arc<Mutex<Chain>>,
+	}
+
+	#[async_trait::async_trait]
+	impl SimulatedAuthority<Block> for TestAuthority {
+		type Proof = ();
+		type Worker = RoundRobin;
+
+		fn worker(&mut self) -> &mut RoundRobin {
+			&mut self.worker
+		}
+
+		async fn best_header(&mut self) -> Header {
+			self.chain.lock().unwrap().best.clone()
+		}
+
+		fn inherent_data(&mut self, _slot: Slot, _timestamp: u64) -> InherentData {
+			InherentData::new()
+		}
+
+		async fn import_block(&mut self, block: Block) {
+			self.chain.lock().unwrap().import(block.header);
+		}
+
+		fn finalized_number(&self) -> u64 {
+			let chain = self.chain.lock().unwrap();
+			chain.finality_depth.map_or(0, |depth| chain.best.number.saturating_sub(depth))
+		}
+	}
+
+	fn new_simulation(
+		authorities: u64,
+		clock_skews: &[i64],
+		finality_depth: Option<u64>,
+		backoff: Option<BackoffAuthoringOnFinalizedHeadLagging<u64>>,
+	) -> Simulation<Block, TestAuthority> {
+		let genesis = Header::new(
+			0,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		let mut simulation = Simulation::new(SLOT_DURATION, START);
+
+		for index in 0..authorities {
+			let chain = Arc::new(Mutex::new(Chain { best: genesis.clone(), finality_depth }));
+			let worker =
+				RoundRobin { index, authorities, backoff: backoff.clone(), chain: chain.clone() };
+			let clock_skew = clock_skews.get(index as usize).copied().unwrap_or_default();
+			simulation.add_authority(TestAuthority { worker, chain }, clock_skew);
+		}
+
+		simulation
+	}
+
+	fn slots(n: u32) -> Duration {
+		SLOT_DURATION.as_duration() * n
+	}
+
+	#[test]
+	fn authorities_build_a_single_chain() {
+		let mut simulation = new_simulation(3, &[], Some(2), None);
+		futures::executor::block_on(simulation.run_for(slots(30)));
+
+		// every slot has been claimed, in turn, on top of the previous block.
+		let authored = simulation.authored_blocks();
+		assert_eq!(authored.len(), 30);
+		assert!(authored.iter().enumerate().all(|(i, block)| block.number == i as u64 + 1));
+		assert!(authored.iter().all(|block| *block.slot % 3 == block.authority as u64));
+		assert_eq!(simulation.leaves().len(), 1);
+		assert_eq!(simulation.finalized_numbers(), vec![28, 28, 28]);
+
+		// an offline authority skips its slots, which doesn't fork the chain.
+		simulation.set_online(1, false);
+		futures::executor::block_on(simulation.run_for(slots(30)));
+		assert_eq!(simulation.authored_blocks().len(), 50);
+		assert_eq!(simulation.leaves().len(), 1);
+		assert_eq!(simulation.finalized_numbers(), vec![48, 28, 48]);
+	}
+
+	#[test]
+	fn latency_and_clock_skew_cause_forks() {
+		// blocks are received after the next slot started.
+		let mut simulation = new_simulation(3, &[], Some(2), None)
+			.with_latency(|_, _| Some(SLOT_DURATION.as_duration() * 3 / 2));
+		futures::executor::block_on(simulation.run_for(slots(30)));
+		assert_eq!(simulation.authored_blocks().len(), 30);
+		assert!(simulation.leaves().len() > 1);
+
+		// blocks are never received.
+		let mut simulation = new_simulation(3, &[], Some(2), None).with_latency(|_, _| None);
+		futures::executor::block_on(simulation.run_for(slots(30)));
+		assert_eq!(simulation.leaves().len(), 3);
+		assert_eq!(simulation.finalized_numbers(), vec![8, 8, 8]);
+
+		// the clock of the second authority is a slot ahead, so it claims its slots at the same
+		// time as the first authority.
+		let skew = SLOT_DURATION.as_millis() as i64;
+		let mut simulation = new_simulation(2, &[0, skew], Some(2), None);
+		futures::executor::block_on(simulation.run_for(slots(30)));
+		assert_eq!(simulation.authored_blocks().len(), 30);
+		assert!(simulation.leaves().len() > 1);
+	}
+
+	#[test]
+	fn authoring_backs_off_when_finality_stalls() {
+		let backoff = BackoffAuthoringOnFinalizedHeadLagging {
+			max_interval: 100,
+			unfinalized_slack: 5,
+			authoring_bias: 2,
+		};
+
+		let mut simulation = new_simulation(3, &[], None, Some(backoff.clone()));
+		futures::executor::block_on(simulation.run_for(slots(100)));
+		assert!(simulation.authored_blocks().len() < 50);
+		assert_eq!(simulation.leaves().len(), 1);
+		assert_eq!(simulation.finalized_numbers(), vec![0, 0, 0]);
+
+		// no backoff as long as finality keeps up.
+		let mut simulation = new_simulation(3, &[], Some(2), Some(backoff));
+		futures::executor::block_on(simulation.run_for(slots(100)));
+		assert_eq!(simulation.authored_blocks().len(), 100);
+	}
+}
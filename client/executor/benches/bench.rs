@@ -73,6 +73,7 @@ fn initialize(
 					canonicalize_nans: false,
 					parallel_compilation: true,
 					max_memory_size: None,
+					consume_fuel: false,
 				},
 			};
 
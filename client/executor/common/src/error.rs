@@ -107,6 +107,9 @@ pub enum Error {
 
 	#[error("Execution aborted due to trap: {0}")]
 	AbortedDueToTrap(MessageWithBacktrace),
+
+	#[error("Execution aborted after consuming all of its {0} units of fuel")]
+	OutOfFuel(u64),
 }
 
 impl wasmi::HostError for Error {}
@@ -22,6 +22,7 @@
 #![deny(unused_crate_dependencies)]
 
 pub mod error;
+pub mod profiler;
 pub mod runtime_blob;
 pub mod sandbox;
 pub mod util;
@@ -0,0 +1,233 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Profiler for calls into the runtime.
+//!
+//! When compiled with the `host-function-profiling` feature, the executors record for every call
+//! into a runtime instance how long it took, how often and for how long each host function was
+//! called and the peak number of bytes allocated on the wasm heap. Recording only takes place
+//! between [`start`] and [`finish`]; without the feature [`start`] fails and nothing is recorded.
+//!
+//! The resulting [`Profile`] can be rendered as JSON or as folded stacks, the input format of
+//! most flamegraph tools.
+
+use crate::wasm_runtime::InvokeMethod;
+use sc_allocator::AllocationStats;
+use serde::Serialize;
+use std::{
+	cell::RefCell,
+	collections::BTreeMap,
+	fmt::Write,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Mutex,
+	},
+	time::Instant,
+};
+
+/// Whether the executors were compiled with profiling support.
+pub const IS_SUPPORTED: bool = cfg!(feature = "host-function-profiling");
+
+static RECORDING: AtomicBool = AtomicBool::new(false);
+static RECORDED: Mutex<Vec<CallProfile>> = Mutex::new(Vec::new());
+
+thread_local! {
+	/// The calls into the runtime currently executing on this thread, innermost last.
+	///
+	/// Host functions may call into a runtime again, e.g. to read its version.
+	static ACTIVE_CALLS: RefCell<Vec<CallProfile>> = RefCell::new(Vec::new());
+}
+
+/// Statistics of a single host function.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostFunctionProfile {
+	/// How often the host function was called.
+	pub calls: u64,
+	/// The time spent in the host function, in nanoseconds.
+	pub duration_nanos: u64,
+}
+
+/// Profile of a single call into the runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallProfile {
+	/// The name of the called export.
+	pub method: String,
+	/// The duration of the call, in nanoseconds.
+	pub duration_nanos: u64,
+	/// The peak number of bytes allocated on the wasm heap during the call.
+	pub peak_heap_bytes: u32,
+	/// The host functions called, by name.
+	pub host_functions: BTreeMap<String, HostFunctionProfile>,
+}
+
+impl CallProfile {
+	/// Time spent in the runtime itself, i.e. not within any host function.
+	pub fn self_duration_nanos(&self) -> u64 {
+		let host = self.host_functions.values().map(|f| f.duration_nanos).sum::<u64>();
+		self.duration_nanos.saturating_sub(host)
+	}
+}
+
+/// Everything recorded between [`start`] and [`finish`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+	/// The calls into the runtime, in the order they finished.
+	pub calls: Vec<CallProfile>,
+}
+
+impl Profile {
+	/// Renders the profile as pretty printed JSON.
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self).expect("the profile only contains plain data; qed")
+	}
+
+	/// Renders the profile as folded stacks with nanoseconds as the unit.
+	///
+	/// Every call contributes `method` for the time spent in the runtime and `method;host_function`
+	/// for the time spent in each host function. Identical stacks are summed up.
+	pub fn to_folded_stacks(&self) -> String {
+		let mut stacks = BTreeMap::<String, u64>::new();
+		for call in &self.calls {
+			*stacks.entry(call.method.clone()).or_default() += call.self_duration_nanos();
+			for (name, function) in &call.host_functions {
+				*stacks.entry(format!("{};{}", call.method, name)).or_default() +=
+					function.duration_nanos;
+			}
+		}
+
+		let mut folded = String::new();
+		for (stack, nanos) in stacks {
+			let _ = writeln!(folded, "{} {}", stack, nanos);
+		}
+		folded
+	}
+}
+
+/// Starts recording calls into the runtime, discarding anything recorded before.
+///
+/// Fails if the executors were not compiled with the `host-function-profiling` feature.
+pub fn start() -> Result<(), &'static str> {
+	if !IS_SUPPORTED {
+		return Err("the executor was compiled without the `host-function-profiling` feature")
+	}
+	RECORDED.lock().expect("never poisoned; qed").clear();
+	RECORDING.store(true, Ordering::SeqCst);
+	Ok(())
+}
+
+/// Stops recording and returns everything recorded since [`start`].
+pub fn finish() -> Profile {
+	RECORDING.store(false, Ordering::SeqCst);
+	Profile { calls: std::mem::take(&mut *RECORDED.lock().expect("never poisoned; qed")) }
+}
+
+fn is_recording() -> bool {
+	IS_SUPPORTED && RECORDING.load(Ordering::Relaxed)
+}
+
+fn method_name(method: &InvokeMethod) -> String {
+	match method {
+		InvokeMethod::Export(name) => (*name).to_owned(),
+		InvokeMethod::Table(func) => format!("table[{}]", func),
+		InvokeMethod::TableWithWrapper { func, .. } => format!("table[{}]", func),
+	}
+}
+
+/// Records the call of `method` performed by `f`.
+///
+/// `f` is passed `method` and returns the result of the call together with the allocation
+/// statistics, if any.
+pub fn record_call<'a, R>(
+	method: InvokeMethod<'a>,
+	f: impl FnOnce(InvokeMethod<'a>) -> (R, Option<AllocationStats>),
+) -> (R, Option<AllocationStats>) {
+	if !is_recording() {
+		return f(method)
+	}
+
+	ACTIVE_CALLS.with(|calls| {
+		calls.borrow_mut().push(CallProfile {
+			method: method_name(&method),
+			duration_nanos: 0,
+			peak_heap_bytes: 0,
+			host_functions: BTreeMap::new(),
+		})
+	});
+
+	let started = Instant::now();
+	let (result, stats) = f(method);
+	let duration = started.elapsed();
+
+	if let Some(mut call) = ACTIVE_CALLS.with(|calls| calls.borrow_mut().pop()) {
+		call.duration_nanos = duration.as_nanos() as u64;
+		call.peak_heap_bytes = stats.as_ref().map_or(0, |stats| stats.bytes_allocated_peak);
+		RECORDED.lock().expect("never poisoned; qed").push(call);
+	}
+
+	(result, stats)
+}
+
+/// Records the call of the host function `name` performed by `f`.
+pub fn record_host_function<R>(name: &str, f: impl FnOnce() -> R) -> R {
+	if !is_recording() {
+		return f()
+	}
+
+	let started = Instant::now();
+	let result = f();
+	let duration = started.elapsed();
+
+	ACTIVE_CALLS.with(|calls| {
+		if let Some(call) = calls.borrow_mut().last_mut() {
+			let function = call.host_functions.entry(name.to_owned()).or_default();
+			function.calls += 1;
+			function.duration_nanos += duration.as_nanos() as u64;
+		}
+	});
+
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn folded_stacks_sum_up_identical_stacks() {
+		let call = |duration_nanos, host_nanos| CallProfile {
+			method: "Core_version".into(),
+			duration_nanos,
+			peak_heap_bytes: 0,
+			host_functions: [(
+				"ext_storage_get_version_1".to_owned(),
+				HostFunctionProfile { calls: 1, duration_nanos: host_nanos },
+			)]
+			.into_iter()
+			.collect(),
+		};
+		let profile = Profile { calls: vec![call(100, 40), call(50, 10)] };
+
+		assert_eq!(
+			profile.to_folded_stacks(),
+			"Core_version 100\nCore_version;ext_storage_get_version_1 50\n",
+		);
+	}
+}
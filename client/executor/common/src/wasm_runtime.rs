@@ -104,6 +104,21 @@ pub trait WasmInstance: Send {
 		self.call(method.into(), data)
 	}
 
+	/// Call a method on this WASM instance, aborting it once it consumed `fuel_limit` units of
+	/// fuel.
+	///
+	/// Exhausting the budget fails with [`Error::OutOfFuel`]. Fails for instances which can't
+	/// meter their execution.
+	fn call_with_fuel_limit(
+		&mut self,
+		method: InvokeMethod,
+		data: &[u8],
+		fuel_limit: u64,
+	) -> Result<Vec<u8>, Error> {
+		let _ = (method, data, fuel_limit);
+		Err(Error::Other("fuel metering is not supported by this instance".into()))
+	}
+
 	/// Get the value from a global with the given `name`.
 	///
 	/// This method is only suitable for getting immutable globals.
@@ -34,13 +34,32 @@ use sc_executor_common::{
 	runtime_blob::RuntimeBlob,
 	wasm_runtime::{AllocationStats, WasmInstance, WasmModule},
 };
-use sp_core::traits::{CodeExecutor, Externalities, RuntimeCode};
+use sp_core::traits::{CodeExecutor, Externalities, FuelLimitExt, RuntimeCode};
+use sp_externalities::ExternalitiesExt;
 use sp_version::{GetNativeVersion, NativeVersion, RuntimeVersion};
 use sp_wasm_interface::{ExtendedHostFunctions, HostFunctions};
 
 /// Default num of pages for the heap
 const DEFAULT_HEAP_PAGES: u64 = 2048;
 
+/// Returns the fuel limit registered for the call through [`FuelLimitExt`], if any.
+fn fuel_limit(mut ext: &mut dyn Externalities) -> Option<u64> {
+	ext.extension::<FuelLimitExt>().map(|fuel_limit| fuel_limit.0)
+}
+
+/// Calls `method` on `instance`, within `fuel_limit` if given.
+fn call_instance(
+	instance: &mut dyn WasmInstance,
+	method: &str,
+	data: &[u8],
+	fuel_limit: Option<u64>,
+) -> Result<Vec<u8>> {
+	match fuel_limit {
+		Some(fuel_limit) => instance.call_with_fuel_limit(method.into(), data, fuel_limit),
+		None => instance.call_export(method, data),
+	}
+}
+
 /// Set up the externalities and safe calling environment to execute runtime calls.
 ///
 /// If the inner closure panics, it will be caught and return an error.
@@ -174,6 +193,26 @@ where
 		ext: &mut dyn Externalities,
 		f: F,
 	) -> Result<R>
+	where
+		F: FnOnce(
+			AssertUnwindSafe<&Arc<dyn WasmModule>>,
+			AssertUnwindSafe<&mut dyn WasmInstance>,
+			Option<&RuntimeVersion>,
+			AssertUnwindSafe<&mut dyn Externalities>,
+		) -> Result<Result<R>>,
+	{
+		self.with_instance_impl(runtime_code, ext, false, f)
+	}
+
+	/// Like [`Self::with_instance`], but the runtime is compiled to consume fuel if
+	/// `consume_fuel` is set.
+	fn with_instance_impl<R, F>(
+		&self,
+		runtime_code: &RuntimeCode,
+		ext: &mut dyn Externalities,
+		consume_fuel: bool,
+		f: F,
+	) -> Result<R>
 	where
 		F: FnOnce(
 			AssertUnwindSafe<&Arc<dyn WasmModule>>,
@@ -188,6 +227,7 @@ where
 			self.method,
 			self.default_heap_pages,
 			self.allow_missing_host_functions,
+			consume_fuel,
 			|module, instance, version, ext| {
 				let module = AssertUnwindSafe(module);
 				let instance = AssertUnwindSafe(instance);
@@ -341,10 +381,17 @@ where
 			"Executing function",
 		);
 
-		let result =
-			self.with_instance(runtime_code, ext, |_, mut instance, _onchain_version, mut ext| {
-				with_externalities_safe(&mut **ext, move || instance.call_export(method, data))
-			});
+		let fuel_limit = fuel_limit(ext);
+		let result = self.with_instance_impl(
+			runtime_code,
+			ext,
+			fuel_limit.is_some(),
+			|_, mut instance, _onchain_version, mut ext| {
+				with_externalities_safe(&mut **ext, move || {
+					call_instance(&mut **instance, method, data, fuel_limit)
+				})
+			},
+		);
 		(result, false)
 	}
 }
@@ -455,9 +502,11 @@ impl<D: NativeExecutionDispatch + 'static> CodeExecutor for NativeElseWasmExecut
 		);
 
 		let mut used_native = false;
-		let result = self.wasm.with_instance(
+		let fuel_limit = fuel_limit(ext);
+		let result = self.wasm.with_instance_impl(
 			runtime_code,
 			ext,
+			fuel_limit.is_some(),
 			|_, mut instance, onchain_version, mut ext| {
 				let onchain_version =
 					onchain_version.ok_or_else(|| Error::ApiError("Unknown version".into()))?;
@@ -465,7 +514,8 @@ impl<D: NativeExecutionDispatch + 'static> CodeExecutor for NativeElseWasmExecut
 				let can_call_with =
 					onchain_version.can_call_with(&self.native_version.runtime_version);
 
-				if use_native && can_call_with {
+				// Native execution can't be metered, so calls with a fuel limit always run in Wasm.
+				if use_native && can_call_with && fuel_limit.is_none() {
 					tracing::trace!(
 						target: "executor",
 						native = %self.native_version.runtime_version,
@@ -486,7 +536,9 @@ impl<D: NativeExecutionDispatch + 'static> CodeExecutor for NativeElseWasmExecut
 						);
 					}
 
-					with_externalities_safe(&mut **ext, move || instance.call_export(method, data))
+					with_externalities_safe(&mut **ext, move || {
+						call_instance(&mut **instance, method, data, fuel_limit)
+					})
 				}
 			},
 		);
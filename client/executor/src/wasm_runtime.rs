@@ -66,6 +66,8 @@ struct VersionedRuntimeId {
 	wasm_method: WasmExecutionMethod,
 	/// The number of WebAssembly heap pages this instance was created with.
 	heap_pages: u64,
+	/// Whether the runtime was compiled to consume fuel.
+	consume_fuel: bool,
 }
 
 /// A Wasm runtime object along with its cached runtime version.
@@ -203,6 +205,10 @@ impl RuntimeCache {
 	///
 	/// `allow_missing_func_imports` - Ignore missing function imports.
 	///
+	/// `consume_fuel` - Compile the runtime to consume fuel, so that its instances support
+	/// [`WasmInstance::call_with_fuel_limit`]. Such runtimes are cached separately, as the
+	/// instrumentation slows down every call.
+	///
 	/// `f` - Function to execute.
 	///
 	/// `H` - A compile-time list of host functions to expose to the runtime.
@@ -221,6 +227,7 @@ impl RuntimeCache {
 		wasm_method: WasmExecutionMethod,
 		default_heap_pages: u64,
 		allow_missing_func_imports: bool,
+		consume_fuel: bool,
 		f: F,
 	) -> Result<Result<R, Error>, Error>
 	where
@@ -235,8 +242,12 @@ impl RuntimeCache {
 		let code_hash = &runtime_code.hash;
 		let heap_pages = runtime_code.heap_pages.unwrap_or(default_heap_pages);
 
-		let versioned_runtime_id =
-			VersionedRuntimeId { code_hash: code_hash.clone(), heap_pages, wasm_method };
+		let versioned_runtime_id = VersionedRuntimeId {
+			code_hash: code_hash.clone(),
+			heap_pages,
+			wasm_method,
+			consume_fuel,
+		};
 
 		let mut runtimes = self.runtimes.lock(); // this must be released prior to calling f
 		let versioned_runtime = if let Some(versioned_runtime) = runtimes.get(&versioned_runtime_id)
@@ -253,6 +264,7 @@ impl RuntimeCache {
 				wasm_method,
 				heap_pages,
 				allow_missing_func_imports,
+				consume_fuel,
 				self.max_runtime_instances,
 				self.cache_path.as_deref(),
 			);
@@ -294,6 +306,27 @@ pub fn create_wasm_runtime_with_code<H>(
 	allow_missing_func_imports: bool,
 	cache_path: Option<&Path>,
 ) -> Result<Arc<dyn WasmModule>, WasmError>
+where
+	H: HostFunctions,
+{
+	create_wasm_runtime::<H>(
+		wasm_method,
+		heap_pages,
+		blob,
+		allow_missing_func_imports,
+		false,
+		cache_path,
+	)
+}
+
+fn create_wasm_runtime<H>(
+	wasm_method: WasmExecutionMethod,
+	heap_pages: u64,
+	blob: RuntimeBlob,
+	allow_missing_func_imports: bool,
+	consume_fuel: bool,
+	cache_path: Option<&Path>,
+) -> Result<Arc<dyn WasmModule>, WasmError>
 where
 	H: HostFunctions,
 {
@@ -326,6 +359,7 @@ where
 						canonicalize_nans: false,
 						parallel_compilation: true,
 						max_memory_size: None,
+						consume_fuel,
 					},
 				},
 			)
@@ -395,6 +429,7 @@ fn create_versioned_wasm_runtime<H>(
 	wasm_method: WasmExecutionMethod,
 	heap_pages: u64,
 	allow_missing_func_imports: bool,
+	consume_fuel: bool,
 	max_instances: usize,
 	cache_path: Option<&Path>,
 ) -> Result<VersionedRuntime, WasmError>
@@ -410,11 +445,12 @@ where
 	// runtime.
 	let mut version: Option<_> = read_embedded_version(&blob)?;
 
-	let runtime = create_wasm_runtime_with_code::<H>(
+	let runtime = create_wasm_runtime::<H>(
 		wasm_method,
 		heap_pages,
 		blob,
 		allow_missing_func_imports,
+		consume_fuel,
 		cache_path,
 	)?;
 
@@ -437,11 +437,12 @@ impl wasmi::Externals for FunctionExecutor {
 		let mut args = args.as_ref().iter().copied().map(Into::into);
 
 		if let Some(function) = self.host_functions.clone().get(index) {
-			function
-				.execute(self, &mut args)
-				.map_err(|msg| Error::FunctionExecution(function.name().to_string(), msg))
-				.map_err(wasmi::Trap::from)
-				.map(|v| v.map(Into::into))
+			sc_executor_common::profiler::record_host_function(function.name(), || {
+				function.execute(self, &mut args)
+			})
+			.map_err(|msg| Error::FunctionExecution(function.name().to_string(), msg))
+			.map_err(wasmi::Trap::from)
+			.map(|v| v.map(Into::into))
 		} else if self.allow_missing_func_imports &&
 			index >= self.host_functions.len() &&
 			index < self.host_functions.len() + self.missing_functions.len()
@@ -812,9 +813,11 @@ impl WasmInstance for WasmiInstance {
 		method: InvokeMethod,
 		data: &[u8],
 	) -> (Result<Vec<u8>, Error>, Option<AllocationStats>) {
-		let mut allocation_stats = None;
-		let result = self.call_impl(method, data, &mut allocation_stats);
-		(result, allocation_stats)
+		sc_executor_common::profiler::record_call(method, |method| {
+			let mut allocation_stats = None;
+			let result = self.call_impl(method, data, &mut allocation_stats);
+			(result, allocation_stats)
+		})
 	}
 
 	fn get_global_const(&mut self, name: &str) -> Result<Option<sp_wasm_interface::Value>, Error> {
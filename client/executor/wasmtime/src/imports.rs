@@ -104,6 +104,10 @@ impl<'a, 'b> sp_wasm_interface::HostFunctionRegistry for Registry<'a, 'b> {
 		callback(&mut HostContext { caller })
 	}
 
+	fn call_host_function<R>(fn_name: &'static str, call: impl FnOnce() -> R) -> R {
+		sc_executor_common::profiler::record_host_function(fn_name, call)
+	}
+
 	fn register_static<Params, Results>(
 		&mut self,
 		fn_name: &str,
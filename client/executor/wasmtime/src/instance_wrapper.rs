@@ -59,6 +59,7 @@ impl EntryPoint {
 		store: &mut Store,
 		data_ptr: Pointer<u8>,
 		data_len: WordSize,
+		fuel_limit: Option<u64>,
 	) -> Result<u64> {
 		let data_ptr = u32::from(data_ptr);
 		let data_len = u32::from(data_len);
@@ -70,6 +71,10 @@ impl EntryPoint {
 				dispatcher.call(&mut *store, (func, data_ptr, data_len)),
 		}
 		.map_err(|trap| {
+			if let Some(fuel_limit) = fuel_limit.filter(|_| is_out_of_fuel(&trap)) {
+				return Error::OutOfFuel(fuel_limit)
+			}
+
 			let host_state = store
 				.data_mut()
 				.host_state
@@ -304,6 +309,43 @@ impl InstanceWrapper {
 	}
 }
 
+/// The reason of the trap raised by wasmtime when a store runs out of fuel.
+const OUT_OF_FUEL_REASON: &str = "all fuel consumed by WebAssembly";
+
+/// Whether `trap` was raised because the call consumed all of its fuel.
+///
+/// Wasmtime 1.0 has no [`wasmtime::TrapCode`] for running out of fuel and raises it as a host
+/// error with a fixed reason instead, which is what this matches. Other traps always carry a trap
+/// code, and traps raised by host functions have a different reason.
+fn is_out_of_fuel(trap: &wasmtime::Trap) -> bool {
+	trap.trap_code().is_none() && trap.display_reason().to_string() == OUT_OF_FUEL_REASON
+}
+
+/// Functions related to fuel.
+impl InstanceWrapper {
+	/// Returns the fuel left in the store, or `None` if fuel consumption is disabled.
+	pub fn remaining_fuel(&mut self) -> Option<u64> {
+		self.store.fuel_consumed()?;
+		// Consuming nothing fails only if no fuel is left at all.
+		Some(self.store.consume_fuel(0).unwrap_or(0))
+	}
+
+	/// Tops up or drains the fuel of the store so that exactly `fuel` is left.
+	///
+	/// Fails if fuel consumption is disabled.
+	pub fn set_remaining_fuel(&mut self, fuel: u64) -> Result<()> {
+		let remaining = self
+			.remaining_fuel()
+			.ok_or_else(|| Error::from("fuel consumption is not enabled for this instance"))?;
+		let result = if remaining < fuel {
+			self.store.add_fuel(fuel - remaining)
+		} else {
+			self.store.consume_fuel(remaining - fuel).map(drop)
+		};
+		result.map_err(|error| Error::Other(format!("failed to set the fuel: {:#}", error)))
+	}
+}
+
 /// Extract linear memory instance from the given instance.
 fn get_linear_memory(instance: &Instance, ctx: impl AsContextMut) -> Result<Memory> {
 	let memory_export = instance
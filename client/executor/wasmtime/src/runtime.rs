@@ -26,7 +26,7 @@ use crate::{
 
 use sc_allocator::{AllocationStats, FreeingBumpHeapAllocator};
 use sc_executor_common::{
-	error::{Result, WasmError},
+	error::{Error, Result, WasmError},
 	runtime_blob::{
 		self, DataSegmentsSnapshot, ExposedMutableGlobalsSet, GlobalsSnapshot, RuntimeBlob,
 	},
@@ -174,7 +174,10 @@ impl WasmModule for WasmtimeRuntime {
 			}),
 		};
 
-		Ok(Box::new(WasmtimeInstance { strategy }))
+		Ok(Box::new(WasmtimeInstance {
+			strategy,
+			consume_fuel: self.config.semantics.consume_fuel,
+		}))
 	}
 }
 
@@ -182,6 +185,7 @@ impl WasmModule for WasmtimeRuntime {
 /// to execute the compiled code.
 pub struct WasmtimeInstance {
 	strategy: Strategy,
+	consume_fuel: bool,
 }
 
 impl WasmtimeInstance {
@@ -189,8 +193,19 @@ impl WasmtimeInstance {
 		&mut self,
 		method: InvokeMethod,
 		data: &[u8],
+		fuel_limit: Option<u64>,
 		allocation_stats: &mut Option<AllocationStats>,
 	) -> Result<Vec<u8>> {
+		// Calls without a budget still consume fuel if the code is instrumented for it, so they
+		// are given as much as the store can hold.
+		let fuel = match (fuel_limit, self.consume_fuel) {
+			(Some(fuel_limit), true) => Some(fuel_limit),
+			(None, true) => Some(u64::MAX),
+			(None, false) => None,
+			(Some(_), false) =>
+				return Err(Error::from("the runtime was not compiled to consume fuel")),
+		};
+
 		match &mut self.strategy {
 			Strategy::LegacyInstanceReuse {
 				ref mut instance_wrapper,
@@ -210,8 +225,15 @@ impl WasmtimeInstance {
 				globals_snapshot.apply(&mut InstanceGlobals { instance: instance_wrapper });
 				let allocator = FreeingBumpHeapAllocator::new(*heap_base);
 
-				let result =
-					perform_call(data, instance_wrapper, entrypoint, allocator, allocation_stats);
+				let result = perform_call(
+					data,
+					instance_wrapper,
+					entrypoint,
+					allocator,
+					fuel,
+					fuel_limit,
+					allocation_stats,
+				);
 
 				// Signal to the OS that we are done with the linear memory and that it can be
 				// reclaimed.
@@ -225,7 +247,15 @@ impl WasmtimeInstance {
 				let entrypoint = instance_wrapper.resolve_entrypoint(method)?;
 
 				let allocator = FreeingBumpHeapAllocator::new(heap_base);
-				perform_call(data, &mut instance_wrapper, entrypoint, allocator, allocation_stats)
+				perform_call(
+					data,
+					&mut instance_wrapper,
+					entrypoint,
+					allocator,
+					fuel,
+					fuel_limit,
+					allocation_stats,
+				)
 			},
 		}
 	}
@@ -237,9 +267,25 @@ impl WasmInstance for WasmtimeInstance {
 		method: InvokeMethod,
 		data: &[u8],
 	) -> (Result<Vec<u8>>, Option<AllocationStats>) {
-		let mut allocation_stats = None;
-		let result = self.call_impl(method, data, &mut allocation_stats);
-		(result, allocation_stats)
+		sc_executor_common::profiler::record_call(method, |method| {
+			let mut allocation_stats = None;
+			let result = self.call_impl(method, data, None, &mut allocation_stats);
+			(result, allocation_stats)
+		})
+	}
+
+	fn call_with_fuel_limit(
+		&mut self,
+		method: InvokeMethod,
+		data: &[u8],
+		fuel_limit: u64,
+	) -> Result<Vec<u8>> {
+		sc_executor_common::profiler::record_call(method, |method| {
+			let mut allocation_stats = None;
+			let result = self.call_impl(method, data, Some(fuel_limit), &mut allocation_stats);
+			(result, allocation_stats)
+		})
+		.0
 	}
 
 	fn get_global_const(&mut self, name: &str) -> Result<Option<Value>> {
@@ -336,6 +382,8 @@ fn common_config(semantics: &Semantics) -> std::result::Result<wasmtime::Config,
 
 	config.parallel_compilation(semantics.parallel_compilation);
 
+	config.consume_fuel(semantics.consume_fuel);
+
 	// Be clear and specific about the extensions we support. If an update brings new features
 	// they should be introduced here as well.
 	config.wasm_reference_types(false);
@@ -539,6 +587,17 @@ pub struct Semantics {
 	///
 	/// The default is `None`.
 	pub max_memory_size: Option<usize>,
+
+	/// Instrument the compiled code to consume fuel while executing.
+	///
+	/// This allows calls made with
+	/// [`WasmInstance::call_with_fuel_limit`](sc_executor_common::wasm_runtime::WasmInstance::call_with_fuel_limit)
+	/// to be aborted with [`Error::OutOfFuel`](sc_executor_common::error::Error::OutOfFuel) once
+	/// they exhausted their budget, at the cost of some execution speed for every call. Other
+	/// calls are not limited.
+	///
+	/// The default is `false`.
+	pub consume_fuel: bool,
 }
 
 #[derive(Clone)]
@@ -759,17 +818,23 @@ fn perform_call(
 	instance_wrapper: &mut InstanceWrapper,
 	entrypoint: EntryPoint,
 	mut allocator: FreeingBumpHeapAllocator,
+	fuel: Option<u64>,
+	fuel_limit: Option<u64>,
 	allocation_stats: &mut Option<AllocationStats>,
 ) -> Result<Vec<u8>> {
 	let (data_ptr, data_len) = inject_input_data(instance_wrapper, &mut allocator, data)?;
 
+	if let Some(fuel) = fuel {
+		instance_wrapper.set_remaining_fuel(fuel)?;
+	}
+
 	let host_state = HostState::new(allocator);
 
 	// Set the host state before calling into wasm.
 	instance_wrapper.store_mut().data_mut().host_state = Some(host_state);
 
 	let ret = entrypoint
-		.call(instance_wrapper.store_mut(), data_ptr, data_len)
+		.call(instance_wrapper.store_mut(), data_ptr, data_len, fuel_limit)
 		.map(unpack_ptr_and_len);
 
 	// Reset the host state
@@ -79,6 +79,7 @@ struct RuntimeBuilder {
 	deterministic_stack: bool,
 	extra_heap_pages: u64,
 	max_memory_size: Option<usize>,
+	consume_fuel: bool,
 	precompile_runtime: bool,
 	tmpdir: Option<tempfile::TempDir>,
 }
@@ -92,6 +93,7 @@ impl RuntimeBuilder {
 			deterministic_stack: false,
 			extra_heap_pages: 1024,
 			max_memory_size: None,
+			consume_fuel: false,
 			precompile_runtime: false,
 			tmpdir: None,
 		}
@@ -122,6 +124,11 @@ impl RuntimeBuilder {
 		self
 	}
 
+	fn consume_fuel(mut self, consume_fuel: bool) -> Self {
+		self.consume_fuel = consume_fuel;
+		self
+	}
+
 	fn build(&mut self) -> impl WasmModule + '_ {
 		let blob = {
 			let wasm: Vec<u8>;
@@ -154,6 +161,7 @@ impl RuntimeBuilder {
 				parallel_compilation: true,
 				extra_heap_pages: self.extra_heap_pages,
 				max_memory_size: self.max_memory_size,
+				consume_fuel: self.consume_fuel,
 			},
 		};
 
@@ -253,6 +261,65 @@ fn test_consume_over_1mb_of_stack_does_trap(instantiation_strategy: Instantiatio
 	}
 }
 
+test_wasm_execution!(test_fuel_is_refilled_for_every_call);
+fn test_fuel_is_refilled_for_every_call(instantiation_strategy: InstantiationStrategy) {
+	let wat = deep_call_stack_wat(1000);
+	let mut builder = RuntimeBuilder::new(instantiation_strategy).use_wat(wat).consume_fuel(true);
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+	for _ in 0..3 {
+		instance.call_with_fuel_limit("main".into(), &[], 1_000_000).unwrap();
+	}
+}
+
+test_wasm_execution!(test_running_out_of_fuel_aborts_the_call);
+fn test_running_out_of_fuel_aborts_the_call(instantiation_strategy: InstantiationStrategy) {
+	let wat = deep_call_stack_wat(CALL_DEPTH_LOWER_LIMIT);
+	let mut builder = RuntimeBuilder::new(instantiation_strategy).use_wat(wat).consume_fuel(true);
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+	match instance.call_with_fuel_limit("main".into(), &[], 1000).unwrap_err() {
+		Error::OutOfFuel(1000) => {},
+		error => panic!("unexpected error: {:?}", error),
+	}
+
+	// Calls without a budget are not limited, even though the code consumes fuel.
+	instance.call_export("main", &[]).unwrap();
+}
+
+test_wasm_execution!(test_other_traps_are_not_reported_as_out_of_fuel);
+fn test_other_traps_are_not_reported_as_out_of_fuel(instantiation_strategy: InstantiationStrategy) {
+	let wat = r#"
+			(module
+			  (memory $0 32)
+			  (export "memory" (memory $0))
+			  (global (export "__heap_base") i32 (i32.const 0))
+			  (func (export "main") (param i32 i32) (result i64)
+			    unreachable
+			  )
+			)
+		"#
+	.to_string();
+	let mut builder = RuntimeBuilder::new(instantiation_strategy).use_wat(wat).consume_fuel(true);
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+	match instance.call_with_fuel_limit("main".into(), &[], 1_000_000).unwrap_err() {
+		Error::AbortedDueToTrap(error) => {
+			let expected = "wasm trap: wasm `unreachable` instruction executed";
+			assert_eq!(error.message, expected);
+		},
+		error => panic!("unexpected error: {:?}", error),
+	}
+}
+
+test_wasm_execution!(test_fuel_limit_requires_fuel_consumption);
+fn test_fuel_limit_requires_fuel_consumption(instantiation_strategy: InstantiationStrategy) {
+	let mut builder = RuntimeBuilder::new(instantiation_strategy);
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+	assert!(instance.call_with_fuel_limit("test_empty_return".into(), &[], 1000).is_err());
+}
+
 test_wasm_execution!(test_nan_canonicalization);
 fn test_nan_canonicalization(instantiation_strategy: InstantiationStrategy) {
 	let mut builder = RuntimeBuilder::new(instantiation_strategy).canonicalize_nans(true);
@@ -540,6 +607,7 @@ fn test_instances_without_reuse_are_not_leaked() {
 				parallel_compilation: true,
 				extra_heap_pages: 2048,
 				max_memory_size: None,
+				consume_fuel: false,
 			},
 		},
 	)
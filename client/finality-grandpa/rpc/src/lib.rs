@@ -35,13 +35,15 @@ mod finality;
 mod notification;
 mod report;
 
-use sc_finality_grandpa::GrandpaJustificationStream;
+use sc_finality_grandpa::{GrandpaJustificationStream, SharedVoterParticipation};
 use sc_rpc::SubscriptionTaskExecutor;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
 use finality::{EncodedFinalityProof, RpcFinalityProofProvider};
 use notification::JustificationNotification;
-use report::{ReportAuthoritySet, ReportVoterState, ReportedRoundStates};
+use report::{
+	ReportAuthoritySet, ReportVoterState, ReportedRoundStates, ReportedVoterParticipation,
+};
 
 /// Provides RPC methods for interacting with GRANDPA.
 #[rpc(client, server)]
@@ -64,6 +66,23 @@ pub trait GrandpaApi<Notification, Hash, Number> {
 	/// in the set and all the intermediary headers to link them together.
 	#[method(name = "grandpa_proveFinality")]
 	async fn prove_finality(&self, block: Number) -> RpcResult<Option<EncodedFinalityProof>>;
+
+	/// Prove finality of all blocks in the range `[from, to]` by returning the justifications for
+	/// the last block of every authority set the range spans, together with their headers.
+	///
+	/// The proof can be checked with `sc_finality_grandpa::verify_range_finality_proof`. Ranges
+	/// whose proof would be larger than 8 MiB are refused, a smaller range must be requested.
+	#[method(name = "grandpa_proveFinalityRange")]
+	async fn prove_finality_range(
+		&self,
+		from: Number,
+		to: Number,
+	) -> RpcResult<Option<EncodedFinalityProof>>;
+
+	/// Returns the participation of the voters in the rounds of the most recent authority sets,
+	/// as recorded by the voter participation monitor.
+	#[method(name = "grandpa_voterParticipation")]
+	async fn voter_participation(&self) -> RpcResult<ReportedVoterParticipation>;
 }
 
 /// Provides RPC methods for interacting with GRANDPA.
@@ -71,6 +90,7 @@ pub struct Grandpa<AuthoritySet, VoterState, Block: BlockT, ProofProvider> {
 	executor: SubscriptionTaskExecutor,
 	authority_set: AuthoritySet,
 	voter_state: VoterState,
+	voter_participation: SharedVoterParticipation,
 	justification_stream: GrandpaJustificationStream<Block>,
 	finality_proof_provider: Arc<ProofProvider>,
 }
@@ -82,10 +102,18 @@ impl<AuthoritySet, VoterState, Block: BlockT, ProofProvider>
 		executor: SubscriptionTaskExecutor,
 		authority_set: AuthoritySet,
 		voter_state: VoterState,
+		voter_participation: SharedVoterParticipation,
 		justification_stream: GrandpaJustificationStream<Block>,
 		finality_proof_provider: Arc<ProofProvider>,
 	) -> Self {
-		Self { executor, authority_set, voter_state, justification_stream, finality_proof_provider }
+		Self {
+			executor,
+			authority_set,
+			voter_state,
+			voter_participation,
+			justification_stream,
+			finality_proof_provider,
+		}
 	}
 }
 
@@ -130,6 +158,24 @@ where
 			})
 			.map_err(Into::into)
 	}
+
+	async fn prove_finality_range(
+		&self,
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+	) -> RpcResult<Option<EncodedFinalityProof>> {
+		self.finality_proof_provider
+			.rpc_prove_finality_range(from, to)
+			.map_err(|e| {
+				warn!("Error proving finality of range: {}", e);
+				error::Error::ProveFinalityFailed(e)
+			})
+			.map_err(Into::into)
+	}
+
+	async fn voter_participation(&self) -> RpcResult<ReportedVoterParticipation> {
+		ReportedVoterParticipation::from(&self.voter_participation.history()).map_err(Into::into)
+	}
 }
 
 #[cfg(test)]
@@ -209,6 +255,14 @@ mod tests {
 					.into(),
 			)))
 		}
+
+		fn rpc_prove_finality_range(
+			&self,
+			_from: NumberFor<Block>,
+			_to: NumberFor<Block>,
+		) -> Result<Option<EncodedFinalityProof>, sc_finality_grandpa::FinalityProofError> {
+			Err(sc_finality_grandpa::FinalityProofError::InvalidRange)
+		}
 	}
 
 	impl ReportVoterState for TestVoterState {
@@ -270,6 +324,7 @@ mod tests {
 			executor,
 			TestAuthoritySet,
 			voter_state,
+			SharedVoterParticipation::empty(),
 			justification_stream,
 			finality_proof_provider,
 		)
@@ -310,6 +365,28 @@ mod tests {
 		assert_eq!(expected_response, response.result);
 	}
 
+	#[tokio::test]
+	async fn prove_finality_range_reports_errors() {
+		let (rpc, _) = setup_io_handler(TestVoterState);
+		let request =
+			r#"{"jsonrpc":"2.0","method":"grandpa_proveFinalityRange","params":[5,2],"id":0}"#;
+		let (response, _) = rpc.raw_json_request(&request).await.unwrap();
+
+		let expected_response = r#"{"jsonrpc":"2.0","error":{"code":4,"message":"GRANDPA prove finality rpc failed: Invalid block range"},"id":0}"#;
+		assert_eq!(expected_response, response.result);
+	}
+
+	#[tokio::test]
+	async fn voter_participation_rpc_handler() {
+		let (rpc, _) = setup_io_handler(TestVoterState);
+		let expected_response = r#"{"jsonrpc":"2.0","result":{"sets":[]},"id":0}"#.to_string();
+		let request =
+			r#"{"jsonrpc":"2.0","method":"grandpa_voterParticipation","params":[],"id":0}"#;
+		let (response, _) = rpc.raw_json_request(&request).await.unwrap();
+
+		assert_eq!(expected_response, response.result);
+	}
+
 	#[tokio::test]
 	async fn subscribe_and_unsubscribe_with_wrong_id() {
 		let (rpc, _) = setup_io_handler(TestVoterState);
@@ -24,7 +24,10 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use sc_finality_grandpa::{report, AuthorityId, SharedAuthoritySet, SharedVoterState};
+use sc_finality_grandpa::{
+	report, AuthorityId, ParticipationHistory, SetParticipation, SharedAuthoritySet,
+	SharedVoterState,
+};
 
 use crate::error::Error;
 
@@ -148,3 +151,75 @@ impl ReportedRoundStates {
 		Ok(Self { set_id, best, background })
 	}
 }
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VoterParticipation {
+	authority: AuthorityId,
+	prevotes_missed: u32,
+	precommits_missed: u32,
+	consecutive_rounds_missed: u32,
+	last_seen_round: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportedSetParticipation {
+	set_id: u32,
+	first_round: u32,
+	last_round: u32,
+	rounds_observed: u32,
+	voters: Vec<VoterParticipation>,
+}
+
+impl ReportedSetParticipation {
+	fn from(set: &SetParticipation) -> Result<Self, Error> {
+		let voters = set
+			.voters
+			.iter()
+			.map(|(authority, participation)| {
+				Ok(VoterParticipation {
+					authority: authority.clone(),
+					prevotes_missed: participation.prevotes_missed.try_into()?,
+					precommits_missed: participation.precommits_missed.try_into()?,
+					consecutive_rounds_missed: participation
+						.consecutive_rounds_missed
+						.try_into()?,
+					last_seen_round: participation
+						.last_seen_round
+						.map(u32::try_from)
+						.transpose()?,
+				})
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+
+		Ok(Self {
+			set_id: u32::try_from(set.set_id)
+				.map_err(|_| Error::AuthoritySetIdReportedAsUnreasonablyLarge)?,
+			first_round: set.first_round.try_into()?,
+			last_round: set.last_round.try_into()?,
+			rounds_observed: set.rounds_observed.try_into()?,
+			voters,
+		})
+	}
+}
+
+/// The participation of the voters in the rounds of the most recent authority sets, in a form
+/// suitable for serialization.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportedVoterParticipation {
+	sets: Vec<ReportedSetParticipation>,
+}
+
+impl ReportedVoterParticipation {
+	pub fn from(history: &ParticipationHistory) -> Result<Self, Error> {
+		let sets = history
+			.sets
+			.iter()
+			.map(ReportedSetParticipation::from)
+			.collect::<Result<_, _>>()?;
+
+		Ok(Self { sets })
+	}
+}
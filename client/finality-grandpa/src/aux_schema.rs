@@ -18,7 +18,7 @@
 
 //! Schema for stuff in the aux-db.
 
-use std::fmt::Debug;
+use std::{collections::VecDeque, fmt::Debug};
 
 use finality_grandpa::round::State as RoundState;
 use log::{info, warn};
@@ -38,6 +38,7 @@ use crate::{
 		CompletedRound, CompletedRounds, CurrentRounds, HasVoted, SharedVoterSetState,
 		VoterSetState,
 	},
+	participation::{ParticipationHistory, SetParticipation},
 	GrandpaJustification, NewAuthoritySet,
 };
 
@@ -46,6 +47,7 @@ const SET_STATE_KEY: &[u8] = b"grandpa_completed_round";
 const CONCLUDED_ROUNDS: &[u8] = b"grandpa_concluded_rounds";
 const AUTHORITY_SET_KEY: &[u8] = b"grandpa_voters";
 const BEST_JUSTIFICATION: &[u8] = b"grandpa_best_justification";
+const VOTER_PARTICIPATION: &[u8] = b"grandpa_voter_participation";
 
 const CURRENT_VERSION: u32 = 3;
 
@@ -488,6 +490,56 @@ pub(crate) fn write_concluded_round<Block: BlockT, B: AuxStore>(
 	backend.insert_aux(&[(&key[..], round_data.encode().as_slice())], &[])
 }
 
+fn set_participation_key(set_id: SetId) -> Vec<u8> {
+	let mut key = VOTER_PARTICIPATION.to_vec();
+	set_id.using_encoded(|id| key.extend(id));
+	key
+}
+
+/// Load the participation history of the voters.
+pub(crate) fn load_voter_participation<B: AuxStore>(
+	backend: &B,
+) -> ClientResult<Option<ParticipationHistory>> {
+	let set_ids = match load_decode::<_, Vec<SetId>>(backend, VOTER_PARTICIPATION)? {
+		Some(set_ids) => set_ids,
+		None => return Ok(None),
+	};
+
+	let mut sets = VecDeque::with_capacity(set_ids.len());
+	for set_id in set_ids {
+		if let Some(set) = load_decode(backend, &set_participation_key(set_id))? {
+			sets.push_back(set);
+		}
+	}
+
+	Ok(Some(ParticipationHistory { sets }))
+}
+
+/// Write the participation of the voters of an authority set. If the tracked sets changed,
+/// `set_ids` are the ids of the sets now tracked, and the participation of the `pruned` sets is
+/// deleted.
+pub(crate) fn write_set_participation<B: AuxStore>(
+	backend: &B,
+	set: &SetParticipation,
+	set_ids: Option<&[SetId]>,
+	pruned: &[SetId],
+) -> ClientResult<()> {
+	let set_key = set_participation_key(set.set_id);
+	let encoded_set = set.encode();
+	let encoded_set_ids = set_ids.map(|set_ids| set_ids.encode());
+
+	let mut insert = vec![(&set_key[..], &encoded_set[..])];
+	if let Some(encoded_set_ids) = &encoded_set_ids {
+		insert.push((VOTER_PARTICIPATION, &encoded_set_ids[..]));
+	}
+
+	let pruned_keys =
+		pruned.iter().map(|set_id| set_participation_key(*set_id)).collect::<Vec<_>>();
+	let delete = pruned_keys.iter().map(|key| &key[..]).collect::<Vec<_>>();
+
+	backend.insert_aux(&insert, &delete)
+}
+
 #[cfg(test)]
 pub(crate) fn load_authorities<B: AuxStore, H: Decode, N: Decode + Clone + Ord>(
 	backend: &B,
@@ -495,6 +547,14 @@ pub(crate) fn load_authorities<B: AuxStore, H: Decode, N: Decode + Clone + Ord>(
 	load_decode::<_, AuthoritySet<H, N>>(backend, AUTHORITY_SET_KEY).expect("backend error")
 }
 
+#[cfg(test)]
+pub(crate) fn load_set_participation<B: AuxStore>(
+	backend: &B,
+	set_id: SetId,
+) -> Option<SetParticipation> {
+	load_decode(backend, &set_participation_key(set_id)).expect("backend error")
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
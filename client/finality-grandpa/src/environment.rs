@@ -41,7 +41,10 @@ use sc_client_api::{
 };
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_INFO};
 use sp_blockchain::HeaderMetadata;
-use sp_consensus::SelectChain as SelectChainT;
+use sp_consensus::{
+	equivocation::{EquivocationReport, EquivocationSink},
+	SelectChain as SelectChainT,
+};
 use sp_finality_grandpa::{
 	AuthorityId, AuthoritySignature, Equivocation, EquivocationProof, GrandpaApi, RoundNumber,
 	SetId, GRANDPA_ENGINE_ID,
@@ -57,6 +60,7 @@ use crate::{
 	justification::GrandpaJustification,
 	local_authority_id,
 	notification::GrandpaJustificationSender,
+	participation::SharedVoterParticipation,
 	until_imported::UntilVoteTargetImported,
 	voting_rule::VotingRule as VotingRuleT,
 	ClientForGrandpa, CommandOrError, Commit, Config, Error, NewAuthoritySet, Precommit, Prevote,
@@ -438,6 +442,8 @@ pub(crate) struct Environment<Backend, Block: BlockT, C, N: NetworkT<Block>, SC,
 	pub(crate) voting_rule: VR,
 	pub(crate) metrics: Option<Metrics>,
 	pub(crate) justification_sender: Option<GrandpaJustificationSender<Block>>,
+	pub(crate) voter_participation: Option<SharedVoterParticipation>,
+	pub(crate) equivocation_reporting: Option<EquivocationSink>,
 	pub(crate) telemetry: Option<TelemetryHandle>,
 	pub(crate) _phantom: PhantomData<Backend>,
 }
@@ -556,9 +562,19 @@ where
 			},
 		};
 
-		// submit equivocation report at **best** block
+		let offence =
+			(equivocation.offender().clone(), authority_set.set_id, equivocation.round_number());
 		let equivocation_proof = EquivocationProof::new(authority_set.set_id, equivocation);
 
+		// leave the submission to the equivocation reporting, if any.
+		if let Some(sink) = &self.equivocation_reporting {
+			let proof = (equivocation_proof, key_owner_proof);
+			sink.report(EquivocationReport::new(GRANDPA_ENGINE_ID, offence, &proof));
+			return Ok(())
+		}
+
+		// submit equivocation report at **best** block
+
 		self.client
 			.runtime_api()
 			.submit_report_equivocation_unsigned_extrinsic(
@@ -1061,6 +1077,15 @@ where
 			Ok(Some(set_state))
 		})?;
 
+		if let Some(voter_participation) = &self.voter_participation {
+			voter_participation.note_concluded_round(
+				self.set_id,
+				&self.voters,
+				round,
+				historical_votes.seen(),
+			);
+		}
+
 		Ok(())
 	}
 
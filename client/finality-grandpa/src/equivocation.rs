@@ -0,0 +1,85 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Submission of GRANDPA equivocation reports to the runtime.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use log::info;
+use parity_scale_codec::Decode;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::{equivocation::SubmitEquivocation, Error as ConsensusError};
+use sp_finality_grandpa::{EquivocationProof, GrandpaApi, OpaqueKeyOwnershipProof};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor},
+};
+
+/// Submits the equivocation proofs of other voters, along with the key ownership proofs the
+/// voter generated for them, as unsigned `report_equivocation_unsigned` extrinsics through the
+/// [`GrandpaApi`].
+///
+/// Register it for [`GRANDPA_ENGINE_ID`](crate::GRANDPA_ENGINE_ID) with
+/// [`EquivocationReporting::with_submitter`](sp_consensus::equivocation::EquivocationReporting::with_submitter).
+pub struct GrandpaEquivocationSubmitter<Block, C> {
+	client: Arc<C>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, C> GrandpaEquivocationSubmitter<Block, C> {
+	/// Create a new submitter using the runtime of `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _phantom: PhantomData }
+	}
+}
+
+impl<Block, C> SubmitEquivocation for GrandpaEquivocationSubmitter<Block, C>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync,
+	C::Api: GrandpaApi<Block>,
+{
+	fn submit(&mut self, proof: &[u8]) -> Result<(), ConsensusError> {
+		let (equivocation_proof, key_owner_proof) = <(
+			EquivocationProof<Block::Hash, NumberFor<Block>>,
+			OpaqueKeyOwnershipProof,
+		)>::decode(&mut &proof[..])
+		.map_err(|e| ConsensusError::Other(Box::new(e)))?;
+		let offender = equivocation_proof.offender().clone();
+
+		// submit equivocation report at **best** block
+		self.client
+			.runtime_api()
+			.submit_report_equivocation_unsigned_extrinsic(
+				&BlockId::Hash(self.client.info().best_hash),
+				equivocation_proof,
+				key_owner_proof,
+			)
+			.map_err(|e| ConsensusError::Other(Box::new(e)))?
+			.ok_or_else(|| {
+				ConsensusError::Other(
+					"Runtime failed to submit the equivocation report transaction".into(),
+				)
+			})?;
+
+		info!(target: "afg", "Submitted equivocation report for voter {:?}", offender);
+
+		Ok(())
+	}
+}
@@ -35,6 +35,10 @@
 //! Finality proof provider can choose how to provide finality proof on its own. The incomplete
 //! finality proof (that finalizes some block C that is ancestor of the B and descendant
 //! of the U) could be returned.
+//!
+//! Finality of a whole range of blocks is proved by a [`RangeFinalityProof`], which contains a
+//! justification for the last block of every authority set the range spans and can be checked
+//! without a client using [`verify_range_finality_proof`].
 
 use log::{trace, warn};
 use std::sync::Arc;
@@ -42,21 +46,25 @@ use std::sync::Arc;
 use parity_scale_codec::{Decode, Encode};
 use sc_client_api::backend::Backend;
 use sp_blockchain::{Backend as BlockchainBackend, HeaderBackend};
-use sp_finality_grandpa::GRANDPA_ENGINE_ID;
+use sp_finality_grandpa::{AuthorityList, SetId, GRANDPA_ENGINE_ID};
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block as BlockT, Header as HeaderT, NumberFor, One},
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Saturating},
 };
 
 use crate::{
 	authorities::{AuthoritySetChangeId, AuthoritySetChanges},
 	best_justification,
 	justification::GrandpaJustification,
-	SharedAuthoritySet,
+	warp_proof::{WarpSyncFragment, MAX_WARP_SYNC_PROOF_SIZE},
+	BlockNumberOps, SharedAuthoritySet,
 };
 
 const MAX_UNKNOWN_HEADERS: usize = 100_000;
 
+/// The maximum size of an encoded [`RangeFinalityProof`], same as for warp sync proofs.
+const MAX_RANGE_FINALITY_PROOF_SIZE: usize = MAX_WARP_SYNC_PROOF_SIZE;
+
 /// Finality proof provider for serving network requests.
 pub struct FinalityProofProvider<BE, Block: BlockT> {
 	backend: Arc<BE>,
@@ -116,6 +124,35 @@ where
 
 		prove_finality(&*self.backend, authority_set_changes, block)
 	}
+
+	/// Prove finality of all blocks in the range `[from, to]` by returning a justification for the
+	/// last block of every authority set the range spans.
+	///
+	/// Fails with [`FinalityProofError::RangeTooLarge`] if the proof would be larger than 8 MiB,
+	/// in which case a smaller range should be requested.
+	pub fn prove_finality_range(
+		&self,
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+	) -> Result<Option<Vec<u8>>, FinalityProofError> {
+		let authority_set_changes = if let Some(changes) = self
+			.shared_authority_set
+			.as_ref()
+			.map(SharedAuthoritySet::authority_set_changes)
+		{
+			changes
+		} else {
+			return Ok(None)
+		};
+
+		prove_finality_range(
+			&*self.backend,
+			authority_set_changes,
+			from,
+			to,
+			MAX_RANGE_FINALITY_PROOF_SIZE,
+		)
+	}
 }
 
 /// Finality for block B is proved by providing:
@@ -131,12 +168,43 @@ pub struct FinalityProof<Header: HeaderT> {
 	pub unknown_headers: Vec<Header>,
 }
 
+/// Finality proof for all blocks in a range, which may span multiple authority sets.
+///
+/// For every authority set the range spans it contains the header of the last block finalized by
+/// that set, together with its justification. The header signals the next authority set, except
+/// for the last fragment, which may instead be the latest block finalized by the current set.
+#[derive(Debug, Encode, Decode)]
+pub struct RangeFinalityProof<Block: BlockT> {
+	/// The justified headers, ordered by authority set.
+	pub fragments: Vec<WarpSyncFragment<Block>>,
+}
+
+/// The result of verifying a [`RangeFinalityProof`].
+#[derive(Debug, PartialEq)]
+pub struct VerifiedRangeFinality<Header: HeaderT> {
+	/// The headers proven to be final, in order. All of their ancestors are final as well.
+	pub finalized: Vec<Header>,
+	/// The set id of the authority set following the last finalized header.
+	pub set_id: SetId,
+	/// The authorities of the set following the last finalized header.
+	pub authorities: AuthorityList,
+}
+
 /// Errors occurring when trying to prove finality
 #[derive(Debug, thiserror::Error)]
 pub enum FinalityProofError {
 	/// The requested block has not yet been finalized.
 	#[error("Block not yet finalized")]
 	BlockNotYetFinalized,
+	/// The requested range of blocks is empty.
+	#[error("Invalid block range")]
+	InvalidRange,
+	/// The finality proof of the requested range of blocks spans too many authority sets.
+	#[error("Block range too large")]
+	RangeTooLarge,
+	/// The given finality proof is invalid.
+	#[error("Invalid finality proof: {0}")]
+	InvalidProof(String),
 	/// The requested block is not covered by authority set changes. Likely this means the block is
 	/// in the latest authority set, and the subscription API is more appropriate.
 	#[error("Block not covered by authority set changes")]
@@ -238,6 +306,140 @@ where
 	))
 }
 
+fn prove_finality_range<Block, B>(
+	backend: &B,
+	authority_set_changes: AuthoritySetChanges<NumberFor<Block>>,
+	from: NumberFor<Block>,
+	to: NumberFor<Block>,
+	max_proof_size: usize,
+) -> Result<Option<Vec<u8>>, FinalityProofError>
+where
+	Block: BlockT,
+	B: Backend<Block>,
+{
+	if from > to {
+		return Err(FinalityProofError::InvalidRange)
+	}
+
+	let blockchain = backend.blockchain();
+	if blockchain.info().finalized_number < to {
+		trace!(
+			target: "afg",
+			"Requested finality proof for range ending at #{} while we only have finalized #{}.",
+			to,
+			blockchain.info().finalized_number,
+		);
+		return Err(FinalityProofError::BlockNotYetFinalized)
+	}
+
+	// include the set whose last block is `from` itself.
+	let set_changes = authority_set_changes
+		.iter_from(from.saturating_sub(One::one()))
+		.ok_or(FinalityProofError::BlockNotInAuthoritySetChanges)?;
+
+	let mut fragments = Vec::new();
+	// Only the fragments are counted, the margin leaves room for the length of the `Vec`.
+	let max_fragments_size = max_proof_size.saturating_sub(50);
+	let mut fragments_size = 0;
+
+	for (_, last_block) in set_changes {
+		let header = blockchain.expect_header(BlockId::Number(*last_block))?;
+		let justification = match blockchain
+			.justifications(header.hash())?
+			.and_then(|justifications| justifications.into_justification(GRANDPA_ENGINE_ID))
+		{
+			Some(justification) => GrandpaJustification::<Block>::decode(&mut &justification[..])
+				.map_err(|_| sp_blockchain::Error::JustificationDecode)?,
+			None => {
+				trace!(
+					target: "afg",
+					"No justification found for the last block #{} of a set. Returning empty proof.",
+					last_block,
+				);
+				return Ok(None)
+			},
+		};
+
+		let fragment = WarpSyncFragment { header, justification };
+		fragments_size += fragment.encoded_size();
+		if fragments_size > max_fragments_size {
+			return Err(FinalityProofError::RangeTooLarge)
+		}
+		fragments.push(fragment);
+
+		if *last_block >= to {
+			return Ok(Some(RangeFinalityProof { fragments }.encode()))
+		}
+	}
+
+	// the end of the range is finalized by the current authority set.
+	let justification = match best_justification(backend)?
+		.filter(|justification: &GrandpaJustification<Block>| justification.target().0 >= to)
+	{
+		Some(justification) => justification,
+		None => {
+			trace!(
+				target: "afg",
+				"No justification found for the latest finalized block. Returning empty proof.",
+			);
+			return Ok(None)
+		},
+	};
+	let header = blockchain.expect_header(BlockId::Hash(justification.target().1))?;
+	let fragment = WarpSyncFragment { header, justification };
+	if fragments_size + fragment.encoded_size() > max_fragments_size {
+		return Err(FinalityProofError::RangeTooLarge)
+	}
+	fragments.push(fragment);
+
+	Ok(Some(RangeFinalityProof { fragments }.encode()))
+}
+
+/// Verify an encoded [`RangeFinalityProof`], starting with the given authority set.
+///
+/// Every fragment but the last one must signal a standard authority set change, which is then used
+/// to verify the next fragment. This doesn't need access to a client.
+pub fn verify_range_finality_proof<Block>(
+	proof: &[u8],
+	set_id: SetId,
+	authorities: AuthorityList,
+) -> Result<VerifiedRangeFinality<Block::Header>, FinalityProofError>
+where
+	Block: BlockT,
+	NumberFor<Block>: BlockNumberOps,
+{
+	let proof = RangeFinalityProof::<Block>::decode(&mut &proof[..])
+		.map_err(|e| FinalityProofError::InvalidProof(e.to_string()))?;
+
+	if proof.fragments.is_empty() {
+		return Err(FinalityProofError::InvalidProof("Empty proof".into()))
+	}
+
+	let mut verified = VerifiedRangeFinality { finalized: Vec::new(), set_id, authorities };
+	let last = proof.fragments.len() - 1;
+	for (index, fragment) in proof.fragments.into_iter().enumerate() {
+		let next_authorities = fragment
+			.verify(verified.set_id, &verified.authorities)
+			.map_err(|e| FinalityProofError::InvalidProof(e.to_string()))?;
+
+		match next_authorities {
+			Some(next_authorities) => {
+				verified.authorities = next_authorities;
+				verified.set_id += 1;
+			},
+			None if index != last =>
+				return Err(FinalityProofError::InvalidProof(
+					"Header is missing authority set change digest".into(),
+				)),
+			None => {},
+		}
+
+		verified.finalized.push(fragment.header);
+	}
+
+	Ok(verified)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -556,4 +758,185 @@ mod tests {
 			}
 		);
 	}
+
+	#[test]
+	fn range_finality_proof_fails_for_invalid_or_unfinalized_range() {
+		let (_, backend, _) = test_blockchain(8, &[4]);
+
+		assert!(matches!(
+			prove_finality_range(
+				&*backend,
+				AuthoritySetChanges::empty(),
+				3,
+				2,
+				MAX_RANGE_FINALITY_PROOF_SIZE,
+			),
+			Err(FinalityProofError::InvalidRange),
+		));
+		assert!(matches!(
+			prove_finality_range(
+				&*backend,
+				AuthoritySetChanges::empty(),
+				3,
+				5,
+				MAX_RANGE_FINALITY_PROOF_SIZE,
+			),
+			Err(FinalityProofError::BlockNotYetFinalized),
+		));
+	}
+
+	#[test]
+	fn range_finality_proof_spans_authority_set_changes() {
+		let (client, backend, blocks) = test_blockchain(8, &[4]);
+		let block5 = &blocks[4];
+		let block8 = &blocks[7];
+
+		let commit = create_commit(block5.clone(), 5, 0, &[Ed25519Keyring::Alice]);
+		let grandpa_just5 = GrandpaJustification::from_commit(&client, 5, commit).unwrap();
+		client
+			.finalize_block(block5.hash(), Some((ID, grandpa_just5.encode())))
+			.unwrap();
+
+		let commit = create_commit(block8.clone(), 8, 1, &[Ed25519Keyring::Bob]);
+		let grandpa_just8 = GrandpaJustification::from_commit(&client, 8, commit).unwrap();
+		client.finalize_block(block8.hash(), None).unwrap();
+		store_best_justification(&client, &grandpa_just8);
+
+		let mut authority_set_changes = AuthoritySetChanges::empty();
+		authority_set_changes.append(0, 5);
+
+		// the range ends in the latest set, so the best justification completes the proof.
+		let proof = prove_finality_range(
+			&*backend,
+			authority_set_changes.clone(),
+			5,
+			7,
+			MAX_RANGE_FINALITY_PROOF_SIZE,
+		)
+		.unwrap()
+		.unwrap();
+		let expected = RangeFinalityProof::<Block> {
+			fragments: vec![
+				WarpSyncFragment { header: block5.header().clone(), justification: grandpa_just5 },
+				WarpSyncFragment { header: block8.header().clone(), justification: grandpa_just8 },
+			],
+		};
+		assert_eq!(proof, expected.encode());
+
+		// block 5 doesn't signal the change to Bob, so the proof doesn't verify.
+		assert!(matches!(
+			verify_range_finality_proof::<Block>(
+				&proof,
+				0,
+				vec![(Ed25519Keyring::Alice.public().into(), 1)],
+			),
+			Err(FinalityProofError::InvalidProof(_)),
+		));
+
+		// a range within the latest set only needs the best justification.
+		let proof = prove_finality_range(
+			&*backend,
+			authority_set_changes,
+			6,
+			7,
+			MAX_RANGE_FINALITY_PROOF_SIZE,
+		)
+		.unwrap()
+		.unwrap();
+		assert_eq!(
+			verify_range_finality_proof::<Block>(
+				&proof,
+				1,
+				vec![(Ed25519Keyring::Bob.public().into(), 1)],
+			)
+			.unwrap(),
+			VerifiedRangeFinality {
+				finalized: vec![block8.header().clone()],
+				set_id: 1,
+				authorities: vec![(Ed25519Keyring::Bob.public().into(), 1)],
+			},
+		);
+	}
+
+	#[test]
+	fn range_finality_proof_verifies_across_authority_set_change() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let mut client = Arc::new(builder.build());
+		let alice = vec![(Ed25519Keyring::Alice.public().into(), 1)];
+		let bob = vec![(Ed25519Keyring::Bob.public().into(), 1)];
+
+		// block 5 signals the change from Alice to Bob.
+		let mut blocks = Vec::new();
+		for number in 1..=8 {
+			let mut block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+			if number == 5 {
+				let change = sp_finality_grandpa::ConsensusLog::ScheduledChange(
+					sp_finality_grandpa::ScheduledChange {
+						delay: 0u64,
+						next_authorities: bob.clone(),
+					},
+				);
+				block
+					.header
+					.digest_mut()
+					.push(sp_runtime::DigestItem::Consensus(ID, change.encode()));
+			}
+			block_on(client.import(BlockOrigin::Own, block.clone())).unwrap();
+			blocks.push(block);
+		}
+		let block5 = &blocks[4];
+		let block8 = &blocks[7];
+
+		let commit = create_commit(block5.clone(), 5, 0, &[Ed25519Keyring::Alice]);
+		let grandpa_just5 = GrandpaJustification::from_commit(&client, 5, commit).unwrap();
+		client
+			.finalize_block(block5.hash(), Some((ID, grandpa_just5.encode())))
+			.unwrap();
+
+		let commit = create_commit(block8.clone(), 8, 1, &[Ed25519Keyring::Bob]);
+		let grandpa_just8 = GrandpaJustification::from_commit(&client, 8, commit).unwrap();
+		client.finalize_block(block8.hash(), None).unwrap();
+		store_best_justification(&client, &grandpa_just8);
+
+		let mut authority_set_changes = AuthoritySetChanges::empty();
+		authority_set_changes.append(0, 5);
+
+		let proof = prove_finality_range(
+			&*backend,
+			authority_set_changes.clone(),
+			3,
+			7,
+			MAX_RANGE_FINALITY_PROOF_SIZE,
+		)
+		.unwrap()
+		.unwrap();
+
+		// starting from Alice's set, the proof hands over to Bob's set at block 5.
+		assert_eq!(
+			verify_range_finality_proof::<Block>(&proof, 0, alice.clone()).unwrap(),
+			VerifiedRangeFinality {
+				finalized: vec![block5.header().clone(), block8.header().clone()],
+				set_id: 1,
+				authorities: bob.clone(),
+			},
+		);
+
+		// the proof doesn't verify for another starting set.
+		assert!(matches!(
+			verify_range_finality_proof::<Block>(&proof, 1, bob),
+			Err(FinalityProofError::InvalidProof(_)),
+		));
+
+		// the proof doesn't fit once the fragment of the first set is counted.
+		let first_fragment_size = WarpSyncFragment::<Block> {
+			header: block5.header().clone(),
+			justification: grandpa_just5,
+		}
+		.encoded_size();
+		assert!(matches!(
+			prove_finality_range(&*backend, authority_set_changes, 3, 7, first_fragment_size + 50),
+			Err(FinalityProofError::RangeTooLarge),
+		));
+	}
 }
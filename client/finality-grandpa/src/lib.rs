@@ -74,7 +74,7 @@ use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver};
 use sp_api::ProvideRuntimeApi;
 use sp_application_crypto::AppKey;
 use sp_blockchain::{Error as ClientError, HeaderBackend, HeaderMetadata, Result as ClientResult};
-use sp_consensus::SelectChain;
+use sp_consensus::{equivocation::EquivocationSink, SelectChain};
 use sp_core::crypto::ByteArray;
 use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
 use sp_runtime::{
@@ -114,11 +114,13 @@ mod authorities;
 mod aux_schema;
 mod communication;
 mod environment;
+mod equivocation;
 mod finality_proof;
 mod import;
 mod justification;
 mod notification;
 mod observer;
+mod participation;
 mod until_imported;
 mod voting_rule;
 pub mod warp_proof;
@@ -126,14 +128,23 @@ pub mod warp_proof;
 pub use authorities::{AuthoritySet, AuthoritySetChanges, SharedAuthoritySet};
 pub use aux_schema::best_justification;
 pub use communication::grandpa_protocol_name::standard_name as protocol_standard_name;
+pub use equivocation::GrandpaEquivocationSubmitter;
 pub use finality_grandpa::voter::report;
-pub use finality_proof::{FinalityProof, FinalityProofError, FinalityProofProvider};
+pub use finality_proof::{
+	verify_range_finality_proof, FinalityProof, FinalityProofError, FinalityProofProvider,
+	RangeFinalityProof, VerifiedRangeFinality,
+};
 pub use import::{find_forced_change, find_scheduled_change, GrandpaBlockImport};
 pub use justification::GrandpaJustification;
 pub use notification::{GrandpaJustificationSender, GrandpaJustificationStream};
 pub use observer::run_grandpa_observer;
+pub use participation::{
+	run_voter_participation_monitor, ParticipationHistory, SetParticipation,
+	SharedVoterParticipation, VoterParticipation,
+};
 pub use voting_rule::{
-	BeforeBestBlockBy, ThreeQuartersOfTheUnfinalizedChain, VotingRule, VotingRuleResult,
+	BeforeBestBlockBy, FinalityOracle, FinalityOracleResult, FollowFinalityOracle,
+	OffchainStorageOracle, ThreeQuartersOfTheUnfinalizedChain, VotingRule, VotingRuleResult,
 	VotingRulesBuilder,
 };
 
@@ -678,6 +689,12 @@ pub struct GrandpaParams<Block: BlockT, C, N, SC, VR> {
 	pub prometheus_registry: Option<prometheus_endpoint::Registry>,
 	/// The voter state is exposed at an RPC endpoint.
 	pub shared_voter_state: SharedVoterState,
+	/// The rounds concluded by the voter are noted towards the participation monitor.
+	pub shared_voter_participation: SharedVoterParticipation,
+	/// Where to report the equivocations of other voters.
+	///
+	/// If `None`, they are submitted to the runtime directly.
+	pub equivocation_reporting: Option<EquivocationSink>,
 	/// TelemetryHandle instance.
 	pub telemetry: Option<TelemetryHandle>,
 }
@@ -726,6 +743,8 @@ where
 		voting_rule,
 		prometheus_registry,
 		shared_voter_state,
+		shared_voter_participation,
+		equivocation_reporting,
 		telemetry,
 	} = grandpa_params;
 
@@ -798,6 +817,8 @@ where
 		voter_commands_rx,
 		prometheus_registry,
 		shared_voter_state,
+		shared_voter_participation,
+		equivocation_reporting,
 		justification_sender,
 		telemetry,
 	);
@@ -865,6 +886,8 @@ where
 		voter_commands_rx: TracingUnboundedReceiver<VoterCommand<Block::Hash, NumberFor<Block>>>,
 		prometheus_registry: Option<prometheus_endpoint::Registry>,
 		shared_voter_state: SharedVoterState,
+		shared_voter_participation: SharedVoterParticipation,
+		equivocation_reporting: Option<EquivocationSink>,
 		justification_sender: GrandpaJustificationSender<Block>,
 		telemetry: Option<TelemetryHandle>,
 	) -> Self {
@@ -890,6 +913,8 @@ where
 			voter_set_state: persistent_data.set_state,
 			metrics: metrics.as_ref().map(|m| m.environment.clone()),
 			justification_sender: Some(justification_sender),
+			voter_participation: Some(shared_voter_participation),
+			equivocation_reporting,
 			telemetry: telemetry.clone(),
 			_phantom: PhantomData,
 		});
@@ -1035,6 +1060,8 @@ where
 					voting_rule: self.env.voting_rule.clone(),
 					metrics: self.env.metrics.clone(),
 					justification_sender: self.env.justification_sender.clone(),
+					voter_participation: self.env.voter_participation.clone(),
+					equivocation_reporting: self.env.equivocation_reporting.clone(),
 					telemetry: self.telemetry.clone(),
 					_phantom: PhantomData,
 				});
@@ -0,0 +1,493 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Monitoring of the participation of the GRANDPA voters.
+//!
+//! Whenever the local voter concludes a round it notes the votes it has seen in that round
+//! through the [`SharedVoterParticipation`] it was started with, together with the authority set
+//! the round belongs to. The monitor records for every authority of that set whether it prevoted
+//! and precommitted in the round. The resulting history is kept for the last
+//! `MAX_TRACKED_SETS` authority sets, exposed through [`SharedVoterParticipation`] and
+//! Prometheus and persisted in the aux store so that it survives restarts. Every recorded round
+//! only rewrites the participation of its own set, and sets dropped from the history are deleted.
+//!
+//! Participation is taken from the votes of the concluded round as seen by the voter, not from
+//! the gossip messages. Votes that arrive after the round was concluded are therefore not
+//! counted, and neither are rounds that are still running in the background when the authority
+//! set changes, since they are never concluded by the voter.
+
+use std::{
+	collections::{BTreeMap, HashSet, VecDeque},
+	sync::Arc,
+};
+
+use finality_grandpa::{voter_set::VoterSet, Message, SignedMessage};
+use futures::prelude::*;
+use log::{debug, warn};
+use parity_scale_codec::{Decode, Encode};
+use parking_lot::{Mutex, RwLock};
+use prometheus_endpoint::{register, CounterVec, GaugeVec, Opts, PrometheusError, Registry, U64};
+
+use sc_client_api::backend::AuxStore;
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
+use sp_blockchain::Result as ClientResult;
+use sp_finality_grandpa::{RoundNumber, SetId};
+
+use crate::{aux_schema, AuthorityId};
+
+/// The number of authority sets the participation history is kept for.
+const MAX_TRACKED_SETS: usize = 16;
+
+/// Participation of a single voter in the rounds of an authority set.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct VoterParticipation {
+	/// The number of rounds in which no prevote of the voter was seen.
+	pub prevotes_missed: u64,
+	/// The number of rounds in which no precommit of the voter was seen.
+	pub precommits_missed: u64,
+	/// The number of most recent rounds in which the voter did not vote at all.
+	pub consecutive_rounds_missed: u64,
+	/// The last round in which the voter cast a vote, if any.
+	pub last_seen_round: Option<RoundNumber>,
+}
+
+/// Participation of the voters of a single authority set.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SetParticipation {
+	/// The id of the authority set.
+	pub set_id: SetId,
+	/// The first round of the set that was observed.
+	pub first_round: RoundNumber,
+	/// The last round of the set that was observed.
+	pub last_round: RoundNumber,
+	/// The number of rounds observed.
+	pub rounds_observed: u64,
+	/// The participation of every voter of the set.
+	pub voters: BTreeMap<AuthorityId, VoterParticipation>,
+}
+
+/// Participation of the voters of the most recent authority sets, oldest first.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ParticipationHistory {
+	/// The observed authority sets, oldest first.
+	pub sets: VecDeque<SetParticipation>,
+}
+
+impl ParticipationHistory {
+	/// Get the ids of the tracked authority sets, oldest first.
+	fn set_ids(&self) -> Vec<SetId> {
+		self.sets.iter().map(|set| set.set_id).collect()
+	}
+
+	/// Record the votes seen in `round` of the authority set `set_id` with the given `voters`.
+	fn record_round(
+		&mut self,
+		set_id: SetId,
+		voters: &[AuthorityId],
+		round: RoundNumber,
+		votes: &RoundVotes,
+	) {
+		if self.sets.back().map_or(true, |set| set.set_id != set_id) {
+			while self.sets.len() >= MAX_TRACKED_SETS {
+				self.sets.pop_front();
+			}
+			self.sets.push_back(SetParticipation {
+				set_id,
+				first_round: round,
+				last_round: round,
+				rounds_observed: 0,
+				voters: voters.iter().map(|id| (id.clone(), Default::default())).collect(),
+			});
+		}
+
+		let set = self.sets.back_mut().expect("an entry for the set was pushed above; qed");
+		set.first_round = set.first_round.min(round);
+		set.last_round = set.last_round.max(round);
+		set.rounds_observed += 1;
+
+		for (id, participation) in set.voters.iter_mut() {
+			let prevoted = votes.prevotes.contains(id);
+			let precommitted = votes.precommits.contains(id);
+
+			participation.prevotes_missed += u64::from(!prevoted);
+			participation.precommits_missed += u64::from(!precommitted);
+			if prevoted || precommitted {
+				participation.consecutive_rounds_missed = 0;
+				participation.last_seen_round =
+					Some(participation.last_seen_round.map_or(round, |last| last.max(round)));
+			} else {
+				participation.consecutive_rounds_missed += 1;
+			}
+		}
+	}
+}
+
+/// Shared participation history of the GRANDPA voters, kept up to date by the monitor
+/// started with [`run_voter_participation_monitor`].
+///
+/// The GRANDPA voter has to be started with the same instance, see
+/// [`GrandpaParams::shared_voter_participation`](crate::GrandpaParams).
+#[derive(Clone, Default)]
+pub struct SharedVoterParticipation {
+	inner: Arc<RwLock<ParticipationHistory>>,
+	concluded_rounds: Arc<Mutex<Option<TracingUnboundedSender<ConcludedRound>>>>,
+}
+
+impl SharedVoterParticipation {
+	/// Create a new empty `SharedVoterParticipation` instance.
+	pub fn empty() -> Self {
+		Self::default()
+	}
+
+	/// Get a copy of the participation history.
+	pub fn history(&self) -> ParticipationHistory {
+		self.inner.read().clone()
+	}
+
+	/// Note that the voter of the authority set `set_id` with the given `voters` concluded
+	/// `round`, having seen the given `votes` in it.
+	///
+	/// Ignored if no monitor is running.
+	pub(crate) fn note_concluded_round<H, N, S>(
+		&self,
+		set_id: SetId,
+		voters: &VoterSet<AuthorityId>,
+		round: RoundNumber,
+		votes: &[SignedMessage<H, N, S, AuthorityId>],
+	) {
+		let mut concluded_rounds = self.concluded_rounds.lock();
+		let sender = match concluded_rounds.as_ref() {
+			Some(sender) => sender,
+			None => return,
+		};
+
+		let concluded = ConcludedRound {
+			set_id,
+			voters: voters.iter().map(|(id, _)| id.clone()).collect(),
+			round,
+			votes: RoundVotes::from_votes(votes),
+		};
+
+		if sender.unbounded_send(concluded).is_err() {
+			// the monitor has stopped.
+			*concluded_rounds = None;
+		}
+	}
+}
+
+/// A round concluded by the voter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ConcludedRound {
+	set_id: SetId,
+	voters: Vec<AuthorityId>,
+	round: RoundNumber,
+	votes: RoundVotes,
+}
+
+/// The voters seen voting in a round.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct RoundVotes {
+	prevotes: HashSet<AuthorityId>,
+	precommits: HashSet<AuthorityId>,
+}
+
+impl RoundVotes {
+	fn from_votes<H, N, S>(votes: &[SignedMessage<H, N, S, AuthorityId>]) -> Self {
+		let mut round_votes = Self::default();
+		for vote in votes {
+			match vote.message {
+				Message::Prevote(_) => {
+					round_votes.prevotes.insert(vote.id.clone());
+				},
+				Message::Precommit(_) => {
+					round_votes.precommits.insert(vote.id.clone());
+				},
+				Message::PrimaryPropose(_) => {},
+			}
+		}
+		round_votes
+	}
+}
+
+struct Metrics {
+	prevotes_missed: CounterVec<U64>,
+	precommits_missed: CounterVec<U64>,
+	consecutive_rounds_missed: GaugeVec<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			prevotes_missed: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_finality_grandpa_voter_prevotes_missed_total",
+						"Number of observed GRANDPA rounds without a prevote of the voter.",
+					),
+					&["authority"],
+				)?,
+				registry,
+			)?,
+			precommits_missed: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_finality_grandpa_voter_precommits_missed_total",
+						"Number of observed GRANDPA rounds without a precommit of the voter.",
+					),
+					&["authority"],
+				)?,
+				registry,
+			)?,
+			consecutive_rounds_missed: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_finality_grandpa_voter_consecutive_rounds_missed",
+						"Number of most recent GRANDPA rounds in which the voter did not vote.",
+					),
+					&["authority"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	fn report(&self, voters: &[AuthorityId], votes: &RoundVotes, history: &ParticipationHistory) {
+		let participation = history.sets.back().map(|set| &set.voters);
+		for id in voters {
+			let label = id.to_string();
+			let label = [label.as_str()];
+			if !votes.prevotes.contains(id) {
+				self.prevotes_missed.with_label_values(&label).inc();
+			}
+			if !votes.precommits.contains(id) {
+				self.precommits_missed.with_label_values(&label).inc();
+			}
+			if let Some(participation) = participation.and_then(|voters| voters.get(id)) {
+				self.consecutive_rounds_missed
+					.with_label_values(&label)
+					.set(participation.consecutive_rounds_missed);
+			}
+		}
+	}
+}
+
+/// Run the monitor of the participation of the GRANDPA voters as a task.
+///
+/// The history persisted by an earlier run is loaded into `participation` before the task is
+/// returned. The voter has to be started with the same `participation`.
+pub fn run_voter_participation_monitor<C>(
+	client: Arc<C>,
+	participation: SharedVoterParticipation,
+	prometheus_registry: Option<&Registry>,
+) -> ClientResult<impl Future<Output = ()> + Send>
+where
+	C: AuxStore + Send + Sync + 'static,
+{
+	if let Some(history) = aux_schema::load_voter_participation(&*client)? {
+		*participation.inner.write() = history;
+	}
+
+	let metrics = match prometheus_registry.map(Metrics::register) {
+		Some(Ok(metrics)) => Some(metrics),
+		Some(Err(e)) => {
+			warn!(target: "afg", "Failed to register voter participation metrics: {:?}", e);
+			None
+		},
+		None => None,
+	};
+
+	let (sender, mut concluded_rounds) = tracing_unbounded("mpsc_grandpa_voter_participation");
+	*participation.concluded_rounds.lock() = Some(sender);
+
+	Ok(async move {
+		while let Some(ConcludedRound { set_id, voters, round, votes }) =
+			concluded_rounds.next().await
+		{
+			let (set, set_ids, pruned) = {
+				let mut history = participation.inner.write();
+				let tracked = history.set_ids();
+				history.record_round(set_id, &voters, round, &votes);
+				if let Some(metrics) = &metrics {
+					metrics.report(&voters, &votes, &history);
+				}
+
+				let set = history.sets.back().expect("a round was just recorded; qed").clone();
+				let set_ids = history.set_ids();
+				if set_ids == tracked {
+					(set, None, Vec::new())
+				} else {
+					let pruned = tracked.into_iter().filter(|id| !set_ids.contains(id)).collect();
+					(set, Some(set_ids), pruned)
+				}
+			};
+
+			debug!(
+				target: "afg",
+				"Recorded voter participation of round {} in set {}",
+				round,
+				set_id,
+			);
+
+			if let Err(e) =
+				aux_schema::write_set_participation(&*client, &set, set_ids.as_deref(), &pruned)
+			{
+				warn!(target: "afg", "Failed to persist voter participation: {}", e);
+			}
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use finality_grandpa::{Precommit, Prevote};
+	use sp_core::crypto::ByteArray;
+
+	fn voters() -> Vec<AuthorityId> {
+		(1..=3u8).map(|i| AuthorityId::from_slice(&[i; 32]).unwrap()).collect()
+	}
+
+	fn votes(prevotes: &[usize], precommits: &[usize]) -> RoundVotes {
+		let voters = voters();
+		RoundVotes {
+			prevotes: prevotes.iter().map(|i| voters[*i].clone()).collect(),
+			precommits: precommits.iter().map(|i| voters[*i].clone()).collect(),
+		}
+	}
+
+	fn signed(
+		message: Message<u64, u64>,
+		voter: usize,
+	) -> SignedMessage<u64, u64, (), AuthorityId> {
+		SignedMessage { message, signature: (), id: voters()[voter].clone() }
+	}
+
+	#[test]
+	fn concluded_rounds_are_recorded_for_their_set() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let participation = SharedVoterParticipation::empty();
+
+		// rounds concluded before the monitor is started are not recorded.
+		let voter_set = VoterSet::new(voters().into_iter().map(|id| (id, 1))).unwrap();
+		participation.note_concluded_round::<u64, u64, ()>(1, &voter_set, 1, &[]);
+
+		let monitor =
+			run_voter_participation_monitor(client.clone(), participation.clone(), None).unwrap();
+
+		let prevote = || Message::Prevote(Prevote::new(1, 1));
+		let precommit = || Message::Precommit(Precommit::new(1, 1));
+		participation.note_concluded_round(
+			1,
+			&voter_set,
+			2,
+			&[signed(prevote(), 0), signed(prevote(), 1), signed(precommit(), 0)],
+		);
+
+		// the voter of the next set is made up of the first two voters only.
+		let next_voter_set = VoterSet::new(voters().into_iter().take(2).map(|id| (id, 1))).unwrap();
+		participation.note_concluded_round(2, &next_voter_set, 1, &[signed(precommit(), 1)]);
+
+		// stop the monitor once it has processed the noted rounds.
+		participation.concluded_rounds.lock().take();
+		futures::executor::block_on(monitor);
+
+		let history = participation.history();
+		assert_eq!(
+			history
+				.sets
+				.iter()
+				.map(|set| (set.set_id, set.first_round, set.rounds_observed, set.voters.len()))
+				.collect::<Vec<_>>(),
+			vec![(1, 2, 1, 3), (2, 1, 1, 2)],
+		);
+
+		let voters = voters();
+		assert_eq!(history.sets[0].voters[&voters[1]].precommits_missed, 1);
+		assert_eq!(history.sets[0].voters[&voters[1]].last_seen_round, Some(2));
+		assert_eq!(history.sets[0].voters[&voters[2]].consecutive_rounds_missed, 1);
+		assert_eq!(history.sets[1].voters[&voters[1]].prevotes_missed, 1);
+		assert_eq!(history.sets[1].voters[&voters[0]].consecutive_rounds_missed, 1);
+
+		assert_eq!(aux_schema::load_voter_participation(&*client).unwrap(), Some(history));
+	}
+
+	#[test]
+	fn sets_dropped_from_the_history_are_pruned() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let participation = SharedVoterParticipation::empty();
+		let monitor =
+			run_voter_participation_monitor(client.clone(), participation.clone(), None).unwrap();
+
+		let voter_set = VoterSet::new(voters().into_iter().map(|id| (id, 1))).unwrap();
+		for set_id in 1..=MAX_TRACKED_SETS as u64 + 1 {
+			participation.note_concluded_round::<u64, u64, ()>(set_id, &voter_set, 1, &[]);
+			participation.note_concluded_round::<u64, u64, ()>(set_id, &voter_set, 2, &[]);
+		}
+
+		participation.concluded_rounds.lock().take();
+		futures::executor::block_on(monitor);
+
+		let history = participation.history();
+		assert_eq!(history.sets.len(), MAX_TRACKED_SETS);
+		assert_eq!(history.sets.front().unwrap().set_id, 2);
+		assert_eq!(history.sets.back().unwrap().rounds_observed, 2);
+
+		assert_eq!(aux_schema::load_set_participation(&*client, 1), None);
+		assert_eq!(aux_schema::load_voter_participation(&*client).unwrap(), Some(history));
+	}
+
+	#[test]
+	fn participation_is_recorded_per_set() {
+		let mut history = ParticipationHistory::default();
+		let voters = voters();
+
+		history.record_round(1, &voters, 1, &votes(&[0, 1], &[0, 1]));
+		history.record_round(1, &voters, 2, &votes(&[0, 1], &[0]));
+		history.record_round(1, &voters, 3, &votes(&[0], &[0]));
+
+		let set = history.sets.back().unwrap();
+		assert_eq!(
+			(set.set_id, set.first_round, set.last_round, set.rounds_observed),
+			(1, 1, 3, 3)
+		);
+		assert_eq!(
+			set.voters[&voters[1]],
+			VoterParticipation {
+				prevotes_missed: 1,
+				precommits_missed: 2,
+				consecutive_rounds_missed: 1,
+				last_seen_round: Some(2),
+			},
+		);
+		assert_eq!(
+			set.voters[&voters[2]],
+			VoterParticipation {
+				prevotes_missed: 3,
+				precommits_missed: 3,
+				consecutive_rounds_missed: 3,
+				last_seen_round: None,
+			},
+		);
+
+		for set_id in 2..=MAX_TRACKED_SETS as u64 + 1 {
+			history.record_round(set_id, &voters, 1, &votes(&[0], &[0]));
+		}
+		assert_eq!(history.sets.len(), MAX_TRACKED_SETS);
+		assert_eq!(history.sets.front().unwrap().set_id, 2);
+	}
+}
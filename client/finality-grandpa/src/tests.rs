@@ -264,6 +264,8 @@ fn initialize_grandpa(
 			voting_rule: (),
 			prometheus_registry: None,
 			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_participation: SharedVoterParticipation::empty(),
+			equivocation_reporting: None,
 			telemetry: None,
 		};
 		let voter =
@@ -411,6 +413,8 @@ fn finalize_3_voters_1_full_observer() {
 			voting_rule: (),
 			prometheus_registry: None,
 			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_participation: SharedVoterParticipation::empty(),
+			equivocation_reporting: None,
 			telemetry: None,
 		};
 
@@ -500,6 +504,8 @@ fn transition_3_voters_twice_1_full_observer() {
 			voting_rule: (),
 			prometheus_registry: None,
 			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_participation: SharedVoterParticipation::empty(),
+			equivocation_reporting: None,
 			telemetry: None,
 		};
 
@@ -977,6 +983,8 @@ fn voter_persists_its_votes() {
 			voting_rule: VotingRulesBuilder::default().build(),
 			prometheus_registry: None,
 			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_participation: SharedVoterParticipation::empty(),
+			equivocation_reporting: None,
 			telemetry: None,
 		};
 
@@ -1018,6 +1026,8 @@ fn voter_persists_its_votes() {
 			voting_rule: VotingRulesBuilder::default().build(),
 			prometheus_registry: None,
 			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_participation: SharedVoterParticipation::empty(),
+			equivocation_reporting: None,
 			telemetry: None,
 		};
 
@@ -1229,6 +1239,8 @@ fn voter_catches_up_to_latest_round_when_behind() {
 			voting_rule: (),
 			prometheus_registry: None,
 			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_participation: SharedVoterParticipation::empty(),
+			equivocation_reporting: None,
 			telemetry: None,
 		};
 
@@ -1355,6 +1367,8 @@ where
 		voting_rule,
 		metrics: None,
 		justification_sender: None,
+		voter_participation: None,
+		equivocation_reporting: None,
 		telemetry: None,
 		_phantom: PhantomData,
 	}
@@ -22,11 +22,16 @@
 //! restrictions that are taken into account by the GRANDPA environment when
 //! selecting a finality target to vote on.
 
-use std::{future::Future, pin::Pin, sync::Arc};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
 
 use dyn_clone::DynClone;
+use futures::future::{self, Either};
+use futures_timer::Delay;
+use log::debug;
+use parity_scale_codec::Decode;
 
 use sc_client_api::blockchain::HeaderBackend;
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, Header, NumberFor, One, Zero},
@@ -169,6 +174,120 @@ where
 	}
 }
 
+/// A future returned by a `FinalityOracle` with the highest block number it allows voting on.
+pub type FinalityOracleResult<Block> =
+	Pin<Box<dyn Future<Output = Option<NumberFor<Block>>> + Send>>;
+
+/// An external source deciding how far GRANDPA is allowed to vote, e.g. a bridge relayer
+/// confirming that blocks were delivered to a counterparty chain.
+pub trait FinalityOracle<Block: BlockT>: Send + Sync {
+	/// Return the highest block number on the chain of `target` that may be voted on, or `None`
+	/// if the oracle is not able to tell.
+	fn approved_number(&self, target: &Block::Header) -> FinalityOracleResult<Block>;
+}
+
+/// A [`FinalityOracle`] reading the SCALE-encoded approved block number from a key of the
+/// persistent offchain storage, where it is written by an offchain worker or a local service.
+#[derive(Clone)]
+pub struct OffchainStorageOracle<S> {
+	storage: S,
+	key: Vec<u8>,
+}
+
+impl<S> OffchainStorageOracle<S> {
+	/// Create a new oracle reading `key` from `storage`.
+	pub fn new(storage: S, key: Vec<u8>) -> Self {
+		OffchainStorageOracle { storage, key }
+	}
+}
+
+impl<Block, S> FinalityOracle<Block> for OffchainStorageOracle<S>
+where
+	Block: BlockT,
+	S: OffchainStorage,
+{
+	fn approved_number(&self, _target: &Block::Header) -> FinalityOracleResult<Block> {
+		let approved = self
+			.storage
+			.get(STORAGE_PREFIX, &self.key)
+			.and_then(|encoded| NumberFor::<Block>::decode(&mut &encoded[..]).ok());
+
+		Box::pin(future::ready(approved))
+	}
+}
+
+/// A custom voting rule that doesn't vote past the block approved by a [`FinalityOracle`],
+/// nor below the base. If the oracle can't tell or doesn't answer within `timeout`, the
+/// `fallback` voting rule is applied instead.
+pub struct FollowFinalityOracle<O, R> {
+	oracle: Arc<O>,
+	timeout: Duration,
+	fallback: R,
+}
+
+impl<O, R: Clone> Clone for FollowFinalityOracle<O, R> {
+	fn clone(&self) -> Self {
+		FollowFinalityOracle {
+			oracle: self.oracle.clone(),
+			timeout: self.timeout,
+			fallback: self.fallback.clone(),
+		}
+	}
+}
+
+impl<O, R> FollowFinalityOracle<O, R> {
+	/// Create a new voting rule following `oracle`, applying `fallback` if it fails to answer
+	/// within `timeout`.
+	pub fn new(oracle: O, timeout: Duration, fallback: R) -> Self {
+		FollowFinalityOracle { oracle: Arc::new(oracle), timeout, fallback }
+	}
+}
+
+impl<Block, B, O, R> VotingRule<Block, B> for FollowFinalityOracle<O, R>
+where
+	Block: BlockT,
+	B: HeaderBackend<Block> + 'static,
+	O: FinalityOracle<Block>,
+	R: VotingRule<Block, B> + Clone,
+{
+	fn restrict_vote(
+		&self,
+		backend: Arc<B>,
+		base: &Block::Header,
+		best_target: &Block::Header,
+		current_target: &Block::Header,
+	) -> VotingRuleResult<Block> {
+		let approved = self.oracle.approved_number(current_target);
+		let timeout = Delay::new(self.timeout);
+		let fallback =
+			self.fallback.restrict_vote(backend.clone(), base, best_target, current_target);
+		let base_number = *base.number();
+		let current_target = current_target.clone();
+
+		Box::pin(async move {
+			let approved = match future::select(approved, timeout).await {
+				Either::Left((approved, _)) => approved,
+				Either::Right(_) => {
+					debug!(target: "afg", "Finality oracle timed out, using fallback rule");
+					None
+				},
+			};
+
+			let approved = match approved {
+				Some(approved) => approved,
+				None => return fallback.await,
+			};
+
+			// our current target is already lower than the oracle would restrict
+			if approved >= *current_target.number() {
+				return None
+			}
+
+			find_target(&*backend, approved.max(base_number), &current_target)
+		})
+	}
+}
+
 // walk backwards until we find the target block
 fn find_target<Block, B>(
 	backend: &B,
@@ -333,8 +452,10 @@ where
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use parity_scale_codec::Encode;
 	use sc_block_builder::BlockBuilderProvider;
 	use sp_consensus::BlockOrigin;
+	use sp_core::offchain::storage::InMemOffchainStorage;
 	use sp_runtime::traits::Header as _;
 
 	use substrate_test_runtime_client::{
@@ -435,4 +556,62 @@ mod tests {
 			assert_eq!(number, expected, "best = {}, lag = 2, base = {}", best_number, i);
 		}
 	}
+
+	#[derive(Clone)]
+	struct Approve(Option<u64>);
+	impl FinalityOracle<Block> for Approve {
+		fn approved_number(&self, _target: &Header) -> FinalityOracleResult<Block> {
+			Box::pin(std::future::ready(self.0))
+		}
+	}
+
+	#[derive(Clone)]
+	struct Unresponsive;
+	impl FinalityOracle<Block> for Unresponsive {
+		fn approved_number(&self, _target: &Header) -> FinalityOracleResult<Block> {
+			Box::pin(future::pending())
+		}
+	}
+
+	#[test]
+	fn follow_finality_oracle_restricts_vote() {
+		let mut client = Arc::new(TestClientBuilder::new().build());
+
+		for _ in 0..20 {
+			let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+
+			futures::executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+		}
+
+		let base = client.header(&BlockId::Number(5u32.into())).unwrap().unwrap();
+		let best = client.header(&BlockId::Hash(client.info().best_hash)).unwrap().unwrap();
+		let restrict = |rule: &dyn VotingRule<Block, Client<Backend>>| {
+			futures::executor::block_on(rule.restrict_vote(client.clone(), &base, &best, &best))
+				.map(|(_, number)| number)
+		};
+		let timeout = Duration::from_millis(50);
+
+		// votes are restricted to the approved block, but not below the base.
+		assert_eq!(restrict(&FollowFinalityOracle::new(Approve(Some(12)), timeout, ())), Some(12));
+		assert_eq!(restrict(&FollowFinalityOracle::new(Approve(Some(2)), timeout, ())), Some(5));
+		assert_eq!(restrict(&FollowFinalityOracle::new(Approve(Some(30)), timeout, ())), None);
+
+		// the fallback rule applies if the oracle can't tell or times out.
+		let fallback = BeforeBestBlockBy(4);
+		let rule = FollowFinalityOracle::new(Approve(None), timeout, fallback.clone());
+		assert_eq!(restrict(&rule), Some(16));
+		let rule = FollowFinalityOracle::new(Unresponsive, timeout, fallback.clone());
+		assert_eq!(restrict(&rule), Some(16));
+
+		// the approved number can be read from the offchain storage.
+		let mut storage = InMemOffchainStorage::default();
+		let oracle = OffchainStorageOracle::new(storage.clone(), b"approved".to_vec());
+		let rule = FollowFinalityOracle::new(oracle, timeout, fallback.clone());
+		assert_eq!(restrict(&rule), Some(16));
+
+		storage.set(STORAGE_PREFIX, b"approved", &10u64.encode());
+		let oracle = OffchainStorageOracle::new(storage, b"approved".to_vec());
+		let rule = FollowFinalityOracle::new(oracle, timeout, fallback);
+		assert_eq!(restrict(&rule), Some(10));
+	}
 }
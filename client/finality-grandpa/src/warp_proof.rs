@@ -67,6 +67,30 @@ pub struct WarpSyncFragment<Block: BlockT> {
 	pub justification: GrandpaJustification<Block>,
 }
 
+impl<Block: BlockT> WarpSyncFragment<Block> {
+	/// Verifies the justification of the fragment with the given set id and authorities.
+	///
+	/// Returns the next authorities if the header signals a standard authority set change.
+	pub(crate) fn verify(
+		&self,
+		set_id: SetId,
+		authorities: &AuthorityList,
+	) -> Result<Option<AuthorityList>, Error>
+	where
+		NumberFor<Block>: BlockNumberOps,
+	{
+		self.justification
+			.verify(set_id, authorities)
+			.map_err(|err| Error::InvalidProof(err.to_string()))?;
+
+		if self.justification.target().1 != self.header.hash() {
+			return Err(Error::InvalidProof("Mismatch between header and justification".to_owned()))
+		}
+
+		Ok(find_scheduled_change::<Block>(&self.header).map(|change| change.next_authorities))
+	}
+}
+
 /// An accumulated proof of multiple authority set changes.
 #[derive(Decode, Encode)]
 pub struct WarpSyncProof<Block: BlockT> {
@@ -208,28 +232,17 @@ impl<Block: BlockT> WarpSyncProof<Block> {
 			if let Some((set_id, list)) = hard_forks.get(&(hash, number)) {
 				current_set_id = *set_id;
 				current_authorities = list.clone();
-			} else {
-				proof
-					.justification
-					.verify(current_set_id, &current_authorities)
-					.map_err(|err| Error::InvalidProof(err.to_string()))?;
-
-				if proof.justification.target().1 != hash {
-					return Err(Error::InvalidProof(
-						"Mismatch between header and justification".to_owned(),
-					))
-				}
-
-				if let Some(scheduled_change) = find_scheduled_change::<Block>(&proof.header) {
-					current_authorities = scheduled_change.next_authorities;
-					current_set_id += 1;
-				} else if fragment_num != self.proofs.len() - 1 || !self.is_finished {
-					// Only the last fragment of the last proof message is allowed to be missing the
-					// authority set change.
-					return Err(Error::InvalidProof(
-						"Header is missing authority set change digest".to_string(),
-					))
-				}
+			} else if let Some(next_authorities) =
+				proof.verify(current_set_id, &current_authorities)?
+			{
+				current_authorities = next_authorities;
+				current_set_id += 1;
+			} else if fragment_num != self.proofs.len() - 1 || !self.is_finished {
+				// Only the last fragment of the last proof message is allowed to be missing the
+				// authority set change.
+				return Err(Error::InvalidProof(
+					"Header is missing authority set change digest".to_string(),
+				))
 			}
 		}
 		Ok((current_set_id, current_authorities))
@@ -22,6 +22,8 @@ use crate::NetworkProvider;
 use codec::{Decode, Encode};
 use futures::Future;
 pub use http::SharedClient;
+pub(crate) use http_policy::{HttpAllowList, HttpRecorder};
+pub use http_policy::{HttpAllowedHost, HttpMode};
 use libp2p::{Multiaddr, PeerId};
 use sp_core::{
 	offchain::{
@@ -34,6 +36,8 @@ pub use sp_offchain::STORAGE_PREFIX;
 
 mod http;
 
+mod http_policy;
+
 mod timestamp;
 
 fn unavailable_yet<R: Default>(name: &str) -> R {
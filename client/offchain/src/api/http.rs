@@ -27,10 +27,16 @@
 //! (i.e.: the socket should continue being processed) in the background even if the runtime isn't
 //! actively calling any function.
 
-use crate::api::timestamp;
+use crate::{
+	api::{
+		http_policy::{HttpAllowList, HttpRecorder, RecordedRequest, RecordedResponse},
+		timestamp,
+	},
+	metrics::MetricsLink,
+};
 use bytes::buf::{Buf, Reader};
 use fnv::FnvHashMap;
-use futures::{channel::mpsc, future, prelude::*};
+use futures::{channel::mpsc, future, future::BoxFuture, prelude::*};
 use hyper::{client, Body, Client as HyperClient};
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use once_cell::sync::Lazy;
@@ -46,21 +52,44 @@ use std::{
 
 const LOG_TARGET: &str = "offchain-worker::http";
 
-/// Wrapper struct used for keeping the hyper_rustls client running.
+type LazyHyperClient = Lazy<HyperClient<HttpsConnector<client::HttpConnector>, Body>>;
+
+/// Wrapper struct used for keeping the hyper_rustls client running, together with the
+/// restrictions and recording applied to the requests made through it.
 #[derive(Clone)]
-pub struct SharedClient(Arc<Lazy<HyperClient<HttpsConnector<client::HttpConnector>, Body>>>);
+pub struct SharedClient {
+	client: Arc<LazyHyperClient>,
+	allow_list: Option<Arc<HttpAllowList>>,
+	recorder: Option<Arc<HttpRecorder>>,
+	metrics: MetricsLink,
+}
 
 impl SharedClient {
 	pub fn new() -> Self {
-		Self(Arc::new(Lazy::new(|| {
-			let connector = HttpsConnectorBuilder::new()
-				.with_native_roots()
-				.https_or_http()
-				.enable_http1()
-				.enable_http2()
-				.build();
-			HyperClient::builder().build(connector)
-		})))
+		Self::with_policy(None, None, MetricsLink::default())
+	}
+
+	/// Creates a client that only contacts the hosts in `allow_list`, if any, and records or
+	/// replays requests with `recorder`, if any.
+	pub(crate) fn with_policy(
+		allow_list: Option<HttpAllowList>,
+		recorder: Option<HttpRecorder>,
+		metrics: MetricsLink,
+	) -> Self {
+		Self {
+			client: Arc::new(Lazy::new(|| {
+				let connector = HttpsConnectorBuilder::new()
+					.with_native_roots()
+					.https_or_http()
+					.enable_http1()
+					.enable_http2()
+					.build();
+				HyperClient::builder().build(connector)
+			})),
+			allow_list: allow_list.map(Arc::new),
+			recorder: recorder.map(Arc::new),
+			metrics,
+		}
 	}
 }
 
@@ -76,10 +105,17 @@ pub fn http(shared_client: SharedClient) -> (HttpApi, HttpWorker) {
 		// writing runtime code with hardcoded IDs.
 		next_id: HttpRequestId(rand::random::<u16>() % 2000),
 		requests: FnvHashMap::default(),
+		allow_list: shared_client.allow_list,
 	};
 
-	let engine =
-		HttpWorker { to_api, from_api, http_client: shared_client.0, requests: Vec::new() };
+	let engine = HttpWorker {
+		to_api,
+		from_api,
+		http_client: shared_client.client,
+		recorder: shared_client.recorder,
+		metrics: shared_client.metrics,
+		requests: Vec::new(),
+	};
 
 	(api, engine)
 }
@@ -98,6 +134,8 @@ pub struct HttpApi {
 	next_id: HttpRequestId,
 	/// List of HTTP requests in preparation or in progress.
 	requests: FnvHashMap<HttpRequestId, HttpApiRequest>,
+	/// Hosts that requests may be sent to, if restricted.
+	allow_list: Option<Arc<HttpAllowList>>,
 }
 
 /// One active request within `HttpApi`.
@@ -112,7 +150,7 @@ enum HttpApiRequest {
 	/// A request has been dispatched but the worker notified us of an error. We report this
 	/// failure to the user as an `IoError` and remove the request from the list as soon as
 	/// possible.
-	Fail(hyper::Error),
+	Fail(RequestError),
 }
 
 /// A request within `HttpApi` that has received a response.
@@ -147,6 +185,12 @@ impl HttpApi {
 		*request.method_mut() = hyper::Method::from_bytes(method.as_bytes()).map_err(|_| ())?;
 		*request.uri_mut() = hyper::Uri::from_maybe_shared(uri.to_owned()).map_err(|_| ())?;
 
+		if let Some(allow_list) = &self.allow_list {
+			if !allow_list.check(request.uri().host().unwrap_or_default()) {
+				return Err(())
+			}
+		}
+
 		let new_id = self.next_id;
 		debug_assert!(!self.requests.contains_key(&new_id));
 		match self.next_id.0.checked_add(1) {
@@ -613,10 +657,19 @@ enum WorkerToApi {
 		/// The ID that was passed to the worker.
 		id: HttpRequestId,
 		/// Error that happened.
-		error: hyper::Error,
+		error: RequestError,
 	},
 }
 
+/// Reason for a request to fail in the worker.
+#[derive(Debug)]
+enum RequestError {
+	/// The HTTP client returned an error.
+	Hyper(hyper::Error),
+	/// No response to the request was found in the recording being replayed.
+	NotRecorded,
+}
+
 /// Must be continuously polled for the [`HttpApi`] to properly work.
 pub struct HttpWorker {
 	/// Used to sends messages to the `HttpApi`.
@@ -624,15 +677,78 @@ pub struct HttpWorker {
 	/// Used to receive messages from the `HttpApi`.
 	from_api: TracingUnboundedReceiver<ApiToWorker>,
 	/// The engine that runs HTTP requests.
-	http_client: Arc<Lazy<HyperClient<HttpsConnector<client::HttpConnector>, Body>>>,
+	http_client: Arc<LazyHyperClient>,
+	/// Records requests, or answers them from a recording, if enabled.
+	recorder: Option<Arc<HttpRecorder>>,
+	/// Prometheus metrics.
+	metrics: MetricsLink,
 	/// HTTP requests that are being worked on by the engine.
 	requests: Vec<(HttpRequestId, HttpWorkerRequest)>,
 }
 
+impl HttpWorker {
+	/// Returns the future resolving to the response to `request`.
+	fn dispatch(
+		&self,
+		request: hyper::Request<hyper::Body>,
+	) -> BoxFuture<'static, Result<hyper::Response<hyper::Body>, RequestError>> {
+		let recorder = match &self.recorder {
+			Some(recorder) => recorder.clone(),
+			None => return self.http_client.request(request).map_err(RequestError::Hyper).boxed(),
+		};
+		let http_client = self.http_client.clone();
+
+		async move {
+			// The whole request is needed to identify it in the recording.
+			let (parts, body) = request.into_parts();
+			let body = hyper::body::to_bytes(body).await.map_err(RequestError::Hyper)?;
+			let recorded_request = RecordedRequest {
+				method: parts.method.to_string(),
+				uri: parts.uri.to_string(),
+				body: body.to_vec().into(),
+			};
+
+			let recorded_response = if recorder.is_replay() {
+				recorder.replay(&recorded_request).ok_or(RequestError::NotRecorded)?
+			} else {
+				let request = hyper::Request::from_parts(parts, hyper::Body::from(body));
+				let (parts, body) =
+					http_client.request(request).await.map_err(RequestError::Hyper)?.into_parts();
+				let body = hyper::body::to_bytes(body).await.map_err(RequestError::Hyper)?;
+				let recorded_response = RecordedResponse {
+					status: parts.status.as_u16(),
+					headers: parts
+						.headers
+						.iter()
+						.map(|(name, value)| (name.to_string(), value.as_bytes().to_vec().into()))
+						.collect(),
+					body: body.to_vec().into(),
+				};
+				recorder.record(recorded_request, recorded_response.clone());
+				recorded_response
+			};
+
+			let mut response = hyper::Response::new(hyper::Body::from(recorded_response.body.0));
+			*response.status_mut() = hyper::StatusCode::from_u16(recorded_response.status)
+				.unwrap_or(hyper::StatusCode::INTERNAL_SERVER_ERROR);
+			for (name, value) in recorded_response.headers {
+				if let (Ok(name), Ok(value)) = (
+					hyper::header::HeaderName::try_from(name),
+					hyper::header::HeaderValue::try_from(value.0),
+				) {
+					response.headers_mut().append(name, value);
+				}
+			}
+			Ok(response)
+		}
+		.boxed()
+	}
+}
+
 /// HTTP request being processed by the worker.
 enum HttpWorkerRequest {
 	/// Request has been dispatched and is waiting for a response from the Internet.
-	Dispatched(hyper::client::ResponseFuture),
+	Dispatched(BoxFuture<'static, Result<hyper::Response<hyper::Body>, RequestError>>),
 	/// Progressively reading the body of the response and sending it to the channel.
 	ReadBody {
 		/// Body to read `Chunk`s from. Only used if the channel is ready to accept data.
@@ -665,6 +781,9 @@ impl Future for HttpWorker {
 						},
 						Poll::Ready(Ok(response)) => response,
 						Poll::Ready(Err(error)) => {
+							me.metrics.report(|metrics| {
+								metrics.http_requests.with_label_values(&["error"]).inc()
+							});
 							let _ = me.to_api.unbounded_send(WorkerToApi::Fail { id, error });
 							continue // don't insert the request back
 						},
@@ -673,6 +792,9 @@ impl Future for HttpWorker {
 					// We received a response! Decompose it into its parts.
 					let (head, body) = response.into_parts();
 					let (status_code, headers) = (head.status, head.headers);
+					me.metrics.report(|metrics| {
+						metrics.http_requests.with_label_values(&[status_code.as_str()]).inc()
+					});
 
 					let (body_tx, body_rx) = mpsc::channel(3);
 					let _ = me.to_api.unbounded_send(WorkerToApi::Response {
@@ -724,7 +846,7 @@ impl Future for HttpWorker {
 			Poll::Pending => {},
 			Poll::Ready(None) => return Poll::Ready(()), // stops the worker
 			Poll::Ready(Some(ApiToWorker::Dispatch { id, request })) => {
-				let future = me.http_client.request(request);
+				let future = me.dispatch(request);
 				debug_assert!(me.requests.iter().all(|(i, _)| *i != id));
 				me.requests.push((id, HttpWorkerRequest::Dispatched(future)));
 				cx.waker().wake_by_ref(); // reschedule the task to poll the request
@@ -755,7 +877,7 @@ impl fmt::Debug for HttpWorkerRequest {
 #[cfg(test)]
 mod tests {
 	use super::{
-		super::{tests::TestNetwork, AsyncApi},
+		super::{tests::TestNetwork, AsyncApi, HttpMode},
 		*,
 	};
 	use crate::api::timestamp;
@@ -776,9 +898,11 @@ mod tests {
 		() => {
 			build_api_server!(hyper::Response::new(hyper::Body::from("Hello World!")))
 		};
-		( $response:expr ) => {{
-			let hyper_client = SHARED_CLIENT.clone();
-			let (api, worker) = http(hyper_client.clone());
+		( $response:expr ) => {
+			build_api_server!(SHARED_CLIENT.clone(), $response)
+		};
+		( $shared_client:expr, $response:expr ) => {{
+			let (api, worker) = http($shared_client);
 
 			let (addr_tx, addr_rx) = std::sync::mpsc::channel();
 			std::thread::spawn(move || {
@@ -1095,6 +1219,66 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn requests_to_hosts_not_allowed_fail() {
+		let allow_list = HttpAllowList::new(vec!["127.0.0.1=1".parse().unwrap()]);
+		let (mut api, addr) = build_api_server!(
+			SharedClient::with_policy(Some(allow_list), None, Default::default()),
+			{ hyper::Response::new(hyper::Body::from("Hello World!")) }
+		);
+
+		assert!(api.request_start("GET", "http://example.com").is_err());
+		assert!(api.request_start("GET", &format!("http://{}", addr)).is_ok());
+		// Only one request per minute is allowed to the host.
+		assert!(api.request_start("GET", &format!("http://{}", addr)).is_err());
+	}
+
+	#[test]
+	fn recorded_requests_are_replayed() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("http.jsonl");
+
+		let read_response = |api: &mut HttpApi, uri: &str| {
+			let id = api.request_start("POST", uri).unwrap();
+			api.request_write_body(id, b"ping", Some(deadline)).unwrap();
+			api.request_write_body(id, &[], Some(deadline)).unwrap();
+			let status = api.response_wait(&[id], Some(deadline))[0];
+			let mut buf = vec![0; 2048];
+			let n = api.response_read_body(id, &mut buf, Some(deadline)).unwrap_or(0);
+			(status, buf[..n].to_vec())
+		};
+
+		let uri = {
+			let recorder = HttpRecorder::new(&HttpMode::Record(path.clone())).unwrap();
+			let (mut api, addr) = build_api_server!(
+				SharedClient::with_policy(None, recorder, Default::default()),
+				hyper::Response::new(hyper::Body::from("Hello World!"))
+			);
+			let uri = format!("http://{}", addr);
+
+			let (status, body) = read_response(&mut api, &uri);
+			assert_eq!(status, HttpRequestStatus::Finished(200));
+			assert_eq!(body, b"Hello World!");
+			uri
+		};
+
+		let recorder = HttpRecorder::new(&HttpMode::Replay(path)).unwrap();
+		let (mut api, worker) = http(SharedClient::with_policy(None, recorder, Default::default()));
+		std::thread::spawn(move || {
+			let _ = tokio::runtime::Runtime::new().unwrap().block_on(worker);
+		});
+
+		// The server is gone, the response is served from the recording.
+		let (status, body) = read_response(&mut api, &uri);
+		assert_eq!(status, HttpRequestStatus::Finished(200));
+		assert_eq!(body, b"Hello World!");
+
+		// Requests that were not recorded fail.
+		let (status, _) = read_response(&mut api, "http://127.0.0.1:1/unknown");
+		assert_eq!(status, HttpRequestStatus::IoError);
+	}
+
 	#[test]
 	fn shared_http_client_is_only_initialized_on_access() {
 		let shared_client = SharedClient::new();
@@ -1110,7 +1294,7 @@ mod tests {
 		}
 
 		// Check that the http client wasn't initialized, because it wasn't used.
-		assert!(Lazy::into_value(Arc::try_unwrap(shared_client.0).unwrap()).is_err());
+		assert!(Lazy::into_value(Arc::try_unwrap(shared_client.client).unwrap()).is_err());
 
 		let shared_client = SharedClient::new();
 
@@ -1125,6 +1309,6 @@ mod tests {
 		}
 
 		// Check that the http client initialized, because it was used.
-		assert!(Lazy::into_value(Arc::try_unwrap(shared_client.0).unwrap()).is_ok());
+		assert!(Lazy::into_value(Arc::try_unwrap(shared_client.client).unwrap()).is_ok());
 	}
 }
@@ -0,0 +1,297 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Operator controls over the HTTP requests made by offchain workers.
+//!
+//! An [`HttpAllowList`] restricts the hosts that may be contacted and how often, while an
+//! [`HttpRecorder`] either saves every request/response pair to a file or answers requests from
+//! such a file without touching the network. Both are shared by the HTTP workers of all blocks.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use std::{
+	collections::{HashMap, VecDeque},
+	fs::{File, OpenOptions},
+	io::{BufRead, BufReader, Write},
+	path::{Path, PathBuf},
+	str::FromStr,
+	time::{Duration, Instant},
+};
+
+const LOG_TARGET: &str = "offchain-worker::http";
+
+/// Window over which [`HttpAllowedHost::requests_per_minute`] is enforced.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// A host offchain workers are allowed to send HTTP requests to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpAllowedHost {
+	/// Name of the host. A leading `*.` also allows all of its subdomains.
+	pub host: String,
+	/// Maximum number of requests per minute to this host, or `None` for no limit.
+	pub requests_per_minute: Option<u32>,
+}
+
+impl HttpAllowedHost {
+	fn matches(&self, host: &str) -> bool {
+		match self.host.strip_prefix("*.") {
+			Some(domain) =>
+				host.eq_ignore_ascii_case(domain) ||
+					(host.len() > domain.len() &&
+						host[host.len() - domain.len()..].eq_ignore_ascii_case(domain) &&
+						host.as_bytes()[host.len() - domain.len() - 1] == b'.'),
+			None => host.eq_ignore_ascii_case(&self.host),
+		}
+	}
+}
+
+impl FromStr for HttpAllowedHost {
+	type Err = String;
+
+	/// Parses `HOST` or `HOST=REQUESTS_PER_MINUTE`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (host, limit) = match s.split_once('=') {
+			Some((host, limit)) => (host, Some(limit)),
+			None => (s, None),
+		};
+
+		if host.is_empty() || host.contains(|c: char| c == '/' || c.is_whitespace()) {
+			return Err(format!("Invalid host name: {:?}", host))
+		}
+
+		let requests_per_minute = limit
+			.map(|limit| limit.parse::<u32>().map_err(|e| format!("Invalid rate limit: {}", e)))
+			.transpose()?;
+
+		Ok(Self { host: host.to_owned(), requests_per_minute })
+	}
+}
+
+/// How HTTP requests of offchain workers are carried out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HttpMode {
+	/// Requests are sent over the network.
+	Live,
+	/// Requests are sent over the network and, together with their responses, appended to the
+	/// given file.
+	Record(PathBuf),
+	/// Requests are answered from a file written in [`HttpMode::Record`] mode, without any
+	/// network access. Requests that were not recorded fail.
+	Replay(PathBuf),
+}
+
+impl Default for HttpMode {
+	fn default() -> Self {
+		Self::Live
+	}
+}
+
+/// Enforces the allowed hosts and their rate limits.
+pub(crate) struct HttpAllowList {
+	hosts: Vec<HttpAllowedHost>,
+	/// Start times of the requests made within the last [`RATE_LIMIT_WINDOW`], per entry of
+	/// `hosts`.
+	recent: Mutex<Vec<VecDeque<Instant>>>,
+}
+
+impl HttpAllowList {
+	pub(crate) fn new(hosts: Vec<HttpAllowedHost>) -> Self {
+		let recent = Mutex::new(vec![VecDeque::new(); hosts.len()]);
+		Self { hosts, recent }
+	}
+
+	/// Checks whether a request to `host` may be started now, and accounts for it if so.
+	pub(crate) fn check(&self, host: &str) -> bool {
+		let index = match self.hosts.iter().position(|allowed| allowed.matches(host)) {
+			Some(index) => index,
+			None => {
+				tracing::warn!(target: LOG_TARGET, %host, "Request to host not in the allow list");
+				return false
+			},
+		};
+
+		let limit = match self.hosts[index].requests_per_minute {
+			Some(limit) => limit as usize,
+			None => return true,
+		};
+
+		let now = Instant::now();
+		let mut recent = self.recent.lock();
+		let recent = &mut recent[index];
+		while recent
+			.front()
+			.map_or(false, |start| now.duration_since(*start) >= RATE_LIMIT_WINDOW)
+		{
+			recent.pop_front();
+		}
+
+		if recent.len() >= limit {
+			tracing::warn!(target: LOG_TARGET, %host, %limit, "Request rate limit reached");
+			return false
+		}
+
+		recent.push_back(now);
+		true
+	}
+}
+
+/// A request as identified in a recording.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct RecordedRequest {
+	pub method: String,
+	pub uri: String,
+	pub body: Bytes,
+}
+
+/// A response as stored in a recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RecordedResponse {
+	pub status: u16,
+	pub headers: Vec<(String, Bytes)>,
+	pub body: Bytes,
+}
+
+/// One line of a recording file.
+#[derive(Serialize, Deserialize)]
+struct RecordedExchange {
+	request: RecordedRequest,
+	response: RecordedResponse,
+}
+
+/// Records or replays request/response pairs.
+pub(crate) enum HttpRecorder {
+	/// Appends every exchange to the file.
+	Record(Mutex<File>),
+	/// Responses read from a recording, in the order they were recorded.
+	Replay(Mutex<HashMap<RecordedRequest, VecDeque<RecordedResponse>>>),
+}
+
+impl HttpRecorder {
+	/// Creates the recorder for the given mode, or `None` for [`HttpMode::Live`].
+	pub(crate) fn new(mode: &HttpMode) -> std::io::Result<Option<Self>> {
+		match mode {
+			HttpMode::Live => Ok(None),
+			HttpMode::Record(path) => {
+				let file = OpenOptions::new().create(true).append(true).open(path)?;
+				Ok(Some(Self::Record(Mutex::new(file))))
+			},
+			HttpMode::Replay(path) => Ok(Some(Self::Replay(Mutex::new(Self::load(path)?)))),
+		}
+	}
+
+	fn load(path: &Path) -> std::io::Result<HashMap<RecordedRequest, VecDeque<RecordedResponse>>> {
+		let mut exchanges = HashMap::<_, VecDeque<_>>::new();
+		for line in BufReader::new(File::open(path)?).lines() {
+			let line = line?;
+			if line.trim().is_empty() {
+				continue
+			}
+			let exchange: RecordedExchange = serde_json::from_str(&line)
+				.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+			exchanges.entry(exchange.request).or_default().push_back(exchange.response);
+		}
+		Ok(exchanges)
+	}
+
+	/// Whether requests must be answered from the recording instead of the network.
+	pub(crate) fn is_replay(&self) -> bool {
+		matches!(self, Self::Replay(_))
+	}
+
+	/// Saves an exchange. Does nothing when replaying.
+	pub(crate) fn record(&self, request: RecordedRequest, response: RecordedResponse) {
+		if let Self::Record(file) = self {
+			let exchange = RecordedExchange { request, response };
+			let result = serde_json::to_string(&exchange)
+				.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+				.and_then(|line| writeln!(file.lock(), "{}", line));
+			if let Err(error) = result {
+				tracing::error!(target: LOG_TARGET, %error, "Failed to record HTTP exchange");
+			}
+		}
+	}
+
+	/// Returns the recorded response to `request`.
+	///
+	/// Identical requests are answered with their responses in the order they were recorded. Once
+	/// all of them have been served, the last one keeps being returned.
+	pub(crate) fn replay(&self, request: &RecordedRequest) -> Option<RecordedResponse> {
+		let mut exchanges = match self {
+			Self::Replay(exchanges) => exchanges.lock(),
+			Self::Record(_) => return None,
+		};
+		let responses = exchanges.get_mut(request)?;
+		if responses.len() > 1 {
+			responses.pop_front()
+		} else {
+			responses.front().cloned()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn allowed_host_parsing() {
+		assert_eq!(
+			"example.com".parse(),
+			Ok(HttpAllowedHost { host: "example.com".into(), requests_per_minute: None })
+		);
+		assert_eq!(
+			"*.example.com=10".parse(),
+			Ok(HttpAllowedHost { host: "*.example.com".into(), requests_per_minute: Some(10) })
+		);
+		assert!("".parse::<HttpAllowedHost>().is_err());
+		assert!("example.com/path".parse::<HttpAllowedHost>().is_err());
+		assert!("example.com=lots".parse::<HttpAllowedHost>().is_err());
+	}
+
+	#[test]
+	fn allow_list_matches_hosts() {
+		let list = HttpAllowList::new(vec![
+			"example.com".parse().unwrap(),
+			"*.parity.io".parse().unwrap(),
+		]);
+
+		assert!(list.check("example.com"));
+		assert!(list.check("EXAMPLE.com"));
+		assert!(!list.check("api.example.com"));
+		assert!(list.check("parity.io"));
+		assert!(list.check("api.parity.io"));
+		assert!(!list.check("notparity.io"));
+		assert!(!list.check("localhost"));
+	}
+
+	#[test]
+	fn allow_list_enforces_rate_limit() {
+		let list = HttpAllowList::new(vec![
+			"example.com=2".parse().unwrap(),
+			"unlimited.com".parse().unwrap(),
+		]);
+
+		assert!(list.check("example.com"));
+		assert!(list.check("example.com"));
+		assert!(!list.check("example.com"));
+		for _ in 0..10 {
+			assert!(list.check("unlimited.com"));
+		}
+	}
+}
@@ -35,13 +35,15 @@
 
 #![warn(missing_docs)]
 
-use std::{fmt, marker::PhantomData, sync::Arc};
+use std::{fmt, marker::PhantomData, sync::Arc, time::Instant};
 
 use futures::{
 	future::{ready, Future},
 	prelude::*,
 };
 use parking_lot::Mutex;
+use prometheus_endpoint::Registry;
+use sc_client_api::execution_extensions::take_offchain_call_stats;
 use sc_network_common::service::{NetworkPeers, NetworkStateInfo};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_core::{offchain, traits::SpawnNamed, ExecutionContext};
@@ -52,8 +54,9 @@ use sp_runtime::{
 use threadpool::ThreadPool;
 
 mod api;
+mod metrics;
 
-pub use api::Db as OffchainDb;
+pub use api::{Db as OffchainDb, HttpAllowedHost, HttpMode};
 pub use sp_offchain::{OffchainWorkerApi, STORAGE_PREFIX};
 
 const LOG_TARGET: &str = "offchain-worker";
@@ -70,6 +73,25 @@ pub struct OffchainWorkerOptions {
 	///
 	/// If not enabled, any http request will panic.
 	pub enable_http_requests: bool,
+	/// Hosts offchain workers may send http requests to.
+	///
+	/// If `None`, all hosts are allowed.
+	pub http_allow_list: Option<Vec<HttpAllowedHost>>,
+	/// Whether http requests are sent over the network, recorded or replayed.
+	pub http_mode: HttpMode,
+	/// Registry to report the offchain workers metrics to.
+	pub prometheus_registry: Option<Registry>,
+}
+
+impl Default for OffchainWorkerOptions {
+	fn default() -> Self {
+		Self {
+			enable_http_requests: true,
+			http_allow_list: None,
+			http_mode: HttpMode::Live,
+			prometheus_registry: None,
+		}
+	}
 }
 
 /// An offchain workers manager.
@@ -79,16 +101,31 @@ pub struct OffchainWorkers<Client, Block: traits::Block> {
 	thread_pool: Mutex<ThreadPool>,
 	shared_http_client: api::SharedClient,
 	enable_http: bool,
+	metrics: metrics::MetricsLink,
 }
 
 impl<Client, Block: traits::Block> OffchainWorkers<Client, Block> {
 	/// Creates new [`OffchainWorkers`].
 	pub fn new(client: Arc<Client>) -> Self {
-		Self::new_with_options(client, OffchainWorkerOptions { enable_http_requests: true })
+		Self::new_with_options(client, OffchainWorkerOptions::default())
 	}
 
 	/// Creates new [`OffchainWorkers`] using the given `options`.
 	pub fn new_with_options(client: Arc<Client>, options: OffchainWorkerOptions) -> Self {
+		let recorder = api::HttpRecorder::new(&options.http_mode).unwrap_or_else(|error| {
+			tracing::error!(
+				target: LOG_TARGET,
+				"Failed to open the http recording for {:?}: {}",
+				options.http_mode,
+				error,
+			);
+			// Never fall back to the network when asked to replay.
+			matches!(options.http_mode, HttpMode::Replay(_))
+				.then(|| api::HttpRecorder::Replay(Default::default()))
+		});
+		let allow_list = options.http_allow_list.map(api::HttpAllowList::new);
+		let metrics = metrics::MetricsLink::new(options.prometheus_registry.as_ref());
+
 		Self {
 			client,
 			_block: PhantomData,
@@ -96,8 +133,13 @@ impl<Client, Block: traits::Block> OffchainWorkers<Client, Block> {
 				"offchain-worker".into(),
 				num_cpus::get(),
 			)),
-			shared_http_client: api::SharedClient::new(),
+			shared_http_client: api::SharedClient::with_policy(
+				allow_list,
+				recorder,
+				metrics.clone(),
+			),
 			enable_http: options.enable_http_requests,
+			metrics,
 		}
 	}
 }
@@ -153,6 +195,7 @@ where
 			tracing::debug!(target: LOG_TARGET, "Spawning offchain workers at {:?}", at);
 			let header = header.clone();
 			let client = self.client.clone();
+			let metrics = self.metrics.clone();
 
 			let mut capabilities = offchain::Capabilities::all();
 
@@ -162,6 +205,9 @@ where
 				let api = Box::new(api);
 				tracing::debug!(target: LOG_TARGET, "Running offchain workers at {:?}", at);
 
+				// Discard what earlier calls on this thread of the pool left behind.
+				let _ = take_offchain_call_stats();
+				let start = Instant::now();
 				let context = ExecutionContext::OffchainCall(Some((api, capabilities)));
 				let run = if version == 2 {
 					runtime.offchain_worker_with_context(&at, context, &header)
@@ -173,6 +219,20 @@ where
 						*header.number(),
 					)
 				};
+				let stats = take_offchain_call_stats();
+				metrics.report(|metrics| {
+					metrics.duration.observe(start.elapsed().as_secs_f64());
+					metrics.local_storage_bytes_written.inc_by(stats.local_storage_bytes_written);
+					metrics.extrinsics_submitted.inc_by(stats.transactions_submitted);
+				});
+				tracing::debug!(
+					target: LOG_TARGET,
+					"Offchain workers at {:?} took {:?}, wrote {} bytes, submitted {} extrinsics",
+					at,
+					start.elapsed(),
+					stats.local_storage_bytes_written,
+					stats.transactions_submitted,
+				);
 				if let Err(e) = run {
 					tracing::error!(
 						target: LOG_TARGET,
@@ -386,6 +446,57 @@ mod tests {
 		assert_eq!(pool.0.ready().next().unwrap().is_propagable(), false);
 	}
 
+	#[test]
+	fn reports_metrics_of_offchain_worker_runs() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let _pool = TestPool(BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner,
+			client.clone(),
+		));
+		let network = Arc::new(TestNetwork());
+		let header = client.header(&BlockId::number(0)).unwrap().unwrap();
+		let registry = Registry::new();
+
+		// when
+		let offchain = OffchainWorkers::new_with_options(
+			client,
+			OffchainWorkerOptions {
+				prometheus_registry: Some(registry.clone()),
+				..Default::default()
+			},
+		);
+		futures::executor::block_on(offchain.on_block_imported(&header, network, false));
+
+		// then
+		let metric = |name: &str| {
+			registry
+				.gather()
+				.into_iter()
+				.find(|family| family.get_name() == name)
+				.unwrap()
+				.get_metric()[0]
+				.clone()
+		};
+		assert_eq!(
+			metric("substrate_offchain_worker_extrinsics_submitted_total")
+				.get_counter()
+				.get_value(),
+			1.0,
+		);
+		assert_eq!(
+			metric("substrate_offchain_worker_duration_seconds")
+				.get_histogram()
+				.get_sample_count(),
+			1,
+		);
+	}
+
 	#[test]
 	fn offchain_index_set_and_clear_works() {
 		use sp_core::offchain::OffchainStorage;
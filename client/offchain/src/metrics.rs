@@ -0,0 +1,100 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Offchain workers Prometheus metrics.
+
+use std::sync::Arc;
+
+use prometheus_endpoint::{
+	exponential_buckets, register, Counter, CounterVec, Histogram, HistogramOpts, Opts,
+	PrometheusError, Registry, U64,
+};
+
+#[derive(Clone, Default)]
+pub struct MetricsLink(Arc<Option<Metrics>>);
+
+impl MetricsLink {
+	pub fn new(registry: Option<&Registry>) -> Self {
+		Self(Arc::new(registry.and_then(|registry| {
+			Metrics::register(registry)
+				.map_err(|err| {
+					tracing::warn!(
+						target: crate::LOG_TARGET,
+						"Failed to register prometheus metrics: {}",
+						err,
+					);
+				})
+				.ok()
+		})))
+	}
+
+	pub fn report(&self, do_this: impl FnOnce(&Metrics)) {
+		if let Some(metrics) = self.0.as_ref() {
+			do_this(metrics);
+		}
+	}
+}
+
+/// Offchain workers Prometheus metrics.
+pub struct Metrics {
+	pub duration: Histogram,
+	pub http_requests: CounterVec<U64>,
+	pub local_storage_bytes_written: Counter<U64>,
+	pub extrinsics_submitted: Counter<U64>,
+}
+
+impl Metrics {
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			duration: register(
+				Histogram::with_opts(
+					HistogramOpts::new(
+						"substrate_offchain_worker_duration_seconds",
+						"Time taken to run the offchain workers of a block",
+					)
+					.buckets(exponential_buckets(0.001, 4.0, 9)?),
+				)?,
+				registry,
+			)?,
+			http_requests: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_offchain_worker_http_requests_total",
+						"Total number of HTTP requests completed by offchain workers",
+					),
+					&["status"],
+				)?,
+				registry,
+			)?,
+			local_storage_bytes_written: register(
+				Counter::new(
+					"substrate_offchain_worker_local_storage_bytes_written_total",
+					"Total number of bytes written to the offchain database by offchain workers",
+				)?,
+				registry,
+			)?,
+			extrinsics_submitted: register(
+				Counter::new(
+					"substrate_offchain_worker_extrinsics_submitted_total",
+					"Total number of extrinsics submitted to the transaction pool by offchain workers",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
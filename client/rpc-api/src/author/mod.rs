@@ -21,6 +21,7 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use sc_transaction_pool_api::TransactionStatus;
 use sp_core::Bytes;
+use sp_session::audit::SessionKeysAudit;
 
 pub mod error;
 pub mod hash;
@@ -76,3 +77,25 @@ pub trait AuthorApi<Hash, BlockHash> {
 	)]
 	fn watch_extrinsic(&self, bytes: Bytes);
 }
+
+/// Substrate session keys audit RPC API.
+#[rpc(client, server)]
+pub trait AuthorSessionKeysApi<BlockHash> {
+	/// Cross-check the keystore against the session keys registered on chain for a validator,
+	/// and the keys it uses in the current session.
+	///
+	/// `validator` is the SCALE encoded validator id. Besides the key types registered on chain,
+	/// the keystore is searched for orphan keys of the given `key_types`, or of the key types
+	/// commonly used as session keys if none are given. `pallet` is the name of the session
+	/// pallet in the runtime, `Session` if not given.
+	///
+	/// Returns the missing, stale and orphan keys per key type.
+	#[method(name = "author_auditSessionKeys")]
+	fn audit_session_keys(
+		&self,
+		validator: Bytes,
+		key_types: Option<Vec<String>>,
+		at: Option<BlockHash>,
+		pallet: Option<String>,
+	) -> RpcResult<SessionKeysAudit>;
+}
@@ -21,6 +21,8 @@
 #[cfg(test)]
 mod tests;
 
+mod session_keys;
+
 use std::sync::Arc;
 
 use crate::SubscriptionTaskExecutor;
@@ -47,6 +49,7 @@ use sp_session::SessionKeys;
 use self::error::{Error, Result};
 /// Re-export the API for backward compatibility.
 pub use sc_rpc_api::author::*;
+pub use session_keys::AuthorSessionKeys;
 
 /// Authoring API
 pub struct Author<P, Client> {
@@ -0,0 +1,166 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Audit of the local session keys against the keys registered on chain.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use jsonrpsee::core::RpcResult;
+use sc_client_api::{Backend, StorageProvider};
+use sc_rpc_api::DenyUnsafe;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::{Error as ClientError, HeaderBackend};
+use sp_core::{crypto::KeyTypeId, storage::StorageKey, Bytes};
+use sp_keystore::SyncCryptoStorePtr;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor},
+};
+use sp_session::{
+	audit::{
+		audit_session_keys, current_index_storage_key, find_current_keys_block,
+		next_keys_storage_key, SessionKeysAudit, DEFAULT_AUDITED_KEY_TYPES,
+		DEFAULT_SESSION_PALLET_PREFIX,
+	},
+	SessionKeys,
+};
+
+use super::error::Error;
+use sc_rpc_api::author::AuthorSessionKeysApiServer;
+
+/// Session keys audit API.
+pub struct AuthorSessionKeys<Block, Client, BE> {
+	/// Substrate client
+	client: Arc<Client>,
+	/// The key store.
+	keystore: SyncCryptoStorePtr,
+	/// Whether to deny unsafe calls
+	deny_unsafe: DenyUnsafe,
+	_marker: PhantomData<(Block, BE)>,
+}
+
+impl<Block, Client, BE> AuthorSessionKeys<Block, Client, BE> {
+	/// Create new instance of the session keys audit API.
+	pub fn new(client: Arc<Client>, keystore: SyncCryptoStorePtr, deny_unsafe: DenyUnsafe) -> Self {
+		AuthorSessionKeys { client, keystore, deny_unsafe, _marker: PhantomData }
+	}
+}
+
+impl<Block, Client, BE> AuthorSessionKeys<Block, Client, BE>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: HeaderBackend<Block> + ProvideRuntimeApi<Block> + StorageProvider<Block, BE>,
+	Client::Api: SessionKeys<Block>,
+{
+	/// Get the raw public keys of the session keys stored under `key` at the block `at`.
+	fn session_keys(
+		&self,
+		at: Block::Hash,
+		key: &StorageKey,
+	) -> Result<Option<Vec<(Vec<u8>, KeyTypeId)>>, Error> {
+		match self.client.storage(at, key).map_err(|e| Error::Client(Box::new(e)))? {
+			Some(encoded) => Ok(Some(
+				self.client
+					.runtime_api()
+					.decode_session_keys(&BlockId::Hash(at), encoded.0)
+					.map_err(|e| Error::Client(Box::new(e)))?
+					.ok_or(Error::InvalidSessionKeys)?,
+			)),
+			None => Ok(None),
+		}
+	}
+
+	/// Get the index of the session of the block `at`.
+	fn session_index(&self, pallet: &[u8], at: Block::Hash) -> Result<u32, Error> {
+		let key = StorageKey(current_index_storage_key(pallet));
+		match self.client.storage(at, &key).map_err(|e| Error::Client(Box::new(e)))? {
+			Some(encoded) =>
+				u32::decode(&mut &encoded.0[..]).map_err(|e| Error::Client(Box::new(e))),
+			None => Ok(0),
+		}
+	}
+
+	/// Get the block whose `NextKeys` are the session keys used in the session of the block `at`.
+	///
+	/// That block is searched for on the canonical chain, which is assumed to agree with the
+	/// chain of `at` that far back.
+	fn current_keys_block(&self, pallet: &[u8], at: Block::Hash) -> Result<Block::Hash, Error> {
+		let unknown_block =
+			|block: String| Error::Client(Box::new(ClientError::UnknownBlock(block)));
+		let hash = |number: NumberFor<Block>| -> Result<Block::Hash, Error> {
+			self.client
+				.hash(number)
+				.map_err(|e| Error::Client(Box::new(e)))?
+				.ok_or_else(|| unknown_block(format!("{:?}", number)))
+		};
+
+		let number = self
+			.client
+			.number(at)
+			.map_err(|e| Error::Client(Box::new(e)))?
+			.ok_or_else(|| unknown_block(format!("{:?}", at)))?;
+		let number = find_current_keys_block(self.session_index(pallet, at)?, number, |number| {
+			self.session_index(pallet, hash(number)?)
+		})?;
+
+		hash(number)
+	}
+}
+
+impl<Block, Client, BE> AuthorSessionKeysApiServer<Block::Hash>
+	for AuthorSessionKeys<Block, Client, BE>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: HeaderBackend<Block>
+		+ ProvideRuntimeApi<Block>
+		+ StorageProvider<Block, BE>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: SessionKeys<Block>,
+{
+	fn audit_session_keys(
+		&self,
+		validator: Bytes,
+		key_types: Option<Vec<String>>,
+		at: Option<Block::Hash>,
+		pallet: Option<String>,
+	) -> RpcResult<SessionKeysAudit> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let key_types = match key_types {
+			Some(key_types) => key_types
+				.iter()
+				.map(|key_type| key_type.as_str().try_into().map_err(|_| Error::BadKeyType))
+				.collect::<Result<Vec<KeyTypeId>, _>>()?,
+			None => DEFAULT_AUDITED_KEY_TYPES.to_vec(),
+		};
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let pallet = pallet.as_deref().map_or(DEFAULT_SESSION_PALLET_PREFIX, str::as_bytes);
+		let key = StorageKey(next_keys_storage_key(pallet, &validator));
+
+		let on_chain = self.session_keys(at, &key)?;
+		let current = self.session_keys(self.current_keys_block(pallet, at)?, &key)?;
+
+		Ok(audit_session_keys(&*self.keystore, on_chain.as_deref(), current.as_deref(), &key_types))
+	}
+}
@@ -31,12 +31,13 @@ use sc_transaction_pool_api::TransactionStatus;
 use sp_core::{
 	blake2_256,
 	bytes::to_hex,
-	crypto::{ByteArray, CryptoTypePublicPair, Pair},
-	ed25519, sr25519,
+	crypto::{ByteArray, CryptoTypePublicPair, KeyTypeId, Pair},
+	ecdsa, ed25519, sr25519,
 	testing::{ED25519, SR25519},
 	H256,
 };
 use sp_keystore::testing::KeyStore;
+use sp_session::audit::{next_keys_storage_key, SessionKeysAudit, DEFAULT_SESSION_PALLET_PREFIX};
 use std::sync::Arc;
 use substrate_test_runtime_client::{
 	self,
@@ -330,3 +331,66 @@ async fn author_has_key() {
 	};
 	assert!(!has_bob_ed);
 }
+
+#[tokio::test]
+async fn author_audit_session_keys() {
+	let keystore = Arc::new(KeyStore::new());
+	let stale = SyncCryptoStore::sr25519_generate_new(&*keystore, SR25519, None).unwrap();
+	let sr25519 = SyncCryptoStore::sr25519_generate_new(&*keystore, SR25519, None).unwrap();
+	let ed25519 = SyncCryptoStore::ed25519_generate_new(&*keystore, ED25519, None).unwrap();
+	let ecdsa = ecdsa::Pair::from_string("//Alice", None).unwrap().public();
+	let orphan =
+		SyncCryptoStore::ed25519_generate_new(&*keystore, KeyTypeId(*b"dumy"), None).unwrap();
+
+	let validator = AccountKeyring::Alice.to_account_id().encode();
+	let session_keys =
+		SessionKeys { ed25519: ed25519.into(), sr25519: sr25519.into(), ecdsa: ecdsa.into() };
+	let client = substrate_test_runtime_client::TestClientBuilder::new()
+		.set_keystore(keystore.clone())
+		.add_extra_storage(
+			next_keys_storage_key(DEFAULT_SESSION_PALLET_PREFIX, &validator),
+			session_keys.encode(),
+		)
+		.build();
+	let api = AuthorSessionKeys::new(Arc::new(client), keystore, DenyUnsafe::No).into_rpc();
+
+	let audit: SessionKeysAudit = api
+		.call(
+			"author_auditSessionKeys",
+			(Bytes::from(validator.clone()), Some(vec!["dumy".to_string()]), None::<H256>),
+		)
+		.await
+		.unwrap();
+
+	assert!(audit.registered);
+	assert!(!audit.is_healthy());
+
+	let find = |key_type: &str| audit.key_types.iter().find(|a| a.key_type == key_type).unwrap();
+	assert!(!find("ed25").missing);
+	assert!(find("ecds").missing);
+	assert_eq!(find("sr25").stale, vec![Bytes::from(stale.to_raw_vec())]);
+	assert_eq!(find("dumy").orphan, vec![Bytes::from(orphan.to_raw_vec())]);
+
+	// During the first sessions, the keys of the current session are the genesis `NextKeys`.
+	assert_eq!(find("sr25").current, Some(Bytes::from(sr25519.to_raw_vec())));
+
+	// The keys are looked up in the session pallet with the given name.
+	let audit: SessionKeysAudit = api
+		.call(
+			"author_auditSessionKeys",
+			(Bytes::from(validator), None::<Vec<String>>, None::<H256>, Some("Sessions")),
+		)
+		.await
+		.unwrap();
+	assert!(!audit.registered);
+
+	// Validators without registered keys are reported as such.
+	let audit: SessionKeysAudit = api
+		.call(
+			"author_auditSessionKeys",
+			(Bytes::from(vec![0u8; 32]), None::<Vec<String>>, None::<H256>),
+		)
+		.await
+		.unwrap();
+	assert!(!audit.registered);
+}
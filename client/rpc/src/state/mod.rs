@@ -163,11 +163,14 @@ where
 }
 
 /// Create new state API that works on full node.
+///
+/// `call_fuel_limit` bounds the fuel a single `state_call` may consume, if given.
 pub fn new_full<BE, Block: BlockT, Client>(
 	client: Arc<Client>,
 	executor: SubscriptionTaskExecutor,
 	deny_unsafe: DenyUnsafe,
 	rpc_max_payload: Option<usize>,
+	call_fuel_limit: Option<u64>,
 ) -> (State<Block, Client>, ChildState<Block, Client>)
 where
 	Block: BlockT + 'static,
@@ -191,8 +194,14 @@ where
 		client.clone(),
 		executor.clone(),
 		rpc_max_payload,
+		call_fuel_limit,
+	));
+	let backend = Box::new(self::state_full::FullState::new(
+		client,
+		executor,
+		rpc_max_payload,
+		call_fuel_limit,
 	));
-	let backend = Box::new(self::state_full::FullState::new(client, executor, rpc_max_payload));
 	(State { backend, deny_unsafe }, ChildState { backend: child_backend })
 }
 
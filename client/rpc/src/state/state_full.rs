@@ -60,6 +60,7 @@ pub struct FullState<BE, Block: BlockT, Client> {
 	executor: SubscriptionTaskExecutor,
 	_phantom: PhantomData<(BE, Block)>,
 	rpc_max_payload: Option<usize>,
+	call_fuel_limit: Option<u64>,
 }
 
 impl<BE, Block: BlockT, Client> FullState<BE, Block, Client>
@@ -72,12 +73,15 @@ where
 	Block: BlockT + 'static,
 {
 	/// Create new state API backend for full nodes.
+	///
+	/// `call_fuel_limit` bounds the fuel a single `state_call` may consume.
 	pub fn new(
 		client: Arc<Client>,
 		executor: SubscriptionTaskExecutor,
 		rpc_max_payload: Option<usize>,
+		call_fuel_limit: Option<u64>,
 	) -> Self {
-		Self { client, executor, _phantom: PhantomData, rpc_max_payload }
+		Self { client, executor, _phantom: PhantomData, rpc_max_payload, call_fuel_limit }
 	}
 
 	/// Returns given block hash or best block hash if None is passed.
@@ -193,15 +197,19 @@ where
 	) -> std::result::Result<Bytes, Error> {
 		self.block_or_best(block)
 			.and_then(|block| {
-				self.client
-					.executor()
-					.call(
+				let executor = self.client.executor();
+				let strategy = self.client.execution_extensions().strategies().other;
+				match self.call_fuel_limit {
+					Some(fuel_limit) => executor.call_with_fuel_limit(
 						&BlockId::Hash(block),
 						&method,
 						&call_data,
-						self.client.execution_extensions().strategies().other,
-					)
-					.map(Into::into)
+						strategy,
+						fuel_limit,
+					),
+					None => executor.call(&BlockId::Hash(block), &method, &call_data, strategy),
+				}
+				.map(Into::into)
 			})
 			.map_err(client_err)
 	}
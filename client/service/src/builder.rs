@@ -51,7 +51,7 @@ use sc_network_sync::{
 	warp_request_handler::RequestHandler as WarpSyncRequestHandler, ChainSync,
 };
 use sc_rpc::{
-	author::AuthorApiServer,
+	author::{AuthorApiServer, AuthorSessionKeysApiServer},
 	chain::ChainApiServer,
 	offchain::OffchainApiServer,
 	state::{ChildStateApiServer, StateApiServer},
@@ -388,7 +388,14 @@ where
 	TCl: Send + Sync + ProvideRuntimeApi<TBl> + BlockchainEvents<TBl> + 'static,
 	<TCl as ProvideRuntimeApi<TBl>>::Api: sc_offchain::OffchainWorkerApi<TBl>,
 {
-	let offchain_workers = Some(Arc::new(sc_offchain::OffchainWorkers::new(client.clone())));
+	let options = sc_offchain::OffchainWorkerOptions {
+		http_allow_list: config.offchain_worker.http_allow_list.clone(),
+		http_mode: config.offchain_worker.http_mode.clone(),
+		prometheus_registry: config.prometheus_registry().cloned(),
+		..Default::default()
+	};
+	let offchain_workers =
+		Some(Arc::new(sc_offchain::OffchainWorkers::new_with_options(client.clone(), options)));
 
 	// Inform the offchain worker about new imported blocks
 	if let Some(offchain) = offchain_workers.clone() {
@@ -668,6 +675,7 @@ where
 			task_executor.clone(),
 			deny_unsafe,
 			config.rpc_max_payload,
+			config.rpc_call_fuel_limit,
 		);
 		let state = state.into_rpc();
 		let child_state = child_state.into_rpc();
@@ -685,12 +693,15 @@ where
 	let author = sc_rpc::author::Author::new(
 		client.clone(),
 		transaction_pool,
-		keystore,
+		keystore.clone(),
 		deny_unsafe,
 		task_executor.clone(),
 	)
 	.into_rpc();
 
+	let author_session_keys =
+		sc_rpc::author::AuthorSessionKeys::new(client.clone(), keystore, deny_unsafe).into_rpc();
+
 	let system = sc_rpc::system::System::new(system_info, system_rpc_tx, deny_unsafe).into_rpc();
 
 	if let Some(storage) = offchain_storage {
@@ -705,6 +716,7 @@ where
 	// Part of the old RPC spec.
 	rpc_api.merge(chain).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(author).map_err(|e| Error::Application(e.into()))?;
+	rpc_api.merge(author_session_keys).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(system).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(state).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(child_state).map_err(|e| Error::Application(e.into()))?;
@@ -22,7 +22,7 @@ use sc_client_api::{
 };
 use sc_executor::{RuntimeVersion, RuntimeVersionOf};
 use sp_api::{ExecutionContext, ProofRecorder, StorageTransactionCache};
-use sp_core::traits::{CodeExecutor, RuntimeCode, SpawnNamed};
+use sp_core::traits::{CodeExecutor, FuelLimitExt, RuntimeCode, SpawnNamed};
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 use sp_state_machine::{
 	backend::AsTrieBackend, ExecutionStrategy, Ext, OverlayedChanges, StateMachine, StorageProof,
@@ -113,6 +113,51 @@ where
 
 		Ok(code)
 	}
+
+	/// Execute a call on top of the state of `at`, within `fuel_limit` if given.
+	fn call_impl(
+		&self,
+		at: &BlockId<Block>,
+		method: &str,
+		call_data: &[u8],
+		strategy: ExecutionStrategy,
+		fuel_limit: Option<u64>,
+	) -> sp_blockchain::Result<Vec<u8>> {
+		let mut changes = OverlayedChanges::default();
+		let at_hash = self.backend.blockchain().expect_block_hash_from_id(at)?;
+		let at_number = self.backend.blockchain().expect_block_number_from_id(at)?;
+		let state = self.backend.state_at(at_hash)?;
+
+		let state_runtime_code = sp_state_machine::backend::BackendRuntimeCode::new(&state);
+		let runtime_code =
+			state_runtime_code.runtime_code().map_err(sp_blockchain::Error::RuntimeCode)?;
+
+		let runtime_code = self.check_override(runtime_code, at)?;
+
+		let mut extensions = self.execution_extensions.extensions(
+			at_hash,
+			at_number,
+			ExecutionContext::OffchainCall(None),
+		);
+		if let Some(fuel_limit) = fuel_limit {
+			extensions.register(FuelLimitExt(fuel_limit));
+		}
+
+		let mut sm = StateMachine::new(
+			&state,
+			&mut changes,
+			&self.executor,
+			method,
+			call_data,
+			extensions,
+			&runtime_code,
+			self.spawn_handle.clone(),
+		)
+		.set_parent_hash(at_hash);
+
+		sm.execute_using_consensus_failure_handler(strategy.get_manager())
+			.map_err(Into::into)
+	}
 }
 
 impl<Block: BlockT, B, E> Clone for LocalCallExecutor<Block, B, E>
@@ -153,37 +198,18 @@ where
 		call_data: &[u8],
 		strategy: ExecutionStrategy,
 	) -> sp_blockchain::Result<Vec<u8>> {
-		let mut changes = OverlayedChanges::default();
-		let at_hash = self.backend.blockchain().expect_block_hash_from_id(at)?;
-		let at_number = self.backend.blockchain().expect_block_number_from_id(at)?;
-		let state = self.backend.state_at(at_hash)?;
-
-		let state_runtime_code = sp_state_machine::backend::BackendRuntimeCode::new(&state);
-		let runtime_code =
-			state_runtime_code.runtime_code().map_err(sp_blockchain::Error::RuntimeCode)?;
-
-		let runtime_code = self.check_override(runtime_code, at)?;
-
-		let extensions = self.execution_extensions.extensions(
-			at_hash,
-			at_number,
-			ExecutionContext::OffchainCall(None),
-		);
-
-		let mut sm = StateMachine::new(
-			&state,
-			&mut changes,
-			&self.executor,
-			method,
-			call_data,
-			extensions,
-			&runtime_code,
-			self.spawn_handle.clone(),
-		)
-		.set_parent_hash(at_hash);
+		self.call_impl(at, method, call_data, strategy, None)
+	}
 
-		sm.execute_using_consensus_failure_handler(strategy.get_manager())
-			.map_err(Into::into)
+	fn call_with_fuel_limit(
+		&self,
+		at: &BlockId<Block>,
+		method: &str,
+		call_data: &[u8],
+		strategy: ExecutionStrategy,
+		fuel_limit: u64,
+	) -> sp_blockchain::Result<Vec<u8>> {
+		self.call_impl(at, method, call_data, strategy, Some(fuel_limit))
 	}
 
 	fn contextual_call(
@@ -31,6 +31,7 @@ pub use sc_network_common::{
 		IncomingRequest, OutgoingResponse, ProtocolConfig as RequestResponseConfig,
 	},
 };
+pub use sc_offchain::{HttpAllowedHost as OffchainHttpAllowedHost, HttpMode as OffchainHttpMode};
 
 use prometheus_endpoint::Registry;
 use sc_chain_spec::ChainSpec;
@@ -111,6 +112,10 @@ pub struct Configuration {
 	///
 	/// Default: 1024.
 	pub rpc_max_subs_per_conn: Option<usize>,
+	/// Maximum fuel a single `state_call` RPC may consume. `None` if unlimited.
+	///
+	/// Limited calls always run in Wasm compiled with the `Compiled` execution method.
+	pub rpc_call_fuel_limit: Option<u64>,
 	/// Maximum size of the output buffer capacity for websocket connections.
 	pub ws_max_out_buffer_capacity: Option<usize>,
 	/// Prometheus endpoint configuration. `None` if disabled.
@@ -189,6 +194,10 @@ pub struct OffchainWorkerConfig {
 	pub enabled: bool,
 	/// allow writes from the runtime to the offchain worker database.
 	pub indexing_enabled: bool,
+	/// Hosts offchain workers may send http requests to. `None` allows all hosts.
+	pub http_allow_list: Option<Vec<OffchainHttpAllowedHost>>,
+	/// Whether offchain http requests are sent over the network, recorded or replayed.
+	pub http_mode: OffchainHttpMode,
 }
 
 /// Configuration of the Prometheus endpoint.
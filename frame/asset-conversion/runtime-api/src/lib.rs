@@ -0,0 +1,52 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the asset conversion pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	/// Runtime api for quoting the prices of the asset conversion pools.
+	///
+	/// `AssetId` is the pallet's `NativeOrAssetId`, so that the native currency can be quoted too.
+	pub trait AssetConversionApi<Balance, AssetId>
+		where Balance: Codec, AssetId: Codec
+	{
+		/// The amount of `asset1` needed to buy exactly `amount` of `asset2`, or the spot value of
+		/// `amount` if `include_fee` is false.
+		fn quote_price_tokens_for_exact_tokens(
+			asset1: AssetId,
+			asset2: AssetId,
+			amount: Balance,
+			include_fee: bool,
+		) -> Option<Balance>;
+
+		/// The amount of `asset2` bought with exactly `amount` of `asset1`, or the spot value of
+		/// `amount` if `include_fee` is false.
+		fn quote_price_exact_tokens_for_tokens(
+			asset1: AssetId,
+			asset2: AssetId,
+			amount: Balance,
+			include_fee: bool,
+		) -> Option<Balance>;
+
+		/// The reserves of the pool of `asset1` and `asset2`, in that order.
+		fn get_reserves(asset1: AssetId, asset2: AssetId) -> Option<(Balance, Balance)>;
+	}
+}
@@ -0,0 +1,224 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Asset conversion pallet benchmarking.
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{
+	assert_ok,
+	traits::{fungible::Mutate as MutateFungible, Get},
+};
+use frame_system::RawOrigin as SystemOrigin;
+
+use crate::Pallet as AssetConversion;
+
+const SEED: u32 = 0;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+/// A round amount, comfortably above the existential deposit of the native currency.
+fn unit<T: Config>() -> T::Balance {
+	T::Currency::minimum_balance().max(One::one()) * 1_000u32.into()
+}
+
+/// Create the sufficient asset `id` and fund `who` with it.
+fn create_funded_asset<T: Config>(id: u32, who: &T::AccountId) -> NativeOrAssetId<T::AssetId>
+where
+	T::Assets: Create<T::AccountId> + Mutate<T::AccountId>,
+	T::AssetId: From<u32>,
+{
+	let owner: T::AccountId = account("owner", id, SEED);
+	assert_ok!(T::Assets::create(id.into(), owner, true, One::one()));
+	assert_ok!(T::Assets::mint_into(id.into(), who, unit::<T>() * 1_000u32.into()));
+	NativeOrAssetId::Asset(id.into())
+}
+
+fn funded_caller<T: Config>() -> T::AccountId
+where
+	T::Currency: MutateFungible<T::AccountId>,
+{
+	let caller: T::AccountId = whitelisted_caller();
+	assert_ok!(T::Currency::mint_into(&caller, unit::<T>() * 1_000u32.into()));
+	caller
+}
+
+/// Create the pool of `asset1`/`asset2` and seed it with one `unit` of each side.
+fn create_pool_with_liquidity<T: Config>(
+	who: &T::AccountId,
+	asset1: NativeOrAssetId<T::AssetId>,
+	asset2: NativeOrAssetId<T::AssetId>,
+) {
+	let origin = SystemOrigin::Signed(who.clone());
+	assert_ok!(AssetConversion::<T>::create_pool(origin.clone().into(), asset1, asset2));
+	assert_ok!(AssetConversion::<T>::add_liquidity(
+		origin.into(),
+		asset1,
+		asset2,
+		unit::<T>(),
+		unit::<T>(),
+		One::one(),
+		One::one(),
+		who.clone(),
+	));
+}
+
+/// A path of `n` currencies starting from the native currency, with a seeded pool for every hop.
+fn create_path<T: Config>(who: &T::AccountId, n: u32) -> SwapPathOf<T>
+where
+	T::Assets: Create<T::AccountId> + Mutate<T::AccountId>,
+	T::AssetId: From<u32>,
+{
+	let mut path: Vec<NativeOrAssetId<T::AssetId>> = vec![NativeOrAssetId::Native];
+	for i in 1..n {
+		let asset = create_funded_asset::<T>(i, who);
+		create_pool_with_liquidity::<T>(who, path[path.len() - 1], asset);
+		path.push(asset);
+	}
+	path.try_into().expect("`n` is bounded by `MaxSwapPathLength`; qed")
+}
+
+benchmarks! {
+	where_clause {
+		where
+			T::Assets: Create<T::AccountId> + Mutate<T::AccountId>,
+			T::Currency: MutateFungible<T::AccountId>,
+			T::AssetId: From<u32>,
+	}
+
+	create_pool {
+		let caller = funded_caller::<T>();
+		let asset = create_funded_asset::<T>(1, &caller);
+		let lp_token = NextPoolAssetId::<T>::get().unwrap_or_else(Zero::zero);
+	}: _(SystemOrigin::Signed(caller.clone()), NativeOrAssetId::Native, asset)
+	verify {
+		assert_last_event::<T>(
+			Event::PoolCreated {
+				creator: caller,
+				pool_id: (NativeOrAssetId::Native, asset),
+				lp_token,
+			}
+			.into(),
+		);
+	}
+
+	// The first deposit of a pool is the heaviest, as it also mints the locked LP tokens.
+	add_liquidity {
+		let caller = funded_caller::<T>();
+		let asset = create_funded_asset::<T>(1, &caller);
+		let origin = SystemOrigin::Signed(caller.clone());
+		AssetConversion::<T>::create_pool(origin.into(), NativeOrAssetId::Native, asset)?;
+		let amount = unit::<T>();
+	}: _(
+		SystemOrigin::Signed(caller.clone()),
+		NativeOrAssetId::Native,
+		asset,
+		amount,
+		amount,
+		One::one(),
+		One::one(),
+		caller.clone()
+	)
+	verify {
+		let pool_id = (NativeOrAssetId::Native, asset);
+		let lp_token = Pools::<T>::get(pool_id).expect("pool was created; qed").lp_token;
+		assert_last_event::<T>(
+			Event::LiquidityAdded {
+				who: caller.clone(),
+				mint_to: caller,
+				pool_id,
+				amount1_provided: amount,
+				amount2_provided: amount,
+				lp_token,
+				lp_token_minted: amount - T::MintMinLiquidity::get(),
+			}
+			.into(),
+		);
+	}
+
+	remove_liquidity {
+		let caller = funded_caller::<T>();
+		let asset = create_funded_asset::<T>(1, &caller);
+		create_pool_with_liquidity::<T>(&caller, NativeOrAssetId::Native, asset);
+		let lp_token_burn = unit::<T>() / 2u32.into();
+	}: _(
+		SystemOrigin::Signed(caller.clone()),
+		NativeOrAssetId::Native,
+		asset,
+		lp_token_burn,
+		One::one(),
+		One::one(),
+		caller.clone()
+	)
+	verify {
+		let pool_id = (NativeOrAssetId::Native, asset);
+		let lp_token = Pools::<T>::get(pool_id).expect("pool was created; qed").lp_token;
+		assert_eq!(
+			T::PoolAssets::balance(lp_token, &caller),
+			unit::<T>() - T::MintMinLiquidity::get() - lp_token_burn,
+		);
+	}
+
+	swap_exact_tokens_for_tokens {
+		let n in 2 .. T::MaxSwapPathLength::get();
+		let caller = funded_caller::<T>();
+		let path = create_path::<T>(&caller, n);
+		let amount_in = unit::<T>() / 10u32.into();
+		let amount_out = *AssetConversion::<T>::get_amounts_out(amount_in, &path)?
+			.last()
+			.expect("the path has at least two currencies; qed");
+	}: _(
+		SystemOrigin::Signed(caller.clone()),
+		path.clone(),
+		amount_in,
+		One::one(),
+		caller.clone(),
+		false
+	)
+	verify {
+		assert_last_event::<T>(
+			Event::SwapExecuted { who: caller.clone(), send_to: caller, path, amount_in, amount_out }
+				.into(),
+		);
+	}
+
+	swap_tokens_for_exact_tokens {
+		let n in 2 .. T::MaxSwapPathLength::get();
+		let caller = funded_caller::<T>();
+		let path = create_path::<T>(&caller, n);
+		let amount_out = unit::<T>() / 10u32.into();
+		let amount_in = AssetConversion::<T>::get_amounts_in(amount_out, &path)?[0];
+	}: _(
+		SystemOrigin::Signed(caller.clone()),
+		path.clone(),
+		amount_out,
+		amount_in,
+		caller.clone(),
+		false
+	)
+	verify {
+		assert_last_event::<T>(
+			Event::SwapExecuted { who: caller.clone(), send_to: caller, path, amount_in, amount_out }
+				.into(),
+		);
+	}
+
+	impl_benchmark_test_suite!(AssetConversion, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,701 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Asset Conversion Pallet
+//!
+//! A decentralised exchange for the native currency and the assets of `Config::Assets`, based on
+//! constant-product (`x * y = k`) liquidity pools.
+//!
+//! ## Overview
+//!
+//! Anyone can create a pool for a pair of distinct currencies with [`Call::create_pool`]. Each
+//! pool holds its reserves in its own account, derived from `Config::PalletId` and the pair, and
+//! is assigned a fresh liquidity provider (LP) token in `Config::PoolAssets`.
+//!
+//! - [`Call::add_liquidity`] deposits both currencies in the ratio of the current reserves and
+//!   mints LP tokens to the provider. The first deposit sets the price; `MintMinLiquidity` of the
+//!   LP tokens minted for it are locked in the pool account forever so that the pool can never be
+//!   fully drained.
+//! - [`Call::remove_liquidity`] burns LP tokens and pays out the matching share of both reserves.
+//! - [`Call::swap_exact_tokens_for_tokens`] and [`Call::swap_tokens_for_exact_tokens`] trade along
+//!   a path of currencies, hopping through one pool for every consecutive pair of the path. Every
+//!   hop pays `Config::LPFee` of its input to the liquidity providers of that pool.
+//!
+//! Prices can be queried off-chain through the `AssetConversionApi` runtime API, which is backed
+//! by [`Pallet::quote_price_exact_tokens_for_tokens`] and
+//! [`Pallet::quote_price_tokens_for_exact_tokens`]. The pallet also implements
+//! [`BalanceConversion`] using the spot price of the native/asset pools, so that it can price
+//! transaction fees paid in assets.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod types;
+pub mod weights;
+
+pub use pallet::*;
+pub use types::*;
+pub use weights::WeightInfo;
+
+use frame_support::{
+	ensure,
+	traits::tokens::{
+		fungible::{Inspect as InspectFungible, Transfer as TransferFungible},
+		fungibles::{Create, Inspect, Mutate, Transfer},
+		AssetId, Balance as BalanceT, BalanceConversion,
+	},
+	BoundedVec, PalletId,
+};
+use sp_core::U256;
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{
+		AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedSub, Hash as HashT, One, Zero,
+	},
+	DispatchError, Permill, Rounding,
+};
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The native currency.
+		type Currency: InspectFungible<Self::AccountId, Balance = Self::Balance>
+			+ TransferFungible<Self::AccountId>;
+
+		/// The balance type shared by the native currency, the assets and the LP tokens.
+		type Balance: BalanceT;
+
+		/// Identifier of the assets that can be pooled.
+		type AssetId: AssetId + Ord;
+
+		/// The assets that can be pooled against each other and against the native currency.
+		type Assets: Inspect<Self::AccountId, AssetId = Self::AssetId, Balance = Self::Balance>
+			+ Transfer<Self::AccountId>;
+
+		/// Identifier of the LP tokens. New pools take the next free id, starting from zero.
+		type PoolAssetId: AssetId + AtLeast32BitUnsigned;
+
+		/// Registry of the LP tokens. It should not be shared with `Assets`, otherwise the ids
+		/// handed out to pools may collide with existing assets.
+		type PoolAssets: Inspect<Self::AccountId, AssetId = Self::PoolAssetId, Balance = Self::Balance>
+			+ Create<Self::AccountId>
+			+ Mutate<Self::AccountId>;
+
+		/// The share of every swap's input kept by the pool for its liquidity providers.
+		#[pallet::constant]
+		type LPFee: Get<Permill>;
+
+		/// The pallet id, used for deriving the accounts of the pools.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The amount of LP tokens locked in a pool when its first liquidity is added.
+		#[pallet::constant]
+		type MintMinLiquidity: Get<Self::Balance>;
+
+		/// The maximum number of currencies in a swap path, including both ends.
+		#[pallet::constant]
+		type MaxSwapPathLength: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The existing pools, keyed by their sorted pair of currencies.
+	#[pallet::storage]
+	pub type Pools<T: Config> =
+		StorageMap<_, Blake2_128Concat, PoolIdOf<T>, PoolInfo<T::PoolAssetId>>;
+
+	/// The LP token id that will be assigned to the next pool.
+	#[pallet::storage]
+	pub type NextPoolAssetId<T: Config> = StorageValue<_, T::PoolAssetId, OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A pool was created.
+		PoolCreated { creator: T::AccountId, pool_id: PoolIdOf<T>, lp_token: T::PoolAssetId },
+		/// Liquidity was added to a pool. The amounts follow the order of `pool_id`.
+		LiquidityAdded {
+			who: T::AccountId,
+			mint_to: T::AccountId,
+			pool_id: PoolIdOf<T>,
+			amount1_provided: T::Balance,
+			amount2_provided: T::Balance,
+			lp_token: T::PoolAssetId,
+			lp_token_minted: T::Balance,
+		},
+		/// Liquidity was removed from a pool. The amounts follow the order of `pool_id`.
+		LiquidityRemoved {
+			who: T::AccountId,
+			withdraw_to: T::AccountId,
+			pool_id: PoolIdOf<T>,
+			amount1: T::Balance,
+			amount2: T::Balance,
+			lp_token: T::PoolAssetId,
+			lp_token_burned: T::Balance,
+		},
+		/// A swap along `path` was executed.
+		SwapExecuted {
+			who: T::AccountId,
+			send_to: T::AccountId,
+			path: SwapPathOf<T>,
+			amount_in: T::Balance,
+			amount_out: T::Balance,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Both currencies of the pair are the same.
+		EqualAssets,
+		/// A pool for this pair already exists.
+		PoolExists,
+		/// No pool exists for this pair.
+		PoolNotFound,
+		/// The pool has no liquidity.
+		EmptyPool,
+		/// An amount given is zero.
+		ZeroAmount,
+		/// The deposit of the first currency would be below the requested minimum.
+		AssetOneDepositDidNotMeetMinimum,
+		/// The deposit of the second currency would be below the requested minimum.
+		AssetTwoDepositDidNotMeetMinimum,
+		/// The withdrawal of the first currency would be below the requested minimum.
+		AssetOneWithdrawalDidNotMeetMinimum,
+		/// The withdrawal of the second currency would be below the requested minimum.
+		AssetTwoWithdrawalDidNotMeetMinimum,
+		/// The first deposit of a pool must be worth more than `MintMinLiquidity` LP tokens.
+		InsufficientLiquidityMinted,
+		/// The requested output is not smaller than the reserve of the pool.
+		InsufficientLiquidity,
+		/// The swap would yield less than the requested minimum.
+		ProvidedMinimumNotSufficientForSwap,
+		/// The swap would cost more than the allowed maximum.
+		ProvidedMaximumNotSufficientForSwap,
+		/// The swap path is shorter than two currencies or hops between a currency and itself.
+		InvalidPath,
+		/// An arithmetic operation overflowed.
+		Overflow,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create an empty pool for the pair `asset1`/`asset2`.
+		///
+		/// The pool is given a new LP token, owned by the pool's account. The order of the pair
+		/// does not matter.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::create_pool())]
+		pub fn create_pool(
+			origin: OriginFor<T>,
+			asset1: NativeOrAssetId<T::AssetId>,
+			asset2: NativeOrAssetId<T::AssetId>,
+		) -> DispatchResult {
+			let creator = ensure_signed(origin)?;
+			ensure!(asset1 != asset2, Error::<T>::EqualAssets);
+
+			let pool_id = Self::get_pool_id(asset1, asset2);
+			ensure!(!Pools::<T>::contains_key(pool_id), Error::<T>::PoolExists);
+
+			// The pool account must exist before it can own the LP token or receive assets.
+			let pool_account = Self::get_pool_account(&pool_id);
+			frame_system::Pallet::<T>::inc_providers(&pool_account);
+
+			let lp_token = NextPoolAssetId::<T>::get().unwrap_or_else(Zero::zero);
+			let next_lp_token = lp_token.checked_add(&One::one()).ok_or(Error::<T>::Overflow)?;
+			T::PoolAssets::create(lp_token, pool_account, false, One::one())?;
+			NextPoolAssetId::<T>::put(next_lp_token);
+
+			Pools::<T>::insert(pool_id, PoolInfo { lp_token });
+			Self::deposit_event(Event::PoolCreated { creator, pool_id, lp_token });
+			Ok(())
+		}
+
+		/// Provide liquidity to the pool of `asset1`/`asset2`.
+		///
+		/// At most `amount1_desired` of `asset1` and `amount2_desired` of `asset2` are taken, in
+		/// the ratio of the current reserves, and never less than `amount1_min` and `amount2_min`
+		/// respectively. The LP tokens for the deposit are minted to `mint_to`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::add_liquidity())]
+		pub fn add_liquidity(
+			origin: OriginFor<T>,
+			asset1: NativeOrAssetId<T::AssetId>,
+			asset2: NativeOrAssetId<T::AssetId>,
+			amount1_desired: T::Balance,
+			amount2_desired: T::Balance,
+			amount1_min: T::Balance,
+			amount2_min: T::Balance,
+			mint_to: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(asset1 != asset2, Error::<T>::EqualAssets);
+			ensure!(
+				!amount1_desired.is_zero() && !amount2_desired.is_zero(),
+				Error::<T>::ZeroAmount
+			);
+
+			let pool_id = Self::get_pool_id(asset1, asset2);
+			let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let pool_account = Self::get_pool_account(&pool_id);
+
+			let reserve1 = Self::get_balance(&pool_account, asset1);
+			let reserve2 = Self::get_balance(&pool_account, asset2);
+
+			let (amount1, amount2) = if reserve1.is_zero() || reserve2.is_zero() {
+				(amount1_desired, amount2_desired)
+			} else {
+				let amount2_optimal = Self::quote(amount1_desired, reserve1, reserve2)?;
+				if amount2_optimal <= amount2_desired {
+					ensure!(
+						amount2_optimal >= amount2_min,
+						Error::<T>::AssetTwoDepositDidNotMeetMinimum
+					);
+					(amount1_desired, amount2_optimal)
+				} else {
+					let amount1_optimal = Self::quote(amount2_desired, reserve2, reserve1)?;
+					ensure!(
+						amount1_optimal >= amount1_min,
+						Error::<T>::AssetOneDepositDidNotMeetMinimum
+					);
+					(amount1_optimal, amount2_desired)
+				}
+			};
+
+			Self::transfer(asset1, &who, &pool_account, amount1, true)?;
+			Self::transfer(asset2, &who, &pool_account, amount2, true)?;
+
+			let total_supply = T::PoolAssets::total_issuance(pool.lp_token);
+			let lp_token_minted = if total_supply.is_zero() {
+				let liquidity = Self::calc_initial_liquidity(amount1, amount2)?;
+				let lp_token_minted = liquidity
+					.checked_sub(&T::MintMinLiquidity::get())
+					.filter(|minted| !minted.is_zero())
+					.ok_or(Error::<T>::InsufficientLiquidityMinted)?;
+				T::PoolAssets::mint_into(pool.lp_token, &pool_account, T::MintMinLiquidity::get())?;
+				lp_token_minted
+			} else {
+				let side1 = Self::mul_div(amount1, total_supply, reserve1, Rounding::Down)?;
+				let side2 = Self::mul_div(amount2, total_supply, reserve2, Rounding::Down)?;
+				side1.min(side2)
+			};
+			ensure!(!lp_token_minted.is_zero(), Error::<T>::InsufficientLiquidityMinted);
+			T::PoolAssets::mint_into(pool.lp_token, &mint_to, lp_token_minted)?;
+
+			let (amount1_provided, amount2_provided) =
+				if pool_id.0 == asset1 { (amount1, amount2) } else { (amount2, amount1) };
+			Self::deposit_event(Event::LiquidityAdded {
+				who,
+				mint_to,
+				pool_id,
+				amount1_provided,
+				amount2_provided,
+				lp_token: pool.lp_token,
+				lp_token_minted,
+			});
+			Ok(())
+		}
+
+		/// Burn `lp_token_burn` LP tokens of the pool of `asset1`/`asset2` and send the matching
+		/// share of its reserves to `withdraw_to`.
+		///
+		/// Fails if less than `amount1_min_receive` of `asset1` or `amount2_min_receive` of
+		/// `asset2` would be paid out.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::remove_liquidity())]
+		pub fn remove_liquidity(
+			origin: OriginFor<T>,
+			asset1: NativeOrAssetId<T::AssetId>,
+			asset2: NativeOrAssetId<T::AssetId>,
+			lp_token_burn: T::Balance,
+			amount1_min_receive: T::Balance,
+			amount2_min_receive: T::Balance,
+			withdraw_to: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(asset1 != asset2, Error::<T>::EqualAssets);
+			ensure!(!lp_token_burn.is_zero(), Error::<T>::ZeroAmount);
+
+			let pool_id = Self::get_pool_id(asset1, asset2);
+			let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let pool_account = Self::get_pool_account(&pool_id);
+
+			let reserve1 = Self::get_balance(&pool_account, asset1);
+			let reserve2 = Self::get_balance(&pool_account, asset2);
+
+			let total_supply = T::PoolAssets::total_issuance(pool.lp_token);
+			ensure!(!total_supply.is_zero(), Error::<T>::EmptyPool);
+
+			let amount1 = Self::mul_div(lp_token_burn, reserve1, total_supply, Rounding::Down)?;
+			let amount2 = Self::mul_div(lp_token_burn, reserve2, total_supply, Rounding::Down)?;
+			ensure!(
+				!amount1.is_zero() && amount1 >= amount1_min_receive,
+				Error::<T>::AssetOneWithdrawalDidNotMeetMinimum
+			);
+			ensure!(
+				!amount2.is_zero() && amount2 >= amount2_min_receive,
+				Error::<T>::AssetTwoWithdrawalDidNotMeetMinimum
+			);
+
+			T::PoolAssets::burn_from(pool.lp_token, &who, lp_token_burn)?;
+			Self::transfer(asset1, &pool_account, &withdraw_to, amount1, true)?;
+			Self::transfer(asset2, &pool_account, &withdraw_to, amount2, true)?;
+
+			let (amount1, amount2) =
+				if pool_id.0 == asset1 { (amount1, amount2) } else { (amount2, amount1) };
+			Self::deposit_event(Event::LiquidityRemoved {
+				who,
+				withdraw_to,
+				pool_id,
+				amount1,
+				amount2,
+				lp_token: pool.lp_token,
+				lp_token_burned: lp_token_burn,
+			});
+			Ok(())
+		}
+
+		/// Swap exactly `amount_in` of the first currency of `path` for as much as possible of
+		/// its last currency, which is sent to `send_to`.
+		///
+		/// Fails if less than `amount_out_min` would be received. `keep_alive` prevents the
+		/// sender's account from being reaped by the swap.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::swap_exact_tokens_for_tokens(path.len() as u32))]
+		pub fn swap_exact_tokens_for_tokens(
+			origin: OriginFor<T>,
+			path: SwapPathOf<T>,
+			amount_in: T::Balance,
+			amount_out_min: T::Balance,
+			send_to: T::AccountId,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!amount_in.is_zero(), Error::<T>::ZeroAmount);
+
+			let amounts = Self::get_amounts_out(amount_in, &path)?;
+			let amount_out = *amounts.last().ok_or(Error::<T>::InvalidPath)?;
+			ensure!(amount_out >= amount_out_min, Error::<T>::ProvidedMinimumNotSufficientForSwap);
+
+			Self::do_swap(who, &amounts, path, send_to, keep_alive)
+		}
+
+		/// Swap as little as possible of the first currency of `path` for exactly `amount_out` of
+		/// its last currency, which is sent to `send_to`.
+		///
+		/// Fails if more than `amount_in_max` would be paid. `keep_alive` prevents the sender's
+		/// account from being reaped by the swap.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::swap_tokens_for_exact_tokens(path.len() as u32))]
+		pub fn swap_tokens_for_exact_tokens(
+			origin: OriginFor<T>,
+			path: SwapPathOf<T>,
+			amount_out: T::Balance,
+			amount_in_max: T::Balance,
+			send_to: T::AccountId,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!amount_out.is_zero(), Error::<T>::ZeroAmount);
+
+			let amounts = Self::get_amounts_in(amount_out, &path)?;
+			let amount_in = *amounts.first().ok_or(Error::<T>::InvalidPath)?;
+			ensure!(amount_in <= amount_in_max, Error::<T>::ProvidedMaximumNotSufficientForSwap);
+
+			Self::do_swap(who, &amounts, path, send_to, keep_alive)
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The normalised id of the pool for a pair, with the smaller currency first.
+		pub fn get_pool_id(
+			asset1: NativeOrAssetId<T::AssetId>,
+			asset2: NativeOrAssetId<T::AssetId>,
+		) -> PoolIdOf<T> {
+			if asset1 <= asset2 {
+				(asset1, asset2)
+			} else {
+				(asset2, asset1)
+			}
+		}
+
+		/// The account holding the reserves of a pool.
+		pub fn get_pool_account(pool_id: &PoolIdOf<T>) -> T::AccountId {
+			// Hash the pair, so that short account ids still tell pools apart once truncated.
+			T::PalletId::get().into_sub_account_truncating(T::Hashing::hash_of(pool_id))
+		}
+
+		/// The reserves of the pool of `asset1`/`asset2`, in the order of the arguments.
+		pub fn get_reserves(
+			asset1: NativeOrAssetId<T::AssetId>,
+			asset2: NativeOrAssetId<T::AssetId>,
+		) -> Result<(T::Balance, T::Balance), Error<T>> {
+			ensure!(asset1 != asset2, Error::<T>::EqualAssets);
+			let pool_id = Self::get_pool_id(asset1, asset2);
+			ensure!(Pools::<T>::contains_key(pool_id), Error::<T>::PoolNotFound);
+			let pool_account = Self::get_pool_account(&pool_id);
+
+			let reserve1 = Self::get_balance(&pool_account, asset1);
+			let reserve2 = Self::get_balance(&pool_account, asset2);
+			ensure!(!reserve1.is_zero() && !reserve2.is_zero(), Error::<T>::EmptyPool);
+			Ok((reserve1, reserve2))
+		}
+
+		/// The amount of `asset2` received for exactly `amount` of `asset1`, if `include_fee`,
+		/// otherwise the value of `amount` at the spot price of the pool.
+		pub fn quote_price_exact_tokens_for_tokens(
+			asset1: NativeOrAssetId<T::AssetId>,
+			asset2: NativeOrAssetId<T::AssetId>,
+			amount: T::Balance,
+			include_fee: bool,
+		) -> Option<T::Balance> {
+			let (reserve1, reserve2) = Self::get_reserves(asset1, asset2).ok()?;
+			if include_fee {
+				Self::get_amount_out(amount, reserve1, reserve2).ok()
+			} else {
+				Self::quote(amount, reserve1, reserve2).ok()
+			}
+		}
+
+		/// The amount of `asset1` needed to receive exactly `amount` of `asset2`, if
+		/// `include_fee`, otherwise the value of `amount` at the spot price of the pool.
+		pub fn quote_price_tokens_for_exact_tokens(
+			asset1: NativeOrAssetId<T::AssetId>,
+			asset2: NativeOrAssetId<T::AssetId>,
+			amount: T::Balance,
+			include_fee: bool,
+		) -> Option<T::Balance> {
+			let (reserve1, reserve2) = Self::get_reserves(asset1, asset2).ok()?;
+			if include_fee {
+				Self::get_amount_in(amount, reserve1, reserve2).ok()
+			} else {
+				Self::quote(amount, reserve2, reserve1).ok()
+			}
+		}
+
+		/// The value of `amount` of one side of a pool in terms of the other, at the ratio of
+		/// `reserve1` to `reserve2`.
+		pub fn quote(
+			amount: T::Balance,
+			reserve1: T::Balance,
+			reserve2: T::Balance,
+		) -> Result<T::Balance, Error<T>> {
+			ensure!(!reserve1.is_zero() && !reserve2.is_zero(), Error::<T>::EmptyPool);
+			Self::mul_div(amount, reserve2, reserve1, Rounding::Down)
+		}
+
+		/// The output of a single hop taking exactly `amount_in`, after the LP fee.
+		pub fn get_amount_out(
+			amount_in: T::Balance,
+			reserve_in: T::Balance,
+			reserve_out: T::Balance,
+		) -> Result<T::Balance, Error<T>> {
+			ensure!(!reserve_in.is_zero() && !reserve_out.is_zero(), Error::<T>::EmptyPool);
+
+			let amount_in_with_fee = (Permill::one() - T::LPFee::get()).mul_floor(amount_in);
+			let denominator =
+				reserve_in.checked_add(&amount_in_with_fee).ok_or(Error::<T>::Overflow)?;
+			Self::mul_div(amount_in_with_fee, reserve_out, denominator, Rounding::Down)
+		}
+
+		/// The input of a single hop needed for exactly `amount_out`, including the LP fee.
+		pub fn get_amount_in(
+			amount_out: T::Balance,
+			reserve_in: T::Balance,
+			reserve_out: T::Balance,
+		) -> Result<T::Balance, Error<T>> {
+			ensure!(!reserve_in.is_zero() && !reserve_out.is_zero(), Error::<T>::EmptyPool);
+			ensure!(amount_out < reserve_out, Error::<T>::InsufficientLiquidity);
+
+			// `reserve_out - amount_out` is non-zero thanks to the check above.
+			let amount_in_without_fee =
+				Self::mul_div(amount_out, reserve_in, reserve_out - amount_out, Rounding::Up)?;
+			let fee_free = Permill::one() - T::LPFee::get();
+			ensure!(!fee_free.is_zero(), Error::<T>::Overflow);
+			Self::mul_div(
+				amount_in_without_fee,
+				Permill::one().deconstruct().into(),
+				fee_free.deconstruct().into(),
+				Rounding::Up,
+			)
+		}
+
+		/// The amounts flowing through every currency of `path` when paying exactly `amount_in`.
+		pub(crate) fn get_amounts_out(
+			amount_in: T::Balance,
+			path: &SwapPathOf<T>,
+		) -> Result<Vec<T::Balance>, DispatchError> {
+			Self::validate_swap_path(path)?;
+
+			let mut amounts = Vec::with_capacity(path.len());
+			amounts.push(amount_in);
+			let mut amount = amount_in;
+			for pair in path.windows(2) {
+				let (reserve_in, reserve_out) = Self::get_reserves(pair[0], pair[1])?;
+				amount = Self::get_amount_out(amount, reserve_in, reserve_out)?;
+				ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+				amounts.push(amount);
+			}
+			Ok(amounts)
+		}
+
+		/// The amounts flowing through every currency of `path` when receiving exactly
+		/// `amount_out`.
+		pub(crate) fn get_amounts_in(
+			amount_out: T::Balance,
+			path: &SwapPathOf<T>,
+		) -> Result<Vec<T::Balance>, DispatchError> {
+			Self::validate_swap_path(path)?;
+
+			let mut amounts = Vec::with_capacity(path.len());
+			amounts.push(amount_out);
+			let mut amount = amount_out;
+			for pair in path.windows(2).rev() {
+				let (reserve_in, reserve_out) = Self::get_reserves(pair[0], pair[1])?;
+				amount = Self::get_amount_in(amount, reserve_in, reserve_out)?;
+				amounts.push(amount);
+			}
+			amounts.reverse();
+			Ok(amounts)
+		}
+
+		/// Move the `amounts` along `path`, from `who` through the pool of every hop to `send_to`.
+		fn do_swap(
+			who: T::AccountId,
+			amounts: &[T::Balance],
+			path: SwapPathOf<T>,
+			send_to: T::AccountId,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let first_pool = Self::get_pool_account(&Self::get_pool_id(path[0], path[1]));
+			Self::transfer(path[0], &who, &first_pool, amounts[0], keep_alive)?;
+
+			let mut pool_account = first_pool;
+			for (i, pair) in path.windows(2).enumerate() {
+				let amount_out = amounts[i + 1];
+				let next = match path.get(i + 2) {
+					Some(after) => Self::get_pool_account(&Self::get_pool_id(pair[1], *after)),
+					None => send_to.clone(),
+				};
+				Self::transfer(pair[1], &pool_account, &next, amount_out, true)?;
+				pool_account = next;
+			}
+
+			let amount_in = amounts[0];
+			let amount_out = amounts[amounts.len() - 1];
+			Self::deposit_event(Event::SwapExecuted { who, send_to, path, amount_in, amount_out });
+			Ok(())
+		}
+
+		/// Ensure that `path` has at least one hop and never swaps a currency for itself.
+		fn validate_swap_path(path: &SwapPathOf<T>) -> Result<(), Error<T>> {
+			ensure!(path.len() >= 2, Error::<T>::InvalidPath);
+			ensure!(path.windows(2).all(|pair| pair[0] != pair[1]), Error::<T>::InvalidPath);
+			Ok(())
+		}
+
+		/// The balance of `asset` available to a pool, leaving its account alive.
+		fn get_balance(owner: &T::AccountId, asset: NativeOrAssetId<T::AssetId>) -> T::Balance {
+			match asset {
+				NativeOrAssetId::Native => T::Currency::reducible_balance(owner, true),
+				NativeOrAssetId::Asset(id) => T::Assets::reducible_balance(id, owner, true),
+			}
+		}
+
+		/// Transfer `amount` of `asset` from `from` to `to`.
+		fn transfer(
+			asset: NativeOrAssetId<T::AssetId>,
+			from: &T::AccountId,
+			to: &T::AccountId,
+			amount: T::Balance,
+			keep_alive: bool,
+		) -> Result<T::Balance, DispatchError> {
+			match asset {
+				NativeOrAssetId::Native => T::Currency::transfer(from, to, amount, keep_alive),
+				NativeOrAssetId::Asset(id) => T::Assets::transfer(id, from, to, amount, keep_alive),
+			}
+		}
+
+		/// The LP tokens backing the first deposit of a pool: `sqrt(amount1 * amount2)`.
+		fn calc_initial_liquidity(
+			amount1: T::Balance,
+			amount2: T::Balance,
+		) -> Result<T::Balance, Error<T>> {
+			let amount1: u128 = amount1.try_into().map_err(|_| Error::<T>::Overflow)?;
+			let amount2: u128 = amount2.try_into().map_err(|_| Error::<T>::Overflow)?;
+			// The product of two `u128`s always fits in a `U256`, and its root in a `u128`.
+			let product = U256::from(amount1) * U256::from(amount2);
+
+			// Newton's method, converging on the root from above.
+			let mut root = product;
+			let mut next = (product + 1) >> 1;
+			while next < root {
+				root = next;
+				next = (root + product / root) >> 1;
+			}
+			root.as_u128().try_into().map_err(|_| Error::<T>::Overflow)
+		}
+
+		/// `a * b / c`, computed without intermediate overflow.
+		fn mul_div(
+			a: T::Balance,
+			b: T::Balance,
+			c: T::Balance,
+			rounding: Rounding,
+		) -> Result<T::Balance, Error<T>> {
+			let a: u128 = a.try_into().map_err(|_| Error::<T>::Overflow)?;
+			let b: u128 = b.try_into().map_err(|_| Error::<T>::Overflow)?;
+			let c: u128 = c.try_into().map_err(|_| Error::<T>::Overflow)?;
+			multiply_by_rational_with_rounding(a, b, c, rounding)
+				.ok_or(Error::<T>::Overflow)?
+				.try_into()
+				.map_err(|_| Error::<T>::Overflow)
+		}
+	}
+}
+
+/// Prices `balance` of the native currency in `asset_id` at the spot price of their pool.
+///
+/// This allows e.g. `pallet-asset-tx-payment` to charge fees in any asset that has a pool with
+/// the native currency.
+impl<T: Config> BalanceConversion<T::Balance, T::AssetId, T::Balance> for Pallet<T> {
+	type Error = Error<T>;
+
+	fn to_asset_balance(balance: T::Balance, asset_id: T::AssetId) -> Result<T::Balance, Error<T>> {
+		let (reserve_native, reserve_asset) =
+			Self::get_reserves(NativeOrAssetId::Native, NativeOrAssetId::Asset(asset_id))?;
+		Self::quote(balance, reserve_native, reserve_asset)
+	}
+}
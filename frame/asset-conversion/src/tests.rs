@@ -0,0 +1,598 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for pallet-asset-conversion.
+
+use super::*;
+use crate::{mock::*, Event};
+use frame_support::{
+	assert_noop, assert_ok, bounded_vec,
+	traits::{fungible::Inspect as _, fungibles},
+};
+
+const NATIVE: NativeOrAssetId<u32> = NativeOrAssetId::Native;
+const ASSET_2: NativeOrAssetId<u32> = NativeOrAssetId::Asset(2);
+const ASSET_3: NativeOrAssetId<u32> = NativeOrAssetId::Asset(3);
+
+fn create_asset(id: u32, holders: &[(AccountId, Balance)]) {
+	assert_ok!(Assets::force_create(RuntimeOrigin::root(), id, 1, true, 1));
+	for (who, amount) in holders {
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(1), id, *who, *amount));
+	}
+}
+
+fn pool_account(asset1: NativeOrAssetId<u32>, asset2: NativeOrAssetId<u32>) -> AccountId {
+	AssetConversion::get_pool_account(&AssetConversion::get_pool_id(asset1, asset2))
+}
+
+fn balance(who: AccountId, asset: NativeOrAssetId<u32>) -> Balance {
+	match asset {
+		NativeOrAssetId::Native => Balances::balance(&who),
+		NativeOrAssetId::Asset(id) => Assets::balance(id, who),
+	}
+}
+
+fn lp_balance(lp_token: u32, who: AccountId) -> Balance {
+	PoolAssets::balance(lp_token, who)
+}
+
+fn last_event() -> Event<Test> {
+	System::events()
+		.into_iter()
+		.rev()
+		.find_map(|r| if let RuntimeEvent::AssetConversion(e) = r.event { Some(e) } else { None })
+		.expect("an asset conversion event was deposited")
+}
+
+/// Create the pool of `asset1`/`asset2` and seed it from account 1.
+fn setup_pool(
+	asset1: NativeOrAssetId<u32>,
+	asset2: NativeOrAssetId<u32>,
+	amount1: Balance,
+	amount2: Balance,
+) {
+	assert_ok!(AssetConversion::create_pool(RuntimeOrigin::signed(1), asset1, asset2));
+	assert_ok!(AssetConversion::add_liquidity(
+		RuntimeOrigin::signed(1),
+		asset1,
+		asset2,
+		amount1,
+		amount2,
+		1,
+		1,
+		1,
+	));
+}
+
+#[test]
+fn create_pool_works() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, &[]);
+
+		assert_ok!(AssetConversion::create_pool(RuntimeOrigin::signed(1), ASSET_2, NATIVE));
+
+		let pool_id = (NATIVE, ASSET_2);
+		assert_eq!(Pools::<Test>::get(pool_id), Some(PoolInfo { lp_token: 0 }));
+		assert_eq!(NextPoolAssetId::<Test>::get(), Some(1));
+		assert_eq!(last_event(), Event::PoolCreated { creator: 1, pool_id, lp_token: 0 });
+		// The LP token is owned by the pool.
+		assert_eq!(
+			<PoolAssets as fungibles::roles::Inspect<_>>::owner(0),
+			Some(pool_account(NATIVE, ASSET_2))
+		);
+
+		// The order of the pair does not matter.
+		assert_noop!(
+			AssetConversion::create_pool(RuntimeOrigin::signed(2), NATIVE, ASSET_2),
+			Error::<Test>::PoolExists
+		);
+		assert_noop!(
+			AssetConversion::create_pool(RuntimeOrigin::signed(2), ASSET_2, ASSET_2),
+			Error::<Test>::EqualAssets
+		);
+
+		// Pools of two assets get the next LP token and their own account.
+		assert_ok!(AssetConversion::create_pool(RuntimeOrigin::signed(2), ASSET_3, ASSET_2));
+		assert_eq!(Pools::<Test>::get((ASSET_2, ASSET_3)), Some(PoolInfo { lp_token: 1 }));
+		assert_ne!(pool_account(ASSET_2, ASSET_3), pool_account(NATIVE, ASSET_2));
+	});
+}
+
+#[test]
+fn add_liquidity_works() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, &[(1, 100_000), (2, 100_000)]);
+		assert_ok!(AssetConversion::create_pool(RuntimeOrigin::signed(1), NATIVE, ASSET_2));
+		let pool = pool_account(NATIVE, ASSET_2);
+
+		assert_noop!(
+			AssetConversion::add_liquidity(
+				RuntimeOrigin::signed(1),
+				NATIVE,
+				ASSET_3,
+				10,
+				10,
+				1,
+				1,
+				1
+			),
+			Error::<Test>::PoolNotFound
+		);
+		assert_noop!(
+			AssetConversion::add_liquidity(
+				RuntimeOrigin::signed(1),
+				NATIVE,
+				ASSET_2,
+				0,
+				10,
+				0,
+				0,
+				1
+			),
+			Error::<Test>::ZeroAmount
+		);
+		// sqrt(100 * 100) does not exceed the locked minimum.
+		assert_noop!(
+			AssetConversion::add_liquidity(
+				RuntimeOrigin::signed(1),
+				NATIVE,
+				ASSET_2,
+				100,
+				100,
+				1,
+				1,
+				1
+			),
+			Error::<Test>::InsufficientLiquidityMinted
+		);
+
+		// The first deposit is taken as is, sqrt(10_000 * 1_000) = 3_162 LP tokens are minted.
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(1),
+			ASSET_2,
+			NATIVE,
+			1_000,
+			10_000,
+			1,
+			1,
+			1,
+		));
+		assert_eq!(
+			last_event(),
+			Event::LiquidityAdded {
+				who: 1,
+				mint_to: 1,
+				pool_id: (NATIVE, ASSET_2),
+				amount1_provided: 10_000,
+				amount2_provided: 1_000,
+				lp_token: 0,
+				lp_token_minted: 3_062,
+			}
+		);
+		assert_eq!(balance(pool, NATIVE), 10_000);
+		assert_eq!(balance(pool, ASSET_2), 1_000);
+		assert_eq!(lp_balance(0, 1), 3_062);
+		assert_eq!(lp_balance(0, pool), 100);
+
+		// Later deposits follow the ratio of the reserves, which leave the minimum balances of
+		// the pool account untouched: 9_999 to 999.
+		assert_noop!(
+			AssetConversion::add_liquidity(
+				RuntimeOrigin::signed(2),
+				NATIVE,
+				ASSET_2,
+				5_000,
+				1_000,
+				1,
+				600,
+				2
+			),
+			Error::<Test>::AssetTwoDepositDidNotMeetMinimum
+		);
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(2),
+			NATIVE,
+			ASSET_2,
+			5_000,
+			1_000,
+			1,
+			1,
+			3,
+		));
+		// 5_000 * 999 / 9_999 = 499 of the asset; 3_162 * 499 / 999 = 1_579 LP tokens.
+		assert_eq!(balance(pool, NATIVE), 15_000);
+		assert_eq!(balance(pool, ASSET_2), 1_499);
+		assert_eq!(lp_balance(0, 2), 0);
+		assert_eq!(lp_balance(0, 3), 1_579);
+
+		// When the asset is the limiting side, the native amount is derived from it instead:
+		// 100 * 14_999 / 1_498 = 1_001.
+		assert_noop!(
+			AssetConversion::add_liquidity(
+				RuntimeOrigin::signed(2),
+				NATIVE,
+				ASSET_2,
+				5_000,
+				100,
+				1_002,
+				1,
+				2
+			),
+			Error::<Test>::AssetOneDepositDidNotMeetMinimum
+		);
+	});
+}
+
+#[test]
+fn remove_liquidity_works() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, &[(1, 100_000)]);
+		setup_pool(NATIVE, ASSET_2, 10_000, 1_000);
+		let pool = pool_account(NATIVE, ASSET_2);
+		let native_before = balance(1, NATIVE);
+
+		assert_noop!(
+			AssetConversion::remove_liquidity(
+				RuntimeOrigin::signed(1),
+				NATIVE,
+				ASSET_2,
+				0,
+				0,
+				0,
+				1
+			),
+			Error::<Test>::ZeroAmount
+		);
+		// 3_062 of 3_162 LP tokens are worth 3_062 * 9_999 / 3_162 = 9_682 native and
+		// 3_062 * 999 / 3_162 = 967 of the asset.
+		assert_noop!(
+			AssetConversion::remove_liquidity(
+				RuntimeOrigin::signed(1),
+				ASSET_2,
+				NATIVE,
+				3_062,
+				968,
+				1,
+				1
+			),
+			Error::<Test>::AssetOneWithdrawalDidNotMeetMinimum
+		);
+		assert_ok!(AssetConversion::remove_liquidity(
+			RuntimeOrigin::signed(1),
+			ASSET_2,
+			NATIVE,
+			3_062,
+			967,
+			9_682,
+			2,
+		));
+		assert_eq!(
+			last_event(),
+			Event::LiquidityRemoved {
+				who: 1,
+				withdraw_to: 2,
+				pool_id: (NATIVE, ASSET_2),
+				amount1: 9_682,
+				amount2: 967,
+				lp_token: 0,
+				lp_token_burned: 3_062,
+			}
+		);
+		assert_eq!(lp_balance(0, 1), 0);
+		assert_eq!(balance(1, NATIVE), native_before);
+		assert_eq!(balance(2, ASSET_2), 967);
+		assert_eq!(balance(pool, NATIVE), 10_000 - 9_682);
+		assert_eq!(balance(pool, ASSET_2), 1_000 - 967);
+
+		// Only the locked LP tokens are left, and they are not withdrawable by anyone else.
+		assert!(AssetConversion::remove_liquidity(
+			RuntimeOrigin::signed(1),
+			NATIVE,
+			ASSET_2,
+			1,
+			0,
+			0,
+			1
+		)
+		.is_err());
+	});
+}
+
+#[test]
+fn pricing_functions_work() {
+	new_test_ext().execute_with(|| {
+		// 0.3% of 1_000 is kept: 997 * 10_000 / 10_997.
+		assert_eq!(AssetConversion::get_amount_out(1_000, 10_000, 10_000).ok(), Some(906));
+		// And the inverse rounds up to cover the output: 906 * 10_000 / 9_094 / 0.997.
+		assert_eq!(AssetConversion::get_amount_in(906, 10_000, 10_000).ok(), Some(1_000));
+		assert_eq!(AssetConversion::quote(1_000, 10_000, 20_000).ok(), Some(2_000));
+
+		assert!(matches!(
+			AssetConversion::get_amount_in(10_000, 10_000, 10_000),
+			Err(Error::<Test>::InsufficientLiquidity)
+		));
+		assert!(matches!(AssetConversion::get_amount_out(1, 0, 10), Err(Error::<Test>::EmptyPool)));
+		assert!(matches!(AssetConversion::quote(1, 10, 0), Err(Error::<Test>::EmptyPool)));
+
+		// Large balances do not overflow the intermediate products.
+		let big = u128::MAX / 2;
+		assert_eq!(AssetConversion::quote(big, big, big).ok(), Some(big));
+	});
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_works() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, &[(1, 1_000_000)]);
+		setup_pool(NATIVE, ASSET_2, 100_001, 200_001);
+		let pool = pool_account(NATIVE, ASSET_2);
+		let native_before = balance(2, NATIVE);
+
+		let expected = AssetConversion::get_amount_out(1_000, 100_000, 200_000).unwrap();
+		assert_eq!(expected, 1_974);
+
+		assert_noop!(
+			AssetConversion::swap_exact_tokens_for_tokens(
+				RuntimeOrigin::signed(2),
+				bounded_vec![NATIVE, ASSET_2],
+				1_000,
+				expected + 1,
+				3,
+				true,
+			),
+			Error::<Test>::ProvidedMinimumNotSufficientForSwap
+		);
+		assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+			RuntimeOrigin::signed(2),
+			bounded_vec![NATIVE, ASSET_2],
+			1_000,
+			expected,
+			3,
+			true,
+		));
+		assert_eq!(
+			last_event(),
+			Event::SwapExecuted {
+				who: 2,
+				send_to: 3,
+				path: bounded_vec![NATIVE, ASSET_2],
+				amount_in: 1_000,
+				amount_out: expected,
+			}
+		);
+		assert_eq!(balance(2, NATIVE), native_before - 1_000);
+		assert_eq!(balance(3, ASSET_2), expected);
+		assert_eq!(balance(pool, NATIVE), 101_001);
+		assert_eq!(balance(pool, ASSET_2), 200_001 - expected);
+	});
+}
+
+#[test]
+fn swap_tokens_for_exact_tokens_works() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, &[(1, 1_000_000), (2, 1_000)]);
+		setup_pool(NATIVE, ASSET_2, 100_001, 200_001);
+		let pool = pool_account(NATIVE, ASSET_2);
+		let native_before = balance(3, NATIVE);
+
+		// Buy native with the asset this time.
+		let expected = AssetConversion::get_amount_in(400, 200_000, 100_000).unwrap();
+		assert_eq!(expected, 807);
+
+		assert_noop!(
+			AssetConversion::swap_tokens_for_exact_tokens(
+				RuntimeOrigin::signed(2),
+				bounded_vec![ASSET_2, NATIVE],
+				400,
+				expected - 1,
+				3,
+				true,
+			),
+			Error::<Test>::ProvidedMaximumNotSufficientForSwap
+		);
+		assert_noop!(
+			AssetConversion::swap_tokens_for_exact_tokens(
+				RuntimeOrigin::signed(2),
+				bounded_vec![ASSET_2, NATIVE],
+				100_000,
+				u128::MAX,
+				3,
+				true,
+			),
+			Error::<Test>::InsufficientLiquidity
+		);
+		assert_ok!(AssetConversion::swap_tokens_for_exact_tokens(
+			RuntimeOrigin::signed(2),
+			bounded_vec![ASSET_2, NATIVE],
+			400,
+			expected,
+			3,
+			true,
+		));
+		assert_eq!(
+			last_event(),
+			Event::SwapExecuted {
+				who: 2,
+				send_to: 3,
+				path: bounded_vec![ASSET_2, NATIVE],
+				amount_in: expected,
+				amount_out: 400,
+			}
+		);
+		assert_eq!(balance(2, ASSET_2), 1_000 - expected);
+		assert_eq!(balance(3, NATIVE), native_before + 400);
+		assert_eq!(balance(pool, NATIVE), 100_001 - 400);
+		assert_eq!(balance(pool, ASSET_2), 200_001 + expected);
+	});
+}
+
+#[test]
+fn multi_hop_swaps_work() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, &[(1, 1_000_000), (2, 10_000)]);
+		create_asset(3, &[(1, 1_000_000)]);
+		setup_pool(NATIVE, ASSET_2, 100_001, 200_001);
+		setup_pool(NATIVE, ASSET_3, 100_001, 50_001);
+		let pool_2 = pool_account(NATIVE, ASSET_2);
+		let pool_3 = pool_account(NATIVE, ASSET_3);
+
+		// Asset 2 is sold for native in the first pool, which buys asset 3 in the second one.
+		let native_out = AssetConversion::get_amount_out(1_000, 200_000, 100_000).unwrap();
+		let asset_3_out = AssetConversion::get_amount_out(native_out, 100_000, 50_000).unwrap();
+		assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+			RuntimeOrigin::signed(2),
+			bounded_vec![ASSET_2, NATIVE, ASSET_3],
+			1_000,
+			asset_3_out,
+			2,
+			true,
+		));
+		assert_eq!(balance(2, ASSET_2), 9_000);
+		assert_eq!(balance(2, ASSET_3), asset_3_out);
+		assert_eq!(balance(pool_2, NATIVE), 100_001 - native_out);
+		assert_eq!(balance(pool_3, NATIVE), 100_001 + native_out);
+		assert_eq!(balance(pool_3, ASSET_3), 50_001 - asset_3_out);
+
+		// The exact output variant prices every hop backwards from the output.
+		let path: SwapPathOf<Test> = bounded_vec![ASSET_2, NATIVE, ASSET_3];
+		let amounts = AssetConversion::get_amounts_in(100, &path).unwrap();
+		assert_eq!(amounts.len(), 3);
+		assert_eq!(amounts[2], 100);
+		assert_ok!(AssetConversion::swap_tokens_for_exact_tokens(
+			RuntimeOrigin::signed(2),
+			path,
+			100,
+			amounts[0],
+			2,
+			true,
+		));
+		assert_eq!(balance(2, ASSET_2), 9_000 - amounts[0]);
+		assert_eq!(balance(2, ASSET_3), asset_3_out + 100);
+	});
+}
+
+#[test]
+fn invalid_swap_paths_fail() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, &[(1, 1_000_000)]);
+		create_asset(3, &[(1, 1_000_000)]);
+		setup_pool(NATIVE, ASSET_2, 100_001, 200_001);
+
+		assert_noop!(
+			AssetConversion::swap_exact_tokens_for_tokens(
+				RuntimeOrigin::signed(1),
+				bounded_vec![NATIVE],
+				1_000,
+				1,
+				1,
+				true,
+			),
+			Error::<Test>::InvalidPath
+		);
+		assert_noop!(
+			AssetConversion::swap_exact_tokens_for_tokens(
+				RuntimeOrigin::signed(1),
+				bounded_vec![NATIVE, ASSET_2, ASSET_2],
+				1_000,
+				1,
+				1,
+				true,
+			),
+			Error::<Test>::InvalidPath
+		);
+		assert_noop!(
+			AssetConversion::swap_tokens_for_exact_tokens(
+				RuntimeOrigin::signed(1),
+				bounded_vec![NATIVE, ASSET_3],
+				1_000,
+				u128::MAX,
+				1,
+				true,
+			),
+			Error::<Test>::PoolNotFound
+		);
+		assert_noop!(
+			AssetConversion::swap_exact_tokens_for_tokens(
+				RuntimeOrigin::signed(1),
+				bounded_vec![NATIVE, ASSET_2],
+				0,
+				1,
+				1,
+				true,
+			),
+			Error::<Test>::ZeroAmount
+		);
+	});
+}
+
+#[test]
+fn quote_price_works() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, &[(1, 1_000_000), (2, 1_000_000)]);
+		assert_eq!(
+			AssetConversion::quote_price_exact_tokens_for_tokens(NATIVE, ASSET_2, 1, true),
+			None
+		);
+		setup_pool(NATIVE, ASSET_2, 100_001, 200_001);
+
+		assert_eq!(AssetConversion::get_reserves(ASSET_2, NATIVE).ok(), Some((200_000, 100_000)));
+		assert_eq!(
+			AssetConversion::quote_price_exact_tokens_for_tokens(NATIVE, ASSET_2, 1_000, false),
+			Some(2_000)
+		);
+		assert_eq!(
+			AssetConversion::quote_price_tokens_for_exact_tokens(NATIVE, ASSET_2, 2_000, false),
+			Some(1_000)
+		);
+		assert_eq!(
+			AssetConversion::quote_price_exact_tokens_for_tokens(NATIVE, ASSET_2, 1_000, true),
+			Some(1_974)
+		);
+
+		// Paying the quoted input buys at least the requested output.
+		let amount_in =
+			AssetConversion::quote_price_tokens_for_exact_tokens(ASSET_2, NATIVE, 500, true)
+				.unwrap();
+		let native_before = balance(2, NATIVE);
+		assert_ok!(AssetConversion::swap_exact_tokens_for_tokens(
+			RuntimeOrigin::signed(2),
+			bounded_vec![ASSET_2, NATIVE],
+			amount_in,
+			500,
+			2,
+			true,
+		));
+		assert!(balance(2, NATIVE) >= native_before + 500);
+	});
+}
+
+#[test]
+fn balance_conversion_uses_native_pool_spot_price() {
+	new_test_ext().execute_with(|| {
+		create_asset(2, &[(1, 1_000_000)]);
+		assert!(matches!(
+			<AssetConversion as BalanceConversion<_, _, _>>::to_asset_balance(100, 2),
+			Err(Error::<Test>::PoolNotFound)
+		));
+
+		setup_pool(NATIVE, ASSET_2, 100_001, 200_001);
+		assert_eq!(
+			<AssetConversion as BalanceConversion<_, _, _>>::to_asset_balance(100, 2).ok(),
+			Some(200)
+		);
+	});
+}
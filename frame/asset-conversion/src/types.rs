@@ -0,0 +1,70 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types used by the asset conversion pallet.
+
+use super::*;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// Identifier of a currency that can be pooled: either the native currency or one of the assets.
+///
+/// `Native` orders before every `Asset`, which is relied upon when normalising pool ids.
+#[derive(
+	Clone,
+	Copy,
+	Decode,
+	Default,
+	Encode,
+	Eq,
+	MaxEncodedLen,
+	Ord,
+	PartialEq,
+	PartialOrd,
+	RuntimeDebug,
+	TypeInfo,
+)]
+pub enum NativeOrAssetId<AssetId> {
+	/// The native currency, e.g. `pallet-balances`.
+	#[default]
+	Native,
+	/// An asset managed by `Config::Assets`.
+	Asset(AssetId),
+}
+
+impl<AssetId> From<AssetId> for NativeOrAssetId<AssetId> {
+	fn from(asset: AssetId) -> Self {
+		Self::Asset(asset)
+	}
+}
+
+/// Information kept about an existing pool.
+#[derive(Clone, Decode, Encode, Default, PartialEq, Eq, MaxEncodedLen, RuntimeDebug, TypeInfo)]
+pub struct PoolInfo<PoolAssetId> {
+	/// The asset used to represent shares of the pool's liquidity.
+	pub lp_token: PoolAssetId,
+}
+
+/// A pair of currencies identifying a pool, always stored in ascending order.
+pub type PoolIdOf<T> =
+	(NativeOrAssetId<<T as Config>::AssetId>, NativeOrAssetId<<T as Config>::AssetId>);
+
+/// The path of a swap, from the currency paid in to the currency received.
+pub type SwapPathOf<T> =
+	BoundedVec<NativeOrAssetId<<T as Config>::AssetId>, <T as Config>::MaxSwapPathLength>;
@@ -22,7 +22,8 @@
 //!
 //! ## Overview
 //!
-//! The Aura module extends Aura consensus by managing offline reporting.
+//! The Aura module extends Aura consensus by managing offline reporting and by
+//! handling equivocation reports, see [`HandleEquivocation`].
 //!
 //! ## Interface
 //!
@@ -40,24 +41,45 @@
 
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
+	dispatch::{DispatchResultWithPostInfo, Pays},
 	log,
 	traits::{DisabledValidators, FindAuthor, Get, OnTimestampSet, OneSessionHandler},
+	weights::Weight,
 	BoundedSlice, BoundedVec, ConsensusEngineId, Parameter,
 };
-use sp_consensus_aura::{AuthorityIndex, ConsensusLog, Slot, AURA_ENGINE_ID};
+use sp_consensus_aura::{AuthorityIndex, ConsensusLog, EquivocationProof, Slot, AURA_ENGINE_ID};
 use sp_runtime::{
 	generic::DigestItem,
 	traits::{IsMember, Member, SaturatedConversion, Saturating, Zero},
 	RuntimeAppPublic,
 };
+use sp_session::{GetSessionNumber, GetValidatorCount};
 use sp_std::prelude::*;
 
+mod default_weights;
+mod equivocation;
 pub mod migrations;
 mod mock;
 mod tests;
 
+pub use equivocation::{AuraEquivocationOffence, EquivocationHandler, HandleEquivocation};
 pub use pallet::*;
 
+/// The proof of key ownership used by the [`HandleEquivocation`] of a runtime.
+pub type KeyOwnerProof<T> =
+	<<T as Config>::HandleEquivocation as HandleEquivocation<T>>::KeyOwnerProof;
+
+/// The weight functions used by the [`HandleEquivocation`] of a runtime.
+type EquivocationWeightInfo<T> =
+	<<T as Config>::HandleEquivocation as HandleEquivocation<T>>::WeightInfo;
+
+/// Weight functions needed for `pallet_aura`.
+pub trait WeightInfo {
+	/// The weight of `report_equivocation` and `report_equivocation_unsigned`, for a key
+	/// ownership proof over a validator set of `validator_count` validators.
+	fn report_equivocation(validator_count: u32) -> Weight;
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -79,6 +101,26 @@ pub mod pallet {
 		/// Blocks authored by a disabled validator will lead to a panic as part of this module's
 		/// initialization.
 		type DisabledValidators: DisabledValidators;
+
+		/// The equivocation handling subsystem, defines how key ownership is proven, methods to
+		/// report an offence (after the equivocation has been validated) and for submitting a
+		/// transaction to report an equivocation (from an offchain context).
+		///
+		/// Set it to `()` to disable equivocation reporting, or to an [`EquivocationHandler`].
+		/// NOTE: when enabling equivocation handling (i.e. this type isn't set to
+		/// `()`) you must use this pallet's `ValidateUnsigned` in the runtime
+		/// definition.
+		type HandleEquivocation: HandleEquivocation<Self>;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// An equivocation proof provided as part of an equivocation report is invalid.
+		InvalidEquivocationProof,
+		/// A key ownership proof provided as part of an equivocation report is invalid.
+		InvalidKeyOwnershipProof,
+		/// A given equivocation report is valid but already previously reported.
+		DuplicateOffenceReport,
 	}
 
 	#[pallet::pallet]
@@ -113,6 +155,63 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Report authority equivocation/misbehavior. This method will verify
+		/// the equivocation proof and validate the given key ownership proof
+		/// against the extracted offender. If both are valid, the offence will
+		/// be reported.
+		#[pallet::weight(EquivocationWeightInfo::<T>::report_equivocation(
+			key_owner_proof.validator_count(),
+		))]
+		pub fn report_equivocation(
+			origin: OriginFor<T>,
+			equivocation_proof: Box<EquivocationProof<T::Header, T::AuthorityId>>,
+			key_owner_proof: KeyOwnerProof<T>,
+		) -> DispatchResultWithPostInfo {
+			let reporter = ensure_signed(origin)?;
+
+			Self::do_report_equivocation(Some(reporter), *equivocation_proof, key_owner_proof)
+		}
+
+		/// Report authority equivocation/misbehavior. This method will verify
+		/// the equivocation proof and validate the given key ownership proof
+		/// against the extracted offender. If both are valid, the offence will
+		/// be reported.
+		/// This extrinsic must be called unsigned and it is expected that only
+		/// block authors will call it (validated in `ValidateUnsigned`), as such
+		/// if the block author is defined it will be defined as the equivocation
+		/// reporter.
+		#[pallet::weight(EquivocationWeightInfo::<T>::report_equivocation(
+			key_owner_proof.validator_count(),
+		))]
+		pub fn report_equivocation_unsigned(
+			origin: OriginFor<T>,
+			equivocation_proof: Box<EquivocationProof<T::Header, T::AuthorityId>>,
+			key_owner_proof: KeyOwnerProof<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			Self::do_report_equivocation(
+				T::HandleEquivocation::block_author(),
+				*equivocation_proof,
+				key_owner_proof,
+			)
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			Self::validate_unsigned(source, call)
+		}
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			Self::pre_dispatch(call)
+		}
+	}
+
 	/// The current authority set.
 	#[pallet::storage]
 	#[pallet::getter(fn authorities)]
@@ -194,6 +293,57 @@ impl<T: Config> Pallet<T> {
 		// the majority of its slot.
 		<T as pallet_timestamp::Config>::MinimumPeriod::get().saturating_mul(2u32.into())
 	}
+
+	fn do_report_equivocation(
+		reporter: Option<T::AccountId>,
+		equivocation_proof: EquivocationProof<T::Header, T::AuthorityId>,
+		key_owner_proof: KeyOwnerProof<T>,
+	) -> DispatchResultWithPostInfo {
+		let offender = equivocation_proof.offender.clone();
+		let slot = equivocation_proof.slot;
+
+		// validate the equivocation proof
+		if !sp_consensus_aura::check_equivocation_proof(equivocation_proof) {
+			return Err(Error::<T>::InvalidEquivocationProof.into())
+		}
+
+		let validator_set_count = key_owner_proof.validator_count();
+		let session_index = key_owner_proof.session();
+
+		// check the membership proof and extract the offender's id
+		let key = (sp_consensus_aura::KEY_TYPE, offender);
+		let offender = T::HandleEquivocation::check_proof(key, key_owner_proof)
+			.ok_or(Error::<T>::InvalidKeyOwnershipProof)?;
+
+		let offence =
+			AuraEquivocationOffence { slot, validator_set_count, offender, session_index };
+
+		let reporters = match reporter {
+			Some(id) => vec![id],
+			None => vec![],
+		};
+
+		T::HandleEquivocation::report_offence(reporters, offence)
+			.map_err(|_| Error::<T>::DuplicateOffenceReport)?;
+
+		// waive the fee since the report is valid and beneficial
+		Ok(Pays::No.into())
+	}
+
+	/// Submits an extrinsic to report an equivocation. This method will create
+	/// an unsigned extrinsic with a call to `report_equivocation_unsigned` and
+	/// will push the transaction to the pool. Only useful in an offchain
+	/// context.
+	pub fn submit_unsigned_equivocation_report(
+		equivocation_proof: EquivocationProof<T::Header, T::AuthorityId>,
+		key_owner_proof: KeyOwnerProof<T>,
+	) -> Option<()> {
+		T::HandleEquivocation::submit_unsigned_equivocation_report(
+			equivocation_proof,
+			key_owner_proof,
+		)
+		.ok()
+	}
 }
 
 impl<T: Config> sp_runtime::BoundToRuntimeAppPublic for Pallet<T> {
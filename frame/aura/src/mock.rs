@@ -20,15 +20,27 @@
 #![cfg(test)]
 
 use crate as pallet_aura;
+use codec::{Decode, Encode};
 use frame_support::{
 	parameter_types,
-	traits::{ConstU32, ConstU64, DisabledValidators, GenesisBuild},
+	traits::{
+		ConstU32, ConstU64, DisabledValidators, FindAuthor, GenesisBuild, KeyOwnerProofSystem,
+	},
+	weights::Weight,
+	ConsensusEngineId,
 };
-use sp_consensus_aura::{ed25519::AuthorityId, AuthorityIndex};
+use scale_info::TypeInfo;
+use sp_consensus_aura::{AuthorityIndex, KEY_TYPE};
 use sp_core::H256;
 use sp_runtime::{
-	testing::{Header, UintAuthorityId},
+	testing::{Header, TestXt, UintAuthorityId},
 	traits::IdentityLookup,
+	KeyTypeId, Perbill,
+};
+use sp_session::{GetSessionNumber, GetValidatorCount, ValidatorCount};
+use sp_staking::{
+	offence::{DisableStrategy, OffenceDetails, OnOffenceHandler},
+	SessionIndex,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -42,10 +54,20 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
-		Aura: pallet_aura::{Pallet, Storage, Config<T>},
+		Aura: pallet_aura::{Pallet, Call, Storage, Config<T>, ValidateUnsigned},
+		Authorship: pallet_authorship::{Pallet, Call, Storage, Inherent},
+		Offences: pallet_offences::{Pallet, Storage, Event},
 	}
 );
 
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = TestXt<RuntimeCall, ()>;
+}
+
 parameter_types! {
 	pub BlockWeights: frame_system::limits::BlockWeights =
 		frame_system::limits::BlockWeights::simple_max(frame_support::weights::Weight::from_ref_time(1024));
@@ -60,6 +82,7 @@ impl frame_system::Config for Test {
 	type Index = u64;
 	type BlockNumber = u64;
 	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
 	type Hash = H256;
 	type Hashing = ::sp_runtime::traits::BlakeTwo256;
 	type AccountId = u64;
@@ -107,16 +130,109 @@ impl DisabledValidators for MockDisabledValidators {
 	}
 }
 
+pub const BLOCK_AUTHOR: u64 = 42;
+
+pub struct HardcodedAuthor;
+
+impl FindAuthor<u64> for HardcodedAuthor {
+	fn find_author<'a, I>(_: I) -> Option<u64>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		Some(BLOCK_AUTHOR)
+	}
+}
+
+impl pallet_authorship::Config for Test {
+	type FindAuthor = HardcodedAuthor;
+	type UncleGenerations = ();
+	type FilterUncle = ();
+	type EventHandler = ();
+}
+
+parameter_types! {
+	/// The offences passed to the offence handler, with their reporters, slash fraction and
+	/// session.
+	pub static OnOffences: Vec<(Vec<u64>, u64, Perbill, SessionIndex)> = Vec::new();
+}
+
+pub struct RecordOffences;
+
+impl OnOffenceHandler<u64, u64, Weight> for RecordOffences {
+	fn on_offence(
+		offenders: &[OffenceDetails<u64, u64>],
+		slash_fraction: &[Perbill],
+		session: SessionIndex,
+		_disable_strategy: DisableStrategy,
+	) -> Weight {
+		OnOffences::mutate(|offences| {
+			for (details, fraction) in offenders.iter().zip(slash_fraction) {
+				offences.push((details.reporters.clone(), details.offender, *fraction, session));
+			}
+		});
+		Weight::zero()
+	}
+}
+
+impl pallet_offences::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type IdentificationTuple = u64;
+	type OnOffenceHandler = RecordOffences;
+}
+
+/// Proof that an authority key is owned by the validator with the same id.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct MockKeyOwnerProof {
+	pub owner: u64,
+	pub session: SessionIndex,
+	pub validator_count: ValidatorCount,
+}
+
+impl GetSessionNumber for MockKeyOwnerProof {
+	fn session(&self) -> SessionIndex {
+		self.session
+	}
+}
+
+impl GetValidatorCount for MockKeyOwnerProof {
+	fn validator_count(&self) -> ValidatorCount {
+		self.validator_count
+	}
+}
+
+/// Proves the ownership of the keys of the current authorities, in session 0.
+pub struct MockKeyOwnerProofSystem;
+
+impl KeyOwnerProofSystem<(KeyTypeId, UintAuthorityId)> for MockKeyOwnerProofSystem {
+	type Proof = MockKeyOwnerProof;
+	type IdentificationTuple = u64;
+
+	fn prove(key: (KeyTypeId, UintAuthorityId)) -> Option<Self::Proof> {
+		let authorities = Aura::authorities();
+		(key.0 == KEY_TYPE && authorities.contains(&key.1)).then(|| MockKeyOwnerProof {
+			owner: key.1 .0,
+			session: 0,
+			validator_count: authorities.len() as u32,
+		})
+	}
+
+	fn check_proof(key: (KeyTypeId, UintAuthorityId), proof: Self::Proof) -> Option<u64> {
+		Self::prove(key).filter(|valid| *valid == proof).map(|proof| proof.owner)
+	}
+}
+
 impl pallet_aura::Config for Test {
-	type AuthorityId = AuthorityId;
+	type AuthorityId = UintAuthorityId;
 	type DisabledValidators = MockDisabledValidators;
 	type MaxAuthorities = ConstU32<10>;
+	type HandleEquivocation =
+		pallet_aura::EquivocationHandler<MockKeyOwnerProofSystem, Offences, ConstU64<10>>;
 }
 
 pub fn new_test_ext(authorities: Vec<u64>) -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_aura::GenesisConfig::<Test> {
-		authorities: authorities.into_iter().map(|a| UintAuthorityId(a).to_public_key()).collect(),
+		authorities: authorities.into_iter().map(UintAuthorityId).collect(),
 	}
 	.assimilate_storage(&mut t)
 	.unwrap();
@@ -19,11 +19,35 @@
 
 #![cfg(test)]
 
-use crate::mock::{new_test_ext, Aura, MockDisabledValidators, System};
-use codec::Encode;
-use frame_support::traits::OnInitialize;
-use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
-use sp_runtime::{Digest, DigestItem};
+use crate::{
+	mock::{
+		new_test_ext, Aura, MockDisabledValidators, MockKeyOwnerProof, MockKeyOwnerProofSystem,
+		OnOffences, RuntimeCall, RuntimeOrigin, System, Test, BLOCK_AUTHOR,
+	},
+	Call, Error,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_err, assert_noop, assert_ok,
+	dispatch::Pays,
+	traits::{KeyOwnerProofSystem, OnInitialize},
+};
+use sp_consensus_aura::{
+	digests::CompatibleDigestItem, EquivocationProof, Slot, AURA_ENGINE_ID, KEY_TYPE,
+};
+use sp_core::{
+	offchain::{testing::TestTransactionPoolExt, TransactionPoolExt},
+	H256,
+};
+use sp_runtime::{
+	testing::{Header, TestXt, UintAuthorityId},
+	traits::{Header as _, ValidateUnsigned},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
+		TransactionValidityError, ValidTransaction,
+	},
+	Digest, DigestItem, Perbill, RuntimeAppPublic,
+};
 
 #[test]
 fn initial_values() {
@@ -54,3 +78,253 @@ fn disabled_validators_cannot_author_blocks() {
 		Aura::on_initialize(42);
 	});
 }
+
+fn sealed_header(author: &UintAuthorityId, slot: u64, extrinsics_root: H256) -> Header {
+	let mut header = Header::new(
+		1,
+		extrinsics_root,
+		Default::default(),
+		Default::default(),
+		Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, Slot::from(slot).encode())] },
+	);
+	let signature = author.sign(&header.hash()).unwrap();
+	header.digest_mut().push(DigestItem::aura_seal(signature));
+	header
+}
+
+#[test]
+fn check_equivocation_proof_works() {
+	let offender = UintAuthorityId(1);
+	let other = UintAuthorityId(2);
+	let proof = |first_header, second_header| EquivocationProof {
+		offender: offender.clone(),
+		slot: Slot::from(5),
+		first_header,
+		second_header,
+	};
+	let check = sp_consensus_aura::check_equivocation_proof::<Header, _>;
+
+	// two different headers for the same slot, signed by the offender
+	let first = sealed_header(&offender, 5, H256::repeat_byte(1));
+	let second = sealed_header(&offender, 5, H256::repeat_byte(2));
+	assert!(check(proof(first.clone(), second.clone())));
+
+	// the same header twice
+	assert!(!check(proof(first.clone(), first.clone())));
+
+	// headers for different slots
+	let later = sealed_header(&offender, 6, H256::repeat_byte(2));
+	assert!(!check(proof(first.clone(), later)));
+
+	// one header not signed by the offender
+	let foreign = sealed_header(&other, 5, H256::repeat_byte(2));
+	assert!(!check(proof(first, foreign)));
+}
+
+fn equivocation_proof(offender: u64, slot: u64) -> EquivocationProof<Header, UintAuthorityId> {
+	let offender = UintAuthorityId(offender);
+	EquivocationProof {
+		first_header: sealed_header(&offender, slot, H256::repeat_byte(1)),
+		second_header: sealed_header(&offender, slot, H256::repeat_byte(2)),
+		offender,
+		slot: Slot::from(slot),
+	}
+}
+
+fn key_owner_proof(offender: u64) -> MockKeyOwnerProof {
+	MockKeyOwnerProofSystem::prove((KEY_TYPE, UintAuthorityId(offender))).unwrap()
+}
+
+#[test]
+fn report_equivocation_works() {
+	new_test_ext(vec![0, 1, 2, 3]).execute_with(|| {
+		let post_info = Aura::report_equivocation(
+			RuntimeOrigin::signed(7),
+			Box::new(equivocation_proof(1, 5)),
+			key_owner_proof(1),
+		)
+		.unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+
+		// the offence is reported against the owner of the key, by the signer, with a slash
+		// fraction of (3 * 1 / 4)^2.
+		let fraction = Perbill::from_rational(3u32, 4u32).square();
+		assert_eq!(OnOffences::get(), vec![(vec![7], 1, fraction, 0)]);
+
+		// the same offence can't be reported twice
+		assert_noop!(
+			Aura::report_equivocation(
+				RuntimeOrigin::signed(7),
+				Box::new(equivocation_proof(1, 5)),
+				key_owner_proof(1),
+			),
+			Error::<Test>::DuplicateOffenceReport,
+		);
+
+		// but the offender can be reported for another slot
+		assert_ok!(Aura::report_equivocation(
+			RuntimeOrigin::signed(7),
+			Box::new(equivocation_proof(1, 6)),
+			key_owner_proof(1),
+		));
+		assert_eq!(OnOffences::get().len(), 2);
+	});
+}
+
+#[test]
+fn report_equivocation_unsigned_is_reported_by_the_block_author() {
+	new_test_ext(vec![0, 1, 2, 3]).execute_with(|| {
+		assert_noop!(
+			Aura::report_equivocation_unsigned(
+				RuntimeOrigin::signed(7),
+				Box::new(equivocation_proof(1, 5)),
+				key_owner_proof(1),
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+
+		assert_ok!(Aura::report_equivocation_unsigned(
+			RuntimeOrigin::none(),
+			Box::new(equivocation_proof(1, 5)),
+			key_owner_proof(1),
+		));
+
+		let fraction = Perbill::from_rational(3u32, 4u32).square();
+		assert_eq!(OnOffences::get(), vec![(vec![BLOCK_AUTHOR], 1, fraction, 0)]);
+	});
+}
+
+#[test]
+fn report_equivocation_invalid_proofs() {
+	new_test_ext(vec![0, 1, 2, 3]).execute_with(|| {
+		let report = |equivocation_proof, key_owner_proof| {
+			Aura::report_equivocation(
+				RuntimeOrigin::signed(7),
+				Box::new(equivocation_proof),
+				key_owner_proof,
+			)
+		};
+
+		// both headers are the same
+		let mut proof = equivocation_proof(1, 5);
+		proof.second_header = proof.first_header.clone();
+		assert_err!(report(proof, key_owner_proof(1)), Error::<Test>::InvalidEquivocationProof);
+
+		// the headers are signed by someone else than the offender
+		let mut proof = equivocation_proof(1, 5);
+		proof.offender = UintAuthorityId(2);
+		assert_err!(report(proof, key_owner_proof(2)), Error::<Test>::InvalidEquivocationProof);
+
+		// the key ownership proof is for another validator
+		assert_err!(
+			report(equivocation_proof(1, 5), key_owner_proof(2)),
+			Error::<Test>::InvalidKeyOwnershipProof,
+		);
+
+		// the key ownership proof claims another validator set
+		let mut owner_proof = key_owner_proof(1);
+		owner_proof.validator_count = 10;
+		assert_err!(
+			report(equivocation_proof(1, 5), owner_proof),
+			Error::<Test>::InvalidKeyOwnershipProof,
+		);
+
+		// the offender is not an authority
+		let mut owner_proof = key_owner_proof(1);
+		owner_proof.owner = 9;
+		assert_err!(
+			report(equivocation_proof(9, 5), owner_proof),
+			Error::<Test>::InvalidKeyOwnershipProof,
+		);
+
+		assert!(OnOffences::get().is_empty());
+	});
+}
+
+#[test]
+fn report_equivocation_validate_unsigned_prevents_duplicates() {
+	new_test_ext(vec![0, 1, 2, 3]).execute_with(|| {
+		let call = Call::report_equivocation_unsigned {
+			equivocation_proof: Box::new(equivocation_proof(1, 5)),
+			key_owner_proof: key_owner_proof(1),
+		};
+
+		// only local sources are accepted
+		assert_eq!(
+			<Aura as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into(),
+		);
+		assert_eq!(
+			<Aura as ValidateUnsigned>::validate_unsigned(TransactionSource::InBlock, &call),
+			<Aura as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &call),
+		);
+
+		let tx_tag = (UintAuthorityId(1), 5u64);
+		assert_eq!(
+			<Aura as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &call),
+			TransactionValidity::Ok(ValidTransaction {
+				priority: TransactionPriority::MAX,
+				requires: vec![],
+				provides: vec![("AuraEquivocation", tx_tag).encode()],
+				longevity: 10,
+				propagate: false,
+			})
+		);
+		assert_ok!(<Aura as ValidateUnsigned>::pre_dispatch(&call));
+
+		// an invalid key ownership proof is rejected
+		let bad_call = Call::report_equivocation_unsigned {
+			equivocation_proof: Box::new(equivocation_proof(1, 5)),
+			key_owner_proof: key_owner_proof(2),
+		};
+		assert_eq!(
+			<Aura as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &bad_call),
+			InvalidTransaction::BadProof.into(),
+		);
+		assert_err!(
+			<Aura as ValidateUnsigned>::pre_dispatch(&bad_call),
+			TransactionValidityError::from(InvalidTransaction::BadProof),
+		);
+
+		// once the offence is reported, the transaction is stale
+		assert_ok!(Aura::report_equivocation_unsigned(
+			RuntimeOrigin::none(),
+			Box::new(equivocation_proof(1, 5)),
+			key_owner_proof(1),
+		));
+		assert_eq!(
+			<Aura as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &call),
+			InvalidTransaction::Stale.into(),
+		);
+		assert_err!(
+			<Aura as ValidateUnsigned>::pre_dispatch(&call),
+			TransactionValidityError::from(InvalidTransaction::Stale),
+		);
+	});
+}
+
+#[test]
+fn submit_unsigned_equivocation_report_sends_the_transaction_to_the_pool() {
+	let mut ext = new_test_ext(vec![0, 1, 2, 3]);
+	let (pool, state) = TestTransactionPoolExt::new();
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		assert_eq!(
+			Aura::submit_unsigned_equivocation_report(equivocation_proof(1, 5), key_owner_proof(1)),
+			Some(()),
+		);
+
+		let transactions = &state.read().transactions;
+		assert_eq!(transactions.len(), 1);
+		let tx = TestXt::<RuntimeCall, ()>::decode(&mut &transactions[0][..]).unwrap();
+		assert_eq!(tx.signature, None);
+		assert_eq!(
+			tx.call,
+			RuntimeCall::Aura(Call::report_equivocation_unsigned {
+				equivocation_proof: Box::new(equivocation_proof(1, 5)),
+				key_owner_proof: key_owner_proof(1),
+			}),
+		);
+	});
+}
@@ -69,6 +69,14 @@
 //!   number. Multiple
 //! locks always operate over the same funds, so they "overlay" rather than "stack".
 //!
+//! - **Hold:** Some part of the reserved balance of an account which is set aside for a given
+//!   reason, `Config::HoldIdentifier`. Unlike anonymous reserves, funds on hold can only be
+//!   released or slashed for the reason they were held for. Holds are the replacement of reserves.
+//!
+//! - **Freeze:** An amount of an account's free balance which may not be withdrawn, for a given
+//!   `Config::FreezeIdentifier`. Freezes overlay each other and the locks. Freezes are the
+//!   replacement of locks.
+//!
 //! ### Implementations
 //!
 //! The Balances pallet provides implementations for the following traits. If these traits provide
@@ -81,6 +89,8 @@
 //! Functions for dealing with assets that can be reserved from an account.
 //! - [`LockableCurrency`](frame_support::traits::LockableCurrency): Functions for
 //! dealing with accounts that allow liquidity restrictions.
+//! - [`fungible::InspectHold`], [`fungible::MutateHold`], [`fungible::InspectFreeze`] and
+//! [`fungible::MutateFreeze`]: Functions for dealing with holds and freezes.
 //! - [`Imbalance`](frame_support::traits::Imbalance): Functions for handling
 //! imbalances between total issuance in the system and account balances. Must be used when a
 //! function creates new funds (e.g. a reward) or destroys some funds (e.g. a system fee).
@@ -143,6 +153,25 @@
 //! # fn main() {}
 //! ```
 //!
+//! ### Moving from reserves to holds
+//!
+//! A pallet can move from `ReservableCurrency` or `NamedReservableCurrency` to
+//! `fungible::MutateHold` without moving any funds: [`Pallet::hold_reserved`] and
+//! [`Pallet::named_reserve_to_hold`] account the funds it reserved to a hold, e.g. in a migration
+//! iterating over its deposits, after which it only uses the hold traits. A migration which is
+//! generic over the currency can instead unreserve each deposit and hold it again, as
+//! `pallet_preimage::migration::v2::MigrateToHolds` does.
+//!
+//! A pallet declares the reasons it holds or freezes funds for with `#[pallet::composite_enum]`,
+//! and `construct_runtime` aggregates them into `RuntimeHoldReason` and `RuntimeFreezeReason`.
+//! These are the `HoldIdentifier` and `FreezeIdentifier` of the runtime, with
+//! `VariantCountOf<RuntimeHoldReason>` and `VariantCountOf<RuntimeFreezeReason>` as `MaxHolds`
+//! and `MaxFreezes` so that an account can hold and freeze funds once per reason.
+//!
+//! Funds on hold are part of the reserved balance, but the `ReservableCurrency` and
+//! `NamedReservableCurrency` functions never unreserve, slash or repatriate them: held funds are
+//! only released, transferred or slashed for the reason they are held for.
+//!
 //! ## Genesis config
 //!
 //! The Balances pallet depends on the [`GenesisConfig`].
@@ -176,7 +205,7 @@ use frame_support::{
 		Get, Imbalance, LockIdentifier, LockableCurrency, NamedReservableCurrency, OnUnbalanced,
 		ReservableCurrency, SignedImbalance, StoredMap, TryDrop, WithdrawReasons,
 	},
-	WeakBoundedVec,
+	BoundedVec, WeakBoundedVec,
 };
 use frame_system as system;
 use scale_info::TypeInfo;
@@ -243,6 +272,22 @@ pub mod pallet {
 
 		/// The id type for named reserves.
 		type ReserveIdentifier: Parameter + Member + MaxEncodedLen + Ord + Copy;
+
+		/// The reason for which funds are placed on hold, usually an enum aggregating the reasons
+		/// of all the pallets of the runtime.
+		type HoldIdentifier: Parameter + Member + MaxEncodedLen + Ord + Copy;
+
+		/// The id type for freezes, usually an enum aggregating the freezes of all the pallets of
+		/// the runtime.
+		type FreezeIdentifier: Parameter + Member + MaxEncodedLen + Ord + Copy;
+
+		/// The maximum number of holds that can exist on an account.
+		#[pallet::constant]
+		type MaxHolds: Get<u32>;
+
+		/// The maximum number of freezes that can exist on an account.
+		#[pallet::constant]
+		type MaxFreezes: Get<u32>;
 	}
 
 	#[pallet::pallet]
@@ -489,6 +534,10 @@ pub mod pallet {
 		DeadAccount,
 		/// Number of named reserves exceed MaxReserves
 		TooManyReserves,
+		/// Number of holds exceed MaxHolds
+		TooManyHolds,
+		/// Number of freezes exceed MaxFreezes
+		TooManyFreezes,
 	}
 
 	/// The total units issued in the system.
@@ -548,6 +597,28 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// Holds on account balances.
+	///
+	/// The held funds are part of the reserved balance of the account.
+	#[pallet::storage]
+	pub type Holds<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<IdAmount<T::HoldIdentifier, T::Balance>, T::MaxHolds>,
+		ValueQuery,
+	>;
+
+	/// Freezes on account balances.
+	#[pallet::storage]
+	pub type Freezes<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<IdAmount<T::FreezeIdentifier, T::Balance>, T::MaxFreezes>,
+		ValueQuery,
+	>;
+
 	/// Storage version of the pallet.
 	///
 	/// This is set to v2.0.0 for new networks.
@@ -674,6 +745,15 @@ pub struct ReserveData<ReserveIdentifier, Balance> {
 	pub amount: Balance,
 }
 
+/// An amount associated with an identifier, used for holds and freezes.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct IdAmount<Id, Balance> {
+	/// The identifier of the hold or freeze.
+	pub id: Id,
+	/// The amount held or frozen.
+	pub amount: Balance,
+}
+
 /// All balance information for an account.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, MaxEncodedLen, TypeInfo)]
 pub struct AccountData<Balance> {
@@ -960,10 +1040,43 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				A runtime configuration adjustment may be needed."
 			);
 		}
+		Self::update_frozen(who, locks, &Freezes::<T, I>::get(who));
+
+		let existed = Locks::<T, I>::contains_key(who);
+		if locks.is_empty() {
+			Locks::<T, I>::remove(who);
+			if existed {
+				// TODO: use Locks::<T, I>::hashed_key
+				// https://github.com/paritytech/substrate/issues/4969
+				system::Pallet::<T>::dec_consumers(who);
+			}
+		} else {
+			Locks::<T, I>::insert(who, bounded_locks);
+			if !existed && system::Pallet::<T>::inc_consumers_without_limit(who).is_err() {
+				// No providers for the locks. This is impossible under normal circumstances
+				// since the funds that are under the lock will themselves be stored in the
+				// account and therefore will need a reference.
+				log::warn!(
+					target: "runtime::balances",
+					"Warning: Attempt to introduce lock consumer reference, yet no providers. \
+					This is unexpected but should be safe."
+				);
+			}
+		}
+	}
+
+	/// Update the frozen balances of `who`, given its locks and freezes.
+	fn update_frozen(
+		who: &T::AccountId,
+		locks: &[BalanceLock<T::Balance>],
+		freezes: &[IdAmount<T::FreezeIdentifier, T::Balance>],
+	) {
+		// Freezes apply to withdrawals for any reason.
+		let frozen = freezes.iter().map(|f| f.amount).max().unwrap_or_else(Zero::zero);
 		// No way this can fail since we do not alter the existential balances.
 		let res = Self::mutate_account(who, |b| {
-			b.misc_frozen = Zero::zero();
-			b.fee_frozen = Zero::zero();
+			b.misc_frozen = frozen;
+			b.fee_frozen = frozen;
 			for l in locks.iter() {
 				if l.reasons == Reasons::All || l.reasons == Reasons::Misc {
 					b.misc_frozen = b.misc_frozen.max(l.amount);
@@ -974,41 +1087,182 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			}
 		});
 		debug_assert!(res.is_ok());
+	}
 
-		let existed = Locks::<T, I>::contains_key(who);
-		if locks.is_empty() {
-			Locks::<T, I>::remove(who);
+	/// Update the account entry for `who`, given the freezes.
+	fn update_freezes(
+		who: &T::AccountId,
+		freezes: BoundedVec<IdAmount<T::FreezeIdentifier, T::Balance>, T::MaxFreezes>,
+	) {
+		Self::update_frozen(who, &Locks::<T, I>::get(who), &freezes);
+
+		let existed = Freezes::<T, I>::contains_key(who);
+		if freezes.is_empty() {
+			Freezes::<T, I>::remove(who);
 			if existed {
-				// TODO: use Locks::<T, I>::hashed_key
-				// https://github.com/paritytech/substrate/issues/4969
 				system::Pallet::<T>::dec_consumers(who);
 			}
 		} else {
-			Locks::<T, I>::insert(who, bounded_locks);
+			Freezes::<T, I>::insert(who, freezes);
 			if !existed && system::Pallet::<T>::inc_consumers_without_limit(who).is_err() {
-				// No providers for the locks. This is impossible under normal circumstances
-				// since the funds that are under the lock will themselves be stored in the
-				// account and therefore will need a reference.
+				// No providers for the freezes, see `update_locks`.
 				log::warn!(
 					target: "runtime::balances",
-					"Warning: Attempt to introduce lock consumer reference, yet no providers. \
+					"Warning: Attempt to introduce freeze consumer reference, yet no providers. \
 					This is unexpected but should be safe."
 				);
 			}
 		}
 	}
 
+	/// Update the holds of `who`.
+	///
+	/// NOTE: The held funds must already be accounted for in the reserved balance of `who`.
+	fn update_holds(
+		who: &T::AccountId,
+		holds: BoundedVec<IdAmount<T::HoldIdentifier, T::Balance>, T::MaxHolds>,
+	) {
+		let existed = Holds::<T, I>::contains_key(who);
+		if holds.is_empty() {
+			Holds::<T, I>::remove(who);
+			if existed {
+				system::Pallet::<T>::dec_consumers(who);
+			}
+		} else {
+			Holds::<T, I>::insert(who, holds);
+			if !existed && system::Pallet::<T>::inc_consumers_without_limit(who).is_err() {
+				// No providers for the holds, see `update_locks`.
+				log::warn!(
+					target: "runtime::balances",
+					"Warning: Attempt to introduce hold consumer reference, yet no providers. \
+					This is unexpected but should be safe."
+				);
+			}
+		}
+	}
+
+	/// Add `amount` to the hold for `reason` in `holds`.
+	fn increase_hold(
+		holds: &mut BoundedVec<IdAmount<T::HoldIdentifier, T::Balance>, T::MaxHolds>,
+		reason: &T::HoldIdentifier,
+		amount: T::Balance,
+	) -> DispatchResult {
+		match holds.binary_search_by_key(reason, |h| h.id) {
+			Ok(index) =>
+				holds[index].amount =
+					holds[index].amount.checked_add(&amount).ok_or(ArithmeticError::Overflow)?,
+			Err(index) => holds
+				.try_insert(index, IdAmount { id: *reason, amount })
+				.map_err(|_| Error::<T, I>::TooManyHolds)?,
+		}
+		Ok(())
+	}
+
+	/// Remove `amount` from the hold for `reason` of `who`, dropping the hold once it is empty.
+	///
+	/// NOTE: LOW-LEVEL: This does not touch the reserved balance of `who`.
+	fn decrease_hold(who: &T::AccountId, reason: &T::HoldIdentifier, amount: T::Balance) {
+		let mut holds = Holds::<T, I>::get(who);
+		if let Ok(index) = holds.binary_search_by_key(reason, |h| h.id) {
+			holds[index].amount = holds[index].amount.defensive_saturating_sub(amount);
+			if holds[index].amount.is_zero() {
+				holds.remove(index);
+			}
+			Self::update_holds(who, holds);
+		}
+	}
+
+	/// The funds of `who` on hold, for any reason.
+	fn total_held(who: &T::AccountId) -> T::Balance {
+		Holds::<T, I>::get(who)
+			.iter()
+			.fold(T::Balance::zero(), |acc, h| acc.saturating_add(h.amount))
+	}
+
+	/// The part of the reserved balance of `who` which is not on hold.
+	///
+	/// Only these funds can be unreserved, slashed or repatriated through `ReservableCurrency`
+	/// and `NamedReservableCurrency`.
+	fn unheld_reserved(who: &T::AccountId) -> T::Balance {
+		Self::reserved_balance(who).saturating_sub(Self::total_held(who))
+	}
+
+	/// Place `amount` of the anonymous reserves of `who` on hold for `reason`.
+	///
+	/// This allows pallets to migrate from `ReservableCurrency` to `fungible::MutateHold` without
+	/// moving any funds: the reserves they made are converted into holds, after which they are
+	/// released through `fungible::MutateHold`. Funds which are on hold or in a named reserve
+	/// can't be placed on hold this way.
+	pub fn hold_reserved(
+		reason: &T::HoldIdentifier,
+		who: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(())
+		}
+		let named = Reserves::<T, I>::get(who)
+			.iter()
+			.fold(T::Balance::zero(), |acc, r| acc.saturating_add(r.amount));
+		let anonymous = Self::unheld_reserved(who).saturating_sub(named);
+		ensure!(amount <= anonymous, Error::<T, I>::InsufficientBalance);
+		Self::do_hold_reserved(reason, who, amount)
+	}
+
+	/// Place `amount` of the reserved balance of `who` on hold for `reason`.
+	///
+	/// NOTE: LOW-LEVEL: The caller must ensure `amount` is not accounted for elsewhere.
+	fn do_hold_reserved(
+		reason: &T::HoldIdentifier,
+		who: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		let mut holds = Holds::<T, I>::get(who);
+		Self::increase_hold(&mut holds, reason, amount)?;
+		Self::update_holds(who, holds);
+		Ok(())
+	}
+
+	/// Convert the named reserve `id` of `who` into a hold for `reason`, returning the amount
+	/// placed on hold.
+	///
+	/// This allows pallets to migrate from `NamedReservableCurrency` to `fungible::MutateHold`
+	/// without moving any funds. It is a no-op if there is no such named reserve.
+	pub fn named_reserve_to_hold(
+		id: &T::ReserveIdentifier,
+		reason: &T::HoldIdentifier,
+		who: &T::AccountId,
+	) -> Result<T::Balance, DispatchError> {
+		let mut reserves = Reserves::<T, I>::get(who);
+		let amount = match reserves.binary_search_by_key(id, |data| data.id) {
+			Ok(index) => reserves.remove(index).amount,
+			Err(_) => return Ok(Zero::zero()),
+		};
+		if !amount.is_zero() {
+			Self::do_hold_reserved(reason, who, amount)?;
+		}
+		if reserves.is_empty() {
+			Reserves::<T, I>::remove(who);
+		} else {
+			Reserves::<T, I>::insert(who, reserves);
+		}
+		Ok(amount)
+	}
+
 	/// Move the reserved balance of one account into the balance of another, according to `status`.
 	///
 	/// Is a no-op if:
 	/// - the value to be moved is zero; or
 	/// - the `slashed` id equal to `beneficiary` and the `status` is `Reserved`.
 	///
+	/// The first `kept` of the reserved balance of `slashed` is never moved.
+	///
 	/// NOTE: returns actual amount of transferred value in `Ok` case.
 	fn do_transfer_reserved(
 		slashed: &T::AccountId,
 		beneficiary: &T::AccountId,
 		value: T::Balance,
+		kept: T::Balance,
 		best_effort: bool,
 		status: Status,
 	) -> Result<T::Balance, DispatchError> {
@@ -1030,7 +1284,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				Self::try_mutate_account_with_dust(
 					slashed,
 					|from_account, _| -> Result<T::Balance, DispatchError> {
-						let actual = cmp::min(from_account.reserved, value);
+						let actual = cmp::min(from_account.reserved.saturating_sub(kept), value);
 						ensure!(best_effort || actual == value, Error::<T, I>::InsufficientBalance);
 						match status {
 							Status::Free =>
@@ -1170,10 +1424,23 @@ impl<T: Config<I>, I: 'static> fungible::Unbalanced<T::AccountId> for Pallet<T,
 }
 
 impl<T: Config<I>, I: 'static> fungible::InspectHold<T::AccountId> for Pallet<T, I> {
-	fn balance_on_hold(who: &T::AccountId) -> T::Balance {
+	type Reason = T::HoldIdentifier;
+
+	fn total_balance_on_hold(who: &T::AccountId) -> T::Balance {
+		// Also includes any funds reserved through `ReservableCurrency`.
 		Self::account(who).reserved
 	}
-	fn can_hold(who: &T::AccountId, amount: T::Balance) -> bool {
+	fn balance_on_hold(reason: &Self::Reason, who: &T::AccountId) -> T::Balance {
+		Holds::<T, I>::get(who)
+			.iter()
+			.find(|h| &h.id == reason)
+			.map_or_else(Zero::zero, |h| h.amount)
+	}
+	fn can_hold(reason: &Self::Reason, who: &T::AccountId, amount: T::Balance) -> bool {
+		let holds = Holds::<T, I>::get(who);
+		if !holds.iter().any(|h| &h.id == reason) && holds.len() as u32 >= T::MaxHolds::get() {
+			return false
+		}
 		let a = Self::account(who);
 		let min_balance = T::ExistentialDeposit::get().max(a.frozen(Reasons::All));
 		if a.reserved.checked_add(&amount).is_none() {
@@ -1189,18 +1456,22 @@ impl<T: Config<I>, I: 'static> fungible::InspectHold<T::AccountId> for Pallet<T,
 	}
 }
 impl<T: Config<I>, I: 'static> fungible::MutateHold<T::AccountId> for Pallet<T, I> {
-	fn hold(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+	fn hold(reason: &Self::Reason, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
 		if amount.is_zero() {
 			return Ok(())
 		}
 		ensure!(Self::can_reserve(who, amount), Error::<T, I>::InsufficientBalance);
+		let mut holds = Holds::<T, I>::get(who);
+		Self::increase_hold(&mut holds, reason, amount)?;
 		Self::mutate_account(who, |a| {
 			a.free -= amount;
 			a.reserved += amount;
 		})?;
+		Self::update_holds(who, holds);
 		Ok(())
 	}
 	fn release(
+		reason: &Self::Reason,
 		who: &T::AccountId,
 		amount: Self::Balance,
 		best_effort: bool,
@@ -1208,26 +1479,103 @@ impl<T: Config<I>, I: 'static> fungible::MutateHold<T::AccountId> for Pallet<T,
 		if amount.is_zero() {
 			return Ok(amount)
 		}
-		// Done on a best-effort basis.
-		Self::try_mutate_account(who, |a, _| {
-			let new_free = a.free.saturating_add(amount.min(a.reserved));
+		let held = <Self as fungible::InspectHold<_>>::balance_on_hold(reason, who);
+		let actual = Self::try_mutate_account(who, |a, _| {
+			let new_free = a.free.saturating_add(amount.min(held).min(a.reserved));
 			let actual = new_free - a.free;
 			ensure!(best_effort || actual == amount, Error::<T, I>::InsufficientBalance);
-			// ^^^ Guaranteed to be <= amount and <= a.reserved
+			// ^^^ Guaranteed to be <= amount, <= held and <= a.reserved
 			a.free = new_free;
 			a.reserved = a.reserved.saturating_sub(actual);
-			Ok(actual)
-		})
+			Ok::<_, DispatchError>(actual)
+		})?;
+		Self::decrease_hold(who, reason, actual);
+		Ok(actual)
 	}
 	fn transfer_held(
+		reason: &Self::Reason,
 		source: &T::AccountId,
 		dest: &T::AccountId,
 		amount: Self::Balance,
 		best_effort: bool,
 		on_hold: bool,
 	) -> Result<Self::Balance, DispatchError> {
+		let held = <Self as fungible::InspectHold<_>>::balance_on_hold(reason, source);
+		ensure!(best_effort || amount <= held, Error::<T, I>::InsufficientBalance);
+		let amount = amount.min(held);
+		if amount.is_zero() {
+			return Ok(amount)
+		}
+		if source == dest {
+			return if on_hold {
+				Ok(amount)
+			} else {
+				<Self as fungible::MutateHold<_>>::release(reason, source, amount, false)
+			}
+		}
+
+		let mut dest_holds = Holds::<T, I>::get(dest);
+		if on_hold {
+			Self::increase_hold(&mut dest_holds, reason, amount)?;
+		}
 		let status = if on_hold { Status::Reserved } else { Status::Free };
-		Self::do_transfer_reserved(source, dest, amount, best_effort, status)
+		let actual = Self::do_transfer_reserved(source, dest, amount, Zero::zero(), false, status)?;
+		Self::decrease_hold(source, reason, actual);
+		if on_hold {
+			Self::update_holds(dest, dest_holds);
+		}
+		Ok(actual)
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::InspectFreeze<T::AccountId> for Pallet<T, I> {
+	type Id = T::FreezeIdentifier;
+
+	fn balance_frozen(id: &Self::Id, who: &T::AccountId) -> Self::Balance {
+		Freezes::<T, I>::get(who)
+			.iter()
+			.find(|f| &f.id == id)
+			.map_or_else(Zero::zero, |f| f.amount)
+	}
+	fn balance_freezable(who: &T::AccountId) -> Self::Balance {
+		// Freezes only restrict the free balance.
+		Self::account(who).free
+	}
+	fn can_freeze(id: &Self::Id, who: &T::AccountId) -> bool {
+		let freezes = Freezes::<T, I>::get(who);
+		freezes.iter().any(|f| &f.id == id) || (freezes.len() as u32) < T::MaxFreezes::get()
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::MutateFreeze<T::AccountId> for Pallet<T, I> {
+	fn set_freeze(id: &Self::Id, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		if amount.is_zero() {
+			return <Self as fungible::MutateFreeze<_>>::thaw(id, who)
+		}
+		let mut freezes = Freezes::<T, I>::get(who);
+		match freezes.binary_search_by_key(id, |f| f.id) {
+			Ok(index) => freezes[index].amount = amount,
+			Err(index) => freezes
+				.try_insert(index, IdAmount { id: *id, amount })
+				.map_err(|_| Error::<T, I>::TooManyFreezes)?,
+		}
+		Self::update_freezes(who, freezes);
+		Ok(())
+	}
+	fn extend_freeze(id: &Self::Id, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		let current = <Self as fungible::InspectFreeze<_>>::balance_frozen(id, who);
+		if amount <= current {
+			return Ok(())
+		}
+		<Self as fungible::MutateFreeze<_>>::set_freeze(id, who, amount)
+	}
+	fn thaw(id: &Self::Id, who: &T::AccountId) -> DispatchResult {
+		let mut freezes = Freezes::<T, I>::get(who);
+		if let Ok(index) = freezes.binary_search_by_key(id, |f| f.id) {
+			freezes.remove(index);
+			Self::update_freezes(who, freezes);
+		}
+		Ok(())
 	}
 }
 
@@ -1553,8 +1901,8 @@ where
 	///
 	/// Is a no-op if `value` to be slashed is zero or the account does not exist.
 	///
-	/// NOTE: `slash()` prefers free balance, but assumes that reserve balance can be drawn
-	/// from in extreme circumstances. `can_slash()` should be used prior to `slash()` to avoid
+	/// NOTE: `slash()` prefers free balance, but assumes that reserve balance which is not on hold
+	/// can be drawn from in extreme circumstances. `can_slash()` should be used prior to `slash()` to avoid
 	/// having to draw from reserved funds, however we err on the side of punishment if things are
 	/// inconsistent or `can_slash` wasn't used appropriately.
 	fn slash(who: &T::AccountId, value: Self::Balance) -> (Self::NegativeImbalance, Self::Balance) {
@@ -1564,6 +1912,8 @@ where
 		if Self::total_balance(who).is_zero() {
 			return (NegativeImbalance::zero(), value)
 		}
+		// Funds on hold are only slashed through `fungible::BalancedHold`.
+		let held = Self::total_held(who);
 
 		for attempt in 0..2 {
 			match Self::try_mutate_account(
@@ -1590,7 +1940,8 @@ where
 
 					if !remaining_slash.is_zero() {
 						// If we have remaining slash, take it from reserved balance.
-						let reserved_slash = cmp::min(account.reserved, remaining_slash);
+						let reserved_slash =
+							cmp::min(account.reserved.saturating_sub(held), remaining_slash);
 						account.reserved -= reserved_slash; // Safe because of above check
 						Ok((
 							NegativeImbalance::new(free_slash + reserved_slash),
@@ -1793,7 +2144,8 @@ where
 
 	/// Unreserve some funds, returning any amount that was unable to be unreserved.
 	///
-	/// Is a no-op if the value to be unreserved is zero or the account does not exist.
+	/// Is a no-op if the value to be unreserved is zero or the account does not exist. Funds on
+	/// hold are never unreserved.
 	///
 	/// NOTE: returns amount value which wasn't successfully unreserved.
 	fn unreserve(who: &T::AccountId, value: Self::Balance) -> Self::Balance {
@@ -1803,9 +2155,10 @@ where
 		if Self::total_balance(who).is_zero() {
 			return value
 		}
+		let unheld = Self::unheld_reserved(who);
 
 		let actual = match Self::mutate_account(who, |account| {
-			let actual = cmp::min(account.reserved, value);
+			let actual = cmp::min(unheld, value);
 			account.reserved -= actual;
 			// defensive only: this can never fail since total issuance which is at least
 			// free+reserved fits into the same data type.
@@ -1828,7 +2181,8 @@ where
 	/// Slash from reserved balance, returning the negative imbalance created,
 	/// and any amount that was unable to be slashed.
 	///
-	/// Is a no-op if the value to be slashed is zero or the account does not exist.
+	/// Is a no-op if the value to be slashed is zero or the account does not exist. Funds on hold
+	/// are never slashed.
 	fn slash_reserved(
 		who: &T::AccountId,
 		value: Self::Balance,
@@ -1842,6 +2196,7 @@ where
 
 		// NOTE: `mutate_account` may fail if it attempts to reduce the balance to the point that an
 		//   account is attempted to be illegally destroyed.
+		let unheld = Self::unheld_reserved(who);
 
 		for attempt in 0..2 {
 			match Self::mutate_account(who, |account| {
@@ -1855,7 +2210,7 @@ where
 					),
 				};
 
-				let actual = cmp::min(account.reserved, best_value);
+				let actual = cmp::min(unheld, best_value);
 				account.reserved -= actual;
 
 				// underflow should never happen, but it if does, there's nothing to be done here.
@@ -1881,13 +2236,16 @@ where
 	/// Is a no-op if:
 	/// - the value to be moved is zero; or
 	/// - the `slashed` id equal to `beneficiary` and the `status` is `Reserved`.
+	///
+	/// Funds on hold are never moved.
 	fn repatriate_reserved(
 		slashed: &T::AccountId,
 		beneficiary: &T::AccountId,
 		value: Self::Balance,
 		status: Status,
 	) -> Result<Self::Balance, DispatchError> {
-		let actual = Self::do_transfer_reserved(slashed, beneficiary, value, true, status)?;
+		let held = Self::total_held(slashed);
+		let actual = Self::do_transfer_reserved(slashed, beneficiary, value, held, true, status)?;
 		Ok(value.saturating_sub(actual))
 	}
 }
@@ -19,6 +19,30 @@
 
 #![cfg(test)]
 
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// The hold and freeze identifiers of the test runtimes.
+#[derive(
+	Encode,
+	Decode,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	RuntimeDebug,
+	MaxEncodedLen,
+	TypeInfo,
+)]
+pub enum TestId {
+	Foo,
+	Bar,
+	Baz,
+}
+
 #[macro_export]
 macro_rules! decl_tests {
 	($test:ty, $ext_builder:ty, $existential_deposit:expr) => {
@@ -1456,5 +1480,267 @@ macro_rules! decl_tests {
 				);
 			});
 		}
+
+		#[test]
+		fn holds_should_work() {
+			use fungible::{InspectHold, MutateHold};
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				assert_ok!(Balances::hold(&TestId::Foo, &1, 3));
+				assert_ok!(Balances::hold(&TestId::Bar, &1, 2));
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 3);
+				assert_eq!(Balances::balance_on_hold(&TestId::Bar, &1), 2);
+				assert_eq!(Balances::total_balance_on_hold(&1), 5);
+				assert_eq!(Balances::free_balance(1), 5);
+				assert_eq!(Balances::reserved_balance(1), 5);
+				assert_eq!(System::consumers(&1), 1);
+
+				// Only `MaxHolds` different holds may exist.
+				assert!(!Balances::can_hold(&TestId::Baz, &1, 1));
+				assert_noop!(
+					Balances::hold(&TestId::Baz, &1, 1),
+					Error::<$test, _>::TooManyHolds
+				);
+				assert_ok!(Balances::hold(&TestId::Foo, &1, 1));
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 4);
+
+				// Funds are only released from the given hold.
+				assert_noop!(
+					Balances::release(&TestId::Foo, &1, 5, false),
+					Error::<$test, _>::InsufficientBalance
+				);
+				assert_eq!(Balances::release(&TestId::Foo, &1, 5, true), Ok(4));
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 0);
+				assert_eq!(Balances::balance_on_hold(&TestId::Bar, &1), 2);
+				assert_eq!(Balances::free_balance(1), 8);
+
+				// Anonymous reserves are not released through holds.
+				assert_ok!(Balances::reserve(&1, 3));
+				assert_eq!(Balances::release(&TestId::Bar, &1, 5, true), Ok(2));
+				assert_eq!(Balances::reserved_balance(1), 3);
+				assert!(!Holds::<$test>::contains_key(&1));
+				assert_eq!(System::consumers(&1), 0);
+			});
+		}
+
+		#[test]
+		fn transfer_held_should_work() {
+			use fungible::{InspectHold, MutateHold};
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				assert_ok!(Balances::hold(&TestId::Foo, &1, 5));
+
+				// Stays on hold in the destination account.
+				assert_eq!(
+					Balances::transfer_held(&TestId::Foo, &1, &2, 2, false, true),
+					Ok(2)
+				);
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 3);
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &2), 2);
+				assert_eq!(Balances::reserved_balance(2), 2);
+				assert_eq!(Balances::free_balance(2), 20);
+
+				// Becomes free in the destination account.
+				assert_noop!(
+					Balances::transfer_held(&TestId::Foo, &1, &2, 4, false, false),
+					Error::<$test, _>::InsufficientBalance
+				);
+				assert_eq!(
+					Balances::transfer_held(&TestId::Foo, &1, &2, 4, true, false),
+					Ok(3)
+				);
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 0);
+				assert_eq!(Balances::reserved_balance(1), 0);
+				assert_eq!(Balances::free_balance(1), 5);
+				assert_eq!(Balances::free_balance(2), 23);
+				assert!(!Holds::<$test>::contains_key(&1));
+			});
+		}
+
+		#[test]
+		fn slash_held_should_work() {
+			use fungible::{BalancedHold, InspectHold, MutateHold};
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				assert_ok!(Balances::hold(&TestId::Foo, &1, 5));
+				let issuance = Balances::total_issuance();
+
+				let (credit, missing) = Balances::slash_held(&TestId::Foo, &1, 7);
+				assert_eq!(credit.peek(), 5);
+				assert_eq!(missing, 2);
+				drop(credit);
+
+				assert_eq!(Balances::total_issuance(), issuance - 5);
+				assert_eq!(Balances::free_balance(1), 5);
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 0);
+			});
+		}
+
+		#[test]
+		fn freezes_should_work() {
+			use fungible::{InspectFreeze, MutateFreeze};
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				assert_ok!(Balances::set_freeze(&TestId::Foo, &1, 5));
+				assert_eq!(Balances::balance_frozen(&TestId::Foo, &1), 5);
+				assert_eq!(System::consumers(&1), 1);
+				assert_noop!(
+					<Balances as Currency<_>>::transfer(&1, &2, 6, AllowDeath),
+					Error::<$test, _>::LiquidityRestrictions
+				);
+
+				// Extending never lowers a freeze.
+				assert_ok!(Balances::extend_freeze(&TestId::Foo, &1, 3));
+				assert_eq!(Balances::balance_frozen(&TestId::Foo, &1), 5);
+				assert_ok!(Balances::extend_freeze(&TestId::Foo, &1, 7));
+				assert_eq!(Balances::balance_frozen(&TestId::Foo, &1), 7);
+
+				// Freezes overlap.
+				assert_ok!(Balances::set_freeze(&TestId::Bar, &1, 2));
+				assert!(!Balances::can_freeze(&TestId::Baz, &1));
+				assert_noop!(
+					Balances::set_freeze(&TestId::Baz, &1, 1),
+					Error::<$test, _>::TooManyFreezes
+				);
+				assert_noop!(
+					<Balances as Currency<_>>::transfer(&1, &2, 4, AllowDeath),
+					Error::<$test, _>::LiquidityRestrictions
+				);
+
+				// Freezes and locks overlap too.
+				Balances::set_lock(ID_1, &1, 9, WithdrawReasons::all());
+				assert_noop!(
+					<Balances as Currency<_>>::transfer(&1, &2, 2, AllowDeath),
+					Error::<$test, _>::LiquidityRestrictions
+				);
+				Balances::remove_lock(ID_1, &1);
+				assert_noop!(
+					<Balances as Currency<_>>::transfer(&1, &2, 4, AllowDeath),
+					Error::<$test, _>::LiquidityRestrictions
+				);
+
+				assert_ok!(Balances::thaw(&TestId::Foo, &1));
+				assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 8, AllowDeath));
+
+				// A zero freeze removes the freeze.
+				assert_ok!(Balances::set_freeze(&TestId::Bar, &1, 0));
+				assert!(!Freezes::<$test>::contains_key(&1));
+				assert_eq!(System::consumers(&1), 0);
+				assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 2, AllowDeath));
+			});
+		}
+
+		#[test]
+		fn reserves_can_be_converted_into_holds() {
+			use fungible::{InspectHold, MutateHold};
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				let id = [1u8; 8];
+				assert_ok!(Balances::reserve_named(&id, &1, 3));
+				assert_ok!(Balances::reserve(&1, 2));
+
+				assert_eq!(Balances::named_reserve_to_hold(&id, &TestId::Foo, &1), Ok(3));
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 0);
+				assert!(!Reserves::<$test>::contains_key(&1));
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 3);
+				assert_eq!(Balances::reserved_balance(1), 5);
+
+				// Only the reserved funds which are not on hold yet can be placed on hold.
+				assert_noop!(
+					Balances::hold_reserved(&TestId::Bar, &1, 3),
+					Error::<$test, _>::InsufficientBalance
+				);
+				assert_ok!(Balances::hold_reserved(&TestId::Bar, &1, 2));
+				assert_eq!(Balances::total_balance_on_hold(&1), 5);
+
+				assert_eq!(Balances::release(&TestId::Foo, &1, 3, false), Ok(3));
+				assert_eq!(Balances::release(&TestId::Bar, &1, 2, false), Ok(2));
+				assert_eq!(Balances::free_balance(1), 10);
+				assert_eq!(Balances::reserved_balance(1), 0);
+			});
+		}
+
+		#[test]
+		fn named_reserves_cannot_be_placed_on_hold_as_anonymous_reserves() {
+			use fungible::InspectHold;
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				let id = [1u8; 8];
+				assert_ok!(Balances::reserve_named(&id, &1, 3));
+				assert_ok!(Balances::reserve(&1, 2));
+
+				assert_noop!(
+					Balances::hold_reserved(&TestId::Foo, &1, 3),
+					Error::<$test, _>::InsufficientBalance
+				);
+				assert_ok!(Balances::hold_reserved(&TestId::Foo, &1, 2));
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 2);
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 3);
+			});
+		}
+
+		#[test]
+		fn unreserve_after_hold_leaves_held_funds() {
+			use fungible::{InspectHold, MutateHold};
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				let id = [1u8; 8];
+				assert_ok!(Balances::hold(&TestId::Foo, &1, 4));
+				assert_ok!(Balances::reserve(&1, 1));
+
+				assert_eq!(Balances::unreserve(&1, 10), 9);
+				assert_eq!(Balances::reserved_balance(1), 4);
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 4);
+
+				assert_ok!(Balances::reserve_named(&id, &1, 2));
+				assert_eq!(Balances::unreserve_named(&id, &1, 10), 8);
+				assert_eq!(Balances::reserved_balance(1), 4);
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 4);
+
+				// The held funds are still there to be released.
+				assert_eq!(Balances::release(&TestId::Foo, &1, 4, false), Ok(4));
+				assert_eq!(Balances::reserved_balance(1), 0);
+				assert_eq!(Balances::free_balance(1), 10);
+			});
+		}
+
+		#[test]
+		fn slash_after_hold_leaves_held_funds() {
+			use fungible::{InspectHold, MutateHold};
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				assert_ok!(Balances::hold(&TestId::Foo, &1, 4));
+				assert_ok!(Balances::reserve(&1, 2));
+
+				assert_eq!(Balances::slash_reserved(&1, 10).1, 8);
+				assert_eq!(Balances::reserved_balance(1), 4);
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 4);
+
+				// `slash` takes the free balance, then the reserved funds which are not on hold.
+				assert_ok!(Balances::reserve(&1, 1));
+				assert_eq!(Balances::slash(&1, 10).1, 6);
+				assert_eq!(Balances::free_balance(1), 0);
+				assert_eq!(Balances::reserved_balance(1), 4);
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 4);
+
+				assert_eq!(Balances::release(&TestId::Foo, &1, 4, false), Ok(4));
+				assert_eq!(Balances::free_balance(1), 4);
+			});
+		}
+
+		#[test]
+		fn repatriate_reserved_after_hold_leaves_held_funds() {
+			use fungible::{InspectHold, MutateHold};
+
+			<$ext_builder>::default().existential_deposit(1).monied(true).build().execute_with(|| {
+				assert_ok!(Balances::hold(&TestId::Foo, &1, 4));
+				assert_ok!(Balances::reserve(&1, 1));
+
+				assert_eq!(Balances::repatriate_reserved(&1, &2, 5, Status::Free), Ok(4));
+				assert_eq!(Balances::reserved_balance(1), 4);
+				assert_eq!(Balances::free_balance(2), 21);
+				assert_eq!(Balances::balance_on_hold(&TestId::Foo, &1), 4);
+			});
+		}
 	}
 }
@@ -19,7 +19,7 @@
 
 #![cfg(test)]
 
-use crate::{self as pallet_balances, decl_tests, Config, Pallet};
+use crate::{self as pallet_balances, decl_tests, tests::TestId, Config, Pallet};
 use frame_support::{
 	dispatch::DispatchInfo,
 	parameter_types,
@@ -62,6 +62,7 @@ impl frame_system::Config for Test {
 	type Index = u64;
 	type BlockNumber = u64;
 	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
 	type Hash = H256;
 	type Hashing = ::sp_runtime::traits::BlakeTwo256;
 	type AccountId = u64;
@@ -99,6 +100,10 @@ impl Config for Test {
 	type MaxLocks = ConstU32<50>;
 	type MaxReserves = ConstU32<2>;
 	type ReserveIdentifier = [u8; 8];
+	type HoldIdentifier = TestId;
+	type FreezeIdentifier = TestId;
+	type MaxHolds = ConstU32<2>;
+	type MaxFreezes = ConstU32<2>;
 	type WeightInfo = ();
 }
 
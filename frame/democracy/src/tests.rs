@@ -23,7 +23,7 @@ use frame_support::{
 	assert_noop, assert_ok, ord_parameter_types, parameter_types,
 	traits::{
 		ConstU32, ConstU64, Contains, EqualPrivilegeOnly, GenesisBuild, OnInitialize,
-		SortedMembers, StorePreimage,
+		SortedMembers, StorePreimage, VariantCountOf,
 	},
 	weights::Weight,
 };
@@ -88,6 +88,7 @@ impl frame_system::Config for Test {
 	type Index = u64;
 	type BlockNumber = u64;
 	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
 	type Hash = H256;
 	type Hashing = BlakeTwo256;
 	type AccountId = u64;
@@ -112,6 +113,7 @@ parameter_types! {
 impl pallet_preimage::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type Currency = Balances;
 	type ManagerOrigin = EnsureRoot<u64>;
 	type BaseDeposit = ConstU64<0>;
@@ -134,6 +136,10 @@ impl pallet_scheduler::Config for Test {
 impl pallet_balances::Config for Test {
 	type MaxReserves = ();
 	type ReserveIdentifier = [u8; 8];
+	type HoldIdentifier = RuntimeHoldReason;
+	type FreezeIdentifier = ();
+	type MaxHolds = VariantCountOf<RuntimeHoldReason>;
+	type MaxFreezes = ();
 	type MaxLocks = ConstU32<10>;
 	type Balance = u64;
 	type RuntimeEvent = RuntimeEvent;
@@ -113,17 +113,32 @@
 //!
 //! pub type Executive = executive::Executive<Runtime, Block, Context, Runtime, AllPalletsWithSystem, CustomOnRuntimeUpgrade>;
 //! ```
+//!
+//! ### Multi-block migrations
+//!
+//! Migrations too large for a single block implement
+//! [`SteppedMigration`](frame_support::migrations::SteppedMigration) and are executed across
+//! blocks by a [`SteppedMigrationRunner`], set as the last generic parameter of the `Executive`.
+//! The runner starts them after the `OnRuntimeUpgrade` logic and steps them after `on_initialize`
+//! of every block, optionally rejecting all non-mandatory extrinsics until they are complete.
+//!
+//! ## Tasks
+//!
+//! The weight left after `on_idle` is used to run the valid tasks of the runtime, i.e. of
+//! `frame_system::Config::RuntimeTask`, until the next one doesn't fit. Checking the validity of
+//! a task is accounted as one storage read. A failing task has its changes reverted.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Codec, Encode};
 use frame_support::{
 	dispatch::{DispatchClass, DispatchInfo, GetDispatchInfo, PostDispatchInfo},
+	storage::with_storage_layer,
 	traits::{
-		EnsureInherentsAreFirst, ExecuteBlock, OffchainWorker, OnFinalize, OnIdle, OnInitialize,
-		OnRuntimeUpgrade,
+		EnsureInherentsAreFirst, ExecuteBlock, Get, OffchainWorker, OnFinalize, OnIdle,
+		OnInitialize, OnRuntimeUpgrade, Task,
 	},
-	weights::Weight,
+	weights::{Weight, WeightMeter},
 };
 use sp_runtime::{
 	generic::Digest,
@@ -131,11 +146,14 @@ use sp_runtime::{
 		self, Applyable, CheckEqual, Checkable, Dispatchable, Header, NumberFor, One,
 		ValidateUnsigned, Zero,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult,
 };
 use sp_std::{marker::PhantomData, prelude::*};
 
+mod migrations;
+pub use self::migrations::{MigrationStatus, SteppedMigrationRunner};
+
 pub type CheckedOf<E, C> = <E as Checkable<C>>::Checked;
 pub type CallOf<E, C> = <CheckedOf<E, C> as Applyable>::Call;
 pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::RuntimeOrigin;
@@ -151,6 +169,8 @@ pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::RuntimeOrigin;
 ///   used to call hooks e.g. `on_initialize`.
 /// - `OnRuntimeUpgrade`: Custom logic that should be called after a runtime upgrade. Modules are
 ///   already called by `AllPalletsWithSystem`. It will be called before all modules will be called.
+/// - `MultiStepMigrator`: Executes the multi-block migrations, e.g. a [`SteppedMigrationRunner`].
+///   It is started after all `OnRuntimeUpgrade`s and stepped after `on_initialize`.
 pub struct Executive<
 	System,
 	Block,
@@ -158,6 +178,7 @@ pub struct Executive<
 	UnsignedValidator,
 	AllPalletsWithSystem,
 	OnRuntimeUpgrade = (),
+	MultiStepMigrator = (),
 >(
 	PhantomData<(
 		System,
@@ -166,6 +187,7 @@ pub struct Executive<
 		UnsignedValidator,
 		AllPalletsWithSystem,
 		OnRuntimeUpgrade,
+		MultiStepMigrator,
 	)>,
 );
 
@@ -180,8 +202,17 @@ impl<
 			+ OnFinalize<System::BlockNumber>
 			+ OffchainWorker<System::BlockNumber>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
+		MultiStepMigrator: frame_support::migrations::MultiStepMigrator + OnRuntimeUpgrade,
 	> ExecuteBlock<Block>
-	for Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+	for Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		MultiStepMigrator,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -198,6 +229,7 @@ where
 			UnsignedValidator,
 			AllPalletsWithSystem,
 			COnRuntimeUpgrade,
+			MultiStepMigrator,
 		>::execute_block(block);
 	}
 }
@@ -215,7 +247,17 @@ impl<
 			+ OffchainWorker<System::BlockNumber>
 			+ frame_support::traits::TryState<System::BlockNumber>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
-	> Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+		MultiStepMigrator: frame_support::migrations::MultiStepMigrator + OnRuntimeUpgrade,
+	>
+	Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		MultiStepMigrator,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -285,9 +327,13 @@ where
 
 	/// Execute all `OnRuntimeUpgrade` of this runtime, including the pre and post migration checks.
 	///
+	/// The multi-block migrations are then executed until they are complete, as they would over
+	/// the following blocks.
+	///
 	/// This should only be used for testing.
 	pub fn try_runtime_upgrade() -> Result<frame_support::weights::Weight, &'static str> {
 		let weight = Self::execute_on_runtime_upgrade();
+		let weight = weight.saturating_add(MultiStepMigrator::try_run_to_completion()?);
 		Ok(weight)
 	}
 }
@@ -303,7 +349,17 @@ impl<
 			+ OnFinalize<System::BlockNumber>
 			+ OffchainWorker<System::BlockNumber>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
-	> Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+		MultiStepMigrator: frame_support::migrations::MultiStepMigrator + OnRuntimeUpgrade,
+	>
+	Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		MultiStepMigrator,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -312,9 +368,11 @@ where
 	OriginOf<Block::Extrinsic, Context>: From<Option<System::AccountId>>,
 	UnsignedValidator: ValidateUnsigned<Call = CallOf<Block::Extrinsic, Context>>,
 {
-	/// Execute all `OnRuntimeUpgrade` of this runtime, and return the aggregate weight.
+	/// Execute all `OnRuntimeUpgrade` of this runtime, start the multi-block migrations and return
+	/// the aggregate weight.
 	pub fn execute_on_runtime_upgrade() -> frame_support::weights::Weight {
-		<(COnRuntimeUpgrade, AllPalletsWithSystem) as OnRuntimeUpgrade>::on_runtime_upgrade()
+		<(COnRuntimeUpgrade, AllPalletsWithSystem, MultiStepMigrator) as OnRuntimeUpgrade>::
+			on_runtime_upgrade()
 	}
 
 	/// Start the execution of a particular block.
@@ -353,6 +411,7 @@ where
 		weight = weight.saturating_add(<AllPalletsWithSystem as OnInitialize<
 			System::BlockNumber,
 		>>::on_initialize(*block_number));
+		weight = weight.saturating_add(MultiStepMigrator::step());
 		weight = weight.saturating_add(
 			<System::BlockWeights as frame_support::traits::Get<_>>::get().base_block,
 		);
@@ -468,11 +527,40 @@ where
 				used_weight,
 				DispatchClass::Mandatory,
 			);
+			Self::run_idle_tasks(remaining_weight.saturating_sub(used_weight));
 		}
 
 		<AllPalletsWithSystem as OnFinalize<System::BlockNumber>>::on_finalize(block_number);
 	}
 
+	/// Run the valid tasks of the runtime as long as they fit in `limit`.
+	fn run_idle_tasks(limit: Weight) {
+		let mut meter = WeightMeter::from_limit(limit);
+		let check_weight = System::DbWeight::get().reads(1);
+
+		for task in System::RuntimeTask::iter() {
+			if !meter.check_accrue(check_weight) {
+				break
+			}
+			if !task.is_valid() {
+				continue
+			}
+			if !meter.check_accrue(task.weight()) {
+				break
+			}
+			let event = match with_storage_layer(|| task.run()) {
+				Ok(()) => frame_system::Event::TaskCompleted { task },
+				Err(err) => frame_system::Event::TaskFailed { task, err },
+			};
+			<frame_system::Pallet<System>>::deposit_event(event);
+		}
+
+		<frame_system::Pallet<System>>::register_extra_weight_unchecked(
+			meter.consumed,
+			DispatchClass::Mandatory,
+		);
+	}
+
 	/// Apply extrinsic outside of the block execution function.
 	///
 	/// This doesn't attempt to validate anything regarding the block, but it builds a list of uxt
@@ -486,6 +574,14 @@ where
 		// Verify that the signature is good.
 		let xt = uxt.check(&Default::default())?;
 
+		// Only inherents are applied while the multi-block migrations pause extrinsics. The
+		// transaction pool rejects the others as well, see `validate_transaction`.
+		let dispatch_info = xt.get_dispatch_info();
+		if dispatch_info.class != DispatchClass::Mandatory && MultiStepMigrator::extrinsics_paused()
+		{
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
+
 		// We don't need to make sure to `note_extrinsic` only after we know it's going to be
 		// executed to prevent it from leaking in storage since at this point, it will either
 		// execute or panic (and revert storage changes).
@@ -494,7 +590,6 @@ where
 		// AUDIT: Under no circumstances may this function panic from here onwards.
 
 		// Decode parameters and dispatch
-		let dispatch_info = xt.get_dispatch_info();
 		let r = Applyable::apply::<UnsignedValidator>(xt, &dispatch_info, encoded_len)?;
 
 		<frame_system::Pallet<System>>::note_applied_extrinsic(&r, dispatch_info);
@@ -534,6 +629,8 @@ where
 	/// side-effects; it merely checks whether the transaction would panic if it were included or
 	/// not.
 	///
+	/// Non-mandatory transactions are invalid while the multi-block migrations pause extrinsics.
+	///
 	/// Changes made to storage should be discarded.
 	pub fn validate_transaction(
 		source: TransactionSource,
@@ -563,6 +660,12 @@ where
 			xt.get_dispatch_info()
 		};
 
+		// Transactions can't be included while the multi-block migrations pause extrinsics.
+		if dispatch_info.class != DispatchClass::Mandatory && MultiStepMigrator::extrinsics_paused()
+		{
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
+
 		within_span! {
 			sp_tracing::Level::TRACE, "validate";
 			xt.validate::<UnsignedValidator>(source, &dispatch_info, encoded_len)
@@ -606,12 +709,16 @@ mod tests {
 	};
 
 	use frame_support::{
-		assert_err, parameter_types,
+		assert_err,
+		migrations::{MultiStepMigrator, SteppedMigration, SteppedMigrationError},
+		parameter_types,
 		traits::{
-			ConstU32, ConstU64, ConstU8, Currency, LockIdentifier, LockableCurrency,
+			ConstBool, ConstU32, ConstU64, ConstU8, Currency, LockIdentifier, LockableCurrency,
 			WithdrawReasons,
 		},
-		weights::{ConstantMultiplier, IdentityFee, RuntimeDbWeight, Weight, WeightToFee},
+		weights::{
+			ConstantMultiplier, IdentityFee, RuntimeDbWeight, Weight, WeightMeter, WeightToFee,
+		},
 	};
 	use frame_system::{Call as SystemCall, ChainContext, LastRuntimeUpgradeInfo};
 	use pallet_balances::Call as BalancesCall;
@@ -706,6 +813,25 @@ mod tests {
 			}
 		}
 
+		/// Values to be moved to `Processed` by `process_pending`.
+		#[pallet::storage]
+		pub type Pending<T> = StorageMap<_, Twox64Concat, u32, u32>;
+
+		#[pallet::storage]
+		pub type Processed<T> = StorageMap<_, Twox64Concat, u32, u32>;
+
+		#[pallet::tasks]
+		impl<T: Config> Pallet<T> {
+			#[pallet::task_list(Pending::<T>::iter_keys())]
+			#[pallet::task_condition(|i: &u32| Pending::<T>::contains_key(i))]
+			#[pallet::task_weight(Weight::from_ref_time(10))]
+			pub fn process_pending(i: u32) -> DispatchResult {
+				let value = Pending::<T>::take(i).ok_or(DispatchError::Other("not pending"))?;
+				Processed::<T>::insert(i, value);
+				Ok(())
+			}
+		}
+
 		#[pallet::inherent]
 		impl<T: Config> ProvideInherent for Pallet<T> {
 			type Call = Call<T>;
@@ -755,10 +881,10 @@ mod tests {
 			NodeBlock = TestBlock,
 			UncheckedExtrinsic = TestUncheckedExtrinsic
 		{
-			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>, ValidateUnsigned},
 			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 			TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>},
-			Custom: custom::{Pallet, Call, ValidateUnsigned, Inherent},
+			Custom: custom::{Pallet, Call, ValidateUnsigned, Inherent, Task},
 		}
 	);
 
@@ -782,6 +908,7 @@ mod tests {
 		type RuntimeOrigin = RuntimeOrigin;
 		type Index = u64;
 		type RuntimeCall = RuntimeCall;
+		type RuntimeTask = RuntimeTask;
 		type BlockNumber = u64;
 		type Hash = sp_core::H256;
 		type Hashing = BlakeTwo256;
@@ -811,6 +938,10 @@ mod tests {
 		type MaxLocks = ();
 		type MaxReserves = ();
 		type ReserveIdentifier = [u8; 8];
+		type HoldIdentifier = ();
+		type FreezeIdentifier = ();
+		type MaxHolds = ();
+		type MaxFreezes = ();
 		type WeightInfo = ();
 	}
 
@@ -871,6 +1002,88 @@ mod tests {
 		CustomOnRuntimeUpgrade,
 	>;
 
+	// Number of the last step taken by the `CountingMigration` with the given id.
+	fn migration_progress(id: u8) -> Option<u32> {
+		sp_io::storage::get(&[b":migration:".as_slice(), &[id]].concat())
+			.map(|value| codec::Decode::decode(&mut &value[..]).unwrap())
+	}
+
+	// Migration with identifier `ID`, taking `STEPS` steps of 10 weight each.
+	struct CountingMigration<const ID: u8, const STEPS: u32>;
+	impl<const ID: u8, const STEPS: u32> SteppedMigration for CountingMigration<ID, STEPS> {
+		type Cursor = u32;
+		type Identifier = u8;
+
+		fn id() -> u8 {
+			ID
+		}
+
+		fn step(
+			cursor: Option<u32>,
+			meter: &mut WeightMeter,
+		) -> Result<Option<u32>, SteppedMigrationError> {
+			let required = Weight::from_ref_time(10);
+			if !meter.check_accrue(required) {
+				return Err(SteppedMigrationError::InsufficientWeight { required })
+			}
+
+			let step = cursor.unwrap_or(0) + 1;
+			sp_io::storage::set(&[b":migration:".as_slice(), &[ID]].concat(), &step.encode());
+			Ok((step < STEPS).then_some(step))
+		}
+	}
+
+	// Migration with identifier `ID` counting like a `CountingMigration`, but failing at step
+	// `FAIL_AT`.
+	struct BrokenMigration<const ID: u8, const FAIL_AT: u32>;
+	impl<const ID: u8, const FAIL_AT: u32> SteppedMigration for BrokenMigration<ID, FAIL_AT> {
+		type Cursor = u32;
+		type Identifier = u8;
+
+		fn id() -> u8 {
+			ID
+		}
+
+		fn step(
+			cursor: Option<u32>,
+			meter: &mut WeightMeter,
+		) -> Result<Option<u32>, SteppedMigrationError> {
+			if cursor.unwrap_or(0) + 1 == FAIL_AT {
+				return Err(SteppedMigrationError::Failed)
+			}
+			CountingMigration::<ID, { u32::MAX }>::step(cursor, meter)
+		}
+	}
+
+	struct FailingMigration;
+	impl SteppedMigration for FailingMigration {
+		type Cursor = ();
+		type Identifier = u8;
+
+		fn id() -> u8 {
+			0
+		}
+
+		fn step(_: Option<()>, _: &mut WeightMeter) -> Result<Option<()>, SteppedMigrationError> {
+			Err(SteppedMigrationError::Failed)
+		}
+	}
+
+	parameter_types! {
+		pub const MigrationStepWeight: Weight = Weight::from_ref_time(25);
+		pub const SmallMigrationStepWeight: Weight = Weight::from_ref_time(5);
+	}
+
+	type MigratingExecutive<Migrations> = super::Executive<
+		Runtime,
+		Block<TestXt>,
+		ChainContext<Runtime>,
+		Runtime,
+		AllPalletsWithSystem,
+		(),
+		SteppedMigrationRunner<Runtime, Migrations, MigrationStepWeight, ConstBool<true>>,
+	>;
+
 	fn extra(nonce: u64, fee: Balance) -> SignedExtra {
 		(
 			frame_system::CheckEra::from(Era::Immortal),
@@ -1170,6 +1383,70 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn tasks_run_on_idle() {
+		new_test_ext(1).execute_with(|| {
+			custom::Pending::<Runtime>::insert(1, 10);
+			custom::Pending::<Runtime>::insert(2, 20);
+
+			Executive::initialize_block(&Header::new_from_number(1));
+			Executive::finalize_block();
+
+			assert_eq!(custom::Pending::<Runtime>::iter().count(), 0);
+			assert_eq!(custom::Processed::<Runtime>::get(1), Some(10));
+			assert_eq!(custom::Processed::<Runtime>::get(2), Some(20));
+			let completed = System::events()
+				.into_iter()
+				.filter(|record| {
+					matches!(
+						record.event,
+						RuntimeEvent::System(frame_system::Event::TaskCompleted { .. })
+					)
+				})
+				.count();
+			assert_eq!(completed, 2);
+			// The weight of the two tasks is registered along with the hooks.
+			assert_eq!(
+				<frame_system::Pallet<Runtime>>::block_weight().total(),
+				Weight::from_ref_time(175 + 175 + 10 + 2 * 10)
+			);
+		});
+	}
+
+	#[test]
+	fn tasks_can_be_submitted_unsigned() {
+		let task = RuntimeTask::from(custom::Task::<Runtime>::process_pending { i: 1 });
+		let xt = TestXt::new(RuntimeCall::System(frame_system::Call::do_task { task }), None);
+		new_test_ext(1).execute_with(|| {
+			Executive::initialize_block(&Header::new_from_number(1));
+
+			// Not valid while there is nothing to process.
+			assert_eq!(
+				Executive::validate_transaction(
+					TransactionSource::External,
+					xt.clone(),
+					Default::default(),
+				),
+				Err(TransactionValidityError::Invalid(InvalidTransaction::Call)),
+			);
+			assert_eq!(
+				Executive::apply_extrinsic(xt.clone()),
+				Err(TransactionValidityError::Invalid(InvalidTransaction::Call)),
+			);
+
+			custom::Pending::<Runtime>::insert(1, 10);
+			assert!(Executive::validate_transaction(
+				TransactionSource::External,
+				xt.clone(),
+				Default::default(),
+			)
+			.is_ok());
+			assert_eq!(Executive::apply_extrinsic(xt), Ok(Ok(())));
+			assert_eq!(custom::Pending::<Runtime>::get(1), None);
+			assert_eq!(custom::Processed::<Runtime>::get(1), Some(10));
+		});
+	}
+
 	#[test]
 	fn can_pay_for_tx_fee_on_full_lock() {
 		let id: LockIdentifier = *b"0       ";
@@ -1533,4 +1810,208 @@ mod tests {
 			Executive::execute_block(Block::new(header, vec![xt1, xt2]));
 		});
 	}
+
+	#[test]
+	fn multi_block_migrations_run_across_blocks() {
+		type Migrations = (CountingMigration<1, 3>, CountingMigration<2, 2>);
+		type Migrator =
+			SteppedMigrationRunner<Runtime, Migrations, MigrationStepWeight, ConstBool<true>>;
+
+		new_test_ext(1).execute_with(|| {
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 1, ..Default::default() }
+			});
+
+			// Two steps fit in a block.
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(1));
+			assert_eq!(migration_progress(1), Some(2));
+			assert_eq!(
+				Migrator::status(),
+				Some(MigrationStatus::Active {
+					id: 1u8.encode(),
+					cursor: Some(2u32.encode()),
+					steps: 2
+				}),
+			);
+
+			// Non-mandatory extrinsics are rejected while migrating.
+			let xt = TestXt::new(call_transfer(2, 69), sign_extra(1, 0, 0));
+			assert_eq!(
+				MigratingExecutive::<Migrations>::apply_extrinsic(xt.clone()),
+				Err(InvalidTransaction::ExhaustsResources.into()),
+			);
+			MigratingExecutive::<Migrations>::finalize_block();
+
+			// They are not accepted into the transaction pool either.
+			assert_eq!(
+				MigratingExecutive::<Migrations>::validate_transaction(
+					TransactionSource::External,
+					xt.clone(),
+					Default::default(),
+				),
+				Err(InvalidTransaction::ExhaustsResources.into()),
+			);
+
+			// The first migration completes and the second one starts in the same block.
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(2));
+			assert_eq!(migration_progress(1), Some(3));
+			assert_eq!(migration_progress(2), Some(1));
+			assert!(Migrator::completed(&1u8.encode()));
+			assert_eq!(
+				<frame_system::Pallet<Runtime>>::block_weight().total(),
+				Weight::from_ref_time(175 + 20 + 10)
+			);
+			MigratingExecutive::<Migrations>::finalize_block();
+
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(3));
+			assert_eq!(migration_progress(2), Some(2));
+			assert_eq!(Migrator::status(), None);
+			assert!(MigratingExecutive::<Migrations>::apply_extrinsic(xt).unwrap().is_ok());
+			MigratingExecutive::<Migrations>::finalize_block();
+
+			// Completed migrations are not executed again on the next upgrade.
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 2, ..Default::default() }
+			});
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(4));
+			assert_eq!(Migrator::status(), None);
+			assert_eq!(migration_progress(1), Some(3));
+		});
+	}
+
+	#[test]
+	fn runtime_upgrade_continues_ongoing_multi_block_migration() {
+		type Migrations = (CountingMigration<1, 4>,);
+		type Migrator =
+			SteppedMigrationRunner<Runtime, Migrations, MigrationStepWeight, ConstBool<true>>;
+
+		new_test_ext(1).execute_with(|| {
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 1, ..Default::default() }
+			});
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(1));
+			assert_eq!(migration_progress(1), Some(2));
+			MigratingExecutive::<Migrations>::finalize_block();
+
+			// Another upgrade while migrating keeps the cursor instead of starting over.
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 2, ..Default::default() }
+			});
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(2));
+			assert_eq!(migration_progress(1), Some(4));
+			assert_eq!(Migrator::status(), None);
+			assert!(Migrator::completed(&1u8.encode()));
+		});
+	}
+
+	#[test]
+	fn failed_multi_block_migration_is_stuck() {
+		type Migrations = (FailingMigration,);
+		type Migrator =
+			SteppedMigrationRunner<Runtime, Migrations, MigrationStepWeight, ConstBool<true>>;
+
+		new_test_ext(1).execute_with(|| {
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 1, ..Default::default() }
+			});
+
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(1));
+			assert_eq!(
+				Migrator::status(),
+				Some(MigrationStatus::Stuck { id: 0u8.encode(), cursor: None, steps: 0 })
+			);
+			assert!(!Migrator::completed(&0u8.encode()));
+
+			// Extrinsics are not paused by stuck migrations.
+			let xt = TestXt::new(call_transfer(2, 69), sign_extra(1, 0, 0));
+			assert!(MigratingExecutive::<Migrations>::apply_extrinsic(xt).unwrap().is_ok());
+		});
+	}
+
+	#[test]
+	fn multi_block_migration_step_heavier_than_step_weight_is_stuck() {
+		type Migrator =
+			SteppedMigrationRunner<Runtime, (CountingMigration<1, 1>,), SmallMigrationStepWeight>;
+
+		new_test_ext(1).execute_with(|| {
+			Migrator::on_runtime_upgrade();
+			assert!(Migrator::ongoing());
+			// `PauseExtrinsics` defaults to `false`.
+			assert!(!Migrator::extrinsics_paused());
+
+			Migrator::step();
+			assert_eq!(
+				Migrator::status(),
+				Some(MigrationStatus::Stuck { id: 1u8.encode(), cursor: None, steps: 0 })
+			);
+			assert_eq!(migration_progress(1), None);
+		});
+	}
+
+	#[test]
+	fn multi_block_migrations_are_tracked_by_id() {
+		new_test_ext(1).execute_with(|| {
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 1, ..Default::default() }
+			});
+			type Migrations = (CountingMigration<1, 4>,);
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(1));
+			assert_eq!(migration_progress(1), Some(2));
+			MigratingExecutive::<Migrations>::finalize_block();
+
+			// An upgrade adds a migration in front of the ongoing one, which continues first.
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 2, ..Default::default() }
+			});
+			type Upgraded = (CountingMigration<2, 1>, CountingMigration<1, 4>);
+			type Migrator = SteppedMigrationRunner<Runtime, Upgraded, MigrationStepWeight>;
+			MigratingExecutive::<Upgraded>::initialize_block(&Header::new_from_number(2));
+			assert_eq!(migration_progress(1), Some(4));
+			assert!(Migrator::completed(&1u8.encode()));
+			assert_eq!(
+				Migrator::status(),
+				Some(MigrationStatus::Active { id: 2u8.encode(), cursor: None, steps: 0 })
+			);
+			MigratingExecutive::<Upgraded>::finalize_block();
+
+			// The added migration runs afterwards.
+			MigratingExecutive::<Upgraded>::initialize_block(&Header::new_from_number(3));
+			assert_eq!(migration_progress(2), Some(1));
+			assert_eq!(Migrator::status(), None);
+		});
+	}
+
+	#[test]
+	fn stuck_multi_block_migration_resumes_from_its_cursor() {
+		new_test_ext(1).execute_with(|| {
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 1, ..Default::default() }
+			});
+			type Migrations = (BrokenMigration<1, 3>,);
+			type Migrator = SteppedMigrationRunner<Runtime, Migrations, MigrationStepWeight>;
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(1));
+			MigratingExecutive::<Migrations>::finalize_block();
+			MigratingExecutive::<Migrations>::initialize_block(&Header::new_from_number(2));
+			assert_eq!(migration_progress(1), Some(2));
+			assert_eq!(
+				Migrator::status(),
+				Some(MigrationStatus::Stuck {
+					id: 1u8.encode(),
+					cursor: Some(2u32.encode()),
+					steps: 2
+				})
+			);
+			MigratingExecutive::<Migrations>::finalize_block();
+
+			// The fixed migration continues after the steps that were already applied.
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 2, ..Default::default() }
+			});
+			type Fixed = (CountingMigration<1, 4>,);
+			MigratingExecutive::<Fixed>::initialize_block(&Header::new_from_number(3));
+			assert_eq!(migration_progress(1), Some(4));
+			assert_eq!(Migrator::status(), None);
+			assert!(Migrator::completed(&1u8.encode()));
+		});
+	}
 }
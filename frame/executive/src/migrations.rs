@@ -0,0 +1,384 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Execution of multi-block migrations.
+//!
+//! [`SteppedMigrationRunner`] executes a tuple of
+//! [`SteppedMigration`](frame_support::migrations::SteppedMigration)s across as many blocks as
+//! they need. It is passed to the [`Executive`](crate::Executive) as its `MultiStepMigrator`:
+//!
+//! - on runtime upgrade, after all `OnRuntimeUpgrade`s, it starts at the first migration that did
+//!   not complete before, unless migrations are still ongoing, in which case they continue from
+//!   where they are;
+//! - every block, after `on_initialize`, it steps the ongoing migration with at most
+//!   `MaxStepWeight`, moving on to the next migration when one completes;
+//! - while migrations are ongoing, non-mandatory extrinsics are rejected, both when applied and
+//!   when validated for the transaction pool, if `PauseExtrinsics` is `true`.
+//!
+//! The progress is kept in the [`MigrationStatus`] storage item, which refers to the ongoing
+//! migration by its id rather than its position in the tuple, so that an upgrade can add or
+//! remove migrations around it. A migration that fails, needs more than `MaxStepWeight` for a
+//! single step or exceeds its `max_steps` leaves the status [`MigrationStatus::Stuck`]: no more
+//! steps are executed and extrinsics are no longer paused, so that the chain can be fixed with
+//! another runtime upgrade. That upgrade resumes the stuck migration from its last cursor, or
+//! starts at the first migration that did not complete if the stuck one was removed.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	log,
+	migrations::{MultiStepMigrator, SteppedMigrationError, SteppedMigrations},
+	traits::{ConstBool, Get, OnRuntimeUpgrade},
+	weights::{Weight, WeightMeter},
+	Twox64Concat,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+const LOG_TARGET: &str = "runtime::executive::migrations";
+
+/// Progress of the multi-block migrations.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, scale_info::TypeInfo)]
+pub enum MigrationStatus {
+	/// The migration with the encoded `id` is ongoing.
+	Active {
+		/// Encoded id of the migration.
+		id: Vec<u8>,
+		/// Encoded cursor to continue the migration from, `None` if it did not start yet.
+		cursor: Option<Vec<u8>>,
+		/// Number of steps the migration took so far.
+		steps: u32,
+	},
+	/// The migration with the encoded `id` failed and the migrations were stopped.
+	Stuck {
+		/// Encoded id of the migration.
+		id: Vec<u8>,
+		/// Encoded cursor the migration failed to continue from.
+		cursor: Option<Vec<u8>>,
+		/// Number of steps the migration took before failing.
+		steps: u32,
+	},
+}
+
+#[frame_support::storage_alias]
+type Status = StorageValue<MultiBlockMigrations, MigrationStatus>;
+
+#[frame_support::storage_alias]
+type Completed = StorageMap<MultiBlockMigrations, Twox64Concat, Vec<u8>, ()>;
+
+/// Outcome of stepping a migration in a block.
+enum Progress {
+	/// The migration completed.
+	Completed,
+	/// The migration ran out of weight for this block.
+	Paused,
+	/// The migration can not make any more progress.
+	Stuck(&'static str),
+}
+
+/// Executes the multi-block `Migrations` on behalf of the [`Executive`](crate::Executive).
+///
+/// Generic parameters:
+/// - `System`: Something that implements `frame_system::Config`
+/// - `Migrations`: Tuple of the stepped migrations, executed in order.
+/// - `MaxStepWeight`: The maximum weight used by the migrations in a block.
+/// - `PauseExtrinsics`: Whether non-mandatory extrinsics are rejected while migrating.
+pub struct SteppedMigrationRunner<
+	System,
+	Migrations,
+	MaxStepWeight,
+	PauseExtrinsics = ConstBool<false>,
+>(PhantomData<(System, Migrations, MaxStepWeight, PauseExtrinsics)>);
+
+impl<System, Migrations, MaxStepWeight, PauseExtrinsics>
+	SteppedMigrationRunner<System, Migrations, MaxStepWeight, PauseExtrinsics>
+where
+	System: frame_system::Config,
+	Migrations: SteppedMigrations,
+	MaxStepWeight: Get<Weight>,
+	PauseExtrinsics: Get<bool>,
+{
+	/// The progress of the migrations, `None` if no migration is ongoing.
+	pub fn status() -> Option<MigrationStatus> {
+		Status::get()
+	}
+
+	/// Whether the migration with the encoded `id` completed.
+	pub fn completed(id: &[u8]) -> bool {
+		Completed::contains_key(id)
+	}
+
+	/// Step the migration at `index` until it completes or runs out of weight.
+	fn step_migration(
+		index: u32,
+		cursor: &mut Option<Vec<u8>>,
+		steps: &mut u32,
+		meter: &mut WeightMeter,
+	) -> Progress {
+		let max_steps = match Migrations::nth_max_steps(index) {
+			Some(max_steps) => max_steps,
+			None => return Progress::Stuck("migration index out of bounds"),
+		};
+
+		loop {
+			if max_steps.map_or(false, |max_steps| *steps >= max_steps) {
+				return Progress::Stuck("migration exceeded its maximum number of steps")
+			}
+
+			let consumed = meter.consumed;
+			let result = Migrations::nth_step(index, cursor.clone(), meter)
+				.unwrap_or(Err(SteppedMigrationError::Failed));
+			// Only the steps that made progress count towards the limit.
+			if result.is_ok() {
+				*steps = steps.saturating_add(1);
+			}
+
+			match result {
+				Ok(Some(next)) => {
+					*cursor = Some(next);
+					// A step that did not consume any weight is not called again in this block, so
+					// that it can not loop forever.
+					if meter.consumed == consumed {
+						return Progress::Paused
+					}
+				},
+				Ok(None) => return Progress::Completed,
+				Err(SteppedMigrationError::InsufficientWeight { required }) => {
+					return if required.any_gt(MaxStepWeight::get()) {
+						Progress::Stuck("migration step needs more than the maximum step weight")
+					} else {
+						Progress::Paused
+					}
+				},
+				Err(SteppedMigrationError::InvalidCursor) =>
+					return Progress::Stuck("migration cursor could not be decoded"),
+				Err(SteppedMigrationError::Failed) => return Progress::Stuck("migration failed"),
+			}
+		}
+	}
+
+	/// The index of the migration with the encoded `id`, if it is still part of `Migrations`.
+	fn index_of(id: &[u8]) -> Option<u32> {
+		(0..Migrations::len()).find(|index| Migrations::nth_id(*index).as_deref() == Some(id))
+	}
+
+	/// The index of the first migration from `index` on that did not complete before.
+	fn next_pending(mut index: u32) -> Option<u32> {
+		while let Some(id) = Migrations::nth_id(index) {
+			if !Completed::contains_key(&id) {
+				return Some(index)
+			}
+			index += 1;
+		}
+		None
+	}
+}
+
+impl<System, Migrations, MaxStepWeight, PauseExtrinsics> OnRuntimeUpgrade
+	for SteppedMigrationRunner<System, Migrations, MaxStepWeight, PauseExtrinsics>
+where
+	System: frame_system::Config,
+	Migrations: SteppedMigrations,
+	MaxStepWeight: Get<Weight>,
+	PauseExtrinsics: Get<bool>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = System::DbWeight::get();
+
+		// Restarting an ongoing or stuck migration would apply its completed steps a second time.
+		match Status::get() {
+			Some(MigrationStatus::Active { id, steps, .. }) if Self::index_of(&id).is_some() => {
+				log::info!(
+					target: LOG_TARGET,
+					"Continuing multi-block migration {:?} after {} steps",
+					id,
+					steps,
+				);
+				return db_weight.reads(1)
+			},
+			Some(MigrationStatus::Stuck { id, cursor, steps }) if Self::index_of(&id).is_some() => {
+				log::info!(
+					target: LOG_TARGET,
+					"Resuming stuck multi-block migration {:?} after {} steps",
+					id,
+					steps,
+				);
+				Status::put(MigrationStatus::Active { id, cursor, steps });
+				return db_weight.reads_writes(1, 1)
+			},
+			_ => {},
+		}
+
+		match Self::next_pending(0).and_then(Migrations::nth_id) {
+			Some(id) => {
+				log::info!(target: LOG_TARGET, "Starting multi-block migrations at {:?}", id);
+				Status::put(MigrationStatus::Active { id, cursor: None, steps: 0 });
+			},
+			None => Status::kill(),
+		}
+
+		db_weight.reads_writes(Migrations::len() as u64 + 1, 1)
+	}
+}
+
+impl<System, Migrations, MaxStepWeight, PauseExtrinsics> MultiStepMigrator
+	for SteppedMigrationRunner<System, Migrations, MaxStepWeight, PauseExtrinsics>
+where
+	System: frame_system::Config,
+	Migrations: SteppedMigrations,
+	MaxStepWeight: Get<Weight>,
+	PauseExtrinsics: Get<bool>,
+{
+	fn ongoing() -> bool {
+		matches!(Status::get(), Some(MigrationStatus::Active { .. }))
+	}
+
+	fn extrinsics_paused() -> bool {
+		PauseExtrinsics::get() && Self::ongoing()
+	}
+
+	fn step() -> Weight {
+		let db_weight = System::DbWeight::get();
+		let mut meter = WeightMeter::from_limit(MaxStepWeight::get());
+		meter.consumed = db_weight.reads(1);
+
+		let (id, mut cursor, mut steps) = match Status::get() {
+			Some(MigrationStatus::Active { id, cursor, steps }) => (id, cursor, steps),
+			_ => return meter.consumed,
+		};
+		meter.consumed.saturating_accrue(db_weight.writes(1));
+
+		let mut index = match Self::index_of(&id) {
+			Some(index) => index,
+			None => {
+				log::error!(target: LOG_TARGET, "Multi-block migration {:?} is unknown", id);
+				Status::put(MigrationStatus::Stuck { id, cursor, steps });
+				return meter.consumed
+			},
+		};
+
+		loop {
+			meter.consumed.saturating_accrue(db_weight.reads(1));
+			let id = match Migrations::nth_id(index) {
+				Some(id) => id,
+				None => {
+					log::info!(target: LOG_TARGET, "All multi-block migrations completed");
+					Status::kill();
+					return meter.consumed
+				},
+			};
+			if Completed::contains_key(&id) {
+				(index, cursor, steps) = (index + 1, None, 0);
+				continue
+			}
+
+			match Self::step_migration(index, &mut cursor, &mut steps, &mut meter) {
+				Progress::Completed => {
+					log::info!(
+						target: LOG_TARGET,
+						"Multi-block migration {:?} completed after {} steps",
+						id,
+						steps,
+					);
+					Completed::insert(id, ());
+					meter.consumed.saturating_accrue(db_weight.writes(1));
+					// Start over to pick up migrations added before this one by an upgrade.
+					(index, cursor, steps) = (0, None, 0);
+				},
+				Progress::Paused => {
+					Status::put(MigrationStatus::Active { id, cursor, steps });
+					return meter.consumed
+				},
+				Progress::Stuck(reason) => {
+					log::error!(
+						target: LOG_TARGET,
+						"Multi-block migration {:?} is stuck after {} steps: {}",
+						id,
+						steps,
+						reason,
+					);
+					Status::put(MigrationStatus::Stuck { id, cursor, steps });
+					return meter.consumed
+				},
+			}
+		}
+	}
+
+	/// Execute the remaining migrations block by block, each between its pre and post checks.
+	///
+	/// The pre checks of a migration that already started see its partially migrated state.
+	#[cfg(feature = "try-runtime")]
+	fn try_run_to_completion() -> Result<Weight, &'static str> {
+		let mut weight = Weight::zero();
+		let (mut index, mut cursor, mut steps) = match Status::get() {
+			Some(MigrationStatus::Active { id, cursor, steps }) =>
+				(Self::index_of(&id).ok_or("unknown multi-block migration")?, cursor, steps),
+			Some(MigrationStatus::Stuck { .. }) => return Err("multi-block migrations are stuck"),
+			None => return Ok(weight),
+		};
+
+		let mut blocks = 0u32;
+		while let Some(pending) = Self::next_pending(index) {
+			if pending != index {
+				(index, cursor, steps) = (pending, None, 0);
+			}
+
+			let _guard = frame_support::StorageNoopGuard::default();
+			let state = Migrations::nth_pre_upgrade(index).ok_or("migration index out of bounds")??;
+			drop(_guard);
+
+			loop {
+				blocks += 1;
+				let mut meter = WeightMeter::from_limit(MaxStepWeight::get());
+				let progress = Self::step_migration(index, &mut cursor, &mut steps, &mut meter);
+				weight.saturating_accrue(meter.consumed);
+
+				match progress {
+					Progress::Completed => break,
+					Progress::Paused => continue,
+					Progress::Stuck(reason) => {
+						log::error!(
+							target: LOG_TARGET,
+							"Multi-block migration #{} is stuck after {} steps: {}",
+							index,
+							steps,
+							reason,
+						);
+						return Err(reason)
+					},
+				}
+			}
+
+			let _guard = frame_support::StorageNoopGuard::default();
+			Migrations::nth_post_upgrade(index, state).ok_or("migration index out of bounds")??;
+			drop(_guard);
+
+			let id = Migrations::nth_id(index).ok_or("migration index out of bounds")?;
+			Completed::insert(id, ());
+			log::info!(
+				target: LOG_TARGET,
+				"Multi-block migration #{} completed after {} steps",
+				index,
+				steps,
+			);
+			(index, cursor, steps) = (0, None, 0);
+		}
+
+		Status::kill();
+		log::info!(target: LOG_TARGET, "All multi-block migrations completed in {} blocks", blocks);
+
+		Ok(weight)
+	}
+}
@@ -25,6 +25,9 @@
 //! The Preimage pallet allows for the users and the runtime to store the preimage
 //! of a hash on chain. This can be used by other pallets for storing and managing
 //! large byte-blobs.
+//!
+//! The deposit of a preimage noted by a user is put on hold in their account for
+//! [`HoldReason::Preimage`].
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -46,8 +49,9 @@ use frame_support::{
 	ensure,
 	pallet_prelude::Get,
 	traits::{
-		Currency, Defensive, FetchResult, Hash as PreimageHash, PreimageProvider,
-		PreimageRecipient, QueryPreimage, ReservableCurrency, StorePreimage,
+		fungible::{Inspect, MutateHold},
+		Defensive, FetchResult, Hash as PreimageHash, PreimageProvider, PreimageRecipient,
+		QueryPreimage, StorePreimage,
 	},
 	BoundedSlice, BoundedVec,
 };
@@ -72,7 +76,7 @@ pub enum RequestStatus<AccountId, Balance> {
 }
 
 type BalanceOf<T> =
-	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	<<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
 /// Maximum size of preimage we can store is 4mb.
 const MAX_SIZE: u32 = 4 * 1024 * 1024;
@@ -82,7 +86,7 @@ pub mod pallet {
 	use super::*;
 
 	/// The current storage version.
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
@@ -92,8 +96,11 @@ pub mod pallet {
 		/// The Weight information for this pallet.
 		type WeightInfo: weights::WeightInfo;
 
-		/// Currency type for this pallet.
-		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+
+		/// Currency type for this pallet, holding the deposits for [`HoldReason::Preimage`].
+		type Currency: MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
 
 		/// An origin that can request a preimage be placed on-chain without a deposit or fee, or
 		/// manage existing preimages.
@@ -122,6 +129,13 @@ pub mod pallet {
 		Cleared { hash: T::Hash },
 	}
 
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The funds are held as the deposit for noting a preimage.
+		Preimage,
+	}
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Preimage is too large to store on-chain.
@@ -180,7 +194,7 @@ pub mod pallet {
 
 		/// Request a preimage be uploaded to the chain without paying any fees or deposits.
 		///
-		/// If the preimage requests has already been provided on-chain, we unreserve any deposit
+		/// If the preimage requests has already been provided on-chain, we release any deposit
 		/// a user may have paid, and take the control of the preimage out of their hands.
 		#[pallet::weight(T::WeightInfo::request_preimage())]
 		pub fn request_preimage(origin: OriginFor<T>, hash: T::Hash) -> DispatchResult {
@@ -241,7 +255,7 @@ impl<T: Config> Pallet<T> {
 				let length = preimage.len() as u32;
 				let deposit = T::BaseDeposit::get()
 					.saturating_add(T::ByteDeposit::get().saturating_mul(length.into()));
-				T::Currency::reserve(depositor, deposit)?;
+				T::Currency::hold(&HoldReason::Preimage.into(), depositor, deposit)?;
 				RequestStatus::Unrequested { deposit: (depositor.clone(), deposit), len }
 			},
 		};
@@ -275,7 +289,7 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
-	// Clear a preimage from the storage of the chain, returning any deposit that may be reserved.
+	// Clear a preimage from the storage of the chain, returning any deposit that may be held.
 	//
 	// If `len` is provided, it will be a much cheaper operation.
 	//
@@ -288,7 +302,7 @@ impl<T: Config> Pallet<T> {
 		match StatusFor::<T>::get(hash).ok_or(Error::<T>::NotNoted)? {
 			RequestStatus::Requested { deposit: Some((owner, deposit)), count, len } => {
 				ensure!(maybe_check_owner.map_or(true, |c| c == owner), Error::<T>::NotAuthorized);
-				T::Currency::unreserve(&owner, deposit);
+				T::Currency::release(&HoldReason::Preimage.into(), &owner, deposit, true)?;
 				StatusFor::<T>::insert(
 					hash,
 					RequestStatus::Requested { deposit: None, count, len },
@@ -301,7 +315,7 @@ impl<T: Config> Pallet<T> {
 			},
 			RequestStatus::Unrequested { deposit: (owner, deposit), len } => {
 				ensure!(maybe_check_owner.map_or(true, |c| c == owner), Error::<T>::NotAuthorized);
-				T::Currency::unreserve(&owner, deposit);
+				T::Currency::release(&HoldReason::Preimage.into(), &owner, deposit, true)?;
 				StatusFor::<T>::remove(hash);
 
 				Self::remove(hash, len);
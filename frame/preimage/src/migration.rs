@@ -20,8 +20,9 @@
 use super::*;
 use frame_support::{
 	storage_alias,
-	traits::{ConstU32, OnRuntimeUpgrade},
+	traits::{ConstU32, OnRuntimeUpgrade, ReservableCurrency},
 };
+use sp_runtime::traits::Zero;
 use sp_std::collections::btree_map::BTreeMap;
 
 /// The log target.
@@ -181,14 +182,103 @@ pub mod v1 {
 	}
 }
 
+pub mod v2 {
+	use super::*;
+
+	/// The log target.
+	const TARGET: &str = "runtime::preimage::migration::v2";
+
+	/// Migration moving the deposits of the preimages from reserves of `OldCurrency` to holds for
+	/// [`HoldReason::Preimage`].
+	///
+	/// Each deposit is unreserved and then put on hold. A deposit which can't be put on hold is
+	/// left free in the account of the depositor and recorded as zero.
+	pub struct MigrateToHolds<T, OldCurrency>(sp_std::marker::PhantomData<(T, OldCurrency)>);
+
+	impl<T, OldCurrency> MigrateToHolds<T, OldCurrency>
+	where
+		T: Config,
+		OldCurrency: ReservableCurrency<T::AccountId, Balance = BalanceOf<T>>,
+	{
+		/// Move the reserved `deposit` of `who` on hold, returning the amount now on hold.
+		fn migrate_deposit(who: &T::AccountId, deposit: BalanceOf<T>) -> BalanceOf<T> {
+			let unreserved = deposit.saturating_sub(OldCurrency::unreserve(who, deposit));
+			match T::Currency::hold(&HoldReason::Preimage.into(), who, unreserved) {
+				Ok(()) => unreserved,
+				Err(e) => {
+					log::error!(
+						target: TARGET,
+						"failed to hold the deposit of {:?}: {:?}",
+						who,
+						e,
+					);
+					Zero::zero()
+				},
+			}
+		}
+	}
+
+	impl<T, OldCurrency> OnRuntimeUpgrade for MigrateToHolds<T, OldCurrency>
+	where
+		T: Config,
+		OldCurrency: ReservableCurrency<T::AccountId, Balance = BalanceOf<T>>,
+	{
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			assert_eq!(StorageVersion::get::<Pallet<T>>(), 1, "can only upgrade from version 1");
+			Ok(Vec::new())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if StorageVersion::get::<Pallet<T>>() != 1 {
+				log::warn!(
+					target: TARGET,
+					"skipping MigrateToHolds: executed on wrong storage version. Expected version 1"
+				);
+				return weight
+			}
+
+			crate::StatusFor::<T>::translate::<RequestStatus<T::AccountId, BalanceOf<T>>, _>(
+				|_, status| {
+					weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 1));
+					Some(match status {
+						RequestStatus::Unrequested { deposit: (who, deposit), len } => {
+							// Unreserving and holding each touch the account.
+							weight.saturating_accrue(T::DbWeight::get().reads_writes(2, 2));
+							let deposit = Self::migrate_deposit(&who, deposit);
+							RequestStatus::Unrequested { deposit: (who, deposit), len }
+						},
+						RequestStatus::Requested { deposit: Some((who, deposit)), count, len } => {
+							weight.saturating_accrue(T::DbWeight::get().reads_writes(2, 2));
+							let deposit = Self::migrate_deposit(&who, deposit);
+							RequestStatus::Requested { deposit: Some((who, deposit)), count, len }
+						},
+						status @ RequestStatus::Requested { deposit: None, .. } => status,
+					})
+				},
+			);
+			StorageVersion::new(2).put::<Pallet<T>>();
+
+			weight.saturating_add(T::DbWeight::get().writes(1))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			assert_eq!(StorageVersion::get::<Pallet<T>>(), 2, "must upgrade");
+			Ok(())
+		}
+	}
+}
+
 #[cfg(test)]
-#[cfg(feature = "try-runtime")]
 mod test {
 	use super::*;
 	use crate::mock::{Test as T, *};
 
-	use frame_support::bounded_vec;
+	use frame_support::{assert_ok, bounded_vec};
 
+	#[cfg(feature = "try-runtime")]
 	#[test]
 	fn migration_works() {
 		new_test_ext().execute_with(|| {
@@ -252,6 +342,54 @@ mod test {
 		});
 	}
 
+	#[test]
+	fn migration_to_holds_works() {
+		use frame_support::traits::fungible::InspectHold;
+
+		new_test_ext().execute_with(|| {
+			StorageVersion::new(1).put::<Pallet<T>>();
+			let hold_reason = RuntimeHoldReason::Preimage(HoldReason::Preimage);
+
+			let (_, unrequested) = preimage::<T>(128);
+			assert_ok!(Balances::reserve(&1, 10));
+			crate::StatusFor::<T>::insert(
+				unrequested,
+				RequestStatus::Unrequested { deposit: (1, 10), len: 128 },
+			);
+			let (_, requested) = preimage::<T>(256);
+			assert_ok!(Balances::reserve(&2, 20));
+			crate::StatusFor::<T>::insert(
+				requested,
+				RequestStatus::Requested { deposit: Some((2, 20)), count: 1, len: Some(256) },
+			);
+			// Only part of this deposit is still reserved.
+			let (_, partial) = preimage::<T>(512);
+			assert_ok!(Balances::reserve(&3, 5));
+			crate::StatusFor::<T>::insert(
+				partial,
+				RequestStatus::Unrequested { deposit: (3, 30), len: 512 },
+			);
+
+			v2::MigrateToHolds::<T, Balances>::on_runtime_upgrade();
+
+			assert_eq!(StorageVersion::get::<Pallet<T>>(), 2);
+			assert_eq!(Balances::balance_on_hold(&hold_reason, &1), 10);
+			assert_eq!(Balances::reserved_balance(1), 10);
+			assert_eq!(Balances::balance_on_hold(&hold_reason, &2), 20);
+			assert_eq!(
+				crate::StatusFor::<T>::get(partial),
+				Some(RequestStatus::Unrequested { deposit: (3, 5), len: 512 })
+			);
+			assert_eq!(Balances::balance_on_hold(&hold_reason, &3), 5);
+
+			// The deposits are released through the holds.
+			crate::PreimageFor::<T>::insert((unrequested, 128), preimage::<T>(128).0);
+			assert_ok!(Preimage::unnote_preimage(RuntimeOrigin::signed(1), unrequested));
+			assert_eq!(Balances::balance_on_hold(&hold_reason, &1), 0);
+			assert_eq!(Balances::reserved_balance(1), 0);
+		});
+	}
+
 	/// Returns a preimage with a given size and its hash.
 	fn preimage<T: Config>(
 		len: usize,
@@ -24,7 +24,7 @@ use crate::mock::*;
 
 use frame_support::{
 	assert_err, assert_noop, assert_ok, assert_storage_noop, bounded_vec,
-	traits::{Bounded, BoundedInline, Hash as PreimageHash},
+	traits::{fungible::InspectHold, Bounded, BoundedInline, Hash as PreimageHash},
 	StorageNoopGuard,
 };
 use pallet_balances::Error as BalancesError;
@@ -53,6 +53,8 @@ fn user_note_preimage_works() {
 		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(2), vec![1]));
 		assert_eq!(Balances::reserved_balance(2), 3);
 		assert_eq!(Balances::free_balance(2), 97);
+		let hold_reason = RuntimeHoldReason::Preimage(HoldReason::Preimage);
+		assert_eq!(Balances::balance_on_hold(&hold_reason, &2), 3);
 
 		let h = hashed([1]);
 		assert!(Preimage::have_preimage(&h));
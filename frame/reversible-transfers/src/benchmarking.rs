@@ -0,0 +1,132 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reversible transfers pallet benchmarking.
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{
+	assert_ok,
+	traits::{fungible::Mutate, Get},
+};
+use frame_system::RawOrigin as SystemOrigin;
+use sp_runtime::traits::One;
+
+use crate::Pallet as ReversibleTransfers;
+
+const SEED: u32 = 0;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+/// An amount comfortably above the existential deposit.
+fn unit<T: Config>() -> BalanceOf<T> {
+	T::Currency::minimum_balance().max(One::one()) * 100u32.into()
+}
+
+/// A funded caller that opted into reversible transfers, guarded by the returned guardian.
+fn reversible_caller<T: Config>() -> (T::AccountId, T::AccountId)
+where
+	T::Currency: Mutate<T::AccountId>,
+{
+	let caller: T::AccountId = whitelisted_caller();
+	let guardian: T::AccountId = account("guardian", 0, SEED);
+	assert_ok!(T::Currency::mint_into(&caller, unit::<T>() * 10u32.into()));
+	assert_ok!(ReversibleTransfers::<T>::set_reversibility(
+		SystemOrigin::Signed(caller.clone()).into(),
+		T::MaxDelay::get(),
+		Some(guardian.clone()),
+	));
+	(caller, guardian)
+}
+
+/// Schedule a transfer of one `unit` from `from`, returning its id.
+fn schedule<T: Config>(from: &T::AccountId) -> TransferId
+where
+	T::Currency: Mutate<T::AccountId>,
+{
+	let id = NextTransferId::<T>::get();
+	let dest: T::AccountId = account("dest", 0, SEED);
+	assert_ok!(ReversibleTransfers::<T>::schedule_transfer(
+		SystemOrigin::Signed(from.clone()).into(),
+		dest,
+		unit::<T>(),
+	));
+	id
+}
+
+benchmarks! {
+	where_clause { where T::Currency: Mutate<T::AccountId> }
+
+	set_reversibility {
+		let caller: T::AccountId = whitelisted_caller();
+		let guardian: T::AccountId = account("guardian", 0, SEED);
+		let delay = T::MaxDelay::get();
+	}: _(SystemOrigin::Signed(caller.clone()), delay, Some(guardian.clone()))
+	verify {
+		assert_last_event::<T>(
+			Event::ReversibilitySet { who: caller, delay, guardian: Some(guardian) }.into(),
+		);
+	}
+
+	remove_reversibility {
+		let (caller, _) = reversible_caller::<T>();
+	}: _(SystemOrigin::Signed(caller.clone()))
+	verify {
+		assert_last_event::<T>(Event::ReversibilityRemoved { who: caller }.into());
+	}
+
+	schedule_transfer {
+		let (caller, _) = reversible_caller::<T>();
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let amount = unit::<T>();
+		let execute_at = frame_system::Pallet::<T>::block_number() + T::MaxDelay::get();
+	}: _(SystemOrigin::Signed(caller.clone()), dest.clone(), amount)
+	verify {
+		assert_last_event::<T>(
+			Event::TransferScheduled { id: 0, from: caller, to: dest, amount, execute_at }.into(),
+		);
+	}
+
+	cancel {
+		let (caller, guardian) = reversible_caller::<T>();
+		let id = schedule::<T>(&caller);
+	}: _(SystemOrigin::Signed(guardian.clone()), id)
+	verify {
+		assert_last_event::<T>(Event::TransferCancelled { id, cancelled_by: guardian }.into());
+	}
+
+	execute_transfer {
+		let (caller, _) = reversible_caller::<T>();
+		let id = schedule::<T>(&caller);
+	}: _(SystemOrigin::Root, id)
+	verify {
+		assert_last_event::<T>(
+			Event::TransferExecuted {
+				id,
+				from: caller,
+				to: account("dest", 0, SEED),
+				amount: unit::<T>(),
+			}
+			.into(),
+		);
+	}
+
+	impl_benchmark_test_suite!(ReversibleTransfers, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,392 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Reversible Transfers Pallet
+//!
+//! Lets an account opt into delayed transfers, which can be called back during a challenge
+//! window.
+//!
+//! ## Overview
+//!
+//! An account opts in with [`Call::set_reversibility`], choosing a delay between
+//! `Config::MinDelay` and `Config::MaxDelay` and optionally a guardian account.
+//!
+//! A transfer made with [`Call::schedule_transfer`] does not move any funds right away: the amount
+//! is put on hold in the sender's account for [`HoldReason::Transfer`] and the payout is scheduled
+//! through `Config::Scheduler` for when the delay has passed. Until then the sender or the guardian
+//! the sender had when scheduling the transfer can call it back with [`Call::cancel`], which
+//! releases the hold.
+//!
+//! When the delay has passed the scheduler dispatches [`Call::execute_transfer`] as root, which
+//! releases the hold and transfers the funds to the recipient. If that transfer fails, e.g.
+//! because the amount would not create the recipient's account, the funds are left released with
+//! the sender.
+//!
+//! This pallet only delays the transfers made through it. A runtime that wants to protect its
+//! users' opted-in accounts from direct transfers as well can check [`Pallet::is_reversible`],
+//! e.g. in a call filter or signed extension.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo},
+	ensure,
+	traits::{
+		fungible::{Inspect, MutateHold, Transfer},
+		schedule::{v3::Named as ScheduleNamed, DispatchTime, LOWEST_PRIORITY},
+		Bounded, OriginTrait,
+	},
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	RuntimeDebug,
+};
+
+/// The identifier of a pending transfer.
+pub type TransferId = u64;
+
+pub(crate) type BalanceOf<T> =
+	<<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+pub(crate) type CallOf<T> = <T as Config>::RuntimeCall;
+pub(crate) type PalletsOriginOf<T> =
+	<<T as frame_system::Config>::RuntimeOrigin as OriginTrait>::PalletsOrigin;
+
+/// The settings of an account that opted into reversible transfers.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, MaxEncodedLen, RuntimeDebug, TypeInfo)]
+pub struct ReversibleAccount<AccountId, BlockNumber> {
+	/// How long every outgoing transfer is held before it is paid out.
+	pub delay: BlockNumber,
+	/// An account that may cancel pending transfers, besides the sender.
+	pub guardian: Option<AccountId>,
+}
+
+/// A transfer waiting for its challenge window to pass.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, MaxEncodedLen, RuntimeDebug, TypeInfo)]
+pub struct PendingTransfer<AccountId, Balance, BlockNumber> {
+	/// The sender, holding `amount` until the transfer executes or is cancelled.
+	pub from: AccountId,
+	/// The recipient.
+	pub to: AccountId,
+	/// The amount to transfer.
+	pub amount: Balance,
+	/// The block at which the transfer is scheduled to execute.
+	pub execute_at: BlockNumber,
+	/// The guardian of the sender when the transfer was scheduled, who may cancel it.
+	pub guardian: Option<AccountId>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The overarching call type, used to schedule the execution of transfers.
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = Self::RuntimeOrigin>
+			+ GetDispatchInfo
+			+ From<Call<Self>>
+			+ IsType<<Self as frame_system::Config>::RuntimeCall>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+
+		/// The currency whose transfers can be reversed.
+		type Currency: MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>
+			+ Transfer<Self::AccountId>;
+
+		/// The scheduler executing transfers once their delay has passed.
+		type Scheduler: ScheduleNamed<Self::BlockNumber, CallOf<Self>, PalletsOriginOf<Self>>;
+
+		/// The shortest delay an account can choose.
+		#[pallet::constant]
+		type MinDelay: Get<Self::BlockNumber>;
+
+		/// The longest delay an account can choose.
+		#[pallet::constant]
+		type MaxDelay: Get<Self::BlockNumber>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The accounts that opted into reversible transfers.
+	#[pallet::storage]
+	pub type ReversibleAccounts<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		ReversibleAccount<T::AccountId, T::BlockNumber>,
+	>;
+
+	/// The transfers that were neither executed nor cancelled yet.
+	#[pallet::storage]
+	pub type PendingTransfers<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		TransferId,
+		PendingTransfer<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	/// The id of the next scheduled transfer.
+	#[pallet::storage]
+	pub type NextTransferId<T: Config> = StorageValue<_, TransferId, ValueQuery>;
+
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The funds are held for a pending transfer until it executes or is cancelled.
+		Transfer,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An account opted into reversible transfers or changed its settings.
+		ReversibilitySet {
+			who: T::AccountId,
+			delay: T::BlockNumber,
+			guardian: Option<T::AccountId>,
+		},
+		/// An account opted out of reversible transfers. Its pending transfers are unaffected.
+		ReversibilityRemoved { who: T::AccountId },
+		/// A transfer was scheduled and its amount put on hold.
+		TransferScheduled {
+			id: TransferId,
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+			execute_at: T::BlockNumber,
+		},
+		/// A pending transfer was cancelled by `cancelled_by` and its amount released.
+		TransferCancelled { id: TransferId, cancelled_by: T::AccountId },
+		/// A pending transfer was paid out.
+		TransferExecuted {
+			id: TransferId,
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// A pending transfer could not be paid out. Its amount was released to the sender.
+		TransferFailed { id: TransferId, error: DispatchError },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account has not opted into reversible transfers.
+		NotReversible,
+		/// The delay is shorter than `MinDelay`.
+		DelayTooShort,
+		/// The delay is longer than `MaxDelay`.
+		DelayTooLong,
+		/// An account cannot be its own guardian.
+		GuardianIsSelf,
+		/// The amount to transfer is zero.
+		ZeroAmount,
+		/// There is no pending transfer with this id.
+		TransferNotFound,
+		/// Only the sender and the guardian the transfer was scheduled with can cancel it.
+		NotAllowed,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Opt into reversible transfers, or change the settings of an opted-in account.
+		///
+		/// Every transfer made through [`Call::schedule_transfer`] afterwards is held for `delay`
+		/// blocks, during which the origin or `guardian` can cancel it.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_reversibility())]
+		pub fn set_reversibility(
+			origin: OriginFor<T>,
+			delay: T::BlockNumber,
+			guardian: Option<T::AccountId>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(delay >= T::MinDelay::get() && !delay.is_zero(), Error::<T>::DelayTooShort);
+			ensure!(delay <= T::MaxDelay::get(), Error::<T>::DelayTooLong);
+			ensure!(guardian.as_ref() != Some(&who), Error::<T>::GuardianIsSelf);
+
+			ReversibleAccounts::<T>::insert(
+				&who,
+				ReversibleAccount { delay, guardian: guardian.clone() },
+			);
+			Self::deposit_event(Event::ReversibilitySet { who, delay, guardian });
+			Ok(())
+		}
+
+		/// Opt out of reversible transfers.
+		///
+		/// Transfers that are already pending still execute after their delay, and can still be
+		/// cancelled by the origin and the guardian they were scheduled with.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::remove_reversibility())]
+		pub fn remove_reversibility(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(ReversibleAccounts::<T>::contains_key(&who), Error::<T>::NotReversible);
+
+			ReversibleAccounts::<T>::remove(&who);
+			Self::deposit_event(Event::ReversibilityRemoved { who });
+			Ok(())
+		}
+
+		/// Transfer `amount` to `dest` once the origin's delay has passed.
+		///
+		/// The amount is put on hold until then. The origin must have opted into reversible
+		/// transfers. Its current guardian can cancel the transfer, even if the origin changes
+		/// its settings afterwards.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::schedule_transfer())]
+		pub fn schedule_transfer(
+			origin: OriginFor<T>,
+			dest: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			let settings = ReversibleAccounts::<T>::get(&from).ok_or(Error::<T>::NotReversible)?;
+
+			let id = NextTransferId::<T>::get();
+			let execute_at =
+				frame_system::Pallet::<T>::block_number().saturating_add(settings.delay);
+
+			T::Currency::hold(&HoldReason::Transfer.into(), &from, amount)?;
+			T::Scheduler::schedule_named(
+				Self::task_name(id),
+				DispatchTime::At(execute_at),
+				None,
+				LOWEST_PRIORITY,
+				frame_system::RawOrigin::Root.into(),
+				Self::bound_call(Call::execute_transfer { id }),
+			)?;
+
+			NextTransferId::<T>::put(id.wrapping_add(1));
+			PendingTransfers::<T>::insert(
+				id,
+				PendingTransfer {
+					from: from.clone(),
+					to: dest.clone(),
+					amount,
+					execute_at,
+					guardian: settings.guardian,
+				},
+			);
+			Self::deposit_event(Event::TransferScheduled {
+				id,
+				from,
+				to: dest,
+				amount,
+				execute_at,
+			});
+			Ok(())
+		}
+
+		/// Cancel the pending transfer `id` and release its amount to the sender.
+		///
+		/// The origin must be the sender or the guardian the sender had when scheduling the
+		/// transfer.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::cancel())]
+		pub fn cancel(origin: OriginFor<T>, id: TransferId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let pending = PendingTransfers::<T>::get(id).ok_or(Error::<T>::TransferNotFound)?;
+			ensure!(
+				who == pending.from || pending.guardian.as_ref() == Some(&who),
+				Error::<T>::NotAllowed
+			);
+
+			T::Scheduler::cancel_named(Self::task_name(id))?;
+			T::Currency::release(&HoldReason::Transfer.into(), &pending.from, pending.amount, true)?;
+			PendingTransfers::<T>::remove(id);
+
+			Self::deposit_event(Event::TransferCancelled { id, cancelled_by: who });
+			Ok(())
+		}
+
+		/// Pay out the pending transfer `id`.
+		///
+		/// Dispatched by the scheduler once the delay of the transfer has passed.
+		///
+		/// The hold is released before paying out, and stays released if the payout fails, e.g.
+		/// because the amount is below the existential deposit of a new recipient. In that case
+		/// the transfer is dropped and `TransferFailed` is emitted, leaving the funds free in the
+		/// sender's account. The call still succeeds, so the outcome is only visible through the
+		/// events.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::execute_transfer())]
+		pub fn execute_transfer(origin: OriginFor<T>, id: TransferId) -> DispatchResult {
+			ensure_root(origin)?;
+			let pending = PendingTransfers::<T>::take(id).ok_or(Error::<T>::TransferNotFound)?;
+			// Holds can only be transferred to existing accounts, so pay out from free balance.
+			let amount =
+				T::Currency::release(&HoldReason::Transfer.into(), &pending.from, pending.amount, true)?;
+
+			match T::Currency::transfer(&pending.from, &pending.to, amount, false) {
+				Ok(_) => Self::deposit_event(Event::TransferExecuted {
+					id,
+					from: pending.from,
+					to: pending.to,
+					amount,
+				}),
+				// The funds stay released, so they are not locked up forever.
+				Err(error) => Self::deposit_event(Event::TransferFailed { id, error }),
+			}
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether `who` opted into reversible transfers.
+		pub fn is_reversible(who: &T::AccountId) -> bool {
+			ReversibleAccounts::<T>::contains_key(who)
+		}
+
+		/// The name of the scheduler task executing transfer `id`.
+		fn task_name(id: TransferId) -> [u8; 32] {
+			(b"reversible-transfers", id).using_encoded(sp_io::hashing::blake2_256)
+		}
+
+		/// The scheduled call, small enough to always be stored inline.
+		fn bound_call(call: Call<T>) -> Bounded<CallOf<T>> {
+			let call: CallOf<T> = call.into();
+			Bounded::Inline(call.encode().try_into().expect(
+				"`execute_transfer` encodes to a few bytes, well below the inline limit; qed",
+			))
+		}
+	}
+}
@@ -0,0 +1,292 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the reversible transfers pallet.
+
+use super::*;
+use crate::mock::*;
+
+use frame_support::{assert_noop, assert_ok, traits::fungible::InspectHold};
+use sp_runtime::traits::BadOrigin;
+
+fn events() -> Vec<Event<Test>> {
+	let result = System::events()
+		.into_iter()
+		.filter_map(|r| {
+			if let RuntimeEvent::ReversibleTransfers(inner) = r.event {
+				Some(inner)
+			} else {
+				None
+			}
+		})
+		.collect();
+
+	System::reset_events();
+
+	result
+}
+
+fn on_hold(who: u64) -> u64 {
+	Balances::balance_on_hold(&HoldReason::Transfer.into(), &who)
+}
+
+#[test]
+fn set_reversibility_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 1, None),
+			Error::<Test>::DelayTooShort
+		);
+		assert_noop!(
+			ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 101, None),
+			Error::<Test>::DelayTooLong
+		);
+		assert_noop!(
+			ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, Some(1)),
+			Error::<Test>::GuardianIsSelf
+		);
+		assert!(!ReversibleTransfers::is_reversible(&1));
+
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, Some(3)));
+		assert!(ReversibleTransfers::is_reversible(&1));
+		assert_eq!(
+			ReversibleAccounts::<Test>::get(1),
+			Some(ReversibleAccount { delay: 10, guardian: Some(3) })
+		);
+
+		assert_ok!(ReversibleTransfers::remove_reversibility(RuntimeOrigin::signed(1)));
+		assert!(!ReversibleTransfers::is_reversible(&1));
+		assert_noop!(
+			ReversibleTransfers::remove_reversibility(RuntimeOrigin::signed(1)),
+			Error::<Test>::NotReversible
+		);
+
+		assert_eq!(
+			events(),
+			[
+				Event::ReversibilitySet { who: 1, delay: 10, guardian: Some(3) },
+				Event::ReversibilityRemoved { who: 1 },
+			]
+		);
+	});
+}
+
+#[test]
+fn schedule_transfer_requires_opt_in() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 100),
+			Error::<Test>::NotReversible
+		);
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, None));
+		assert_noop!(
+			ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 0),
+			Error::<Test>::ZeroAmount
+		);
+		assert_noop!(
+			ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 2_000),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn transfer_executes_after_delay() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, None));
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 100));
+
+		assert_eq!(on_hold(1), 100);
+		assert_eq!(Balances::free_balance(1), 900);
+		assert_eq!(
+			PendingTransfers::<Test>::get(0),
+			Some(PendingTransfer { from: 1, to: 2, amount: 100, execute_at: 11, guardian: None })
+		);
+		assert_eq!(NextTransferId::<Test>::get(), 1);
+
+		run_to_block(10);
+		assert_eq!(on_hold(1), 100);
+		assert_eq!(Balances::free_balance(2), 1_000);
+
+		run_to_block(11);
+		assert_eq!(on_hold(1), 0);
+		assert_eq!(Balances::free_balance(1), 900);
+		assert_eq!(Balances::free_balance(2), 1_100);
+		assert!(PendingTransfers::<Test>::get(0).is_none());
+
+		assert_eq!(
+			events(),
+			[
+				Event::ReversibilitySet { who: 1, delay: 10, guardian: None },
+				Event::TransferScheduled { id: 0, from: 1, to: 2, amount: 100, execute_at: 11 },
+				Event::TransferExecuted { id: 0, from: 1, to: 2, amount: 100 },
+			]
+		);
+	});
+}
+
+#[test]
+fn sender_and_guardian_can_cancel() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, Some(3)));
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 100));
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 200));
+		assert_eq!(on_hold(1), 300);
+		System::reset_events();
+
+		// The recipient cannot cancel.
+		assert_noop!(
+			ReversibleTransfers::cancel(RuntimeOrigin::signed(2), 0),
+			Error::<Test>::NotAllowed
+		);
+		assert_noop!(
+			ReversibleTransfers::cancel(RuntimeOrigin::signed(1), 2),
+			Error::<Test>::TransferNotFound
+		);
+
+		assert_ok!(ReversibleTransfers::cancel(RuntimeOrigin::signed(1), 0));
+		assert_ok!(ReversibleTransfers::cancel(RuntimeOrigin::signed(3), 1));
+		assert_eq!(on_hold(1), 0);
+		assert_eq!(Balances::free_balance(1), 1_000);
+		assert_noop!(
+			ReversibleTransfers::cancel(RuntimeOrigin::signed(1), 0),
+			Error::<Test>::TransferNotFound
+		);
+
+		// Nothing is paid out once the delay has passed.
+		run_to_block(20);
+		assert_eq!(Balances::free_balance(2), 1_000);
+		assert_eq!(
+			events(),
+			[
+				Event::TransferCancelled { id: 0, cancelled_by: 1 },
+				Event::TransferCancelled { id: 1, cancelled_by: 3 },
+			]
+		);
+	});
+}
+
+#[test]
+fn opting_out_keeps_pending_transfers() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, Some(3)));
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 100));
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 200));
+		assert_ok!(ReversibleTransfers::remove_reversibility(RuntimeOrigin::signed(1)));
+
+		// The guardian can still cancel the transfers scheduled while it was guarding.
+		assert_ok!(ReversibleTransfers::cancel(RuntimeOrigin::signed(3), 1));
+		assert_eq!(on_hold(1), 100);
+
+		// The other transfer still executes.
+		run_to_block(11);
+		assert_eq!(Balances::free_balance(2), 1_100);
+		assert_eq!(on_hold(1), 0);
+	});
+}
+
+#[test]
+fn guardian_is_fixed_when_scheduling() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, Some(3)));
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 100));
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, Some(2)));
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 3, 200));
+		assert_eq!(PendingTransfers::<Test>::get(0).unwrap().guardian, Some(3));
+		assert_eq!(PendingTransfers::<Test>::get(1).unwrap().guardian, Some(2));
+
+		// Changing the guardian doesn't hand the pending transfers over to the new one.
+		assert_noop!(
+			ReversibleTransfers::cancel(RuntimeOrigin::signed(2), 0),
+			Error::<Test>::NotAllowed
+		);
+		assert_noop!(
+			ReversibleTransfers::cancel(RuntimeOrigin::signed(3), 1),
+			Error::<Test>::NotAllowed
+		);
+		assert_ok!(ReversibleTransfers::cancel(RuntimeOrigin::signed(3), 0));
+		assert_ok!(ReversibleTransfers::cancel(RuntimeOrigin::signed(2), 1));
+		assert_eq!(on_hold(1), 0);
+	});
+}
+
+#[test]
+fn failed_transfer_releases_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, None));
+		// Below the existential deposit, so account 4 cannot be created.
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 4, 5));
+		System::reset_events();
+
+		run_to_block(11);
+		assert_eq!(on_hold(1), 0);
+		assert_eq!(Balances::free_balance(1), 1_000);
+		assert_eq!(Balances::free_balance(4), 0);
+		assert_eq!(
+			events(),
+			[Event::TransferFailed {
+				id: 0,
+				error: pallet_balances::Error::<Test>::ExistentialDeposit.into()
+			}]
+		);
+	});
+}
+
+#[test]
+fn failed_payout_still_succeeds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, Some(3)));
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 4, 5));
+		System::reset_events();
+
+		// The call succeeds even though nothing is paid out.
+		assert_ok!(ReversibleTransfers::execute_transfer(RuntimeOrigin::root(), 0));
+		assert_eq!(on_hold(1), 0);
+		assert_eq!(Balances::free_balance(1), 1_000);
+		assert_eq!(
+			events(),
+			[Event::TransferFailed {
+				id: 0,
+				error: pallet_balances::Error::<Test>::ExistentialDeposit.into()
+			}]
+		);
+
+		// The transfer is gone, so there is nothing left to cancel or execute.
+		assert_noop!(
+			ReversibleTransfers::cancel(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::TransferNotFound
+		);
+		assert_noop!(
+			ReversibleTransfers::execute_transfer(RuntimeOrigin::root(), 0),
+			Error::<Test>::TransferNotFound
+		);
+	});
+}
+
+#[test]
+fn execute_transfer_requires_root() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ReversibleTransfers::set_reversibility(RuntimeOrigin::signed(1), 10, None));
+		assert_ok!(ReversibleTransfers::schedule_transfer(RuntimeOrigin::signed(1), 2, 100));
+
+		assert_noop!(ReversibleTransfers::execute_transfer(RuntimeOrigin::signed(2), 0), BadOrigin);
+		assert_noop!(
+			ReversibleTransfers::execute_transfer(RuntimeOrigin::root(), 1),
+			Error::<Test>::TransferNotFound
+		);
+	});
+}
@@ -0,0 +1,129 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for pallet_reversible_transfers
+//!
+//! These are conservative estimates in the shape of the benchmark template and must be replaced by
+//! running the benchmarks on reference hardware:
+//!
+//! ./target/production/substrate benchmark pallet --chain=dev --steps=50 --repeat=20
+//! --pallet=pallet_reversible_transfers --extrinsic=* --execution=wasm --wasm-execution=compiled
+//! --heap-pages=4096 --output=./frame/reversible-transfers/src/weights.rs
+//! --header=./HEADER-APACHE2 --template=./.maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_reversible_transfers.
+pub trait WeightInfo {
+	fn set_reversibility() -> Weight;
+	fn remove_reversibility() -> Weight;
+	fn schedule_transfer() -> Weight;
+	fn cancel() -> Weight;
+	fn execute_transfer() -> Weight;
+}
+
+/// Weights for pallet_reversible_transfers using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: ReversibleTransfers ReversibleAccounts (r:0 w:1)
+	fn set_reversibility() -> Weight {
+		Weight::from_ref_time(25_000_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: ReversibleTransfers ReversibleAccounts (r:1 w:1)
+	fn remove_reversibility() -> Weight {
+		Weight::from_ref_time(25_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: ReversibleTransfers ReversibleAccounts (r:1 w:0)
+	// Storage: ReversibleTransfers NextTransferId (r:1 w:1)
+	// Storage: Balances Holds (r:1 w:1)
+	// Storage: Scheduler Lookup (r:1 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	// Storage: ReversibleTransfers PendingTransfers (r:0 w:1)
+	fn schedule_transfer() -> Weight {
+		Weight::from_ref_time(70_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: ReversibleTransfers PendingTransfers (r:1 w:1)
+	// Storage: Scheduler Lookup (r:1 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	// Storage: Balances Holds (r:1 w:1)
+	fn cancel() -> Weight {
+		Weight::from_ref_time(62_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: ReversibleTransfers PendingTransfers (r:1 w:1)
+	// Storage: Balances Holds (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn execute_transfer() -> Weight {
+		Weight::from_ref_time(60_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: ReversibleTransfers ReversibleAccounts (r:0 w:1)
+	fn set_reversibility() -> Weight {
+		Weight::from_ref_time(25_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: ReversibleTransfers ReversibleAccounts (r:1 w:1)
+	fn remove_reversibility() -> Weight {
+		Weight::from_ref_time(25_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: ReversibleTransfers ReversibleAccounts (r:1 w:0)
+	// Storage: ReversibleTransfers NextTransferId (r:1 w:1)
+	// Storage: Balances Holds (r:1 w:1)
+	// Storage: Scheduler Lookup (r:1 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	// Storage: ReversibleTransfers PendingTransfers (r:0 w:1)
+	fn schedule_transfer() -> Weight {
+		Weight::from_ref_time(70_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+	// Storage: ReversibleTransfers PendingTransfers (r:1 w:1)
+	// Storage: Scheduler Lookup (r:1 w:1)
+	// Storage: Scheduler Agenda (r:1 w:1)
+	// Storage: Balances Holds (r:1 w:1)
+	fn cancel() -> Weight {
+		Weight::from_ref_time(62_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	// Storage: ReversibleTransfers PendingTransfers (r:1 w:1)
+	// Storage: Balances Holds (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn execute_transfer() -> Weight {
+		Weight::from_ref_time(60_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+}
@@ -0,0 +1,117 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::construct_runtime::Pallet;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::str::FromStr;
+use syn::Ident;
+
+/// Generate the `Runtime{name}` enum aggregating the `name` composite enums of the pallets,
+/// e.g. `RuntimeHoldReason` for the pallets with the `HoldReason` part.
+pub fn expand_outer_composite(
+	pallet_decls: &[Pallet],
+	scrate: &TokenStream,
+	name: &str,
+) -> TokenStream {
+	let outer_ident = Ident::new(&format!("Runtime{}", name), Span::call_site());
+	let check_mod =
+		Ident::new(&format!("__substrate_{}_check", to_snake_case(name)), Span::call_site());
+	let check_macro =
+		Ident::new(&format!("is_{}_part_defined", to_snake_case(name)), Span::call_site());
+	let composite_ident = Ident::new(name, Span::call_site());
+
+	let mut variant_defs = TokenStream::new();
+	let mut conversions = TokenStream::new();
+	let mut query_part_macros = Vec::new();
+	let mut variant_counts = Vec::new();
+
+	for pallet_decl in pallet_decls.iter().filter(|decl| decl.exists_part(name)) {
+		let pallet_name = &pallet_decl.name;
+		let path = &pallet_decl.path;
+		let index = pallet_decl.index;
+		let attr = pallet_decl.cfg_pattern.iter().fold(TokenStream::new(), |acc, pattern| {
+			let attr = TokenStream::from_str(&format!("#[cfg({})]", pattern.original()))
+				.expect("was successfully parsed before; qed");
+			quote! {
+				#acc
+				#attr
+			}
+		});
+
+		variant_defs.extend(quote! {
+			#attr
+			#[codec(index = #index)]
+			#pallet_name(#path::#composite_ident),
+		});
+		conversions.extend(quote! {
+			#attr
+			impl From<#path::#composite_ident> for #outer_ident {
+				fn from(reason: #path::#composite_ident) -> Self {
+					#outer_ident::#pallet_name(reason)
+				}
+			}
+		});
+		query_part_macros.push(quote! {
+			#path::#check_mod::#check_macro!(#pallet_name);
+		});
+		variant_counts.push(quote! {
+			#attr
+			{
+				count += <#path::#composite_ident as #scrate::traits::VariantCount>::VARIANT_COUNT;
+			}
+		});
+	}
+
+	quote! {
+		#( #query_part_macros )*
+
+		#[derive(
+			Copy, Clone, PartialEq, Eq, PartialOrd, Ord,
+			#scrate::codec::Encode,
+			#scrate::codec::Decode,
+			#scrate::codec::MaxEncodedLen,
+			#scrate::scale_info::TypeInfo,
+			#scrate::RuntimeDebug,
+		)]
+		pub enum #outer_ident {
+			#variant_defs
+		}
+
+		impl #scrate::traits::VariantCount for #outer_ident {
+			const VARIANT_COUNT: u32 = {
+				#[allow(unused_mut)]
+				let mut count = 0u32;
+				#( #variant_counts )*
+				count
+			};
+		}
+
+		#conversions
+	}
+}
+
+/// Convert a part name such as `HoldReason` to `hold_reason`.
+fn to_snake_case(name: &str) -> String {
+	let mut snake = String::new();
+	for (i, c) in name.chars().enumerate() {
+		if c.is_uppercase() && i != 0 {
+			snake.push('_');
+		}
+		snake.push(c.to_ascii_lowercase());
+	}
+	snake
+}
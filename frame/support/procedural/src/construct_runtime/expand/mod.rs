@@ -16,17 +16,21 @@
 // limitations under the License
 
 mod call;
+mod composite;
 mod config;
 mod event;
 mod inherent;
 mod metadata;
 mod origin;
+mod task;
 mod unsigned;
 
 pub use call::expand_outer_dispatch;
+pub use composite::expand_outer_composite;
 pub use config::expand_outer_config;
 pub use event::expand_outer_event;
 pub use inherent::expand_outer_inherent;
 pub use metadata::expand_runtime_metadata;
 pub use origin::expand_outer_origin;
+pub use task::expand_outer_task;
 pub use unsigned::expand_outer_validate_unsigned;
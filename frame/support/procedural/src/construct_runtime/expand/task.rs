@@ -0,0 +1,143 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::construct_runtime::Pallet;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::str::FromStr;
+use syn::Ident;
+
+/// Generate the `RuntimeTask` enum aggregating the tasks of the pallets with the `Task` part.
+pub fn expand_outer_task(
+	runtime: &Ident,
+	pallet_decls: &[Pallet],
+	scrate: &TokenStream,
+) -> TokenStream {
+	let mut variant_defs = TokenStream::new();
+	let mut task_conversions = TokenStream::new();
+	let mut query_task_part_macros = Vec::new();
+	let mut pallet_names = Vec::new();
+	let mut pallet_tasks = Vec::new();
+	let mut pallet_attrs = Vec::new();
+
+	for pallet_decl in pallet_decls.iter().filter(|decl| decl.exists_part("Task")) {
+		let name = &pallet_decl.name;
+		let path = &pallet_decl.path;
+		let index = pallet_decl.index;
+		let attr = pallet_decl.cfg_pattern.iter().fold(TokenStream::new(), |acc, pattern| {
+			let attr = TokenStream::from_str(&format!("#[cfg({})]", pattern.original()))
+				.expect("was successfully parsed before; qed");
+			quote! {
+				#acc
+				#attr
+			}
+		});
+		let pallet_task = match pallet_decl.instance.as_ref() {
+			Some(inst) => quote!(#path::Task<#runtime, #path::#inst>),
+			None => quote!(#path::Task<#runtime>),
+		};
+
+		variant_defs.extend(quote! {
+			#attr
+			#[codec(index = #index)]
+			#name(#pallet_task),
+		});
+		task_conversions.extend(quote! {
+			#attr
+			impl From<#pallet_task> for RuntimeTask {
+				fn from(task: #pallet_task) -> Self {
+					RuntimeTask::#name(task)
+				}
+			}
+		});
+		query_task_part_macros.push(quote! {
+			#path::__substrate_task_check::is_task_part_defined!(#name);
+		});
+		pallet_names.push(name);
+		pallet_tasks.push(pallet_task);
+		pallet_attrs.push(attr);
+	}
+
+	quote! {
+		#( #query_task_part_macros )*
+
+		#[derive(
+			Clone, PartialEq, Eq,
+			#scrate::codec::Encode,
+			#scrate::codec::Decode,
+			#scrate::scale_info::TypeInfo,
+			#scrate::RuntimeDebug,
+		)]
+		pub enum RuntimeTask {
+			#variant_defs
+		}
+
+		impl #scrate::traits::Task for RuntimeTask {
+			fn iter() -> #scrate::sp_std::boxed::Box<dyn Iterator<Item = Self>> {
+				#[allow(unused_mut)]
+				let mut iter: #scrate::sp_std::boxed::Box<dyn Iterator<Item = Self>> =
+					#scrate::sp_std::boxed::Box::new(#scrate::sp_std::iter::empty());
+				#(
+					#pallet_attrs
+					{
+						iter = #scrate::sp_std::boxed::Box::new(iter.chain(
+							<#pallet_tasks as #scrate::traits::Task>::iter().map(RuntimeTask::#pallet_names)
+						));
+					}
+				)*
+				iter
+			}
+
+			fn is_valid(&self) -> bool {
+				match *self {
+					#(
+						#pallet_attrs
+						RuntimeTask::#pallet_names(ref task) => task.is_valid(),
+					)*
+				}
+			}
+
+			fn run(&self) -> #scrate::dispatch::DispatchResult {
+				match *self {
+					#(
+						#pallet_attrs
+						RuntimeTask::#pallet_names(ref task) => task.run(),
+					)*
+				}
+			}
+
+			fn weight(&self) -> #scrate::weights::Weight {
+				match *self {
+					#(
+						#pallet_attrs
+						RuntimeTask::#pallet_names(ref task) => task.weight(),
+					)*
+				}
+			}
+
+			fn task_index(&self) -> u32 {
+				match *self {
+					#(
+						#pallet_attrs
+						RuntimeTask::#pallet_names(ref task) => task.task_index(),
+					)*
+				}
+			}
+		}
+
+		#task_conversions
+	}
+}
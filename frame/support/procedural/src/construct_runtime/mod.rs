@@ -265,6 +265,9 @@ fn construct_runtime_final_expansion(
 	let inherent =
 		expand::expand_outer_inherent(&name, &block, &unchecked_extrinsic, &pallets, &scrate);
 	let validate_unsigned = expand::expand_outer_validate_unsigned(&name, &pallets, &scrate);
+	let outer_task = expand::expand_outer_task(&name, &pallets, &scrate);
+	let hold_reason = expand::expand_outer_composite(&pallets, &scrate, "HoldReason");
+	let freeze_reason = expand::expand_outer_composite(&pallets, &scrate, "FreezeReason");
 	let integrity_test = decl_integrity_test(&scrate);
 	let static_assertions = decl_static_assertions(&name, &pallets, &scrate);
 
@@ -307,6 +310,12 @@ fn construct_runtime_final_expansion(
 
 		#validate_unsigned
 
+		#outer_task
+
+		#hold_reason
+
+		#freeze_reason
+
 		#integrity_test
 
 		#static_assertions
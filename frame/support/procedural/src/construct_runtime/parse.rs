@@ -38,6 +38,9 @@ mod keyword {
 	syn::custom_keyword!(Origin);
 	syn::custom_keyword!(Inherent);
 	syn::custom_keyword!(ValidateUnsigned);
+	syn::custom_keyword!(Task);
+	syn::custom_keyword!(HoldReason);
+	syn::custom_keyword!(FreezeReason);
 	syn::custom_keyword!(exclude_parts);
 	syn::custom_keyword!(use_parts);
 }
@@ -370,6 +373,9 @@ pub enum PalletPartKeyword {
 	Origin(keyword::Origin),
 	Inherent(keyword::Inherent),
 	ValidateUnsigned(keyword::ValidateUnsigned),
+	Task(keyword::Task),
+	HoldReason(keyword::HoldReason),
+	FreezeReason(keyword::FreezeReason),
 }
 
 impl Parse for PalletPartKeyword {
@@ -392,6 +398,12 @@ impl Parse for PalletPartKeyword {
 			Ok(Self::Inherent(input.parse()?))
 		} else if lookahead.peek(keyword::ValidateUnsigned) {
 			Ok(Self::ValidateUnsigned(input.parse()?))
+		} else if lookahead.peek(keyword::Task) {
+			Ok(Self::Task(input.parse()?))
+		} else if lookahead.peek(keyword::HoldReason) {
+			Ok(Self::HoldReason(input.parse()?))
+		} else if lookahead.peek(keyword::FreezeReason) {
+			Ok(Self::FreezeReason(input.parse()?))
 		} else {
 			Err(lookahead.error())
 		}
@@ -410,6 +422,9 @@ impl PalletPartKeyword {
 			Self::Origin(_) => "Origin",
 			Self::Inherent(_) => "Inherent",
 			Self::ValidateUnsigned(_) => "ValidateUnsigned",
+			Self::Task(_) => "Task",
+			Self::HoldReason(_) => "HoldReason",
+			Self::FreezeReason(_) => "FreezeReason",
 		}
 	}
 
@@ -435,6 +450,9 @@ impl Spanned for PalletPartKeyword {
 			Self::Origin(inner) => inner.span(),
 			Self::Inherent(inner) => inner.span(),
 			Self::ValidateUnsigned(inner) => inner.span(),
+			Self::Task(inner) => inner.span(),
+			Self::HoldReason(inner) => inner.span(),
+			Self::FreezeReason(inner) => inner.span(),
 		}
 	}
 }
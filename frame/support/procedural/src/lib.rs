@@ -356,6 +356,10 @@ pub fn decl_storage(input: TokenStream) -> TokenStream {
 ///     storage with `GenesisConfig`
 ///   - `Inherent` - If the pallet provides/can check inherents.
 ///   - `ValidateUnsigned` - If the pallet validates unsigned extrinsics.
+///   - `Task` - If the pallet defines tasks with `#[pallet::tasks]`.
+///   - `HoldReason` and `FreezeReason` - If the pallet defines the corresponding
+///     `#[pallet::composite_enum]`. They are aggregated in `RuntimeHoldReason` and
+///     `RuntimeFreezeReason`, which are generated even if no pallet has the part.
 ///
 ///   It is important to list these parts here to export them correctly in the metadata or to make
 /// the pallet usable in the runtime.
@@ -780,6 +784,23 @@ pub fn storage_version(_: TokenStream, _: TokenStream) -> TokenStream {
 	pallet_macro_stub()
 }
 
+/// To bound the number of bytes occupied by all the size tracked storages of the pallet, such
+/// as `SizeTrackedStorageMap`, use the attribute `#[pallet::max_storage_bytes($type)]` with a
+/// type implementing `Get<Option<u64>>`, e.g.:
+///
+/// ```ignore
+/// #[pallet::pallet]
+/// #[pallet::max_storage_bytes(T::MaxStorageBytes)]
+/// pub struct Pallet<T>(_);
+/// ```
+///
+/// Writes to a size tracked storage which would exceed it are rejected. If not present, the
+/// size tracked storages of the pallet are only bounded by their own `MaxBytes`.
+#[proc_macro_attribute]
+pub fn max_storage_bytes(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
 /// The `#[pallet::hooks]` attribute allows you to specify a `Hooks` implementation for
 /// `Pallet` that specifies pallet-specific logic.
 ///
@@ -897,6 +918,141 @@ pub fn extra_constants(_: TokenStream, _: TokenStream) -> TokenStream {
 	pallet_macro_stub()
 }
 
+/// Allows you to define read-only functions which clients can call through the single
+/// `RuntimeViewFunction` runtime API, without a runtime API per pallet.
+///
+/// Item must be defined as:
+///
+/// ```ignore
+/// #[pallet::view_functions]
+/// impl<T: Config> Pallet<T> where $optional_where_clause {
+/// 	/// $some_doc
+/// 	$vis fn $fn_name($some_arg: $some_type, ...) -> $some_return_type {
+/// 		...
+/// 	}
+/// 	...
+/// }
+/// ```
+/// I.e. a regular rust `impl` block with some optional where clause and functions with 0
+/// generics, arguments which are plain identifiers, and some return type. The argument types
+/// must implement `Encode`, `Decode` and `TypeInfo`, the return type `Encode` and `TypeInfo`.
+///
+/// ## Macro expansion
+///
+/// For each function the macro generates a struct named after the function in upper camel
+/// case (e.g. `GetValue` for `get_value`) holding its arguments. It implements
+/// `frame_support::view_functions::ViewFunction`, whose id is made of the hash of the pallet
+/// name and the hash of the function name and of the encoding of its argument and return types,
+/// see `frame_support::view_functions::view_function_id_suffix`.
+///
+/// The macro implements `DispatchViewFunction` and `ViewFunctionsMetadata` on `Pallet`, so
+/// that the runtime can implement the `RuntimeViewFunction` runtime API with
+/// `AllPalletsWithSystem`, see `frame_support::view_functions`.
+#[proc_macro_attribute]
+pub fn view_functions(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Allows you to define tasks: work which isn't bound to a block, run when a block has spare
+/// weight or submitted as an unsigned extrinsic.
+///
+/// Item must be defined as:
+///
+/// ```ignore
+/// #[pallet::tasks]
+/// impl<T: Config> Pallet<T> where $optional_where_clause {
+/// 	/// $some_doc
+/// 	#[pallet::task_index($idx)] // optional
+/// 	#[pallet::task_list($iter_expr)]
+/// 	#[pallet::task_condition($closure)]
+/// 	#[pallet::task_weight($weight_expr)]
+/// 	$vis fn $fn_name($some_arg: $some_type, ...) -> DispatchResult {
+/// 		...
+/// 	}
+/// 	...
+/// }
+/// ```
+/// I.e. a regular rust `impl` block with some optional where clause and functions with 0
+/// generics and arguments which are plain identifiers, returning `DispatchResult`. For each
+/// function:
+/// * `task_list` is an iterator over the arguments of the tasks to run, as a tuple if the
+///   function has several arguments,
+/// * `task_condition` is a closure taking a reference to each argument and returning whether
+///   the task is still valid,
+/// * `task_weight` is the weight of the task, with a reference to each argument in scope,
+/// * `task_index` is the index of the task in the encoding, like `call_index` for calls.
+///
+/// ## Macro expansion
+///
+/// The macro creates an enum `Task` with one variant per function, holding its arguments, and
+/// implements `frame_support::traits::Task` on it. `construct_runtime` aggregates the `Task`
+/// enums of the pallets into `RuntimeTask`, the `frame_system::Config::RuntimeTask` of the
+/// runtime.
+///
+/// `frame_executive` runs the valid tasks of `RuntimeTask::iter()` while there is weight left
+/// at the end of a block, and anyone can submit a valid task as an unsigned
+/// `frame_system::Call::do_task`.
+#[proc_macro_attribute]
+pub fn tasks(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Allows you to define an enum which is aggregated with the ones of the other pallets by
+/// `construct_runtime`, such as the reasons for which the pallet holds or freezes funds.
+///
+/// Item must be defined as:
+///
+/// ```ignore
+/// #[pallet::composite_enum]
+/// pub enum HoldReason {
+/// 	/// $some_doc
+/// 	$SomeVariant,
+/// 	...
+/// }
+/// ```
+/// I.e. a public enum without generics and with unit variants, named either `HoldReason` or
+/// `FreezeReason`.
+///
+/// ## Macro expansion
+///
+/// The macro derives `Copy`, `Clone`, `PartialEq`, `Eq`, `PartialOrd`, `Ord`, `Encode`,
+/// `Decode`, `MaxEncodedLen`, `TypeInfo` and `RuntimeDebug` on the enum and implements
+/// `frame_support::traits::VariantCount` on it. `construct_runtime` aggregates the enums of the
+/// pallets into `RuntimeHoldReason` and `RuntimeFreezeReason`, to be used as the
+/// `HoldIdentifier` and `FreezeIdentifier` of `pallet_balances`, with
+/// `frame_support::traits::VariantCountOf` as `MaxHolds` and `MaxFreezes`.
+#[proc_macro_attribute]
+pub fn composite_enum(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Gives the index of a task in the `Task` enum, see `#[pallet::tasks]`.
+///
+/// Tasks without this attribute are given the index of the previous task plus one, starting at
+/// 0.
+#[proc_macro_attribute]
+pub fn task_index(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Gives the iterator over the arguments of the tasks to run, see `#[pallet::tasks]`.
+#[proc_macro_attribute]
+pub fn task_list(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Gives the closure checking that a task is valid, see `#[pallet::tasks]`.
+#[proc_macro_attribute]
+pub fn task_condition(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Gives the weight of a task, see `#[pallet::tasks]`.
+#[proc_macro_attribute]
+pub fn task_weight(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
 /// The `#[pallet::error]` attribute allows you to define an error enum that will be returned
 /// from the dispatchable when an error occurs. The information for this error type is then
 /// stored in metadata.
@@ -1027,7 +1183,10 @@ pub fn generate_deposit(_: TokenStream, _: TokenStream) -> TokenStream {
 /// * `StorageMap` expects `Hasher`, `Key`, `Value` and optionally `QueryKind` and `OnEmpty`,
 /// * `CountedStorageMap` expects `Hasher`, `Key`, `Value` and optionally `QueryKind` and `OnEmpty`,
 /// * `StorageDoubleMap` expects `Hasher1`, `Key1`, `Hasher2`, `Key2`, `Value` and optionally
-///   `QueryKind` and `OnEmpty`.
+///   `QueryKind` and `OnEmpty`,
+/// * `SizeTrackedStorageValue`, `SizeTrackedStorageMap`, `SizeTrackedStorageDoubleMap` and
+///   `SizeTrackedStorageNMap` expect the same generics as their counterpart and optionally
+///   `MaxBytes`.
 ///
 /// For unnamed generic arguments: Their first generic must be `_` as it is replaced by the
 /// macro and other generic must declared as a normal generic type declaration.
@@ -1043,6 +1202,11 @@ pub fn generate_deposit(_: TokenStream, _: TokenStream) -> TokenStream {
 /// the pallet "MyExample" then the storage `type Foo<T> = CountedStorageaMap<...>` will store
 /// its counter at the prefix: `Twox128(b"MyExample") ++ Twox128(b"CounterForFoo")`.
 ///
+/// For the size tracked variants, the `Prefix` also implements `SizeTrackedStorageInstance`
+/// with a `SizePrefix` prepended with `"ByteSizeFor"`, where the number of bytes occupied by the
+/// storage is stored, and a `PalletSizePrefix` named `"StorageByteSize"`, where the number of
+/// bytes occupied by all the size tracked storages of the pallet is stored.
+///
 /// E.g:
 ///
 /// ```ignore
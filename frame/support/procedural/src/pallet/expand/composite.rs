@@ -0,0 +1,112 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	pallet::{parse::composite::CompositeKeyword, Def},
+	COUNTER,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Ident};
+
+/// * Derive the traits required by `construct_runtime` on the composite enums and implement
+///   `VariantCount` on them.
+/// * Generate the macros checking the `HoldReason` and `FreezeReason` parts of
+///   `construct_runtime`.
+pub fn expand_composites(def: &mut Def) -> TokenStream {
+	let frame_support = def.frame_support.clone();
+	let mut expand = TokenStream::new();
+
+	for keyword in [CompositeKeyword::HoldReason, CompositeKeyword::FreezeReason] {
+		expand.extend(expand_composite_check(def, keyword));
+	}
+
+	for composite in &def.composites {
+		let item =
+			&mut def.item.content.as_mut().expect("Checked by def parser").1[composite.index];
+		let item = if let syn::Item::Enum(item) = item {
+			item
+		} else {
+			unreachable!("Checked by composite parser")
+		};
+
+		item.attrs.push(syn::parse_quote!(
+			#[derive(
+				Copy, Clone, PartialEq, Eq, PartialOrd, Ord,
+				#frame_support::codec::Encode,
+				#frame_support::codec::Decode,
+				#frame_support::codec::MaxEncodedLen,
+				#frame_support::scale_info::TypeInfo,
+				#frame_support::RuntimeDebug,
+			)]
+		));
+
+		let ident = &item.ident;
+		let variant_count = composite.variant_count;
+		expand.extend(quote::quote_spanned!(composite.attr_span =>
+			impl #frame_support::traits::VariantCount for #ident {
+				const VARIANT_COUNT: u32 = #variant_count;
+			}
+		));
+	}
+
+	expand
+}
+
+/// Generate the macro checking the `construct_runtime` part of one kind of composite enum.
+fn expand_composite_check(def: &Def, keyword: CompositeKeyword) -> TokenStream {
+	let count = COUNTER.with(|counter| counter.borrow_mut().inc());
+	let (check_mod, check_macro) = match keyword {
+		CompositeKeyword::HoldReason =>
+			(quote!(__substrate_hold_reason_check), quote!(is_hold_reason_part_defined)),
+		CompositeKeyword::FreezeReason =>
+			(quote!(__substrate_freeze_reason_check), quote!(is_freeze_reason_part_defined)),
+	};
+	let name = keyword.name();
+	let macro_ident = Ident::new(
+		&format!("__is_{}_part_defined_{}", name.to_lowercase(), count),
+		def.item.span(),
+	);
+
+	let maybe_compile_error = if def.composites.iter().all(|c| c.composite_keyword != keyword) {
+		let msg = format!(
+			"` does not have #[pallet::composite_enum] {} defined, perhaps you should remove \
+			`{}` from construct_runtime?",
+			name, name,
+		);
+		quote! {
+			compile_error!(concat!("`", stringify!($pallet_name), #msg));
+		}
+	} else {
+		TokenStream::new()
+	};
+
+	quote! {
+		#[doc(hidden)]
+		pub mod #check_mod {
+			#[macro_export]
+			#[doc(hidden)]
+			macro_rules! #macro_ident {
+				($pallet_name:ident) => {
+					#maybe_compile_error
+				}
+			}
+
+			#[doc(hidden)]
+			pub use #macro_ident as #check_macro;
+		}
+	}
+}
@@ -58,6 +58,48 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 		}
 	};
 
+	// Check, after the runtime upgrade, that the migrations brought the on-chain storage version up
+	// to the in-code storage version.
+	let post_storage_version_check = if def.pallet_struct.storage_version.is_some() {
+		quote::quote! {
+			let on_chain_version =
+				<Self as #frame_support::traits::GetStorageVersion>::on_chain_storage_version();
+			let current_version =
+				<Self as #frame_support::traits::GetStorageVersion>::current_storage_version();
+
+			if on_chain_version != current_version {
+				#frame_support::log::error!(
+					target: #frame_support::LOG_TARGET,
+					"{}: On chain storage version {:?} doesn't match current storage version {:?}.",
+					pallet_name,
+					on_chain_version,
+					current_version,
+				);
+
+				return Err("On chain and current storage version do not match. \
+					Missing runtime upgrade?")
+			}
+		}
+	} else {
+		quote::quote! {
+			let on_chain_version =
+				<Self as #frame_support::traits::GetStorageVersion>::on_chain_storage_version();
+
+			if on_chain_version != #frame_support::traits::StorageVersion::new(0) {
+				#frame_support::log::error!(
+					target: #frame_support::LOG_TARGET,
+					"{}: On chain storage version {:?} is set to non zero, while the pallet \
+					 is missing the `#[pallet::storage_version(VERSION)]` attribute.",
+					pallet_name,
+					on_chain_version,
+				);
+
+				return Err("On chain storage version set, while the pallet doesn't \
+					have the `#[pallet::storage_version(VERSION)]` attribute.")
+			}
+		}
+	};
+
 	let log_try_state = quote::quote! {
 		let pallet_name = <
 			<T as #frame_system::Config>::PalletInfo
@@ -174,7 +216,16 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 					Self
 					as
 					#frame_support::traits::Hooks<<T as #frame_system::Config>::BlockNumber>
-				>::post_upgrade(state)
+				>::post_upgrade(state)?;
+
+				let pallet_name = <
+					<T as #frame_system::Config>::PalletInfo
+					as
+					#frame_support::traits::PalletInfo
+				>::name::<Self>().unwrap_or("<unknown pallet name>");
+				#post_storage_version_check
+
+				Ok(())
 			}
 		}
 
@@ -183,6 +234,9 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 			for #pallet_ident<#type_use_gen> #where_clause
 		{
 			fn offchain_worker(n: <T as #frame_system::Config>::BlockNumber) {
+				#frame_support::sp_tracing::enter_span!(
+					#frame_support::sp_tracing::trace_span!("offchain_worker")
+				);
 				<
 					Self as #frame_support::traits::Hooks<
 						<T as #frame_system::Config>::BlockNumber
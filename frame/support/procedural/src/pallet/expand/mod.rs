@@ -16,6 +16,7 @@
 // limitations under the License.
 
 mod call;
+mod composite;
 mod config;
 mod constants;
 mod error;
@@ -29,9 +30,11 @@ mod origin;
 mod pallet_struct;
 mod storage;
 mod store_trait;
+mod tasks;
 mod tt_default_parts;
 mod type_value;
 mod validate_unsigned;
+mod view_functions;
 
 use crate::pallet::Def;
 use frame_support_procedural_tools::get_doc_literals;
@@ -68,6 +71,9 @@ pub fn expand(mut def: Def) -> proc_macro2::TokenStream {
 	let type_values = type_value::expand_type_values(&mut def);
 	let origins = origin::expand_origins(&mut def);
 	let validate_unsigned = validate_unsigned::expand_validate_unsigned(&mut def);
+	let tasks = tasks::expand_tasks(&mut def);
+	let composites = composite::expand_composites(&mut def);
+	let view_functions = view_functions::expand_view_functions(&mut def);
 	let tt_default_parts = tt_default_parts::expand_tt_default_parts(&mut def);
 
 	if get_doc_literals(&def.item.attrs).is_empty() {
@@ -98,6 +104,9 @@ pub fn expand(mut def: Def) -> proc_macro2::TokenStream {
 		#type_values
 		#origins
 		#validate_unsigned
+		#tasks
+		#composites
+		#view_functions
 		#tt_default_parts
 	);
 
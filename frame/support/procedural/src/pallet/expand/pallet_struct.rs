@@ -15,7 +15,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::pallet::{expand::merge_where_clauses, Def};
+use crate::pallet::{
+	expand::{merge_where_clauses, storage::pallet_storage_size},
+	Def,
+};
 use frame_support_procedural_tools::get_doc_literals;
 
 ///
@@ -27,6 +30,7 @@ use frame_support_procedural_tools::get_doc_literals;
 /// * replace the first field type of `struct Pallet` with `PhantomData` if it is `_`
 /// * implementation of `PalletInfoAccess` information
 /// * implementation of `StorageInfoTrait` on Pallet
+/// * implementation of `StorageSizeTrait` on Pallet
 pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
 	let frame_support = &def.frame_support;
 	let frame_system = &def.frame_system;
@@ -133,6 +137,15 @@ pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
 		})
 		.collect::<Vec<_>>();
 
+	let pallet_storage_size = pallet_storage_size(def);
+	let pallet_storage_size_info = pallet_storage_size.as_ref().map(|pallet_storage_size| {
+		quote::quote_spanned!(storage_info_span =>
+			res.append(&mut <
+				#pallet_storage_size as #frame_support::traits::StorageInfoTrait
+			>::storage_info());
+		)
+	});
+
 	let storage_info = quote::quote_spanned!(storage_info_span =>
 		impl<#type_impl_gen> #frame_support::traits::StorageInfoTrait
 			for #pallet_ident<#type_use_gen>
@@ -154,6 +167,46 @@ pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
 						res.append(&mut storage_info);
 					}
 				)*
+				#pallet_storage_size_info
+
+				res
+			}
+		}
+	);
+
+	let pallet_storage_size = pallet_storage_size.map(|pallet_storage_size| {
+		quote::quote_spanned!(def.pallet_struct.attr_span =>
+			res.append(&mut <
+				#pallet_storage_size as #frame_support::traits::StorageSizeTrait
+			>::storage_size());
+		)
+	});
+	let size_tracked_storages = def.storages.iter().filter(|storage| storage.size_tracked);
+	let size_tracked_storage_names = size_tracked_storages.clone().map(|storage| &storage.ident);
+	let size_tracked_storage_cfg_attrs = size_tracked_storages.map(|storage| &storage.cfg_attrs);
+
+	let storage_size = quote::quote_spanned!(def.pallet_struct.attr_span =>
+		impl<#type_impl_gen> #frame_support::traits::StorageSizeTrait
+			for #pallet_ident<#type_use_gen>
+			#storages_where_clauses
+		{
+			fn storage_size()
+				-> #frame_support::sp_std::vec::Vec<#frame_support::traits::StorageSize>
+			{
+				#[allow(unused_mut)]
+				let mut res = #frame_support::sp_std::vec![];
+
+				#(
+					#(#size_tracked_storage_cfg_attrs)*
+					{
+						let mut storage_size = <
+							#size_tracked_storage_names<#type_use_gen>
+							as #frame_support::traits::StorageSizeTrait
+						>::storage_size();
+						res.append(&mut storage_size);
+					}
+				)*
+				#pallet_storage_size
 
 				res
 			}
@@ -271,6 +324,7 @@ pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
 		}
 
 		#storage_info
+		#storage_size
 		#whitelisted_storage_keys_impl
 	)
 }
@@ -45,6 +45,58 @@ fn counter_prefix(prefix: &str) -> String {
 	format!("CounterFor{}", prefix)
 }
 
+/// Generate the size_prefix_ident related to the storage.
+/// size_prefix_ident is used for the prefix struct to be given to size tracked storage.
+fn size_prefix_ident(storage_ident: &syn::Ident) -> syn::Ident {
+	syn::Ident::new(
+		&format!("_GeneratedSizePrefixForStorage{}", storage_ident),
+		storage_ident.span(),
+	)
+}
+
+/// Generate the size_prefix related to the storage.
+/// size_prefix is used by size tracked storage.
+fn size_prefix(prefix: &str) -> String {
+	format!("ByteSizeFor{}", prefix)
+}
+
+/// The ident of the prefix struct for the size of all the size tracked storages of the pallet.
+fn pallet_size_prefix_ident() -> syn::Ident {
+	syn::Ident::new("_GeneratedPalletStorageSizePrefix", proc_macro2::Span::call_site())
+}
+
+/// The prefix of the size of all the size tracked storages of the pallet.
+const PALLET_SIZE_PREFIX: &str = "StorageByteSize";
+
+/// Return the `PalletStorageSize` type of the pallet, if the pallet has any size tracked
+/// storage.
+pub fn pallet_storage_size(def: &Def) -> Option<proc_macro2::TokenStream> {
+	if !def.storages.iter().any(|storage| storage.size_tracked) {
+		return None
+	}
+
+	let frame_support = &def.frame_support;
+	let type_use_gen = &def.type_use_generics(proc_macro2::Span::call_site());
+	let pallet_size_prefix_ident = pallet_size_prefix_ident();
+	let max_storage_bytes = pallet_max_storage_bytes(def);
+
+	Some(quote::quote!(
+		#frame_support::storage::types::PalletStorageSize<
+			#pallet_size_prefix_ident<#type_use_gen>,
+			#max_storage_bytes,
+		>
+	))
+}
+
+/// The type bounding the bytes occupied by the size tracked storages of the pallet.
+fn pallet_max_storage_bytes(def: &Def) -> syn::Type {
+	let frame_support = &def.frame_support;
+	def.pallet_struct
+		.max_storage_bytes
+		.clone()
+		.unwrap_or_else(|| syn::parse_quote!(#frame_support::traits::GetDefault))
+}
+
 /// Check for duplicated storage prefixes. This step is necessary since users can specify an
 /// alternative storage prefix using the #[pallet::storage_prefix] syntax, and we need to ensure
 /// that the prefix specified by the user is not a duplicate of an existing one.
@@ -83,6 +135,24 @@ fn check_prefix_duplicates(
 		}
 	}
 
+	if storage_def.size_tracked {
+		let size_prefix = size_prefix(&prefix);
+		let size_dup_err = syn::Error::new(
+			storage_def.prefix_span(),
+			format!(
+				"Duplicate storage prefixes found for `{}`, used for size associated to \
+				size tracked storage",
+				size_prefix,
+			),
+		);
+
+		if let Some(other_dup_err) = used_prefixes.insert(size_prefix, size_dup_err.clone()) {
+			let mut err = size_dup_err;
+			err.combine(other_dup_err);
+			return Err(err)
+		}
+	}
+
 	Ok(())
 }
 
@@ -157,6 +227,15 @@ pub fn process_generics(def: &mut Def) -> syn::Result<Vec<ResultOnEmptyStructMet
 			syn::parse_quote!(#frame_support::traits::GetDefault)
 		};
 		let default_max_values: syn::Type = syn::parse_quote!(#frame_support::traits::GetDefault);
+		let default_max_bytes: syn::Type = syn::parse_quote!(#frame_support::traits::GetDefault);
+		// Only size tracked storages take `MaxBytes`, as their last generic.
+		let push_max_bytes = |args: &mut syn::punctuated::Punctuated<_, _>,
+		                      max_bytes: Option<syn::Type>| {
+			if storage_def.size_tracked {
+				let max_bytes = max_bytes.unwrap_or_else(|| default_max_bytes.clone());
+				args.push(syn::GenericArgument::Type(max_bytes));
+			}
+		};
 
 		let set_result_query_type_parameter = |query_type: &mut syn::Type| -> syn::Result<()> {
 			if let Some(QueryKind::ResultQuery(error_path, _)) = storage_def.query_kind.as_ref() {
@@ -188,15 +267,24 @@ pub fn process_generics(def: &mut Def) -> syn::Result<Vec<ResultOnEmptyStructMet
 			args.args.clear();
 			args.args.push(syn::parse_quote!( #prefix_ident<#type_use_gen> ));
 			match named_generics {
-				StorageGenerics::Value { value, query_kind, on_empty } => {
+				StorageGenerics::Value { value, query_kind, on_empty, max_bytes } => {
 					args.args.push(syn::GenericArgument::Type(value.clone()));
 					let mut query_kind = query_kind.unwrap_or_else(|| default_query_kind.clone());
 					set_result_query_type_parameter(&mut query_kind)?;
 					args.args.push(syn::GenericArgument::Type(query_kind));
 					let on_empty = on_empty.unwrap_or_else(|| default_on_empty(value));
 					args.args.push(syn::GenericArgument::Type(on_empty));
+					push_max_bytes(&mut args.args, max_bytes);
 				},
-				StorageGenerics::Map { hasher, key, value, query_kind, on_empty, max_values } => {
+				StorageGenerics::Map {
+					hasher,
+					key,
+					value,
+					query_kind,
+					on_empty,
+					max_values,
+					max_bytes,
+				} => {
 					args.args.push(syn::GenericArgument::Type(hasher));
 					args.args.push(syn::GenericArgument::Type(key));
 					args.args.push(syn::GenericArgument::Type(value.clone()));
@@ -207,6 +295,7 @@ pub fn process_generics(def: &mut Def) -> syn::Result<Vec<ResultOnEmptyStructMet
 					args.args.push(syn::GenericArgument::Type(on_empty));
 					let max_values = max_values.unwrap_or_else(|| default_max_values.clone());
 					args.args.push(syn::GenericArgument::Type(max_values));
+					push_max_bytes(&mut args.args, max_bytes);
 				},
 				StorageGenerics::CountedMap {
 					hasher,
@@ -236,6 +325,7 @@ pub fn process_generics(def: &mut Def) -> syn::Result<Vec<ResultOnEmptyStructMet
 					query_kind,
 					on_empty,
 					max_values,
+					max_bytes,
 				} => {
 					args.args.push(syn::GenericArgument::Type(hasher1));
 					args.args.push(syn::GenericArgument::Type(key1));
@@ -249,8 +339,16 @@ pub fn process_generics(def: &mut Def) -> syn::Result<Vec<ResultOnEmptyStructMet
 					args.args.push(syn::GenericArgument::Type(on_empty));
 					let max_values = max_values.unwrap_or_else(|| default_max_values.clone());
 					args.args.push(syn::GenericArgument::Type(max_values));
+					push_max_bytes(&mut args.args, max_bytes);
 				},
-				StorageGenerics::NMap { keygen, value, query_kind, on_empty, max_values } => {
+				StorageGenerics::NMap {
+					keygen,
+					value,
+					query_kind,
+					on_empty,
+					max_values,
+					max_bytes,
+				} => {
 					args.args.push(syn::GenericArgument::Type(keygen));
 					args.args.push(syn::GenericArgument::Type(value.clone()));
 					let mut query_kind = query_kind.unwrap_or_else(|| default_query_kind.clone());
@@ -260,6 +358,7 @@ pub fn process_generics(def: &mut Def) -> syn::Result<Vec<ResultOnEmptyStructMet
 					args.args.push(syn::GenericArgument::Type(on_empty));
 					let max_values = max_values.unwrap_or_else(|| default_max_values.clone());
 					args.args.push(syn::GenericArgument::Type(max_values));
+					push_max_bytes(&mut args.args, max_bytes);
 				},
 			}
 		} else {
@@ -324,6 +423,22 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 		return final_error.into_compile_error()
 	}
 
+	let pallet_storage_size = pallet_storage_size(def);
+	if pallet_storage_size.is_some() {
+		if let Some(other_dup_err) = prefix_set.get(PALLET_SIZE_PREFIX) {
+			let mut err = syn::Error::new(
+				def.pallet_struct.attr_span,
+				format!(
+					"Duplicate storage prefixes found for `{}`, used for size of the size tracked \
+					storages of the pallet",
+					PALLET_SIZE_PREFIX,
+				),
+			);
+			err.combine(other_dup_err.clone());
+			return err.into_compile_error()
+		}
+	}
+
 	let frame_support = &def.frame_support;
 	let frame_system = &def.frame_system;
 	let pallet_ident = &def.pallet_struct.pallet;
@@ -351,6 +466,25 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 		)
 	});
 
+	let pallet_storage_size_entry_builder =
+		pallet_storage_size.as_ref().map(|pallet_storage_size| {
+			let docs = if cfg!(feature = "no-metadata-docs") {
+				vec![]
+			} else {
+				vec!["Bytes occupied by the size tracked storages of the pallet"]
+			};
+
+			quote::quote!(
+				<#pallet_storage_size as #frame_support::storage::StorageEntryMetadataBuilder>
+					::build_metadata(
+						#frame_support::sp_std::vec![
+							#( #docs, )*
+						],
+						&mut entries,
+					);
+			)
+		});
+
 	let getters = def.storages.iter().map(|storage| {
 		if let Some(getter) = &storage.getter {
 			let completed_where_clause =
@@ -380,14 +514,21 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 							),
 						QueryKind::ValueQuery => quote::quote!(#value),
 					};
+					let get = if storage.size_tracked {
+						// NOTE: we can't use any trait here because size tracked storages
+						// don't implement any.
+						quote::quote_spanned!(storage.attr_span => <#full_ident>::get())
+					} else {
+						quote::quote_spanned!(storage.attr_span =>
+							<#full_ident as #frame_support::storage::StorageValue<#value>>::get()
+						)
+					};
 					quote::quote_spanned!(storage.attr_span =>
 						#(#cfg_attrs)*
 						impl<#type_impl_gen> #pallet_ident<#type_use_gen> #completed_where_clause {
 							#( #docs )*
 							pub fn #getter() -> #query {
-								<
-									#full_ident as #frame_support::storage::StorageValue<#value>
-								>::get()
+								#get
 							}
 						}
 					)
@@ -403,6 +544,17 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 							),
 						QueryKind::ValueQuery => quote::quote!(#value),
 					};
+					let get = if storage.size_tracked {
+						// NOTE: we can't use any trait here because size tracked storages
+						// don't implement any.
+						quote::quote_spanned!(storage.attr_span => <#full_ident>::get(k))
+					} else {
+						quote::quote_spanned!(storage.attr_span =>
+							<
+								#full_ident as #frame_support::storage::StorageMap<#key, #value>
+							>::get(k)
+						)
+					};
 					quote::quote_spanned!(storage.attr_span =>
 						#(#cfg_attrs)*
 						impl<#type_impl_gen> #pallet_ident<#type_use_gen> #completed_where_clause {
@@ -410,9 +562,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 							pub fn #getter<KArg>(k: KArg) -> #query where
 								KArg: #frame_support::codec::EncodeLike<#key>,
 							{
-								<
-									#full_ident as #frame_support::storage::StorageMap<#key, #value>
-								>::get(k)
+								#get
 							}
 						}
 					)
@@ -453,6 +603,18 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 							),
 						QueryKind::ValueQuery => quote::quote!(#value),
 					};
+					let get = if storage.size_tracked {
+						// NOTE: we can't use any trait here because size tracked storages
+						// don't implement any.
+						quote::quote_spanned!(storage.attr_span => <#full_ident>::get(k1, k2))
+					} else {
+						quote::quote_spanned!(storage.attr_span =>
+							<
+								#full_ident as
+								#frame_support::storage::StorageDoubleMap<#key1, #key2, #value>
+							>::get(k1, k2)
+						)
+					};
 					quote::quote_spanned!(storage.attr_span =>
 						#(#cfg_attrs)*
 						impl<#type_impl_gen> #pallet_ident<#type_use_gen> #completed_where_clause {
@@ -461,10 +623,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 								KArg1: #frame_support::codec::EncodeLike<#key1>,
 								KArg2: #frame_support::codec::EncodeLike<#key2>,
 							{
-								<
-									#full_ident as
-									#frame_support::storage::StorageDoubleMap<#key1, #key2, #value>
-								>::get(k1, k2)
+								#get
 							}
 						}
 					)
@@ -480,6 +639,18 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 							),
 						QueryKind::ValueQuery => quote::quote!(#value),
 					};
+					let get = if storage.size_tracked {
+						// NOTE: we can't use any trait here because size tracked storages
+						// don't implement any.
+						quote::quote_spanned!(storage.attr_span => <#full_ident>::get(key))
+					} else {
+						quote::quote_spanned!(storage.attr_span =>
+							<
+								#full_ident as
+								#frame_support::storage::StorageNMap<#keygen, #value>
+							>::get(key)
+						)
+					};
 					quote::quote_spanned!(storage.attr_span =>
 						#(#cfg_attrs)*
 						impl<#type_impl_gen> #pallet_ident<#type_use_gen> #completed_where_clause {
@@ -491,10 +662,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 								>
 									+ #frame_support::storage::types::TupleToEncodedIter,
 							{
-								<
-									#full_ident as
-									#frame_support::storage::StorageNMap<#keygen, #value>
-								>::get(key)
+								#get
 							}
 						}
 					)
@@ -551,8 +719,49 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 			proc_macro2::TokenStream::default()
 		};
 
+		let maybe_size = if storage_def.size_tracked {
+			let size_prefix_struct_ident = size_prefix_ident(&storage_def.ident);
+			let size_prefix_struct_const = size_prefix(&prefix_struct_const);
+			let pallet_size_prefix_struct_ident = pallet_size_prefix_ident();
+			let max_storage_bytes = pallet_max_storage_bytes(def);
+
+			quote::quote_spanned!(storage_def.attr_span =>
+				#(#cfg_attrs)*
+				#[doc(hidden)]
+				#prefix_struct_vis struct #size_prefix_struct_ident<#type_use_gen>(
+					core::marker::PhantomData<(#type_use_gen,)>
+				);
+				#(#cfg_attrs)*
+				impl<#type_impl_gen> #frame_support::traits::StorageInstance
+					for #size_prefix_struct_ident<#type_use_gen>
+					#config_where_clause
+				{
+					fn pallet_prefix() -> &'static str {
+						<
+							<T as #frame_system::Config>::PalletInfo
+							as #frame_support::traits::PalletInfo
+						>::name::<Pallet<#type_use_gen>>()
+							.expect("Every active pallet has a name in the runtime; qed")
+					}
+					const STORAGE_PREFIX: &'static str = #size_prefix_struct_const;
+				}
+				#(#cfg_attrs)*
+				impl<#type_impl_gen> #frame_support::storage::types::SizeTrackedStorageInstance
+					for #prefix_struct_ident<#type_use_gen>
+					#config_where_clause
+				{
+					type SizePrefix = #size_prefix_struct_ident<#type_use_gen>;
+					type PalletSizePrefix = #pallet_size_prefix_struct_ident<#type_use_gen>;
+					type PalletMaxBytes = #max_storage_bytes;
+				}
+			)
+		} else {
+			proc_macro2::TokenStream::default()
+		};
+
 		quote::quote_spanned!(storage_def.attr_span =>
 			#maybe_counter
+			#maybe_size
 
 			#(#cfg_attrs)*
 			#[doc(hidden)]
@@ -576,6 +785,33 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 		)
 	});
 
+	let pallet_size_prefix_struct = pallet_storage_size.as_ref().map(|_| {
+		let type_impl_gen = &def.type_impl_generics(proc_macro2::Span::call_site());
+		let type_use_gen = &def.type_use_generics(proc_macro2::Span::call_site());
+		let pallet_size_prefix_struct_ident = pallet_size_prefix_ident();
+		let config_where_clause = &def.config.where_clause;
+
+		quote::quote!(
+			#[doc(hidden)]
+			pub struct #pallet_size_prefix_struct_ident<#type_use_gen>(
+				core::marker::PhantomData<(#type_use_gen,)>
+			);
+			impl<#type_impl_gen> #frame_support::traits::StorageInstance
+				for #pallet_size_prefix_struct_ident<#type_use_gen>
+				#config_where_clause
+			{
+				fn pallet_prefix() -> &'static str {
+					<
+						<T as #frame_system::Config>::PalletInfo
+						as #frame_support::traits::PalletInfo
+					>::name::<Pallet<#type_use_gen>>()
+						.expect("Every active pallet has a name in the runtime; qed")
+				}
+				const STORAGE_PREFIX: &'static str = #PALLET_SIZE_PREFIX;
+			}
+		)
+	});
+
 	let on_empty_structs = on_empty_struct_metadata.into_iter().map(|metadata| {
 		use crate::pallet::parse::GenericKind;
 		use syn::{GenericArgument, Path, PathArguments, PathSegment, Type, TypePath};
@@ -653,6 +889,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						#[allow(unused_mut)]
 						let mut entries = #frame_support::sp_std::vec![];
 						#( #entries_builder )*
+						#pallet_storage_size_entry_builder
 						entries
 					},
 				}
@@ -661,6 +898,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 
 		#( #getters )*
 		#( #prefix_structs )*
+		#pallet_size_prefix_struct
 		#( #on_empty_structs )*
 	)
 }
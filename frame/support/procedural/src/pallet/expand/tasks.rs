@@ -0,0 +1,184 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{pallet::Def, COUNTER};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Ident};
+
+/// * Generate enum task and implement `Task` on it.
+/// * Generate the macro checking the `Task` part of `construct_runtime`.
+pub fn expand_tasks(def: &mut Def) -> TokenStream {
+	let count = COUNTER.with(|counter| counter.borrow_mut().inc());
+	let macro_ident = Ident::new(&format!("__is_task_part_defined_{}", count), def.item.span());
+
+	let maybe_compile_error = if def.tasks.is_none() {
+		quote! {
+			compile_error!(concat!(
+				"`",
+				stringify!($pallet_name),
+				"` does not have #[pallet::tasks] defined, perhaps you should \
+				remove `Task` from construct_runtime?",
+			));
+		}
+	} else {
+		TokenStream::new()
+	};
+
+	let task_check = quote! {
+		#[doc(hidden)]
+		pub mod __substrate_task_check {
+			#[macro_export]
+			#[doc(hidden)]
+			macro_rules! #macro_ident {
+				($pallet_name:ident) => {
+					#maybe_compile_error
+				}
+			}
+
+			#[doc(hidden)]
+			pub use #macro_ident as is_task_part_defined;
+		}
+	};
+
+	let tasks = match def.tasks.as_ref() {
+		Some(tasks) => tasks,
+		None => return task_check,
+	};
+
+	let span = tasks.attr_span;
+	let frame_support = &def.frame_support;
+	let type_impl_gen = &def.type_impl_generics(span);
+	let type_decl_bounded_gen = &def.type_decl_bounded_generics(span);
+	let type_use_gen = &def.type_use_generics(span);
+	let pallet_ident = &def.pallet_struct.pallet;
+	let task_ident = Ident::new("Task", span);
+
+	let where_clause = super::merge_where_clauses(&[&def.config.where_clause, &tasks.where_clause]);
+
+	let fn_name = tasks.tasks.iter().map(|task| &task.ident).collect::<Vec<_>>();
+	let task_index = tasks.tasks.iter().map(|task| task.task_index).collect::<Vec<_>>();
+	let task_index_u32 = task_index.iter().map(|index| *index as u32);
+	let task_doc = tasks.tasks.iter().map(|task| &task.docs).collect::<Vec<_>>();
+	let list = tasks.tasks.iter().map(|task| &task.list);
+	let condition = tasks.tasks.iter().map(|task| &task.condition);
+	let weight = tasks.tasks.iter().map(|task| &task.weight);
+	let args_name = tasks
+		.tasks
+		.iter()
+		.map(|task| task.args.iter().map(|(name, _)| name).collect::<Vec<_>>())
+		.collect::<Vec<_>>();
+	let args_type = tasks
+		.tasks
+		.iter()
+		.map(|task| task.args.iter().map(|(_, type_)| type_).collect::<Vec<_>>());
+
+	// The items of `task_list` are the arguments of the task, as a tuple if there are several.
+	let list_item_pattern = args_name.iter().map(|args_name| match &args_name[..] {
+		[arg] => quote!(#arg),
+		args => quote!( ( #( #args ),* ) ),
+	});
+
+	let capture_docs = if cfg!(feature = "no-metadata-docs") { "never" } else { "always" };
+
+	quote::quote_spanned!(span =>
+		#task_check
+
+		/// Contains one variant per task of the pallet, see `#[pallet::tasks]`.
+		#[derive(
+			#frame_support::RuntimeDebugNoBound,
+			#frame_support::CloneNoBound,
+			#frame_support::EqNoBound,
+			#frame_support::PartialEqNoBound,
+			#frame_support::codec::Encode,
+			#frame_support::codec::Decode,
+			#frame_support::scale_info::TypeInfo,
+		)]
+		#[codec(encode_bound())]
+		#[codec(decode_bound())]
+		#[scale_info(skip_type_params(#type_use_gen), capture_docs = #capture_docs)]
+		#[allow(non_camel_case_types)]
+		pub enum #task_ident<#type_decl_bounded_gen> #where_clause {
+			#[doc(hidden)]
+			#[codec(skip)]
+			__Ignore(
+				#frame_support::sp_std::marker::PhantomData<(#type_use_gen,)>,
+				#frame_support::Never,
+			),
+			#(
+				#( #[doc = #task_doc] )*
+				#[codec(index = #task_index)]
+				#fn_name {
+					#(
+						#[allow(missing_docs)]
+						#args_name: #args_type
+					),*
+				},
+			)*
+		}
+
+		impl<#type_impl_gen> #frame_support::traits::Task for #task_ident<#type_use_gen>
+			#where_clause
+		{
+			fn iter() -> #frame_support::sp_std::boxed::Box<dyn Iterator<Item = Self>> {
+				#frame_support::sp_std::boxed::Box::new(
+					#frame_support::sp_std::iter::empty()
+					#(
+						.chain((#list).map(|#list_item_pattern| Self::#fn_name { #( #args_name ),* }))
+					)*
+				)
+			}
+
+			fn is_valid(&self) -> bool {
+				match self {
+					#(
+						Self::#fn_name { #( #args_name ),* } => (#condition)( #( #args_name ),* ),
+					)*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+
+			fn run(&self) -> #frame_support::dispatch::DispatchResult {
+				match self.clone() {
+					#(
+						Self::#fn_name { #( #args_name ),* } =>
+							<#pallet_ident<#type_use_gen>>::#fn_name( #( #args_name ),* ),
+					)*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+
+			#[allow(unused_variables)]
+			fn weight(&self) -> #frame_support::weights::Weight {
+				match self {
+					#(
+						Self::#fn_name { #( #args_name ),* } => #weight,
+					)*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+
+			fn task_index(&self) -> u32 {
+				match self {
+					#(
+						Self::#fn_name { .. } => #task_index_u32,
+					)*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+		}
+	)
+}
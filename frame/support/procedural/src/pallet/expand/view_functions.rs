@@ -0,0 +1,200 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::pallet::{parse::view_functions::ViewFunctionDef, Def};
+use inflector::Inflector;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The name of the struct holding the arguments of the view function.
+fn view_function_struct_ident(view_fn: &ViewFunctionDef) -> syn::Ident {
+	syn::Ident::new(&view_fn.ident.to_string().to_pascal_case(), view_fn.ident.span())
+}
+
+/// * Generate a struct implementing `ViewFunction` for each view function,
+/// * impl `DispatchViewFunction` and `ViewFunctionsMetadata` on the pallet.
+pub fn expand_view_functions(def: &mut Def) -> TokenStream {
+	let frame_support = &def.frame_support;
+	let span = def.view_functions.as_ref().map_or(def.pallet_struct.attr_span, |v| v.attr_span);
+	let type_impl_gen = &def.type_impl_generics(span);
+	let type_decl_bounded_gen = &def.type_decl_bounded_generics(span);
+	let type_use_gen = &def.type_use_generics(span);
+	let pallet_ident = &def.pallet_struct.pallet;
+
+	let mut where_clauses = vec![&def.config.where_clause];
+	where_clauses.extend(def.view_functions.iter().map(|v| &v.where_clause));
+	let where_clause = super::merge_where_clauses(&where_clauses);
+
+	let view_fns = def.view_functions.iter().flat_map(|v| &v.view_functions).collect::<Vec<_>>();
+
+	let structs = view_fns.iter().map(|view_fn| {
+		let struct_ident = view_function_struct_ident(view_fn);
+		let fn_ident = &view_fn.ident;
+		let arg_names = view_fn.args.iter().map(|(name, _)| name).collect::<Vec<_>>();
+		let arg_types = view_fn.args.iter().map(|(_, type_)| type_).collect::<Vec<_>>();
+		let return_type = &view_fn.return_type;
+
+		let name = fn_ident.to_string();
+
+		let doc = format!("The arguments of the view function [`Pallet::{}`].", fn_ident);
+
+		quote::quote_spanned!(span =>
+			#[doc = #doc]
+			#[derive(#frame_support::codec::Encode, #frame_support::codec::Decode)]
+			#[codec(encode_bound())]
+			#[codec(decode_bound())]
+			pub struct #struct_ident<#type_decl_bounded_gen> #where_clause {
+				#( pub #arg_names: #arg_types, )*
+				#[codec(skip)]
+				_marker: #frame_support::sp_std::marker::PhantomData<(#type_use_gen,)>,
+			}
+
+			impl<#type_impl_gen> #struct_ident<#type_use_gen> #where_clause {
+				/// Create a call of the view function with its arguments.
+				pub fn new( #( #arg_names: #arg_types ),* ) -> Self {
+					Self {
+						#( #arg_names, )*
+						_marker: Default::default(),
+					}
+				}
+			}
+
+			impl<#type_impl_gen> #frame_support::view_functions::ViewFunction
+				for #struct_ident<#type_use_gen> #where_clause
+			{
+				type ReturnType = #return_type;
+
+				fn id() -> #frame_support::view_functions::ViewFunctionId {
+					#frame_support::view_functions::ViewFunctionId {
+						prefix: <#pallet_ident<#type_use_gen>>::view_function_id_prefix(),
+						suffix: #frame_support::view_functions::view_function_id_suffix(
+							#name,
+							#frame_support::sp_std::vec![ #(
+								#frame_support::scale_info::meta_type::<#arg_types>()
+							),* ],
+							#frame_support::scale_info::meta_type::<#return_type>(),
+						),
+					}
+				}
+
+				fn invoke(self) -> Self::ReturnType {
+					<#pallet_ident<#type_use_gen>>::#fn_ident( #( self.#arg_names ),* )
+				}
+			}
+		)
+	});
+
+	let dispatch = if view_fns.is_empty() {
+		quote::quote_spanned!(span =>
+			Err(#frame_support::view_functions::ViewFunctionDispatchError::NotFound(*id))
+		)
+	} else {
+		let struct_idents = view_fns.iter().map(|view_fn| view_function_struct_ident(view_fn));
+		quote::quote_spanned!(span =>
+			if id.prefix == Self::view_function_id_prefix() {
+				#(
+					let view_fn_id = <#struct_idents<#type_use_gen> as
+						#frame_support::view_functions::ViewFunction>::id();
+					if id.suffix == view_fn_id.suffix {
+						return <#struct_idents<#type_use_gen> as
+							#frame_support::view_functions::ViewFunction>::execute(input, output)
+					}
+				)*
+			}
+			Err(#frame_support::view_functions::ViewFunctionDispatchError::NotFound(*id))
+		)
+	};
+
+	let metadata = view_fns.iter().map(|view_fn| {
+		let struct_ident = view_function_struct_ident(view_fn);
+		let name = view_fn.ident.to_string();
+		let arg_names = view_fn.args.iter().map(|(name, _)| name.to_string());
+		let arg_types = view_fn.args.iter().map(|(_, type_)| type_);
+		let return_type = &view_fn.return_type;
+		let no_docs = vec![];
+		let docs = if cfg!(feature = "no-metadata-docs") { &no_docs } else { &view_fn.docs };
+
+		quote::quote_spanned!(span =>
+			#frame_support::view_functions::ViewFunctionMetadata {
+				name: #name,
+				id: <#struct_ident<#type_use_gen> as
+					#frame_support::view_functions::ViewFunction>::id(),
+				inputs: #frame_support::sp_std::vec![ #(
+					#frame_support::view_functions::ViewFunctionArgMetadata {
+						name: #arg_names,
+						ty: #frame_support::scale_info::meta_type::<#arg_types>(),
+					}
+				),* ],
+				output: #frame_support::scale_info::meta_type::<#return_type>(),
+				docs: #frame_support::sp_std::vec![ #( #docs ),* ],
+			}
+		)
+	});
+
+	let pallet_metadata = if view_fns.is_empty() {
+		quote::quote_spanned!(span => #frame_support::sp_std::vec![])
+	} else {
+		quote::quote_spanned!(span =>
+			#frame_support::sp_std::vec![
+				#frame_support::view_functions::PalletViewFunctionsMetadata {
+					name: <Self as #frame_support::traits::PalletInfoAccess>::name(),
+					view_functions: #frame_support::sp_std::vec![ #( #metadata ),* ],
+				}
+			]
+		)
+	};
+
+	let (input, output) = if view_fns.is_empty() {
+		(quote!(_input), quote!(_output))
+	} else {
+		(quote!(input), quote!(output))
+	};
+
+	quote::quote_spanned!(span =>
+		#( #structs )*
+
+		impl<#type_impl_gen> #pallet_ident<#type_use_gen> #where_clause {
+			#[doc(hidden)]
+			pub fn view_function_id_prefix() -> [u8; 16] {
+				#frame_support::sp_io::hashing::twox_128(
+					<Self as #frame_support::traits::PalletInfoAccess>::name().as_bytes()
+				)
+			}
+		}
+
+		impl<#type_impl_gen> #frame_support::view_functions::DispatchViewFunction
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			fn dispatch_view_function<O: #frame_support::codec::Output>(
+				id: &#frame_support::view_functions::ViewFunctionId,
+				#input: &mut &[u8],
+				#output: &mut O,
+			) -> Result<(), #frame_support::view_functions::ViewFunctionDispatchError> {
+				#dispatch
+			}
+		}
+
+		impl<#type_impl_gen> #frame_support::view_functions::ViewFunctionsMetadata
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			fn view_functions_metadata() -> #frame_support::sp_std::vec::Vec<
+				#frame_support::view_functions::PalletViewFunctionsMetadata
+			> {
+				#pallet_metadata
+			}
+		}
+	)
+}
@@ -0,0 +1,106 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use syn::spanned::Spanned;
+
+/// The kinds of composite enums a pallet can declare, aggregated by `construct_runtime`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CompositeKeyword {
+	/// `HoldReason`, aggregated into `RuntimeHoldReason`.
+	HoldReason,
+	/// `FreezeReason`, aggregated into `RuntimeFreezeReason`.
+	FreezeReason,
+}
+
+impl CompositeKeyword {
+	/// All the identifiers allowed for a composite enum.
+	const ALL: &'static [&'static str] = &["HoldReason", "FreezeReason"];
+
+	fn from_ident(ident: &syn::Ident) -> Option<Self> {
+		match ident.to_string().as_str() {
+			"HoldReason" => Some(Self::HoldReason),
+			"FreezeReason" => Some(Self::FreezeReason),
+			_ => None,
+		}
+	}
+
+	/// The name of the enum, which is also the name of the `construct_runtime` part.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::HoldReason => "HoldReason",
+			Self::FreezeReason => "FreezeReason",
+		}
+	}
+}
+
+/// Definition of a composite enum, i.e. `pub enum HoldReason { .. }`.
+pub struct CompositeDef {
+	/// The index of the enum in the pallet module.
+	pub index: usize,
+	/// Which composite enum it is.
+	pub composite_keyword: CompositeKeyword,
+	/// The number of variants of the enum.
+	pub variant_count: u32,
+	/// The span of the pallet::composite_enum attribute.
+	pub attr_span: proc_macro2::Span,
+}
+
+impl CompositeDef {
+	pub fn try_from(
+		attr_span: proc_macro2::Span,
+		index: usize,
+		item: &mut syn::Item,
+	) -> syn::Result<Self> {
+		let item = if let syn::Item::Enum(item) = item {
+			item
+		} else {
+			let msg = "Invalid pallet::composite_enum, expected enum item";
+			return Err(syn::Error::new(item.span(), msg))
+		};
+
+		if !matches!(item.vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::composite_enum, enum must be public";
+			return Err(syn::Error::new(item.span(), msg))
+		}
+
+		if !item.generics.params.is_empty() || item.generics.where_clause.is_some() {
+			let msg = "Invalid pallet::composite_enum, enum must have no generics";
+			return Err(syn::Error::new(item.generics.span(), msg))
+		}
+
+		let composite_keyword = CompositeKeyword::from_ident(&item.ident).ok_or_else(|| {
+			let msg = format!(
+				"Invalid pallet::composite_enum, ident must be one of: {}",
+				CompositeKeyword::ALL.join(", "),
+			);
+			syn::Error::new(item.ident.span(), msg)
+		})?;
+
+		for variant in &item.variants {
+			if !matches!(variant.fields, syn::Fields::Unit) {
+				let msg = "Invalid pallet::composite_enum, variants must have no fields";
+				return Err(syn::Error::new(variant.fields.span(), msg))
+			}
+		}
+
+		Ok(CompositeDef {
+			index,
+			composite_keyword,
+			variant_count: item.variants.len() as u32,
+			attr_span,
+		})
+	}
+}
@@ -26,6 +26,7 @@ mod keyword {
 	syn::custom_keyword!(generate_store);
 	syn::custom_keyword!(without_storage_info);
 	syn::custom_keyword!(storage_version);
+	syn::custom_keyword!(max_storage_bytes);
 	syn::custom_keyword!(Store);
 }
 
@@ -46,16 +47,20 @@ pub struct PalletStructDef {
 	pub without_storage_info: Option<proc_macro2::Span>,
 	/// The current storage version of the pallet.
 	pub storage_version: Option<syn::Path>,
+	/// The type bounding the bytes occupied by the size tracked storages of the pallet.
+	pub max_storage_bytes: Option<syn::Type>,
 }
 
 /// Parse for one variant of:
 /// * `#[pallet::generate_store($vis trait Store)]`
 /// * `#[pallet::without_storage_info]`
 /// * `#[pallet::storage_version(STORAGE_VERSION)]`
+/// * `#[pallet::max_storage_bytes(MaxStorageBytes)]`
 pub enum PalletStructAttr {
 	GenerateStore { span: proc_macro2::Span, vis: syn::Visibility, keyword: keyword::Store },
 	WithoutStorageInfoTrait(proc_macro2::Span),
 	StorageVersion { storage_version: syn::Path, span: proc_macro2::Span },
+	MaxStorageBytes { max_storage_bytes: syn::Type, span: proc_macro2::Span },
 }
 
 impl PalletStructAttr {
@@ -64,6 +69,7 @@ impl PalletStructAttr {
 			Self::GenerateStore { span, .. } => *span,
 			Self::WithoutStorageInfoTrait(span) => *span,
 			Self::StorageVersion { span, .. } => *span,
+			Self::MaxStorageBytes { span, .. } => *span,
 		}
 	}
 }
@@ -97,6 +103,14 @@ impl syn::parse::Parse for PalletStructAttr {
 			let storage_version = version_content.parse::<syn::Path>()?;
 
 			Ok(Self::StorageVersion { storage_version, span })
+		} else if lookahead.peek(keyword::max_storage_bytes) {
+			let span = content.parse::<keyword::max_storage_bytes>()?.span();
+
+			let max_content;
+			syn::parenthesized!(max_content in content);
+			let max_storage_bytes = max_content.parse::<syn::Type>()?;
+
+			Ok(Self::MaxStorageBytes { max_storage_bytes, span })
 		} else {
 			Err(lookahead.error())
 		}
@@ -119,6 +133,7 @@ impl PalletStructDef {
 		let mut store = None;
 		let mut without_storage_info = None;
 		let mut storage_version_found = None;
+		let mut max_storage_bytes_found = None;
 
 		let struct_attrs: Vec<PalletStructAttr> = helper::take_item_pallet_attrs(&mut item.attrs)?;
 		for attr in struct_attrs {
@@ -136,6 +151,11 @@ impl PalletStructDef {
 				{
 					storage_version_found = Some(storage_version);
 				},
+				PalletStructAttr::MaxStorageBytes { max_storage_bytes, .. }
+					if max_storage_bytes_found.is_none() =>
+				{
+					max_storage_bytes_found = Some(max_storage_bytes);
+				},
 				attr => {
 					let msg = "Unexpected duplicated attribute";
 					return Err(syn::Error::new(attr.span(), msg))
@@ -166,6 +186,7 @@ impl PalletStructDef {
 			attr_span,
 			without_storage_info,
 			storage_version: storage_version_found,
+			max_storage_bytes: max_storage_bytes_found,
 		})
 	}
 }
@@ -185,6 +185,8 @@ pub struct StorageDef {
 	pub unbounded: bool,
 	/// Whether or not reads to this storage key will be ignored by benchmarking
 	pub whitelisted: bool,
+	/// Whether the storage is a size tracked storage, e.g. `SizeTrackedStorageMap`.
+	pub size_tracked: bool,
 }
 
 /// The parsed generic from the
@@ -199,6 +201,7 @@ pub enum StorageGenerics {
 		query_kind: Option<syn::Type>,
 		on_empty: Option<syn::Type>,
 		max_values: Option<syn::Type>,
+		max_bytes: Option<syn::Type>,
 	},
 	Map {
 		hasher: syn::Type,
@@ -207,6 +210,7 @@ pub enum StorageGenerics {
 		query_kind: Option<syn::Type>,
 		on_empty: Option<syn::Type>,
 		max_values: Option<syn::Type>,
+		max_bytes: Option<syn::Type>,
 	},
 	CountedMap {
 		hasher: syn::Type,
@@ -220,6 +224,7 @@ pub enum StorageGenerics {
 		value: syn::Type,
 		query_kind: Option<syn::Type>,
 		on_empty: Option<syn::Type>,
+		max_bytes: Option<syn::Type>,
 	},
 	NMap {
 		keygen: syn::Type,
@@ -227,6 +232,7 @@ pub enum StorageGenerics {
 		query_kind: Option<syn::Type>,
 		on_empty: Option<syn::Type>,
 		max_values: Option<syn::Type>,
+		max_bytes: Option<syn::Type>,
 	},
 }
 
@@ -326,8 +332,11 @@ fn check_generics(
 }
 
 /// Returns `(named generics, metadata, query kind)`
+///
+/// The size tracked counterpart of a storage additionally accepts the optional `MaxBytes`.
 fn process_named_generics(
 	storage: &StorageKind,
+	size_tracked: bool,
 	args_span: proc_macro2::Span,
 	args: &[syn::Binding],
 ) -> syn::Result<(Option<StorageGenerics>, Metadata, Option<syn::Type>)> {
@@ -344,13 +353,28 @@ fn process_named_generics(
 		parsed.insert(arg.ident.to_string(), arg.clone());
 	}
 
+	let storage_type_name = |name: &str| {
+		if size_tracked {
+			format!("SizeTracked{}", name)
+		} else {
+			name.to_string()
+		}
+	};
+	let optional_generics = |generics: &[&'static str]| {
+		let mut generics = generics.to_vec();
+		if size_tracked {
+			generics.push("MaxBytes");
+		}
+		generics
+	};
+
 	let generics = match storage {
 		StorageKind::Value => {
 			check_generics(
 				&parsed,
 				&["Value"],
-				&["QueryKind", "OnEmpty"],
-				"StorageValue",
+				&optional_generics(&["QueryKind", "OnEmpty"]),
+				&storage_type_name("StorageValue"),
 				args_span,
 			)?;
 
@@ -361,14 +385,15 @@ fn process_named_generics(
 					.expect("checked above as mandatory generic"),
 				query_kind: parsed.remove("QueryKind").map(|binding| binding.ty),
 				on_empty: parsed.remove("OnEmpty").map(|binding| binding.ty),
+				max_bytes: parsed.remove("MaxBytes").map(|binding| binding.ty),
 			}
 		},
 		StorageKind::Map => {
 			check_generics(
 				&parsed,
 				&["Hasher", "Key", "Value"],
-				&["QueryKind", "OnEmpty", "MaxValues"],
-				"StorageMap",
+				&optional_generics(&["QueryKind", "OnEmpty", "MaxValues"]),
+				&storage_type_name("StorageMap"),
 				args_span,
 			)?;
 
@@ -388,6 +413,7 @@ fn process_named_generics(
 				query_kind: parsed.remove("QueryKind").map(|binding| binding.ty),
 				on_empty: parsed.remove("OnEmpty").map(|binding| binding.ty),
 				max_values: parsed.remove("MaxValues").map(|binding| binding.ty),
+				max_bytes: parsed.remove("MaxBytes").map(|binding| binding.ty),
 			}
 		},
 		StorageKind::CountedMap => {
@@ -421,8 +447,8 @@ fn process_named_generics(
 			check_generics(
 				&parsed,
 				&["Hasher1", "Key1", "Hasher2", "Key2", "Value"],
-				&["QueryKind", "OnEmpty", "MaxValues"],
-				"StorageDoubleMap",
+				&optional_generics(&["QueryKind", "OnEmpty", "MaxValues"]),
+				&storage_type_name("StorageDoubleMap"),
 				args_span,
 			)?;
 
@@ -450,14 +476,15 @@ fn process_named_generics(
 				query_kind: parsed.remove("QueryKind").map(|binding| binding.ty),
 				on_empty: parsed.remove("OnEmpty").map(|binding| binding.ty),
 				max_values: parsed.remove("MaxValues").map(|binding| binding.ty),
+				max_bytes: parsed.remove("MaxBytes").map(|binding| binding.ty),
 			}
 		},
 		StorageKind::NMap => {
 			check_generics(
 				&parsed,
 				&["Key", "Value"],
-				&["QueryKind", "OnEmpty", "MaxValues"],
-				"StorageNMap",
+				&optional_generics(&["QueryKind", "OnEmpty", "MaxValues"]),
+				&storage_type_name("StorageNMap"),
 				args_span,
 			)?;
 
@@ -473,6 +500,7 @@ fn process_named_generics(
 				query_kind: parsed.remove("QueryKind").map(|binding| binding.ty),
 				on_empty: parsed.remove("OnEmpty").map(|binding| binding.ty),
 				max_values: parsed.remove("MaxValues").map(|binding| binding.ty),
+				max_bytes: parsed.remove("MaxBytes").map(|binding| binding.ty),
 			}
 		},
 	};
@@ -542,21 +570,25 @@ fn process_unnamed_generics(
 	Ok(res)
 }
 
-/// Returns `(named generics, metadata, query kind)`
+/// Returns `(named generics, metadata, query kind, size tracked)`
 fn process_generics(
 	segment: &syn::PathSegment,
-) -> syn::Result<(Option<StorageGenerics>, Metadata, Option<syn::Type>)> {
-	let storage_kind = match &*segment.ident.to_string() {
-		"StorageValue" => StorageKind::Value,
-		"StorageMap" => StorageKind::Map,
-		"CountedStorageMap" => StorageKind::CountedMap,
-		"StorageDoubleMap" => StorageKind::DoubleMap,
-		"StorageNMap" => StorageKind::NMap,
+) -> syn::Result<(Option<StorageGenerics>, Metadata, Option<syn::Type>, bool)> {
+	let (storage_kind, size_tracked) = match &*segment.ident.to_string() {
+		"StorageValue" => (StorageKind::Value, false),
+		"StorageMap" => (StorageKind::Map, false),
+		"CountedStorageMap" => (StorageKind::CountedMap, false),
+		"StorageDoubleMap" => (StorageKind::DoubleMap, false),
+		"StorageNMap" => (StorageKind::NMap, false),
+		"SizeTrackedStorageValue" => (StorageKind::Value, true),
+		"SizeTrackedStorageMap" => (StorageKind::Map, true),
+		"SizeTrackedStorageDoubleMap" => (StorageKind::DoubleMap, true),
+		"SizeTrackedStorageNMap" => (StorageKind::NMap, true),
 		found => {
 			let msg = format!(
 				"Invalid pallet::storage, expected ident: `StorageValue` or \
 				`StorageMap` or `CountedStorageMap` or `StorageDoubleMap` or `StorageNMap` \
-				in order to expand metadata, found `{}`.",
+				or their `SizeTracked` counterpart in order to expand metadata, found `{}`.",
 				found,
 			);
 			return Err(syn::Error::new(segment.ident.span(), msg))
@@ -583,7 +615,9 @@ fn process_generics(
 				_ => unreachable!("It is asserted above that all generics are types"),
 			})
 			.collect::<Vec<_>>();
-		process_unnamed_generics(&storage_kind, args_span, &args)
+		let (named_generics, metadata, query_kind) =
+			process_unnamed_generics(&storage_kind, args_span, &args)?;
+		Ok((named_generics, metadata, query_kind, size_tracked))
 	} else if args.args.iter().all(|gen| matches!(gen, syn::GenericArgument::Binding(_))) {
 		let args = args
 			.args
@@ -593,7 +627,9 @@ fn process_generics(
 				_ => unreachable!("It is asserted above that all generics are bindings"),
 			})
 			.collect::<Vec<_>>();
-		process_named_generics(&storage_kind, args_span, &args)
+		let (named_generics, metadata, query_kind) =
+			process_named_generics(&storage_kind, size_tracked, args_span, &args)?;
+		Ok((named_generics, metadata, query_kind, size_tracked))
 	} else {
 		let msg = "Invalid pallet::storage, invalid generic declaration for storage. Expect only \
 			type generics or binding generics, e.g. `<Name1 = Gen1, Name2 = Gen2, ..>` or \
@@ -711,7 +747,8 @@ impl StorageDef {
 			return Err(syn::Error::new(item.ty.span(), msg))
 		}
 
-		let (named_generics, metadata, query_kind) = process_generics(&typ.path.segments[0])?;
+		let (named_generics, metadata, query_kind, size_tracked) =
+			process_generics(&typ.path.segments[0])?;
 
 		let query_kind = query_kind
 			.map(|query_kind| {
@@ -832,6 +869,7 @@ impl StorageDef {
 			named_generics,
 			unbounded,
 			whitelisted,
+			size_tracked,
 		})
 	}
 }
@@ -0,0 +1,243 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use super::helper;
+use frame_support_procedural_tools::get_doc_literals;
+use std::collections::HashMap;
+use syn::spanned::Spanned;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(pallet);
+	syn::custom_keyword!(task_index);
+	syn::custom_keyword!(task_list);
+	syn::custom_keyword!(task_condition);
+	syn::custom_keyword!(task_weight);
+}
+
+/// Definition of the tasks, i.e. `impl<T: Config> Pallet<T> { .. }`.
+pub struct TasksDef {
+	/// The where_clause used.
+	pub where_clause: Option<syn::WhereClause>,
+	/// A set of usage of instance, must be check for consistency with trait.
+	pub instances: Vec<helper::InstanceUsage>,
+	/// The index of tasks item in pallet module.
+	pub index: usize,
+	/// The tasks defined.
+	pub tasks: Vec<TaskDef>,
+	/// The span of the pallet::tasks attribute.
+	pub attr_span: proc_macro2::Span,
+}
+
+/// Definition of a task.
+pub struct TaskDef {
+	/// Name of the function.
+	pub ident: syn::Ident,
+	/// The index of the task, used to encode it.
+	pub task_index: u8,
+	/// The arguments of the function, with their names.
+	pub args: Vec<(syn::Ident, syn::Type)>,
+	/// The iterator over the arguments of the tasks to run, given by `#[pallet::task_list]`.
+	pub list: syn::Expr,
+	/// The closure checking the task is valid, given by `#[pallet::task_condition]`.
+	pub condition: syn::Expr,
+	/// The weight of the task, given by `#[pallet::task_weight]`.
+	pub weight: syn::Expr,
+	/// The doc associated.
+	pub docs: Vec<syn::Lit>,
+}
+
+/// Attributes for functions in tasks impl block.
+pub enum TaskAttr {
+	Index(u8),
+	List(syn::Expr),
+	Condition(syn::Expr),
+	Weight(syn::Expr),
+}
+
+impl syn::parse::Parse for TaskAttr {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		input.parse::<syn::Token![#]>()?;
+		let content;
+		syn::bracketed!(content in input);
+		content.parse::<keyword::pallet>()?;
+		content.parse::<syn::Token![::]>()?;
+
+		let lookahead = content.lookahead1();
+		if lookahead.peek(keyword::task_index) {
+			content.parse::<keyword::task_index>()?;
+			let index_content;
+			syn::parenthesized!(index_content in content);
+			let index = index_content.parse::<syn::LitInt>()?;
+			Ok(TaskAttr::Index(index.base10_parse()?))
+		} else if lookahead.peek(keyword::task_list) {
+			content.parse::<keyword::task_list>()?;
+			let list_content;
+			syn::parenthesized!(list_content in content);
+			Ok(TaskAttr::List(list_content.parse::<syn::Expr>()?))
+		} else if lookahead.peek(keyword::task_condition) {
+			content.parse::<keyword::task_condition>()?;
+			let condition_content;
+			syn::parenthesized!(condition_content in content);
+			Ok(TaskAttr::Condition(condition_content.parse::<syn::Expr>()?))
+		} else if lookahead.peek(keyword::task_weight) {
+			content.parse::<keyword::task_weight>()?;
+			let weight_content;
+			syn::parenthesized!(weight_content in content);
+			Ok(TaskAttr::Weight(weight_content.parse::<syn::Expr>()?))
+		} else {
+			Err(lookahead.error())
+		}
+	}
+}
+
+impl TasksDef {
+	pub fn try_from(
+		attr_span: proc_macro2::Span,
+		index: usize,
+		item: &mut syn::Item,
+	) -> syn::Result<Self> {
+		let item = if let syn::Item::Impl(item) = item {
+			item
+		} else {
+			let msg = "Invalid pallet::tasks, expected item impl";
+			return Err(syn::Error::new(item.span(), msg))
+		};
+
+		let instances = vec![
+			helper::check_impl_gen(&item.generics, item.impl_token.span())?,
+			helper::check_pallet_struct_usage(&item.self_ty)?,
+		];
+
+		if let Some((_, _, for_)) = item.trait_ {
+			let msg = "Invalid pallet::tasks, expected no trait ident as in \
+				`impl<..> Pallet<..> { .. }`";
+			return Err(syn::Error::new(for_.span(), msg))
+		}
+
+		let mut tasks = vec![];
+		let mut indices = HashMap::new();
+		let mut last_index: Option<u8> = None;
+		for impl_item in &mut item.items {
+			let method = if let syn::ImplItem::Method(method) = impl_item {
+				method
+			} else {
+				let msg = "Invalid pallet::tasks, only method accepted";
+				return Err(syn::Error::new(impl_item.span(), msg))
+			};
+
+			if !method.sig.generics.params.is_empty() {
+				let msg = "Invalid pallet::tasks, method must have 0 generics";
+				return Err(syn::Error::new(method.sig.generics.params[0].span(), msg))
+			}
+
+			if method.sig.generics.where_clause.is_some() {
+				let msg = "Invalid pallet::tasks, method must have no where clause";
+				return Err(syn::Error::new(method.sig.generics.where_clause.span(), msg))
+			}
+
+			if let syn::ReturnType::Default = method.sig.output {
+				let msg = "Invalid pallet::tasks, require return type DispatchResult";
+				return Err(syn::Error::new(method.sig.span(), msg))
+			}
+
+			let mut args = vec![];
+			for input in &method.sig.inputs {
+				let input = match input {
+					syn::FnArg::Typed(input) => input,
+					syn::FnArg::Receiver(_) => {
+						let msg = "Invalid pallet::tasks, method must not take self";
+						return Err(syn::Error::new(input.span(), msg))
+					},
+				};
+				let ident = match &*input.pat {
+					syn::Pat::Ident(pat) => pat.ident.clone(),
+					_ => {
+						let msg = "Invalid pallet::tasks, argument must be ident";
+						return Err(syn::Error::new(input.pat.span(), msg))
+					},
+				};
+				args.push((ident, *input.ty.clone()));
+			}
+
+			let mut task_index = None;
+			let mut list = None;
+			let mut condition = None;
+			let mut weight = None;
+			for attr in helper::take_item_pallet_attrs(&mut method.attrs)? {
+				let (slot, name) = match attr {
+					TaskAttr::Index(i) => (task_index.replace(i).map(|_| ()), "task_index"),
+					TaskAttr::List(e) => (list.replace(e).map(|_| ()), "task_list"),
+					TaskAttr::Condition(e) => (condition.replace(e).map(|_| ()), "task_condition"),
+					TaskAttr::Weight(e) => (weight.replace(e).map(|_| ()), "task_weight"),
+				};
+				if slot.is_some() {
+					let msg = format!("Invalid pallet::tasks, too many {} attributes given", name);
+					return Err(syn::Error::new(method.sig.span(), msg))
+				}
+			}
+
+			let missing = |name| {
+				let msg = format!(
+					"Invalid pallet::tasks, requires {} attribute i.e. `#[pallet::{}($expr)]`",
+					name, name,
+				);
+				syn::Error::new(method.sig.span(), msg)
+			};
+			let list = list.ok_or_else(|| missing("task_list"))?;
+			let condition = condition.ok_or_else(|| missing("task_condition"))?;
+			let weight = weight.ok_or_else(|| missing("task_weight"))?;
+
+			let final_index = match task_index {
+				Some(i) => i,
+				None =>
+					last_index.map_or(Some(0), |idx| idx.checked_add(1)).ok_or_else(|| {
+						let msg = "Task index doesn't fit into u8, index is 256";
+						syn::Error::new(method.sig.span(), msg)
+					})?,
+			};
+			last_index = Some(final_index);
+
+			if let Some(used_fn) = indices.insert(final_index, method.sig.ident.clone()) {
+				let msg = format!(
+					"Task indices are conflicting: Both functions {} and {} are at index {}",
+					used_fn, method.sig.ident, final_index,
+				);
+				let mut err = syn::Error::new(used_fn.span(), &msg);
+				err.combine(syn::Error::new(method.sig.ident.span(), msg));
+				return Err(err)
+			}
+
+			tasks.push(TaskDef {
+				ident: method.sig.ident.clone(),
+				task_index: final_index,
+				args,
+				list,
+				condition,
+				weight,
+				docs: get_doc_literals(&method.attrs),
+			});
+		}
+
+		Ok(Self {
+			where_clause: item.generics.where_clause.clone(),
+			instances,
+			index,
+			tasks,
+			attr_span,
+		})
+	}
+}
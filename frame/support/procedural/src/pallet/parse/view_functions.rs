@@ -0,0 +1,133 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use super::helper;
+use frame_support_procedural_tools::get_doc_literals;
+use syn::spanned::Spanned;
+
+/// Definition of the view functions, i.e. `impl<T: Config> Pallet<T> { .. }`.
+pub struct ViewFunctionsDef {
+	/// The where_clause used.
+	pub where_clause: Option<syn::WhereClause>,
+	/// A set of usage of instance, must be check for consistency with trait.
+	pub instances: Vec<helper::InstanceUsage>,
+	/// The index of view functions item in pallet module.
+	pub index: usize,
+	/// The view functions defined.
+	pub view_functions: Vec<ViewFunctionDef>,
+	/// The span of the pallet::view_functions attribute.
+	pub attr_span: proc_macro2::Span,
+}
+
+/// Definition of a view function.
+pub struct ViewFunctionDef {
+	/// Name of the function.
+	pub ident: syn::Ident,
+	/// The arguments of the function, with their names.
+	pub args: Vec<(syn::Ident, syn::Type)>,
+	/// The type returned by the function.
+	pub return_type: syn::Type,
+	/// The doc associated.
+	pub docs: Vec<syn::Lit>,
+}
+
+impl ViewFunctionsDef {
+	pub fn try_from(
+		attr_span: proc_macro2::Span,
+		index: usize,
+		item: &mut syn::Item,
+	) -> syn::Result<Self> {
+		let item = if let syn::Item::Impl(item) = item {
+			item
+		} else {
+			let msg = "Invalid pallet::view_functions, expected item impl";
+			return Err(syn::Error::new(item.span(), msg))
+		};
+
+		let instances = vec![
+			helper::check_impl_gen(&item.generics, item.impl_token.span())?,
+			helper::check_pallet_struct_usage(&item.self_ty)?,
+		];
+
+		if let Some((_, _, for_)) = item.trait_ {
+			let msg = "Invalid pallet::view_functions, expected no trait ident as in \
+				`impl<..> Pallet<..> { .. }`";
+			return Err(syn::Error::new(for_.span(), msg))
+		}
+
+		let mut view_functions = vec![];
+		for impl_item in &mut item.items {
+			let method = if let syn::ImplItem::Method(method) = impl_item {
+				method
+			} else {
+				let msg = "Invalid pallet::view_functions, only method accepted";
+				return Err(syn::Error::new(impl_item.span(), msg))
+			};
+
+			if !method.sig.generics.params.is_empty() {
+				let msg = "Invalid pallet::view_functions, method must have 0 generics";
+				return Err(syn::Error::new(method.sig.generics.params[0].span(), msg))
+			}
+
+			if method.sig.generics.where_clause.is_some() {
+				let msg = "Invalid pallet::view_functions, method must have no where clause";
+				return Err(syn::Error::new(method.sig.generics.where_clause.span(), msg))
+			}
+
+			let mut args = vec![];
+			for input in &method.sig.inputs {
+				let input = match input {
+					syn::FnArg::Typed(input) => input,
+					syn::FnArg::Receiver(_) => {
+						let msg = "Invalid pallet::view_functions, method must not take self";
+						return Err(syn::Error::new(input.span(), msg))
+					},
+				};
+				let ident = match &*input.pat {
+					syn::Pat::Ident(pat) => pat.ident.clone(),
+					_ => {
+						let msg = "Invalid pallet::view_functions, argument must be ident";
+						return Err(syn::Error::new(input.pat.span(), msg))
+					},
+				};
+				args.push((ident, *input.ty.clone()));
+			}
+
+			let return_type = match &method.sig.output {
+				syn::ReturnType::Default => {
+					let msg = "Invalid pallet::view_functions, method must have a return type";
+					return Err(syn::Error::new(method.sig.span(), msg))
+				},
+				syn::ReturnType::Type(_, type_) => *type_.clone(),
+			};
+
+			view_functions.push(ViewFunctionDef {
+				ident: method.sig.ident.clone(),
+				args,
+				return_type,
+				docs: get_doc_literals(&method.attrs),
+			});
+		}
+
+		Ok(Self {
+			where_clause: item.generics.where_clause.clone(),
+			instances,
+			index,
+			view_functions,
+			attr_span,
+		})
+	}
+}
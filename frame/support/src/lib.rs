@@ -81,6 +81,7 @@ pub mod crypto;
 pub mod instances;
 pub mod migrations;
 pub mod traits;
+pub mod view_functions;
 pub mod weights;
 
 #[doc(hidden)]
@@ -1396,8 +1397,10 @@ pub mod pallet_prelude {
 		storage::{
 			bounded_vec::BoundedVec,
 			types::{
-				CountedStorageMap, Key as NMapKey, OptionQuery, ResultQuery, StorageDoubleMap,
-				StorageMap, StorageNMap, StorageValue, ValueQuery,
+				CountedStorageMap, Key as NMapKey, OptionQuery, ResultQuery,
+				SizeTrackedStorageDoubleMap, SizeTrackedStorageMap, SizeTrackedStorageNMap,
+				SizeTrackedStorageValue, StorageDoubleMap, StorageMap, StorageNMap, StorageValue,
+				ValueQuery,
 			},
 		},
 		traits::{
@@ -1460,12 +1463,16 @@ pub mod pallet_prelude {
 /// * [`pallet::generate_store($vis trait Store)`](#palletgenerate_storevis-trait-store)
 /// * [`pallet::generate_storage_info`](#palletgenerate_storage_info)
 /// * [`pallet::storage_version`](#palletstorage_version)
+/// * [`pallet::max_storage_bytes`](#palletmax_storage_bytes)
 /// * [`pallet::hooks`](#hooks-pallethooks-optional)
 /// * [`pallet::call`](#call-palletcall-optional)
 /// * [`pallet::weight($expr)`](#palletweightexpr)
 /// * [`pallet::compact`](#palletcompact-some_arg-some_type)
 /// * [`pallet::call_index($idx)`](#palletcall_indexidx)
 /// * [`pallet::extra_constants`](#extra-constants-palletextra_constants-optional)
+/// * [`pallet::view_functions`](#view-functions-palletview_functions-optional)
+/// * [`pallet::tasks`](#tasks-pallettasks-optional)
+/// * [`pallet::composite_enum`](#composite-enum-palletcomposite_enum-optional)
 /// * [`pallet::error`](#error-palleterror-optional)
 /// * [`pallet::event`](#event-palletevent-optional)
 /// * [`pallet::generate_deposit($visibility fn
@@ -1698,6 +1705,23 @@ pub mod pallet_prelude {
 ///
 /// Also see [`pallet::storage_version`](`frame_support::pallet_macros::storage_version`)
 ///
+/// # `pallet::max_storage_bytes`
+///
+/// The number of bytes occupied by all the size tracked storages of the pallet, such as
+/// [`SizeTrackedStorageMap`](`pallet_prelude::SizeTrackedStorageMap`), can be bounded by using
+/// the `pallet::max_storage_bytes` attribute with a type implementing `Get<Option<u64>>`:
+///
+/// ```ignore
+/// #[pallet::pallet]
+/// #[pallet::max_storage_bytes(T::MaxStorageBytes)]
+/// pub struct Pallet<T>(_);
+/// ```
+///
+/// Writes to a size tracked storage which would exceed it are rejected. If not present, the
+/// size tracked storages of the pallet are only bounded by their own `MaxBytes`.
+///
+/// Also see [`pallet::max_storage_bytes`](`frame_support::pallet_macros::max_storage_bytes`)
+///
 /// # Hooks: `#[pallet::hooks]` (optional)
 ///
 /// The `pallet::hooks` attribute allows you to specify a `Hooks` implementation for `Pallet`
@@ -1808,6 +1832,113 @@ pub mod pallet_prelude {
 ///
 /// Also see: [`pallet::extra_constants`](`frame_support::pallet_macros::extra_constants`)
 ///
+/// # View functions: `#[pallet::view_functions]` (optional)
+///
+/// Allows you to define read-only functions which clients can call through the single
+/// `RuntimeViewFunction` runtime API, and which are described in its metadata.
+///
+/// Item must be defined as:
+///
+/// ```ignore
+/// #[pallet::view_functions]
+/// impl<T: Config> Pallet<T> where $optional_where_clause {
+/// 	/// $some_doc
+/// 	$vis fn $fn_name($some_arg: $some_type, ...) -> $some_return_type {
+/// 		...
+/// 	}
+/// 	...
+/// }
+/// ```
+/// I.e. a regular rust `impl` block with some optional where clause and functions with 0
+/// generics, arguments which are plain identifiers, and some return type. The argument types
+/// must implement `Encode`, `Decode` and `TypeInfo`, the return type `Encode` and `TypeInfo`.
+///
+/// ## Macro expansion
+///
+/// For each function the macro generates a struct named after the function in upper camel
+/// case (e.g. `GetValue` for `get_value`) holding its arguments. It implements
+/// `frame_support::view_functions::ViewFunction`, whose id is made of the hash of the pallet
+/// name and the hash of the function name and of the encoding of its argument and return types,
+/// see `frame_support::view_functions::view_function_id_suffix`.
+///
+/// The macro implements `DispatchViewFunction` and `ViewFunctionsMetadata` on `Pallet`, so
+/// that the runtime can implement the `RuntimeViewFunction` runtime API with
+/// `AllPalletsWithSystem`, see [`frame_support::view_functions`].
+///
+/// Also see: [`pallet::view_functions`](`frame_support::pallet_macros::view_functions`)
+///
+/// # Tasks: `#[pallet::tasks]` (optional)
+///
+/// Allows you to define work which isn't bound to a block, such as cleaning up storage: the
+/// tasks are enumerable, weighed, and checked to still be valid before they run.
+///
+/// Item must be defined as:
+///
+/// ```ignore
+/// #[pallet::tasks]
+/// impl<T: Config> Pallet<T> where $optional_where_clause {
+/// 	/// $some_doc
+/// 	#[pallet::task_index($idx)] // optional
+/// 	#[pallet::task_list($iter_expr)]
+/// 	#[pallet::task_condition($closure)]
+/// 	#[pallet::task_weight($weight_expr)]
+/// 	$vis fn $fn_name($some_arg: $some_type, ...) -> DispatchResult {
+/// 		...
+/// 	}
+/// 	...
+/// }
+/// ```
+/// I.e. a regular rust `impl` block with some optional where clause and functions with 0
+/// generics and arguments which are plain identifiers, returning `DispatchResult`. For each
+/// function:
+/// * `task_list` is an iterator over the arguments of the tasks to run, as a tuple if the
+///   function has several arguments,
+/// * `task_condition` is a closure taking a reference to each argument and returning whether
+///   the task is still valid,
+/// * `task_weight` is the weight of the task, with a reference to each argument in scope,
+/// * `task_index` is the index of the task in the encoding, like `call_index` for calls.
+///
+/// ## Macro expansion
+///
+/// The macro creates an enum `Task` with one variant per function, holding its arguments, and
+/// implements [`traits::Task`] on it. `construct_runtime` aggregates the `Task`
+/// enums of the pallets into `RuntimeTask`, the `frame_system::Config::RuntimeTask` of the
+/// runtime.
+///
+/// `frame_executive` runs the valid tasks of `RuntimeTask::iter()` while there is weight left
+/// at the end of a block, and anyone can submit a valid task as an unsigned
+/// `frame_system::Call::do_task`.
+///
+/// Also see: [`pallet::tasks`](`frame_support::pallet_macros::tasks`)
+///
+/// # Composite enum: `#[pallet::composite_enum]` (optional)
+///
+/// Allows you to define an enum which `construct_runtime` aggregates with the ones of the other
+/// pallets, such as the reasons for which the pallet holds or freezes funds.
+///
+/// Item must be defined as:
+///
+/// ```ignore
+/// #[pallet::composite_enum]
+/// pub enum HoldReason {
+/// 	/// $some_doc
+/// 	$SomeVariant,
+/// 	...
+/// }
+/// ```
+/// I.e. a public enum without generics and with unit variants, named either `HoldReason` or
+/// `FreezeReason`.
+///
+/// ## Macro expansion
+///
+/// The macro derives `Copy`, `Clone`, `PartialEq`, `Eq`, `PartialOrd`, `Ord`, `Encode`,
+/// `Decode`, `MaxEncodedLen`, `TypeInfo` and `RuntimeDebug` on the enum and implements
+/// [`traits::VariantCount`] on it. `construct_runtime` aggregates the enums of the pallets into
+/// `RuntimeHoldReason` and `RuntimeFreezeReason`, which a runtime gives to `pallet_balances` as
+/// `HoldIdentifier` and `FreezeIdentifier`, bounded by [`traits::VariantCountOf`].
+///
+/// Also see: [`pallet::composite_enum`](`frame_support::pallet_macros::composite_enum`)
+///
 /// # Error: `#[pallet::error]` (optional)
 ///
 /// The `#[pallet::error]` attribute allows you to define an error enum that will be returned
@@ -1921,7 +2052,12 @@ pub mod pallet_prelude {
 /// * [`CountedStorageMap`](`pallet_prelude::CountedStorageMap`) expects `Hasher`, `Key`,
 ///   `Value` and optionally `QueryKind` and `OnEmpty`,
 /// * [`StorageDoubleMap`](`pallet_prelude::StorageDoubleMap`) expects `Hasher1`, `Key1`,
-///   `Hasher2`, `Key2`, `Value` and optionally `QueryKind` and `OnEmpty`.
+///   `Hasher2`, `Key2`, `Value` and optionally `QueryKind` and `OnEmpty`,
+/// * [`SizeTrackedStorageValue`](`pallet_prelude::SizeTrackedStorageValue`),
+///   [`SizeTrackedStorageMap`](`pallet_prelude::SizeTrackedStorageMap`),
+///   [`SizeTrackedStorageDoubleMap`](`pallet_prelude::SizeTrackedStorageDoubleMap`) and
+///   [`SizeTrackedStorageNMap`](`pallet_prelude::SizeTrackedStorageNMap`) expect the same
+///   generics as their counterpart and optionally `MaxBytes`.
 ///
 /// For unnamed generic arguments: Their first generic must be `_` as it is replaced by the
 /// macro and other generic must declared as a normal generic type declaration.
@@ -1940,6 +2076,18 @@ pub mod pallet_prelude {
 /// CountedStorageaMap<...>` will store its counter at the prefix: `Twox128(b"MyExample") ++
 /// Twox128(b"CounterForFoo")`.
 ///
+/// Similarly, for the size tracked variants, e.g.
+/// [`SizeTrackedStorageMap`](`pallet_prelude::SizeTrackedStorageMap`), the `Prefix` also
+/// implements [`SizeTrackedStorageInstance`](storage::types::SizeTrackedStorageInstance)
+/// and the number of bytes occupied by the storage is stored at the prefix
+/// `Twox128(b"MyExample") ++ Twox128(b"ByteSizeForFoo")`. The number of bytes occupied by all
+/// the size tracked storages of the pallet is stored at the prefix `Twox128(b"MyExample") ++
+/// Twox128(b"StorageByteSize")`. Writes which would exceed the optional `MaxBytes` of the
+/// storage, or the [`pallet::max_storage_bytes`](#palletmax_storage_bytes) of the pallet, are
+/// rejected. These sizes are returned by [`StorageSizeTrait`](`traits::StorageSizeTrait`),
+/// implemented on the `Pallet`, and can be exposed with the `sp_storage_size::StorageSizeApi`
+/// runtime API.
+///
 /// E.g:
 ///
 /// ```ignore
@@ -2050,7 +2198,10 @@ pub mod pallet_prelude {
 /// then uses it as the first generic of the aliased type. For
 /// [`CountedStorageMap`](`pallet_prelude::CountedStorageMap`),
 /// [`CountedStorageMapInstance`](`frame_support::storage::types::CountedStorageMapInstance`)
-/// is implemented, and another similar struct is generated.
+/// is implemented, and another similar struct is generated. The same goes for
+/// the size tracked variants and
+/// [`SizeTrackedStorageInstance`](storage::types::SizeTrackedStorageInstance), with one more
+/// struct for the size of the pallet.
 ///
 /// For a named generic, the macro will reorder the generics, and remove the names.
 ///
@@ -2738,9 +2889,10 @@ pub use frame_support_procedural::pallet;
 /// Contains macro stubs for all of the pallet:: macros
 pub mod pallet_macros {
 	pub use frame_support_procedural::{
-		call_index, compact, config, constant, disable_frame_system_supertrait_check, error, event,
-		extra_constants, generate_deposit, generate_storage_info, generate_store, genesis_build,
-		genesis_config, getter, hooks, inherent, origin, storage, storage_prefix, storage_version,
-		type_value, unbounded, validate_unsigned, weight, whitelist_storage,
+		call_index, compact, composite_enum, config, constant, disable_frame_system_supertrait_check,
+		error, event, extra_constants, generate_deposit, generate_storage_info, generate_store,
+		genesis_build, genesis_config, getter, hooks, inherent, max_storage_bytes, origin, storage,
+		storage_prefix, storage_version, task_condition, task_index, task_list, task_weight, tasks, type_value,
+		unbounded, validate_unsigned, view_functions, weight, whitelist_storage,
 	};
 }
@@ -16,10 +16,12 @@
 // limitations under the License.
 
 use crate::{
-	traits::{GetStorageVersion, PalletInfoAccess},
-	weights::{RuntimeDbWeight, Weight},
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	weights::{RuntimeDbWeight, Weight, WeightMeter},
 };
+use codec::{Decode, Encode, FullCodec, MaxEncodedLen};
 use impl_trait_for_tuples::impl_for_tuples;
+use sp_std::{marker::PhantomData, vec::Vec};
 
 /// Trait used by [`migrate_from_pallet_version_to_storage_version`] to do the actual migration.
 pub trait PalletVersionToStorageVersionHelper {
@@ -67,3 +69,348 @@ pub fn migrate_from_pallet_version_to_storage_version<
 ) -> Weight {
 	Pallets::migrate(db_weight)
 }
+
+/// Executes the migration `Inner` if the on-chain storage version of `Pallet` is `FROM`, and then
+/// sets it to `TO`.
+///
+/// Otherwise `Inner` is skipped, e.g. because it already ran in a previous runtime upgrade, or
+/// because it is ordered after a migration to a later storage version. `TO` must be greater than
+/// `FROM`, which is checked at compile time, and at most the in-code storage version of
+/// `Pallet`, which is checked by the try-runtime post-upgrade hook.
+///
+/// `Inner` does not need to check nor set the storage version itself.
+///
+/// # Example
+///
+/// ```ignore
+/// pub type MigrateV0ToV1<T> = VersionedMigration<
+///     0,
+///     1,
+///     v1::MigrateToV1<T>,
+///     crate::Pallet<T>,
+///     <T as frame_system::Config>::DbWeight,
+/// >;
+/// ```
+pub struct VersionedMigration<const FROM: u16, const TO: u16, Inner, Pallet, DbWeight>(
+	PhantomData<(Inner, Pallet, DbWeight)>,
+);
+
+impl<const FROM: u16, const TO: u16, Inner, Pallet, DbWeight>
+	VersionedMigration<FROM, TO, Inner, Pallet, DbWeight>
+{
+	/// Fails to compile if the migration does not increase the storage version.
+	const INCREASES_VERSION: () =
+		assert!(FROM < TO, "`VersionedMigration` must migrate to a greater storage version");
+}
+
+/// State passed from the pre- to the post-upgrade hook of a [`VersionedMigration`].
+#[cfg(feature = "try-runtime")]
+#[derive(Encode, Decode)]
+enum VersionedPostUpgradeData {
+	/// The inner migration is executed, with the state of its pre-upgrade hook.
+	MigrationExecuted(Vec<u8>),
+	/// The inner migration is skipped.
+	Noop,
+}
+
+impl<const FROM: u16, const TO: u16, Inner, Pallet, DbWeight> OnRuntimeUpgrade
+	for VersionedMigration<FROM, TO, Inner, Pallet, DbWeight>
+where
+	Inner: OnRuntimeUpgrade,
+	Pallet: GetStorageVersion + PalletInfoAccess,
+	DbWeight: Get<RuntimeDbWeight>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		#[allow(clippy::let_unit_value)]
+		let _ = Self::INCREASES_VERSION;
+
+		let on_chain_version = Pallet::on_chain_storage_version();
+		if on_chain_version == FROM {
+			log::info!(
+				target: crate::LOG_TARGET,
+				"🚚 Pallet {:?} migrating storage version from {:?} to {:?}.",
+				Pallet::name(),
+				FROM,
+				TO,
+			);
+
+			let weight = Inner::on_runtime_upgrade();
+			StorageVersion::new(TO).put::<Pallet>();
+
+			weight.saturating_add(DbWeight::get().reads_writes(1, 1))
+		} else {
+			log::warn!(
+				target: crate::LOG_TARGET,
+				"🚚 Pallet {:?} migration {}->{} was skipped, \
+				 the on-chain storage version is {:?}.",
+				Pallet::name(),
+				FROM,
+				TO,
+				on_chain_version,
+			);
+
+			DbWeight::get().reads(1)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+		let data = if Pallet::on_chain_storage_version() == FROM {
+			VersionedPostUpgradeData::MigrationExecuted(Inner::pre_upgrade()?)
+		} else {
+			VersionedPostUpgradeData::Noop
+		};
+
+		Ok(data.encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+		if Pallet::current_storage_version() < TO {
+			return Err("`VersionedMigration` migrates beyond the in-code storage version")
+		}
+
+		match VersionedPostUpgradeData::decode(&mut &state[..])
+			.map_err(|_| "Failed to decode the `VersionedMigration` pre-upgrade state")?
+		{
+			VersionedPostUpgradeData::MigrationExecuted(inner_state) => {
+				Inner::post_upgrade(inner_state)?;
+				crate::ensure!(
+					Pallet::on_chain_storage_version() == TO,
+					"`VersionedMigration` did not set the storage version",
+				);
+				Ok(())
+			},
+			VersionedPostUpgradeData::Noop => Ok(()),
+		}
+	}
+}
+
+/// A migration that is executed in steps, across as many blocks as it needs.
+///
+/// Unlike an [`OnRuntimeUpgrade`](crate::traits::OnRuntimeUpgrade), which has to finish in the
+/// block of the runtime upgrade, a stepped migration is resumed every block from the cursor its
+/// last step returned, until it returns no cursor. It is driven by a [`MultiStepMigrator`].
+///
+/// The try-runtime post-upgrade hook of a pallet requires its on-chain storage version to match
+/// its in-code storage version once the `OnRuntimeUpgrade`s executed, so the storage version of a
+/// pallet migrated by a stepped migration should be bumped when the migration is scheduled, e.g. by
+/// a [`VersionedMigration`].
+pub trait SteppedMigration {
+	/// Position in the migration to resume from, e.g. the last migrated storage key.
+	type Cursor: FullCodec + MaxEncodedLen;
+
+	/// Unique identifier of the migration.
+	///
+	/// Migrations whose identifier was already completed are not executed again.
+	type Identifier: FullCodec + MaxEncodedLen;
+
+	/// The identifier of this migration.
+	fn id() -> Self::Identifier;
+
+	/// The maximum number of steps this migration takes, if known.
+	///
+	/// The migration is considered stuck when it did not complete within this many steps.
+	fn max_steps() -> Option<u32> {
+		None
+	}
+
+	/// Make progress from `cursor`, consuming at most the weight left in `meter`.
+	///
+	/// `cursor` is `None` for the first step. Returns the cursor to continue from in the next step
+	/// or `None` once the migration is complete. A step that can not make any progress with the
+	/// weight left should return [`SteppedMigrationError::InsufficientWeight`] without modifying
+	/// storage; it is then retried in the next block.
+	fn step(
+		cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError>;
+
+	/// Execute some pre-checks prior to the first step of the migration.
+	///
+	/// Same as [`OnRuntimeUpgrade::pre_upgrade`](crate::traits::OnRuntimeUpgrade::pre_upgrade).
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+		Ok(Vec::new())
+	}
+
+	/// Execute some post-checks after the last step of the migration.
+	///
+	/// Same as [`OnRuntimeUpgrade::post_upgrade`](crate::traits::OnRuntimeUpgrade::post_upgrade).
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+		Ok(())
+	}
+}
+
+/// Error returned by a [`SteppedMigration::step`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, scale_info::TypeInfo)]
+pub enum SteppedMigrationError {
+	/// Not enough weight was left to make progress; the step should be retried with at least
+	/// `required` weight.
+	InsufficientWeight {
+		/// The weight the step needs to make progress.
+		required: Weight,
+	},
+	/// The cursor could not be decoded.
+	InvalidCursor,
+	/// The migration failed and can not make any further progress.
+	Failed,
+}
+
+/// A sequence of [`SteppedMigration`]s, executed in order.
+///
+/// Implemented for tuples of [`SteppedMigration`]s, with SCALE-encoded identifiers and cursors so
+/// that the migrations of a tuple can be addressed by their index.
+pub trait SteppedMigrations {
+	/// The number of migrations.
+	fn len() -> u32;
+
+	/// The encoded identifier of the `n`th migration, if there is one.
+	fn nth_id(n: u32) -> Option<Vec<u8>>;
+
+	/// The maximum number of steps of the `n`th migration, if there is one.
+	fn nth_max_steps(n: u32) -> Option<Option<u32>>;
+
+	/// Make progress on the `n`th migration from the encoded `cursor`, if there is one.
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>>;
+
+	/// Execute the pre-checks of the `n`th migration, if there is one.
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32) -> Option<Result<Vec<u8>, &'static str>>;
+
+	/// Execute the post-checks of the `n`th migration, if there is one.
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(n: u32, state: Vec<u8>) -> Option<Result<(), &'static str>>;
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+#[tuple_types_custom_trait_bound(SteppedMigration)]
+impl SteppedMigrations for Tuple {
+	fn len() -> u32 {
+		let mut len = 0;
+		for_tuples!( #( len += 1; )* );
+		len
+	}
+
+	fn nth_id(n: u32) -> Option<Vec<u8>> {
+		let mut i = 0;
+		for_tuples!( #(
+			if i == n {
+				return Some(Tuple::id().encode())
+			}
+			i += 1;
+		)* );
+		let _ = i;
+		None
+	}
+
+	fn nth_max_steps(n: u32) -> Option<Option<u32>> {
+		let mut i = 0;
+		for_tuples!( #(
+			if i == n {
+				return Some(Tuple::max_steps())
+			}
+			i += 1;
+		)* );
+		let _ = i;
+		None
+	}
+
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		let mut i = 0;
+		for_tuples!( #(
+			if i == n {
+				return Some(step_encoded::<Tuple>(cursor, meter))
+			}
+			i += 1;
+		)* );
+		let _ = (i, cursor, meter);
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32) -> Option<Result<Vec<u8>, &'static str>> {
+		let mut i = 0;
+		for_tuples!( #(
+			if i == n {
+				return Some(Tuple::pre_upgrade())
+			}
+			i += 1;
+		)* );
+		let _ = i;
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(n: u32, state: Vec<u8>) -> Option<Result<(), &'static str>> {
+		let mut i = 0;
+		for_tuples!( #(
+			if i == n {
+				return Some(Tuple::post_upgrade(state))
+			}
+			i += 1;
+		)* );
+		let _ = (i, state);
+		None
+	}
+}
+
+/// Step `M` from the encoded `cursor`, returning the encoded cursor to continue from.
+fn step_encoded<M: SteppedMigration>(
+	cursor: Option<Vec<u8>>,
+	meter: &mut WeightMeter,
+) -> Result<Option<Vec<u8>>, SteppedMigrationError> {
+	let cursor = cursor
+		.map(|cursor| M::Cursor::decode(&mut &cursor[..]))
+		.transpose()
+		.map_err(|_| SteppedMigrationError::InvalidCursor)?;
+
+	M::step(cursor, meter).map(|next| next.map(|cursor| cursor.encode()))
+}
+
+/// Drives multi-block migrations from the block execution.
+///
+/// The migrations are started by the [`OnRuntimeUpgrade`](crate::traits::OnRuntimeUpgrade)
+/// implementation of the migrator, and stepped once per block after `on_initialize`.
+pub trait MultiStepMigrator {
+	/// Whether a multi-block migration is ongoing.
+	fn ongoing() -> bool;
+
+	/// Whether non-mandatory extrinsics must be rejected, because a migration is ongoing.
+	fn extrinsics_paused() -> bool {
+		false
+	}
+
+	/// Make progress on the ongoing migrations and return the consumed weight.
+	fn step() -> Weight;
+
+	/// Execute the ongoing migrations until they are complete, including the pre and post
+	/// migration checks.
+	///
+	/// This should only be used for testing.
+	#[cfg(feature = "try-runtime")]
+	fn try_run_to_completion() -> Result<Weight, &'static str> {
+		Ok(Weight::zero())
+	}
+}
+
+impl MultiStepMigrator for () {
+	fn ongoing() -> bool {
+		false
+	}
+
+	fn step() -> Weight {
+		Weight::zero()
+	}
+}
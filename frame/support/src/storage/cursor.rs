@@ -0,0 +1,212 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Iteration over storage in batches, resumable in a later call.
+//!
+//! The `iter_batch` functions of the storage maps process items until a [`IterBudget`] is
+//! exhausted and return a [`StorageIterCursor`] to continue from, so that work which does not fit
+//! in a single block (e.g. in `on_idle`, or a cleanup over several blocks) can be written as:
+//!
+//! ```ignore
+//! let cursor = Cursor::<T>::take();
+//! let item_weight = T::DbWeight::get().reads_writes(1, 1);
+//! let next = Stale::<T>::iter_batch(cursor, (&mut meter, item_weight), |key, _value| {
+//! 	Stale::<T>::remove(key);
+//! });
+//! if let Some(next) = next {
+//! 	Cursor::<T>::put(next);
+//! }
+//! ```
+//!
+//! The iteration always continues after the last processed key, so removing the processed items
+//! (or any other item) is fine. Items inserted before the cursor while the iteration is ongoing
+//! are not visited.
+
+use super::{KeyPrefixIterator, PrefixIterator, PrefixIteratorOnRemoval};
+use crate::weights::{Weight, WeightMeter};
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// An opaque position in the iteration of a storage prefix.
+///
+/// It can only be used to resume the iteration it was returned by.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct StorageIterCursor(Vec<u8>);
+
+/// The budget of a batch of a storage iteration.
+pub trait IterBudget {
+	/// Consume the budget for processing one more item, returning `false` if it is exhausted.
+	fn try_consume(&mut self) -> bool;
+}
+
+/// A budget of a number of items.
+impl IterBudget for u32 {
+	fn try_consume(&mut self) -> bool {
+		if *self == 0 {
+			return false
+		}
+		*self -= 1;
+		true
+	}
+}
+
+/// A budget of weight: every item accrues the given weight to the meter.
+impl IterBudget for (&mut WeightMeter, Weight) {
+	fn try_consume(&mut self) -> bool {
+		self.0.check_accrue(self.1)
+	}
+}
+
+/// Resume the iteration at `cursor`, returning the key to continue after or `None` if the cursor
+/// does not belong to `prefix`.
+fn resume(prefix: &[u8], cursor: Option<StorageIterCursor>) -> Option<Vec<u8>> {
+	match cursor {
+		None => Some(prefix.to_vec()),
+		Some(StorageIterCursor(key)) if key.starts_with(prefix) => Some(key),
+		Some(StorageIterCursor(key)) => {
+			log::error!("cursor {:?} is not in the iterated prefix {:?}", key, prefix);
+			None
+		},
+	}
+}
+
+/// The cursor to continue after `previous_key`, `None` if no more keys are in `prefix`.
+fn next_cursor(prefix: &[u8], previous_key: Vec<u8>) -> Option<StorageIterCursor> {
+	sp_io::storage::next_key(&previous_key)
+		.filter(|n| n.starts_with(prefix))
+		.map(|_| StorageIterCursor(previous_key))
+}
+
+impl<T, OnRemoval: PrefixIteratorOnRemoval> PrefixIterator<T, OnRemoval> {
+	/// Call `f` on the items of the iterator after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all items were processed, otherwise the cursor to pass to the next
+	/// call. A cursor not returned by an iteration of the same prefix ends the iteration.
+	pub fn for_each_batch(
+		mut self,
+		cursor: Option<StorageIterCursor>,
+		mut budget: impl IterBudget,
+		mut f: impl FnMut(T),
+	) -> Option<StorageIterCursor> {
+		self.previous_key = resume(&self.prefix, cursor)?;
+		loop {
+			if !budget.try_consume() {
+				return next_cursor(&self.prefix, self.previous_key)
+			}
+			f(self.next()?);
+		}
+	}
+}
+
+impl<T> KeyPrefixIterator<T> {
+	/// Call `f` on the keys of the iterator after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all keys were processed, otherwise the cursor to pass to the next
+	/// call. A cursor not returned by an iteration of the same prefix ends the iteration.
+	pub fn for_each_batch(
+		mut self,
+		cursor: Option<StorageIterCursor>,
+		mut budget: impl IterBudget,
+		mut f: impl FnMut(T),
+	) -> Option<StorageIterCursor> {
+		self.previous_key = resume(&self.prefix, cursor)?;
+		loop {
+			if !budget.try_consume() {
+				return next_cursor(&self.prefix, self.previous_key)
+			}
+			f(self.next()?);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::storage::unhashed;
+	use sp_io::TestExternalities;
+
+	fn iter(prefix: &[u8]) -> PrefixIterator<(u8, u32)> {
+		PrefixIterator::new(prefix.to_vec(), prefix.to_vec(), |raw_key, mut raw_value| {
+			Ok((raw_key[0], u32::decode(&mut raw_value)?))
+		})
+	}
+
+	#[test]
+	fn for_each_batch_resumes_from_cursor() {
+		TestExternalities::default().execute_with(|| {
+			for i in 0u8..5 {
+				unhashed::put(&[1, 2, i], &(i as u32));
+			}
+			unhashed::put(&[1, 3], &42u32);
+
+			let mut items = vec![];
+			let cursor = iter(&[1, 2]).for_each_batch(None, 2, |item| items.push(item));
+			assert_eq!(items, vec![(0, 0), (1, 1)]);
+			assert!(cursor.is_some());
+
+			// Removals during the iteration are fine.
+			let cursor = iter(&[1, 2]).for_each_batch(cursor, 2, |(i, _)| {
+				unhashed::kill(&[1, 2, i]);
+				items.push((i, 0));
+			});
+			assert_eq!(items, vec![(0, 0), (1, 1), (2, 0), (3, 0)]);
+			assert!(!unhashed::exists(&[1, 2, 3]));
+
+			// Exactly exhausting the budget ends the iteration.
+			let cursor = iter(&[1, 2]).for_each_batch(cursor, 1, |item| items.push(item));
+			assert_eq!(items.last(), Some(&(4, 4)));
+			assert_eq!(cursor, None);
+		});
+	}
+
+	#[test]
+	fn for_each_batch_respects_weight_budget() {
+		TestExternalities::default().execute_with(|| {
+			for i in 0u8..5 {
+				unhashed::put(&[1, i], &(i as u32));
+			}
+
+			let item_weight = Weight::from_ref_time(10);
+			let mut meter = WeightMeter::from_limit(Weight::from_ref_time(25));
+			let mut count = 0;
+			let cursor = iter(&[1]).for_each_batch(None, (&mut meter, item_weight), |_| count += 1);
+			assert_eq!(count, 2);
+			assert_eq!(meter.consumed, Weight::from_ref_time(20));
+
+			let mut meter = WeightMeter::max_limit();
+			let cursor =
+				iter(&[1]).for_each_batch(cursor, (&mut meter, item_weight), |_| count += 1);
+			assert_eq!(count, 5);
+			assert_eq!(cursor, None);
+		});
+	}
+
+	#[test]
+	fn for_each_batch_rejects_foreign_cursor() {
+		TestExternalities::default().execute_with(|| {
+			unhashed::put(&[1, 0], &0u32);
+			unhashed::put(&[2, 0], &0u32);
+
+			let cursor = iter(&[1]).for_each_batch(None, 0, |_| ());
+			let mut count = 0;
+			assert_eq!(iter(&[2]).for_each_batch(cursor, 10, |_| count += 1), None);
+			assert_eq!(count, 0);
+		});
+	}
+}
@@ -30,6 +30,7 @@ use sp_runtime::generic::{Digest, DigestItem};
 use sp_std::{collections::btree_set::BTreeSet, marker::PhantomData, prelude::*};
 
 pub use self::{
+	cursor::{IterBudget, StorageIterCursor},
 	transactional::{
 		in_storage_layer, with_storage_layer, with_transaction, with_transaction_unchecked,
 	},
@@ -42,6 +43,7 @@ pub mod bounded_btree_map;
 pub mod bounded_btree_set;
 pub mod bounded_vec;
 pub mod child;
+pub mod cursor;
 #[doc(hidden)]
 pub mod generator;
 pub mod hashed;
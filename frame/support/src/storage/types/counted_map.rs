@@ -443,6 +443,30 @@ where
 	pub fn iter_keys() -> crate::storage::KeyPrefixIterator<Key> {
 		<Self as MapWrapper>::Map::iter_keys()
 	}
+
+	/// Call `f` on the elements of the counted map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all elements were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		f: impl FnMut(Key, Value),
+	) -> Option<crate::storage::StorageIterCursor> {
+		<Self as MapWrapper>::Map::iter_batch(cursor, budget, f)
+	}
+
+	/// Call `f` on the keys of the counted map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all keys were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_keys_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		f: impl FnMut(Key),
+	) -> Option<crate::storage::StorageIterCursor> {
+		<Self as MapWrapper>::Map::iter_keys_batch(cursor, budget, f)
+	}
 }
 
 impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues> StorageEntryMetadataBuilder
@@ -1109,4 +1133,29 @@ mod test {
 			]
 		);
 	}
+
+	#[test]
+	fn iter_batch_keeps_counter() {
+		type A = CountedStorageMap<Prefix, Twox64Concat, u16, u32>;
+
+		TestExternalities::default().execute_with(|| {
+			for i in 0..6 {
+				A::insert(i, i as u32);
+			}
+
+			let cursor = A::iter_batch(None, 4, |k, v| {
+				if v % 2 == 0 {
+					A::remove(k);
+				}
+			});
+			let cursor = A::iter_keys_batch(cursor, 2, |k| {
+				if k % 2 == 0 {
+					A::remove(k);
+				}
+			});
+			assert_eq!(cursor, None);
+			assert_eq!(A::count(), 3);
+			assert_eq!(A::iter().count(), 3);
+		})
+	}
 }
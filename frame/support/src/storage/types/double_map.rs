@@ -640,6 +640,44 @@ where
 	pub fn translate<O: Decode, F: FnMut(Key1, Key2, O) -> Option<Value>>(f: F) {
 		<Self as crate::storage::IterableStorageDoubleMap<Key1, Key2, Value>>::translate(f)
 	}
+
+	/// Call `f` on the elements of the map with first key `k1` after `cursor` as long as
+	/// `budget` allows.
+	///
+	/// Returns `None` once all elements were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_prefix_batch(
+		k1: impl EncodeLike<Key1>,
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		mut f: impl FnMut(Key2, Value),
+	) -> Option<crate::storage::StorageIterCursor> {
+		Self::iter_prefix(k1).for_each_batch(cursor, budget, |(key2, value)| f(key2, value))
+	}
+
+	/// Call `f` on the elements of the map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all elements were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		mut f: impl FnMut(Key1, Key2, Value),
+	) -> Option<crate::storage::StorageIterCursor> {
+		Self::iter().for_each_batch(cursor, budget, |(key1, key2, value)| f(key1, key2, value))
+	}
+
+	/// Call `f` on the keys of the map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all keys were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_keys_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		mut f: impl FnMut(Key1, Key2),
+	) -> Option<crate::storage::StorageIterCursor> {
+		Self::iter_keys().for_each_batch(cursor, budget, |(key1, key2)| f(key1, key2))
+	}
 }
 
 impl<Prefix, Hasher1, Hasher2, Key1, Key2, Value, QueryKind, OnEmpty, MaxValues>
@@ -970,4 +1008,31 @@ mod test {
 			assert_eq!(A::drain_prefix(4).collect::<Vec<_>>(), vec![]);
 		})
 	}
+
+	#[test]
+	fn iter_batch_works() {
+		type A = StorageDoubleMap<Prefix, Blake2_128Concat, u16, Twox64Concat, u8, u32>;
+
+		TestExternalities::default().execute_with(|| {
+			for i in 0..5 {
+				A::insert(3, i, i as u32);
+				A::insert(4, i, i as u32);
+			}
+
+			let mut items = vec![];
+			let cursor = A::iter_prefix_batch(3, None, 2, |k2, v| items.push((k2, v)));
+			assert!(cursor.is_some());
+			let cursor = A::iter_prefix_batch(3, cursor, 2, |k2, _| A::remove(3, k2));
+			assert!(cursor.is_some());
+			assert_eq!(A::iter_prefix_batch(3, cursor, 2, |k2, v| items.push((k2, v))), None);
+			assert_eq!(items.len(), 3);
+			assert_eq!(A::iter_prefix(3).count(), 3);
+			assert_eq!(A::iter_prefix(4).count(), 5);
+
+			let mut count = 0;
+			assert_eq!(A::iter_batch(None, 8, |_, _, _| count += 1), None);
+			assert_eq!(count, 8);
+			assert!(A::iter_keys_batch(None, 7, |_, _| ()).is_some());
+		})
+	}
 }
@@ -396,6 +396,30 @@ where
 	pub fn translate<O: Decode, F: FnMut(Key, O) -> Option<Value>>(f: F) {
 		<Self as crate::storage::IterableStorageMap<Key, Value>>::translate(f)
 	}
+
+	/// Call `f` on the elements of the map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all elements were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		mut f: impl FnMut(Key, Value),
+	) -> Option<crate::storage::StorageIterCursor> {
+		Self::iter().for_each_batch(cursor, budget, |(key, value)| f(key, value))
+	}
+
+	/// Call `f` on the keys of the map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all keys were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_keys_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		f: impl FnMut(Key),
+	) -> Option<crate::storage::StorageIterCursor> {
+		Self::iter_keys().for_each_batch(cursor, budget, f)
+	}
 }
 
 impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues> StorageEntryMetadataBuilder
@@ -695,4 +719,43 @@ mod test {
 			assert_eq!(WithLen::decode_len(0), Some(1));
 		})
 	}
+
+	#[test]
+	fn iter_batch_works() {
+		type A = StorageMap<Prefix, Twox64Concat, u16, u32>;
+
+		TestExternalities::default().execute_with(|| {
+			for i in 0..10 {
+				A::insert(i, i as u32 * 10);
+			}
+
+			let mut keys = vec![];
+			let cursor = A::iter_keys_batch(None, 4, |k| keys.push(k));
+			assert_eq!(keys.len(), 4);
+			// The cursor can be stored and resumed from.
+			let cursor = cursor.map(|c| c.encode()).unwrap();
+			let cursor = Decode::decode(&mut &cursor[..]).unwrap();
+			assert_eq!(A::iter_keys_batch(Some(cursor), 10, |k| keys.push(k)), None);
+			keys.sort();
+			assert_eq!(keys, (0..10).collect::<Vec<_>>());
+
+			let mut items = vec![];
+			let mut cursor = None;
+			let mut batches = 0;
+			loop {
+				cursor = A::iter_batch(cursor, 3, |k, v| {
+					items.push((k, v));
+					A::remove(k);
+				});
+				batches += 1;
+				if cursor.is_none() {
+					break
+				}
+			}
+			items.sort();
+			assert_eq!(items, (0..10).map(|i| (i, i as u32 * 10)).collect::<Vec<_>>());
+			assert_eq!(batches, 4);
+			assert_eq!(A::iter().count(), 0);
+		})
+	}
 }
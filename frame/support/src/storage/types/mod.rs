@@ -27,6 +27,11 @@ mod double_map;
 mod key;
 mod map;
 mod nmap;
+mod size_tracked;
+mod size_tracked_double_map;
+mod size_tracked_map;
+mod size_tracked_nmap;
+mod size_tracked_value;
 mod value;
 
 pub use counted_map::{CountedStorageMap, CountedStorageMapInstance};
@@ -37,6 +42,13 @@ pub use key::{
 };
 pub use map::StorageMap;
 pub use nmap::StorageNMap;
+pub use size_tracked::{
+	MaxBytesExceeded, OnRemovalSizeUpdate, PalletStorageSize, SizeTrackedStorageInstance,
+};
+pub use size_tracked_double_map::SizeTrackedStorageDoubleMap;
+pub use size_tracked_map::SizeTrackedStorageMap;
+pub use size_tracked_nmap::SizeTrackedStorageNMap;
+pub use size_tracked_value::SizeTrackedStorageValue;
 pub use value::StorageValue;
 
 /// Trait implementing how the storage optional value is converted into the queried type.
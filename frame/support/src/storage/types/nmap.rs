@@ -538,6 +538,47 @@ where
 	pub fn translate<O: Decode, F: FnMut(Key::Key, O) -> Option<Value>>(f: F) {
 		<Self as crate::storage::IterableStorageNMap<Key, Value>>::translate(f)
 	}
+
+	/// Call `f` on the elements of the map with prefix key `kp` after `cursor` as long as
+	/// `budget` allows.
+	///
+	/// Returns `None` once all elements were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_prefix_batch<KP>(
+		kp: KP,
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		mut f: impl FnMut(<Key as HasKeyPrefix<KP>>::Suffix, Value),
+	) -> Option<crate::storage::StorageIterCursor>
+	where
+		Key: HasReversibleKeyPrefix<KP>,
+	{
+		Self::iter_prefix(kp).for_each_batch(cursor, budget, |(suffix, value)| f(suffix, value))
+	}
+
+	/// Call `f` on the elements of the map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all elements were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		mut f: impl FnMut(Key::Key, Value),
+	) -> Option<crate::storage::StorageIterCursor> {
+		Self::iter().for_each_batch(cursor, budget, |(key, value)| f(key, value))
+	}
+
+	/// Call `f` on the keys of the map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all keys were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_keys_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		f: impl FnMut(Key::Key),
+	) -> Option<crate::storage::StorageIterCursor> {
+		Self::iter_keys().for_each_batch(cursor, budget, f)
+	}
 }
 
 impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues> StorageEntryMetadataBuilder
@@ -1280,4 +1321,42 @@ mod test {
 			assert_eq!(A::iter_prefix_values((4, 40)).collect::<Vec<_>>(), vec![14, 13]);
 		});
 	}
+
+	#[test]
+	fn iter_batch_works() {
+		type A = StorageNMap<
+			Prefix,
+			(NMapKey<Blake2_128Concat, u16>, NMapKey<Twox64Concat, u16>),
+			u32,
+			OptionQuery,
+		>;
+
+		TestExternalities::default().execute_with(|| {
+			for i in 0..5 {
+				A::insert((3, i), i as u32);
+				A::insert((4, i), i as u32);
+			}
+
+			let mut items = vec![];
+			let mut cursor = None;
+			loop {
+				cursor = A::iter_prefix_batch((3,), cursor, 2, |k2, v| {
+					items.push((k2, v));
+					A::remove((3, k2));
+				});
+				if cursor.is_none() {
+					break
+				}
+			}
+			items.sort();
+			assert_eq!(items, (0..5).map(|i| (i, i as u32)).collect::<Vec<_>>());
+			assert_eq!(A::iter_prefix((4,)).count(), 5);
+
+			let mut keys = vec![];
+			assert_eq!(A::iter_keys_batch(None, 5, |k| keys.push(k)), None);
+			keys.sort();
+			assert_eq!(keys, (0..5).map(|i| (4, i)).collect::<Vec<_>>());
+			assert!(A::iter_batch(None, 4, |_, _| ()).is_some());
+		});
+	}
 }
@@ -0,0 +1,289 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Size accounting shared by the size tracked storage types.
+//!
+//! Every size tracked storage keeps the number of bytes it occupies in its own `ByteSizeFor*`
+//! storage value, and adds it to the total of the pallet, which is bounded by the pallet's
+//! `MaxStorageBytes`.
+
+use crate::{
+	metadata::StorageEntryMetadata,
+	storage::{
+		types::{QueryKindTrait, StorageEntryMetadataBuilder, StorageValue, ValueQuery},
+		unhashed,
+	},
+	traits::{Get, StorageInfo, StorageInfoTrait, StorageInstance, StorageSize, StorageSizeTrait},
+};
+use codec::{Encode, FullCodec};
+use sp_runtime::{traits::Saturating, DispatchError, RuntimeDebug};
+use sp_std::prelude::*;
+
+/// The requirement for an instance of a size tracked storage, such as
+/// [`SizeTrackedStorageMap`](super::SizeTrackedStorageMap).
+pub trait SizeTrackedStorageInstance: StorageInstance {
+	/// The prefix to use for the size storage value.
+	type SizePrefix: StorageInstance;
+	/// The prefix to use for the size of all the size tracked storages of the pallet.
+	type PalletSizePrefix: StorageInstance;
+	/// The maximum number of bytes all the size tracked storages of the pallet may occupy.
+	type PalletMaxBytes: Get<Option<u64>>;
+}
+
+/// A write to a size tracked storage would have exceeded its `MaxBytes`, or the
+/// `MaxStorageBytes` of its pallet.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct MaxBytesExceeded;
+
+impl From<MaxBytesExceeded> for DispatchError {
+	fn from(_: MaxBytesExceeded) -> Self {
+		DispatchError::Exhausted
+	}
+}
+
+/// The number of bytes occupied by all the size tracked storages of a pallet, bounded by
+/// `MaxBytes`.
+///
+/// It is maintained by the size tracked storages themselves, this type only exposes it.
+pub struct PalletStorageSize<Prefix, MaxBytes>(core::marker::PhantomData<(Prefix, MaxBytes)>);
+
+type PalletByteSize<P> = StorageValue<P, u64, ValueQuery>;
+
+impl<Prefix: StorageInstance, MaxBytes: Get<Option<u64>>> PalletStorageSize<Prefix, MaxBytes> {
+	/// Return the number of bytes the size tracked storages of the pallet occupy.
+	pub fn byte_size() -> u64 {
+		PalletByteSize::<Prefix>::get()
+	}
+}
+
+impl<Prefix: StorageInstance, MaxBytes: Get<Option<u64>>> StorageEntryMetadataBuilder
+	for PalletStorageSize<Prefix, MaxBytes>
+{
+	fn build_metadata(docs: Vec<&'static str>, entries: &mut Vec<StorageEntryMetadata>) {
+		PalletByteSize::<Prefix>::build_metadata(docs, entries);
+	}
+}
+
+impl<Prefix: StorageInstance, MaxBytes: Get<Option<u64>>> StorageInfoTrait
+	for PalletStorageSize<Prefix, MaxBytes>
+{
+	fn storage_info() -> Vec<StorageInfo> {
+		PalletByteSize::<Prefix>::storage_info()
+	}
+}
+
+/// The size of the pallet is reported with an empty `storage_name`.
+impl<Prefix: StorageInstance, MaxBytes: Get<Option<u64>>> StorageSizeTrait
+	for PalletStorageSize<Prefix, MaxBytes>
+{
+	fn storage_size() -> Vec<StorageSize> {
+		vec![StorageSize {
+			pallet_name: Prefix::pallet_prefix().as_bytes().to_vec(),
+			storage_name: vec![],
+			bytes: Self::byte_size(),
+			max_bytes: MaxBytes::get(),
+		}]
+	}
+}
+
+pub(crate) type ByteSizeFor<P> =
+	StorageValue<<P as SizeTrackedStorageInstance>::SizePrefix, u64, ValueQuery>;
+
+/// On removal logic for updating the size while draining upon some prefix with
+/// [`crate::storage::PrefixIterator`].
+pub struct OnRemovalSizeUpdate<Prefix>(core::marker::PhantomData<Prefix>);
+
+impl<Prefix: SizeTrackedStorageInstance> crate::storage::PrefixIteratorOnRemoval
+	for OnRemovalSizeUpdate<Prefix>
+{
+	fn on_removal(key: &[u8], value: &[u8]) {
+		reduce_size::<Prefix>(item_size(key, value.len()));
+	}
+}
+
+/// The bytes an item with the final `key` and a value of `value_len` occupies.
+fn item_size(key: &[u8], value_len: usize) -> u64 {
+	key.len().saturating_add(value_len) as u64
+}
+
+/// The bytes the item at the final `key` occupies, zero if there is none.
+fn stored_item_size(key: &[u8]) -> u64 {
+	sp_io::storage::read(key, &mut [], 0).map_or(0, |len| item_size(key, len as usize))
+}
+
+/// Subtract `size` from the size of the storage and of its pallet.
+fn reduce_size<P: SizeTrackedStorageInstance>(size: u64) {
+	ByteSizeFor::<P>::mutate(|value| value.saturating_reduce(size));
+	PalletByteSize::<P::PalletSizePrefix>::mutate(|value| value.saturating_reduce(size));
+}
+
+/// Replace the size of an item, `old_size`, by `new_size` unless the storage would then exceed
+/// `max_bytes` or its pallet would exceed its `PalletMaxBytes`.
+///
+/// Shrinking is always allowed.
+fn try_update_size<P: SizeTrackedStorageInstance>(
+	old_size: u64,
+	new_size: u64,
+	max_bytes: Option<u64>,
+) -> Result<(), MaxBytesExceeded> {
+	if new_size < old_size {
+		reduce_size::<P>(old_size - new_size);
+		return Ok(())
+	}
+	let growth = new_size - old_size;
+	if growth == 0 {
+		return Ok(())
+	}
+	let size = ByteSizeFor::<P>::get().saturating_add(growth);
+	let pallet_size = PalletByteSize::<P::PalletSizePrefix>::get().saturating_add(growth);
+	if max_bytes.map_or(false, |max| size > max) ||
+		P::PalletMaxBytes::get().map_or(false, |max| pallet_size > max)
+	{
+		return Err(MaxBytesExceeded)
+	}
+	ByteSizeFor::<P>::put(size);
+	PalletByteSize::<P::PalletSizePrefix>::put(pallet_size);
+	Ok(())
+}
+
+/// Store `val` at the final `key`, unless the storage would exceed `max_bytes`.
+pub(crate) fn try_put<P: SizeTrackedStorageInstance, V: Encode>(
+	key: &[u8],
+	val: &V,
+	max_bytes: Option<u64>,
+) -> Result<(), MaxBytesExceeded> {
+	try_update_size::<P>(stored_item_size(key), item_size(key, val.encoded_size()), max_bytes)?;
+	unhashed::put(key, val);
+	Ok(())
+}
+
+/// Remove the item at the final `key`.
+pub(crate) fn kill<P: SizeTrackedStorageInstance>(key: &[u8]) {
+	let size = stored_item_size(key);
+	if size > 0 {
+		reduce_size::<P>(size);
+		unhashed::kill(key);
+	}
+}
+
+/// Remove the item at the final `key` and return its value.
+pub(crate) fn take<P: SizeTrackedStorageInstance, V: FullCodec>(key: &[u8]) -> Option<V> {
+	unhashed::get_raw(key).and_then(|raw| {
+		reduce_size::<P>(item_size(key, raw.len()));
+		unhashed::kill(key);
+		V::decode(&mut &raw[..]).ok()
+	})
+}
+
+/// Mutate the item at the final `key`, only if an `Ok` value is returned and the storage would
+/// not exceed `max_bytes`. Deletes the item if mutated to a `None`.
+pub(crate) fn try_mutate_exists<P, V, R, E, F>(
+	key: &[u8],
+	max_bytes: Option<u64>,
+	f: F,
+) -> Result<R, E>
+where
+	P: SizeTrackedStorageInstance,
+	V: FullCodec,
+	E: From<MaxBytesExceeded>,
+	F: FnOnce(&mut Option<V>) -> Result<R, E>,
+{
+	let raw = unhashed::get_raw(key);
+	let old_size = raw.as_ref().map_or(0, |raw| item_size(key, raw.len()));
+	let mut value = raw.and_then(|raw| V::decode(&mut &raw[..]).ok());
+
+	let res = f(&mut value)?;
+	let new_size = value.as_ref().map_or(0, |value| item_size(key, value.encoded_size()));
+	try_update_size::<P>(old_size, new_size, max_bytes)?;
+	match value {
+		Some(value) => unhashed::put(key, &value),
+		None => unhashed::kill(key),
+	}
+	Ok(res)
+}
+
+/// Mutate the query of the item at the final `key`, only if an `Ok` value is returned and the
+/// storage would not exceed `max_bytes`.
+pub(crate) fn try_mutate<P, V, QueryKind, OnEmpty, R, E, F>(
+	key: &[u8],
+	max_bytes: Option<u64>,
+	f: F,
+) -> Result<R, E>
+where
+	P: SizeTrackedStorageInstance,
+	V: FullCodec,
+	QueryKind: QueryKindTrait<V, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	E: From<MaxBytesExceeded>,
+	F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
+{
+	try_mutate_exists::<P, V, _, _, _>(key, max_bytes, |option_value_ref| {
+		let option_value = core::mem::replace(option_value_ref, None);
+		let mut query = QueryKind::from_optional_value_to_query(option_value);
+		let res = f(&mut query);
+		let option_value = QueryKind::from_query_to_optional_value(query);
+		let _ = core::mem::replace(option_value_ref, option_value);
+		res
+	})
+}
+
+/// Set the size of the storage to the number of bytes occupied by the items under `prefix`, and
+/// update the size of its pallet accordingly.
+///
+/// Returns the number of bytes which is used to set the size.
+pub(crate) fn initialize_byte_size<P: SizeTrackedStorageInstance>(prefix: &[u8]) -> u64 {
+	let mut size = stored_item_size(prefix);
+	let mut previous_key = prefix.to_vec();
+	while let Some(key) =
+		sp_io::storage::next_key(&previous_key).filter(|key| key.starts_with(prefix))
+	{
+		size.saturating_accrue(stored_item_size(&key));
+		previous_key = key;
+	}
+	let old_size = ByteSizeFor::<P>::get();
+	PalletByteSize::<P::PalletSizePrefix>::mutate(|pallet_size| {
+		*pallet_size = pallet_size.saturating_sub(old_size).saturating_add(size)
+	});
+	ByteSizeFor::<P>::put(size);
+	size
+}
+
+/// Add the metadata of the size value of a size tracked storage to `entries`.
+pub(crate) fn build_size_metadata<P: SizeTrackedStorageInstance>(
+	entries: &mut Vec<StorageEntryMetadata>,
+) {
+	ByteSizeFor::<P>::build_metadata(
+		if cfg!(feature = "no-metadata-docs") {
+			vec![]
+		} else {
+			vec!["Bytes occupied by the related size tracked storage"]
+		},
+		entries,
+	);
+}
+
+/// The size of a size tracked storage.
+pub(crate) fn storage_size<P: SizeTrackedStorageInstance>(
+	max_bytes: Option<u64>,
+) -> Vec<StorageSize> {
+	vec![StorageSize {
+		pallet_name: P::pallet_prefix().as_bytes().to_vec(),
+		storage_name: P::STORAGE_PREFIX.as_bytes().to_vec(),
+		bytes: ByteSizeFor::<P>::get(),
+		max_bytes,
+	}]
+}
@@ -0,0 +1,568 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage double map type keeping track of the bytes it occupies.
+
+use crate::{
+	metadata::StorageEntryMetadata,
+	storage::types::{
+		size_tracked::{self, ByteSizeFor, OnRemovalSizeUpdate},
+		MaxBytesExceeded, OptionQuery, QueryKindTrait, SizeTrackedStorageInstance,
+		StorageDoubleMap, StorageEntryMetadataBuilder,
+	},
+	traits::{Get, GetDefault, StorageInfo, StorageInfoTrait, StorageSize},
+};
+use codec::{EncodeLike, FullCodec, MaxEncodedLen};
+use sp_std::prelude::*;
+
+/// A wrapper around a `StorageDoubleMap` and a `StorageValue<Value=u64>` to keep track of how many
+/// bytes the keys and values of a double map occupy, optionally rejecting writes beyond
+/// `MaxBytes`.
+///
+/// See [`SizeTrackedStorageMap`](super::SizeTrackedStorageMap) for how the size is accounted.
+pub struct SizeTrackedStorageDoubleMap<
+	Prefix,
+	Hasher1,
+	Key1,
+	Hasher2,
+	Key2,
+	Value,
+	QueryKind = OptionQuery,
+	OnEmpty = GetDefault,
+	MaxValues = GetDefault,
+	MaxBytes = GetDefault,
+>(
+	core::marker::PhantomData<(
+		Prefix,
+		Hasher1,
+		Key1,
+		Hasher2,
+		Key2,
+		Value,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+		MaxBytes,
+	)>,
+);
+
+// Private helper trait to access map from size tracked storage double map.
+trait MapWrapper {
+	type Map;
+}
+
+impl<P: SizeTrackedStorageInstance, H1, K1, H2, K2, V, Q, O, M, B> MapWrapper
+	for SizeTrackedStorageDoubleMap<P, H1, K1, H2, K2, V, Q, O, M, B>
+{
+	type Map = StorageDoubleMap<P, H1, K1, H2, K2, V, Q, O, M>;
+}
+
+impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	SizeTrackedStorageDoubleMap<
+		Prefix,
+		Hasher1,
+		Key1,
+		Hasher2,
+		Key2,
+		Value,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+		MaxBytes,
+	> where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher1: crate::hash::StorageHasher,
+	Hasher2: crate::hash::StorageHasher,
+	Key1: FullCodec,
+	Key2: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	/// The key used to store the size of the map.
+	pub fn size_storage_final_key() -> [u8; 32] {
+		ByteSizeFor::<Prefix>::hashed_key()
+	}
+
+	/// The prefix used to generate the key of the map.
+	pub fn map_storage_final_prefix() -> Vec<u8> {
+		use crate::storage::generator::StorageDoubleMap;
+		<Self as MapWrapper>::Map::prefix_hash()
+	}
+
+	/// Get the storage key used to fetch a value corresponding to a specific key.
+	pub fn hashed_key_for<KArg1, KArg2>(k1: KArg1, k2: KArg2) -> Vec<u8>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+	{
+		<Self as MapWrapper>::Map::hashed_key_for(k1, k2)
+	}
+
+	/// Does the value (explicitly) exist in storage?
+	pub fn contains_key<KArg1, KArg2>(k1: KArg1, k2: KArg2) -> bool
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+	{
+		<Self as MapWrapper>::Map::contains_key(k1, k2)
+	}
+
+	/// Load the value associated with the given key from the double map.
+	pub fn get<KArg1, KArg2>(k1: KArg1, k2: KArg2) -> QueryKind::Query
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+	{
+		<Self as MapWrapper>::Map::get(k1, k2)
+	}
+
+	/// Try to get the value for the given key from the double map.
+	///
+	/// Returns `Ok` if it exists, `Err` if not.
+	pub fn try_get<KArg1, KArg2>(k1: KArg1, k2: KArg2) -> Result<Value, ()>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+	{
+		<Self as MapWrapper>::Map::try_get(k1, k2)
+	}
+
+	/// Try to store a value to be associated with the given keys from the double map.
+	///
+	/// Fails without writing if the map would exceed `MaxBytes`.
+	pub fn try_insert<KArg1, KArg2, VArg>(
+		k1: KArg1,
+		k2: KArg2,
+		val: VArg,
+	) -> Result<(), MaxBytesExceeded>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+		VArg: EncodeLike<Value>,
+	{
+		size_tracked::try_put::<Prefix, _>(&Self::hashed_key_for(k1, k2), &val, MaxBytes::get())
+	}
+
+	/// Remove the value under the given keys.
+	pub fn remove<KArg1, KArg2>(k1: KArg1, k2: KArg2)
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+	{
+		size_tracked::kill::<Prefix>(&Self::hashed_key_for(k1, k2))
+	}
+
+	/// Take a value from storage, removing it afterwards.
+	pub fn take<KArg1, KArg2>(k1: KArg1, k2: KArg2) -> QueryKind::Query
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+	{
+		let removed_value = size_tracked::take::<Prefix, Value>(&Self::hashed_key_for(k1, k2));
+		QueryKind::from_optional_value_to_query(removed_value)
+	}
+
+	/// Mutate the value under the given keys.
+	///
+	/// Fails without writing if the map would exceed `MaxBytes`.
+	pub fn mutate<KArg1, KArg2, R, F>(k1: KArg1, k2: KArg2, f: F) -> Result<R, MaxBytesExceeded>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+		F: FnOnce(&mut QueryKind::Query) -> R,
+	{
+		Self::try_mutate(k1, k2, |v| Ok(f(v)))
+	}
+
+	/// Mutate the value under the given keys, only if an `Ok` value is returned and the map would
+	/// not exceed `MaxBytes`.
+	pub fn try_mutate<KArg1, KArg2, R, E, F>(k1: KArg1, k2: KArg2, f: F) -> Result<R, E>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+		E: From<MaxBytesExceeded>,
+		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
+	{
+		size_tracked::try_mutate::<Prefix, Value, QueryKind, OnEmpty, _, _, _>(
+			&Self::hashed_key_for(k1, k2),
+			MaxBytes::get(),
+			f,
+		)
+	}
+
+	/// Mutate the value under the given keys. Deletes the item if mutated to a `None`.
+	///
+	/// Fails without writing if the map would exceed `MaxBytes`.
+	pub fn mutate_exists<KArg1, KArg2, R, F>(
+		k1: KArg1,
+		k2: KArg2,
+		f: F,
+	) -> Result<R, MaxBytesExceeded>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+		F: FnOnce(&mut Option<Value>) -> R,
+	{
+		Self::try_mutate_exists(k1, k2, |v| Ok(f(v)))
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned and the map would not exceed `MaxBytes`.
+	/// Deletes the item if mutated to a `None`.
+	pub fn try_mutate_exists<KArg1, KArg2, R, E, F>(k1: KArg1, k2: KArg2, f: F) -> Result<R, E>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+		E: From<MaxBytesExceeded>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		size_tracked::try_mutate_exists::<Prefix, _, _, _, _>(
+			&Self::hashed_key_for(k1, k2),
+			MaxBytes::get(),
+			f,
+		)
+	}
+
+	/// Iter over all value of the storage.
+	///
+	/// NOTE: If a value failed to decode because storage is corrupted then it is skipped.
+	pub fn iter_values() -> crate::storage::PrefixIterator<Value, OnRemovalSizeUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::iter_values().convert_on_removal()
+	}
+
+	/// Initialize the size with the actual number of bytes the map occupies.
+	///
+	/// This function iterates through all the items in the map and sets the size. This operation
+	/// can be very heavy, so use with caution.
+	///
+	/// Returns the number of bytes which is used to set the size.
+	pub fn initialize_byte_size() -> u64 {
+		size_tracked::initialize_byte_size::<Prefix>(&Self::map_storage_final_prefix())
+	}
+
+	/// Return the number of bytes the map occupies.
+	pub fn byte_size() -> u64 {
+		ByteSizeFor::<Prefix>::get()
+	}
+}
+
+impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	SizeTrackedStorageDoubleMap<
+		Prefix,
+		Hasher1,
+		Key1,
+		Hasher2,
+		Key2,
+		Value,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+		MaxBytes,
+	> where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher1: crate::hash::StorageHasher + crate::ReversibleStorageHasher,
+	Hasher2: crate::hash::StorageHasher + crate::ReversibleStorageHasher,
+	Key1: FullCodec,
+	Key2: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	/// Enumerate all elements in the map with first key `k1` in no particular order.
+	///
+	/// If you add or remove values whose first key is `k1` to the map while doing this, you'll get
+	/// undefined results.
+	pub fn iter_prefix(
+		k1: impl EncodeLike<Key1>,
+	) -> crate::storage::PrefixIterator<(Key2, Value), OnRemovalSizeUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::iter_prefix(k1).convert_on_removal()
+	}
+
+	/// Remove all elements from the map with first key `k1` and iterate through them in no
+	/// particular order.
+	///
+	/// If you add elements with first key `k1` to the map while doing this, you'll get undefined
+	/// results.
+	pub fn drain_prefix(
+		k1: impl EncodeLike<Key1>,
+	) -> crate::storage::PrefixIterator<(Key2, Value), OnRemovalSizeUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::drain_prefix(k1).convert_on_removal()
+	}
+
+	/// Enumerate all elements in the map in no particular order.
+	///
+	/// If you add or remove values to the map while doing this, you'll get undefined results.
+	pub fn iter(
+	) -> crate::storage::PrefixIterator<(Key1, Key2, Value), OnRemovalSizeUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::iter().convert_on_removal()
+	}
+
+	/// Remove all elements from the map and iterate through them in no particular order.
+	///
+	/// If you add elements to the map while doing this, you'll get undefined results.
+	pub fn drain(
+	) -> crate::storage::PrefixIterator<(Key1, Key2, Value), OnRemovalSizeUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::drain().convert_on_removal()
+	}
+
+	/// Enumerate all keys in the map in no particular order.
+	///
+	/// If you add or remove values to the map while doing this, you'll get undefined results.
+	pub fn iter_keys() -> crate::storage::KeyPrefixIterator<(Key1, Key2)> {
+		<Self as MapWrapper>::Map::iter_keys()
+	}
+}
+
+impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	StorageEntryMetadataBuilder
+	for SizeTrackedStorageDoubleMap<
+		Prefix,
+		Hasher1,
+		Key1,
+		Hasher2,
+		Key2,
+		Value,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+		MaxBytes,
+	> where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher1: crate::hash::StorageHasher,
+	Hasher2: crate::hash::StorageHasher,
+	Key1: FullCodec + scale_info::StaticTypeInfo,
+	Key2: FullCodec + scale_info::StaticTypeInfo,
+	Value: FullCodec + scale_info::StaticTypeInfo,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn build_metadata(docs: Vec<&'static str>, entries: &mut Vec<StorageEntryMetadata>) {
+		<Self as MapWrapper>::Map::build_metadata(docs, entries);
+		size_tracked::build_size_metadata::<Prefix>(entries);
+	}
+}
+
+impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	StorageInfoTrait
+	for SizeTrackedStorageDoubleMap<
+		Prefix,
+		Hasher1,
+		Key1,
+		Hasher2,
+		Key2,
+		Value,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+		MaxBytes,
+	> where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher1: crate::hash::StorageHasher,
+	Hasher2: crate::hash::StorageHasher,
+	Key1: FullCodec + MaxEncodedLen,
+	Key2: FullCodec + MaxEncodedLen,
+	Value: FullCodec + MaxEncodedLen,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn storage_info() -> Vec<StorageInfo> {
+		[<Self as MapWrapper>::Map::storage_info(), ByteSizeFor::<Prefix>::storage_info()].concat()
+	}
+}
+
+/// It doesn't require to implement `MaxEncodedLen` and give no information for `max_size`.
+impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	crate::traits::PartialStorageInfoTrait
+	for SizeTrackedStorageDoubleMap<
+		Prefix,
+		Hasher1,
+		Key1,
+		Hasher2,
+		Key2,
+		Value,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+		MaxBytes,
+	> where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher1: crate::hash::StorageHasher,
+	Hasher2: crate::hash::StorageHasher,
+	Key1: FullCodec,
+	Key2: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn partial_storage_info() -> Vec<StorageInfo> {
+		[<Self as MapWrapper>::Map::partial_storage_info(), ByteSizeFor::<Prefix>::storage_info()]
+			.concat()
+	}
+}
+
+impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	crate::traits::StorageSizeTrait
+	for SizeTrackedStorageDoubleMap<
+		Prefix,
+		Hasher1,
+		Key1,
+		Hasher2,
+		Key2,
+		Value,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+		MaxBytes,
+	> where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher1: crate::hash::StorageHasher,
+	Hasher2: crate::hash::StorageHasher,
+	Key1: FullCodec,
+	Key2: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn storage_size() -> Vec<StorageSize> {
+		size_tracked::storage_size::<Prefix>(MaxBytes::get())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		hash::*,
+		storage::types::ValueQuery,
+		traits::{StorageInstance, StorageSizeTrait},
+	};
+	use sp_io::TestExternalities;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	struct SizePrefix;
+	impl StorageInstance for SizePrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "byte_size_for_foo";
+	}
+
+	struct PalletSizePrefix;
+	impl StorageInstance for PalletSizePrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "storage_byte_size";
+	}
+
+	impl SizeTrackedStorageInstance for Prefix {
+		type SizePrefix = SizePrefix;
+		type PalletSizePrefix = PalletSizePrefix;
+		type PalletMaxBytes = GetDefault;
+	}
+
+	crate::parameter_types! {
+		pub const MaxBytes: Option<u64> = Some(130);
+	}
+
+	// 32 bytes of prefix, 8 + 2 and 16 + 1 bytes of keys.
+	const KEY_LEN: u64 = 59;
+
+	type A = SizeTrackedStorageDoubleMap<
+		Prefix,
+		Twox64Concat,
+		u16,
+		Blake2_128Concat,
+		u8,
+		Vec<u8>,
+		ValueQuery,
+		GetDefault,
+		GetDefault,
+		MaxBytes,
+	>;
+
+	#[test]
+	fn size_is_tracked() {
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(A::hashed_key_for(1, 1).len() as u64, KEY_LEN);
+
+			assert_eq!(A::try_insert(1, 1, vec![1, 2]), Ok(()));
+			assert_eq!(A::try_insert(1, 2, vec![1]), Ok(()));
+			assert_eq!(A::byte_size(), 2 * KEY_LEN + 5);
+
+			// Would be 3 * 59 + 6 bytes.
+			assert_eq!(A::try_insert(2, 1, Vec::<u8>::new()), Err(MaxBytesExceeded));
+			assert!(!A::contains_key(2, 1));
+
+			assert_eq!(A::mutate(1, 1, |v| v.pop()), Ok(Some(2)));
+			assert_eq!(A::byte_size(), 2 * KEY_LEN + 4);
+
+			A::remove(1, 2);
+			assert_eq!(A::byte_size(), KEY_LEN + 2);
+			assert_eq!(A::try_insert(2, 1, Vec::<u8>::new()), Ok(()));
+			assert_eq!(A::byte_size(), 2 * KEY_LEN + 3);
+
+			assert_eq!(A::drain_prefix(1).collect::<Vec<_>>(), vec![(1, vec![1])]);
+			assert_eq!(A::byte_size(), KEY_LEN + 1);
+
+			assert_eq!(A::take(2, 1), Vec::<u8>::new());
+			assert_eq!(A::byte_size(), 0);
+
+			assert_eq!(
+				A::storage_size(),
+				vec![StorageSize {
+					pallet_name: b"test".to_vec(),
+					storage_name: b"foo".to_vec(),
+					bytes: 0,
+					max_bytes: Some(130),
+				}]
+			);
+		});
+	}
+
+	#[test]
+	fn initialize_byte_size_works() {
+		type B = StorageDoubleMap<Prefix, Twox64Concat, u16, Blake2_128Concat, u8, u32>;
+
+		TestExternalities::default().execute_with(|| {
+			B::insert(1, 1, 1);
+			B::insert(1, 2, 2);
+			assert_eq!(A::byte_size(), 0);
+			assert_eq!(A::initialize_byte_size(), 2 * (KEY_LEN + 4));
+			assert_eq!(A::byte_size(), 2 * (KEY_LEN + 4));
+		});
+	}
+}
@@ -0,0 +1,572 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage map type keeping track of the bytes it occupies.
+
+use crate::{
+	metadata::StorageEntryMetadata,
+	storage::types::{
+		size_tracked::{self, ByteSizeFor, OnRemovalSizeUpdate},
+		MaxBytesExceeded, OptionQuery, QueryKindTrait, SizeTrackedStorageInstance,
+		StorageEntryMetadataBuilder, StorageMap,
+	},
+	traits::{Get, GetDefault, StorageInfo, StorageInfoTrait, StorageSize},
+};
+use codec::{EncodeLike, FullCodec, MaxEncodedLen};
+use sp_std::prelude::*;
+
+/// A wrapper around a `StorageMap` and a `StorageValue<Value=u64>` to keep track of how many bytes
+/// the keys and values of a map occupy, optionally rejecting writes beyond `MaxBytes`.
+///
+/// The size of an item is the length of its final key plus the length of its encoded value. The
+/// size is maintained on every write, so it can be read without iterating the map.
+///
+/// Every write first reads the length of the previous value, and whenever the size changes
+/// additional reads and writes occur to update it and the size of the pallet, see
+/// [`PalletStorageSize`](super::PalletStorageSize).
+///
+/// Writes which would grow the map beyond `MaxBytes`, or the pallet beyond its `MaxStorageBytes`,
+/// fail with [`MaxBytesExceeded`] and leave the storage untouched, writes which shrink the map
+/// always succeed.
+pub struct SizeTrackedStorageMap<
+	Prefix,
+	Hasher,
+	Key,
+	Value,
+	QueryKind = OptionQuery,
+	OnEmpty = GetDefault,
+	MaxValues = GetDefault,
+	MaxBytes = GetDefault,
+>(
+	core::marker::PhantomData<(
+		Prefix,
+		Hasher,
+		Key,
+		Value,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+		MaxBytes,
+	)>,
+);
+
+// Private helper trait to access map from size tracked storage map.
+trait MapWrapper {
+	type Map;
+}
+
+impl<P: SizeTrackedStorageInstance, H, K, V, Q, O, M, B> MapWrapper
+	for SizeTrackedStorageMap<P, H, K, V, Q, O, M, B>
+{
+	type Map = StorageMap<P, H, K, V, Q, O, M>;
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	SizeTrackedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	/// The key used to store the size of the map.
+	pub fn size_storage_final_key() -> [u8; 32] {
+		ByteSizeFor::<Prefix>::hashed_key()
+	}
+
+	/// The prefix used to generate the key of the map.
+	pub fn map_storage_final_prefix() -> Vec<u8> {
+		use crate::storage::generator::StorageMap;
+		<Self as MapWrapper>::Map::prefix_hash()
+	}
+
+	/// Get the storage key used to fetch a value corresponding to a specific key.
+	pub fn hashed_key_for<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Vec<u8> {
+		<Self as MapWrapper>::Map::hashed_key_for(key)
+	}
+
+	/// Does the value (explicitly) exist in storage?
+	pub fn contains_key<KeyArg: EncodeLike<Key>>(key: KeyArg) -> bool {
+		<Self as MapWrapper>::Map::contains_key(key)
+	}
+
+	/// Load the value associated with the given key from the map.
+	pub fn get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+		<Self as MapWrapper>::Map::get(key)
+	}
+
+	/// Try to get the value for the given key from the map.
+	///
+	/// Returns `Ok` if it exists, `Err` if not.
+	pub fn try_get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Result<Value, ()> {
+		<Self as MapWrapper>::Map::try_get(key)
+	}
+
+	/// Try to store a value to be associated with the given key from the map.
+	///
+	/// Fails without writing if the map would exceed `MaxBytes`.
+	pub fn try_insert<KeyArg: EncodeLike<Key>, ValArg: EncodeLike<Value>>(
+		key: KeyArg,
+		val: ValArg,
+	) -> Result<(), MaxBytesExceeded> {
+		size_tracked::try_put::<Prefix, _>(&Self::hashed_key_for(key), &val, MaxBytes::get())
+	}
+
+	/// Remove the value under a key.
+	pub fn remove<KeyArg: EncodeLike<Key>>(key: KeyArg) {
+		size_tracked::kill::<Prefix>(&Self::hashed_key_for(key))
+	}
+
+	/// Take the value under a key.
+	pub fn take<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+		let removed_value = size_tracked::take::<Prefix, Value>(&Self::hashed_key_for(key));
+		QueryKind::from_optional_value_to_query(removed_value)
+	}
+
+	/// Mutate the value under a key.
+	///
+	/// Fails without writing if the map would exceed `MaxBytes`.
+	pub fn mutate<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut QueryKind::Query) -> R>(
+		key: KeyArg,
+		f: F,
+	) -> Result<R, MaxBytesExceeded> {
+		Self::try_mutate(key, |v| Ok(f(v)))
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned and the map would not exceed `MaxBytes`.
+	pub fn try_mutate<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+	where
+		KeyArg: EncodeLike<Key>,
+		E: From<MaxBytesExceeded>,
+		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
+	{
+		size_tracked::try_mutate::<Prefix, Value, QueryKind, OnEmpty, _, _, _>(
+			&Self::hashed_key_for(key),
+			MaxBytes::get(),
+			f,
+		)
+	}
+
+	/// Mutate the value under a key. Deletes the item if mutated to a `None`.
+	///
+	/// Fails without writing if the map would exceed `MaxBytes`.
+	pub fn mutate_exists<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut Option<Value>) -> R>(
+		key: KeyArg,
+		f: F,
+	) -> Result<R, MaxBytesExceeded> {
+		Self::try_mutate_exists(key, |v| Ok(f(v)))
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned and the map would not exceed `MaxBytes`.
+	/// Deletes the item if mutated to a `None`.
+	///
+	/// `f` will always be called with an option representing if the storage item exists (`Some<V>`)
+	/// or if the storage item does not exist (`None`), independent of the `QueryType`.
+	pub fn try_mutate_exists<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+	where
+		KeyArg: EncodeLike<Key>,
+		E: From<MaxBytesExceeded>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		size_tracked::try_mutate_exists::<Prefix, _, _, _, _>(
+			&Self::hashed_key_for(key),
+			MaxBytes::get(),
+			f,
+		)
+	}
+
+	/// Iter over all value of the storage.
+	///
+	/// NOTE: If a value failed to decode because storage is corrupted then it is skipped.
+	pub fn iter_values() -> crate::storage::PrefixIterator<Value, OnRemovalSizeUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::iter_values().convert_on_removal()
+	}
+
+	/// Initialize the size with the actual number of bytes the map occupies.
+	///
+	/// This function iterates through all the items in the map and sets the size. This operation
+	/// can be very heavy, so use with caution.
+	///
+	/// Returns the number of bytes which is used to set the size.
+	pub fn initialize_byte_size() -> u64 {
+		size_tracked::initialize_byte_size::<Prefix>(&Self::map_storage_final_prefix())
+	}
+
+	/// Return the number of bytes the map occupies.
+	pub fn byte_size() -> u64 {
+		ByteSizeFor::<Prefix>::get()
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	SizeTrackedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher: crate::hash::StorageHasher + crate::ReversibleStorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	/// Enumerate all elements in the map in no particular order.
+	///
+	/// If you alter the map while doing this, you'll get undefined results.
+	pub fn iter() -> crate::storage::PrefixIterator<(Key, Value), OnRemovalSizeUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::iter().convert_on_removal()
+	}
+
+	/// Remove all elements from the map and iterate through them in no particular order.
+	///
+	/// If you add elements to the map while doing this, you'll get undefined results.
+	pub fn drain() -> crate::storage::PrefixIterator<(Key, Value), OnRemovalSizeUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::drain().convert_on_removal()
+	}
+
+	/// Enumerate all keys in the map.
+	///
+	/// If you alter the map while doing this, you'll get undefined results.
+	pub fn iter_keys() -> crate::storage::KeyPrefixIterator<Key> {
+		<Self as MapWrapper>::Map::iter_keys()
+	}
+
+	/// Call `f` on the elements of the map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all elements were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		f: impl FnMut(Key, Value),
+	) -> Option<crate::storage::StorageIterCursor> {
+		<Self as MapWrapper>::Map::iter_batch(cursor, budget, f)
+	}
+
+	/// Call `f` on the keys of the map after `cursor` as long as `budget` allows.
+	///
+	/// Returns `None` once all keys were processed, otherwise the cursor to pass to the next
+	/// call. Removing elements from the map in `f` is fine, see [`crate::storage::cursor`].
+	pub fn iter_keys_batch(
+		cursor: Option<crate::storage::StorageIterCursor>,
+		budget: impl crate::storage::IterBudget,
+		f: impl FnMut(Key),
+	) -> Option<crate::storage::StorageIterCursor> {
+		<Self as MapWrapper>::Map::iter_keys_batch(cursor, budget, f)
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	StorageEntryMetadataBuilder
+	for SizeTrackedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec + scale_info::StaticTypeInfo,
+	Value: FullCodec + scale_info::StaticTypeInfo,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn build_metadata(docs: Vec<&'static str>, entries: &mut Vec<StorageEntryMetadata>) {
+		<Self as MapWrapper>::Map::build_metadata(docs, entries);
+		size_tracked::build_size_metadata::<Prefix>(entries);
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes> StorageInfoTrait
+	for SizeTrackedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec + MaxEncodedLen,
+	Value: FullCodec + MaxEncodedLen,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn storage_info() -> Vec<StorageInfo> {
+		[<Self as MapWrapper>::Map::storage_info(), ByteSizeFor::<Prefix>::storage_info()].concat()
+	}
+}
+
+/// It doesn't require to implement `MaxEncodedLen` and give no information for `max_size`.
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	crate::traits::PartialStorageInfoTrait
+	for SizeTrackedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn partial_storage_info() -> Vec<StorageInfo> {
+		[<Self as MapWrapper>::Map::partial_storage_info(), ByteSizeFor::<Prefix>::storage_info()]
+			.concat()
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	crate::traits::StorageSizeTrait
+	for SizeTrackedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn storage_size() -> Vec<StorageSize> {
+		size_tracked::storage_size::<Prefix>(MaxBytes::get())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		hash::*,
+		metadata::{StorageEntryModifier, StorageEntryType, StorageHasher},
+		storage::types::{PalletStorageSize, ValueQuery},
+		traits::{ConstU32, StorageInstance, StorageSizeTrait},
+	};
+	use sp_io::TestExternalities;
+	use sp_runtime::DispatchError;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	struct SizePrefix;
+	impl StorageInstance for SizePrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "byte_size_for_foo";
+	}
+	struct PalletSizePrefix;
+	impl StorageInstance for PalletSizePrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "storage_byte_size";
+	}
+	impl SizeTrackedStorageInstance for Prefix {
+		type SizePrefix = SizePrefix;
+		type PalletSizePrefix = PalletSizePrefix;
+		type PalletMaxBytes = GetDefault;
+	}
+
+	crate::parameter_types! {
+		pub const MaxBytes: Option<u64> = Some(100);
+	}
+
+	// 32 bytes of prefix, 8 + 2 bytes of key.
+	const KEY_LEN: u64 = 42;
+
+	#[test]
+	fn size_is_tracked() {
+		type A = SizeTrackedStorageMap<Prefix, Twox64Concat, u16, Vec<u8>, ValueQuery>;
+
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(A::hashed_key_for(3).len() as u64, KEY_LEN);
+			assert_eq!(A::byte_size(), 0);
+
+			// Insert non-existing.
+			assert_eq!(A::try_insert(3, vec![1, 2, 3]), Ok(()));
+			assert_eq!(A::byte_size(), KEY_LEN + 4);
+
+			// Overwrite existing.
+			assert_eq!(A::try_insert(3, vec![1]), Ok(()));
+			assert_eq!(A::byte_size(), KEY_LEN + 2);
+
+			assert_eq!(A::mutate(4, |v| v.extend([1, 2])), Ok(()));
+			assert_eq!(A::byte_size(), 2 * KEY_LEN + 5);
+
+			assert_eq!(A::mutate(4, |v| v.clear()), Ok(()));
+			assert_eq!(A::byte_size(), 2 * KEY_LEN + 3);
+
+			assert_eq!(A::mutate_exists(4, |v| *v = None), Ok(()));
+			assert!(!A::contains_key(4));
+			assert_eq!(A::byte_size(), KEY_LEN + 2);
+
+			// Failed mutation doesn't change anything.
+			assert_eq!(
+				A::try_mutate(3, |v| {
+					v.push(4);
+					Err::<(), _>(DispatchError::Other("nope"))
+				}),
+				Err(DispatchError::Other("nope")),
+			);
+			assert_eq!(A::get(3), vec![1]);
+			assert_eq!(A::byte_size(), KEY_LEN + 2);
+
+			A::try_insert(5, vec![5; 10]).unwrap();
+			assert_eq!(A::byte_size(), 2 * KEY_LEN + 13);
+			// The size of the pallet follows the size of its only size tracked storage.
+			assert_eq!(
+				PalletStorageSize::<PalletSizePrefix, GetDefault>::byte_size(),
+				A::byte_size()
+			);
+
+			// Remove non-existing.
+			A::remove(4);
+			assert_eq!(A::byte_size(), 2 * KEY_LEN + 13);
+
+			A::remove(5);
+			assert_eq!(A::byte_size(), KEY_LEN + 2);
+
+			assert_eq!(A::take(3), vec![1]);
+			assert_eq!(A::byte_size(), 0);
+
+			A::try_insert(1, vec![1]).unwrap();
+			A::try_insert(2, vec![2, 2]).unwrap();
+			assert_eq!(A::drain().count(), 2);
+			assert_eq!(A::byte_size(), 0);
+			assert_eq!(PalletStorageSize::<PalletSizePrefix, GetDefault>::byte_size(), 0);
+		});
+	}
+
+	#[test]
+	fn max_bytes_rejects_writes() {
+		type A = SizeTrackedStorageMap<
+			Prefix,
+			Twox64Concat,
+			u16,
+			Vec<u8>,
+			OptionQuery,
+			GetDefault,
+			GetDefault,
+			MaxBytes,
+		>;
+
+		TestExternalities::default().execute_with(|| {
+			// 42 + 11 bytes.
+			assert_eq!(A::try_insert(1, vec![1; 10]), Ok(()));
+			// Would be 106 bytes.
+			assert_eq!(A::try_insert(2, vec![2; 10]), Err(MaxBytesExceeded));
+			assert!(!A::contains_key(2));
+			assert_eq!(A::byte_size(), KEY_LEN + 11);
+
+			// Would be 100 bytes.
+			assert_eq!(A::try_insert(2, vec![2; 4]), Ok(()));
+			assert_eq!(A::byte_size(), 100);
+
+			assert_eq!(A::mutate_exists(2, |v| v.as_mut().unwrap().push(2)), Err(MaxBytesExceeded));
+			assert_eq!(A::get(2), Some(vec![2; 4]));
+			assert_eq!(
+				A::try_mutate_exists(2, |v| {
+					v.as_mut().unwrap().push(2);
+					Ok::<_, DispatchError>(())
+				}),
+				Err(DispatchError::Exhausted)
+			);
+
+			// Shrinking is always fine.
+			assert_eq!(A::mutate_exists(1, |v| v.as_mut().unwrap().pop()), Ok(Some(1)));
+			assert_eq!(A::byte_size(), 99);
+			assert_eq!(A::mutate_exists(1, |v| *v = None), Ok(()));
+			assert_eq!(A::byte_size(), KEY_LEN + 5);
+
+			assert_eq!(
+				A::storage_size(),
+				vec![StorageSize {
+					pallet_name: b"test".to_vec(),
+					storage_name: b"foo".to_vec(),
+					bytes: KEY_LEN + 5,
+					max_bytes: Some(100),
+				}]
+			);
+		});
+	}
+
+	#[test]
+	fn initialize_byte_size_works() {
+		type A = SizeTrackedStorageMap<Prefix, Twox64Concat, u16, u32>;
+		type B = StorageMap<Prefix, Twox64Concat, u16, u32>;
+
+		TestExternalities::default().execute_with(|| {
+			B::insert(1, 1);
+			B::insert(2, 2);
+			assert_eq!(A::byte_size(), 0);
+			assert_eq!(A::initialize_byte_size(), 2 * (KEY_LEN + 4));
+			assert_eq!(A::byte_size(), 2 * (KEY_LEN + 4));
+			assert_eq!(
+				PalletStorageSize::<PalletSizePrefix, GetDefault>::byte_size(),
+				2 * (KEY_LEN + 4)
+			);
+
+			// Initializing again doesn't count the items twice in the size of the pallet.
+			B::remove(2);
+			assert_eq!(A::initialize_byte_size(), KEY_LEN + 4);
+			assert_eq!(PalletStorageSize::<PalletSizePrefix, GetDefault>::byte_size(), KEY_LEN + 4);
+		});
+	}
+
+	#[test]
+	fn metadata_and_storage_info_work() {
+		type A = SizeTrackedStorageMap<
+			Prefix,
+			Twox64Concat,
+			u16,
+			u32,
+			OptionQuery,
+			GetDefault,
+			ConstU32<10>,
+			MaxBytes,
+		>;
+
+		let mut entries = vec![];
+		A::build_metadata(vec![], &mut entries);
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].name, "foo");
+		assert_eq!(
+			entries[0].ty,
+			StorageEntryType::Map {
+				hashers: vec![StorageHasher::Twox64Concat],
+				key: scale_info::meta_type::<u16>(),
+				value: scale_info::meta_type::<u32>(),
+			}
+		);
+		assert_eq!(entries[1].name, "byte_size_for_foo");
+		assert_eq!(entries[1].modifier, StorageEntryModifier::Default);
+		assert_eq!(entries[1].ty, StorageEntryType::Plain(scale_info::meta_type::<u64>()));
+
+		let info = A::storage_info();
+		assert_eq!(info.len(), 2);
+		assert_eq!(info[0].max_values, Some(10));
+		assert_eq!(info[1].storage_name, b"byte_size_for_foo".to_vec());
+		assert_eq!(info[1].max_size, Some(8));
+	}
+}
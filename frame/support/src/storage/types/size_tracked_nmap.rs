@@ -0,0 +1,426 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage n-map type keeping track of the bytes it occupies.
+
+use crate::{
+	metadata::StorageEntryMetadata,
+	storage::types::{
+		size_tracked::{self, ByteSizeFor, OnRemovalSizeUpdate},
+		EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, KeyGenerator,
+		KeyGeneratorMaxEncodedLen, MaxBytesExceeded, OptionQuery, QueryKindTrait,
+		ReversibleKeyGenerator, SizeTrackedStorageInstance, StorageEntryMetadataBuilder,
+		StorageNMap, TupleToEncodedIter,
+	},
+	traits::{Get, GetDefault, StorageInfo, StorageInfoTrait, StorageSize},
+};
+use codec::{EncodeLike, FullCodec, MaxEncodedLen};
+use sp_std::prelude::*;
+
+/// A wrapper around a `StorageNMap` and a `StorageValue<Value=u64>` to keep track of how many
+/// bytes the keys and values of a n-map occupy, optionally rejecting writes beyond `MaxBytes`.
+///
+/// See [`SizeTrackedStorageMap`](super::SizeTrackedStorageMap) for how the size is accounted.
+pub struct SizeTrackedStorageNMap<
+	Prefix,
+	Key,
+	Value,
+	QueryKind = OptionQuery,
+	OnEmpty = GetDefault,
+	MaxValues = GetDefault,
+	MaxBytes = GetDefault,
+>(core::marker::PhantomData<(Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes)>);
+
+// Private helper trait to access map from size tracked storage n-map.
+trait MapWrapper {
+	type Map;
+}
+
+impl<P: SizeTrackedStorageInstance, K, V, Q, O, M, B> MapWrapper
+	for SizeTrackedStorageNMap<P, K, V, Q, O, M, B>
+{
+	type Map = StorageNMap<P, K, V, Q, O, M>;
+}
+
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	SizeTrackedStorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Key: KeyGenerator,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	/// The key used to store the size of the map.
+	pub fn size_storage_final_key() -> [u8; 32] {
+		ByteSizeFor::<Prefix>::hashed_key()
+	}
+
+	/// The prefix used to generate the key of the map.
+	pub fn map_storage_final_prefix() -> Vec<u8> {
+		use crate::storage::generator::StorageNMap;
+		<Self as MapWrapper>::Map::prefix_hash()
+	}
+
+	/// Get the storage key used to fetch a value corresponding to a specific key.
+	pub fn hashed_key_for<KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter>(
+		key: KArg,
+	) -> Vec<u8> {
+		<Self as MapWrapper>::Map::hashed_key_for(key)
+	}
+
+	/// Does the value (explicitly) exist in storage?
+	pub fn contains_key<KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter>(key: KArg) -> bool {
+		<Self as MapWrapper>::Map::contains_key(key)
+	}
+
+	/// Load the value associated with the given key from the map.
+	pub fn get<KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter>(
+		key: KArg,
+	) -> QueryKind::Query {
+		<Self as MapWrapper>::Map::get(key)
+	}
+
+	/// Try to get the value for the given key from the map.
+	///
+	/// Returns `Ok` if it exists, `Err` if not.
+	pub fn try_get<KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter>(
+		key: KArg,
+	) -> Result<Value, ()> {
+		<Self as MapWrapper>::Map::try_get(key)
+	}
+
+	/// Try to store a value to be associated with the given key from the map.
+	///
+	/// Fails without writing if the map would exceed `MaxBytes`.
+	pub fn try_insert<KArg, VArg>(key: KArg, val: VArg) -> Result<(), MaxBytesExceeded>
+	where
+		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
+		VArg: EncodeLike<Value>,
+	{
+		size_tracked::try_put::<Prefix, _>(&Self::hashed_key_for(key), &val, MaxBytes::get())
+	}
+
+	/// Remove the value under a key.
+	pub fn remove<KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter>(key: KArg) {
+		size_tracked::kill::<Prefix>(&Self::hashed_key_for(key))
+	}
+
+	/// Take a value from storage, removing it afterwards.
+	pub fn take<KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter>(
+		key: KArg,
+	) -> QueryKind::Query {
+		let removed_value = size_tracked::take::<Prefix, Value>(&Self::hashed_key_for(key));
+		QueryKind::from_optional_value_to_query(removed_value)
+	}
+
+	/// Mutate the value under a key.
+	///
+	/// Fails without writing if the map would exceed `MaxBytes`.
+	pub fn mutate<KArg, R, F>(key: KArg, f: F) -> Result<R, MaxBytesExceeded>
+	where
+		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
+		F: FnOnce(&mut QueryKind::Query) -> R,
+	{
+		Self::try_mutate(key, |v| Ok(f(v)))
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned and the map would not exceed `MaxBytes`.
+	pub fn try_mutate<KArg, R, E, F>(key: KArg, f: F) -> Result<R, E>
+	where
+		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
+		E: From<MaxBytesExceeded>,
+		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
+	{
+		size_tracked::try_mutate::<Prefix, Value, QueryKind, OnEmpty, _, _, _>(
+			&Self::hashed_key_for(key),
+			MaxBytes::get(),
+			f,
+		)
+	}
+
+	/// Mutate the value under a key. Deletes the item if mutated to a `None`.
+	///
+	/// Fails without writing if the map would exceed `MaxBytes`.
+	pub fn mutate_exists<KArg, R, F>(key: KArg, f: F) -> Result<R, MaxBytesExceeded>
+	where
+		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
+		F: FnOnce(&mut Option<Value>) -> R,
+	{
+		Self::try_mutate_exists(key, |v| Ok(f(v)))
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned and the map would not exceed `MaxBytes`.
+	/// Deletes the item if mutated to a `None`.
+	pub fn try_mutate_exists<KArg, R, E, F>(key: KArg, f: F) -> Result<R, E>
+	where
+		KArg: EncodeLikeTuple<Key::KArg> + TupleToEncodedIter,
+		E: From<MaxBytesExceeded>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		size_tracked::try_mutate_exists::<Prefix, _, _, _, _>(
+			&Self::hashed_key_for(key),
+			MaxBytes::get(),
+			f,
+		)
+	}
+
+	/// Iter over all value of the storage.
+	///
+	/// NOTE: If a value failed to decode because storage is corrupted then it is skipped.
+	pub fn iter_values() -> crate::storage::PrefixIterator<Value, OnRemovalSizeUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::iter_values().convert_on_removal()
+	}
+
+	/// Initialize the size with the actual number of bytes the map occupies.
+	///
+	/// This function iterates through all the items in the map and sets the size. This operation
+	/// can be very heavy, so use with caution.
+	///
+	/// Returns the number of bytes which is used to set the size.
+	pub fn initialize_byte_size() -> u64 {
+		size_tracked::initialize_byte_size::<Prefix>(&Self::map_storage_final_prefix())
+	}
+
+	/// Return the number of bytes the map occupies.
+	pub fn byte_size() -> u64 {
+		ByteSizeFor::<Prefix>::get()
+	}
+}
+
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	SizeTrackedStorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Key: ReversibleKeyGenerator,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	/// Enumerate all elements in the map with prefix key `kp` in no particular order.
+	///
+	/// If you add or remove values whose prefix key is `kp` to the map while doing this, you'll get
+	/// undefined results.
+	pub fn iter_prefix<KP>(
+		kp: KP,
+	) -> crate::storage::PrefixIterator<
+		(<Key as HasKeyPrefix<KP>>::Suffix, Value),
+		OnRemovalSizeUpdate<Prefix>,
+	>
+	where
+		Key: HasReversibleKeyPrefix<KP>,
+	{
+		<Self as MapWrapper>::Map::iter_prefix(kp).convert_on_removal()
+	}
+
+	/// Remove all elements from the map with prefix key `kp` and iterate through them in no
+	/// particular order.
+	///
+	/// If you add elements with prefix key `kp` to the map while doing this, you'll get undefined
+	/// results.
+	pub fn drain_prefix<KP>(
+		kp: KP,
+	) -> crate::storage::PrefixIterator<
+		(<Key as HasKeyPrefix<KP>>::Suffix, Value),
+		OnRemovalSizeUpdate<Prefix>,
+	>
+	where
+		Key: HasReversibleKeyPrefix<KP>,
+	{
+		<Self as MapWrapper>::Map::drain_prefix(kp).convert_on_removal()
+	}
+
+	/// Enumerate all elements in the map in no particular order.
+	///
+	/// If you add or remove values to the map while doing this, you'll get undefined results.
+	pub fn iter() -> crate::storage::PrefixIterator<(Key::Key, Value), OnRemovalSizeUpdate<Prefix>>
+	{
+		<Self as MapWrapper>::Map::iter().convert_on_removal()
+	}
+
+	/// Remove all elements from the map and iterate through them in no particular order.
+	///
+	/// If you add elements to the map while doing this, you'll get undefined results.
+	pub fn drain() -> crate::storage::PrefixIterator<(Key::Key, Value), OnRemovalSizeUpdate<Prefix>>
+	{
+		<Self as MapWrapper>::Map::drain().convert_on_removal()
+	}
+
+	/// Enumerate all keys in the map in no particular order.
+	///
+	/// If you add or remove values to the map while doing this, you'll get undefined results.
+	pub fn iter_keys() -> crate::storage::KeyPrefixIterator<Key::Key> {
+		<Self as MapWrapper>::Map::iter_keys()
+	}
+}
+
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes> StorageEntryMetadataBuilder
+	for SizeTrackedStorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Key: KeyGenerator,
+	Value: FullCodec + scale_info::StaticTypeInfo,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn build_metadata(docs: Vec<&'static str>, entries: &mut Vec<StorageEntryMetadata>) {
+		<Self as MapWrapper>::Map::build_metadata(docs, entries);
+		size_tracked::build_size_metadata::<Prefix>(entries);
+	}
+}
+
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes> StorageInfoTrait
+	for SizeTrackedStorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Key: KeyGenerator + KeyGeneratorMaxEncodedLen,
+	Value: FullCodec + MaxEncodedLen,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn storage_info() -> Vec<StorageInfo> {
+		[<Self as MapWrapper>::Map::storage_info(), ByteSizeFor::<Prefix>::storage_info()].concat()
+	}
+}
+
+/// It doesn't require to implement `MaxEncodedLen` and give no information for `max_size`.
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+	crate::traits::PartialStorageInfoTrait
+	for SizeTrackedStorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Key: KeyGenerator,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn partial_storage_info() -> Vec<StorageInfo> {
+		[<Self as MapWrapper>::Map::partial_storage_info(), ByteSizeFor::<Prefix>::storage_info()]
+			.concat()
+	}
+}
+
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes> crate::traits::StorageSizeTrait
+	for SizeTrackedStorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Key: KeyGenerator,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn storage_size() -> Vec<StorageSize> {
+		size_tracked::storage_size::<Prefix>(MaxBytes::get())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		hash::*,
+		storage::types::{Key, ValueQuery},
+		traits::StorageInstance,
+	};
+	use sp_io::TestExternalities;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	struct SizePrefix;
+	impl StorageInstance for SizePrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "byte_size_for_foo";
+	}
+
+	struct PalletSizePrefix;
+	impl StorageInstance for PalletSizePrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "storage_byte_size";
+	}
+
+	impl SizeTrackedStorageInstance for Prefix {
+		type SizePrefix = SizePrefix;
+		type PalletSizePrefix = PalletSizePrefix;
+		type PalletMaxBytes = GetDefault;
+	}
+
+	// 32 bytes of prefix, 8 + 2 and 8 + 1 bytes of keys.
+	const KEY_LEN: u64 = 51;
+
+	type A = SizeTrackedStorageNMap<
+		Prefix,
+		(Key<Twox64Concat, u16>, Key<Twox64Concat, u8>),
+		Vec<u8>,
+		ValueQuery,
+	>;
+
+	#[test]
+	fn size_is_tracked() {
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(A::hashed_key_for((1, 1)).len() as u64, KEY_LEN);
+
+			assert_eq!(A::try_insert((1, 1), vec![1, 2]), Ok(()));
+			assert_eq!(A::mutate((1, 2), |v| v.push(1)), Ok(()));
+			assert_eq!(A::try_insert((2, 1), Vec::<u8>::new()), Ok(()));
+			assert_eq!(A::byte_size(), 3 * KEY_LEN + 6);
+
+			assert_eq!(A::mutate_exists((1, 1), |v| *v = None), Ok(()));
+			assert_eq!(A::byte_size(), 2 * KEY_LEN + 3);
+
+			assert_eq!(A::drain_prefix((1,)).collect::<Vec<_>>(), vec![(2, vec![1])]);
+			assert_eq!(A::byte_size(), KEY_LEN + 1);
+
+			A::remove((2, 1));
+			assert_eq!(A::byte_size(), 0);
+		});
+	}
+
+	#[test]
+	fn initialize_byte_size_works() {
+		type B = StorageNMap<Prefix, (Key<Twox64Concat, u16>, Key<Twox64Concat, u8>), u32>;
+
+		TestExternalities::default().execute_with(|| {
+			B::insert((1, 1), 1);
+			B::insert((1, 2), 2);
+			assert_eq!(A::byte_size(), 0);
+			assert_eq!(A::initialize_byte_size(), 2 * (KEY_LEN + 4));
+			assert_eq!(A::byte_size(), 2 * (KEY_LEN + 4));
+		});
+	}
+}
@@ -0,0 +1,395 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage value type keeping track of the bytes it occupies.
+
+use crate::{
+	metadata::StorageEntryMetadata,
+	storage::types::{
+		size_tracked::{self, ByteSizeFor},
+		MaxBytesExceeded, OptionQuery, QueryKindTrait, SizeTrackedStorageInstance,
+		StorageEntryMetadataBuilder, StorageValue,
+	},
+	traits::{Get, GetDefault, StorageInfo, StorageInfoTrait, StorageSize},
+};
+use codec::{EncodeLike, FullCodec, MaxEncodedLen};
+use sp_std::prelude::*;
+
+/// A wrapper around a `StorageValue` and a `StorageValue<Value=u64>` to keep track of how many
+/// bytes the key and value of a storage value occupy, optionally rejecting writes beyond
+/// `MaxBytes`.
+///
+/// See [`SizeTrackedStorageMap`](super::SizeTrackedStorageMap) for how the size is accounted.
+pub struct SizeTrackedStorageValue<
+	Prefix,
+	Value,
+	QueryKind = OptionQuery,
+	OnEmpty = GetDefault,
+	MaxBytes = GetDefault,
+>(core::marker::PhantomData<(Prefix, Value, QueryKind, OnEmpty, MaxBytes)>);
+
+// Private helper trait to access value from size tracked storage value.
+trait ValueWrapper {
+	type Value;
+}
+
+impl<P: SizeTrackedStorageInstance, V, Q, O, B> ValueWrapper
+	for SizeTrackedStorageValue<P, V, Q, O, B>
+{
+	type Value = StorageValue<P, V, Q, O>;
+}
+
+impl<Prefix, Value, QueryKind, OnEmpty, MaxBytes>
+	SizeTrackedStorageValue<Prefix, Value, QueryKind, OnEmpty, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxBytes: Get<Option<u64>>,
+{
+	/// Get the storage key.
+	pub fn hashed_key() -> [u8; 32] {
+		<Self as ValueWrapper>::Value::hashed_key()
+	}
+
+	/// The key used to store the size of the value.
+	pub fn size_storage_final_key() -> [u8; 32] {
+		ByteSizeFor::<Prefix>::hashed_key()
+	}
+
+	/// Does the value (explicitly) exist in storage?
+	pub fn exists() -> bool {
+		<Self as ValueWrapper>::Value::exists()
+	}
+
+	/// Load the value from the provided storage instance.
+	pub fn get() -> QueryKind::Query {
+		<Self as ValueWrapper>::Value::get()
+	}
+
+	/// Try to get the underlying value from the provided storage instance.
+	///
+	/// Returns `Ok` if it exists, `Err` if not.
+	pub fn try_get() -> Result<Value, ()> {
+		<Self as ValueWrapper>::Value::try_get()
+	}
+
+	/// Try to store a value under this key into the provided storage instance.
+	///
+	/// Fails without writing if the value would exceed `MaxBytes`.
+	pub fn try_put<Arg: EncodeLike<Value>>(val: Arg) -> Result<(), MaxBytesExceeded> {
+		size_tracked::try_put::<Prefix, _>(&Self::hashed_key(), &val, MaxBytes::get())
+	}
+
+	/// Mutate the value.
+	///
+	/// Fails without writing if the value would exceed `MaxBytes`.
+	pub fn mutate<R, F: FnOnce(&mut QueryKind::Query) -> R>(f: F) -> Result<R, MaxBytesExceeded> {
+		Self::try_mutate(|v| Ok(f(v)))
+	}
+
+	/// Mutate the value, only if an `Ok` value is returned and the value would not exceed
+	/// `MaxBytes`.
+	pub fn try_mutate<R, E, F>(f: F) -> Result<R, E>
+	where
+		E: From<MaxBytesExceeded>,
+		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
+	{
+		size_tracked::try_mutate::<Prefix, Value, QueryKind, OnEmpty, _, _, _>(
+			&Self::hashed_key(),
+			MaxBytes::get(),
+			f,
+		)
+	}
+
+	/// Mutate the value, only if an `Ok` value is returned and the value would not exceed
+	/// `MaxBytes`. Deletes the item if mutated to a `None`.
+	pub fn try_mutate_exists<R, E, F>(f: F) -> Result<R, E>
+	where
+		E: From<MaxBytesExceeded>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		size_tracked::try_mutate_exists::<Prefix, _, _, _, _>(
+			&Self::hashed_key(),
+			MaxBytes::get(),
+			f,
+		)
+	}
+
+	/// Clear the storage value.
+	pub fn kill() {
+		size_tracked::kill::<Prefix>(&Self::hashed_key())
+	}
+
+	/// Take a value from storage, removing it afterwards.
+	pub fn take() -> QueryKind::Query {
+		let removed_value = size_tracked::take::<Prefix, Value>(&Self::hashed_key());
+		QueryKind::from_optional_value_to_query(removed_value)
+	}
+
+	/// Initialize the size with the actual number of bytes the value occupies.
+	///
+	/// Returns the number of bytes which is used to set the size.
+	pub fn initialize_byte_size() -> u64 {
+		size_tracked::initialize_byte_size::<Prefix>(&Self::hashed_key())
+	}
+
+	/// Return the number of bytes the value occupies.
+	pub fn byte_size() -> u64 {
+		ByteSizeFor::<Prefix>::get()
+	}
+}
+
+impl<Prefix, Value, QueryKind, OnEmpty, MaxBytes> StorageEntryMetadataBuilder
+	for SizeTrackedStorageValue<Prefix, Value, QueryKind, OnEmpty, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Value: FullCodec + scale_info::StaticTypeInfo,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn build_metadata(docs: Vec<&'static str>, entries: &mut Vec<StorageEntryMetadata>) {
+		<Self as ValueWrapper>::Value::build_metadata(docs, entries);
+		size_tracked::build_size_metadata::<Prefix>(entries);
+	}
+}
+
+impl<Prefix, Value, QueryKind, OnEmpty, MaxBytes> StorageInfoTrait
+	for SizeTrackedStorageValue<Prefix, Value, QueryKind, OnEmpty, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Value: FullCodec + MaxEncodedLen,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn storage_info() -> Vec<StorageInfo> {
+		[<Self as ValueWrapper>::Value::storage_info(), ByteSizeFor::<Prefix>::storage_info()]
+			.concat()
+	}
+}
+
+/// It doesn't require to implement `MaxEncodedLen` and give no information for `max_size`.
+impl<Prefix, Value, QueryKind, OnEmpty, MaxBytes> crate::traits::PartialStorageInfoTrait
+	for SizeTrackedStorageValue<Prefix, Value, QueryKind, OnEmpty, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn partial_storage_info() -> Vec<StorageInfo> {
+		[
+			<Self as ValueWrapper>::Value::partial_storage_info(),
+			ByteSizeFor::<Prefix>::storage_info(),
+		]
+		.concat()
+	}
+}
+
+impl<Prefix, Value, QueryKind, OnEmpty, MaxBytes> crate::traits::StorageSizeTrait
+	for SizeTrackedStorageValue<Prefix, Value, QueryKind, OnEmpty, MaxBytes>
+where
+	Prefix: SizeTrackedStorageInstance,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxBytes: Get<Option<u64>>,
+{
+	fn storage_size() -> Vec<StorageSize> {
+		size_tracked::storage_size::<Prefix>(MaxBytes::get())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		hash::*,
+		storage::types::{PalletStorageSize, SizeTrackedStorageMap, ValueQuery},
+		traits::{StorageInstance, StorageSizeTrait},
+	};
+	use sp_io::TestExternalities;
+	use sp_runtime::DispatchError;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	struct SizePrefix;
+	impl StorageInstance for SizePrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "byte_size_for_foo";
+	}
+
+	struct MapPrefix;
+	impl StorageInstance for MapPrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "bar";
+	}
+
+	struct MapSizePrefix;
+	impl StorageInstance for MapSizePrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "byte_size_for_bar";
+	}
+
+	struct PalletSizePrefix;
+	impl StorageInstance for PalletSizePrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "storage_byte_size";
+	}
+
+	crate::parameter_types! {
+		pub const MaxBytes: Option<u64> = Some(40);
+		pub const PalletMaxBytes: Option<u64> = Some(100);
+	}
+
+	impl SizeTrackedStorageInstance for Prefix {
+		type SizePrefix = SizePrefix;
+		type PalletSizePrefix = PalletSizePrefix;
+		type PalletMaxBytes = PalletMaxBytes;
+	}
+
+	impl SizeTrackedStorageInstance for MapPrefix {
+		type SizePrefix = MapSizePrefix;
+		type PalletSizePrefix = PalletSizePrefix;
+		type PalletMaxBytes = PalletMaxBytes;
+	}
+
+	// 32 bytes of key.
+	const KEY_LEN: u64 = 32;
+
+	type A = SizeTrackedStorageValue<Prefix, Vec<u8>, ValueQuery, GetDefault, MaxBytes>;
+	type B = SizeTrackedStorageMap<MapPrefix, Twox64Concat, u16, Vec<u8>>;
+	type Pallet = PalletStorageSize<PalletSizePrefix, PalletMaxBytes>;
+
+	#[test]
+	fn size_is_tracked() {
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(A::byte_size(), 0);
+
+			assert_eq!(A::try_put(vec![1, 2, 3]), Ok(()));
+			assert_eq!(A::byte_size(), KEY_LEN + 4);
+
+			assert_eq!(A::mutate(|v| v.push(4)), Ok(()));
+			assert_eq!(A::byte_size(), KEY_LEN + 5);
+
+			// Would be 41 bytes.
+			assert_eq!(A::try_put(vec![1; 8]), Err(MaxBytesExceeded));
+			assert_eq!(A::get(), vec![1, 2, 3, 4]);
+			assert_eq!(
+				A::try_mutate(|v| {
+					v.push(5);
+					Err::<(), _>(DispatchError::Other("nope"))
+				}),
+				Err(DispatchError::Other("nope")),
+			);
+			assert_eq!(A::byte_size(), KEY_LEN + 5);
+
+			assert_eq!(A::take(), vec![1, 2, 3, 4]);
+			assert!(!A::exists());
+			assert_eq!(A::byte_size(), 0);
+
+			A::try_put(vec![1]).unwrap();
+			A::kill();
+			assert_eq!(A::byte_size(), 0);
+			assert_eq!(Pallet::byte_size(), 0);
+		});
+	}
+
+	#[test]
+	fn pallet_max_bytes_rejects_writes() {
+		TestExternalities::default().execute_with(|| {
+			// 32 + 6 bytes.
+			assert_eq!(A::try_put(vec![1; 5]), Ok(()));
+			// 42 + 11 bytes.
+			assert_eq!(B::try_insert(1, vec![1; 10]), Ok(()));
+			assert_eq!(Pallet::byte_size(), A::byte_size() + B::byte_size());
+
+			// The map doesn't have a maximum of its own but the pallet would occupy 101 bytes.
+			assert_eq!(B::try_insert(1, vec![1; 20]), Err(MaxBytesExceeded));
+			assert_eq!(B::try_insert(1, vec![1; 19]), Ok(()));
+			assert_eq!(Pallet::byte_size(), 100);
+			assert_eq!(A::mutate(|v| v.push(1)), Err(MaxBytesExceeded));
+
+			// Freeing bytes in one storage allows the other one to grow.
+			assert_eq!(B::take(1), Some(vec![1; 19]));
+			assert_eq!(Pallet::byte_size(), KEY_LEN + 6);
+			assert_eq!(A::mutate(|v| v.push(1)), Ok(()));
+
+			assert_eq!(
+				Pallet::storage_size(),
+				vec![StorageSize {
+					pallet_name: b"test".to_vec(),
+					storage_name: vec![],
+					bytes: KEY_LEN + 7,
+					max_bytes: Some(100),
+				}]
+			);
+			assert_eq!(
+				A::storage_size(),
+				vec![StorageSize {
+					pallet_name: b"test".to_vec(),
+					storage_name: b"foo".to_vec(),
+					bytes: KEY_LEN + 7,
+					max_bytes: Some(40),
+				}]
+			);
+		});
+	}
+
+	#[test]
+	fn initialize_byte_size_works() {
+		TestExternalities::default().execute_with(|| {
+			StorageValue::<Prefix, u32>::put(1);
+			assert_eq!(A::byte_size(), 0);
+			assert_eq!(A::initialize_byte_size(), KEY_LEN + 4);
+			assert_eq!(A::byte_size(), KEY_LEN + 4);
+			assert_eq!(Pallet::byte_size(), KEY_LEN + 4);
+		});
+	}
+
+	#[test]
+	fn metadata_and_storage_info_work() {
+		let mut entries = vec![];
+		A::build_metadata(vec![], &mut entries);
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].name, "foo");
+		assert_eq!(entries[1].name, "byte_size_for_foo");
+
+		let info = <A as crate::traits::PartialStorageInfoTrait>::partial_storage_info();
+		assert_eq!(info.len(), 2);
+		assert_eq!(info[1].storage_name, b"byte_size_for_foo".to_vec());
+		assert_eq!(info[1].max_size, Some(8));
+	}
+}
@@ -550,6 +550,28 @@ where
 	}
 }
 
+/// Anything that can have a `::VARIANT_COUNT`, the number of variants of an enum.
+///
+/// Implemented by the composite enums of the pallets and by their aggregation in the runtime,
+/// e.g. `RuntimeHoldReason`, so that bounds such as `MaxHolds` can follow the runtime.
+pub trait VariantCount {
+	/// The number of variants of the type.
+	const VARIANT_COUNT: u32;
+}
+
+impl VariantCount for () {
+	const VARIANT_COUNT: u32 = 0;
+}
+
+/// Adapter to get the `VARIANT_COUNT` of a type as a `Get<u32>`.
+pub struct VariantCountOf<T: VariantCount>(sp_std::marker::PhantomData<T>);
+
+impl<T: VariantCount> Get<u32> for VariantCountOf<T> {
+	fn get() -> u32 {
+		T::VARIANT_COUNT
+	}
+}
+
 /// A type for which some values make sense to be able to drop without further consideration.
 pub trait TryDrop: Sized {
 	/// Drop an instance cleanly. Only works if its value represents "no-operation".
@@ -93,6 +93,29 @@ pub trait PartialStorageInfoTrait {
 	fn partial_storage_info() -> Vec<StorageInfo>;
 }
 
+pub use sp_storage_size::StorageSize;
+
+/// A trait to give the number of bytes occupied by storages which keep track of it, such as a
+/// [`SizeTrackedStorageMap`](crate::storage::types::SizeTrackedStorageMap).
+///
+/// It is implemented by pallets for their size tracked storages, and by tuples of pallets such as
+/// `AllPalletsWithSystem`.
+pub trait StorageSizeTrait {
+	/// The size of each size tracked storage, followed by the total of their pallet if any.
+	fn storage_size() -> Vec<StorageSize>;
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+impl StorageSizeTrait for Tuple {
+	fn storage_size() -> Vec<StorageSize> {
+		let mut res = vec![];
+		for_tuples!( #( res.extend(Tuple::storage_size()); )* );
+		res
+	}
+}
+
 /// Allows a pallet to specify storage keys to whitelist during benchmarking.
 /// This means those keys will be excluded from the benchmarking performance
 /// calculation.
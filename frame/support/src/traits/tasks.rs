@@ -0,0 +1,73 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deferred work of pallets, see `#[pallet::tasks]`.
+
+use crate::weights::Weight;
+use codec::FullCodec;
+use scale_info::TypeInfo;
+use sp_runtime::{DispatchError, DispatchResult};
+use sp_std::{boxed::Box, fmt::Debug, iter};
+
+/// A unit of work of a pallet which is run whenever there is spare weight.
+///
+/// Implemented by the `Task` enum generated by `#[pallet::tasks]` and by the `RuntimeTask` enum
+/// aggregating them, generated by `construct_runtime!`. The executive runs the valid tasks of the
+/// runtime with the weight left after `on_idle`, and anyone can submit a valid task with the
+/// unsigned `frame_system::Call::do_task`.
+pub trait Task: Sized + FullCodec + TypeInfo + Clone + Debug + PartialEq + Eq {
+	/// The tasks which may currently be run.
+	///
+	/// The tasks are checked with [`Task::is_valid`] before being run, so this may be a cheap
+	/// superset of the valid tasks.
+	fn iter() -> Box<dyn Iterator<Item = Self>>;
+
+	/// Whether the task can currently be run.
+	fn is_valid(&self) -> bool;
+
+	/// Run the task.
+	fn run(&self) -> DispatchResult;
+
+	/// The weight of running the task.
+	fn weight(&self) -> Weight;
+
+	/// The index of the task in its pallet, given by `#[pallet::task_index]`.
+	fn task_index(&self) -> u32;
+}
+
+/// No tasks, for runtimes not built with `construct_runtime!`.
+impl Task for () {
+	fn iter() -> Box<dyn Iterator<Item = Self>> {
+		Box::new(iter::empty())
+	}
+
+	fn is_valid(&self) -> bool {
+		false
+	}
+
+	fn run(&self) -> DispatchResult {
+		Err(DispatchError::Other("no task to run"))
+	}
+
+	fn weight(&self) -> Weight {
+		Weight::zero()
+	}
+
+	fn task_index(&self) -> u32 {
+		0
+	}
+}
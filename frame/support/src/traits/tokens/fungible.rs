@@ -25,6 +25,8 @@ use crate::{
 	dispatch::{DispatchError, DispatchResult},
 	traits::misc::Get,
 };
+use codec::Encode;
+use scale_info::TypeInfo;
 use sp_runtime::traits::Saturating;
 
 mod balanced;
@@ -122,37 +124,47 @@ pub trait Transfer<AccountId>: Inspect<AccountId> {
 	) -> Result<Self::Balance, DispatchError>;
 }
 
-/// Trait for inspecting a fungible asset which can be reserved.
+/// Trait for inspecting a fungible asset which can be placed on hold.
 pub trait InspectHold<AccountId>: Inspect<AccountId> {
-	/// Amount of funds held in reserve by `who`.
-	fn balance_on_hold(who: &AccountId) -> Self::Balance;
+	/// An identifier for a hold. Used for disambiguating different holds so that
+	/// they can be individually replaced or removed and funds from one hold don't accidentally
+	/// become released or slashed for another.
+	type Reason: Encode + TypeInfo + 'static;
 
-	/// Check to see if some `amount` of funds of `who` may be placed on hold.
-	fn can_hold(who: &AccountId, amount: Self::Balance) -> bool;
+	/// Amount of funds on hold (for all hold reasons) of `who`.
+	fn total_balance_on_hold(who: &AccountId) -> Self::Balance;
+
+	/// Amount of funds on hold for the given `reason` of `who`.
+	fn balance_on_hold(reason: &Self::Reason, who: &AccountId) -> Self::Balance;
+
+	/// Check to see if some `amount` of funds of `who` may be placed on hold for the given
+	/// `reason`.
+	fn can_hold(reason: &Self::Reason, who: &AccountId, amount: Self::Balance) -> bool;
 }
 
-/// Trait for mutating a fungible asset which can be reserved.
+/// Trait for mutating a fungible asset which can be placed on hold.
 pub trait MutateHold<AccountId>: InspectHold<AccountId> + Transfer<AccountId> {
-	/// Hold some funds in an account.
-	fn hold(who: &AccountId, amount: Self::Balance) -> DispatchResult;
+	/// Hold some funds in an account for the given `reason`.
+	fn hold(reason: &Self::Reason, who: &AccountId, amount: Self::Balance) -> DispatchResult;
 
-	/// Release up to `amount` held funds in an account.
+	/// Release up to `amount` held funds in an account which were held for the given `reason`.
 	///
 	/// The actual amount released is returned with `Ok`.
 	///
 	/// If `best_effort` is `true`, then the amount actually unreserved and returned as the inner
 	/// value of `Ok` may be smaller than the `amount` passed.
 	fn release(
+		reason: &Self::Reason,
 		who: &AccountId,
 		amount: Self::Balance,
 		best_effort: bool,
 	) -> Result<Self::Balance, DispatchError>;
 
-	/// Transfer held funds into a destination account.
+	/// Transfer funds held for the given `reason` into a destination account.
 	///
 	/// If `on_hold` is `true`, then the destination account must already exist and the assets
-	/// transferred will still be on hold in the destination account. If not, then the destination
-	/// account need not already exist, but must be creatable.
+	/// transferred will still be on hold for the same `reason` in the destination account. If
+	/// not, then the destination account need not already exist, but must be creatable.
 	///
 	/// If `best_effort` is `true`, then an amount less than `amount` may be transferred without
 	/// error.
@@ -160,23 +172,30 @@ pub trait MutateHold<AccountId>: InspectHold<AccountId> + Transfer<AccountId> {
 	/// The actual amount transferred is returned, or `Err` in the case of error and nothing is
 	/// changed.
 	fn transfer_held(
+		reason: &Self::Reason,
 		source: &AccountId,
 		dest: &AccountId,
 		amount: Self::Balance,
 		best_effort: bool,
-		on_held: bool,
+		on_hold: bool,
 	) -> Result<Self::Balance, DispatchError>;
 }
 
-/// Trait for slashing a fungible asset which can be reserved.
+/// Trait for slashing a fungible asset which can be placed on hold.
 pub trait BalancedHold<AccountId>: Balanced<AccountId> + MutateHold<AccountId> {
-	/// Reduce the balance of some funds on hold in an account.
+	/// Reduce the balance of some funds on hold for the given `reason` in an account.
 	///
 	/// The resulting imbalance is the first item of the tuple returned.
 	///
 	/// As much funds that are on hold up to `amount` will be deducted as possible. If this is less
 	/// than `amount`, then a non-zero second item will be returned.
+	///
+	/// The second item is the part of `amount` which was not slashed, i.e. both the part which
+	/// was not on hold and the part which was released but could not be slashed. Previously only
+	/// the latter was returned, callers comparing it against zero to detect a full slash must
+	/// account for the funds which were never on hold.
 	fn slash_held(
+		reason: &Self::Reason,
 		who: &AccountId,
 		amount: Self::Balance,
 	) -> (CreditOf<AccountId, Self>, Self::Balance);
@@ -184,17 +203,55 @@ pub trait BalancedHold<AccountId>: Balanced<AccountId> + MutateHold<AccountId> {
 
 impl<AccountId, T: Balanced<AccountId> + MutateHold<AccountId>> BalancedHold<AccountId> for T {
 	fn slash_held(
+		reason: &Self::Reason,
 		who: &AccountId,
 		amount: Self::Balance,
 	) -> (CreditOf<AccountId, Self>, Self::Balance) {
-		let actual = match Self::release(who, amount, true) {
+		let actual = match Self::release(reason, who, amount, true) {
 			Ok(x) => x,
 			Err(_) => return (Imbalance::default(), amount),
 		};
-		<Self as fungible::Balanced<AccountId>>::slash(who, actual)
+		let (credit, missing) = <Self as fungible::Balanced<AccountId>>::slash(who, actual);
+		(credit, amount.saturating_sub(actual).saturating_add(missing))
 	}
 }
 
+/// Trait for inspecting a fungible asset which can be frozen. Freezing is essentially setting a
+/// minimum balance below which the free balance cannot drop, for a given identifier.
+///
+/// Several freezes may exist on an account at the same time, they overlap: the amount that can
+/// not be withdrawn is the largest of them.
+pub trait InspectFreeze<AccountId>: Inspect<AccountId> {
+	/// An identifier for a freeze.
+	type Id: Encode + TypeInfo + 'static;
+
+	/// Amount of funds of `who` frozen for the given `id`.
+	fn balance_frozen(id: &Self::Id, who: &AccountId) -> Self::Balance;
+
+	/// The amount of the balance of `who` which may become frozen.
+	fn balance_freezable(who: &AccountId) -> Self::Balance;
+
+	/// Returns `true` if it's possible to introduce a freeze for the given `id` onto the account
+	/// of `who`. This will be true as long as the implementor supports as many concurrent freezes
+	/// as there are possible values of `id`.
+	fn can_freeze(id: &Self::Id, who: &AccountId) -> bool;
+}
+
+/// Trait for introducing, altering and removing freezes on a fungible asset.
+pub trait MutateFreeze<AccountId>: InspectFreeze<AccountId> {
+	/// Prevent the free balance of `who` from being reduced below `amount` for the given `id`.
+	///
+	/// Any existing freeze for `id` is replaced. A zero `amount` removes the freeze.
+	fn set_freeze(id: &Self::Id, who: &AccountId, amount: Self::Balance) -> DispatchResult;
+
+	/// Prevent the free balance of `who` from being reduced below `amount` for the given `id`,
+	/// keeping any existing freeze for `id` if it is larger.
+	fn extend_freeze(id: &Self::Id, who: &AccountId, amount: Self::Balance) -> DispatchResult;
+
+	/// Remove the freeze of `who` for the given `id`.
+	fn thaw(id: &Self::Id, who: &AccountId) -> DispatchResult;
+}
+
 /// Convert a `fungibles` trait implementation into a `fungible` trait implementation by identifying
 /// a single item.
 pub struct ItemOf<
@@ -266,11 +323,16 @@ impl<
 		AccountId,
 	> InspectHold<AccountId> for ItemOf<F, A, AccountId>
 {
-	fn balance_on_hold(who: &AccountId) -> Self::Balance {
-		<F as fungibles::InspectHold<AccountId>>::balance_on_hold(A::get(), who)
+	type Reason = <F as fungibles::InspectHold<AccountId>>::Reason;
+
+	fn total_balance_on_hold(who: &AccountId) -> Self::Balance {
+		<F as fungibles::InspectHold<AccountId>>::total_balance_on_hold(A::get(), who)
+	}
+	fn balance_on_hold(reason: &Self::Reason, who: &AccountId) -> Self::Balance {
+		<F as fungibles::InspectHold<AccountId>>::balance_on_hold(A::get(), reason, who)
 	}
-	fn can_hold(who: &AccountId, amount: Self::Balance) -> bool {
-		<F as fungibles::InspectHold<AccountId>>::can_hold(A::get(), who, amount)
+	fn can_hold(reason: &Self::Reason, who: &AccountId, amount: Self::Balance) -> bool {
+		<F as fungibles::InspectHold<AccountId>>::can_hold(A::get(), reason, who, amount)
 	}
 }
 
@@ -280,17 +342,19 @@ impl<
 		AccountId,
 	> MutateHold<AccountId> for ItemOf<F, A, AccountId>
 {
-	fn hold(who: &AccountId, amount: Self::Balance) -> DispatchResult {
-		<F as fungibles::MutateHold<AccountId>>::hold(A::get(), who, amount)
+	fn hold(reason: &Self::Reason, who: &AccountId, amount: Self::Balance) -> DispatchResult {
+		<F as fungibles::MutateHold<AccountId>>::hold(A::get(), reason, who, amount)
 	}
 	fn release(
+		reason: &Self::Reason,
 		who: &AccountId,
 		amount: Self::Balance,
 		best_effort: bool,
 	) -> Result<Self::Balance, DispatchError> {
-		<F as fungibles::MutateHold<AccountId>>::release(A::get(), who, amount, best_effort)
+		<F as fungibles::MutateHold<AccountId>>::release(A::get(), reason, who, amount, best_effort)
 	}
 	fn transfer_held(
+		reason: &Self::Reason,
 		source: &AccountId,
 		dest: &AccountId,
 		amount: Self::Balance,
@@ -299,6 +363,7 @@ impl<
 	) -> Result<Self::Balance, DispatchError> {
 		<F as fungibles::MutateHold<AccountId>>::transfer_held(
 			A::get(),
+			reason,
 			source,
 			dest,
 			amount,
@@ -22,6 +22,8 @@ use super::{
 	*,
 };
 use crate::dispatch::{DispatchError, DispatchResult};
+use codec::Encode;
+use scale_info::TypeInfo;
 use sp_runtime::traits::Saturating;
 use sp_std::vec::Vec;
 
@@ -181,34 +183,58 @@ pub trait Transfer<AccountId>: Inspect<AccountId> {
 
 /// Trait for inspecting a set of named fungible assets which can be placed on hold.
 pub trait InspectHold<AccountId>: Inspect<AccountId> {
-	/// Amount of funds held in hold.
-	fn balance_on_hold(asset: Self::AssetId, who: &AccountId) -> Self::Balance;
+	/// An identifier for a hold. Used for disambiguating different holds so that
+	/// they can be individually replaced or removed and funds from one hold don't accidentally
+	/// become released or slashed for another.
+	type Reason: Encode + TypeInfo + 'static;
 
-	/// Check to see if some `amount` of `asset` may be held on the account of `who`.
-	fn can_hold(asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> bool;
+	/// Amount of funds of `asset` on hold (for all hold reasons) of `who`.
+	fn total_balance_on_hold(asset: Self::AssetId, who: &AccountId) -> Self::Balance;
+
+	/// Amount of funds of `asset` on hold for the given `reason` of `who`.
+	fn balance_on_hold(
+		asset: Self::AssetId,
+		reason: &Self::Reason,
+		who: &AccountId,
+	) -> Self::Balance;
+
+	/// Check to see if some `amount` of `asset` may be held on the account of `who` for the given
+	/// `reason`.
+	fn can_hold(
+		asset: Self::AssetId,
+		reason: &Self::Reason,
+		who: &AccountId,
+		amount: Self::Balance,
+	) -> bool;
 }
 
 /// Trait for mutating a set of named fungible assets which can be placed on hold.
 pub trait MutateHold<AccountId>: InspectHold<AccountId> + Transfer<AccountId> {
-	/// Hold some funds in an account.
-	fn hold(asset: Self::AssetId, who: &AccountId, amount: Self::Balance) -> DispatchResult;
+	/// Hold some funds in an account for the given `reason`.
+	fn hold(
+		asset: Self::AssetId,
+		reason: &Self::Reason,
+		who: &AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult;
 
-	/// Release some funds in an account from being on hold.
+	/// Release some funds in an account from being on hold for the given `reason`.
 	///
 	/// If `best_effort` is `true`, then the amount actually released and returned as the inner
 	/// value of `Ok` may be smaller than the `amount` passed.
 	fn release(
 		asset: Self::AssetId,
+		reason: &Self::Reason,
 		who: &AccountId,
 		amount: Self::Balance,
 		best_effort: bool,
 	) -> Result<Self::Balance, DispatchError>;
 
-	/// Transfer held funds into a destination account.
+	/// Transfer funds held for the given `reason` into a destination account.
 	///
 	/// If `on_hold` is `true`, then the destination account must already exist and the assets
-	/// transferred will still be on hold in the destination account. If not, then the destination
-	/// account need not already exist, but must be creatable.
+	/// transferred will still be on hold for the same `reason` in the destination account. If
+	/// not, then the destination account need not already exist, but must be creatable.
 	///
 	/// If `best_effort` is `true`, then an amount less than `amount` may be transferred without
 	/// error.
@@ -217,6 +243,7 @@ pub trait MutateHold<AccountId>: InspectHold<AccountId> + Transfer<AccountId> {
 	/// changed.
 	fn transfer_held(
 		asset: Self::AssetId,
+		reason: &Self::Reason,
 		source: &AccountId,
 		dest: &AccountId,
 		amount: Self::Balance,
@@ -227,14 +254,20 @@ pub trait MutateHold<AccountId>: InspectHold<AccountId> + Transfer<AccountId> {
 
 /// Trait for mutating one of several types of fungible assets which can be held.
 pub trait BalancedHold<AccountId>: Balanced<AccountId> + MutateHold<AccountId> {
-	/// Release and slash some funds in an account.
+	/// Release and slash some funds held for the given `reason` in an account.
 	///
 	/// The resulting imbalance is the first item of the tuple returned.
 	///
 	/// As much funds up to `amount` will be deducted as possible. If this is less than `amount`,
 	/// then a non-zero second item will be returned.
+	///
+	/// The second item is the part of `amount` which was not slashed, i.e. both the part which
+	/// was not on hold and the part which was released but could not be slashed. Previously only
+	/// the latter was returned, callers comparing it against zero to detect a full slash must
+	/// account for the funds which were never on hold.
 	fn slash_held(
 		asset: Self::AssetId,
+		reason: &Self::Reason,
 		who: &AccountId,
 		amount: Self::Balance,
 	) -> (CreditOf<AccountId, Self>, Self::Balance);
@@ -243,14 +276,16 @@ pub trait BalancedHold<AccountId>: Balanced<AccountId> + MutateHold<AccountId> {
 impl<AccountId, T: Balanced<AccountId> + MutateHold<AccountId>> BalancedHold<AccountId> for T {
 	fn slash_held(
 		asset: Self::AssetId,
+		reason: &Self::Reason,
 		who: &AccountId,
 		amount: Self::Balance,
 	) -> (CreditOf<AccountId, Self>, Self::Balance) {
-		let actual = match Self::release(asset, who, amount, true) {
+		let actual = match Self::release(asset, reason, who, amount, true) {
 			Ok(x) => x,
 			Err(_) => return (Imbalance::zero(asset), amount),
 		};
-		<Self as fungibles::Balanced<AccountId>>::slash(asset, who, actual)
+		let (credit, missing) = <Self as fungibles::Balanced<AccountId>>::slash(asset, who, actual);
+		(credit, amount.saturating_sub(actual).saturating_add(missing))
 	}
 }
 
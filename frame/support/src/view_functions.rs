@@ -0,0 +1,420 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed read-only queries declared by pallets.
+//!
+//! The functions of a `#[pallet::view_functions]` impl block are callable by clients through the
+//! single [`RuntimeViewFunction`] runtime API, without declaring a runtime API per pallet. Each
+//! view function gets a struct holding its arguments that implements [`ViewFunction`], and is
+//! described in the metadata returned by [`RuntimeViewFunction::view_functions_metadata`].
+//!
+//! The runtime implements the API by dispatching to all of its pallets:
+//!
+//! ```ignore
+//! impl frame_support::view_functions::RuntimeViewFunction<Block> for Runtime {
+//! 	fn execute_view_function(
+//! 		id: ViewFunctionId,
+//! 		input: Vec<u8>,
+//! 	) -> Result<Vec<u8>, ViewFunctionDispatchError> {
+//! 		frame_support::view_functions::execute_view_function::<AllPalletsWithSystem>(id, input)
+//! 	}
+//!
+//! 	fn view_functions_metadata() -> OpaqueMetadata {
+//! 		frame_support::view_functions::view_functions_metadata::<AllPalletsWithSystem>()
+//! 	}
+//! }
+//! ```
+
+use codec::{Decode, DecodeAll, Encode, MaxEncodedLen, Output};
+use impl_trait_for_tuples::impl_for_tuples;
+use scale_info::{
+	form::{Form, MetaForm, PortableForm},
+	Field, IntoPortable, MetaType, PortableRegistry, Registry, TypeDef, TypeInfo,
+};
+use sp_core::OpaqueMetadata;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// The identifier of a view function.
+///
+/// The prefix is the `twox_128` hash of the name of the pallet in the runtime and the suffix is
+/// computed by [`view_function_id_suffix`] from the name of the function and the [`TypeInfo`] of
+/// its argument and return types, so that changing how the arguments or the result of a view
+/// function are encoded also changes its id.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct ViewFunctionId {
+	pub prefix: [u8; 16],
+	pub suffix: [u8; 16],
+}
+
+/// The error of executing a view function.
+#[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ViewFunctionDispatchError {
+	/// No view function of the runtime has this id.
+	NotFound(ViewFunctionId),
+	/// The input is not the encoded arguments of the view function.
+	Codec,
+}
+
+impl From<codec::Error> for ViewFunctionDispatchError {
+	fn from(_: codec::Error) -> Self {
+		Self::Codec
+	}
+}
+
+/// A call to a view function, i.e. its arguments.
+///
+/// Implemented by the structs generated by `#[pallet::view_functions]`, the encoding of such a
+/// struct is the input of the view function.
+pub trait ViewFunction: DecodeAll {
+	/// The type returned by the view function.
+	type ReturnType: Encode;
+
+	/// The id of the view function.
+	fn id() -> ViewFunctionId;
+
+	/// Call the view function.
+	fn invoke(self) -> Self::ReturnType;
+
+	/// Decode the arguments from `input`, call the view function and encode its result into
+	/// `output`.
+	fn execute<O: Output>(
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		let view_function = Self::decode_all(input)?;
+		view_function.invoke().encode_to(output);
+		Ok(())
+	}
+}
+
+/// Execute a view function given its id.
+///
+/// Implemented by the pallets for their view functions and by tuples of pallets such as
+/// `AllPalletsWithSystem`.
+pub trait DispatchViewFunction {
+	/// Execute the view function `id`, see [`ViewFunction::execute`].
+	///
+	/// Returns [`ViewFunctionDispatchError::NotFound`] if `id` is not a view function of `Self`.
+	fn dispatch_view_function<O: Output>(
+		id: &ViewFunctionId,
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError>;
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+impl DispatchViewFunction for Tuple {
+	fn dispatch_view_function<O: Output>(
+		id: &ViewFunctionId,
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		for_tuples!( #(
+			match Tuple::dispatch_view_function(id, input, output) {
+				Err(ViewFunctionDispatchError::NotFound(_)) => (),
+				res => return res,
+			}
+		)* );
+		Err(ViewFunctionDispatchError::NotFound(*id))
+	}
+}
+
+/// The metadata of the view functions.
+///
+/// Implemented by the pallets and by tuples of pallets such as `AllPalletsWithSystem`.
+pub trait ViewFunctionsMetadata {
+	/// The view functions of each pallet, pallets without view functions are omitted.
+	fn view_functions_metadata() -> Vec<PalletViewFunctionsMetadata>;
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+impl ViewFunctionsMetadata for Tuple {
+	fn view_functions_metadata() -> Vec<PalletViewFunctionsMetadata> {
+		let mut res = vec![];
+		for_tuples!( #( res.extend(Tuple::view_functions_metadata()); )* );
+		res
+	}
+}
+
+/// The metadata of the view functions of a pallet.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode))]
+pub struct PalletViewFunctionsMetadata<T: Form = MetaForm> {
+	/// The name of the pallet in the runtime.
+	pub name: T::String,
+	/// The view functions of the pallet.
+	pub view_functions: Vec<ViewFunctionMetadata<T>>,
+}
+
+impl IntoPortable for PalletViewFunctionsMetadata {
+	type Output = PalletViewFunctionsMetadata<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		PalletViewFunctionsMetadata {
+			name: self.name.into_portable(registry),
+			view_functions: registry.map_into_portable(self.view_functions),
+		}
+	}
+}
+
+/// The metadata of a view function.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode))]
+pub struct ViewFunctionMetadata<T: Form = MetaForm> {
+	/// The name of the function.
+	pub name: T::String,
+	/// The id to call the function with.
+	pub id: ViewFunctionId,
+	/// The arguments of the function, in the order they are encoded in the input.
+	pub inputs: Vec<ViewFunctionArgMetadata<T>>,
+	/// The type returned by the function.
+	pub output: T::Type,
+	/// The documentation of the function.
+	pub docs: Vec<T::String>,
+}
+
+impl IntoPortable for ViewFunctionMetadata {
+	type Output = ViewFunctionMetadata<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		ViewFunctionMetadata {
+			name: self.name.into_portable(registry),
+			id: self.id,
+			inputs: registry.map_into_portable(self.inputs),
+			output: registry.register_type(&self.output),
+			docs: registry.map_into_portable(self.docs),
+		}
+	}
+}
+
+/// The metadata of an argument of a view function.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode))]
+pub struct ViewFunctionArgMetadata<T: Form = MetaForm> {
+	/// The name of the argument.
+	pub name: T::String,
+	/// The type of the argument.
+	pub ty: T::Type,
+}
+
+impl IntoPortable for ViewFunctionArgMetadata {
+	type Output = ViewFunctionArgMetadata<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		ViewFunctionArgMetadata {
+			name: self.name.into_portable(registry),
+			ty: registry.register_type(&self.ty),
+		}
+	}
+}
+
+/// The metadata of all the view functions of a runtime, along with the types they refer to.
+///
+/// This is what [`RuntimeViewFunction::view_functions_metadata`] returns encoded.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode))]
+pub struct RuntimeViewFunctionsMetadata {
+	/// The types of the arguments and the return types of the view functions.
+	pub types: PortableRegistry,
+	/// The view functions of each pallet.
+	pub pallets: Vec<PalletViewFunctionsMetadata<PortableForm>>,
+}
+
+impl RuntimeViewFunctionsMetadata {
+	/// Register the types used by the view functions of `pallets`.
+	pub fn new(pallets: Vec<PalletViewFunctionsMetadata>) -> Self {
+		let mut registry = Registry::new();
+		let pallets = registry.map_into_portable(pallets);
+		Self { types: registry.into(), pallets }
+	}
+}
+
+/// The suffix of the [`ViewFunctionId`] of the view function `name` taking arguments of the types
+/// `inputs` and returning `output`.
+///
+/// This is the `twox_128` hash of `name` followed by the shape of the encoding of each type, as
+/// described by its [`TypeInfo`]. The paths and docs of the types and the names of their fields
+/// and variants are left out, as they do not affect the encoding.
+pub fn view_function_id_suffix(name: &str, inputs: Vec<MetaType>, output: MetaType) -> [u8; 16] {
+	let mut registry = Registry::new();
+	let inputs = registry.register_types(inputs);
+	let output = registry.register_type(&output);
+	let registry = PortableRegistry::from(registry);
+
+	let mut shape = name.encode();
+	(inputs.len() as u32).encode_to(&mut shape);
+	for ty in inputs.iter().chain(sp_std::iter::once(&output)) {
+		encode_type_shape(&registry, ty.id(), &mut Vec::new(), &mut shape);
+	}
+	sp_io::hashing::twox_128(&shape)
+}
+
+/// Append the shape of the encoding of the type `id` to `out`, prefixed with the codec index of
+/// its [`TypeDef`].
+///
+/// `enclosing` holds the types being appended, a recursive type refers back to itself by its
+/// position in there.
+fn encode_type_shape(
+	registry: &PortableRegistry,
+	id: u32,
+	enclosing: &mut Vec<u32>,
+	out: &mut Vec<u8>,
+) {
+	if let Some(position) = enclosing.iter().position(|enclosing_id| *enclosing_id == id) {
+		out.push(u8::MAX);
+		(position as u32).encode_to(out);
+		return
+	}
+	let ty = match registry.resolve(id) {
+		Some(ty) => ty,
+		None => return,
+	};
+
+	enclosing.push(id);
+	match ty.type_def() {
+		TypeDef::Composite(composite) => {
+			out.push(0);
+			encode_fields_shape(registry, composite.fields(), enclosing, out);
+		},
+		TypeDef::Variant(variant) => {
+			out.push(1);
+			(variant.variants().len() as u32).encode_to(out);
+			for variant in variant.variants() {
+				out.push(variant.index());
+				encode_fields_shape(registry, variant.fields(), enclosing, out);
+			}
+		},
+		TypeDef::Sequence(sequence) => {
+			out.push(2);
+			encode_type_shape(registry, sequence.type_param().id(), enclosing, out);
+		},
+		TypeDef::Array(array) => {
+			out.push(3);
+			array.len().encode_to(out);
+			encode_type_shape(registry, array.type_param().id(), enclosing, out);
+		},
+		TypeDef::Tuple(tuple) => {
+			out.push(4);
+			(tuple.fields().len() as u32).encode_to(out);
+			for field in tuple.fields() {
+				encode_type_shape(registry, field.id(), enclosing, out);
+			}
+		},
+		TypeDef::Primitive(primitive) => {
+			out.push(5);
+			primitive.encode_to(out);
+		},
+		TypeDef::Compact(compact) => {
+			out.push(6);
+			encode_type_shape(registry, compact.type_param().id(), enclosing, out);
+		},
+		TypeDef::BitSequence(bit_sequence) => {
+			out.push(7);
+			encode_type_shape(registry, bit_sequence.bit_store_type().id(), enclosing, out);
+			// the bit order types have no fields, they only differ by their path.
+			registry
+				.resolve(bit_sequence.bit_order_type().id())
+				.map(|ty| ty.path().segments())
+				.encode_to(out);
+		},
+	}
+	enclosing.pop();
+}
+
+/// Append the shape of the encoding of `fields` to `out`, see [`encode_type_shape`].
+fn encode_fields_shape(
+	registry: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	enclosing: &mut Vec<u32>,
+	out: &mut Vec<u8>,
+) {
+	(fields.len() as u32).encode_to(out);
+	for field in fields {
+		encode_type_shape(registry, field.ty().id(), enclosing, out);
+	}
+}
+
+/// Execute the view function `id` of one of the pallets `P` with the encoded arguments `input`,
+/// returning its encoded result.
+pub fn execute_view_function<P: DispatchViewFunction>(
+	id: ViewFunctionId,
+	input: Vec<u8>,
+) -> Result<Vec<u8>, ViewFunctionDispatchError> {
+	let mut output = vec![];
+	P::dispatch_view_function(&id, &mut &input[..], &mut output)?;
+	Ok(output)
+}
+
+/// The encoded [`RuntimeViewFunctionsMetadata`] of the pallets `P`.
+pub fn view_functions_metadata<P: ViewFunctionsMetadata>() -> OpaqueMetadata {
+	OpaqueMetadata::new(RuntimeViewFunctionsMetadata::new(P::view_functions_metadata()).encode())
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to call the view functions of the pallets of the runtime.
+	pub trait RuntimeViewFunction {
+		/// Execute a view function with its encoded arguments, returning its encoded result.
+		fn execute_view_function(
+			id: ViewFunctionId,
+			input: Vec<u8>,
+		) -> Result<Vec<u8>, ViewFunctionDispatchError>;
+
+		/// The encoded [`RuntimeViewFunctionsMetadata`] of the runtime.
+		fn view_functions_metadata() -> OpaqueMetadata;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::meta_type;
+
+	#[derive(TypeInfo)]
+	struct Named {
+		_value: u32,
+	}
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	struct Unnamed(u32);
+
+	#[allow(dead_code)]
+	#[derive(TypeInfo)]
+	struct Tree(Vec<Tree>);
+
+	#[test]
+	fn id_suffix_follows_the_encoding_of_the_types() {
+		let suffix = |name, inputs, output| view_function_id_suffix(name, inputs, output);
+		let base = suffix("f", vec![meta_type::<Named>()], meta_type::<Option<u32>>());
+
+		// names and paths of the types do not affect their encoding.
+		assert_eq!(suffix("f", vec![meta_type::<Unnamed>()], meta_type::<Option<u32>>()), base);
+
+		assert_ne!(suffix("g", vec![meta_type::<Named>()], meta_type::<Option<u32>>()), base);
+		assert_ne!(suffix("f", vec![meta_type::<Named>()], meta_type::<Option<u64>>()), base);
+		assert_ne!(suffix("f", vec![meta_type::<u32>()], meta_type::<Option<u32>>()), base);
+		assert_ne!(suffix("f", vec![], meta_type::<(Named, Option<u32>)>()), base);
+
+		// recursive types are supported.
+		assert_ne!(suffix("f", vec![meta_type::<Tree>()], meta_type::<()>()), base);
+	}
+}
@@ -15,15 +15,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use codec::{Decode, Encode};
 use frame_support::{
 	dispatch::{
 		DispatchClass, DispatchInfo, GetDispatchInfo, Parameter, Pays, UnfilteredDispatchable,
 	},
+	migrations::VersionedMigration,
 	pallet_prelude::ValueQuery,
-	storage::unhashed,
+	storage::{types::MaxBytesExceeded, unhashed},
 	traits::{
 		ConstU32, GetCallName, GetStorageVersion, OnFinalize, OnGenesis, OnInitialize,
-		OnRuntimeUpgrade, PalletError, PalletInfoAccess, StorageVersion,
+		OnRuntimeUpgrade, PalletError, PalletInfoAccess, StorageSize, StorageSizeTrait,
+		StorageVersion,
 	},
 	weights::{RuntimeDbWeight, Weight},
 };
@@ -153,9 +156,52 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::view_functions]
+	impl<T: Config> Pallet<T>
+	where
+		T::AccountId: From<SomeType1> + SomeAssociation1,
+	{
+		/// Query value no args.
+		pub fn get_value() -> Option<u32> {
+			Value::<T>::get()
+		}
+
+		/// Query value with args.
+		pub fn get_value_with_arg(key: u16) -> Option<u32> {
+			Map2::<T>::get(key)
+		}
+	}
+
+	#[pallet::tasks]
+	impl<T: Config> Pallet<T>
+	where
+		T::AccountId: From<SomeType1> + SomeAssociation1,
+	{
+		/// Add the value of `Map2` at `key` to `Value`.
+		#[pallet::task_list(Map2::<T>::iter_keys())]
+		#[pallet::task_condition(|key: &u16| Map2::<T>::contains_key(key))]
+		#[pallet::task_weight(Weight::from_ref_time(*key as u64))]
+		pub fn add_to_value(key: u16) -> DispatchResult {
+			let added = Map2::<T>::take(key).ok_or(Error::<T>::InsufficientProposersBalance)?;
+			Value::<T>::mutate(|value| *value = Some(value.unwrap_or_default() + added));
+			Ok(())
+		}
+
+		/// Clear `Value`, listed once it is set.
+		#[pallet::task_index(3)]
+		#[pallet::task_list(Value::<T>::get().map(|value| (value as u8, value as u16)).into_iter())]
+		#[pallet::task_condition(|_: &u8, _: &u16| Value::<T>::exists())]
+		#[pallet::task_weight(Weight::zero())]
+		pub fn clear_value(_key: u8, _key2: u16) -> DispatchResult {
+			Value::<T>::kill();
+			Ok(())
+		}
+	}
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(crate) trait Store)]
 	#[pallet::storage_version(STORAGE_VERSION)]
+	#[pallet::max_storage_bytes(PalletMaxStorageBytes)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
@@ -363,6 +409,29 @@ pub mod pallet {
 	pub type SomeCountedStorageMap<T> =
 		CountedStorageMap<Hasher = Twox64Concat, Key = u8, Value = u32>;
 
+	#[pallet::type_value]
+	pub fn SizeTrackedMaxBytes() -> Option<u64> {
+		Some(64)
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn size_tracked_storage_map)]
+	pub type SomeSizeTrackedStorageMap<T> = SizeTrackedStorageMap<
+		Hasher = Twox64Concat,
+		Key = u8,
+		Value = u32,
+		MaxBytes = SizeTrackedMaxBytes,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn size_tracked_storage_value)]
+	pub type SomeSizeTrackedStorageValue<T> = SizeTrackedStorageValue<_, u32>;
+
+	#[pallet::type_value]
+	pub fn PalletMaxStorageBytes() -> Option<u64> {
+		Some(80)
+	}
+
 	#[pallet::storage]
 	#[pallet::unbounded]
 	pub type Unbounded<T> = StorageValue<Value = Vec<u8>>;
@@ -397,6 +466,12 @@ pub mod pallet {
 	)]
 	pub struct Origin<T>(PhantomData<T>);
 
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		Staking,
+		Voting,
+	}
+
 	#[pallet::validate_unsigned]
 	impl<T: Config> ValidateUnsigned for Pallet<T>
 	where
@@ -546,6 +621,11 @@ pub mod pallet2 {
 	{
 		fn build(&self) {}
 	}
+
+	#[pallet::composite_enum]
+	pub enum FreezeReason {
+		Inactive,
+	}
 }
 
 /// Test that the supertrait check works when we pass some parameter to the `frame_system::Config`.
@@ -598,6 +678,7 @@ impl frame_system::Config for Runtime {
 	type Index = u64;
 	type BlockNumber = u32;
 	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
 	type Hash = sp_runtime::testing::H256;
 	type Hashing = sp_runtime::traits::BlakeTwo256;
 	type AccountId = u64;
@@ -1072,16 +1153,200 @@ fn storage_expand() {
 		let k = [twox_128(b"Example"), twox_128(b"CounterForRenamedCountedMap")].concat();
 		assert_eq!(unhashed::get::<u32>(&k), Some(1u32));
 
+		assert_eq!(pallet::SomeSizeTrackedStorageMap::<Runtime>::try_insert(1, 2), Ok(()));
+		let mut k = [twox_128(b"Example"), twox_128(b"SomeSizeTrackedStorageMap")].concat();
+		k.extend(1u8.using_encoded(twox_64_concat));
+		assert_eq!(unhashed::get::<u32>(&k), Some(2u32));
+		let k = [twox_128(b"Example"), twox_128(b"ByteSizeForSomeSizeTrackedStorageMap")].concat();
+		assert_eq!(unhashed::get::<u64>(&k), Some(32 + 9 + 4));
+		// The pallet would exceed its `max_storage_bytes`.
+		assert_eq!(
+			pallet::SomeSizeTrackedStorageValue::<Runtime>::try_put(5),
+			Err(MaxBytesExceeded)
+		);
+		assert_eq!(
+			Example::storage_size(),
+			vec![
+				StorageSize {
+					pallet_name: b"Example".to_vec(),
+					storage_name: b"SomeSizeTrackedStorageMap".to_vec(),
+					bytes: 32 + 9 + 4,
+					max_bytes: Some(64),
+				},
+				StorageSize {
+					pallet_name: b"Example".to_vec(),
+					storage_name: b"SomeSizeTrackedStorageValue".to_vec(),
+					bytes: 0,
+					max_bytes: None,
+				},
+				StorageSize {
+					pallet_name: b"Example".to_vec(),
+					storage_name: vec![],
+					bytes: 32 + 9 + 4,
+					max_bytes: Some(80),
+				},
+			],
+		);
+		assert_eq!(
+			pallet::SomeSizeTrackedStorageMap::<Runtime>::try_insert(2, 3),
+			Err(MaxBytesExceeded),
+		);
+
+		pallet::SomeSizeTrackedStorageMap::<Runtime>::remove(1);
+		assert_eq!(pallet::SomeSizeTrackedStorageValue::<Runtime>::try_put(5), Ok(()));
+		assert_eq!(Example::size_tracked_storage_value(), Some(5));
+		let k = [twox_128(b"Example"), twox_128(b"StorageByteSize")].concat();
+		assert_eq!(unhashed::get::<u64>(&k), Some(32 + 4));
+
 		pallet::Unbounded::<Runtime>::put(vec![1, 2]);
 		let k = [twox_128(b"Example"), twox_128(b"Unbounded")].concat();
 		assert_eq!(unhashed::get::<Vec<u8>>(&k), Some(vec![1, 2]));
 	})
 }
 
+#[test]
+fn tasks_expand() {
+	use frame_support::traits::Task;
+
+	TestExternalities::default().execute_with(|| {
+		pallet::Map2::<Runtime>::insert(1, 3);
+		pallet::Map2::<Runtime>::insert(2, 4);
+
+		let tasks = RuntimeTask::iter().collect::<Vec<_>>();
+		assert_eq!(tasks.len(), 2);
+		assert!(tasks.contains(&RuntimeTask::Example(pallet::Task::add_to_value { key: 1 })));
+
+		let task = pallet::Task::<Runtime>::add_to_value { key: 2 };
+		assert_eq!(task.encode(), (0u8, 2u16).encode());
+		assert_eq!(task.task_index(), 0);
+		assert_eq!(task.weight(), Weight::from_ref_time(2));
+		assert!(task.is_valid());
+		assert_eq!(task.run(), Ok(()));
+		assert!(!task.is_valid());
+		assert_eq!(pallet::Value::<Runtime>::get(), Some(4));
+
+		let task = pallet::Task::<Runtime>::clear_value { _key: 4, _key2: 4 };
+		assert_eq!(task.task_index(), 3);
+		assert_eq!(RuntimeTask::iter().last(), Some(RuntimeTask::Example(task.clone())));
+		let encoded = RuntimeTask::Example(task.clone()).encode();
+		assert_eq!(encoded[..2], [1u8, 3]);
+		assert_eq!(RuntimeTask::decode(&mut &encoded[..]), Ok(RuntimeTask::Example(task.clone())));
+		assert_eq!(task.run(), Ok(()));
+		assert!(!task.is_valid());
+	});
+}
+
+#[test]
+fn composite_enum_expand() {
+	use codec::MaxEncodedLen;
+	use frame_support::traits::VariantCount;
+
+	let reason: RuntimeHoldReason = pallet::HoldReason::Voting.into();
+	assert_eq!(reason, RuntimeHoldReason::Example(pallet::HoldReason::Voting));
+	assert_eq!(reason.encode(), vec![1, 1]);
+	assert_eq!(
+		RuntimeHoldReason::decode(&mut &[1u8, 0][..]).unwrap(),
+		RuntimeHoldReason::Example(pallet::HoldReason::Staking)
+	);
+	assert!(RuntimeHoldReason::Example(pallet::HoldReason::Staking) < reason);
+	assert_eq!(pallet::HoldReason::VARIANT_COUNT, 2);
+	assert_eq!(RuntimeHoldReason::VARIANT_COUNT, 2);
+	assert_eq!(RuntimeHoldReason::max_encoded_len(), 2);
+
+	let reason: RuntimeFreezeReason = pallet2::FreezeReason::Inactive.into();
+	assert_eq!(reason.encode(), vec![2, 0]);
+	assert_eq!(RuntimeFreezeReason::VARIANT_COUNT, 1);
+}
+
+#[test]
+fn view_functions_expand() {
+	use frame_support::view_functions::{
+		execute_view_function, view_function_id_suffix, ViewFunction, ViewFunctionDispatchError,
+		ViewFunctionId,
+	};
+	use scale_info::meta_type;
+
+	TestExternalities::default().execute_with(|| {
+		pallet::Value::<Runtime>::put(42);
+		pallet::Map2::<Runtime>::insert(1, 3);
+
+		assert_eq!(pallet::GetValue::<Runtime>::new().invoke(), Some(42));
+
+		let id = pallet::GetValueWithArg::<Runtime>::id();
+		assert_eq!(id.prefix, twox_128(b"Example"));
+		assert_eq!(
+			id.suffix,
+			view_function_id_suffix(
+				"get_value_with_arg",
+				vec![meta_type::<u16>()],
+				meta_type::<Option<u32>>(),
+			),
+		);
+		assert_ne!(id, pallet::GetValue::<Runtime>::id());
+		assert_eq!(
+			execute_view_function::<AllPalletsWithSystem>(id, 1u16.encode()),
+			Ok(Some(3u32).encode()),
+		);
+		assert_eq!(
+			execute_view_function::<AllPalletsWithSystem>(
+				id,
+				pallet::GetValueWithArg::<Runtime>::new(2).encode()
+			),
+			Ok(None::<u32>.encode()),
+		);
+		assert_eq!(
+			execute_view_function::<AllPalletsWithSystem>(id, vec![1]),
+			Err(ViewFunctionDispatchError::Codec),
+		);
+
+		let unknown = ViewFunctionId { prefix: twox_128(b"Example2"), suffix: id.suffix };
+		assert_eq!(
+			execute_view_function::<AllPalletsWithSystem>(unknown, 1u16.encode()),
+			Err(ViewFunctionDispatchError::NotFound(unknown)),
+		);
+	});
+}
+
+#[test]
+fn view_functions_metadata() {
+	use frame_support::view_functions::{
+		view_functions_metadata, PalletViewFunctionsMetadata, RuntimeViewFunctionsMetadata,
+		ViewFunction, ViewFunctionArgMetadata, ViewFunctionMetadata, ViewFunctionsMetadata,
+	};
+
+	let expected = vec![PalletViewFunctionsMetadata {
+		name: "Example",
+		view_functions: vec![
+			ViewFunctionMetadata {
+				name: "get_value",
+				id: pallet::GetValue::<Runtime>::id(),
+				inputs: vec![],
+				output: meta_type::<Option<u32>>(),
+				docs: maybe_docs(vec![" Query value no args."]),
+			},
+			ViewFunctionMetadata {
+				name: "get_value_with_arg",
+				id: pallet::GetValueWithArg::<Runtime>::id(),
+				inputs: vec![ViewFunctionArgMetadata { name: "key", ty: meta_type::<u16>() }],
+				output: meta_type::<Option<u32>>(),
+				docs: maybe_docs(vec![" Query value with args."]),
+			},
+		],
+	}];
+	assert_eq!(AllPalletsWithSystem::view_functions_metadata(), expected);
+
+	let metadata = view_functions_metadata::<AllPalletsWithSystem>();
+	let metadata = RuntimeViewFunctionsMetadata::decode(&mut &metadata[..]).unwrap();
+	assert_eq!(metadata, RuntimeViewFunctionsMetadata::new(expected));
+	assert_eq!(metadata.pallets[0].view_functions[1].inputs[0].name, "key");
+}
+
 #[test]
 fn pallet_hooks_expand() {
 	TestExternalities::default().execute_with(|| {
 		frame_system::Pallet::<Runtime>::set_block_number(1);
+		// As set at genesis, checked by the try-runtime post-upgrade hook.
+		pallet::STORAGE_VERSION.put::<Example>();
 
 		assert_eq!(AllPalletsWithoutSystem::on_initialize(1), Weight::from_ref_time(10));
 		AllPalletsWithoutSystem::on_finalize(1);
@@ -1119,6 +1384,8 @@ fn pallet_hooks_expand() {
 fn all_pallets_type_reversed_order_is_correct() {
 	TestExternalities::default().execute_with(|| {
 		frame_system::Pallet::<Runtime>::set_block_number(1);
+		// As set at genesis, checked by the try-runtime post-upgrade hook.
+		pallet::STORAGE_VERSION.put::<Example>();
 
 		#[allow(deprecated)]
 		{
@@ -1219,17 +1486,84 @@ fn migrate_from_pallet_version_to_storage_version() {
 	});
 }
 
+struct SomeUnversionedMigration;
+impl OnRuntimeUpgrade for SomeUnversionedMigration {
+	fn on_runtime_upgrade() -> Weight {
+		unhashed::put(b"migrated", &true);
+		Weight::from_ref_time(100)
+	}
+}
+
 #[test]
-fn metadata() {
-	use frame_support::metadata::*;
+fn versioned_migration_works() {
+	frame_support::parameter_types! {
+		const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1, write: 10 };
+	}
+	type Migration = VersionedMigration<9, 10, SomeUnversionedMigration, Example, DbWeight>;
 
-	fn maybe_docs(doc: Vec<&'static str>) -> Vec<&'static str> {
-		if cfg!(feature = "no-metadata-docs") {
-			vec![]
-		} else {
-			doc
-		}
+	TestExternalities::default().execute_with(|| {
+		// The migration is skipped for other on-chain versions.
+		assert_eq!(Migration::on_runtime_upgrade(), Weight::from_ref_time(1));
+		assert_eq!(Example::on_chain_storage_version(), StorageVersion::new(0));
+		assert_eq!(unhashed::get::<bool>(b"migrated"), None);
+
+		StorageVersion::new(9).put::<Example>();
+		assert_eq!(Migration::on_runtime_upgrade(), Weight::from_ref_time(100 + 1 + 10));
+		assert_eq!(Example::on_chain_storage_version(), StorageVersion::new(10));
+		assert_eq!(unhashed::get::<bool>(b"migrated"), Some(true));
+
+		// Executing it again is a noop.
+		unhashed::kill(b"migrated");
+		assert_eq!(Migration::on_runtime_upgrade(), Weight::from_ref_time(1));
+		assert_eq!(unhashed::get::<bool>(b"migrated"), None);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn versioned_migration_checks_in_code_version() {
+	type Migration = VersionedMigration<10, 11, SomeUnversionedMigration, Example, ()>;
+
+	TestExternalities::default().execute_with(|| {
+		StorageVersion::new(10).put::<Example>();
+		let state = Migration::pre_upgrade().unwrap();
+		Migration::on_runtime_upgrade();
+		assert_eq!(
+			Migration::post_upgrade(state),
+			Err("`VersionedMigration` migrates beyond the in-code storage version"),
+		);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn post_upgrade_checks_storage_version() {
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(
+			Example::post_upgrade(Vec::new()),
+			Err("On chain and current storage version do not match. Missing runtime upgrade?"),
+		);
+		pallet::STORAGE_VERSION.put::<Example>();
+		assert_eq!(Example::post_upgrade(Vec::new()), Ok(()));
+
+		// Pallets without a storage version must not have one set on chain.
+		assert_eq!(Example2::post_upgrade(Vec::new()), Ok(()));
+		StorageVersion::new(1).put::<Example2>();
+		assert!(Example2::post_upgrade(Vec::new()).is_err());
+	});
+}
+
+fn maybe_docs(doc: Vec<&'static str>) -> Vec<&'static str> {
+	if cfg!(feature = "no-metadata-docs") {
+		vec![]
+	} else {
+		doc
 	}
+}
+
+#[test]
+fn metadata() {
+	use frame_support::metadata::*;
 
 	let pallets = vec![
 		PalletMetadata {
@@ -1441,6 +1775,42 @@ fn metadata() {
 						default: vec![0, 0, 0, 0],
 						docs: maybe_docs(vec!["Counter for the related counted storage map"]),
 					},
+					StorageEntryMetadata {
+						name: "SomeSizeTrackedStorageMap",
+						modifier: StorageEntryModifier::Optional,
+						ty: StorageEntryType::Map {
+							hashers: vec![StorageHasher::Twox64Concat],
+							key: meta_type::<u8>(),
+							value: meta_type::<u32>(),
+						},
+						default: vec![0],
+						docs: vec![],
+					},
+					StorageEntryMetadata {
+						name: "ByteSizeForSomeSizeTrackedStorageMap",
+						modifier: StorageEntryModifier::Default,
+						ty: StorageEntryType::Plain(meta_type::<u64>()),
+						default: vec![0; 8],
+						docs: maybe_docs(vec![
+							"Bytes occupied by the related size tracked storage",
+						]),
+					},
+					StorageEntryMetadata {
+						name: "SomeSizeTrackedStorageValue",
+						modifier: StorageEntryModifier::Optional,
+						ty: StorageEntryType::Plain(meta_type::<u32>()),
+						default: vec![0],
+						docs: vec![],
+					},
+					StorageEntryMetadata {
+						name: "ByteSizeForSomeSizeTrackedStorageValue",
+						modifier: StorageEntryModifier::Default,
+						ty: StorageEntryType::Plain(meta_type::<u64>()),
+						default: vec![0; 8],
+						docs: maybe_docs(vec![
+							"Bytes occupied by the related size tracked storage",
+						]),
+					},
 					StorageEntryMetadata {
 						name: "Unbounded",
 						modifier: StorageEntryModifier::Optional,
@@ -1448,6 +1818,15 @@ fn metadata() {
 						default: vec![0],
 						docs: vec![],
 					},
+					StorageEntryMetadata {
+						name: "StorageByteSize",
+						modifier: StorageEntryModifier::Default,
+						ty: StorageEntryType::Plain(meta_type::<u64>()),
+						default: vec![0; 8],
+						docs: maybe_docs(vec![
+							"Bytes occupied by the size tracked storages of the pallet",
+						]),
+					},
 				],
 			}),
 			calls: Some(meta_type::<pallet::Call<Runtime>>().into()),
@@ -1748,6 +2127,34 @@ fn test_storage_info() {
 				max_values: Some(1),
 				max_size: Some(4),
 			},
+			StorageInfo {
+				pallet_name: b"Example".to_vec(),
+				storage_name: b"SomeSizeTrackedStorageMap".to_vec(),
+				prefix: prefix(b"Example", b"SomeSizeTrackedStorageMap").to_vec(),
+				max_values: None,
+				max_size: Some(8 + 1 + 4),
+			},
+			StorageInfo {
+				pallet_name: b"Example".to_vec(),
+				storage_name: b"ByteSizeForSomeSizeTrackedStorageMap".to_vec(),
+				prefix: prefix(b"Example", b"ByteSizeForSomeSizeTrackedStorageMap").to_vec(),
+				max_values: Some(1),
+				max_size: Some(8),
+			},
+			StorageInfo {
+				pallet_name: b"Example".to_vec(),
+				storage_name: b"SomeSizeTrackedStorageValue".to_vec(),
+				prefix: prefix(b"Example", b"SomeSizeTrackedStorageValue").to_vec(),
+				max_values: Some(1),
+				max_size: Some(4),
+			},
+			StorageInfo {
+				pallet_name: b"Example".to_vec(),
+				storage_name: b"ByteSizeForSomeSizeTrackedStorageValue".to_vec(),
+				prefix: prefix(b"Example", b"ByteSizeForSomeSizeTrackedStorageValue").to_vec(),
+				max_values: Some(1),
+				max_size: Some(8),
+			},
 			StorageInfo {
 				pallet_name: b"Example".to_vec(),
 				storage_name: b"Unbounded".to_vec(),
@@ -1755,6 +2162,13 @@ fn test_storage_info() {
 				max_values: Some(1),
 				max_size: None,
 			},
+			StorageInfo {
+				pallet_name: b"Example".to_vec(),
+				storage_name: b"StorageByteSize".to_vec(),
+				prefix: prefix(b"Example", b"StorageByteSize").to_vec(),
+				max_values: Some(1),
+				max_size: Some(8),
+			},
 		],
 	);
 
@@ -121,6 +121,13 @@ pub mod pallet {
 		Something(u32),
 	}
 
+	#[pallet::view_functions]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		pub fn get_value() -> Option<u32> {
+			Value::<T, I>::get()
+		}
+	}
+
 	#[pallet::storage]
 	pub type Value<T, I = ()> = StorageValue<_, u32>;
 
@@ -290,6 +297,7 @@ impl frame_system::Config for Runtime {
 	type Index = u64;
 	type BlockNumber = u32;
 	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
 	type Hash = sp_runtime::testing::H256;
 	type Hashing = sp_runtime::traits::BlakeTwo256;
 	type AccountId = u64;
@@ -615,6 +623,32 @@ fn storage_expand() {
 	});
 }
 
+#[test]
+fn view_functions_expand() {
+	use codec::Encode;
+	use frame_support::view_functions::{execute_view_function, ViewFunction};
+
+	TestExternalities::default().execute_with(|| {
+		pallet::Value::<Runtime>::put(1);
+		pallet::Value::<Runtime, pallet::Instance1>::put(2);
+
+		let id = pallet::GetValue::<Runtime>::id();
+		let instance_id = pallet::GetValue::<Runtime, pallet::Instance1>::id();
+		assert_eq!(id.prefix, twox_128(b"Example"));
+		assert_eq!(instance_id.prefix, twox_128(b"Instance1Example"));
+		assert_eq!(id.suffix, instance_id.suffix);
+
+		assert_eq!(
+			execute_view_function::<AllPalletsWithSystem>(id, vec![]),
+			Ok(Some(1u32).encode()),
+		);
+		assert_eq!(
+			execute_view_function::<AllPalletsWithSystem>(instance_id, vec![]),
+			Ok(Some(2u32).encode()),
+		);
+	});
+}
+
 #[test]
 fn pallet_metadata_expands() {
 	use frame_support::traits::{CrateVersion, PalletInfoData, PalletsInfoAccess};
@@ -0,0 +1,24 @@
+#[frame_support::pallet]
+mod pallet {
+	use frame_support::pallet_prelude::{DispatchResult, Hooks};
+	use frame_system::pallet_prelude::BlockNumberFor;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(core::marker::PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::tasks]
+	impl<T: Config> Pallet<T> {
+		#[pallet::task_list(0..10u32)]
+		#[pallet::task_condition(|_| true)]
+		pub fn foo(i: u32) -> DispatchResult {}
+	}
+}
+
+fn main() {
+}
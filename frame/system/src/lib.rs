@@ -91,7 +91,7 @@ use frame_support::{
 	storage,
 	traits::{
 		ConstU32, Contains, EnsureOrigin, Get, HandleLifetime, OnKilledAccount, OnNewAccount,
-		OriginTrait, PalletInfo, SortedMembers, StoredMap, TypedGet,
+		OriginTrait, PalletInfo, SortedMembers, StoredMap, Task, TypedGet,
 	},
 	Parameter,
 };
@@ -226,6 +226,10 @@ pub mod pallet {
 			+ Debug
 			+ From<Call<Self>>;
 
+		/// The aggregated `RuntimeTask` type, run with [`Call::do_task`] or when there is spare
+		/// weight at the end of a block.
+		type RuntimeTask: Task;
+
 		/// Account index (aka nonce) type. This stores the number of previous transactions
 		/// associated with a sender account.
 		type Index: Parameter
@@ -486,6 +490,18 @@ pub mod pallet {
 			Self::deposit_event(Event::Remarked { sender: who, hash });
 			Ok(().into())
 		}
+
+		/// Run a valid task of the runtime.
+		///
+		/// This is an unsigned transaction anyone can submit, see [`Task`].
+		#[pallet::weight(task.weight())]
+		pub fn do_task(origin: OriginFor<T>, task: T::RuntimeTask) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			ensure!(task.is_valid(), Error::<T>::InvalidTask);
+			task.run()?;
+			Self::deposit_event(Event::TaskCompleted { task });
+			Ok(().into())
+		}
 	}
 
 	/// Event for the System pallet.
@@ -503,6 +519,10 @@ pub mod pallet {
 		KilledAccount { account: T::AccountId },
 		/// On on-chain remark happened.
 		Remarked { sender: T::AccountId, hash: T::Hash },
+		/// A task was run.
+		TaskCompleted { task: T::RuntimeTask },
+		/// A task run at the end of a block failed, its changes were reverted.
+		TaskFailed { task: T::RuntimeTask, err: DispatchError },
 	}
 
 	/// Error for the System pallet
@@ -524,6 +544,29 @@ pub mod pallet {
 		NonZeroRefCount,
 		/// The origin filter prevent the call to be dispatched.
 		CallFiltered,
+		/// The task is not valid, e.g. it was already run.
+		InvalidTask,
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::do_task { task } = call {
+				if !task.is_valid() {
+					return InvalidTransaction::Call.into()
+				}
+				ValidTransaction::with_tag_prefix("SystemTask")
+					.priority(TransactionPriority::min_value())
+					// The same task can only be included once.
+					.and_provides(task.encode())
+					.propagate(true)
+					.build()
+			} else {
+				InvalidTransaction::Call.into()
+			}
+		}
 	}
 
 	/// Exposed trait-generic origin type.
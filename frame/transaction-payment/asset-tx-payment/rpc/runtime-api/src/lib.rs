@@ -0,0 +1,42 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the asset transaction payment pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_runtime::traits::MaybeDisplay;
+
+pub use pallet_transaction_payment::RuntimeDispatchInfo;
+
+sp_api::decl_runtime_apis! {
+	pub trait AssetTxPaymentApi<AssetId, AssetBalance> where
+		AssetId: Codec,
+		AssetBalance: Codec + MaybeDisplay,
+	{
+		/// Query information of the dispatch class, weight, and fee of a given extrinsic, with the
+		/// fee in the asset `asset_id`.
+		///
+		/// Returns `None` if the fee can't be paid in the asset.
+		fn query_info_in_asset(
+			uxt: Block::Extrinsic,
+			len: u32,
+			asset_id: AssetId,
+		) -> Option<RuntimeDispatchInfo<AssetBalance>>;
+	}
+}
@@ -0,0 +1,140 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RPC interface for the asset transaction payment pallet.
+
+use std::sync::Arc;
+
+use codec::{Codec, Decode};
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_asset_tx_payment_rpc_runtime_api::RuntimeDispatchInfo;
+use serde::de::DeserializeOwned;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, MaybeDisplay},
+};
+
+pub use pallet_asset_tx_payment_rpc_runtime_api::AssetTxPaymentApi as AssetTxPaymentRuntimeApi;
+
+#[rpc(client, server)]
+pub trait AssetTxPaymentApi<BlockHash, AssetId, ResponseType> {
+	#[method(name = "payment_queryInfoInAsset")]
+	fn query_info_in_asset(
+		&self,
+		encoded_xt: Bytes,
+		asset_id: AssetId,
+		at: Option<BlockHash>,
+	) -> RpcResult<ResponseType>;
+}
+
+/// Provides RPC methods to query a dispatchable's class, weight and fee in an asset.
+pub struct AssetTxPayment<C, P> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> AssetTxPayment<C, P> {
+	/// Creates a new instance of the AssetTxPayment Rpc helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The transaction was not decodable.
+	DecodeError,
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The fee can't be paid in the asset.
+	AssetNotSupported,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::DecodeError => 2,
+			Error::AssetNotSupported => 3,
+		}
+	}
+}
+
+impl<C, Block, AssetId, AssetBalance>
+	AssetTxPaymentApiServer<
+		<Block as BlockT>::Hash,
+		AssetId,
+		RuntimeDispatchInfo<AssetBalance, sp_weights::OldWeight>,
+	> for AssetTxPayment<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AssetTxPaymentRuntimeApi<Block, AssetId, AssetBalance>,
+	AssetId: Codec + DeserializeOwned + Send + Sync + 'static,
+	AssetBalance: Codec + MaybeDisplay + Copy + Send + Sync + 'static,
+{
+	fn query_info_in_asset(
+		&self,
+		encoded_xt: Bytes,
+		asset_id: AssetId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<RuntimeDispatchInfo<AssetBalance, sp_weights::OldWeight>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let encoded_len = encoded_xt.len() as u32;
+
+		let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::DecodeError.into(),
+				"Unable to query dispatch info.",
+				Some(format!("{:?}", e)),
+			))
+		})?;
+
+		let res = api
+			.query_info_in_asset(&at, uxt, encoded_len, asset_id)
+			.map_err(|e| {
+				CallError::Custom(ErrorObject::owned(
+					Error::RuntimeError.into(),
+					"Unable to query dispatch info.",
+					Some(e.to_string()),
+				))
+			})?
+			.ok_or_else(|| {
+				CallError::Custom(ErrorObject::owned(
+					Error::AssetNotSupported.into(),
+					"The fee can't be paid in this asset.",
+					None::<String>,
+				))
+			})?;
+
+		Ok(RuntimeDispatchInfo {
+			weight: sp_weights::OldWeight(res.weight.ref_time()),
+			class: res.class,
+			partial_fee: res.partial_fee,
+		})
+	}
+}
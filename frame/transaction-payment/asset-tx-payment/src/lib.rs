@@ -27,6 +27,13 @@
 //! amount by converting the fee calculated by [`pallet-transaction-payment`] into the desired
 //! asset.
 //!
+//! The conversion is a plugin of [`FungiblesAdapter`]: any
+//! [`BalanceConversion`](frame_support::traits::tokens::BalanceConversion) can price the assets,
+//! such as one reading the liquidity pools of an on-chain exchange to let any asset with a pool pay
+//! fees. Unused fees are refunded in the asset they were paid in, and
+//! [`Pallet::query_info_in_asset`] quotes the fee of an extrinsic in an asset for the
+//! `AssetTxPaymentApi` runtime API.
+//!
 //! ## Integration
 
 //! This pallet wraps FRAME's transaction payment pallet and functions as a replacement. This means
@@ -39,7 +46,7 @@ use sp_std::prelude::*;
 
 use codec::{Decode, Encode};
 use frame_support::{
-	dispatch::{DispatchInfo, DispatchResult, PostDispatchInfo},
+	dispatch::{DispatchInfo, DispatchResult, GetDispatchInfo, PostDispatchInfo},
 	traits::{
 		tokens::{
 			fungibles::{Balanced, CreditOf, Inspect},
@@ -49,7 +56,7 @@ use frame_support::{
 	},
 	DefaultNoBound,
 };
-use pallet_transaction_payment::OnChargeTransaction;
+use pallet_transaction_payment::{OnChargeTransaction, RuntimeDispatchInfo};
 use scale_info::TypeInfo;
 use sp_runtime::{
 	traits::{DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SignedExtension, Zero},
@@ -59,6 +66,8 @@ use sp_runtime::{
 	FixedPointOperand,
 };
 
+#[cfg(test)]
+mod pool_tests;
 #[cfg(test)]
 mod tests;
 
@@ -139,6 +148,30 @@ pub mod pallet {
 	}
 }
 
+impl<T: Config> Pallet<T>
+where
+	BalanceOf<T>: FixedPointOperand + IsType<ChargeAssetBalanceOf<T>>,
+{
+	/// Query the data that we know about the fee of a given extrinsic, like
+	/// [`pallet_transaction_payment::Pallet::query_info`], with the fee in the asset `asset_id`.
+	///
+	/// Returns `None` if the fee can't be paid in the asset.
+	pub fn query_info_in_asset<Extrinsic: sp_runtime::traits::Extrinsic + GetDispatchInfo>(
+		unchecked_extrinsic: Extrinsic,
+		len: u32,
+		asset_id: ChargeAssetIdOf<T>,
+	) -> Option<RuntimeDispatchInfo<AssetBalanceOf<T>>>
+	where
+		T::RuntimeCall: Dispatchable<Info = DispatchInfo>,
+	{
+		let RuntimeDispatchInfo { weight, class, partial_fee } =
+			pallet_transaction_payment::Pallet::<T>::query_info(unchecked_extrinsic, len);
+		let partial_fee =
+			T::OnChargeAssetTransaction::convert_fee(asset_id, partial_fee.into()).ok()?;
+		Some(RuntimeDispatchInfo { weight, class, partial_fee })
+	}
+}
+
 /// Require the transactor pay for themselves and maybe include a tip to gain additional priority
 /// in the queue. Allows paying via both `Currency` as well as `fungibles::Balanced`.
 ///
@@ -61,6 +61,15 @@ pub trait OnChargeAssetTransaction<T: Config> {
 		tip: Self::Balance,
 	) -> Result<Self::LiquidityInfo, TransactionValidityError>;
 
+	/// Convert `fee`, in the native currency, into the amount of the asset `asset_id` that
+	/// [`Self::withdraw_fee`] would withdraw for it.
+	///
+	/// Used to quote fees in assets, see [`Pallet::query_info_in_asset`].
+	fn convert_fee(
+		asset_id: Self::AssetId,
+		fee: Self::Balance,
+	) -> Result<AssetBalanceOf<T>, TransactionValidityError>;
+
 	/// After the transaction was executed the actual fee can be calculated.
 	/// This function should refund any overpaid fees and optionally deposit
 	/// the corrected amount.
@@ -93,9 +102,39 @@ impl<A, B: Balanced<A>> HandleCredit<A, B> for () {
 /// Implements the asset transaction for a balance to asset converter (implementing
 /// [`BalanceConversion`]) and a credit handler (implementing [`HandleCredit`]).
 ///
+/// The converter is the source of the prices of the assets in the native currency, it decides
+/// which assets can pay fees. For example `pallet_assets::BalanceToAssetBalance` prices the
+/// sufficient assets with the ratio of their `min_balance` to the existential deposit, while a
+/// converter quoting the liquidity pools of an exchange pallet prices any asset with a pool.
+///
 /// The credit handler is given the complete fee in terms of the asset used for the transaction.
+/// Unused fees are refunded in the same asset.
 pub struct FungiblesAdapter<CON, HC>(PhantomData<(CON, HC)>);
 
+/// A [`BalanceConversion`] pricing the assets with `First`, and with `Second` the assets `First`
+/// can't price.
+///
+/// For example, the liquidity pools of an exchange pallet with a fallback to
+/// `pallet_assets::BalanceToAssetBalance` let the assets with a pool as well as the sufficient
+/// assets pay fees.
+pub struct ConversionWithFallback<First, Second>(PhantomData<(First, Second)>);
+
+impl<Balance, AssetId, AssetBalance, First, Second>
+	BalanceConversion<Balance, AssetId, AssetBalance> for ConversionWithFallback<First, Second>
+where
+	Balance: Copy,
+	AssetId: Copy,
+	First: BalanceConversion<Balance, AssetId, AssetBalance>,
+	Second: BalanceConversion<Balance, AssetId, AssetBalance>,
+{
+	type Error = Second::Error;
+
+	fn to_asset_balance(balance: Balance, asset_id: AssetId) -> Result<AssetBalance, Self::Error> {
+		First::to_asset_balance(balance, asset_id)
+			.or_else(|_| Second::to_asset_balance(balance, asset_id))
+	}
+}
+
 /// Default implementation for a runtime instantiating this pallet, a balance to asset converter and
 /// a credit handler.
 impl<T, CON, HC> OnChargeAssetTransaction<T> for FungiblesAdapter<CON, HC>
@@ -120,13 +159,7 @@ where
 		fee: Self::Balance,
 		_tip: Self::Balance,
 	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
-		// We don't know the precision of the underlying asset. Because the converted fee could be
-		// less than one (e.g. 0.5) but gets rounded down by integer division we introduce a minimum
-		// fee.
-		let min_converted_fee = if fee.is_zero() { Zero::zero() } else { One::one() };
-		let converted_fee = CON::to_asset_balance(fee, asset_id)
-			.map_err(|_| TransactionValidityError::from(InvalidTransaction::Payment))?
-			.max(min_converted_fee);
+		let converted_fee = <Self as OnChargeAssetTransaction<T>>::convert_fee(asset_id, fee)?;
 		let can_withdraw =
 			<T::Fungibles as Inspect<T::AccountId>>::can_withdraw(asset_id, who, converted_fee);
 		if !matches!(can_withdraw, WithdrawConsequence::Success) {
@@ -136,6 +169,20 @@ where
 			.map_err(|_| TransactionValidityError::from(InvalidTransaction::Payment))
 	}
 
+	/// Convert the fee with `CON`.
+	fn convert_fee(
+		asset_id: Self::AssetId,
+		fee: Self::Balance,
+	) -> Result<AssetBalanceOf<T>, TransactionValidityError> {
+		// We don't know the precision of the underlying asset. Because the converted fee could be
+		// less than one (e.g. 0.5) but gets rounded down by integer division we introduce a minimum
+		// fee.
+		let min_converted_fee = if fee.is_zero() { Zero::zero() } else { One::one() };
+		Ok(CON::to_asset_balance(fee, asset_id)
+			.map_err(|_| TransactionValidityError::from(InvalidTransaction::Payment))?
+			.max(min_converted_fee))
+	}
+
 	/// Hand the fee and the tip over to the `[HandleCredit]` implementation.
 	/// Since the predicted fee might have been too high, parts of the fee may be refunded.
 	///
@@ -148,11 +195,9 @@ where
 		_tip: Self::Balance,
 		paid: Self::LiquidityInfo,
 	) -> Result<(), TransactionValidityError> {
-		let min_converted_fee = if corrected_fee.is_zero() { Zero::zero() } else { One::one() };
 		// Convert the corrected fee into the asset used for payment.
-		let converted_fee = CON::to_asset_balance(corrected_fee, paid.asset())
-			.map_err(|_| -> TransactionValidityError { InvalidTransaction::Payment.into() })?
-			.max(min_converted_fee);
+		let converted_fee =
+			<Self as OnChargeAssetTransaction<T>>::convert_fee(paid.asset(), corrected_fee)?;
 		// Calculate how much refund we should return.
 		let (final_fee, refund) = paid.split(converted_fee);
 		// Refund to the account that paid the fees. If this fails, the account might have dropped
@@ -0,0 +1,353 @@
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests of fees paid in assets priced by the pools of `pallet-asset-conversion`, falling back to
+//! the `min_balance` of the sufficient assets.
+
+use super::*;
+use crate as pallet_asset_tx_payment;
+
+use frame_support::{
+	assert_ok,
+	dispatch::{DispatchClass, DispatchInfo, PostDispatchInfo},
+	parameter_types,
+	traits::{fungibles::Mutate, AsEnsureOriginWithArg, ConstU32, ConstU64, ConstU8, Get},
+	weights::{IdentityFee, Weight},
+	PalletId,
+};
+use frame_system::{EnsureNever, EnsureRoot, EnsureSigned};
+use pallet_asset_conversion::NativeOrAssetId;
+use pallet_balances::Call as BalancesCall;
+use pallet_transaction_payment::CurrencyAdapter;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+	Permill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+type Balance = u64;
+type AccountId = u64;
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>},
+		Assets: pallet_assets::<Instance1>::{Pallet, Call, Storage, Event<T>},
+		PoolAssets: pallet_assets::<Instance2>::{Pallet, Call, Storage, Event<T>},
+		AssetConversion: pallet_asset_conversion::{Pallet, Call, Storage, Event<T>},
+		AssetTxPayment: pallet_asset_tx_payment::{Pallet, Event<T>},
+	}
+);
+
+const CALL: &<Runtime as frame_system::Config>::RuntimeCall =
+	&RuntimeCall::Balances(BalancesCall::transfer { dest: 2, value: 69 });
+
+const BLOCK_AUTHOR: AccountId = 1234;
+
+pub struct BlockWeights;
+impl Get<frame_system::limits::BlockWeights> for BlockWeights {
+	fn get() -> frame_system::limits::BlockWeights {
+		frame_system::limits::BlockWeights::builder()
+			.base_block(Weight::zero())
+			.for_class(DispatchClass::all(), |weights| {
+				weights.base_extrinsic = Weight::zero();
+			})
+			.for_class(DispatchClass::non_mandatory(), |weights| {
+				weights.max_total = Weight::from_ref_time(1024).set_proof_size(u64::MAX).into();
+			})
+			.build_or_panic()
+	}
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type RuntimeCall = RuntimeCall;
+	type RuntimeTask = RuntimeTask;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 10;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = Balance;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = ();
+	type WeightInfo = ();
+	type MaxReserves = ConstU32<50>;
+	type ReserveIdentifier = [u8; 8];
+	type HoldIdentifier = ();
+	type FreezeIdentifier = ();
+	type MaxHolds = ();
+	type MaxFreezes = ();
+}
+
+impl pallet_transaction_payment::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type WeightToFee = IdentityFee<Balance>;
+	type LengthToFee = IdentityFee<Balance>;
+	type FeeMultiplierUpdate = ();
+	type OperationalFeeMultiplier = ConstU8<5>;
+}
+
+impl pallet_assets::Config<pallet_assets::Instance1> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type AssetId = u32;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU64<2>;
+	type AssetAccountDeposit = ConstU64<2>;
+	type MetadataDepositBase = ConstU64<0>;
+	type MetadataDepositPerByte = ConstU64<0>;
+	type ApprovalDeposit = ConstU64<0>;
+	type StringLimit = ConstU32<20>;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+	type RemoveItemsLimit = ConstU32<1000>;
+}
+
+impl pallet_assets::Config<pallet_assets::Instance2> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type AssetId = u32;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureNever<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU64<0>;
+	type AssetAccountDeposit = ConstU64<0>;
+	type MetadataDepositBase = ConstU64<0>;
+	type MetadataDepositPerByte = ConstU64<0>;
+	type ApprovalDeposit = ConstU64<0>;
+	type StringLimit = ConstU32<20>;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+	type RemoveItemsLimit = ConstU32<1000>;
+}
+
+parameter_types! {
+	pub const AssetConversionPalletId: PalletId = PalletId(*b"py/ascon");
+	pub const LPFee: Permill = Permill::from_perthousand(3);
+}
+
+impl pallet_asset_conversion::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type Balance = Balance;
+	type AssetId = u32;
+	type Assets = Assets;
+	type PoolAssetId = u32;
+	type PoolAssets = PoolAssets;
+	type LPFee = LPFee;
+	type PalletId = AssetConversionPalletId;
+	type MintMinLiquidity = ConstU64<100>;
+	type MaxSwapPathLength = ConstU32<4>;
+	type WeightInfo = ();
+}
+
+pub struct CreditToBlockAuthor;
+impl HandleCredit<AccountId, Assets> for CreditToBlockAuthor {
+	fn handle_credit(credit: CreditOf<AccountId, Assets>) {
+		let _ = <Assets as Balanced<AccountId>>::resolve(&BLOCK_AUTHOR, credit);
+	}
+}
+
+impl Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Fungibles = Assets;
+	type OnChargeAssetTransaction = FungiblesAdapter<
+		ConversionWithFallback<
+			AssetConversion,
+			pallet_assets::BalanceToAssetBalance<
+				Balances,
+				Runtime,
+				ConvertInto,
+				pallet_assets::Instance1,
+			>,
+		>,
+		CreditToBlockAuthor,
+	>;
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(1, 10_000), (2, 2_000), (BLOCK_AUTHOR, 100)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t.into()
+}
+
+/// Create a pool of the asset `asset_id` with 1_000 units of native currency and 4_000 units of
+/// the asset.
+///
+/// The pool keeps the existential deposit of both, so it prices the asset at 3_999 / 990.
+fn add_pool(asset_id: u32) {
+	let provider = 1;
+	assert_ok!(Assets::mint_into(asset_id, &provider, 5_000));
+	assert_ok!(AssetConversion::create_pool(
+		RuntimeOrigin::signed(provider),
+		NativeOrAssetId::Native,
+		NativeOrAssetId::Asset(asset_id),
+	));
+	assert_ok!(AssetConversion::add_liquidity(
+		RuntimeOrigin::signed(provider),
+		NativeOrAssetId::Native,
+		NativeOrAssetId::Asset(asset_id),
+		1_000,
+		4_000,
+		1_000,
+		4_000,
+		provider,
+	));
+}
+
+fn info_from_weight(w: Weight) -> DispatchInfo {
+	DispatchInfo { weight: w, ..Default::default() }
+}
+
+fn post_info_from_weight(w: Weight) -> PostDispatchInfo {
+	PostDispatchInfo { actual_weight: Some(w), pays_fee: Default::default() }
+}
+
+#[test]
+fn transaction_payment_in_asset_priced_by_pool() {
+	new_test_ext().execute_with(|| {
+		let asset_id = 1;
+		let caller = 2;
+		let balance = 10_000;
+		let weight = 100;
+		let len = 10;
+		let info = info_from_weight(Weight::from_ref_time(weight));
+
+		// without a pool, the asset isn't sufficient and can't be priced with its min balance
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), asset_id, 1, false, 1));
+		assert_ok!(Assets::mint_into(asset_id, &caller, balance));
+		assert!(ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&caller, CALL, &info, len)
+			.is_err());
+
+		add_pool(asset_id);
+		let fee = weight + len as u64;
+		assert_eq!(fee, 110);
+		// 110 * 3_999 / 990, rounded down
+		let fee_in_asset = 444;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&caller, CALL, &info, len)
+			.unwrap();
+		assert_eq!(Assets::balance(asset_id, caller), balance - fee_in_asset);
+
+		// unused weight is refunded in the asset
+		let final_weight = 50;
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			Some(pre),
+			&info,
+			&post_info_from_weight(Weight::from_ref_time(final_weight)),
+			len,
+			&Ok(())
+		));
+		// 60 * 3_999 / 990, rounded down
+		let final_fee_in_asset = 242;
+		assert_eq!(Assets::balance(asset_id, caller), balance - final_fee_in_asset);
+		assert_eq!(Assets::balance(asset_id, BLOCK_AUTHOR), final_fee_in_asset);
+		// neither the native balance of the caller nor the pool were touched
+		assert_eq!(Balances::free_balance(caller), 2_000);
+		assert_eq!(
+			AssetConversion::get_reserves(
+				NativeOrAssetId::Native,
+				NativeOrAssetId::Asset(asset_id)
+			)
+			.ok(),
+			Some((990, 3_999))
+		);
+	});
+}
+
+#[test]
+fn query_info_in_asset_works() {
+	new_test_ext().execute_with(|| {
+		let (sufficient, pooled, unknown) = (1, 2, 3);
+		let min_balance = 2;
+		assert_ok!(Assets::force_create(
+			RuntimeOrigin::root(),
+			sufficient,
+			42,   /* owner */
+			true, /* is_sufficient */
+			min_balance
+		));
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), pooled, 42, false, 1));
+		add_pool(pooled);
+
+		let call = RuntimeCall::Balances(BalancesCall::transfer { dest: 2, value: 69 });
+		let xt = TestXt::new(call.clone(), Some((2u64, ())));
+		let unsigned_xt = TestXt::<_, ()>::new(call, None);
+		let len = 10;
+		let info = TransactionPayment::query_info(xt.clone(), len);
+		assert!(info.partial_fee > 0);
+
+		let info_in_asset = |xt, asset_id| AssetTxPayment::query_info_in_asset(xt, len, asset_id);
+		assert_eq!(
+			info_in_asset(xt.clone(), sufficient),
+			Some(RuntimeDispatchInfo {
+				partial_fee: info.partial_fee * min_balance / ExistentialDeposit::get(),
+				..info
+			})
+		);
+		assert_eq!(
+			info_in_asset(xt.clone(), pooled),
+			Some(RuntimeDispatchInfo { partial_fee: info.partial_fee * 3_999 / 990, ..info })
+		);
+		assert_eq!(info_in_asset(xt, unknown), None);
+		assert_eq!(info_in_asset(unsigned_xt, pooled).map(|info| info.partial_fee), Some(0));
+	});
+}
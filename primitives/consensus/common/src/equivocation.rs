@@ -0,0 +1,297 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Consensus-agnostic reporting of equivocations.
+//!
+//! Consensus engines send the equivocations they detect to an [`EquivocationSink`]. The other
+//! end, an [`EquivocationReporting`] task, drops the ones it already reported and hands the
+//! others to the [`SubmitEquivocation`] registered for the engine, which usually submits them to
+//! the runtime as an unsigned `report_equivocation_unsigned` extrinsic.
+//!
+//! Aura, BABE and GRANDPA report through it when given an [`EquivocationSink`], and their client
+//! crates provide the matching [`SubmitEquivocation`]. Without a sink, Aura only logs the
+//! equivocations it detects while BABE and GRANDPA submit them through their runtime APIs
+//! directly.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use codec::Encode;
+use futures::{channel::mpsc, prelude::*};
+use log::{debug, warn};
+use sp_runtime::ConsensusEngineId;
+
+use crate::Error;
+
+/// Number of reported offences remembered to drop duplicate reports.
+const MAX_REMEMBERED_OFFENCES: usize = 1024;
+
+/// An equivocation detected by a consensus engine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EquivocationReport {
+	/// The engine that detected the equivocation.
+	pub engine_id: ConsensusEngineId,
+	/// SCALE-encoded offender and time slot (e.g. the slot or round) of the offence.
+	///
+	/// Reports with the same offence are only submitted once.
+	pub offence: Vec<u8>,
+	/// SCALE-encoded equivocation proof, as expected by the engine's [`SubmitEquivocation`].
+	pub proof: Vec<u8>,
+}
+
+impl EquivocationReport {
+	/// Create a new report of an `offence` by `engine_id`, proven by `proof`.
+	pub fn new(engine_id: ConsensusEngineId, offence: impl Encode, proof: &impl Encode) -> Self {
+		Self { engine_id, offence: offence.encode(), proof: proof.encode() }
+	}
+}
+
+/// Submits the equivocation proofs of one consensus engine.
+pub trait SubmitEquivocation: Send {
+	/// Submit the SCALE-encoded equivocation `proof`, e.g. through a runtime API.
+	fn submit(&mut self, proof: &[u8]) -> Result<(), Error>;
+}
+
+/// Sending half of the equivocation reporting, given to consensus engines.
+#[derive(Clone)]
+pub struct EquivocationSink(mpsc::UnboundedSender<EquivocationReport>);
+
+impl EquivocationSink {
+	/// Queue an equivocation for reporting.
+	pub fn report(&self, report: EquivocationReport) {
+		if self.0.unbounded_send(report).is_err() {
+			debug!(target: "equivocation", "Equivocation reporting has stopped, dropping report.");
+		}
+	}
+}
+
+/// Background task submitting the equivocations sent to its [`EquivocationSink`]s.
+pub struct EquivocationReporting {
+	reports: mpsc::UnboundedReceiver<EquivocationReport>,
+	submitters: HashMap<ConsensusEngineId, Box<dyn SubmitEquivocation>>,
+	/// Recently reported offences, oldest first.
+	reported: VecDeque<(ConsensusEngineId, Vec<u8>)>,
+	reported_set: HashSet<(ConsensusEngineId, Vec<u8>)>,
+}
+
+/// Create the equivocation reporting task and a sink to send it reports.
+///
+/// Register a [`SubmitEquivocation`] for each engine with
+/// [`EquivocationReporting::with_submitter`], then spawn [`EquivocationReporting::run`].
+pub fn equivocation_reporting() -> (EquivocationSink, EquivocationReporting) {
+	let (sender, reports) = mpsc::unbounded();
+	let reporting = EquivocationReporting {
+		reports,
+		submitters: HashMap::new(),
+		reported: VecDeque::new(),
+		reported_set: HashSet::new(),
+	};
+
+	(EquivocationSink(sender), reporting)
+}
+
+impl EquivocationReporting {
+	/// Submit the equivocations detected by `engine_id` through `submitter`.
+	pub fn with_submitter(
+		mut self,
+		engine_id: ConsensusEngineId,
+		submitter: impl SubmitEquivocation + 'static,
+	) -> Self {
+		self.submitters.insert(engine_id, Box::new(submitter));
+		self
+	}
+
+	/// Submit reports until all sinks are dropped.
+	pub async fn run(mut self) {
+		while let Some(report) = self.reports.next().await {
+			self.handle_report(report);
+		}
+	}
+
+	/// Submit `report` unless its offence was already reported.
+	///
+	/// Returns whether the report was submitted.
+	pub fn handle_report(&mut self, report: EquivocationReport) -> bool {
+		let key = (report.engine_id, report.offence);
+		if self.reported_set.contains(&key) {
+			debug!(target: "equivocation", "Dropping duplicate equivocation report.");
+			return false
+		}
+
+		let submitter = match self.submitters.get_mut(&key.0) {
+			Some(submitter) => submitter,
+			None => {
+				warn!(
+					target: "equivocation",
+					"No equivocation submitter for engine {:?}, dropping report.",
+					key.0,
+				);
+				return false
+			},
+		};
+
+		if let Err(err) = submitter.submit(&report.proof) {
+			// Not remembered, so that it is submitted if the equivocation is detected again.
+			warn!(target: "equivocation", "Failed to submit equivocation report: {}", err);
+			return false
+		}
+
+		if self.reported.len() >= MAX_REMEMBERED_OFFENCES {
+			if let Some(oldest) = self.reported.pop_front() {
+				self.reported_set.remove(&oldest);
+			}
+		}
+		self.reported.push_back(key.clone());
+		self.reported_set.insert(key);
+
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::{Arc, Mutex};
+
+	const ENGINE: ConsensusEngineId = *b"test";
+
+	/// Records the submitted proofs, failing while `fail` is set.
+	#[derive(Clone, Default)]
+	struct RecordSubmitted {
+		submitted: Arc<Mutex<Vec<Vec<u8>>>>,
+		fail: Arc<Mutex<bool>>,
+	}
+
+	impl SubmitEquivocation for RecordSubmitted {
+		fn submit(&mut self, proof: &[u8]) -> Result<(), Error> {
+			if *self.fail.lock().unwrap() {
+				return Err(Error::Other("submission failed".into()))
+			}
+			self.submitted.lock().unwrap().push(proof.to_vec());
+			Ok(())
+		}
+	}
+
+	fn report(offender: u32, slot: u64) -> EquivocationReport {
+		EquivocationReport::new(ENGINE, (offender, slot), &(offender, slot, "proof"))
+	}
+
+	#[test]
+	fn duplicate_offences_are_submitted_once() {
+		let submitter = RecordSubmitted::default();
+		let (_sink, mut reporting) = equivocation_reporting();
+		reporting = reporting.with_submitter(ENGINE, submitter.clone());
+
+		assert!(reporting.handle_report(report(1, 5)));
+		assert!(!reporting.handle_report(report(1, 5)));
+
+		// the same offence with another proof is still a duplicate
+		let mut other_proof = report(1, 5);
+		other_proof.proof = vec![42];
+		assert!(!reporting.handle_report(other_proof));
+
+		// other offenders and slots are not
+		assert!(reporting.handle_report(report(2, 5)));
+		assert!(reporting.handle_report(report(1, 6)));
+
+		assert_eq!(
+			*submitter.submitted.lock().unwrap(),
+			vec![report(1, 5).proof, report(2, 5).proof, report(1, 6).proof],
+		);
+	}
+
+	#[test]
+	fn same_offence_of_other_engine_is_not_duplicate() {
+		let submitter = RecordSubmitted::default();
+		let other = RecordSubmitted::default();
+		let (_sink, reporting) = equivocation_reporting();
+		let mut reporting = reporting
+			.with_submitter(ENGINE, submitter.clone())
+			.with_submitter(*b"othr", other.clone());
+
+		assert!(reporting.handle_report(report(1, 5)));
+		let mut other_report = report(1, 5);
+		other_report.engine_id = *b"othr";
+		assert!(reporting.handle_report(other_report));
+
+		assert_eq!(submitter.submitted.lock().unwrap().len(), 1);
+		assert_eq!(other.submitted.lock().unwrap().len(), 1);
+	}
+
+	#[test]
+	fn reports_without_submitter_are_dropped() {
+		let (_sink, mut reporting) = equivocation_reporting();
+
+		assert!(!reporting.handle_report(report(1, 5)));
+		assert!(reporting.reported.is_empty());
+	}
+
+	#[test]
+	fn failed_submissions_are_retried() {
+		let submitter = RecordSubmitted::default();
+		let (_sink, reporting) = equivocation_reporting();
+		let mut reporting = reporting.with_submitter(ENGINE, submitter.clone());
+
+		*submitter.fail.lock().unwrap() = true;
+		assert!(!reporting.handle_report(report(1, 5)));
+		assert!(submitter.submitted.lock().unwrap().is_empty());
+
+		*submitter.fail.lock().unwrap() = false;
+		assert!(reporting.handle_report(report(1, 5)));
+		assert_eq!(*submitter.submitted.lock().unwrap(), vec![report(1, 5).proof]);
+	}
+
+	#[test]
+	fn oldest_offences_are_forgotten() {
+		let submitter = RecordSubmitted::default();
+		let (_sink, reporting) = equivocation_reporting();
+		let mut reporting = reporting.with_submitter(ENGINE, submitter.clone());
+
+		for slot in 0..MAX_REMEMBERED_OFFENCES as u64 {
+			assert!(reporting.handle_report(report(1, slot)));
+		}
+		assert!(!reporting.handle_report(report(1, 0)));
+
+		// remembering one more offence forgets the oldest one
+		assert!(reporting.handle_report(report(1, MAX_REMEMBERED_OFFENCES as u64)));
+		assert_eq!(reporting.reported.len(), MAX_REMEMBERED_OFFENCES);
+		assert_eq!(reporting.reported_set.len(), MAX_REMEMBERED_OFFENCES);
+		assert!(reporting.handle_report(report(1, 0)));
+		assert!(!reporting.handle_report(report(1, 2)));
+	}
+
+	#[test]
+	fn run_submits_the_reports_of_all_sinks() {
+		let submitter = RecordSubmitted::default();
+		let (sink, reporting) = equivocation_reporting();
+		let reporting = reporting.with_submitter(ENGINE, submitter.clone());
+
+		let other_sink = sink.clone();
+		sink.report(report(1, 5));
+		other_sink.report(report(1, 5));
+		other_sink.report(report(2, 5));
+		drop(sink);
+		drop(other_sink);
+
+		// ends once all the sinks are dropped
+		futures::executor::block_on(reporting.run());
+
+		assert_eq!(
+			*submitter.submitted.lock().unwrap(),
+			vec![report(1, 5).proof, report(2, 5).proof],
+		);
+	}
+}
@@ -31,6 +31,7 @@ use sp_runtime::{
 use sp_state_machine::StorageProof;
 
 pub mod block_validation;
+pub mod equivocation;
 pub mod error;
 mod select_chain;
 
@@ -166,6 +166,15 @@ impl TaskExecutorExt {
 	}
 }
 
+sp_externalities::decl_extension! {
+	/// Limits the fuel a [`CodeExecutor`] call is allowed to consume.
+	///
+	/// When registered, the executor runs the call in Wasm instrumented to consume fuel, and
+	/// aborts it once it consumed the given amount. Executors that can't meter execution fail the
+	/// call instead of running it unbounded.
+	pub struct FuelLimitExt(u64);
+}
+
 /// Runtime spawn extension.
 pub trait RuntimeSpawn: Send {
 	/// Create new runtime instance and use dynamic dispatch to invoke with specified payload.
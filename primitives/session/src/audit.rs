@@ -0,0 +1,324 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cross-checking of the local keystore against the session keys registered on chain.
+//!
+//! The on-chain side is read from the `NextKeys` map of `pallet-session`, which holds the SCALE
+//! encoded session keys a validator will use from the next session onwards. Those can be turned
+//! into a list of raw public keys with [`SessionKeys::decode_session_keys`](crate::SessionKeys)
+//! and passed to [`audit_session_keys`] together with the keystore to inspect.
+//!
+//! The keys used in the current session are not kept by `pallet-session`. They are the
+//! `NextKeys` of the validator as of the end of the session before the previous one, when they
+//! were queued for the current session. [`find_current_keys_block`] finds that block.
+
+use serde::{Deserialize, Serialize};
+use sp_core::{
+	crypto::{key_types, KeyTypeId},
+	hashing::{twox_128, twox_64},
+	Bytes,
+};
+use sp_keystore::SyncCryptoStore;
+use sp_runtime::traits::AtLeast32BitUnsigned;
+use sp_staking::SessionIndex;
+use std::collections::BTreeSet;
+
+/// The name `pallet-session` is usually given in `construct_runtime!`.
+pub const DEFAULT_SESSION_PALLET_PREFIX: &[u8] = b"Session";
+
+/// Key types that are checked for orphan keys even when they are not part of the on-chain session
+/// keys of a validator.
+pub const DEFAULT_AUDITED_KEY_TYPES: &[KeyTypeId] = &[
+	key_types::BABE,
+	key_types::GRANDPA,
+	key_types::AURA,
+	key_types::IM_ONLINE,
+	key_types::AUTHORITY_DISCOVERY,
+];
+
+/// Returns the storage key of `pallet_session::NextKeys` for the given SCALE encoded validator id.
+///
+/// `pallet_prefix` is the name of the session pallet in the runtime, usually
+/// [`DEFAULT_SESSION_PALLET_PREFIX`].
+pub fn next_keys_storage_key(pallet_prefix: &[u8], validator: &[u8]) -> Vec<u8> {
+	let mut key = Vec::with_capacity(32 + 8 + validator.len());
+	key.extend_from_slice(&twox_128(pallet_prefix));
+	key.extend_from_slice(&twox_128(b"NextKeys"));
+	key.extend_from_slice(&twox_64(validator));
+	key.extend_from_slice(validator);
+	key
+}
+
+/// Returns the storage key of `pallet_session::CurrentIndex`, the index of the current session.
+///
+/// `pallet_prefix` is the name of the session pallet in the runtime, usually
+/// [`DEFAULT_SESSION_PALLET_PREFIX`].
+pub fn current_index_storage_key(pallet_prefix: &[u8]) -> Vec<u8> {
+	let mut key = Vec::with_capacity(32);
+	key.extend_from_slice(&twox_128(pallet_prefix));
+	key.extend_from_slice(&twox_128(b"CurrentIndex"));
+	key
+}
+
+/// Returns the number of the block whose `NextKeys` hold the session keys used in `session`.
+///
+/// The keys of a session are queued from `NextKeys` when the session before it starts, so they
+/// are found at the last block two sessions back, or at genesis during the first two sessions.
+/// The block is searched for between genesis and `best`, a block of `session`, given the
+/// `session_index` of the block with a given number.
+pub fn find_current_keys_block<N, E>(
+	session: SessionIndex,
+	best: N,
+	mut session_index: impl FnMut(N) -> Result<SessionIndex, E>,
+) -> Result<N, E>
+where
+	N: AtLeast32BitUnsigned + Copy,
+{
+	if session < 2 {
+		return Ok(N::zero())
+	}
+
+	// the session index of `low` is always lower than `session - 1`, the one of `high` never.
+	let (mut low, mut high) = (N::zero(), best);
+	while high - low > N::one() {
+		let middle = low + (high - low) / 2u32.into();
+		if session_index(middle)? < session - 1 {
+			low = middle;
+		} else {
+			high = middle;
+		}
+	}
+
+	Ok(low)
+}
+
+/// Audit result for a single key type.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyTypeAudit {
+	/// The key type, as its four character identifier.
+	pub key_type: String,
+	/// The public key registered on chain for this key type, if any.
+	pub on_chain: Option<Bytes>,
+	/// The public key of this key type used in the current session, if any.
+	pub current: Option<Bytes>,
+	/// `true` if a key is registered on chain or used in the current session but its private
+	/// key is not in the keystore.
+	pub missing: bool,
+	/// Local keys of a key type registered on chain that are not the registered key, nor the
+	/// key used in the current session.
+	///
+	/// These are usually left over from earlier key rotations.
+	pub stale: Vec<Bytes>,
+	/// Local keys of a key type that is not registered on chain at all.
+	pub orphan: Vec<Bytes>,
+}
+
+/// Result of auditing the keystore against the on-chain session keys of a validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionKeysAudit {
+	/// Whether the validator has any session keys registered on chain, or used in the current
+	/// session.
+	pub registered: bool,
+	/// Per key type results, ordered by key type.
+	pub key_types: Vec<KeyTypeAudit>,
+}
+
+impl SessionKeysAudit {
+	/// Returns `true` if session keys are registered and none of them is missing locally.
+	pub fn is_healthy(&self) -> bool {
+		self.registered && self.key_types.iter().all(|audit| !audit.missing)
+	}
+}
+
+/// Compare the keys found in `keystore` with the `on_chain` session keys of a validator, and
+/// with the keys it uses in the `current` session.
+///
+/// `on_chain` and `current` are lists of raw public keys and key types as returned by
+/// `SessionKeys::decode_session_keys`, or `None` if the validator has no such keys. Besides the
+/// key types found on chain, the keystore is also searched for keys of the types in
+/// `extra_key_types`, which are reported as orphans when not registered.
+pub fn audit_session_keys(
+	keystore: &dyn SyncCryptoStore,
+	on_chain: Option<&[(Vec<u8>, KeyTypeId)]>,
+	current: Option<&[(Vec<u8>, KeyTypeId)]>,
+	extra_key_types: &[KeyTypeId],
+) -> SessionKeysAudit {
+	let on_chain = on_chain.unwrap_or_default();
+	let current = current.unwrap_or_default();
+	let key_types = on_chain
+		.iter()
+		.chain(current)
+		.map(|(_, key_type)| *key_type)
+		.chain(extra_key_types.iter().copied())
+		.collect::<BTreeSet<_>>();
+
+	let key_types = key_types
+		.into_iter()
+		.map(|key_type| {
+			let registered = find_key(on_chain, key_type);
+			let in_use = find_key(current, key_type);
+
+			// The same raw public key may be reported once per supported crypto type.
+			let local = SyncCryptoStore::keys(keystore, key_type)
+				.unwrap_or_default()
+				.into_iter()
+				.map(|pair| pair.1)
+				.collect::<BTreeSet<_>>();
+
+			let others = local
+				.iter()
+				.filter(|public| Some(*public) != registered && Some(*public) != in_use)
+				.cloned()
+				.map(Bytes)
+				.collect::<Vec<_>>();
+
+			let missing = registered
+				.into_iter()
+				.chain(in_use)
+				.any(|public| !SyncCryptoStore::has_keys(keystore, &[(public.clone(), key_type)]));
+
+			let (stale, orphan) = if registered.is_some() || in_use.is_some() {
+				(others, Vec::new())
+			} else {
+				(Vec::new(), others)
+			};
+
+			KeyTypeAudit {
+				key_type: String::from_utf8_lossy(&key_type.0).into_owned(),
+				on_chain: registered.cloned().map(Bytes),
+				current: in_use.cloned().map(Bytes),
+				missing,
+				stale,
+				orphan,
+			}
+		})
+		.collect();
+
+	SessionKeysAudit { registered: !on_chain.is_empty() || !current.is_empty(), key_types }
+}
+
+fn find_key(keys: &[(Vec<u8>, KeyTypeId)], key_type: KeyTypeId) -> Option<&Vec<u8>> {
+	keys.iter().find(|(_, id)| *id == key_type).map(|(key, _)| key)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{ed25519, sr25519};
+	use sp_keystore::testing::KeyStore;
+
+	#[test]
+	fn finds_the_block_the_current_keys_were_queued_at() {
+		// sessions are 10 blocks long, starting at block 1.
+		let session_index = |number: u64| Ok::<_, ()>(number.div_ceil(10) as SessionIndex);
+
+		assert_eq!(find_current_keys_block(0, 0, session_index), Ok(0));
+		assert_eq!(find_current_keys_block(1, 15, session_index), Ok(0));
+		assert_eq!(find_current_keys_block(2, 11, session_index), Ok(0));
+		assert_eq!(find_current_keys_block(3, 21, session_index), Ok(10));
+		assert_eq!(find_current_keys_block(5, 50, session_index), Ok(30));
+		assert_eq!(find_current_keys_block(5, 41, session_index), Ok(30));
+	}
+
+	#[test]
+	fn reports_missing_stale_and_orphan_keys() {
+		let keystore = KeyStore::new();
+		let babe_old =
+			SyncCryptoStore::sr25519_generate_new(&keystore, key_types::BABE, None).unwrap();
+		let babe = SyncCryptoStore::sr25519_generate_new(&keystore, key_types::BABE, None).unwrap();
+		let aura = SyncCryptoStore::sr25519_generate_new(&keystore, key_types::AURA, None).unwrap();
+		let grandpa = ed25519::Public::from_raw([1; 32]);
+
+		let on_chain =
+			vec![(babe.to_vec(), key_types::BABE), (grandpa.to_vec(), key_types::GRANDPA)];
+
+		let audit = audit_session_keys(&keystore, Some(&on_chain), None, DEFAULT_AUDITED_KEY_TYPES);
+
+		assert!(audit.registered);
+		assert!(!audit.is_healthy());
+
+		let find = |key_type: KeyTypeId| {
+			let name = String::from_utf8_lossy(&key_type.0).into_owned();
+			audit.key_types.iter().find(|a| a.key_type == name).unwrap().clone()
+		};
+
+		let babe_audit = find(key_types::BABE);
+		assert_eq!(babe_audit.on_chain, Some(Bytes(babe.to_vec())));
+		assert!(!babe_audit.missing);
+		assert_eq!(babe_audit.stale, vec![Bytes(babe_old.to_vec())]);
+		assert!(babe_audit.orphan.is_empty());
+
+		let grandpa_audit = find(key_types::GRANDPA);
+		assert!(grandpa_audit.missing);
+		assert!(grandpa_audit.stale.is_empty());
+
+		let aura_audit = find(key_types::AURA);
+		assert_eq!(aura_audit.on_chain, None);
+		assert_eq!(aura_audit.orphan, vec![Bytes(aura.to_vec())]);
+	}
+
+	#[test]
+	fn unregistered_validator_is_not_healthy() {
+		let keystore = KeyStore::new();
+		let public: sr25519::Public =
+			SyncCryptoStore::sr25519_generate_new(&keystore, key_types::BABE, None).unwrap();
+
+		let audit = audit_session_keys(&keystore, None, None, &[key_types::BABE]);
+
+		assert!(!audit.registered);
+		assert!(!audit.is_healthy());
+		assert_eq!(audit.key_types[0].orphan, vec![Bytes(public.to_vec())]);
+	}
+
+	#[test]
+	fn keys_of_the_current_session_are_audited() {
+		let keystore = KeyStore::new();
+		let babe_current =
+			SyncCryptoStore::sr25519_generate_new(&keystore, key_types::BABE, None).unwrap();
+		let babe_next =
+			SyncCryptoStore::sr25519_generate_new(&keystore, key_types::BABE, None).unwrap();
+		let grandpa_current = ed25519::Public::from_raw([1; 32]);
+
+		let on_chain = vec![(babe_next.to_vec(), key_types::BABE)];
+		let current = vec![
+			(babe_current.to_vec(), key_types::BABE),
+			(grandpa_current.to_vec(), key_types::GRANDPA),
+		];
+
+		let audit = audit_session_keys(&keystore, Some(&on_chain), Some(&current), &[]);
+		assert!(audit.registered);
+		assert!(!audit.is_healthy());
+
+		// the key of the current session is still needed, and not stale.
+		let babe_audit = &audit.key_types[0];
+		assert_eq!(babe_audit.on_chain, Some(Bytes(babe_next.to_vec())));
+		assert_eq!(babe_audit.current, Some(Bytes(babe_current.to_vec())));
+		assert!(!babe_audit.missing);
+		assert!(babe_audit.stale.is_empty());
+
+		// keys purged from `NextKeys` are still needed until the end of the current session.
+		let grandpa_audit = &audit.key_types[1];
+		assert_eq!(grandpa_audit.on_chain, None);
+		assert!(grandpa_audit.missing);
+
+		let audit = audit_session_keys(&keystore, None, Some(&current[..1]), &[]);
+		assert!(audit.registered);
+		assert!(audit.is_healthy());
+	}
+}
@@ -30,6 +30,9 @@ use sp_core::{crypto::KeyTypeId, RuntimeDebug};
 use sp_staking::SessionIndex;
 use sp_std::vec::Vec;
 
+#[cfg(feature = "std")]
+pub mod audit;
+
 sp_api::decl_runtime_apis! {
 	/// Session keys runtime api.
 	pub trait SessionKeys {
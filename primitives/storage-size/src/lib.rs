@@ -0,0 +1,49 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Primitives for the number of bytes occupied by the storages of the runtime which keep track
+//! of it.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use sp_core::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// The number of bytes occupied by a storage which keeps track of it.
+///
+/// The total of all such storages of a pallet is reported with an empty `storage_name`.
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone, scale_info::TypeInfo)]
+pub struct StorageSize {
+	/// Encoded string of pallet name.
+	pub pallet_name: Vec<u8>,
+	/// Encoded string of storage name, empty for the total of the pallet.
+	pub storage_name: Vec<u8>,
+	/// The number of bytes occupied by the keys and values of the storage.
+	pub bytes: u64,
+	/// The maximum number of bytes the storage may occupy, or none if no maximum specified.
+	pub max_bytes: Option<u64>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the number of bytes occupied by the size tracked storages of the runtime.
+	pub trait StorageSizeApi {
+		/// The size of all size tracked storages and the totals of their pallets, usually
+		/// `AllPalletsWithSystem::storage_size()`.
+		fn storage_size() -> Vec<StorageSize>;
+	}
+}
@@ -403,6 +403,15 @@ if_wasmtime_is_enabled! {
 			callback: impl FnOnce(&mut dyn FunctionContext) -> R,
 		) -> R;
 
+		/// Called around every invocation of the host function `fn_name`.
+		///
+		/// The default implementation just runs `call`; executors can override it to
+		/// e.g. profile the host functions.
+		fn call_host_function<R>(fn_name: &'static str, call: impl FnOnce() -> R) -> R {
+			let _ = fn_name;
+			call()
+		}
+
 		/// Registers a given host function with the WASM executor.
 		///
 		/// The function has to be statically callable, and all of its arguments
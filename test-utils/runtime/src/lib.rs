@@ -618,6 +618,7 @@ impl frame_system::Config for Runtime {
 	type BlockLength = RuntimeBlockLength;
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = Extrinsic;
+	type RuntimeTask = ();
 	type Index = u64;
 	type BlockNumber = u64;
 	type Hash = H256;
@@ -238,6 +238,7 @@ impl PalletCmd {
 		}
 
 		// Run the benchmarks
+		let wasm_profiling = self.wasm_profiling_params.start()?;
 		let mut batches = Vec::new();
 		let mut batches_db = Vec::new();
 		let mut timer = time::SystemTime::now();
@@ -425,6 +426,7 @@ impl PalletCmd {
 				}
 			}
 		}
+		wasm_profiling.finish()?;
 
 		// Combine all of the benchmark results, so that benchmarks of the same pallet/function
 		// are together.
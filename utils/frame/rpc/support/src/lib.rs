@@ -58,6 +58,7 @@ use sp_storage::{StorageData, StorageKey};
 /// # 	type BlockLength = ();
 /// # 	type RuntimeOrigin = RuntimeOrigin;
 /// # 	type RuntimeCall = RuntimeCall;
+/// # 	type RuntimeTask = RuntimeTask;
 /// # 	type Index = u64;
 /// # 	type BlockNumber = u64;
 /// # 	type Hash = Hash;
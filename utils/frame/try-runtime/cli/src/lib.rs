@@ -419,6 +419,11 @@ pub struct SharedParams {
 	)]
 	pub wasmtime_instantiation_strategy: WasmtimeInstantiationStrategy,
 
+	/// Profiling of the calls into the runtime.
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub wasm_profiling_params: sc_cli::WasmProfilingParams,
+
 	/// The number of 64KB pages to allocate for Wasm execution. Defaults to
 	/// [`sc_service::Configuration.default_heap_pages`].
 	#[arg(long)]
@@ -552,7 +557,8 @@ impl TryRuntimeCmd {
 		<NumberFor<Block> as FromStr>::Err: Debug,
 		ExecDispatch: NativeExecutionDispatch + 'static,
 	{
-		match &self.command {
+		let wasm_profiling = self.shared.wasm_profiling_params.start()?;
+		let result = match &self.command {
 			Command::OnRuntimeUpgrade(ref cmd) =>
 				commands::on_runtime_upgrade::on_runtime_upgrade::<Block, ExecDispatch>(
 					self.shared.clone(),
@@ -581,7 +587,9 @@ impl TryRuntimeCmd {
 					config,
 				)
 				.await,
-		}
+		};
+		wasm_profiling.finish()?;
+		result
 	}
 }
 